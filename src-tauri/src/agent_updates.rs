@@ -0,0 +1,158 @@
+//! Agent CLI update checks.
+//!
+//! Installed agent versions come from the same detection the Agents settings
+//! tab uses; latest versions come from the registry each CLI is published
+//! to. A background watcher repeats the check every
+//! `agent_update_check_hours` and emits `agent-update-available` for each
+//! agent that has fallen behind.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::agents::{detect_agent_status, get_built_in_agents};
+use crate::preferences::load_preferences_internal;
+use crate::stacks::{fetch_latest_version, is_version_outdated};
+
+/// How often the watcher wakes to see whether a check is due.
+const WAKE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Gives startup a head start before the first check.
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// Updates found by the last check, for windows opened after its event.
+static LATEST: Mutex<Vec<AgentUpdate>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentUpdate {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub installed_version: String,
+    pub latest_version: String,
+    pub release_notes_url: String,
+}
+
+/// The npm package an agent's CLI is published as, and where its release
+/// notes are. Agents installed some other way return None.
+fn agent_package(agent_id: &str) -> Option<(&'static str, &'static str)> {
+    let package = match agent_id {
+        "claude-code" => (
+            "@anthropic-ai/claude-code",
+            "https://github.com/anthropics/claude-code/blob/main/CHANGELOG.md",
+        ),
+        "amp" => ("@sourcegraph/amp", "https://ampcode.com/news"),
+        "opencode" => ("opencode-ai", "https://github.com/sst/opencode/releases"),
+        "codex" => ("@openai/codex", "https://github.com/openai/codex/releases"),
+        "continue" => ("@continuedev/cli", "https://github.com/continuedev/continue/releases"),
+        "copilot" => ("@github/copilot", "https://github.com/github/copilot-cli/releases"),
+        _ => return None,
+    };
+    Some(package)
+}
+
+/// Picks the version out of a CLI's version output ("1.0.88 (Claude Code)",
+/// "codex-cli 0.20.0").
+fn extract_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('v'))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.'))
+        .map(str::to_string)
+}
+
+/// Compares every installed agent with a known package against its latest
+/// published version.
+async fn find_agent_updates() -> Result<Vec<AgentUpdate>, String> {
+    let installed = tokio::task::spawn_blocking(|| {
+        get_built_in_agents()
+            .iter()
+            .filter(|agent| agent_package(&agent.id).is_some())
+            .map(detect_agent_status)
+            .filter(|status| status.status == "available")
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("Ideate/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut updates = Vec::new();
+    for status in installed {
+        let Some(installed_version) = status.installed_version.as_deref().and_then(extract_version) else {
+            continue;
+        };
+        let Some((package, release_notes_url)) = agent_package(&status.agent.id) else {
+            continue;
+        };
+        let latest_version = match fetch_latest_version(&client, "npm", package).await {
+            Ok(version) => version,
+            Err(e) => {
+                tracing::warn!("Agent update check: {}", e);
+                continue;
+            }
+        };
+        if is_version_outdated(&installed_version, &latest_version) {
+            updates.push(AgentUpdate {
+                agent_id: status.agent.id.clone(),
+                agent_name: status.agent.name.clone(),
+                installed_version,
+                latest_version,
+                release_notes_url: release_notes_url.to_string(),
+            });
+        }
+    }
+
+    *LATEST.lock().unwrap_or_else(|e| e.into_inner()) = updates.clone();
+    Ok(updates)
+}
+
+/// Checks installed agents for newer published versions now.
+#[tauri::command]
+pub async fn check_agent_updates() -> Result<Vec<AgentUpdate>, String> {
+    find_agent_updates().await
+}
+
+/// Returns the updates found by the last check, without checking again.
+#[tauri::command]
+pub fn get_agent_updates() -> Vec<AgentUpdate> {
+    LATEST.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Checks for agent updates every `agent_update_check_hours`, 0 meaning
+/// never. Each update is emitted once per version found.
+pub fn spawn_agent_update_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        let mut last_check: Option<Instant> = None;
+        let mut announced: HashSet<String> = HashSet::new();
+        loop {
+            let hours = load_preferences_internal(&app)
+                .map(|prefs| prefs.agent_update_check_hours)
+                .unwrap_or_default();
+            let due = hours > 0
+                && last_check.is_none_or(|at| at.elapsed() >= Duration::from_secs(u64::from(hours) * 60 * 60));
+            if due {
+                last_check = Some(Instant::now());
+                match find_agent_updates().await {
+                    Ok(updates) => {
+                        for update in updates {
+                            if announced.insert(format!("{}@{}", update.agent_id, update.latest_version)) {
+                                let _ = app.emit("agent-update-available", update);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("Agent update check failed: {}", e),
+                }
+            }
+            tokio::time::sleep(WAKE_INTERVAL).await;
+        }
+    });
+}
@@ -1,10 +1,15 @@
 //! Agent plugin definitions and detection.
 
+#[cfg(feature = "gui")]
+use std::collections::HashMap;
+#[cfg(feature = "gui")]
 use std::process::Command;
 
+#[cfg(feature = "gui")]
 use crate::models::{AgentModel, AgentPlugin, AgentPluginStatus};
 
 /// Returns the list of built-in agent definitions.
+#[cfg(feature = "gui")]
 pub fn get_built_in_agents() -> Vec<AgentPlugin> {
     vec![
         AgentPlugin {
@@ -198,7 +203,8 @@ pub fn get_built_in_agents() -> Vec<AgentPlugin> {
 }
 
 /// Detects the installation status of an agent.
-fn detect_agent_status(agent: &AgentPlugin) -> AgentPluginStatus {
+#[cfg(feature = "gui")]
+pub(crate) fn detect_agent_status(agent: &AgentPlugin) -> AgentPluginStatus {
     let (status, installed_version, cli_path) = match Command::new("which")
         .arg(&agent.command)
         .output()
@@ -246,13 +252,99 @@ fn detect_agent_status(agent: &AgentPlugin) -> AgentPluginStatus {
     }
 }
 
+/// Whether an agent appears to be signed in: an API key in `env` or the
+/// environment, or the credentials file its CLI writes on login. `None` for
+/// agents whose sign-in can't be seen from outside, such as ones that keep
+/// it in the system keychain only.
+#[cfg(feature = "gui")]
+pub(crate) fn has_credentials(agent_id: &str, env: &HashMap<String, String>) -> Option<bool> {
+    let (keys, files): (&[&str], &[&[&str]]) = match agent_id {
+        "claude-code" => (
+            &["ANTHROPIC_API_KEY", "CLAUDE_CODE_OAUTH_TOKEN"],
+            &[&[".claude", ".credentials.json"], &[".claude.json"]],
+        ),
+        "amp" => (&["AMP_API_KEY"], &[&[".local", "share", "amp", "secrets.json"]]),
+        "codex" => (&["OPENAI_API_KEY"], &[&[".codex", "auth.json"]]),
+        _ => return None,
+    };
+    let has_key = keys.iter().any(|key| {
+        env.get(*key)
+            .cloned()
+            .or_else(|| std::env::var(key).ok())
+            .is_some_and(|value| !value.trim().is_empty())
+    });
+    if has_key {
+        return Some(true);
+    }
+    let home = dirs::home_dir()?;
+    let has_file = files.iter().any(|parts| {
+        let path = parts.iter().fold(home.clone(), |path, part| path.join(part));
+        match agent_id {
+            // Written on first run too; signed in once it has an account
+            "claude-code" if path.ends_with(".claude.json") => std::fs::read_to_string(&path)
+                .is_ok_and(|content| content.contains("\"oauthAccount\"")),
+            _ => path.exists(),
+        }
+    });
+    Some(has_file)
+}
+
+/// Returns the executable and argument template used to run an agent
+/// non-interactively during builds. `{{prompt}}` in the template is replaced
+/// with the story prompt. Mirrors the frontend's `defaultPlugins`.
+pub fn get_build_command(agent_id: &str) -> Option<(String, Vec<String>)> {
+    let (command, args): (&str, &[&str]) = match agent_id {
+        "claude-code" => (
+            "claude",
+            &["--output-format", "stream-json", "--verbose", "--dangerously-skip-permissions", "-p", "{{prompt}}"],
+        ),
+        "amp" => ("amp", &["--execute", "{{prompt}}", "--stream-json"]),
+        "opencode" => ("opencode", &["run", "{{prompt}}"]),
+        "droid" => ("droid", &["{{prompt}}"]),
+        "codex" => ("codex", &["exec", "{{prompt}}"]),
+        "cursor" => ("agent", &["-p", "{{prompt}}"]),
+        "continue" => ("cn", &["-p", "{{prompt}}"]),
+        "copilot" => ("copilot", &["{{prompt}}"]),
+        _ => return None,
+    };
+
+    Some((command.to_string(), args.iter().map(|a| a.to_string()).collect()))
+}
+
+/// Flag an agent's build command takes to select a model, if it has one.
+fn model_flag(agent_id: &str) -> Option<&'static str> {
+    match agent_id {
+        "claude-code" | "codex" | "opencode" | "cursor" => Some("--model"),
+        _ => None,
+    }
+}
+
+/// Like `get_build_command`, selecting `model` when one is given. The model
+/// is ignored for agents without a model flag; see `supports_model`.
+pub fn get_build_command_with_model(agent_id: &str, model: Option<&str>) -> Option<(String, Vec<String>)> {
+    let (command, mut args) = get_build_command(agent_id)?;
+    if let (Some(model), Some(flag)) = (model.filter(|m| !m.is_empty()), model_flag(agent_id)) {
+        // Subcommands such as `run` and `exec` come first
+        let at = if matches!(agent_id, "opencode" | "codex") { 1 } else { 0 };
+        args.splice(at..at, [flag.to_string(), model.to_string()]);
+    }
+    Some((command, args))
+}
+
+/// Whether builds can select a model for this agent.
+pub fn supports_model(agent_id: &str) -> bool {
+    model_flag(agent_id).is_some()
+}
+
 /// Returns the list of all built-in agents.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn list_agents() -> Result<Vec<AgentPlugin>, String> {
     Ok(get_built_in_agents())
 }
 
 /// Detects which agents are installed and their versions.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn detect_agents() -> Result<Vec<AgentPluginStatus>, String> {
     tokio::task::spawn_blocking(|| {
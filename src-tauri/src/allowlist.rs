@@ -0,0 +1,176 @@
+//! Executable allowlist for `spawn_agent`.
+//!
+//! The webview decides what `spawn_agent` runs, so anything that can script
+//! the page could otherwise start arbitrary programs. Spawns are limited to the
+//! agent CLIs Ideate knows, the tunnel clients it launches, package managers
+//! running one of the project's `package.json` scripts, and executables the
+//! user approved. Anything else is rejected with `NOT_ALLOWED_PREFIX`, which
+//! the UI answers by calling `approve_executable`. That shows the confirmation
+//! dialog itself, so approval can't be granted by the page alone; for the same
+//! reason `save_preferences` can remove approvals but never add them.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::agents::get_built_in_agents;
+use crate::models::Preferences;
+use crate::preferences::{load_preferences_internal, write_preferences};
+
+/// Start of the error returned for executables that aren't allowed.
+pub const NOT_ALLOWED_PREFIX: &str = "Executable not allowed:";
+
+/// Tunnel clients the app spawns besides agents.
+const KNOWN_TOOLS: &[&str] = &["outray", "cloudflared", "ngrok"];
+
+/// Package managers dev servers are started with, only ever as
+/// `npm run <script>` or `<manager> <script>` for a script in the working
+/// directory's `package.json`.
+const PACKAGE_MANAGERS: &[&str] = &["npm", "pnpm", "yarn", "bun"];
+
+/// The command name of `executable`, without directory or Windows extension.
+fn command_name(executable: &str) -> String {
+    let name = Path::new(executable)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    for ext in [".exe", ".cmd", ".bat"] {
+        if let Some(stem) = name.strip_suffix(ext) {
+            return stem.to_string();
+        }
+    }
+    name
+}
+
+fn is_bare_name(executable: &str) -> bool {
+    !executable.contains('/') && !executable.contains('\\')
+}
+
+fn is_known_agent(name: &str) -> bool {
+    get_built_in_agents().iter().any(|agent| agent.command == name)
+}
+
+fn is_known_command(name: &str) -> bool {
+    KNOWN_TOOLS.contains(&name) || is_known_agent(name)
+}
+
+/// Whether `executable` may run in `working_directory` when that is outside
+/// every registered project: tunnel clients, which ignore their working
+/// directory, and agents run from the home directory, as idea generation does.
+pub fn may_run_outside_projects(executable: &str, working_directory: &str) -> bool {
+    let name = command_name(executable.trim());
+    if KNOWN_TOOLS.contains(&name.as_str()) {
+        return true;
+    }
+    is_known_agent(&name)
+        && dirs::home_dir().is_some_and(|home| same_file(&home, Path::new(working_directory)))
+}
+
+/// Resolves a bare command name the way the OS would, via `PATH`.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) { &[".exe", ".cmd", ".bat"] } else { &[""] };
+    env::split_paths(&env::var_os("PATH")?).find_map(|dir| {
+        extensions.iter().find_map(|ext| {
+            let candidate = dir.join(format!("{}{}", name, ext));
+            candidate.is_file().then_some(candidate)
+        })
+    })
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Whether `args` run a script defined in `working_directory`'s
+/// `package.json`, the only way the app uses package managers. Anything else,
+/// like `npm exec` or `bun -e`, could run arbitrary code.
+fn runs_package_script(name: &str, args: &[String], working_directory: Option<&str>) -> bool {
+    let script = match args.first().map(String::as_str) {
+        Some("run") => args.get(1),
+        _ if name == "npm" => None,
+        _ => args.first(),
+    };
+    let Some(script) = script.filter(|s| !s.starts_with('-')) else {
+        return false;
+    };
+    working_directory
+        .and_then(|dir| fs::read_to_string(Path::new(dir).join("package.json")).ok())
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|package| package["scripts"].get(script).is_some_and(|s| s.is_string()))
+}
+
+/// Whether `executable` may be spawned from the UI with `args` in
+/// `working_directory`.
+pub fn is_allowed(prefs: &Preferences, executable: &str, args: &[String], working_directory: Option<&str>) -> bool {
+    let executable = executable.trim();
+    if executable.is_empty() {
+        return false;
+    }
+    if prefs.approved_executables.iter().any(|e| e == executable) {
+        return true;
+    }
+
+    let name = command_name(executable);
+    let known = is_known_command(&name)
+        || (PACKAGE_MANAGERS.contains(&name.as_str()) && runs_package_script(&name, args, working_directory));
+    if is_bare_name(executable) {
+        return known;
+    }
+    // A full path to a known command, as agent detection reports it
+    known && find_in_path(&name).is_some_and(|found| same_file(&found, Path::new(executable)))
+}
+
+/// Rejects `executable` unless it is allowed to run with `args` in
+/// `working_directory`.
+pub fn check(app: &AppHandle, executable: &str, args: &[String], working_directory: Option<&str>) -> Result<(), String> {
+    let prefs = load_preferences_internal(app)?;
+    if is_allowed(&prefs, executable, args, working_directory) {
+        Ok(())
+    } else {
+        tracing::warn!("Blocked spawn of unapproved executable {}", executable);
+        Err(format!("{} {}", NOT_ALLOWED_PREFIX, executable))
+    }
+}
+
+/// Asks the user, in a native dialog the webview can't answer, whether to
+/// allow `executable`, and adds it to the approved list if they agree.
+/// Returns whether it was approved.
+#[tauri::command]
+pub async fn approve_executable(app: AppHandle, executable: String) -> Result<bool, String> {
+    let executable = executable.trim().to_string();
+    if executable.is_empty() {
+        return Err("Failed to approve executable: path is empty".to_string());
+    }
+    let mut prefs = load_preferences_internal(&app)?;
+    if prefs.approved_executables.contains(&executable) {
+        return Ok(true);
+    }
+
+    let dialog = app
+        .dialog()
+        .message(format!(
+            "Ideate wants to run \"{}\", which isn't a known agent or tool.\n\nOnly allow it if you trust this program.",
+            executable
+        ))
+        .title("Allow Executable?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Allow".to_string(), "Cancel".to_string()));
+    let approved = tokio::task::spawn_blocking(move || dialog.blocking_show())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    if !approved {
+        tracing::info!("Declined executable {}", executable);
+        return Ok(false);
+    }
+
+    tracing::info!("Approved executable {}", executable);
+    prefs.approved_executables.push(executable);
+    write_preferences(&app, prefs)?;
+    Ok(true)
+}
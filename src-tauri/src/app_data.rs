@@ -0,0 +1,496 @@
+//! Export and import of the app's own data.
+//!
+//! An export is one JSON archive holding the documents in the app data
+//! directory (preferences, the projects index, stacks, ideas, UI state, and
+//! the prompt library) plus a listing of the log files, for moving to another
+//! machine or reproducing a support issue. Secrets are never exported.
+//!
+//! Importing merges into what is already there. List documents are matched
+//! by `id` and object documents by top-level key; `ConflictResolution` decides
+//! which side wins where both have an entry.
+//!
+//! The directory itself can be moved with `move_app_data`. A
+//! `data-location.json` left in the platform's default directory points to
+//! the new one, and `app_data_dir` follows it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::file_lock;
+use crate::models::{Preferences, StoredProject};
+use crate::orchestrator::list_active_builds;
+use crate::preferences::save_preferences;
+use crate::projects::{load_projects, replace_projects};
+use crate::utils::{resolve_app_data_dir, DataLocation, DATA_LOCATION_FILE};
+
+/// Bumped when the archive layout changes incompatibly.
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Exported documents; lists of records with an `id` are merged per record.
+pub(crate) const DOCUMENTS: &[(&str, DocumentKind)] = &[
+    ("preferences.json", DocumentKind::Object),
+    ("projects.json", DocumentKind::List),
+    ("stacks.json", DocumentKind::List),
+    ("ideas.json", DocumentKind::List),
+    ("ui-state.json", DocumentKind::Object),
+    ("prompt-library.json", DocumentKind::List),
+];
+
+#[derive(Clone, Copy)]
+pub(crate) enum DocumentKind {
+    Object,
+    List,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataArchive {
+    pub archive_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub os: String,
+    /// Document contents keyed by file name.
+    pub documents: BTreeMap<String, Value>,
+    /// Log files at export time; their contents are not included.
+    #[serde(default)]
+    pub logs: Vec<LogFileMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileMeta {
+    pub name: String,
+    pub size: u64,
+    pub modified_at: Option<String>,
+}
+
+/// Which side wins when an entry exists both locally and in the archive.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    KeepExisting,
+    UseImported,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedDocument {
+    pub name: String,
+    pub added: usize,
+    pub replaced: usize,
+    pub kept: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub exported_at: String,
+    pub app_version: String,
+    pub documents: Vec<ImportedDocument>,
+}
+
+/// The platform's default app data directory, where `data-location.json`
+/// is kept.
+fn default_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// The app data directory, following a relocation by `move_app_data`.
+/// Every path under it should be resolved from here.
+pub(crate) fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = resolve_app_data_dir(default_app_data_dir(app)?);
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+    Ok(dir)
+}
+
+pub(crate) fn read_document(path: &Path) -> Result<Option<Value>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", name, e))
+}
+
+/// Reads a document by name. The projects registry is split into one file
+/// per project but still travels as a `projects.json` list.
+fn read_app_document(app: &AppHandle, data_dir: &Path, name: &str) -> Result<Option<Value>, String> {
+    if name == "projects.json" {
+        let projects = load_projects(app.clone())?;
+        return serde_json::to_value(projects)
+            .map(Some)
+            .map_err(|e| format!("Failed to serialize projects: {}", e));
+    }
+    read_document(&data_dir.join(name))
+}
+
+fn save_projects_value(app: &AppHandle, value: Value) -> Result<(), String> {
+    let projects: Vec<StoredProject> = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to parse projects.json: {}", e))?;
+    replace_projects(app, projects).map(|_| ())
+}
+
+fn list_logs(dir: &Path) -> Vec<LogFileMeta> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<LogFileMeta> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some(LogFileMeta {
+                name: entry.file_name().to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified_at: metadata
+                    .modified()
+                    .ok()
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+            })
+        })
+        .collect();
+    logs.sort_by(|a, b| a.name.cmp(&b.name));
+    logs
+}
+
+/// Writes the app's data to a single archive at `path`.
+#[tauri::command]
+pub fn export_app_data(app: AppHandle, path: String) -> Result<(), String> {
+    let data_dir = app_data_dir(&app)?;
+
+    let mut documents = BTreeMap::new();
+    for (name, _) in DOCUMENTS {
+        if let Some(value) = read_app_document(&app, &data_dir, name)? {
+            documents.insert(name.to_string(), value);
+        }
+    }
+
+    let archive = AppDataArchive {
+        archive_version: ARCHIVE_VERSION,
+        app_version: app.package_info().version.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        os: std::env::consts::OS.to_string(),
+        documents,
+        logs: list_logs(&data_dir.join("logs")),
+    };
+    let json = serde_json::to_string_pretty(&archive)
+        .map_err(|e| format!("Failed to serialize app data: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write export: {}", e))?;
+
+    tracing::info!("Exported app data to {}", path);
+    Ok(())
+}
+
+/// Merges `imported` into `existing` by top-level key.
+fn merge_object(
+    existing: &mut Map<String, Value>,
+    imported: Map<String, Value>,
+    conflict: ConflictResolution,
+    summary: &mut ImportedDocument,
+) {
+    for (key, value) in imported {
+        match existing.get_mut(&key) {
+            None => {
+                existing.insert(key, value);
+                summary.added += 1;
+            }
+            Some(current) if *current == value => {}
+            Some(current) => match conflict {
+                ConflictResolution::KeepExisting => summary.kept += 1,
+                ConflictResolution::UseImported => {
+                    *current = value;
+                    summary.replaced += 1;
+                }
+            },
+        }
+    }
+}
+
+/// Merges `imported` records into `existing` by `id`, appending new ones.
+fn merge_list(
+    existing: &mut Vec<Value>,
+    imported: Vec<Value>,
+    conflict: ConflictResolution,
+    summary: &mut ImportedDocument,
+) {
+    for record in imported {
+        let id = record.get("id").cloned();
+        let position = id
+            .as_ref()
+            .and_then(|id| existing.iter().position(|r| r.get("id") == Some(id)));
+        match position {
+            None => {
+                existing.push(record);
+                summary.added += 1;
+            }
+            Some(i) if existing[i] == record => {}
+            Some(i) => match conflict {
+                ConflictResolution::KeepExisting => summary.kept += 1,
+                ConflictResolution::UseImported => {
+                    existing[i] = record;
+                    summary.replaced += 1;
+                }
+            },
+        }
+    }
+}
+
+fn merge_document(
+    name: &str,
+    kind: DocumentKind,
+    existing: Option<Value>,
+    imported: Value,
+    conflict: ConflictResolution,
+) -> Result<(Value, ImportedDocument), String> {
+    let mut summary = ImportedDocument {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let invalid = || format!("Failed to import {}: unexpected format", name);
+    let merged = match kind {
+        DocumentKind::Object => {
+            let imported = match imported {
+                Value::Object(map) => map,
+                _ => return Err(invalid()),
+            };
+            let mut merged = match existing {
+                Some(Value::Object(map)) => map,
+                _ => Map::new(),
+            };
+            merge_object(&mut merged, imported, conflict, &mut summary);
+            Value::Object(merged)
+        }
+        DocumentKind::List => {
+            let imported = match imported {
+                Value::Array(records) => records,
+                _ => return Err(invalid()),
+            };
+            let mut merged = match existing {
+                Some(Value::Array(records)) => records,
+                _ => Vec::new(),
+            };
+            merge_list(&mut merged, imported, conflict, &mut summary);
+            Value::Array(merged)
+        }
+    };
+    Ok((merged, summary))
+}
+
+/// Merges `imported` into the document `name` in the app data directory.
+pub(crate) fn merge_into(
+    app: &AppHandle,
+    data_dir: &Path,
+    name: &str,
+    kind: DocumentKind,
+    imported: Value,
+    conflict: ConflictResolution,
+) -> Result<ImportedDocument, String> {
+    let doc_path = data_dir.join(name);
+
+    if name == "preferences.json" {
+        let (merged, summary) = merge_document(name, kind, read_document(&doc_path)?, imported, conflict)?;
+        save_preferences_value(app, merged)?;
+        return Ok(summary);
+    }
+    if name == "projects.json" {
+        let existing = read_app_document(app, data_dir, name)?;
+        let (merged, summary) = merge_document(name, kind, existing, imported, conflict)?;
+        save_projects_value(app, merged)?;
+        return Ok(summary);
+    }
+
+    file_lock::update(&doc_path, |content| {
+        let existing = content
+            .map(serde_json::from_str::<Value>)
+            .transpose()
+            .map_err(|e| format!("Failed to parse {}: {}", name, e))?;
+        let (merged, summary) = merge_document(name, kind, existing, imported.clone(), conflict)?;
+        let json = serde_json::to_string_pretty(&merged)
+            .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+        Ok((json, summary))
+    })
+}
+
+/// Replaces the document `name` in the app data directory with `value`.
+pub(crate) fn replace_document(app: &AppHandle, data_dir: &Path, name: &str, value: Value) -> Result<(), String> {
+    if name == "preferences.json" {
+        return save_preferences_value(app, value);
+    }
+    if name == "projects.json" {
+        return save_projects_value(app, value);
+    }
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    file_lock::update(&data_dir.join(name), |_| Ok((json.clone(), ())))
+}
+
+/// Preferences are saved through the command so validation and side effects apply.
+fn save_preferences_value(app: &AppHandle, value: Value) -> Result<(), String> {
+    let prefs: Preferences = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to parse preferences.json: {}", e))?;
+    save_preferences(app.clone(), prefs)
+}
+
+/// Merges an archive written by `export_app_data` into this machine's data.
+#[tauri::command]
+pub fn import_app_data(
+    app: AppHandle,
+    path: String,
+    conflict: ConflictResolution,
+) -> Result<ImportSummary, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read import: {}", e))?;
+    let mut archive: AppDataArchive =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse import: {}", e))?;
+    if archive.archive_version > ARCHIVE_VERSION {
+        return Err(format!(
+            "Failed to import: archive version {} is newer than this version of Ideate supports",
+            archive.archive_version
+        ));
+    }
+
+    let data_dir = app_data_dir(&app)?;
+    let mut documents = Vec::new();
+    for (name, kind) in DOCUMENTS {
+        let Some(imported) = archive.documents.remove(*name) else {
+            continue;
+        };
+        documents.push(merge_into(&app, &data_dir, name, *kind, imported, conflict)?);
+    }
+
+    tracing::info!("Imported app data from {}", path);
+    Ok(ImportSummary {
+        exported_at: archive.exported_at,
+        app_version: archive.app_version,
+        documents,
+    })
+}
+
+// ============================================================================
+// Data Location
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataLocation {
+    pub path: String,
+    pub default_path: String,
+    pub relocated: bool,
+}
+
+/// Returns where app data is stored and whether it was moved.
+#[tauri::command]
+pub fn get_app_data_location(app: AppHandle) -> Result<AppDataLocation, String> {
+    let default_dir = default_app_data_dir(&app)?;
+    let dir = resolve_app_data_dir(default_dir.clone());
+    Ok(AppDataLocation {
+        path: dir.to_string_lossy().to_string(),
+        default_path: default_dir.to_string_lossy().to_string(),
+        relocated: dir != default_dir,
+    })
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        let entries = fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        for entry in entries.flatten() {
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+    }
+}
+
+/// Moves all app data into `path`, points the app at it, and restarts so
+/// open logs and loaded state switch over; the default directory passed as
+/// `path` moves it back. Everything is copied before the pointer changes and
+/// originals are only removed after, so a failure leaves the data where it
+/// was.
+#[tauri::command]
+pub async fn move_app_data(app: AppHandle, path: String) -> Result<(), String> {
+    if !list_active_builds()?.is_empty() {
+        return Err("Failed to move app data: stop running builds first".to_string());
+    }
+    let default_dir = default_app_data_dir(&app)?;
+    let from = resolve_app_data_dir(default_dir.clone());
+    let to = PathBuf::from(path.trim());
+    if !to.is_absolute() {
+        return Err("Failed to move app data: choose an absolute path".to_string());
+    }
+    if to == from {
+        return Err("App data is already stored there".to_string());
+    }
+    if to.starts_with(&from) || (from.starts_with(&to) && to != default_dir) {
+        return Err("Failed to move app data: the new location can't contain, or be inside, the current one".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let entries: Vec<PathBuf> = fs::read_dir(&from)
+            .map_err(|e| format!("Failed to read app data directory: {}", e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|entry| entry.file_name().is_some_and(|name| name != DATA_LOCATION_FILE))
+            .collect();
+        if let Some(clash) = entries.iter().find(|entry| entry.file_name().is_some_and(|name| to.join(name).exists())) {
+            return Err(format!(
+                "Failed to move app data: {} already exists in the new location",
+                clash.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        }
+
+        fs::create_dir_all(&to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        for entry in &entries {
+            if let Some(name) = entry.file_name() {
+                copy_recursive(entry, &to.join(name))?;
+            }
+        }
+
+        let pointer = default_dir.join(DATA_LOCATION_FILE);
+        if to == default_dir {
+            fs::remove_file(&pointer).map_err(|e| format!("Failed to remove {}: {}", DATA_LOCATION_FILE, e))?;
+        } else {
+            fs::create_dir_all(&default_dir)
+                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+            let location = DataLocation {
+                path: to.to_string_lossy().to_string(),
+            };
+            let json = serde_json::to_string_pretty(&location)
+                .map_err(|e| format!("Failed to serialize {}: {}", DATA_LOCATION_FILE, e))?;
+            fs::write(&pointer, json).map_err(|e| format!("Failed to write {}: {}", DATA_LOCATION_FILE, e))?;
+        }
+
+        for entry in &entries {
+            let removed = if entry.is_dir() {
+                fs::remove_dir_all(entry)
+            } else {
+                fs::remove_file(entry)
+            };
+            if let Err(e) = removed {
+                tracing::warn!("Failed to remove {} after moving app data: {}", entry.display(), e);
+            }
+        }
+        if from != default_dir {
+            let _ = fs::remove_dir(&from);
+        }
+
+        tracing::info!("Moved app data from {} to {}", from.display(), to.display());
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    app.restart()
+}
@@ -0,0 +1,549 @@
+//! Imports task lists from other agent workflows into the PRD.
+//!
+//! Supported formats:
+//! - `tasks-md`: a Markdown checklist. Each top-level `- [ ]` item is a story;
+//!   items nested under it are its acceptance criteria, other nested text its
+//!   description, and the heading above it is kept in its notes.
+//! - `plan-md`: a plan document such as PLAN.md. Each heading at the deepest
+//!   level used is a story; bullets under it are acceptance criteria.
+//! - `taskmaster`: Taskmaster's `tasks.json`, tagged or not. Subtasks and the
+//!   test strategy become acceptance criteria, and dependencies are written
+//!   as "Depends on US-XXX" notes so parallel builds respect them.
+//! - `github-projects`: a GitHub Projects view exported as CSV, or
+//!   `gh project item-list --format json`.
+//!
+//! Tasks marked done import as passed stories. Tasks whose title matches an
+//! existing story are skipped, so importing the same file twice adds nothing.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::integrations::{push_imported_story, split_acceptance_criteria};
+use crate::models::Prd;
+use crate::projects::{load_prd, save_prd};
+
+/// A task read from a backlog file, before it becomes a story.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogTask {
+    /// The task's ID in its source, for resolving dependencies.
+    pub key: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub acceptance_criteria: Vec<String>,
+    pub done: bool,
+    /// Keys of tasks this one waits on.
+    pub depends_on: Vec<String>,
+    /// Where the task came from, e.g. "tasks.md > Setup" or a URL.
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogPreview {
+    pub format: String,
+    pub tasks: Vec<BacklogTask>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogImportResult {
+    pub format: String,
+    pub imported: Vec<String>,
+    /// Tasks matching an existing story's title, or canceled in their source.
+    pub skipped: usize,
+}
+
+fn is_done(status: &str) -> bool {
+    matches!(
+        status.trim().to_lowercase().as_str(),
+        "done" | "complete" | "completed" | "closed" | "merged" | "shipped"
+    )
+}
+
+/// `- [ ] text` or `* [x] text`, with whether it was checked.
+fn checklist_item(line: &str) -> Option<(bool, &str)> {
+    let rest = line.trim_start().strip_prefix(['-', '*', '+'])?.trim_start();
+    let (checked, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]")) {
+        (true, text)
+    } else {
+        return None;
+    };
+    Some((checked, text.trim()))
+}
+
+/// `- text`, `* text`, or `1. text`, checked or not.
+fn list_item(line: &str) -> Option<&str> {
+    if let Some((_, text)) = checklist_item(line) {
+        return Some(text);
+    }
+    let trimmed = line.trim_start();
+    if let Some(text) = trimmed.strip_prefix(['-', '*', '+']) {
+        return text.starts_with(' ').then(|| text.trim());
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    let text = trimmed[digits..].strip_prefix(['.', ')'])?;
+    (digits > 0 && text.starts_with(' ')).then(|| text.trim())
+}
+
+/// `## text`, with its level.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (level > 0).then(|| (level, text.trim().trim_end_matches('#').trim()))
+}
+
+fn indent(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Labels like "**Acceptance criteria:**" that introduce a list.
+fn is_list_label(line: &str) -> bool {
+    let label = line.trim().trim_matches('*').trim_end_matches(':').trim_matches('*').to_lowercase();
+    matches!(
+        label.as_str(),
+        "acceptance criteria" | "criteria" | "tasks" | "subtasks" | "steps" | "checklist" | "definition of done"
+    )
+}
+
+/// Strips leading numbering like "1.", "Task 3:", or "Step 2 -", and a
+/// checkbox, from a heading. Returns the title and whether it was checked off.
+fn clean_title(text: &str) -> (String, bool) {
+    let mut done = false;
+    let mut title = text.trim();
+    for (marker, checked) in [("[ ]", false), ("[x]", true), ("[X]", true), ("✅", true)] {
+        if let Some(rest) = title.strip_prefix(marker) {
+            title = rest.trim();
+            done |= checked;
+        }
+        if let Some(rest) = title.strip_suffix(marker) {
+            title = rest.trim();
+            done |= checked;
+        }
+    }
+    for word in ["Task", "Step", "Phase", "Milestone"] {
+        if let Some(rest) = title.strip_prefix(word) {
+            let rest = rest.trim_start();
+            let digits = rest.chars().take_while(|c| c.is_ascii_digit() || *c == '.').count();
+            if digits > 0 {
+                title = rest[digits..].trim_start_matches([':', '-', '—', ')', ' ']);
+            }
+        }
+    }
+    let digits = title.chars().take_while(|c| c.is_ascii_digit() || *c == '.').count();
+    if digits > 0 && title[digits..].starts_with([' ', ')']) {
+        title = title[digits..].trim_start_matches([')', ' ']);
+    }
+    (title.trim_matches('*').trim().to_string(), done)
+}
+
+fn parse_tasks_md(content: &str, file_name: &str) -> Vec<BacklogTask> {
+    let mut tasks: Vec<BacklogTask> = Vec::new();
+    let mut section: Option<String> = None;
+    // Indentation of the checklist items that start tasks
+    let base = content
+        .lines()
+        .filter(|line| checklist_item(line).is_some())
+        .map(indent)
+        .min()
+        .unwrap_or(0);
+    let mut in_task = false;
+
+    for line in content.lines() {
+        if let Some((_, text)) = heading(line) {
+            section = Some(text.to_string());
+            in_task = false;
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let depth = indent(line);
+        if depth <= base {
+            in_task = false;
+            if let Some((checked, text)) = checklist_item(line) {
+                let (title, done) = clean_title(text);
+                tasks.push(BacklogTask {
+                    title,
+                    done: checked || done,
+                    source: match &section {
+                        Some(section) => format!("{} > {}", file_name, section),
+                        None => file_name.to_string(),
+                    },
+                    ..Default::default()
+                });
+                in_task = true;
+            }
+            continue;
+        }
+        let Some(task) = tasks.last_mut().filter(|_| in_task) else {
+            continue;
+        };
+        match list_item(line) {
+            Some(item) if !item.is_empty() => task.acceptance_criteria.push(item.to_string()),
+            _ if is_list_label(line) => {}
+            _ => {
+                if !task.description.is_empty() {
+                    task.description.push('\n');
+                }
+                task.description.push_str(line.trim());
+            }
+        }
+    }
+    tasks
+}
+
+fn parse_plan_md(content: &str, file_name: &str) -> Vec<BacklogTask> {
+    // The first level-one heading is the document's title; tasks are the
+    // deepest headings below it
+    let levels: Vec<usize> = content.lines().filter_map(heading).map(|(level, _)| level).collect();
+    let Some(&task_level) = levels.iter().filter(|&&l| l > 1).max().or(levels.iter().max()) else {
+        return Vec::new();
+    };
+
+    let mut tasks: Vec<BacklogTask> = Vec::new();
+    let mut sections: Vec<(usize, String)> = Vec::new();
+    let mut in_task = false;
+    let mut in_code = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code {
+            if let Some((level, text)) = heading(line) {
+                if level == task_level {
+                    let (title, done) = clean_title(text);
+                    let parents: Vec<&str> = sections.iter().filter(|(l, _)| *l > 1).map(|(_, t)| t.as_str()).collect();
+                    tasks.push(BacklogTask {
+                        title,
+                        done,
+                        source: std::iter::once(file_name).chain(parents).collect::<Vec<_>>().join(" > "),
+                        ..Default::default()
+                    });
+                    in_task = true;
+                } else if level < task_level {
+                    sections.retain(|(l, _)| *l < level);
+                    sections.push((level, text.to_string()));
+                    in_task = false;
+                }
+                continue;
+            }
+        }
+        let Some(task) = tasks.last_mut().filter(|_| in_task) else {
+            continue;
+        };
+        match list_item(line).filter(|_| !in_code) {
+            Some(item) if !item.is_empty() => task.acceptance_criteria.push(item.to_string()),
+            _ if is_list_label(line) => {}
+            _ => {
+                if !task.description.is_empty() || !line.trim().is_empty() {
+                    task.description.push_str(line);
+                    task.description.push('\n');
+                }
+            }
+        }
+    }
+    for task in &mut tasks {
+        task.description = task.description.trim().to_string();
+    }
+    tasks
+}
+
+fn json_key(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn json_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default().trim()
+}
+
+/// Taskmaster's tasks, from `{ "tasks": [...] }` or, with tags,
+/// `{ "master": { "tasks": [...] }, ... }`.
+fn taskmaster_tasks(root: &Value) -> Option<&Vec<Value>> {
+    root.get("tasks").and_then(Value::as_array).or_else(|| {
+        root.get("master")
+            .and_then(|tag| tag.get("tasks"))
+            .and_then(Value::as_array)
+            .or_else(|| {
+                root.as_object()?
+                    .values()
+                    .find_map(|tag| tag.get("tasks").and_then(Value::as_array))
+            })
+    })
+}
+
+fn parse_taskmaster(root: &Value, file_name: &str) -> (Vec<BacklogTask>, usize) {
+    let mut tasks = Vec::new();
+    let mut canceled = 0;
+    for task in taskmaster_tasks(root).into_iter().flatten() {
+        let status = json_str(task, "status");
+        if matches!(status, "cancelled" | "canceled") {
+            canceled += 1;
+            continue;
+        }
+        let key = task.get("id").and_then(json_key);
+        let description = [json_str(task, "description"), json_str(task, "details")]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let mut acceptance_criteria: Vec<String> = task
+            .get("subtasks")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|subtask| json_str(subtask, "title").to_string())
+            .filter(|title| !title.is_empty())
+            .collect();
+        let test_strategy = json_str(task, "testStrategy");
+        if !test_strategy.is_empty() {
+            acceptance_criteria.push(test_strategy.to_string());
+        }
+        tasks.push(BacklogTask {
+            source: match &key {
+                Some(key) => format!("Taskmaster task {}", key),
+                None => file_name.to_string(),
+            },
+            key,
+            title: json_str(task, "title").to_string(),
+            description,
+            acceptance_criteria,
+            done: is_done(status),
+            depends_on: task
+                .get("dependencies")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(json_key)
+                .collect(),
+        });
+    }
+    (tasks, canceled)
+}
+
+fn github_task(title: &str, body: &str, status: &str, url: &str, file_name: &str) -> BacklogTask {
+    let (description, acceptance_criteria) = split_acceptance_criteria(body);
+    BacklogTask {
+        key: None,
+        title: title.trim().to_string(),
+        description,
+        acceptance_criteria,
+        done: is_done(status),
+        depends_on: Vec::new(),
+        source: if url.is_empty() { file_name.to_string() } else { url.to_string() },
+    }
+}
+
+/// Items from `gh project item-list --format json`.
+fn parse_github_json(root: &Value, file_name: &str) -> Vec<BacklogTask> {
+    root.get("items")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|item| {
+            let content = item.get("content").unwrap_or(&Value::Null);
+            let title = Some(json_str(item, "title"))
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| json_str(content, "title"));
+            github_task(title, json_str(content, "body"), json_str(item, "status"), json_str(content, "url"), file_name)
+        })
+        .collect()
+}
+
+/// Splits CSV into rows of fields, honoring quoted fields with embedded
+/// commas, quotes, and newlines.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|r| r.iter().any(|f| !f.trim().is_empty()));
+    rows
+}
+
+/// Rows of a GitHub Projects CSV export. Only a Title column is required.
+fn parse_github_csv(content: &str, file_name: &str) -> Result<Vec<BacklogTask>, String> {
+    let rows = parse_csv(content);
+    let Some((header, rows)) = rows.split_first() else {
+        return Ok(Vec::new());
+    };
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+    };
+    let title = column(&["Title"]).ok_or("Failed to import backlog: the CSV has no Title column")?;
+    let body = column(&["Body", "Description"]);
+    let status = column(&["Status"]);
+    let url = column(&["URL"]);
+    let cell = |row: &Vec<String>, index: Option<usize>| -> String {
+        index.and_then(|i| row.get(i)).cloned().unwrap_or_default()
+    };
+    Ok(rows
+        .iter()
+        .map(|row| {
+            github_task(
+                &cell(row, Some(title)),
+                &cell(row, body),
+                &cell(row, status),
+                &cell(row, url),
+                file_name,
+            )
+        })
+        .collect())
+}
+
+/// Guesses the format from the file's name and contents.
+fn detect_format(path: &Path, content: &str) -> &'static str {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if name.ends_with(".csv") {
+        return "github-projects";
+    }
+    if name.ends_with(".json") {
+        let root: Value = serde_json::from_str(content).unwrap_or(Value::Null);
+        return if root.get("items").is_some() { "github-projects" } else { "taskmaster" };
+    }
+    if name.contains("plan") || !content.lines().any(|line| checklist_item(line).is_some()) {
+        return "plan-md";
+    }
+    "tasks-md"
+}
+
+/// Reads and parses a backlog file. Returns the format used, the tasks, and
+/// how many were canceled in their source.
+fn read_backlog(path: &str, format: Option<String>) -> Result<(String, Vec<BacklogTask>, usize), String> {
+    let path = Path::new(path);
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let format = format.unwrap_or_else(|| detect_format(path, &content).to_string());
+
+    let parse_json = || {
+        serde_json::from_str::<Value>(&content).map_err(|e| format!("Failed to parse {}: {}", file_name, e))
+    };
+    let (tasks, canceled) = match format.as_str() {
+        "tasks-md" => (parse_tasks_md(&content, &file_name), 0),
+        "plan-md" => (parse_plan_md(&content, &file_name), 0),
+        "taskmaster" => parse_taskmaster(&parse_json()?, &file_name),
+        "github-projects" if content.trim_start().starts_with('{') => (parse_github_json(&parse_json()?, &file_name), 0),
+        "github-projects" => (parse_github_csv(&content, &file_name)?, 0),
+        other => return Err(format!("Failed to import backlog: unknown format {:?}", other)),
+    };
+    let tasks = tasks.into_iter().filter(|t| !t.title.is_empty()).collect();
+    Ok((format, tasks, canceled))
+}
+
+/// Parses a backlog file without changing the PRD. `format` is detected from
+/// the file when omitted.
+#[tauri::command]
+pub fn preview_backlog_import(path: String, format: Option<String>) -> Result<BacklogPreview, String> {
+    let (format, tasks, _) = read_backlog(&path, format)?;
+    Ok(BacklogPreview { format, tasks })
+}
+
+/// Imports a backlog file's tasks into the project's PRD as stories.
+#[tauri::command(rename_all = "camelCase")]
+pub fn import_backlog(
+    project_path: String,
+    path: String,
+    format: Option<String>,
+) -> Result<BacklogImportResult, String> {
+    let (format, tasks, canceled) = read_backlog(&path, format)?;
+
+    let mut prd = load_prd(project_path.clone())?.unwrap_or(Prd {
+        project: None,
+        branch_name: None,
+        description: None,
+        user_stories: Vec::new(),
+    });
+    let mut existing: HashSet<String> = prd.user_stories.iter().map(|s| s.title.to_lowercase()).collect();
+
+    let mut result = BacklogImportResult {
+        format,
+        imported: Vec::new(),
+        skipped: canceled,
+    };
+    let mut story_ids: HashMap<String, String> = HashMap::new();
+    let mut imported = Vec::new();
+    for task in tasks {
+        if !existing.insert(task.title.to_lowercase()) {
+            result.skipped += 1;
+            continue;
+        }
+        let story_id = push_imported_story(
+            &mut prd,
+            task.title.clone(),
+            task.description.clone(),
+            task.acceptance_criteria.clone(),
+            format!("Imported from {}", task.source),
+        );
+        if let Some(key) = &task.key {
+            story_ids.insert(key.clone(), story_id.clone());
+        }
+        imported.push((story_id, task));
+    }
+
+    for (story_id, task) in &imported {
+        let Some(story) = prd.user_stories.iter_mut().find(|s| &s.id == story_id) else {
+            continue;
+        };
+        if task.done {
+            story.passes = true;
+            story.status = Some("complete".to_string());
+        }
+        let prerequisites: Vec<&str> = task
+            .depends_on
+            .iter()
+            .filter_map(|key| story_ids.get(key).map(String::as_str))
+            .collect();
+        if !prerequisites.is_empty() {
+            story.notes.push_str(&format!("\nDepends on {}", prerequisites.join(", ")));
+        }
+        result.imported.push(story_id.clone());
+    }
+
+    if !result.imported.is_empty() {
+        save_prd(project_path, prd)?;
+    }
+    Ok(result)
+}
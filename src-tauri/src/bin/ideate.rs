@@ -0,0 +1,7 @@
+//! `ideate build|status|usage|types`: the headless CLI, for servers and CI.
+//! Built without the GUI; see `cli`.
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    std::process::exit(ideate_lib::run_cli(&args));
+}
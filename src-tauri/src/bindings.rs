@@ -0,0 +1,114 @@
+//! TypeScript definitions generated from the Rust models.
+//!
+//! The camelCase structs in `models` derive `ts_rs::TS`, and
+//! `ideate types` writes one `.ts` file per type, plus an `index.ts`
+//! re-exporting them, so the frontend can import them instead of keeping
+//! hand-written copies in sync. Command signatures are not generated; the
+//! argument names still follow each command's camelCase parameters.
+
+use std::fs;
+use std::path::Path;
+
+use ts_rs::TS;
+
+use crate::models::*;
+
+/// Default output directory, relative to the repository root.
+pub const DEFAULT_OUT_DIR: &str = "src/types/generated";
+
+/// Writes the definitions of every model into `out_dir`. Returns the
+/// exported type names.
+pub fn export(out_dir: &Path) -> Result<Vec<String>, String> {
+    macro_rules! export {
+        ($($ty:ty),* $(,)?) => {{
+            let mut names = Vec::new();
+            $(
+                <$ty as TS>::export_all_to(out_dir)
+                    .map_err(|e| format!("Failed to export {}: {}", <$ty as TS>::name(), e))?;
+                names.push(<$ty as TS>::name());
+            )*
+            names
+        }};
+    }
+
+    fs::create_dir_all(out_dir).map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+    let mut names = export!(
+        ProjectConfig,
+        ProjectHooks,
+        E2eConfig,
+        VerificationConfig,
+        MetadataHistoryConfig,
+        RoutingConfig,
+        RoutingRule,
+        ProjectSettings,
+        CreateProjectResult,
+        StoredProject,
+        ProjectUpdate,
+        Story,
+        ProjectIdea,
+        Prd,
+        StoryRetryInfo,
+        DesignComponent,
+        DesignArchitecture,
+        DesignTechStack,
+        DesignApiEndpoint,
+        DesignDataModel,
+        DesignConsiderations,
+        Design,
+        ProjectState,
+        StoryFailure,
+        StoryVerification,
+        VerificationRun,
+        StoryCoverage,
+        E2eRun,
+        E2eTestResult,
+        DeployPreview,
+        CostEntry,
+        CostHistory,
+        Idea,
+        DraftStory,
+        SpawnAgentResult,
+        KillAgentResult,
+        KillPath,
+        TerminationReason,
+        WaitAgentResult,
+        AgentOutputLine,
+        AgentOutputEvent,
+        ProcessPriority,
+        OutputMode,
+        AgentExitEvent,
+        ProcessLogEntry,
+        ProcessCommand,
+        ProcessHistoryEntry,
+        ProcessHistory,
+        LogChunk,
+        LogFileInfo,
+        AgentModel,
+        AgentPlugin,
+        AgentPluginStatus,
+        ProgressRule,
+        AgentCliPath,
+        OutRayCredentials,
+        OutRayConfig,
+        CloudflareTunnelConfig,
+        NgrokConfig,
+        CurrencyConfig,
+        Preferences,
+        RecentThreadDuration,
+        StackTool,
+        Stack,
+        StackToolUpdate,
+    );
+    names.sort();
+
+    let index: String = names
+        .iter()
+        .map(|name| format!("export type {{ {name} }} from \"./{name}\";\n"))
+        .collect();
+    fs::write(
+        out_dir.join("index.ts"),
+        format!("// Generated by `ideate types`. Do not edit.\n\n{}", index),
+    )
+    .map_err(|e| format!("Failed to write index.ts: {}", e))?;
+    Ok(names)
+}
@@ -0,0 +1,839 @@
+//! Headless command-line interface.
+//!
+//! `ideate build|status|usage|types` works on a project directory without the
+//! app, so builds can run on servers or in CI. The `ideate` binary is built
+//! without the `gui` feature, so it needs no Tauri, GTK, or WebKit. Each story
+//! goes through the orchestrator's `story_loop`, with the same routing,
+//! retries, fallback agents, hooks, and verification as in the app, but
+//! stories run one at a time in the project directory and the build stops
+//! where the app would pause for a human.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::currency::{self, DisplayCurrency};
+use crate::hooks::{HookContext, HookKind};
+use crate::models::{ProcessPriority, ProjectState, RoutingRule, StoredProject, Story};
+use crate::notifier::{self, BuildNotification};
+use crate::orchestrator::journal;
+use crate::orchestrator::modes::{BuildMode, FailureAction};
+use crate::orchestrator::report::{self, parse_usage, BuildReport, StoryReport};
+use crate::orchestrator::routing;
+use crate::orchestrator::story_loop::{self, AttemptFailure, StoryAgent, StoryHost, StoryLimits};
+use crate::orchestrator::{
+    detect_agent_error, detect_auth_error, read_session, relevant_error_lines, reload_story, resolve_agent,
+    resolve_mode, select_stories, short_hash, BuildOptions, StoryRetryContext, RECENT_OUTPUT_LINES,
+};
+use crate::preferences::load_preferences_headless;
+use crate::process::{apply_priority, exit_signal};
+use crate::project_env;
+use crate::redact;
+use crate::prompt_templates::PromptEngine;
+use crate::projects::{
+    load_cost_history, load_prd, load_project_settings, load_project_state, update_prd, update_project_state,
+};
+use crate::usage::{load_amp_usage_sync, load_claude_usage_sync};
+use crate::utils::headless_app_data_dir;
+use crate::{bindings, throttle, worktree};
+
+const USAGE: &str = "Usage: ideate <command> [options]
+
+Commands:
+  build     Build the project's incomplete stories
+  status    Show story progress and the last build
+  usage     Show cost and token usage
+  types     Write TypeScript definitions of the app's models
+
+Options:
+  --project <path>   Project directory (default: current directory)
+  --agent <id>       Agent to build with (build)
+  --mode <id>        Build mode (build)
+  --story <id>       Only build this story; repeatable (build)
+  --from <id>        Start at this story (build)
+  --since <days>     Only count the last N days (usage)
+  --json             Print JSON (status, usage)
+  --out <dir>        Output directory (types; default: src/types/generated)";
+
+struct CliArgs {
+    command: String,
+    project_path: String,
+    agent: Option<String>,
+    mode: Option<String>,
+    stories: Vec<String>,
+    from: Option<String>,
+    since_days: Option<i64>,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs {
+        command: args.get(1).cloned().unwrap_or_default(),
+        project_path: std::env::current_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| ".".to_string()),
+        agent: None,
+        mode: None,
+        stories: Vec::new(),
+        from: None,
+        since_days: None,
+        json: false,
+    };
+
+    let mut rest = args.iter().skip(2);
+    while let Some(flag) = rest.next() {
+        let mut value = || rest.next().cloned().ok_or_else(|| format!("{} needs a value", flag));
+        match flag.as_str() {
+            "--project" => parsed.project_path = value()?,
+            "--agent" => parsed.agent = Some(value()?),
+            "--mode" => parsed.mode = Some(value()?),
+            "--story" => parsed.stories.push(value()?),
+            "--from" => parsed.from = Some(value()?),
+            "--since" => {
+                let days = value()?;
+                parsed.since_days = Some(days.parse().map_err(|_| format!("Invalid number of days: {}", days))?);
+            }
+            "--json" => parsed.json = true,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+
+    if !Path::new(&parsed.project_path).join(".ideate").exists() {
+        return Err(format!("'{}' is not an Ideate project", parsed.project_path));
+    }
+    Ok(parsed)
+}
+
+/// Runs a CLI command. Returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    match args.get(1).map(String::as_str) {
+        Some("help" | "--help") => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        None => {
+            eprintln!("{}", USAGE);
+            return 2;
+        }
+        Some(_) => {}
+    }
+    // Run from the repository, not a project
+    if args.get(1).is_some_and(|a| a == "types") {
+        return match types(args) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("ideate: {}", e);
+                2
+            }
+        };
+    }
+
+    let result = parse_args(args).and_then(|cli| match cli.command.as_str() {
+        "build" => build(&cli).map(|complete| if complete { 0 } else { 1 }),
+        "status" => status(&cli).map(|_| 0),
+        "usage" => usage(&cli).map(|_| 0),
+        _ => Err(USAGE.to_string()),
+    });
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("ideate: {}", e);
+            2
+        }
+    }
+}
+
+/// Writes the TypeScript definitions of the models to `--out`.
+fn types(args: &[String]) -> Result<(), String> {
+    let mut out_dir = bindings::DEFAULT_OUT_DIR.to_string();
+    let mut rest = args.iter().skip(2);
+    while let Some(flag) = rest.next() {
+        match flag.as_str() {
+            "--out" => out_dir = rest.next().cloned().ok_or("--out needs a value")?,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+    let names = bindings::export(Path::new(&out_dir))?;
+    println!("Wrote {} types to {}", names.len(), out_dir);
+    Ok(())
+}
+
+/// The project's registered ID and name, if the app knows it.
+fn registered_project(project_path: &str) -> Option<StoredProject> {
+    let projects = crate::project_registry::load(&headless_app_data_dir().ok()?).ok()?;
+    let wanted = Path::new(project_path).canonicalize().ok()?;
+    projects
+        .into_iter()
+        .find(|p| Path::new(&p.path).canonicalize().ok().as_ref() == Some(&wanted))
+}
+
+fn directory_name(project_path: &str) -> String {
+    Path::new(project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string())
+}
+
+struct AgentRun {
+    failure: Option<(&'static str, String)>,
+    output: String,
+    duration_ms: u64,
+}
+
+/// Streams a child's output to ours while keeping the tail for analysis.
+fn tee_output(stream: impl Read + Send + 'static, recent: Arc<Mutex<VecDeque<String>>>, stderr: bool) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            let line = redact::mask_literals(&line).into_owned();
+            if stderr {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+            if let Ok(mut recent) = recent.lock() {
+                if recent.len() == RECENT_OUTPUT_LINES {
+                    recent.pop_front();
+                }
+                recent.push_back(line);
+            }
+        }
+    })
+}
+
+fn run_agent(
+    agent: &StoryAgent,
+    args: &[String],
+    project_path: &str,
+    timeout_minutes: u32,
+    priority: ProcessPriority,
+) -> AgentRun {
+    let executable = &agent.executable;
+    let started = Instant::now();
+    let mut cmd = Command::new(executable);
+    cmd.args(args)
+        .current_dir(project_path)
+        .envs(project_env::resolve_in(headless_app_data_dir().ok().as_deref(), project_path))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    apply_priority(&mut cmd, priority);
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            let message = format!("Failed to spawn {}: {}", executable, e);
+            return AgentRun {
+                failure: Some(("spawn-error", message.clone())),
+                output: message,
+                duration_ms: 0,
+            };
+        }
+    };
+
+    let recent = Arc::new(Mutex::new(VecDeque::new()));
+    let readers: Vec<_> = [
+        child.stdout.take().map(|s| tee_output(s, recent.clone(), false)),
+        child.stderr.take().map(|s| tee_output(s, recent.clone(), true)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let limit = (timeout_minutes > 0).then(|| Duration::from_secs(u64::from(timeout_minutes) * 60));
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if limit.is_some_and(|limit| started.elapsed() >= limit) => {
+                timed_out = true;
+                let _ = child.kill();
+                break child.wait();
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(200)),
+            Err(e) => break Err(e),
+        }
+    };
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let output = recent
+        .lock()
+        .map(|r| r.iter().cloned().collect::<Vec<_>>().join("\n"))
+        .unwrap_or_default();
+    let failure = match status {
+        _ if timed_out => Some(("timeout", format!("timed out after {} min", timeout_minutes))),
+        Err(e) => Some(("process-error", e.to_string())),
+        Ok(status) if exit_signal(&status).is_some() => Some((
+            "signal",
+            format!("killed by signal {}", exit_signal(&status).unwrap_or_default()),
+        )),
+        Ok(status) if !status.success() => Some((
+            "exit-code",
+            format!(
+                "exit code: {}",
+                status.code().map(|c| c.to_string()).unwrap_or_else(|| "unknown".to_string())
+            ),
+        )),
+        Ok(_) if detect_agent_error(&output) => Some(("agent-error", "agent error detected in output".to_string())),
+        Ok(_) => None,
+    };
+    let throttle = output.lines().find_map(throttle::detect);
+    if let Some(signal) = &throttle {
+        throttle::record(&agent.agent_id, signal, "output", chrono::Utc::now());
+    }
+    let failure = match failure {
+        Some(("exit-code" | "agent-error", _)) if detect_auth_error(&output) => {
+            Some(("auth", format!("{} is not authenticated", agent.agent_id)))
+        }
+        // The provider turned the agent away; waiting it out doesn't cost a retry
+        Some(("exit-code" | "agent-error", _)) if throttle.is_some() => Some((
+            "throttled",
+            throttle.map_or_else(String::new, |t| format!("provider {}: {}", t.kind.replace('-', " "), t.message)),
+        )),
+        failure => failure,
+    };
+
+    AgentRun {
+        failure,
+        output,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// A build run from the command line: stories run one at a time in the
+/// project directory, and everything the app would show is printed.
+#[derive(Clone)]
+struct HeadlessBuild {
+    project_path: String,
+    build_id: String,
+    hook_ctx: HookContext,
+    agent: StoryAgent,
+    prompts: Arc<PromptEngine>,
+    limits: StoryLimits,
+    routing: Arc<Vec<RoutingRule>>,
+    reports: Arc<Mutex<Vec<StoryReport>>>,
+    /// Failure context for stories that will be retried, keyed by story ID.
+    retries: Arc<Mutex<HashMap<String, StoryRetryContext>>>,
+}
+
+impl HeadlessBuild {
+    fn mark_passed(&self, story_id: &str) -> Result<(), String> {
+        update_prd(&self.project_path, |prd| {
+            if let Some(story) = prd.user_stories.iter_mut().find(|s| s.id == story_id) {
+                story.passes = true;
+                story.status = Some("complete".to_string());
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs the agent on a story in the project directory.
+    async fn run_agent(&self, story: &Story) -> AgentRun {
+        let prompt = story_loop::render_prompt(self, story, &self.project_path);
+        let args = self.agent.args(&prompt);
+        let (agent, project_path, limits) = (self.agent.clone(), self.project_path.clone(), self.limits.clone());
+        tokio::task::spawn_blocking(move || {
+            run_agent(&agent, &args, &project_path, limits.timeout_minutes, limits.priority)
+        })
+        .await
+        .unwrap_or_else(|e| AgentRun {
+            failure: Some(("process-error", format!("Task join error: {}", e))),
+            output: String::new(),
+            duration_ms: 0,
+        })
+    }
+}
+
+impl StoryHost for HeadlessBuild {
+    fn project_id(&self) -> &str {
+        &self.hook_ctx.project_id
+    }
+
+    fn project_path(&self) -> &str {
+        &self.project_path
+    }
+
+    fn app_data_dir(&self) -> Option<PathBuf> {
+        headless_app_data_dir().ok()
+    }
+
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            agent_id: self.agent.agent_id.clone(),
+            ..self.hook_ctx.clone()
+        }
+    }
+
+    fn agent(&self) -> &StoryAgent {
+        &self.agent
+    }
+
+    fn with_agent(&self, agent: StoryAgent) -> Self {
+        HeadlessBuild {
+            agent,
+            ..self.clone()
+        }
+    }
+
+    fn limits(&self) -> StoryLimits {
+        self.limits.clone()
+    }
+
+    fn routing_rules(&self) -> &[RoutingRule] {
+        &self.routing
+    }
+
+    fn prompts(&self) -> &PromptEngine {
+        &self.prompts
+    }
+
+    fn build_cost(&self) -> f64 {
+        self.reports
+            .lock()
+            .map(|reports| reports.iter().filter_map(|r| r.cost).sum())
+            .unwrap_or(0.0)
+    }
+
+    fn stopping(&self) -> bool {
+        false
+    }
+
+    fn log(&self, content: impl Into<String>) {
+        println!("{}", content.into());
+    }
+
+    fn journal(&self, kind: &str, story_id: Option<&str>, data: serde_json::Value) {
+        let _ = journal::append(&self.project_path, Some(&self.build_id), kind, story_id, data);
+    }
+
+    fn update_state(&self, apply: impl FnMut(&mut ProjectState)) {
+        if let Err(e) = update_project_state(&self.project_path, apply) {
+            eprintln!("Warning: {}", e);
+        }
+    }
+
+    fn update_story_report(&self, story: &Story, apply: impl FnOnce(&mut StoryReport)) {
+        if let Ok(mut reports) = self.reports.lock() {
+            let index = match reports.iter().position(|r| r.story_id == story.id) {
+                Some(index) => index,
+                None => {
+                    reports.push(StoryReport::new(&story.id, &story.title));
+                    reports.len() - 1
+                }
+            };
+            apply(&mut reports[index]);
+        }
+    }
+
+    fn set_story_status(&self, story_id: &str, status: &str) {
+        self.update_state(|state| {
+            state.story_statuses.insert(story_id.to_string(), status.to_string());
+        });
+    }
+
+    fn retry_context(&self, story_id: &str) -> Option<StoryRetryContext> {
+        self.retries.lock().ok().and_then(|r| r.get(story_id).cloned())
+    }
+
+    fn record_failure(&self, story_id: &str, output: &str) {
+        if let Ok(mut retries) = self.retries.lock() {
+            let attempt = retries.get(story_id).map_or(0, |r| r.attempt) + 1;
+            retries.insert(
+                story_id.to_string(),
+                StoryRetryContext {
+                    attempt,
+                    error_logs: relevant_error_lines(output),
+                },
+            );
+        }
+    }
+
+    async fn attempt(&self, story: &Story, _parallel: bool) -> Result<(), AttemptFailure> {
+        self.set_story_status(&story.id, "in-progress");
+        println!("▶ Starting story {}: {}", story.id, story.title);
+
+        let run = self.run_agent(story).await;
+        let usage = parse_usage(&run.output);
+        self.update_story_report(story, |report| report.add_attempt(run.duration_ms, usage));
+        self.journal(
+            "exit",
+            Some(&story.id),
+            json!({
+                "failureKind": run.failure.as_ref().map(|f| f.0),
+                "reason": run.failure.as_ref().map(|f| &f.1),
+                "durationMs": run.duration_ms,
+                "tokens": usage.tokens,
+                "cost": usage.cost,
+            }),
+        );
+        if let Some((kind, reason)) = run.failure {
+            eprintln!("✗ Story {} failed ({})", story.id, reason);
+            self.set_story_status(&story.id, "failed");
+            self.record_failure(&story.id, &run.output);
+            return Err(AttemptFailure::new(kind, reason));
+        }
+
+        if let Err(failure) = story_loop::verify(self, story, &self.project_path).await {
+            eprintln!("✗ Story {} failed ({})", story.id, failure.message);
+            self.set_story_status(&story.id, "failed");
+            return Err(failure);
+        }
+
+        println!("✓ Story {} completed successfully", story.id);
+        if let Err(e) = self.mark_passed(&story.id) {
+            eprintln!("Warning: Could not save PRD: {}", e);
+        }
+        self.set_story_status(&story.id, "complete");
+        if worktree::is_git_repo(&self.project_path).unwrap_or(false) {
+            match worktree::commit_story(&self.project_path, &story.id, &story.title) {
+                Ok(hash) => {
+                    println!("✓ Committed changes: {}", short_hash(&hash));
+                    story_loop::record_commit(self, story, &hash);
+                }
+                Err(e) => eprintln!("Warning: Could not commit changes: {}", e),
+            }
+        }
+        Ok(())
+    }
+
+    async fn backoff(&self, delay: Duration) -> bool {
+        tokio::time::sleep(delay).await;
+        true
+    }
+}
+
+/// Builds the project's stories. Returns whether every story passed.
+fn build(cli: &CliArgs) -> Result<bool, String> {
+    let project_path = cli.project_path.clone();
+    if read_session(&project_path)?.is_some() {
+        return Err("A build is running or was interrupted in the app - finish or discard it first".to_string());
+    }
+
+    let prefs = load_preferences_headless().unwrap_or_default();
+    if let Err(e) = redact::configure(&prefs) {
+        eprintln!("Warning: {}", e);
+    }
+    let settings = load_project_settings(project_path.clone())?;
+    let options = BuildOptions {
+        from_story_id: cli.from.clone(),
+        story_ids: (!cli.stories.is_empty()).then(|| cli.stories.clone()),
+        agent_id: cli.agent.clone(),
+        ..Default::default()
+    };
+    let mode: &'static dyn BuildMode = resolve_mode(settings.as_ref(), &options, cli.mode.clone())?;
+    let agent_id = resolve_agent(settings.as_ref(), &prefs, &options);
+    let agent = StoryAgent::new(&agent_id, None).ok_or_else(|| format!("Unknown agent: {}", agent_id))?;
+
+    let stories = select_stories(&project_path, &options)?;
+    if stories.is_empty() {
+        return Err("No stories to build".to_string());
+    }
+
+    let project = registered_project(&project_path);
+    let stack_ids = project.as_ref().map(StoredProject::stack_ids).unwrap_or_default();
+    let project_id = project.as_ref().map_or_else(|| directory_name(&project_path), |p| p.id.clone());
+    let project_name = project.map_or_else(|| directory_name(&project_path), |p| p.name);
+
+    if mode.parallel() {
+        println!("Note: {} runs stories in parallel in the app; the CLI runs them one at a time", mode.label());
+    } else if mode.swarm() {
+        println!("Note: {} splits stories between agents in the app; the CLI runs one agent per story", mode.label());
+    }
+    println!("Building {} with {} ({} mode, {} stories)", project_name, agent_id, mode.label(), stories.len());
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to start async runtime: {}", e))?;
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let started = Instant::now();
+    let build_id = format!(
+        "{}-{}",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let ctx = HeadlessBuild {
+        project_path: project_path.clone(),
+        hook_ctx: HookContext {
+            project_id: project_id.clone(),
+            project_name: project_name.clone(),
+            project_path: project_path.clone(),
+            build_id: build_id.clone(),
+            mode: mode.id().to_string(),
+            agent_id: agent_id.clone(),
+        },
+        build_id,
+        agent,
+        prompts: Arc::new(PromptEngine::for_project(
+            &prefs.prompt_overrides,
+            headless_app_data_dir().ok().as_deref(),
+            &project_path,
+            &stack_ids,
+        )),
+        limits: StoryLimits::from_preferences(&prefs),
+        routing: Arc::new(routing::load_rules(&project_path)),
+        reports: Arc::new(Mutex::new(Vec::new())),
+        retries: Arc::new(Mutex::new(HashMap::new())),
+    };
+    ctx.journal(
+        "build-started",
+        None,
+        json!({ "mode": mode.id(), "agentId": agent_id, "storyCount": stories.len(), "headless": true }),
+    );
+
+    // Same loop as the app's sequential build, minus pausing
+    let selected: Vec<String> = stories.into_iter().map(|s| s.id).collect();
+    let mut failures: HashMap<String, u32> = HashMap::new();
+    let mut failed_ids: HashSet<String> = HashSet::new();
+    let mut skipped: HashSet<String> = HashSet::new();
+    let mut passed = 0;
+    let mut stopped = runtime
+        .block_on(story_loop::run_hook(&ctx, HookKind::PreBuild, None, Vec::new()))
+        .is_some_and(|hook| !hook.success);
+    if stopped {
+        println!("Build aborted: the preBuild hook failed");
+    }
+    while !stopped {
+        let candidates: Vec<Story> = selected
+            .iter()
+            .filter(|id| !skipped.contains(*id))
+            .filter_map(|id| reload_story(&project_path, id))
+            .collect();
+        let Some(story) = mode.next_story(&candidates, &failures).cloned() else {
+            break;
+        };
+
+        if runtime.block_on(story_loop::run_story(&ctx, &story, false)) {
+            failed_ids.remove(&story.id);
+            passed += 1;
+            continue;
+        }
+
+        failed_ids.insert(story.id.clone());
+        let count = failures.entry(story.id.clone()).or_insert(0);
+        *count += 1;
+        match mode.on_failure(*count) {
+            FailureAction::Pause => {
+                println!("Stopping: {} mode pauses on failure and there is no one to resume it", mode.label());
+                stopped = true;
+                break;
+            }
+            FailureAction::Retry => println!("Retrying {} (attempt {})", story.id, *count + 1),
+            FailureAction::Skip => {
+                println!("Skipping {} after {} failed attempt(s)", story.id, count);
+                skipped.insert(story.id.clone());
+            }
+        }
+    }
+
+    let failed = failed_ids.len();
+    let outcome = if failed == 0 && !stopped { "complete" } else { "incomplete" };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let stories = ctx.reports.lock().map(|mut r| std::mem::take(&mut *r)).unwrap_or_default();
+    let report = BuildReport {
+        build_id: ctx.build_id.clone(),
+        project_id,
+        project_name: project_name.clone(),
+        mode: mode.id().to_string(),
+        agent_id,
+        outcome: outcome.to_string(),
+        started_at,
+        finished_at: chrono::Utc::now().to_rfc3339(),
+        duration_ms,
+        passed,
+        failed,
+        retries: 0,
+        total_tokens: None,
+        total_cost: None,
+        files_changed: 0,
+        insertions: 0,
+        deletions: 0,
+        stories,
+        display_currency: headless_display_currency(),
+    }
+    .with_totals();
+    match report::write_report(&project_path, &report) {
+        Ok(()) => println!("Build report saved to .ideate/reports/{}.md", report.build_id),
+        Err(e) => eprintln!("Warning: {}", e),
+    }
+    ctx.journal(
+        "build-finished",
+        None,
+        json!({ "outcome": outcome, "passed": passed, "failed": failed, "durationMs": duration_ms }),
+    );
+
+    // Project webhooks (Slack, Discord, ...) work the same as in the app
+    let notification = BuildNotification::BuildCompleted {
+        project_name,
+        passed,
+        failed,
+        duration_secs: Some(duration_ms / 1000),
+    };
+    if let Err(e) = runtime.block_on(notifier::notify(&project_path, &notification)) {
+        eprintln!("Warning: {}", e);
+    }
+
+    runtime.block_on(story_loop::run_hook(
+        &ctx,
+        HookKind::PostBuild,
+        None,
+        vec![
+            ("IDEATE_BUILD_OUTCOME", outcome.to_string()),
+            ("IDEATE_BUILD_PASSED", passed.to_string()),
+            ("IDEATE_BUILD_FAILED", failed.to_string()),
+        ],
+    ));
+
+    println!("Build {}: {} passed, {} failed", outcome, passed, failed);
+    Ok(outcome == "complete")
+}
+
+fn status(cli: &CliArgs) -> Result<(), String> {
+    let prd = load_prd(cli.project_path.clone())?.ok_or_else(|| "No prd.json found for this project".to_string())?;
+    let state = load_project_state(cli.project_path.clone())?;
+    let session = read_session(&cli.project_path)?;
+    let last_build = report::list_build_reports(cli.project_path.clone())?.into_iter().next();
+
+    let mut stories = prd.user_stories;
+    stories.sort_by_key(|s| s.priority);
+    let story_status = |story: &Story| -> String {
+        if story.passes {
+            return "complete".to_string();
+        }
+        state
+            .as_ref()
+            .and_then(|s| s.story_statuses.get(&story.id).cloned())
+            .unwrap_or_else(|| "pending".to_string())
+    };
+    let failures = state.as_ref().and_then(|s| s.story_failures.clone()).unwrap_or_default();
+    let passed = stories.iter().filter(|s| s.passes).count();
+
+    if cli.json {
+        let json = json!({
+            "passed": passed,
+            "total": stories.len(),
+            "buildInProgress": session.is_some(),
+            "stories": stories.iter().map(|s| json!({
+                "id": s.id,
+                "title": s.title,
+                "status": story_status(s),
+                "failure": failures.get(&s.id),
+            })).collect::<Vec<_>>(),
+            "lastBuild": last_build,
+        });
+        println!("{}", serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to serialize status: {}", e))?);
+        return Ok(());
+    }
+
+    println!("{}/{} stories complete", passed, stories.len());
+    if let Some(session) = session {
+        println!("Build in progress or interrupted (started {})", session.started_at);
+    }
+    for story in &stories {
+        let status = story_status(story);
+        let marker = match status.as_str() {
+            "complete" => "✓",
+            "failed" => "✗",
+            "in-progress" => "▶",
+            _ => "·",
+        };
+        println!("  {} {:<10} {}", marker, story.id, story.title);
+        if let Some(failure) = failures.get(&story.id).filter(|_| !story.passes) {
+            println!("      {} after {} attempt(s): {}", failure.kind, failure.attempts, failure.message);
+        }
+    }
+    if let Some(build) = last_build {
+        println!(
+            "Last build {}: {} ({} passed, {} failed{})",
+            build.build_id,
+            build.outcome,
+            build.passed,
+            build.failed,
+            build.total_cost.map(|c| format!(", ${:.2}", c)).unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// The display currency from preferences, using whatever rates the app last cached.
+fn headless_display_currency() -> Option<DisplayCurrency> {
+    let prefs = load_preferences_headless().ok()?;
+    Some(currency::resolve(&headless_app_data_dir().ok()?, &prefs.currency))
+}
+
+fn usage(cli: &CliArgs) -> Result<(), String> {
+    let since = cli.since_days.map(|days| chrono::Utc::now() - chrono::Duration::days(days));
+    let in_range = |timestamp: &str| {
+        since.is_none_or(|since| {
+            chrono::DateTime::parse_from_rfc3339(timestamp).is_ok_and(|t| t.with_timezone(&chrono::Utc) >= since)
+        })
+    };
+
+    // Per-agent totals from the project's cost history
+    let mut by_agent: HashMap<String, (i64, f64, usize)> = HashMap::new();
+    for entry in load_cost_history(cli.project_path.clone())?
+        .entries
+        .iter()
+        .filter(|e| in_range(&e.timestamp))
+    {
+        let totals = by_agent.entry(entry.agent_id.clone()).or_default();
+        totals.0 += entry.total_tokens.unwrap_or(0);
+        totals.1 += entry.cost.unwrap_or(0.0);
+        totals.2 += 1;
+    }
+    let builds: Vec<_> = report::list_build_reports(cli.project_path.clone())?
+        .into_iter()
+        .filter(|b| in_range(&b.started_at))
+        .collect();
+    let build_cost: f64 = builds.iter().filter_map(|b| b.total_cost).sum();
+
+    // Account-wide usage read from the agents' own logs
+    let since_ms = since.map(|s| s.timestamp_millis());
+    let claude = load_claude_usage_sync(since_ms, &|_, _| {}).ok();
+    let amp = load_amp_usage_sync(since_ms, &|_, _| {}).ok();
+    let currency = headless_display_currency().unwrap_or_else(DisplayCurrency::usd);
+
+    if cli.json {
+        let json = json!({
+            "project": by_agent.iter().map(|(agent, (tokens, cost, runs))| {
+                json!({ "agentId": agent, "tokens": tokens, "cost": cost, "runs": runs })
+            }).collect::<Vec<_>>(),
+            "builds": builds.len(),
+            "buildCost": build_cost,
+            "buildCostDisplay": currency.convert(build_cost),
+            "displayCurrency": currency,
+            "claude": claude.as_ref().map(|c| json!({ "totalTokens": c.total_tokens, "sessions": c.session_count })),
+            "amp": amp.as_ref().map(|a| json!({ "totalTokens": a.total_tokens, "credits": a.total_credits, "threads": a.thread_count })),
+        });
+        println!("{}", serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to serialize usage: {}", e))?);
+        return Ok(());
+    }
+
+    match cli.since_days {
+        Some(days) => println!("Usage for the last {} day(s)", days),
+        None => println!("Usage for all time"),
+    }
+    println!("Project:");
+    if by_agent.is_empty() {
+        println!("  No recorded agent runs");
+    }
+    let mut agents: Vec<_> = by_agent.into_iter().collect();
+    agents.sort_by(|a, b| a.0.cmp(&b.0));
+    for (agent, (tokens, cost, runs)) in agents {
+        println!("  {:<12} {:>5} runs {:>12} tokens   {}", agent, runs, tokens, currency.format(cost));
+    }
+    println!("  {} build(s), {} estimated", builds.len(), currency.format(build_cost));
+    if let Some(claude) = claude.filter(|c| c.session_count > 0) {
+        println!("Claude Code (all projects): {} tokens over {} sessions", claude.total_tokens, claude.session_count);
+    }
+    if let Some(amp) = amp.filter(|a| a.thread_count > 0) {
+        println!(
+            "Amp (all projects): {} tokens, {:.2} credits over {} threads",
+            amp.total_tokens, amp.total_credits, amp.thread_count
+        );
+    }
+    Ok(())
+}
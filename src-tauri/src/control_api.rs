@@ -0,0 +1,321 @@
+//! Local HTTP control API.
+//!
+//! When enabled in Preferences, serves a small JSON API on
+//! `http://127.0.0.1:<port>/api` so scripts and other tools can list projects,
+//! check build status and usage, and start or stop builds. Every request must
+//! send `Authorization: Bearer <token>` (or `?token=<token>`, for WebSocket
+//! clients); the token is generated on first use, kept in the secrets store,
+//! and shown in Settings.
+//!
+//! - `GET  /api/projects`
+//! - `GET  /api/builds`
+//! - `GET  /api/projects/{id}/build`
+//! - `POST /api/projects/{id}/build` (optional `BuildOptions` body)
+//! - `POST /api/projects/{id}/build/stop`
+//! - `GET  /api/projects/{id}/usage`
+//! - `GET  /api/events` (WebSocket, see `event_bridge`)
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::json;
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+use crate::currency::{self, DisplayCurrency};
+use crate::event_bridge;
+use crate::models::{Preferences, StoredProject};
+use crate::orchestrator::{self, report, BuildOptions, BuildStatusInfo};
+use crate::preferences::load_preferences_internal;
+use crate::projects::{load_cost_history, load_prd, load_projects};
+use crate::secrets;
+
+const TOKEN_SECRET_KEY: &str = "control-api-token";
+
+lazy_static::lazy_static! {
+    static ref SERVER: Mutex<Option<ServerHandle>> = Mutex::new(None);
+    /// Token requests are checked against; replaced when regenerated.
+    static ref TOKEN: Mutex<String> = Mutex::new(String::new());
+}
+
+struct ServerHandle {
+    port: u16,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+struct ApiState {
+    app: AppHandle,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlApiInfo {
+    pub running: bool,
+    pub port: u16,
+    pub url: String,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectBuildStatus {
+    project_id: String,
+    /// The active build, if any.
+    build: Option<BuildStatusInfo>,
+    passed: usize,
+    total: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentUsage {
+    runs: usize,
+    tokens: i64,
+    cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectUsage {
+    project_id: String,
+    total_tokens: i64,
+    total_cost: f64,
+    /// `total_cost` converted to `display_currency`.
+    total_cost_display: f64,
+    display_currency: DisplayCurrency,
+    by_agent: BTreeMap<String, AgentUsage>,
+    builds: Vec<report::BuildReportSummary>,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, Json<serde_json::Value>)>;
+
+fn api_error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<serde_json::Value>) {
+    (status, Json(json!({ "error": message.into() })))
+}
+
+fn internal(message: String) -> (StatusCode, Json<serde_json::Value>) {
+    api_error(StatusCode::INTERNAL_SERVER_ERROR, message)
+}
+
+/// Compares tokens without leaking how much of a guess matched.
+pub(crate) fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Reads the token from the `Authorization` header, falling back to the
+/// `token` query parameter since browsers can't set headers on WebSockets.
+pub(crate) fn request_token(request: &Request) -> Option<String> {
+    let header_token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string());
+    header_token.or_else(|| {
+        request
+            .uri()
+            .query()?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(str::to_string)
+    })
+}
+
+async fn require_token(request: Request, next: Next) -> Response {
+    let expected = TOKEN.lock().map(|t| t.clone()).unwrap_or_default();
+    let authorized = !expected.is_empty()
+        && request_token(&request).is_some_and(|token| tokens_match(&token, &expected));
+    if !authorized {
+        return api_error(StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response();
+    }
+    next.run(request).await
+}
+
+fn find_project(app: &AppHandle, project_id: &str) -> Result<StoredProject, (StatusCode, Json<serde_json::Value>)> {
+    load_projects(app.clone())
+        .map_err(internal)?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("Project {} not found", project_id)))
+}
+
+async fn list_projects(State(state): State<Arc<ApiState>>) -> ApiResult<Vec<StoredProject>> {
+    load_projects(state.app.clone()).map(Json).map_err(internal)
+}
+
+async fn list_builds() -> ApiResult<Vec<BuildStatusInfo>> {
+    orchestrator::list_active_builds().map(Json).map_err(internal)
+}
+
+async fn build_status(State(state): State<Arc<ApiState>>, Path(project_id): Path<String>) -> ApiResult<ProjectBuildStatus> {
+    let project = find_project(&state.app, &project_id)?;
+    let stories = load_prd(project.path.clone())
+        .map_err(internal)?
+        .map(|prd| prd.user_stories)
+        .unwrap_or_default();
+    Ok(Json(ProjectBuildStatus {
+        build: orchestrator::get_build_status(project_id.clone()).map_err(internal)?,
+        project_id,
+        passed: stories.iter().filter(|s| s.passes).count(),
+        total: stories.len(),
+    }))
+}
+
+async fn start_build(
+    State(state): State<Arc<ApiState>>,
+    Path(project_id): Path<String>,
+    options: Option<Json<BuildOptions>>,
+) -> ApiResult<BuildStatusInfo> {
+    let project = find_project(&state.app, &project_id)?;
+    orchestrator::start_build(state.app.clone(), project.id, project.path, options.map(|Json(o)| o))
+        .await
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::CONFLICT, e))
+}
+
+async fn stop_build(State(state): State<Arc<ApiState>>, Path(project_id): Path<String>) -> ApiResult<serde_json::Value> {
+    find_project(&state.app, &project_id)?;
+    orchestrator::stop_build(state.app.clone(), project_id).await.map_err(internal)?;
+    Ok(Json(json!({ "stopped": true })))
+}
+
+async fn project_usage(State(state): State<Arc<ApiState>>, Path(project_id): Path<String>) -> ApiResult<ProjectUsage> {
+    let project = find_project(&state.app, &project_id)?;
+    let history = load_cost_history(project.path.clone()).map_err(internal)?;
+
+    let mut by_agent: BTreeMap<String, AgentUsage> = BTreeMap::new();
+    for entry in &history.entries {
+        let usage = by_agent.entry(entry.agent_id.clone()).or_default();
+        usage.runs += 1;
+        usage.tokens += entry.total_tokens.unwrap_or(0);
+        usage.cost += entry.cost.unwrap_or(0.0);
+    }
+    let total_cost: f64 = by_agent.values().map(|u| u.cost).sum();
+    let display_currency = currency::display_currency(&state.app);
+    Ok(Json(ProjectUsage {
+        project_id,
+        total_tokens: by_agent.values().map(|u| u.tokens).sum(),
+        total_cost,
+        total_cost_display: display_currency.convert(total_cost),
+        display_currency,
+        by_agent,
+        builds: report::list_build_reports(project.path).map_err(internal)?,
+    }))
+}
+
+/// Returns the API token, creating one on first use.
+fn ensure_token(app: &AppHandle) -> Result<String, String> {
+    if let Some(token) = secrets::get_secret(app, TOKEN_SECRET_KEY)?.filter(|t| !t.is_empty()) {
+        return Ok(token);
+    }
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    secrets::set_secret(app.clone(), TOKEN_SECRET_KEY.to_string(), token.clone())?;
+    Ok(token)
+}
+
+fn running_port() -> Option<u16> {
+    SERVER.lock().ok().and_then(|s| s.as_ref().map(|h| h.port))
+}
+
+async fn start(app: AppHandle, port: u16) -> Result<(), String> {
+    *TOKEN.lock().map_err(|e| format!("Lock error: {}", e))? = ensure_token(&app)?;
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind to port {}: {}", port, e))?;
+
+    let state = Arc::new(ApiState { app });
+    let router = Router::new()
+        .route("/api/projects", get(list_projects))
+        .route("/api/builds", get(list_builds))
+        .route("/api/projects/{id}/build", get(build_status).post(start_build))
+        .route("/api/projects/{id}/build/stop", post(stop_build))
+        .route("/api/projects/{id}/usage", get(project_usage))
+        .route("/api/events", get(event_bridge::ws_handler))
+        .layer(middleware::from_fn(require_token))
+        .with_state(state);
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        if let Err(e) = server.await {
+            tracing::error!("Control API error: {}", e);
+        }
+    });
+
+    let mut server = SERVER.lock().map_err(|e| format!("Lock error: {}", e))?;
+    *server = Some(ServerHandle {
+        port,
+        shutdown_tx: Some(shutdown_tx),
+    });
+    Ok(())
+}
+
+/// Stops the control API server if it is running.
+pub fn stop() {
+    if let Ok(mut server) = SERVER.lock() {
+        if let Some(mut handle) = server.take() {
+            if let Some(tx) = handle.shutdown_tx.take() {
+                let _ = tx.send(());
+            }
+        }
+    }
+}
+
+/// Starts, stops, or restarts the server to match preferences.
+pub fn apply_preferences(app: &AppHandle, prefs: &Preferences) {
+    let running = running_port();
+    if !prefs.control_api_enabled {
+        stop();
+        return;
+    }
+    if running == Some(prefs.control_api_port) {
+        return;
+    }
+    stop();
+    let app = app.clone();
+    let port = prefs.control_api_port;
+    tauri::async_runtime::spawn(async move {
+        match start(app, port).await {
+            Ok(()) => tracing::info!("Control API listening on http://127.0.0.1:{}/api", port),
+            Err(e) => tracing::error!("Control API: {}", e),
+        }
+    });
+}
+
+/// Returns whether the control API is running, where, and its token.
+#[tauri::command]
+pub fn get_control_api_info(app: AppHandle) -> Result<ControlApiInfo, String> {
+    let prefs = load_preferences_internal(&app).unwrap_or_default();
+    let port = running_port().unwrap_or(prefs.control_api_port);
+    Ok(ControlApiInfo {
+        running: running_port().is_some(),
+        port,
+        url: format!("http://127.0.0.1:{}/api", port),
+        token: secrets::get_secret(&app, TOKEN_SECRET_KEY)?,
+    })
+}
+
+/// Replaces the API token. The old one stops working immediately.
+#[tauri::command]
+pub fn regenerate_control_api_token(app: AppHandle) -> Result<ControlApiInfo, String> {
+    secrets::delete_secret(app.clone(), TOKEN_SECRET_KEY.to_string())?;
+    let token = ensure_token(&app)?;
+    if running_port().is_some() {
+        *TOKEN.lock().map_err(|e| format!("Lock error: {}", e))? = token;
+    }
+    get_control_api_info(app)
+}
@@ -0,0 +1,338 @@
+//! Attribution of agent usage to builds and interactive agent sessions.
+//!
+//! Builds and interactive sessions run agents as separate processes, so their
+//! token usage is only recorded in the agents' own logs: Amp threads and
+//! Claude Code sessions. After a run finishes, the threads and sessions that
+//! started during it
+//! (with a little slack on either side) in the project or one of its story
+//! worktrees are appended to the project's costs.json, one entry per thread
+//! with its thread ID. Entries already recorded for a thread are left alone,
+//! so reconciling twice adds nothing.
+//!
+//! Agents that use an OpenAI API key directly keep no such logs; when a key is
+//! stored, what the organization used during their runs is read from the
+//! OpenAI usage API instead, once it has caught up (see `integrations::openai`).
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_data::app_data_dir;
+use crate::file_lock;
+use crate::integrations::openai;
+use crate::models::{CostEntry, CostHistory};
+use crate::orchestrator::report::{self, BuildReport};
+use crate::pricing::{self, Pricing};
+use crate::usage::{self, AmpUsageEntry, ClaudeUsageEntry};
+use crate::usage_index;
+use crate::utils::get_ideate_dir;
+
+/// Slack around a run's start and end when matching threads to it.
+const WINDOW_SLACK_MS: i64 = 60_000;
+
+/// Wait after a run finishes before reconciling, so agents flush their logs.
+const SETTLE_DELAY: Duration = Duration::from_secs(30);
+
+/// Wait after a run finishes before reading its OpenAI usage, which the usage
+/// API reports a few minutes late.
+const OPENAI_SETTLE_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// USD per Amp credit; mirrors `creditsToUsd` in `src/utils/agentPricing.ts`.
+pub(crate) const AMP_CREDIT_USD: f64 = 0.01;
+
+/// A span of agent activity in a project that threads are attributed to.
+#[derive(Debug, Clone)]
+pub struct TrackedRun {
+    pub project_id: String,
+    pub agent_id: String,
+    /// Prefix of the cost entry descriptions, e.g. "Build 20250101-120000".
+    pub label: String,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+impl From<&BuildReport> for TrackedRun {
+    fn from(report: &BuildReport) -> Self {
+        Self {
+            project_id: report.project_id.clone(),
+            agent_id: report.agent_id.clone(),
+            label: format!("Build {}", report.build_id),
+            started_at: report.started_at.clone(),
+            finished_at: report.finished_at.clone(),
+        }
+    }
+}
+
+/// Payload of `costs-reconciled`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileSummary {
+    pub project_id: String,
+    pub project_path: String,
+    /// Entries appended to costs.json.
+    pub added: usize,
+    /// Matching threads that were already recorded.
+    pub already_recorded: usize,
+}
+
+/// Claude Code names each project's session directory after its path with
+/// every character other than ASCII letters and digits replaced by `-`.
+fn claude_dir_name(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn in_window(timestamp: &str, window: (i64, i64)) -> bool {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .is_ok_and(|t| (window.0..=window.1).contains(&t.timestamp_millis()))
+}
+
+fn run_window(run: &TrackedRun) -> Option<(i64, i64)> {
+    let start = chrono::DateTime::parse_from_rfc3339(&run.started_at).ok()?;
+    let end = chrono::DateTime::parse_from_rfc3339(&run.finished_at).ok()?;
+    Some((
+        start.timestamp_millis() - WINDOW_SLACK_MS,
+        end.timestamp_millis() + WINDOW_SLACK_MS,
+    ))
+}
+
+/// Working directories recorded in an Amp thread's environment, if any.
+fn amp_thread_dirs(thread_id: &str) -> Vec<PathBuf> {
+    let Some(dir) = usage::amp_threads_dir() else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(dir.join(format!("{}.json", thread_id))) else {
+        return Vec::new();
+    };
+    let Ok(thread) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+    thread
+        .pointer("/env/initial/trees")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tree| tree.get("uri").and_then(Value::as_str))
+        .map(|uri| PathBuf::from(uri.strip_prefix("file://").unwrap_or(uri)))
+        .collect()
+}
+
+fn claude_matches(session: &ClaudeUsageEntry, project_path: &str) -> bool {
+    let project = claude_dir_name(project_path.trim_end_matches('/'));
+    let worktrees = format!("{}--ideate-worktrees-", project);
+    session.project_path == project || session.project_path.starts_with(&worktrees)
+}
+
+/// Threads without a recorded directory match only runs of Amp.
+fn amp_matches(thread: &AmpUsageEntry, project_path: &str, run: &TrackedRun) -> bool {
+    let dirs = amp_thread_dirs(&thread.thread_id);
+    if dirs.is_empty() {
+        return run.agent_id == "amp";
+    }
+    dirs.iter().any(|dir| dir.starts_with(Path::new(project_path)))
+}
+
+fn claude_entry(session: &ClaudeUsageEntry, run: &TrackedRun, pricing: &Pricing) -> CostEntry {
+    let cost = session
+        .model
+        .as_deref()
+        .and_then(|model| pricing::price_for(pricing, model))
+        .map(|price| {
+            let cache_read = price.cache_read_per_million.unwrap_or(price.input_per_million);
+            let cache_write = price.cache_write_per_million.unwrap_or(price.input_per_million);
+            (session.input_tokens as f64 * price.input_per_million
+                + session.output_tokens as f64 * price.output_per_million
+                + session.cache_read_tokens as f64 * cache_read
+                + session.cache_creation_tokens as f64 * cache_write)
+                / 1_000_000.0
+        });
+    CostEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: run.project_id.clone(),
+        timestamp: session.timestamp.clone(),
+        agent_id: "claude-code".to_string(),
+        description: format!("{} (Claude Code session)", run.label),
+        input_tokens: Some(session.input_tokens),
+        output_tokens: Some(session.output_tokens),
+        total_tokens: Some(session.total_tokens),
+        cost,
+        credits: None,
+        model: session.model.clone(),
+        thread_id: Some(session.session_id.clone()),
+        duration_ms: Some(session.duration_ms),
+    }
+}
+
+fn amp_entry(thread: &AmpUsageEntry, run: &TrackedRun) -> CostEntry {
+    let title = thread.thread_title.as_deref().unwrap_or("Amp thread");
+    CostEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: run.project_id.clone(),
+        timestamp: thread.timestamp.clone(),
+        agent_id: "amp".to_string(),
+        description: format!("{}: {}", run.label, title),
+        input_tokens: Some(thread.input_tokens),
+        output_tokens: Some(thread.output_tokens),
+        total_tokens: Some(thread.total_tokens),
+        cost: Some(thread.credits * AMP_CREDIT_USD),
+        credits: Some(thread.credits),
+        model: thread.model.clone(),
+        thread_id: Some(thread.thread_id.clone()),
+        duration_ms: Some(thread.duration_ms),
+    }
+}
+
+/// Appends entries for the agent threads that ran during `runs`, and
+/// `api_entries` read from provider usage APIs.
+fn reconcile(
+    app: &AppHandle,
+    project_path: &str,
+    runs: &[TrackedRun],
+    api_entries: Vec<CostEntry>,
+) -> Result<ReconcileSummary, String> {
+    let Some(earliest) = runs.iter().filter_map(run_window).map(|w| w.0).min() else {
+        return Ok(ReconcileSummary::default());
+    };
+    let data_dir = app_data_dir(app)?;
+    let no_progress = |_: usize, _: usize| {};
+    let amp = usage_index::amp_usage(&data_dir, Some(earliest), &no_progress)?;
+    let claude = usage_index::claude_usage(&data_dir, Some(earliest), &no_progress)?;
+    let pricing = pricing::load_pricing(app.clone())?;
+
+    let mut candidates = Vec::new();
+    for run in runs {
+        let Some(window) = run_window(run) else { continue };
+        for session in claude.entries.iter().filter(|s| in_window(&s.timestamp, window)) {
+            if claude_matches(session, project_path) {
+                candidates.push(claude_entry(session, run, &pricing));
+            }
+        }
+        for thread in amp.entries.iter().filter(|t| in_window(&t.timestamp, window)) {
+            if amp_matches(thread, project_path, run) {
+                candidates.push(amp_entry(thread, run));
+            }
+        }
+    }
+
+    candidates.extend(api_entries);
+
+    let cost_path = get_ideate_dir(project_path).join("costs.json");
+    if let Some(dir) = cost_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+    let project_id = runs[0].project_id.clone();
+    file_lock::update(&cost_path, |content| {
+        let mut history: CostHistory = serde_json::from_str(content.unwrap_or("{\"entries\":[]}"))
+            .map_err(|e| format!("Failed to parse costs.json: {}", e))?;
+        let mut recorded: HashSet<String> =
+            history.entries.iter().filter_map(|e| e.thread_id.clone()).collect();
+
+        let mut summary = ReconcileSummary {
+            project_id: project_id.clone(),
+            project_path: project_path.to_string(),
+            ..Default::default()
+        };
+        for entry in &candidates {
+            // A thread spanning two runs is attributed to the first
+            let thread_id = entry.thread_id.clone().unwrap_or_default();
+            if recorded.insert(thread_id) {
+                history.entries.push(entry.clone());
+                summary.added += 1;
+            } else {
+                summary.already_recorded += 1;
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&history)
+            .map_err(|e| format!("Failed to serialize cost history: {}", e))?;
+        Ok((json, summary))
+    })
+}
+
+fn finish(app: &AppHandle, project_path: &str, summary: &ReconcileSummary) {
+    if summary.added > 0 {
+        tracing::info!("Attributed {} agent thread(s) to runs in {}", summary.added, project_path);
+        let _ = app.emit("costs-reconciled", summary.clone());
+    }
+}
+
+/// OpenAI usage entries for the runs of agents that use an OpenAI key.
+/// Failures are logged, so they don't hold up attributing the agents' logs.
+async fn openai_entries(app: &AppHandle, runs: &[TrackedRun]) -> Vec<CostEntry> {
+    let runs: Vec<&TrackedRun> = runs.iter().filter(|r| openai::tracks_agent(app, &r.agent_id)).collect();
+    if runs.is_empty() {
+        return Vec::new();
+    }
+    let pricing = match pricing::load_pricing(app.clone()) {
+        Ok(pricing) => pricing,
+        Err(e) => {
+            tracing::warn!("OpenAI usage attribution failed: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut entries = Vec::new();
+    for run in runs {
+        match openai::run_entries(app, run, &pricing).await {
+            Ok(found) => entries.extend(found),
+            Err(e) => tracing::warn!("Failed to read OpenAI usage for {}: {}", run.label, e),
+        }
+    }
+    entries
+}
+
+async fn reconcile_async(
+    app: AppHandle,
+    project_path: String,
+    runs: Vec<TrackedRun>,
+    api_entries: Vec<CostEntry>,
+) -> Result<ReconcileSummary, String> {
+    tokio::task::spawn_blocking(move || {
+        let summary = reconcile(&app, &project_path, &runs, api_entries)?;
+        finish(&app, &project_path, &summary);
+        Ok(summary)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Reconciles a finished run once its agents have had time to flush, and
+/// again for its OpenAI usage once the usage API has caught up.
+pub fn spawn_for_run(app: AppHandle, project_path: String, run: TrackedRun) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SETTLE_DELAY).await;
+        let runs = vec![run];
+        if let Err(e) = reconcile_async(app.clone(), project_path.clone(), runs.clone(), Vec::new()).await {
+            tracing::warn!("Cost attribution failed: {}", e);
+        }
+
+        if !openai::tracks_agent(&app, &runs[0].agent_id) {
+            return;
+        }
+        tokio::time::sleep(OPENAI_SETTLE_DELAY - SETTLE_DELAY).await;
+        let entries = openai_entries(&app, &runs).await;
+        if entries.is_empty() {
+            return;
+        }
+        if let Err(e) = reconcile_async(app, project_path, runs, entries).await {
+            tracing::warn!("Cost attribution failed: {}", e);
+        }
+    });
+}
+
+/// Attributes agent threads, and OpenAI usage, to every recorded build of a
+/// project.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn reconcile_build_costs(app: AppHandle, project_path: String) -> Result<ReconcileSummary, String> {
+    let runs: Vec<TrackedRun> = report::read_build_reports(&project_path)
+        .iter()
+        .map(TrackedRun::from)
+        .collect();
+    let entries = openai_entries(&app, &runs).await;
+    reconcile_async(app, project_path, runs, entries).await
+}
@@ -0,0 +1,172 @@
+//! Cost entries captured from agents' structured output as it streams.
+//!
+//! Claude Code and Amp run with `stream-json` end each run with a `result`
+//! message carrying its usage. A tracked process given a `CostCapture` has
+//! each one appended to its project's costs.json as it arrives, with the
+//! session ID as the thread ID, so `cost_attribution` doesn't count the same
+//! session again when it reconciles the agents' logs. `cost-recorded` tells
+//! the UI about each entry.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::cost_attribution::AMP_CREDIT_USD;
+use crate::file_lock;
+use crate::models::{CostEntry, CostHistory, StoredProject};
+use crate::pricing;
+use crate::process::OutputSink;
+use crate::projects::load_projects;
+use crate::utils::get_ideate_dir;
+
+/// What a captured entry is recorded as.
+#[derive(Debug, Clone)]
+pub struct CostCapture {
+    pub agent_id: String,
+    pub description: String,
+}
+
+/// Payload of `cost-recorded`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRecordedEvent {
+    pub project_id: String,
+    pub project_path: String,
+    pub entry: CostEntry,
+}
+
+fn int(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+/// The cost entry for a `result` message, or None for any other line.
+fn parse_result(app: &AppHandle, line: &str, capture: &CostCapture) -> Option<CostEntry> {
+    // Cheap test first; this runs on every line of output
+    if !line.starts_with('{') || !line.contains("\"result\"") {
+        return None;
+    }
+    let message: Value = serde_json::from_str(line).ok()?;
+    if message.get("type").and_then(Value::as_str) != Some("result") {
+        return None;
+    }
+    let usage = message.get("usage")?;
+    let input_tokens = int(usage, "input_tokens");
+    let output_tokens = int(usage, "output_tokens");
+    let cache_write_tokens = int(usage, "cache_creation_input_tokens");
+    let cache_read_tokens = int(usage, "cache_read_input_tokens");
+    // Claude reports per-model usage; the first model is the main one
+    let model = message
+        .get("modelUsage")
+        .and_then(Value::as_object)
+        .and_then(|models| models.keys().next().cloned())
+        .or_else(|| message.get("model").and_then(Value::as_str).map(str::to_string));
+
+    let credits = message.get("credits").and_then(Value::as_f64);
+    let cost = message
+        .get("total_cost_usd")
+        .and_then(Value::as_f64)
+        .or_else(|| credits.map(|credits| credits * AMP_CREDIT_USD))
+        .or_else(|| {
+            let pricing = pricing::load_pricing(app.clone()).ok()?;
+            let price = pricing::price_for(&pricing, model.as_deref()?)?;
+            let cache_read = price.cache_read_per_million.unwrap_or(price.input_per_million);
+            let cache_write = price.cache_write_per_million.unwrap_or(price.input_per_million);
+            Some(
+                (input_tokens as f64 * price.input_per_million
+                    + output_tokens as f64 * price.output_per_million
+                    + cache_read_tokens as f64 * cache_read
+                    + cache_write_tokens as f64 * cache_write)
+                    / 1_000_000.0,
+            )
+        });
+
+    Some(CostEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: String::new(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        agent_id: capture.agent_id.clone(),
+        description: capture.description.clone(),
+        input_tokens: Some(input_tokens),
+        output_tokens: Some(output_tokens),
+        total_tokens: Some(input_tokens + output_tokens),
+        cost,
+        credits,
+        model,
+        thread_id: message.get("session_id").and_then(Value::as_str).map(str::to_string),
+        duration_ms: message.get("duration_ms").and_then(Value::as_i64),
+    })
+}
+
+/// The registered project a working directory is in; story worktrees are
+/// inside their project.
+fn project_for(app: &AppHandle, working_directory: &str) -> Option<StoredProject> {
+    let dir = Path::new(working_directory).canonicalize().ok()?;
+    load_projects(app.clone())
+        .ok()?
+        .into_iter()
+        .filter(|project| {
+            Path::new(&project.path)
+                .canonicalize()
+                .is_ok_and(|path| dir.starts_with(path))
+        })
+        // The innermost, in case projects are nested
+        .max_by_key(|project| project.path.len())
+}
+
+/// Appends `entry` to the project's costs.json unless its session is
+/// already recorded. Returns whether it was added.
+fn append_entry(project_path: &str, entry: &CostEntry) -> Result<bool, String> {
+    let cost_path = get_ideate_dir(project_path).join("costs.json");
+    if let Some(dir) = cost_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+    file_lock::update(&cost_path, |content| {
+        let mut history: CostHistory = serde_json::from_str(content.unwrap_or("{\"entries\":[]}"))
+            .map_err(|e| format!("Failed to parse costs.json: {}", e))?;
+        let recorded = entry.thread_id.is_some()
+            && history.entries.iter().any(|e| e.thread_id == entry.thread_id);
+        if !recorded {
+            history.entries.push(entry.clone());
+        }
+        let json = serde_json::to_string_pretty(&history)
+            .map_err(|e| format!("Failed to serialize cost history: {}", e))?;
+        Ok((json, !recorded))
+    })
+}
+
+/// Wraps `sink` so `result` messages in a process's output running in
+/// `working_directory` are recorded as cost entries.
+pub fn observe(app: AppHandle, working_directory: String, capture: CostCapture, sink: Option<OutputSink>) -> OutputSink {
+    Arc::new(move |stream, line| {
+        if stream == "stdout" {
+            if let Some(mut entry) = parse_result(&app, line, &capture) {
+                match project_for(&app, &working_directory) {
+                    Some(project) => {
+                        entry.project_id = project.id.clone();
+                        match append_entry(&project.path, &entry) {
+                            Ok(true) => {
+                                let _ = app.emit(
+                                    "cost-recorded",
+                                    CostRecordedEvent {
+                                        project_id: project.id,
+                                        project_path: project.path,
+                                        entry,
+                                    },
+                                );
+                            }
+                            Ok(false) => {}
+                            Err(e) => tracing::warn!("Failed to record agent cost: {}", e),
+                        }
+                    }
+                    None => tracing::debug!("No project for {}; agent cost not recorded", working_directory),
+                }
+            }
+        }
+        if let Some(sink) = &sink {
+            sink(stream, line);
+        }
+    })
+}
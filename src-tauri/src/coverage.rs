@@ -0,0 +1,194 @@
+//! Test coverage tracking per story.
+//!
+//! When the project's verification command writes a coverage report, named
+//! by `verification.coverageReport` in `.ideate/config.json`, the report is
+//! read after each passing run:
+//!
+//! ```json
+//! "verification": { "command": "npm test -- --coverage", "coverageReport": "coverage/lcov.info" }
+//! ```
+//!
+//! LCOV and Cobertura XML are understood. Each story's line coverage is
+//! stored with its verification record in state.json, along with the change
+//! from the story measured before it, so `get_coverage_trend` can show whether
+//! autonomous changes are eroding coverage.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use regex::Regex;
+#[cfg(feature = "gui")]
+use serde::Serialize;
+
+use crate::models::{StoryCoverage, StoryVerification};
+#[cfg(feature = "gui")]
+use crate::projects::{load_prd, load_project_state};
+
+lazy_static::lazy_static! {
+    static ref COBERTURA_ROOT_RE: Regex = Regex::new(r"<coverage\b[^>]*>").unwrap();
+    static ref XML_ATTR_RE: Regex = Regex::new(r#"([\w-]+)\s*=\s*"([^"]*)""#).unwrap();
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoveragePoint {
+    pub story_id: String,
+    pub story_title: Option<String>,
+    pub measured_at: String,
+    pub percent: f64,
+    pub delta: Option<f64>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageTrend {
+    /// Measured stories, oldest first.
+    pub points: Vec<CoveragePoint>,
+    pub latest_percent: Option<f64>,
+    /// Change from the first measurement to the latest, in percentage points.
+    pub net_change: Option<f64>,
+    /// Stories that lowered coverage.
+    pub declines: usize,
+}
+
+/// Totals from an LCOV tracefile, summed over its `LF`/`LH` records, or over
+/// its `DA` lines for files without them.
+fn parse_lcov(content: &str) -> Option<(u64, u64)> {
+    let (mut found, mut hit) = (0u64, 0u64);
+    let (mut file_found, mut file_hit, mut file_da_found, mut file_da_hit) = (None, None, 0u64, 0u64);
+    let mut records = 0;
+    for line in content.lines().map(str::trim) {
+        if let Some(value) = line.strip_prefix("LF:") {
+            file_found = value.parse::<u64>().ok();
+        } else if let Some(value) = line.strip_prefix("LH:") {
+            file_hit = value.parse::<u64>().ok();
+        } else if let Some(value) = line.strip_prefix("DA:") {
+            file_da_found += 1;
+            if value.split(',').nth(1).and_then(|hits| hits.parse::<u64>().ok()).is_some_and(|hits| hits > 0) {
+                file_da_hit += 1;
+            }
+        } else if line == "end_of_record" {
+            records += 1;
+            found += file_found.unwrap_or(file_da_found);
+            hit += file_hit.unwrap_or(file_da_hit);
+            (file_found, file_hit, file_da_found, file_da_hit) = (None, None, 0, 0);
+        }
+    }
+    (records > 0).then_some((hit, found))
+}
+
+/// Reads the totals on a Cobertura report's root element.
+fn parse_cobertura(content: &str) -> Option<(Option<u64>, Option<u64>, f64)> {
+    let root = COBERTURA_ROOT_RE.find(content)?.as_str();
+    let attrs: HashMap<&str, &str> = XML_ATTR_RE
+        .captures_iter(root)
+        .filter_map(|c| Some((c.get(1)?.as_str(), c.get(2)?.as_str())))
+        .collect();
+    let covered = attrs.get("lines-covered").and_then(|v| v.parse::<u64>().ok());
+    let valid = attrs.get("lines-valid").and_then(|v| v.parse::<u64>().ok());
+    let percent = match (covered, valid) {
+        (Some(covered), Some(valid)) if valid > 0 => covered as f64 / valid as f64 * 100.0,
+        _ => attrs.get("line-rate")?.parse::<f64>().ok()? * 100.0,
+    };
+    Some((covered, valid, percent))
+}
+
+/// Reads a coverage report written at or after `since`, so a stale report
+/// from an earlier run isn't mistaken for this one.
+pub fn collect(working_directory: &str, report: &str, since: SystemTime) -> Result<StoryCoverage, String> {
+    let path = Path::new(working_directory).join(report);
+    let modified = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Coverage report {} not found: {}", report, e))?;
+    if modified < since {
+        return Err(format!("Coverage report {} was not updated by the verification run", report));
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read coverage report: {}", e))?;
+
+    let (format, lines_covered, lines_total, percent) = if content.trim_start().starts_with('<') {
+        let (covered, total, percent) =
+            parse_cobertura(&content).ok_or("Coverage report is not a Cobertura report")?;
+        ("cobertura", covered, total, percent)
+    } else {
+        let (hit, found) = parse_lcov(&content).ok_or("Coverage report is not an LCOV tracefile")?;
+        let percent = if found > 0 { hit as f64 / found as f64 * 100.0 } else { 100.0 };
+        ("lcov", Some(hit), Some(found), percent)
+    };
+
+    Ok(StoryCoverage {
+        format: format.to_string(),
+        lines_covered,
+        lines_total,
+        percent: (percent * 100.0).round() / 100.0,
+        delta: None,
+        measured_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Sets `coverage.delta` against the most recent measurement of any other story.
+pub fn with_delta(
+    mut coverage: StoryCoverage,
+    verifications: &HashMap<String, StoryVerification>,
+    story_id: &str,
+) -> StoryCoverage {
+    let previous = verifications
+        .iter()
+        .filter(|(id, _)| id.as_str() != story_id)
+        .filter_map(|(_, v)| v.coverage.as_ref())
+        .max_by(|a, b| a.measured_at.cmp(&b.measured_at));
+    coverage.delta = previous.map(|p| ((coverage.percent - p.percent) * 100.0).round() / 100.0);
+    coverage
+}
+
+/// Formats a coverage figure for build logs, e.g. "82.4% (-1.3 pts)".
+pub fn describe(coverage: &StoryCoverage) -> String {
+    match coverage.delta {
+        Some(delta) => format!("{:.1}% ({:+.1} pts)", coverage.percent, delta),
+        None => format!("{:.1}%", coverage.percent),
+    }
+}
+
+/// Returns each story's measured coverage in the order it was measured.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_coverage_trend(project_path: String) -> Result<CoverageTrend, String> {
+    let verifications = load_project_state(project_path.clone())?
+        .and_then(|state| state.story_verifications)
+        .unwrap_or_default();
+    let titles: HashMap<String, String> = load_prd(project_path)
+        .ok()
+        .flatten()
+        .map(|prd| prd.user_stories.into_iter().map(|s| (s.id, s.title)).collect())
+        .unwrap_or_default();
+
+    let mut points: Vec<CoveragePoint> = verifications
+        .into_iter()
+        .filter_map(|(story_id, verification)| {
+            let coverage = verification.coverage?;
+            Some(CoveragePoint {
+                story_title: titles.get(&story_id).cloned(),
+                story_id,
+                measured_at: coverage.measured_at,
+                percent: coverage.percent,
+                delta: coverage.delta,
+            })
+        })
+        .collect();
+    points.sort_by(|a, b| a.measured_at.cmp(&b.measured_at));
+
+    let latest_percent = points.last().map(|p| p.percent);
+    let net_change = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => Some(((last.percent - first.percent) * 100.0).round() / 100.0),
+        _ => None,
+    };
+    Ok(CoverageTrend {
+        declines: points.iter().filter(|p| p.delta.is_some_and(|d| d < 0.0)).count(),
+        latest_percent,
+        net_change,
+        points,
+    })
+}
@@ -0,0 +1,254 @@
+//! Crash reporting and panic diagnostics.
+//!
+//! A panic hook writes a diagnostic bundle — message, backtrace, recent log
+//! lines, app and OS versions, and preferences with secrets removed — to
+//! `<app data>/crash-reports/`. Nothing leaves the machine unless the user
+//! chooses to report a crash, which opens a prefilled GitHub issue.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Url};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::app_data::app_data_dir;
+use crate::preferences::load_preferences_internal;
+
+const RECENT_LOG_LINES: usize = 200;
+const ISSUE_URL: &str = "https://github.com/kevinelliott/ideate/issues/new";
+/// GitHub rejects issue URLs much longer than this.
+const MAX_ISSUE_BODY: usize = 6000;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+    static ref SECRET_KEY_RE: Regex = Regex::new(r"(?i)token|secret|password|api_?key|apikey|auth").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    #[serde(default)]
+    pub os_version: Option<String>,
+    #[serde(default)]
+    pub thread: Option<String>,
+    pub message: String,
+    #[serde(default)]
+    pub location: Option<String>,
+    pub backtrace: String,
+    #[serde(default)]
+    pub recent_logs: Vec<String>,
+    /// Preferences with secret values and home paths removed.
+    #[serde(default)]
+    pub preferences: serde_json::Value,
+    #[serde(default)]
+    pub submitted_at: Option<String>,
+}
+
+/// Keeps a log line for the next crash report. Fed by the logging layer.
+pub fn record_log(line: String) {
+    if let Ok(mut logs) = RECENT_LOGS.lock() {
+        if logs.len() == RECENT_LOG_LINES {
+            logs.pop_front();
+        }
+        logs.push_back(format!("{} {}", chrono::Utc::now().to_rfc3339(), line));
+    }
+}
+
+fn get_reports_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_data_dir(app)?.join("crash-reports");
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create crash-reports directory: {}", e))?;
+    }
+    Ok(dir)
+}
+
+/// Best-effort OS version, e.g. "14.5" on macOS.
+pub(crate) fn os_version() -> Option<String> {
+    let output = match std::env::consts::OS {
+        "macos" => Command::new("sw_vers").arg("-productVersion").output().ok()?,
+        "windows" => Command::new("cmd").args(["/C", "ver"]).output().ok()?,
+        _ => {
+            let release = fs::read_to_string("/etc/os-release").ok()?;
+            return release
+                .lines()
+                .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+                .map(|v| v.trim_matches('"').to_string());
+        }
+    };
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// Drops secret-looking values and replaces the home directory with `~`.
+pub(crate) fn sanitize(value: serde_json::Value, home: &str) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = if SECRET_KEY_RE.is_match(&key) && !value.is_null() {
+                        Value::String("[redacted]".to_string())
+                    } else {
+                        sanitize(value, home)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(|v| sanitize(v, home)).collect()),
+        Value::String(s) if !home.is_empty() => Value::String(s.replace(home, "~")),
+        other => other,
+    }
+}
+
+pub(crate) fn sanitized_preferences(app: &AppHandle) -> serde_json::Value {
+    let home = dirs::home_dir()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_default();
+    load_preferences_internal(app)
+        .ok()
+        .and_then(|prefs| serde_json::to_value(prefs).ok())
+        .map(|prefs| sanitize(prefs, &home))
+        .unwrap_or(serde_json::Value::Null)
+}
+
+fn write_report(app: &AppHandle, info: &PanicHookInfo) -> Result<PathBuf, String> {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    let now = chrono::Utc::now();
+    let id = format!(
+        "{}-{}",
+        now.format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().to_string()[..8]
+    );
+
+    let report = CrashReport {
+        id: id.clone(),
+        created_at: now.to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        os_version: os_version(),
+        thread: std::thread::current().name().map(str::to_string),
+        message,
+        location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        recent_logs: RECENT_LOGS
+            .lock()
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default(),
+        preferences: sanitized_preferences(app),
+        submitted_at: None,
+    };
+
+    let path = get_reports_dir(app)?.join(format!("{}.json", id));
+    let json = serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write crash report: {}", e))?;
+    Ok(path)
+}
+
+/// Installs the panic hook. The default hook still runs afterwards.
+pub fn install(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(app) = APP.get() {
+            match write_report(app, info) {
+                Ok(path) => tracing::error!("Crash report written to {}", path.display()),
+                Err(e) => tracing::error!("Failed to write crash report: {}", e),
+            }
+        }
+        previous(info);
+    }));
+}
+
+fn report_path(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    if id.contains(['/', '\\']) || id.contains("..") {
+        return Err("Invalid crash report ID".to_string());
+    }
+    Ok(get_reports_dir(app)?.join(format!("{}.json", id)))
+}
+
+fn read_report(path: &PathBuf) -> Result<CrashReport, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read crash report: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse crash report: {}", e))
+}
+
+/// Lists crash reports, newest first.
+#[tauri::command]
+pub fn get_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    let entries = fs::read_dir(get_reports_dir(&app)?)
+        .map_err(|e| format!("Failed to read crash-reports directory: {}", e))?;
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| read_report(&path).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(reports)
+}
+
+/// Deletes a crash report.
+#[tauri::command]
+pub fn delete_crash_report(app: AppHandle, id: String) -> Result<(), String> {
+    let path = report_path(&app, &id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete crash report: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Opens a prefilled GitHub issue for a crash report so the user can review
+/// it before anything is sent.
+#[tauri::command]
+pub fn submit_crash_report(app: AppHandle, id: String) -> Result<(), String> {
+    let path = report_path(&app, &id)?;
+    let mut report = read_report(&path)?;
+
+    let mut body = format!(
+        "**Version:** {}\n**OS:** {} {} ({})\n**Location:** {}\n\n```\n{}\n```\n\n<details><summary>Backtrace</summary>\n\n```\n{}\n```\n</details>\n",
+        report.app_version,
+        report.os,
+        report.os_version.as_deref().unwrap_or_default(),
+        report.arch,
+        report.location.as_deref().unwrap_or("unknown"),
+        report.message,
+        report.backtrace,
+    );
+    if body.len() > MAX_ISSUE_BODY {
+        let cut = (0..=MAX_ISSUE_BODY).rev().find(|i| body.is_char_boundary(*i)).unwrap_or(0);
+        body.truncate(cut);
+        body.push_str("\n```\n(truncated)\n");
+    }
+
+    let url = Url::parse_with_params(
+        ISSUE_URL,
+        &[("title", format!("Crash: {}", report.message.lines().next().unwrap_or_default())), ("body", body)],
+    )
+    .map_err(|e| format!("Failed to build issue URL: {}", e))?;
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open browser: {}", e))?;
+
+    report.submitted_at = Some(chrono::Utc::now().to_rfc3339());
+    let json = serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write crash report: {}", e))
+}
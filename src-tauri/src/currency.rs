@@ -0,0 +1,217 @@
+//! Display currency for costs.
+//!
+//! Costs are recorded in USD. The display currency and its rate come from
+//! `Preferences::currency`: either a rate the user entered, or rates fetched
+//! periodically and cached in `exchange-rates.json` in the app data directory.
+//! Until a usable rate is known, costs are shown in USD only.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+use crate::models::CurrencyConfig;
+#[cfg(feature = "gui")]
+use crate::preferences::load_preferences_internal;
+
+/// Rates for USD; free and keyless.
+#[cfg(feature = "gui")]
+const RATES_URL: &str = "https://open.er-api.com/v6/latest/USD";
+
+/// Fetched rates older than this are refreshed.
+#[cfg(feature = "gui")]
+const MAX_RATE_AGE_HOURS: i64 = 12;
+
+/// How often the background refresher checks the cache.
+#[cfg(feature = "gui")]
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The currency costs are shown in and its rate against USD.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayCurrency {
+    pub code: String,
+    /// Units of `code` per USD.
+    pub rate: f64,
+    /// "usd", "manual", or "fetched".
+    pub source: String,
+    pub updated_at: Option<String>,
+}
+
+impl DisplayCurrency {
+    pub fn usd() -> Self {
+        Self {
+            code: "USD".to_string(),
+            rate: 1.0,
+            source: "usd".to_string(),
+            updated_at: None,
+        }
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.code == "USD"
+    }
+
+    pub fn convert(&self, usd: f64) -> f64 {
+        usd * self.rate
+    }
+
+    /// `$1.23`, or `$1.23 (1.13 EUR)` for other currencies.
+    pub fn format(&self, usd: f64) -> String {
+        if self.is_usd() {
+            format!("${:.2}", usd)
+        } else {
+            format!("${:.2} ({:.2} {})", usd, self.convert(usd), self.code)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateCache {
+    fetched_at: String,
+    /// Units per USD keyed by currency code.
+    rates: HashMap<String, f64>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Deserialize)]
+struct RatesResponse {
+    result: String,
+    #[serde(default)]
+    rates: HashMap<String, f64>,
+}
+
+fn cache_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("exchange-rates.json")
+}
+
+fn read_cache(data_dir: &Path) -> Option<RateCache> {
+    let content = fs::read_to_string(cache_path(data_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(feature = "gui")]
+fn is_fresh(cache: &RateCache) -> bool {
+    chrono::DateTime::parse_from_rfc3339(&cache.fetched_at)
+        .is_ok_and(|t| chrono::Utc::now().signed_duration_since(t).num_hours() < MAX_RATE_AGE_HOURS)
+}
+
+/// Resolves the display currency from preferences and cached rates.
+pub fn resolve(data_dir: &Path, config: &CurrencyConfig) -> DisplayCurrency {
+    let code = config.code.trim().to_uppercase();
+    if code.is_empty() || code == "USD" {
+        return DisplayCurrency::usd();
+    }
+    let valid = |rate: &f64| rate.is_finite() && *rate > 0.0;
+
+    if config.rate_source == "manual" {
+        return match config.manual_rate.filter(valid) {
+            Some(rate) => DisplayCurrency {
+                code,
+                rate,
+                source: "manual".to_string(),
+                updated_at: None,
+            },
+            None => DisplayCurrency::usd(),
+        };
+    }
+
+    read_cache(data_dir)
+        .and_then(|cache| {
+            let rate = cache.rates.get(&code).copied().filter(valid)?;
+            Some(DisplayCurrency {
+                code,
+                rate,
+                source: "fetched".to_string(),
+                updated_at: Some(cache.fetched_at),
+            })
+        })
+        .unwrap_or_else(DisplayCurrency::usd)
+}
+
+/// The display currency for the app's preferences, falling back to USD.
+#[cfg(feature = "gui")]
+pub fn display_currency(app: &AppHandle) -> DisplayCurrency {
+    let Ok(data_dir) = app_data_dir(app) else {
+        return DisplayCurrency::usd();
+    };
+    match load_preferences_internal(app) {
+        Ok(prefs) => resolve(&data_dir, &prefs.currency),
+        Err(_) => DisplayCurrency::usd(),
+    }
+}
+
+#[cfg(feature = "gui")]
+async fn fetch_rates(data_dir: &Path) -> Result<(), String> {
+    let response: RatesResponse = reqwest::Client::new()
+        .get(RATES_URL)
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch exchange rates: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse exchange rates: {}", e))?;
+    if response.result != "success" || response.rates.is_empty() {
+        return Err("Failed to fetch exchange rates: provider returned no rates".to_string());
+    }
+
+    let cache = RateCache {
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        rates: response.rates,
+    };
+    let json = serde_json::to_string_pretty(&cache)
+        .map_err(|e| format!("Failed to serialize exchange rates: {}", e))?;
+    fs::create_dir_all(data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    fs::write(cache_path(data_dir), json).map_err(|e| format!("Failed to write exchange-rates.json: {}", e))
+}
+
+#[cfg(feature = "gui")]
+fn wants_fetched_rates(config: &CurrencyConfig) -> bool {
+    config.rate_source == "fetch" && !config.code.trim().eq_ignore_ascii_case("USD")
+}
+
+/// Keeps fetched rates fresh while a non-USD currency uses them.
+#[cfg(feature = "gui")]
+pub fn spawn_rate_refresher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let prefs = load_preferences_internal(&app).ok();
+            let data_dir = app_data_dir(&app).ok();
+            if let (Some(prefs), Some(data_dir)) = (prefs, data_dir) {
+                let stale = read_cache(&data_dir).is_none_or(|cache| !is_fresh(&cache));
+                if wants_fetched_rates(&prefs.currency) && stale {
+                    if let Err(e) = fetch_rates(&data_dir).await {
+                        tracing::warn!("{}", e);
+                    }
+                }
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Returns the currency costs should be displayed in.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn get_display_currency(app: AppHandle) -> Result<DisplayCurrency, String> {
+    Ok(display_currency(&app))
+}
+
+/// Fetches current exchange rates now and returns the resulting display currency.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn refresh_exchange_rates(app: AppHandle) -> Result<DisplayCurrency, String> {
+    let data_dir = app_data_dir(&app)?;
+    fetch_rates(&data_dir).await?;
+    Ok(display_currency(&app))
+}
@@ -0,0 +1,152 @@
+//! `ideate://` deep links.
+//!
+//! Supported links:
+//! - `ideate://project/<id>` focuses the project
+//! - `ideate://idea/new?title=...&summary=...&description=...` starts a new idea
+//! - `ideate://build/<project>/start` starts a build; `<project>` is an ID or name
+//!
+//! Builds start in the backend once the user confirms them in a native dialog,
+//! since any web page can open a link. Actions that need the UI are queued
+//! until the frontend takes them, so links that launch the app still work.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::models::StoredProject;
+use crate::orchestrator;
+use crate::projects::load_projects;
+use crate::tray;
+
+pub const SCHEME: &str = "ideate";
+
+lazy_static::lazy_static! {
+    static ref PENDING: Mutex<Vec<DeepLinkAction>> = Mutex::new(Vec::new());
+}
+
+/// An action for the frontend to carry out.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum DeepLinkAction {
+    #[serde(rename_all = "camelCase")]
+    OpenProject { project_id: String },
+    #[serde(rename_all = "camelCase")]
+    NewIdea {
+        title: String,
+        summary: String,
+        description: String,
+    },
+}
+
+fn find_project(app: &AppHandle, reference: &str) -> Result<StoredProject, String> {
+    let projects = load_projects(app.clone())?;
+    projects
+        .iter()
+        .find(|p| p.id == reference)
+        .or_else(|| projects.iter().find(|p| p.name.eq_ignore_ascii_case(reference)))
+        .cloned()
+        .ok_or_else(|| format!("Project {} not found", reference))
+}
+
+fn queue(app: &AppHandle, action: DeepLinkAction) -> Result<(), String> {
+    PENDING
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .push(action.clone());
+    let _ = app.emit("deep-link", action);
+    Ok(())
+}
+
+/// Focuses the project's own window if it has one, otherwise asks the main
+/// window to select it.
+fn open_project(app: &AppHandle, project: &StoredProject) -> Result<(), String> {
+    let window_label = format!("project-{}", project.id.replace('-', "").chars().take(12).collect::<String>());
+    if let Some(window) = app.get_webview_window(&window_label) {
+        let _ = window.unminimize();
+        return window.set_focus().map_err(|e| format!("Failed to focus window: {}", e));
+    }
+    tray::show_main_window(app);
+    queue(app, DeepLinkAction::OpenProject { project_id: project.id.clone() })
+}
+
+/// Routes an `ideate://` URL.
+pub fn handle_url(app: &AppHandle, url: &Url) -> Result<(), String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+    crate::telemetry::record(app, "feature.deep-link");
+
+    // For `ideate://project/<id>` the first segment parses as the host
+    let host = url.host_str().unwrap_or_default();
+    let segments: Vec<String> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+            .unwrap_or_default()
+    };
+
+    match (host, segments.as_slice()) {
+        ("project", [reference]) => open_project(app, &find_project(app, reference)?),
+        ("idea", [action]) if action == "new" => {
+            tray::show_main_window(app);
+            queue(
+                app,
+                DeepLinkAction::NewIdea {
+                    title: query("title"),
+                    summary: query("summary"),
+                    description: query("description"),
+                },
+            )
+        }
+        ("build", [reference, action]) if action == "start" => {
+            let project = find_project(app, reference)?;
+            open_project(app, &project)?;
+            let dialog = app
+                .dialog()
+                .message(format!(
+                    "A link asked Ideate to start a build of \"{}\".\n\nOnly start it if you opened this link yourself.",
+                    project.name
+                ))
+                .title("Start Build?")
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancelCustom("Start Build".to_string(), "Cancel".to_string()));
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let confirmed = tokio::task::spawn_blocking(move || dialog.blocking_show())
+                    .await
+                    .unwrap_or(false);
+                if !confirmed {
+                    tracing::info!("Deep link: declined build of {}", project.name);
+                    return;
+                }
+                if let Err(e) = orchestrator::start_build(app, project.id, project.path, None).await {
+                    tracing::error!("Deep link: failed to start build: {}", e);
+                }
+            });
+            Ok(())
+        }
+        _ => Err(format!("Unsupported deep link: {}", url)),
+    }
+}
+
+/// Handles each URL, logging ones that can't be routed.
+pub fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    for url in urls {
+        if let Err(e) = handle_url(app, url) {
+            tracing::warn!("Deep link: {}", e);
+        }
+    }
+}
+
+/// Returns and clears deep link actions waiting for the frontend.
+#[tauri::command]
+pub fn take_pending_deep_links() -> Result<Vec<DeepLinkAction>, String> {
+    let mut pending = PENDING.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(std::mem::take(&mut *pending))
+}
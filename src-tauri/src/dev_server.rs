@@ -0,0 +1,579 @@
+//! Managed local dev servers.
+//!
+//! Each project can run one dev server (e.g. `npm run dev`). Its listening URL
+//! is parsed from output, it is optionally restarted when it crashes, and its
+//! status is exposed to the preview panel and tunnels.
+//!
+//! A story can also be previewed: a dev server runs in the story's worktree
+//! on a port assigned to it, tracked here under the worktree's path.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::process::terminate_child;
+use crate::worktree::{preview_worktree, remove_preview_worktree};
+
+/// Maximum consecutive crash restarts before giving up.
+const MAX_RESTARTS: u32 = 5;
+
+lazy_static::lazy_static! {
+    static ref DEV_SERVERS: Mutex<HashMap<String, DevServerHandle>> = Mutex::new(HashMap::new());
+    static ref LISTEN_URL_RE: Regex =
+        Regex::new(r"https?://(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):(\d{2,5})\S*").unwrap();
+    static ref ANSI_RE: Regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+}
+
+/// Returns the port from a dev server's "listening on" line, ignoring the
+/// color codes servers like Vite put around it.
+pub(crate) fn detect_port(line: &str) -> Option<u16> {
+    let plain = ANSI_RE.replace_all(line, "");
+    LISTEN_URL_RE
+        .captures(&plain)
+        .and_then(|caps| caps.get(1))
+        .and_then(|port| port.as_str().parse().ok())
+}
+
+/// Returns the port the project's managed dev server is listening on, if it
+/// has reported one.
+pub(crate) fn port_for(project_path: &str) -> Option<u16> {
+    DEV_SERVERS.lock().ok()?.get(project_path)?.port
+}
+
+struct DevServerHandle {
+    child: Option<Child>,
+    command: String,
+    env: Option<HashMap<String, String>>,
+    auto_restart: bool,
+    restart_count: u32,
+    /// Incremented on each (re)start so output readers of a dead process are ignored.
+    generation: u64,
+    status: String,
+    port: Option<u16>,
+    url: Option<String>,
+    started_at: String,
+    preview: Option<StoryPreview>,
+}
+
+impl DevServerHandle {
+    fn to_status(&self, project_path: &str) -> DevServerStatus {
+        DevServerStatus {
+            project_path: project_path.to_string(),
+            command: self.command.clone(),
+            status: self.status.clone(),
+            port: self.port,
+            url: self.url.clone(),
+            auto_restart: self.auto_restart,
+            restart_count: self.restart_count,
+            started_at: self.started_at.clone(),
+            preview: self.preview.clone(),
+        }
+    }
+}
+
+/// The story a dev server is previewing, for one running in a story's
+/// worktree rather than the project itself.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryPreview {
+    pub project_path: String,
+    pub story_id: String,
+    pub branch_name: String,
+    /// Port picked for the preview and passed to the server as `PORT`.
+    pub assigned_port: u16,
+    /// The worktree was added for the preview and is removed when it stops.
+    #[serde(skip)]
+    owns_worktree: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevServerStatus {
+    pub project_path: String,
+    pub command: String,
+    /// "starting", "running", "restarting", "crashed", or "stopped"
+    pub status: String,
+    pub port: Option<u16>,
+    pub url: Option<String>,
+    pub auto_restart: bool,
+    pub restart_count: u32,
+    pub started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<StoryPreview>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevServerOutputEvent {
+    pub project_path: String,
+    pub stream_type: String,
+    pub content: String,
+}
+
+fn spawn_server_process(
+    project_path: &str,
+    command: &str,
+    env: &Option<HashMap<String, String>>,
+) -> Result<Child, String> {
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+
+    cmd.current_dir(project_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    if let Some(env_vars) = env {
+        for (key, value) in env_vars {
+            cmd.env(key, value);
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    cmd.spawn()
+        .map_err(|e| format!("Failed to start dev server '{}': {}", command, e))
+}
+
+fn emit_status(app: &AppHandle, project_path: &str) {
+    let status = DEV_SERVERS
+        .lock()
+        .ok()
+        .and_then(|servers| servers.get(project_path).map(|h| h.to_status(project_path)));
+
+    if let Some(status) = status {
+        let _ = app.emit("dev-server-status", status);
+    }
+}
+
+/// Reads dev server output, forwarding each line and capturing the listening URL.
+fn watch_output<R: std::io::Read + Send + 'static>(
+    app: AppHandle,
+    project_path: String,
+    generation: u64,
+    stream_type: &'static str,
+    stream: R,
+) {
+    thread::spawn(move || {
+        let reader = BufReader::new(stream);
+        for line in reader.lines().map_while(Result::ok) {
+            let mut became_ready = false;
+            {
+                let Ok(mut servers) = DEV_SERVERS.lock() else {
+                    break;
+                };
+                let Some(handle) = servers.get_mut(&project_path) else {
+                    break;
+                };
+                if handle.generation != generation {
+                    break;
+                }
+
+                if handle.url.is_none() {
+                    if let Some(port) = detect_port(&line) {
+                        handle.port = Some(port);
+                        handle.url = handle.port.map(|p| format!("http://localhost:{}", p));
+                        handle.status = "running".to_string();
+                        // A server that came up successfully resets the crash budget
+                        handle.restart_count = 0;
+                        became_ready = true;
+                    }
+                }
+            }
+
+            if became_ready {
+                emit_status(&app, &project_path);
+            }
+
+            let _ = app.emit(
+                "dev-server-output",
+                DevServerOutputEvent {
+                    project_path: project_path.clone(),
+                    stream_type: stream_type.to_string(),
+                    content: line,
+                },
+            );
+        }
+    });
+}
+
+fn attach_output(app: &AppHandle, project_path: &str, generation: u64, child: &mut Child) {
+    if let Some(stdout) = child.stdout.take() {
+        watch_output(app.clone(), project_path.to_string(), generation, "stdout", stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        watch_output(app.clone(), project_path.to_string(), generation, "stderr", stderr);
+    }
+}
+
+/// Watches the dev server process, restarting it on crash when enabled.
+fn supervise(app: AppHandle, project_path: String) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(500));
+
+        let exited = {
+            let Ok(mut servers) = DEV_SERVERS.lock() else {
+                return;
+            };
+            let Some(handle) = servers.get_mut(&project_path) else {
+                // Stopped and removed
+                return;
+            };
+            match handle.child.as_mut().map(|c| c.try_wait()) {
+                Some(Ok(None)) => false,
+                _ => {
+                    handle.child = None;
+                    true
+                }
+            }
+        };
+
+        if !exited {
+            continue;
+        }
+
+        let restart = {
+            let Ok(mut servers) = DEV_SERVERS.lock() else {
+                return;
+            };
+            let Some(handle) = servers.get_mut(&project_path) else {
+                return;
+            };
+            handle.port = None;
+            handle.url = None;
+            if handle.auto_restart && handle.restart_count < MAX_RESTARTS {
+                handle.restart_count += 1;
+                handle.status = "restarting".to_string();
+                Some((
+                    handle.command.clone(),
+                    handle.env.clone(),
+                    handle.restart_count,
+                    handle.generation,
+                ))
+            } else {
+                handle.status = "crashed".to_string();
+                None
+            }
+        };
+        emit_status(&app, &project_path);
+
+        let Some((command, env, attempt, generation)) = restart else {
+            return;
+        };
+
+        // Back off a little more on each consecutive crash
+        thread::sleep(Duration::from_secs(attempt as u64));
+
+        let spawned = spawn_server_process(&project_path, &command, &env);
+        let Ok(mut servers) = DEV_SERVERS.lock() else {
+            return;
+        };
+        let handle = match servers.get_mut(&project_path) {
+            Some(handle) if handle.generation == generation => handle,
+            _ => {
+                // Stopped or restarted manually while backing off
+                drop(servers);
+                if let Ok(mut child) = spawned {
+                    terminate_child(&mut child, Duration::from_secs(2));
+                }
+                return;
+            }
+        };
+
+        match spawned {
+            Ok(mut child) => {
+                handle.generation += 1;
+                handle.status = "starting".to_string();
+                handle.started_at = chrono::Utc::now().to_rfc3339();
+                attach_output(&app, &project_path, handle.generation, &mut child);
+                handle.child = Some(child);
+            }
+            Err(e) => {
+                tracing::error!("Dev server restart failed for {}: {}", project_path, e);
+                handle.status = "crashed".to_string();
+                drop(servers);
+                emit_status(&app, &project_path);
+                return;
+            }
+        }
+        drop(servers);
+        emit_status(&app, &project_path);
+    });
+}
+
+/// Starts the project's dev server with the given shell command.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_dev_server(
+    app: AppHandle,
+    project_path: String,
+    command: String,
+    auto_restart: Option<bool>,
+    env: Option<HashMap<String, String>>,
+) -> Result<DevServerStatus, String> {
+    start_server(app, project_path, command, auto_restart.unwrap_or(true), env, None).await
+}
+
+/// Starts a dev server in `path`, which is also the key it is tracked under.
+async fn start_server(
+    app: AppHandle,
+    path: String,
+    command: String,
+    auto_restart: bool,
+    env: Option<HashMap<String, String>>,
+    preview: Option<StoryPreview>,
+) -> Result<DevServerStatus, String> {
+    {
+        let servers = DEV_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(handle) = servers.get(&path) {
+            if handle.child.is_some() {
+                return Err("A dev server is already running for this project".to_string());
+            }
+        }
+    }
+    crate::telemetry::record(&app, "feature.dev-server");
+
+    let path_for_spawn = path.clone();
+    let command_for_spawn = command.clone();
+    let env_for_spawn = env.clone();
+    let mut child = tokio::task::spawn_blocking(move || {
+        spawn_server_process(&path_for_spawn, &command_for_spawn, &env_for_spawn)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let status = {
+        let mut servers = DEV_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let generation = servers.get(&path).map(|h| h.generation + 1).unwrap_or(0);
+        attach_output(&app, &path, generation, &mut child);
+
+        let handle = DevServerHandle {
+            child: Some(child),
+            command,
+            env,
+            auto_restart,
+            restart_count: 0,
+            generation,
+            status: "starting".to_string(),
+            port: None,
+            url: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            preview,
+        };
+        let status = handle.to_status(&path);
+        servers.insert(path.clone(), handle);
+        status
+    };
+
+    supervise(app.clone(), path);
+    let _ = app.emit("dev-server-status", status.clone());
+
+    Ok(status)
+}
+
+/// Stops the project's dev server.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_dev_server(app: AppHandle, project_path: String) -> Result<(), String> {
+    stop_server(&app, &project_path).await.map(|_| ())
+}
+
+/// Stops the dev server tracked under `path`, returning the story it was
+/// previewing, if any.
+async fn stop_server(app: &AppHandle, path: &str) -> Result<Option<StoryPreview>, String> {
+    let handle = {
+        let mut servers = DEV_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        servers.remove(path)
+    };
+
+    let Some(mut handle) = handle else {
+        return Ok(None);
+    };
+    let preview = handle.preview.take();
+
+    tokio::task::spawn_blocking(move || {
+        if let Some(mut child) = handle.child.take() {
+            terminate_child(&mut child, Duration::from_secs(5));
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    let _ = app.emit(
+        "dev-server-status",
+        DevServerStatus {
+            project_path: path.to_string(),
+            command: String::new(),
+            status: "stopped".to_string(),
+            port: None,
+            url: None,
+            auto_restart: false,
+            restart_count: 0,
+            started_at: String::new(),
+            preview: preview.clone(),
+        },
+    );
+
+    Ok(preview)
+}
+
+/// A free local port no managed dev server has been given or reported.
+fn assign_port() -> Result<u16, String> {
+    let taken: Vec<u16> = DEV_SERVERS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .values()
+        .flat_map(|h| [h.port, h.preview.as_ref().map(|p| p.assigned_port)])
+        .flatten()
+        .collect();
+    for _ in 0..10 {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .map_err(|e| format!("Failed to find a free port: {}", e))?;
+        let port = listener.local_addr().map_err(|e| format!("Failed to find a free port: {}", e))?.port();
+        if !taken.contains(&port) {
+            return Ok(port);
+        }
+    }
+    Err("Failed to find a free port".to_string())
+}
+
+/// Path the preview of a story is tracked under, if one is running.
+fn preview_key(project_path: &str, story_id: &str) -> Option<String> {
+    DEV_SERVERS.lock().ok()?.iter().find_map(|(path, handle)| {
+        handle
+            .preview
+            .as_ref()
+            .filter(|p| p.project_path == project_path && p.story_id == story_id)
+            .map(|_| path.clone())
+    })
+}
+
+/// Starts a dev server inside a story's worktree on a port of its own, so
+/// the story can be tried next to the project's main dev server before it
+/// is merged. The port is passed as `PORT` and replaces `{port}` in
+/// `command`. Returns the running preview if there already is one.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_story_preview(
+    app: AppHandle,
+    project_path: String,
+    story_id: String,
+    command: String,
+    env: Option<HashMap<String, String>>,
+) -> Result<DevServerStatus, String> {
+    if let Some(path) = preview_key(&project_path, &story_id) {
+        if let Some(status) = get_dev_server_status(path)? {
+            return Ok(status);
+        }
+    }
+
+    let worktree = {
+        let project_path = project_path.clone();
+        let story_id = story_id.clone();
+        tokio::task::spawn_blocking(move || preview_worktree(&project_path, &story_id))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??
+    };
+    let path = worktree.path.to_string_lossy().to_string();
+
+    let result = async {
+        let port = assign_port()?;
+        let mut env = env.unwrap_or_default();
+        env.insert("PORT".to_string(), port.to_string());
+        let preview = StoryPreview {
+            project_path: project_path.clone(),
+            story_id: story_id.clone(),
+            branch_name: worktree.branch_name.clone(),
+            assigned_port: port,
+            owns_worktree: worktree.created,
+        };
+        start_server(
+            app,
+            path.clone(),
+            command.replace("{port}", &port.to_string()),
+            true,
+            Some(env),
+            Some(preview),
+        )
+        .await
+    }
+    .await;
+
+    if result.is_err() && worktree.created {
+        let _ = tokio::task::spawn_blocking(move || remove_preview_worktree(&project_path, &worktree.path)).await;
+    }
+    result
+}
+
+/// Stops a story's preview and removes the worktree made for it.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_story_preview(app: AppHandle, project_path: String, story_id: String) -> Result<(), String> {
+    let Some(path) = preview_key(&project_path, &story_id) else {
+        return Ok(());
+    };
+    if let Some(preview) = stop_server(&app, &path).await? {
+        if preview.owns_worktree {
+            tokio::task::spawn_blocking(move || remove_preview_worktree(&preview.project_path, Path::new(&path)))
+                .await
+                .map_err(|e| format!("Task join error: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists the project's running story previews.
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_story_previews(project_path: String) -> Result<Vec<DevServerStatus>, String> {
+    let servers = DEV_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(servers
+        .iter()
+        .filter(|(_, h)| h.preview.as_ref().is_some_and(|p| p.project_path == project_path))
+        .map(|(path, h)| h.to_status(path))
+        .collect())
+}
+
+/// Returns the status of the project's dev server, if one has been started.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_dev_server_status(project_path: String) -> Result<Option<DevServerStatus>, String> {
+    let servers = DEV_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(servers.get(&project_path).map(|h| h.to_status(&project_path)))
+}
+
+/// Lists all managed dev servers.
+#[tauri::command]
+pub fn list_dev_servers() -> Result<Vec<DevServerStatus>, String> {
+    let servers = DEV_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(servers.iter().map(|(path, h)| h.to_status(path)).collect())
+}
+
+/// Stops all dev servers. Called on app shutdown.
+pub fn stop_all_dev_servers() {
+    let handles: Vec<DevServerHandle> = match DEV_SERVERS.lock() {
+        Ok(mut servers) => servers.drain().map(|(_, h)| h).collect(),
+        Err(_) => return,
+    };
+
+    for mut handle in handles {
+        if let Some(mut child) = handle.child.take() {
+            terminate_child(&mut child, Duration::from_secs(2));
+        }
+    }
+}
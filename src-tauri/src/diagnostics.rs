@@ -0,0 +1,185 @@
+//! Diagnostics bundles for bug reports.
+//!
+//! `generate_diagnostics_bundle` zips what's needed to look into a problem:
+//! app and OS versions, installed agent versions, preferences with secrets
+//! removed, recent process history, the app's recent logs, timings of heavy
+//! operations, and the event journal of the most recent build. Log lines are
+//! redacted like saved process logs, and the home directory is replaced with
+//! `~` throughout. The zip is written to `<app data>/diagnostics/` and shown
+//! in the file manager for the user to attach to an issue.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::agents::{detect_agent_status, get_built_in_agents};
+use crate::app_data::app_data_dir;
+use crate::crash::{os_version, sanitize, sanitized_preferences};
+use crate::logging::app_log_files;
+use crate::orchestrator::journal::{read_events, JournalEvent};
+use crate::perf;
+use crate::process::read_process_history;
+use crate::projects::load_projects;
+use crate::redact;
+
+/// Most recent process history entries included.
+const PROCESS_HISTORY_ENTRIES: usize = 100;
+
+/// Most recent days of app logs included.
+const APP_LOG_FILES: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentVersion {
+    id: String,
+    status: String,
+    version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemInfo {
+    generated_at: String,
+    app_version: String,
+    os: String,
+    arch: String,
+    os_version: Option<String>,
+    agents: Vec<AgentVersion>,
+}
+
+fn home_dir() -> String {
+    dirs::home_dir()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Redacts a log's secrets and home directory.
+fn scrub(text: &str, home: &str) -> String {
+    let text = redact::redact(text);
+    if home.is_empty() {
+        text.into_owned()
+    } else {
+        text.replace(home, "~")
+    }
+}
+
+fn to_json<T: Serialize>(value: &T, home: &str) -> Result<String, String> {
+    let value = serde_json::to_value(value).map_err(|e| format!("Failed to serialize diagnostics: {}", e))?;
+    serde_json::to_string_pretty(&sanitize(value, home))
+        .map_err(|e| format!("Failed to serialize diagnostics: {}", e))
+}
+
+/// The project and events of the build that ran most recently, across every
+/// project.
+fn last_build_journal(app: &AppHandle) -> Option<(String, Vec<JournalEvent>)> {
+    let mut latest: Option<(String, String, Vec<JournalEvent>)> = None;
+    for project in load_projects(app.clone()).ok()? {
+        let events = read_events(&project.path).unwrap_or_default();
+        let Some(last) = events.iter().rev().find(|e| e.build_id.is_some()) else {
+            continue;
+        };
+        if latest.as_ref().is_none_or(|(timestamp, _, _)| last.timestamp > *timestamp) {
+            latest = Some((last.timestamp.clone(), project.name, events));
+        }
+    }
+    let (_, project_name, events) = latest?;
+    let build_id = events.iter().rev().find_map(|e| e.build_id.clone());
+    let events = events.into_iter().filter(|e| e.build_id == build_id).collect();
+    Some((project_name, events))
+}
+
+fn add_file(zip: &mut ZipWriter<fs::File>, name: &str, content: &str) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to write diagnostics bundle: {}", e))?;
+    zip.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write diagnostics bundle: {}", e))
+}
+
+fn write_bundle(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let home = home_dir();
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create diagnostics bundle: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+
+    let system = SystemInfo {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        os_version: os_version(),
+        agents: get_built_in_agents()
+            .iter()
+            .map(detect_agent_status)
+            .map(|status| AgentVersion {
+                id: status.agent.id,
+                status: status.status,
+                version: status.installed_version,
+            })
+            .collect(),
+    };
+    add_file(&mut zip, "system.json", &to_json(&system, &home)?)?;
+    add_file(&mut zip, "preferences.json", &to_json(&sanitized_preferences(app), &home)?)?;
+    add_file(&mut zip, "performance.json", &to_json(&perf::report(), &home)?)?;
+
+    let app_data_dir = app_data_dir(app)?;
+    let mut history = read_process_history(&app_data_dir)?;
+    history.entries.truncate(PROCESS_HISTORY_ENTRIES);
+    add_file(&mut zip, "process-history.json", &to_json(&history, &home)?)?;
+
+    let logs = app_log_files(app)?;
+    for log in logs.iter().skip(logs.len().saturating_sub(APP_LOG_FILES)) {
+        let (Ok(content), Some(name)) = (fs::read_to_string(log), log.file_name()) else {
+            continue;
+        };
+        add_file(&mut zip, &format!("logs/{}", name.to_string_lossy()), &scrub(&content, &home))?;
+    }
+
+    if let Some((project_name, events)) = last_build_journal(app) {
+        let lines: Vec<String> = events
+            .iter()
+            .filter_map(|event| serde_json::to_value(event).ok())
+            .map(|event| sanitize(event, &home).to_string())
+            .collect();
+        add_file(&mut zip, "last-build/events.jsonl", &scrub(&lines.join("\n"), &home))?;
+        add_file(
+            &mut zip,
+            "last-build/project.json",
+            &to_json(&json!({ "projectName": project_name, "events": events.len() }), &home)?,
+        )?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write diagnostics bundle: {}", e))?;
+    Ok(())
+}
+
+/// Writes a diagnostics bundle for a bug report and reveals it in the file
+/// manager. Returns its path.
+#[tauri::command]
+pub async fn generate_diagnostics_bundle(app: AppHandle) -> Result<String, String> {
+    let dir: PathBuf = app_data_dir(&app)?.join("diagnostics");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create diagnostics directory: {}", e))?;
+    let path = dir.join(format!(
+        "ideate-diagnostics-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    let bundle_app = app.clone();
+    let bundle_path = path.clone();
+    tokio::task::spawn_blocking(move || write_bundle(&bundle_app, &bundle_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    if let Err(e) = app.opener().reveal_item_in_dir(&path) {
+        tracing::warn!("Failed to reveal diagnostics bundle: {}", e);
+    }
+    tracing::info!("Wrote diagnostics bundle to {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
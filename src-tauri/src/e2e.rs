@@ -0,0 +1,388 @@
+//! End-to-end test runs after stories.
+//!
+//! Projects opt in with an `e2e` section in `.ideate/config.json`:
+//!
+//! ```json
+//! "e2e": { "runner": "playwright" }
+//! ```
+//!
+//! After a story's agent succeeds, the suite runs headlessly in the story's
+//! working directory as a tracked process, with the runner's JSON reporter
+//! writing to stdout. The per-test results are attached to the story's
+//! verification record in state.json; a failing suite is reported but does
+//! not fail the story. Each run is saved to the process logs and shows up in
+//! process history as an "e2e" entry.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::models::{
+    E2eConfig, E2eRun, E2eTestResult, ProcessCommand, ProcessHistoryEntry, ProcessLogEntry, ProcessPriority,
+    ProjectConfig, TerminationReason,
+};
+use crate::process::{
+    append_process_history, kill_agent_blocking, save_process_log_blocking, spawn_tracked_process,
+    wait_for_process, OutputOptions, OutputSink,
+};
+use crate::utils::get_ideate_dir;
+
+/// Suites still running after this are killed and count as failed.
+const E2E_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+
+/// Longest error message kept per test.
+const MAX_ERROR_CHARS: usize = 2000;
+
+const PLAYWRIGHT_CONFIGS: &[&str] = &[
+    "playwright.config.ts",
+    "playwright.config.js",
+    "playwright.config.mjs",
+    "playwright.config.cjs",
+];
+
+const CYPRESS_CONFIGS: &[&str] = &[
+    "cypress.config.ts",
+    "cypress.config.js",
+    "cypress.config.mjs",
+    "cypress.config.cjs",
+    "cypress.json",
+];
+
+/// Reads the project's e2e settings, fresh each time so edits apply mid-build.
+/// Returns `None` when the project hasn't opted in.
+pub fn load_config(project_path: &str) -> Option<E2eConfig> {
+    fs::read_to_string(get_ideate_dir(project_path).join("config.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectConfig>(&content).ok())
+        .and_then(|config| config.e2e)
+}
+
+/// Finds the runner a directory is set up for from its config files.
+pub fn detect_runner(working_directory: &str) -> Option<&'static str> {
+    let dir = Path::new(working_directory);
+    if PLAYWRIGHT_CONFIGS.iter().any(|name| dir.join(name).exists()) {
+        Some("playwright")
+    } else if CYPRESS_CONFIGS.iter().any(|name| dir.join(name).exists()) {
+        Some("cypress")
+    } else {
+        None
+    }
+}
+
+/// The runner and shell command to run a suite with.
+pub fn resolve_command(config: &E2eConfig, working_directory: &str) -> Result<(String, String), String> {
+    let runner = match config.runner.as_deref() {
+        Some(runner @ ("playwright" | "cypress")) => runner,
+        Some(other) => return Err(format!("Unknown e2e runner: {}", other)),
+        None => detect_runner(working_directory)
+            .ok_or("No Playwright or Cypress config found; set e2e.runner in config.json")?,
+    };
+    let command = match config.command.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(command) => command.to_string(),
+        None if runner == "playwright" => "npx playwright test --reporter=json".to_string(),
+        None => "npx cypress run --headless --reporter json".to_string(),
+    };
+    Ok((runner.to_string(), command))
+}
+
+/// The platform shell invocation for a command.
+fn shell_invocation(command: &str) -> (String, Vec<String>) {
+    #[cfg(unix)]
+    let (shell, flag) = ("sh", "-c");
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    (shell.to_string(), vec![flag.to_string(), command.to_string()])
+}
+
+/// Pulls JSON objects out of output that may also contain log lines. Reporters
+/// pretty-print, so an object spans from a `{` line to the next `}` line.
+fn json_documents(stdout: &str) -> Vec<Value> {
+    let mut documents = Vec::new();
+    let mut current: Option<String> = None;
+    for line in stdout.lines() {
+        match current.as_mut() {
+            Some(buffer) => {
+                buffer.push_str(line);
+                buffer.push('\n');
+                if line.trim_end() == "}" {
+                    if let Ok(value) = serde_json::from_str(buffer) {
+                        documents.push(value);
+                    }
+                    current = None;
+                }
+            }
+            None if line.trim_end() == "{" => current = Some(String::from("{\n")),
+            None if line.starts_with('{') => {
+                if let Ok(value) = serde_json::from_str(line) {
+                    documents.push(value);
+                }
+            }
+            None => {}
+        }
+    }
+    documents
+}
+
+fn error_message(error: &Value) -> Option<String> {
+    let message = error["message"].as_str().or_else(|| error.as_str())?;
+    Some(message.chars().take(MAX_ERROR_CHARS).collect())
+}
+
+fn collect_playwright_suite(suite: &Value, parents: &[String], tests: &mut Vec<E2eTestResult>) {
+    let mut titles = parents.to_vec();
+    // File-level suites are titled with the file name, which is kept separately
+    if let Some(title) = suite["title"].as_str() {
+        if !title.is_empty() && suite["file"].as_str() != Some(title) {
+            titles.push(title.to_string());
+        }
+    }
+
+    for spec in suite["specs"].as_array().into_iter().flatten() {
+        for test in spec["tests"].as_array().into_iter().flatten() {
+            let results = test["results"].as_array().map(Vec::as_slice).unwrap_or_default();
+            let mut title = titles.clone();
+            title.push(spec["title"].as_str().unwrap_or_default().to_string());
+            let project = test["projectName"].as_str().unwrap_or_default();
+            let title = title.join(" › ");
+            tests.push(E2eTestResult {
+                title: if project.is_empty() { title } else { format!("[{}] {}", project, title) },
+                file: spec["file"].as_str().or(suite["file"].as_str()).map(String::from),
+                status: match test["status"].as_str() {
+                    Some("expected") => "passed",
+                    Some("flaky") => "flaky",
+                    Some("skipped") => "skipped",
+                    _ => "failed",
+                }
+                .to_string(),
+                duration_ms: results.iter().filter_map(|r| r["duration"].as_u64()).sum(),
+                error: results.iter().rev().find_map(|r| error_message(&r["error"])),
+            });
+        }
+    }
+
+    for child in suite["suites"].as_array().into_iter().flatten() {
+        collect_playwright_suite(child, &titles, tests);
+    }
+}
+
+/// Reads Playwright's JSON report.
+fn parse_playwright(report: &Value) -> Vec<E2eTestResult> {
+    let mut tests = Vec::new();
+    for suite in report["suites"].as_array().into_iter().flatten() {
+        collect_playwright_suite(suite, &[], &mut tests);
+    }
+    tests
+}
+
+/// Reads one spec's report from Mocha's JSON reporter, which Cypress prints
+/// once per spec file.
+fn parse_mocha(report: &Value) -> Vec<E2eTestResult> {
+    let pending: Vec<&str> = report["pending"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t["fullTitle"].as_str())
+        .collect();
+
+    report["tests"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|test| {
+            let title = test["fullTitle"].as_str().or(test["title"].as_str()).unwrap_or_default();
+            let error = error_message(&test["err"]);
+            E2eTestResult {
+                title: title.to_string(),
+                file: test["file"].as_str().map(String::from),
+                status: if error.is_some() {
+                    "failed"
+                } else if pending.contains(&title) {
+                    "skipped"
+                } else {
+                    "passed"
+                }
+                .to_string(),
+                duration_ms: test["duration"].as_u64().unwrap_or(0),
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Extracts per-test results from a suite's stdout. Returns `None` when it
+/// contains no report the runner's reporter would print.
+pub fn parse_report(runner: &str, stdout: &str) -> Option<Vec<E2eTestResult>> {
+    let documents = json_documents(stdout);
+    if runner == "playwright" {
+        documents
+            .iter()
+            .find(|doc| doc["suites"].is_array())
+            .map(parse_playwright)
+    } else {
+        let reports: Vec<&Value> = documents.iter().filter(|doc| doc["tests"].is_array()).collect();
+        (!reports.is_empty()).then(|| reports.into_iter().flat_map(parse_mocha).collect())
+    }
+}
+
+fn count(tests: &[E2eTestResult], status: &str) -> u32 {
+    tests.iter().filter(|t| t.status == status).count() as u32
+}
+
+/// Runs a suite as a tracked process and collects its results. `on_spawn`
+/// gets the process ID so the caller can kill it early.
+pub async fn run(
+    app: &AppHandle,
+    project_id: &str,
+    label: &str,
+    runner: &str,
+    command: &str,
+    working_directory: &str,
+    on_spawn: impl FnOnce(&str),
+) -> E2eRun {
+    let (executable, args) = shell_invocation(command);
+    let recorded = ProcessCommand {
+        executable: executable.clone(),
+        args: args.clone(),
+        working_directory: working_directory.to_string(),
+    };
+    let mut env = HashMap::new();
+    // Keeps runners from opening HTML reports or waiting on watch mode
+    env.insert("CI".to_string(), "1".to_string());
+
+    let logs: Arc<Mutex<Vec<ProcessLogEntry>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink_logs = logs.clone();
+    let sink: OutputSink = Arc::new(move |stream, line| {
+        if let Ok(mut logs) = sink_logs.lock() {
+            logs.push(ProcessLogEntry {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                log_type: stream.to_string(),
+                content: line.to_string(),
+            });
+        }
+    });
+
+    let started_at = chrono::Utc::now();
+    let started = Instant::now();
+    let failed_run = |error: String, process_id: Option<String>| E2eRun {
+        runner: runner.to_string(),
+        success: false,
+        passed: 0,
+        failed: 0,
+        flaky: 0,
+        skipped: 0,
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: Some(error),
+        process_id,
+        tests: Vec::new(),
+    };
+
+    let process_id = match spawn_tracked_process(
+        app.clone(),
+        executable,
+        args,
+        working_directory.to_string(),
+        Some(env),
+        OutputOptions {
+            sink: Some(sink),
+            ..Default::default()
+        },
+        ProcessPriority::Normal,
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(e) => return failed_run(e, None),
+    };
+    on_spawn(&process_id);
+
+    let pid = process_id.clone();
+    let mut wait = tokio::task::spawn_blocking(move || wait_for_process(&pid));
+    let waited = match tokio::time::timeout(E2E_TIMEOUT, &mut wait).await {
+        Ok(joined) => joined.map_err(|e| format!("Task join error: {}", e)).and_then(|r| r),
+        Err(_) => {
+            let pid = process_id.clone();
+            let _ = tokio::task::spawn_blocking(move || kill_agent_blocking(&pid, TerminationReason::Timeout)).await;
+            let _ = wait.await;
+            Err(format!("timed out after {} minutes", E2E_TIMEOUT.as_secs() / 60))
+        }
+    };
+
+    let logs = logs.lock().map(|l| l.clone()).unwrap_or_default();
+    let stdout: String = logs
+        .iter()
+        .filter(|entry| entry.log_type == "stdout")
+        .map(|entry| format!("{}\n", entry.content))
+        .collect();
+    let (exit_code, exited_ok) = match &waited {
+        Ok(result) => (result.exit_code, result.success),
+        Err(_) => (None, false),
+    };
+
+    let mut run = match (&waited, parse_report(runner, &stdout)) {
+        (Err(e), _) => failed_run(e.clone(), Some(process_id.clone())),
+        (Ok(_), None) => failed_run(
+            format!("No {} JSON report found in the output", runner),
+            Some(process_id.clone()),
+        ),
+        (Ok(_), Some(tests)) => E2eRun {
+            runner: runner.to_string(),
+            success: false,
+            passed: count(&tests, "passed"),
+            failed: count(&tests, "failed"),
+            flaky: count(&tests, "flaky"),
+            skipped: count(&tests, "skipped"),
+            duration_ms: started.elapsed().as_millis() as u64,
+            error: None,
+            process_id: Some(process_id.clone()),
+            tests,
+        },
+    };
+    run.success = exited_ok && run.error.is_none() && run.failed == 0;
+
+    if let Ok(app_data_dir) = app_data_dir(app) {
+        let project_id = project_id.to_string();
+        let label = label.to_string();
+        let success = run.success;
+        let duration_ms = run.duration_ms as i64;
+        let _ = tokio::task::spawn_blocking(move || {
+            let log_file_path = save_process_log_blocking(
+                app_data_dir.clone(),
+                process_id.clone(),
+                project_id.clone(),
+                "e2e".to_string(),
+                label.clone(),
+                logs,
+            );
+            if let Err(e) = &log_file_path {
+                tracing::warn!("E2E: {}", e);
+            }
+            let entry = ProcessHistoryEntry {
+                process_id,
+                project_id,
+                process_type: "e2e".to_string(),
+                label,
+                started_at: started_at.to_rfc3339(),
+                completed_at: chrono::Utc::now().to_rfc3339(),
+                duration_ms,
+                exit_code,
+                success,
+                agent_id: None,
+                fallback_from: None,
+                command: Some(recorded),
+                log_file_path: log_file_path.ok(),
+            };
+            if let Err(e) = append_process_history(&app_data_dir, entry) {
+                tracing::warn!("E2E: {}", e);
+            }
+        })
+        .await;
+    }
+
+    run
+}
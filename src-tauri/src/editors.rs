@@ -0,0 +1,218 @@
+//! Opening projects and files in external editors.
+//!
+//! Detects installed editors through their command-line launchers and opens a
+//! project, or a specific file at a line, in the chosen editor.
+
+use std::path::PathBuf;
+#[cfg(feature = "gui")]
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+
+/// How an editor's CLI expects a file + line argument.
+#[cfg(feature = "gui")]
+#[derive(Clone, Copy)]
+enum LineSyntax {
+    /// `--goto file:line` (VS Code family)
+    Goto,
+    /// `file:line` (Zed)
+    Suffix,
+    /// `--line N file` (JetBrains)
+    LineFlag,
+}
+
+#[cfg(feature = "gui")]
+struct EditorDef {
+    id: &'static str,
+    name: &'static str,
+    cli: &'static str,
+    /// CLI locations inside the macOS app bundle, used when the CLI isn't on PATH.
+    mac_fallbacks: &'static [&'static str],
+    line_syntax: LineSyntax,
+}
+
+#[cfg(feature = "gui")]
+const EDITORS: &[EditorDef] = &[
+    EditorDef {
+        id: "vscode",
+        name: "Visual Studio Code",
+        cli: "code",
+        mac_fallbacks: &["/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code"],
+        line_syntax: LineSyntax::Goto,
+    },
+    EditorDef {
+        id: "cursor",
+        name: "Cursor",
+        cli: "cursor",
+        mac_fallbacks: &["/Applications/Cursor.app/Contents/Resources/app/bin/cursor"],
+        line_syntax: LineSyntax::Goto,
+    },
+    EditorDef {
+        id: "windsurf",
+        name: "Windsurf",
+        cli: "windsurf",
+        mac_fallbacks: &["/Applications/Windsurf.app/Contents/Resources/app/bin/windsurf"],
+        line_syntax: LineSyntax::Goto,
+    },
+    EditorDef {
+        id: "zed",
+        name: "Zed",
+        cli: "zed",
+        mac_fallbacks: &["/Applications/Zed.app/Contents/MacOS/cli"],
+        line_syntax: LineSyntax::Suffix,
+    },
+    EditorDef {
+        id: "idea",
+        name: "IntelliJ IDEA",
+        cli: "idea",
+        mac_fallbacks: &[
+            "/Applications/IntelliJ IDEA.app/Contents/MacOS/idea",
+            "/Applications/IntelliJ IDEA CE.app/Contents/MacOS/idea",
+        ],
+        line_syntax: LineSyntax::LineFlag,
+    },
+    EditorDef {
+        id: "webstorm",
+        name: "WebStorm",
+        cli: "webstorm",
+        mac_fallbacks: &["/Applications/WebStorm.app/Contents/MacOS/webstorm"],
+        line_syntax: LineSyntax::LineFlag,
+    },
+    EditorDef {
+        id: "pycharm",
+        name: "PyCharm",
+        cli: "pycharm",
+        mac_fallbacks: &[
+            "/Applications/PyCharm.app/Contents/MacOS/pycharm",
+            "/Applications/PyCharm CE.app/Contents/MacOS/pycharm",
+        ],
+        line_syntax: LineSyntax::LineFlag,
+    },
+    EditorDef {
+        id: "goland",
+        name: "GoLand",
+        cli: "goland",
+        mac_fallbacks: &["/Applications/GoLand.app/Contents/MacOS/goland"],
+        line_syntax: LineSyntax::LineFlag,
+    },
+    EditorDef {
+        id: "rustrover",
+        name: "RustRover",
+        cli: "rustrover",
+        mac_fallbacks: &["/Applications/RustRover.app/Contents/MacOS/rustrover"],
+        line_syntax: LineSyntax::LineFlag,
+    },
+];
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorInfo {
+    pub id: String,
+    pub name: String,
+    pub cli_path: String,
+}
+
+pub(crate) fn which(cli: &str) -> Option<PathBuf> {
+    let finder = if cfg!(windows) { "where" } else { "which" };
+    let output = Command::new(finder).arg(cli).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|l| PathBuf::from(l.trim()))
+        .filter(|p| !p.as_os_str().is_empty())
+}
+
+#[cfg(feature = "gui")]
+fn find_editor_cli(editor: &EditorDef) -> Option<PathBuf> {
+    which(editor.cli).or_else(|| {
+        if cfg!(target_os = "macos") {
+            editor
+                .mac_fallbacks
+                .iter()
+                .map(PathBuf::from)
+                .find(|p| p.exists())
+        } else {
+            None
+        }
+    })
+}
+
+/// Lists editors whose launcher can be found on this machine.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn detect_editors() -> Result<Vec<EditorInfo>, String> {
+    tokio::task::spawn_blocking(|| {
+        EDITORS
+            .iter()
+            .filter_map(|editor| {
+                find_editor_cli(editor).map(|path| EditorInfo {
+                    id: editor.id.to_string(),
+                    name: editor.name.to_string(),
+                    cli_path: path.to_string_lossy().to_string(),
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Opens a project in an editor, optionally jumping to a file (and line).
+/// `file` may be absolute or relative to the project.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn open_in_editor(
+    project_path: String,
+    editor_id: String,
+    file: Option<String>,
+    line: Option<u32>,
+) -> Result<(), String> {
+    let editor = EDITORS
+        .iter()
+        .find(|e| e.id == editor_id)
+        .ok_or_else(|| format!("Unknown editor: {}", editor_id))?;
+
+    tokio::task::spawn_blocking(move || {
+        let cli = find_editor_cli(editor)
+            .ok_or_else(|| format!("{} is not installed or its CLI is not on PATH", editor.name))?;
+
+        let mut args: Vec<String> = vec![project_path.clone()];
+        if let Some(file) = file {
+            let file_path = if Path::new(&file).is_absolute() {
+                PathBuf::from(&file)
+            } else {
+                Path::new(&project_path).join(&file)
+            };
+            let file_path = file_path.to_string_lossy().to_string();
+
+            match (line, editor.line_syntax) {
+                (Some(line), LineSyntax::Goto) => {
+                    args.push("--goto".to_string());
+                    args.push(format!("{}:{}", file_path, line));
+                }
+                (Some(line), LineSyntax::Suffix) => args.push(format!("{}:{}", file_path, line)),
+                (Some(line), LineSyntax::LineFlag) => {
+                    args.push("--line".to_string());
+                    args.push(line.to_string());
+                    args.push(file_path);
+                }
+                (None, _) => args.push(file_path),
+            }
+        }
+
+        Command::new(&cli)
+            .args(&args)
+            .spawn()
+            .map_err(|e| format!("Failed to open {}: {}", editor.name, e))?;
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
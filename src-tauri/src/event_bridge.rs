@@ -0,0 +1,186 @@
+//! WebSocket bridge for app events.
+//!
+//! Re-broadcasts agent output and orchestrator events to external clients —
+//! an OBS overlay, a dashboard on another machine through a tunnel — over
+//! `ws://127.0.0.1:<port>/api/events` on the control API server. Clients
+//! authenticate with `?token=` (browsers can't set headers on WebSockets) and
+//! can narrow what they receive with `?projects=<id>,<id>` and
+//! `?events=<name>,<name>`.
+//!
+//! `agent-output` and `agent-exit` carry only a process ID, so they are
+//! attributed to a project through the build's `build-process-started` event.
+//! Agents started outside a build have no project and only reach unfiltered
+//! subscribers.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::Query;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Listener};
+use tokio::sync::broadcast;
+
+/// Events forwarded to subscribers.
+const FORWARDED_EVENTS: &[&str] = &[
+    "agent-output",
+    "agent-exit",
+    "build-status",
+    "build-log",
+    "build-process-started",
+    "build-story-status",
+    "build-story-snapshot",
+    "build-story-finished",
+    "build-story-failed",
+    "build-merge-conflict",
+    "build-finished",
+];
+
+/// Slow clients that fall further behind than this skip ahead.
+const CHANNEL_CAPACITY: usize = 1024;
+
+lazy_static::lazy_static! {
+    static ref BUS: broadcast::Sender<BridgeEvent> = broadcast::channel(CHANNEL_CAPACITY).0;
+    /// Agent process ID -> project ID, learned from `build-process-started`.
+    static ref PROCESS_PROJECTS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// A message sent to subscribers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeEvent {
+    pub event: String,
+    pub project_id: Option<String>,
+    pub payload: serde_json::Value,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SubscribeParams {
+    /// Comma-separated project IDs.
+    #[serde(default)]
+    projects: Option<String>,
+    /// Comma-separated event names.
+    #[serde(default)]
+    events: Option<String>,
+}
+
+struct Filter {
+    projects: Option<HashSet<String>>,
+    events: Option<HashSet<String>>,
+}
+
+impl Filter {
+    fn from_params(params: SubscribeParams) -> Self {
+        let split = |list: Option<String>| {
+            list.map(|l| {
+                l.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect::<HashSet<_>>()
+            })
+            .filter(|set| !set.is_empty())
+        };
+        Filter {
+            projects: split(params.projects),
+            events: split(params.events),
+        }
+    }
+
+    fn matches(&self, event: &BridgeEvent) -> bool {
+        let project_ok = match (&self.projects, &event.project_id) {
+            (None, _) => true,
+            (Some(projects), Some(id)) => projects.contains(id),
+            (Some(_), None) => false,
+        };
+        project_ok && self.events.as_ref().is_none_or(|events| events.contains(&event.event))
+    }
+}
+
+fn project_for(event: &str, payload: &serde_json::Value) -> Option<String> {
+    let field = |name: &str| payload.get(name).and_then(|v| v.as_str()).map(str::to_string);
+    let mut processes = PROCESS_PROJECTS.lock().ok()?;
+    match event {
+        "build-process-started" => {
+            let project_id = field("projectId")?;
+            processes.insert(field("processId")?, project_id.clone());
+            Some(project_id)
+        }
+        "agent-output" | "agent-exit" => processes.get(&field("processId")?).cloned(),
+        _ => field("projectId"),
+    }
+}
+
+/// Subscribes to app events and feeds them to the bridge. Called once during
+/// setup; costs nothing while no client is connected.
+pub fn init(app: &AppHandle) {
+    for &name in FORWARDED_EVENTS {
+        app.listen_any(name, move |event| {
+            // Process mappings are kept up to date even with no subscribers
+            let tracks_processes = matches!(name, "build-process-started" | "build-finished");
+            if BUS.receiver_count() == 0 && !tracks_processes {
+                return;
+            }
+            let payload: serde_json::Value = serde_json::from_str(event.payload()).unwrap_or_default();
+            let project_id = project_for(name, &payload);
+            if name == "build-finished" {
+                if let Some(project_id) = &project_id {
+                    forget_build_processes(project_id);
+                }
+            }
+            let _ = BUS.send(BridgeEvent {
+                event: name.to_string(),
+                project_id,
+                payload,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            });
+        });
+    }
+}
+
+/// Receives the forwarded events in-process, as `lan_sync` does for peers.
+pub(crate) fn subscribe() -> broadcast::Receiver<BridgeEvent> {
+    BUS.subscribe()
+}
+
+/// Drops the process mappings of a finished build.
+fn forget_build_processes(project_id: &str) {
+    if let Ok(mut processes) = PROCESS_PROJECTS.lock() {
+        processes.retain(|_, id| id != project_id);
+    }
+}
+
+async fn forward(mut socket: WebSocket, filter: Filter) {
+    let mut events = BUS.subscribe();
+    loop {
+        tokio::select! {
+            received = events.recv() => match received {
+                Ok(event) => {
+                    if !filter.matches(&event) {
+                        continue;
+                    }
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                // Pings are answered by axum; other messages are ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// `GET /api/events` — upgrades to a WebSocket streaming `BridgeEvent`s.
+pub async fn ws_handler(ws: WebSocketUpgrade, Query(params): Query<SubscribeParams>) -> Response {
+    let filter = Filter::from_params(params);
+    ws.on_upgrade(move |socket| forward(socket, filter))
+}
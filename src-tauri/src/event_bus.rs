@@ -0,0 +1,111 @@
+//! Replayable app events.
+//!
+//! Events sent with `event_bus::emit` go out as ordinary Tauri events, and
+//! are also kept in a bounded buffer per topic (event name) so a window
+//! opened after a build started can catch up with `replay_events`. Each one
+//! is numbered from a single counter; object payloads carry the number as
+//! `eventSeq`, so a subscriber that listens before replaying can drop the
+//! live events the replay already gave it.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+/// Events kept per topic.
+const DEFAULT_CAPACITY: usize = 500;
+
+/// Topics sent often enough to need a bigger buffer.
+const TOPIC_CAPACITIES: &[(&str, usize)] = &[("agent-output", 2000), ("build-log", 2000), ("tunnel-output", 1000)];
+
+lazy_static::lazy_static! {
+    static ref TOPICS: Mutex<HashMap<String, TopicBuffer>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/// A buffered event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BusEvent {
+    pub seq: u64,
+    pub timestamp: String,
+    pub payload: Value,
+}
+
+#[derive(Default)]
+struct TopicBuffer {
+    events: VecDeque<BusEvent>,
+    /// Number of the newest event dropped to make room.
+    dropped_through: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventReplay {
+    pub topic: String,
+    pub events: Vec<BusEvent>,
+    /// Number of the newest event on any topic when the replay was taken.
+    pub latest_seq: u64,
+    /// Events after `since_seq` were dropped from the buffer before they
+    /// could be replayed.
+    pub truncated: bool,
+}
+
+fn capacity(topic: &str) -> usize {
+    TOPIC_CAPACITIES
+        .iter()
+        .find(|(name, _)| *name == topic)
+        .map_or(DEFAULT_CAPACITY, |(_, capacity)| *capacity)
+}
+
+/// Emits `payload` as the `topic` event and buffers it for replay.
+pub fn emit(app: &AppHandle, topic: &str, payload: impl Serialize) -> tauri::Result<()> {
+    let mut payload = serde_json::to_value(payload)?;
+    // Numbered and sent under the lock, so events arrive in number order
+    let mut topics = TOPICS.lock().unwrap_or_else(|e| e.into_inner());
+    let seq = NEXT_SEQ.fetch_add(1, Ordering::SeqCst);
+    if let Value::Object(fields) = &mut payload {
+        fields.insert("eventSeq".to_string(), Value::from(seq));
+    }
+    let buffer = topics.entry(topic.to_string()).or_default();
+    if buffer.events.len() >= capacity(topic) {
+        if let Some(dropped) = buffer.events.pop_front() {
+            buffer.dropped_through = dropped.seq;
+        }
+    }
+    buffer.events.push_back(BusEvent {
+        seq,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        payload: payload.clone(),
+    });
+    app.emit(topic, payload)
+}
+
+/// Returns the buffered `topic` events numbered after `since_seq`, or all of
+/// them.
+#[tauri::command(rename_all = "camelCase")]
+pub fn replay_events(topic: String, since_seq: Option<u64>) -> Result<EventReplay, String> {
+    let since = since_seq.unwrap_or(0);
+    let topics = TOPICS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let latest_seq = NEXT_SEQ.load(Ordering::SeqCst) - 1;
+    let Some(buffer) = topics.get(&topic) else {
+        return Ok(EventReplay {
+            topic,
+            events: Vec::new(),
+            latest_seq,
+            truncated: false,
+        });
+    };
+    let truncated = buffer.dropped_through > since;
+    let events = buffer.events.iter().filter(|e| e.seq > since).cloned().collect();
+    Ok(EventReplay {
+        topic,
+        events,
+        latest_seq,
+        truncated,
+    })
+}
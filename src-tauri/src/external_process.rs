@@ -0,0 +1,259 @@
+//! Monitoring for agent processes started outside the app.
+//!
+//! An agent run from a terminal can be attached by PID. Ideate can't read
+//! its output or exit status, since it isn't our child, but it polls `ps`
+//! for liveness and CPU/memory use, emits `external-process-stats` while the
+//! process runs, and records a process history entry when it goes away.
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::app_data::app_data_dir;
+use crate::models::{ProcessCommand, ProcessHistoryEntry};
+use crate::process::append_process_history;
+
+/// How often attached processes are sampled.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+lazy_static::lazy_static! {
+    static ref ATTACHED: Mutex<HashMap<String, ExternalProcess>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalProcess {
+    pub process_id: String,
+    pub pid: u32,
+    pub label: String,
+    pub project_id: String,
+    pub command: String,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalProcessStats {
+    pub process_id: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalProcessExit {
+    pub process_id: String,
+    pub duration_ms: i64,
+}
+
+/// Trimmed output of `ps -o <format>= -p <pid>`, or None once the process
+/// has gone.
+fn ps(pid: u32, format: &str) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-o", &format!("{}=", format), "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
+
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks that the process exists
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Parses ps's elapsed time, `[[dd-]hh:]mm:ss`, into seconds.
+fn parse_elapsed(etime: &str) -> Option<i64> {
+    let (days, clock) = match etime.split_once('-') {
+        Some((days, clock)) => (days.parse::<i64>().ok()?, clock),
+        None => (0, etime),
+    };
+    let seconds = clock
+        .split(':')
+        .try_fold(0i64, |total, part| part.parse::<i64>().ok().map(|n| total * 60 + n))?;
+    Some(days * 86_400 + seconds)
+}
+
+fn sample(pid: u32) -> Option<(f32, u64)> {
+    let line = ps(pid, "%cpu,rss")?;
+    let mut fields = line.split_whitespace();
+    let cpu = fields.next()?.parse::<f32>().ok()?;
+    let rss_kb = fields.next()?.parse::<u64>().ok()?;
+    Some((cpu, rss_kb * 1024))
+}
+
+fn history_entry(process: &ExternalProcess) -> ProcessHistoryEntry {
+    let completed_at = chrono::Utc::now();
+    let duration_ms = chrono::DateTime::parse_from_rfc3339(&process.started_at)
+        .map(|started| (completed_at - started.with_timezone(&chrono::Utc)).num_milliseconds())
+        .unwrap_or(0);
+    let mut parts = process.command.split_whitespace().map(str::to_string);
+    let command = parts.next().map(|executable| ProcessCommand {
+        executable,
+        args: parts.collect(),
+        working_directory: String::new(),
+    });
+    ProcessHistoryEntry {
+        process_id: process.process_id.clone(),
+        project_id: process.project_id.clone(),
+        process_type: "external".to_string(),
+        label: process.label.clone(),
+        started_at: process.started_at.clone(),
+        completed_at: completed_at.to_rfc3339(),
+        duration_ms,
+        // The exit status of a process we didn't start can't be read
+        exit_code: None,
+        success: true,
+        agent_id: None,
+        fallback_from: None,
+        command,
+        log_file_path: None,
+    }
+}
+
+fn spawn_monitor(app: AppHandle, process_id: String) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let Some(process) = ATTACHED.lock().unwrap_or_else(|e| e.into_inner()).get(&process_id).cloned() else {
+                // Detached
+                return;
+            };
+            let pid = process.pid;
+            let stats = tokio::task::spawn_blocking(move || is_alive(pid).then(|| sample(pid)))
+                .await
+                .unwrap_or(None);
+
+            match stats {
+                Some(Some((cpu_percent, memory_bytes))) => {
+                    let _ = app.emit(
+                        "external-process-stats",
+                        ExternalProcessStats {
+                            process_id: process_id.clone(),
+                            cpu_percent,
+                            memory_bytes,
+                        },
+                    );
+                }
+                // Alive but ps couldn't sample it this time
+                Some(None) => {}
+                None => {
+                    if ATTACHED.lock().unwrap_or_else(|e| e.into_inner()).remove(&process_id).is_none() {
+                        return;
+                    }
+                    let entry = history_entry(&process);
+                    let duration_ms = entry.duration_ms;
+                    match app_data_dir(&app) {
+                        Ok(dir) => {
+                            if let Err(e) = append_process_history(&dir, entry) {
+                                tracing::warn!("Failed to record external process {}: {}", pid, e);
+                            }
+                        }
+                        Err(e) => tracing::warn!("{}", e),
+                    }
+                    tracing::info!("External process {} ({}) exited", pid, process.label);
+                    let _ = app.emit(
+                        "external-process-exit",
+                        ExternalProcessExit {
+                            process_id: process_id.clone(),
+                            duration_ms,
+                        },
+                    );
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Starts monitoring a running process by PID, such as an agent started in a
+/// terminal. Its exit is recorded in the project's process history.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn attach_external_process(
+    app: AppHandle,
+    pid: u32,
+    label: String,
+    project_id: String,
+) -> Result<ExternalProcess, String> {
+    if pid == 0 || pid == std::process::id() {
+        return Err(format!("Invalid PID: {}", pid));
+    }
+    if let Some(existing) = ATTACHED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .find(|p| p.pid == pid)
+    {
+        return Err(format!("Process {} is already attached as {}", pid, existing.label));
+    }
+
+    let (command, elapsed) = tokio::task::spawn_blocking(move || {
+        if !is_alive(pid) {
+            return Err(format!("No running process with PID {}", pid));
+        }
+        let command = ps(pid, "command").unwrap_or_default();
+        let elapsed = ps(pid, "etime").as_deref().and_then(parse_elapsed);
+        Ok((command, elapsed))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let started_at = chrono::Utc::now() - chrono::Duration::seconds(elapsed.unwrap_or(0));
+    let label = match label.trim() {
+        "" => command.split_whitespace().next().unwrap_or("External process").to_string(),
+        label => label.to_string(),
+    };
+    let process = ExternalProcess {
+        process_id: format!("ext-{}", Uuid::new_v4()),
+        pid,
+        label,
+        project_id,
+        command,
+        started_at: started_at.to_rfc3339(),
+    };
+
+    ATTACHED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(process.process_id.clone(), process.clone());
+    tracing::info!("Attached to external process {} ({})", pid, process.label);
+    spawn_monitor(app, process.process_id.clone());
+    Ok(process)
+}
+
+/// Lists attached processes, optionally only those of one project.
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_external_processes(project_id: Option<String>) -> Vec<ExternalProcess> {
+    let attached = ATTACHED.lock().unwrap_or_else(|e| e.into_inner());
+    let mut processes: Vec<ExternalProcess> = attached
+        .values()
+        .filter(|p| project_id.as_ref().is_none_or(|id| p.project_id == *id))
+        .cloned()
+        .collect();
+    processes.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    processes
+}
+
+/// Stops monitoring an attached process without touching it. Nothing is
+/// recorded in history.
+#[tauri::command(rename_all = "camelCase")]
+pub fn detach_external_process(process_id: String) -> Result<(), String> {
+    ATTACHED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&process_id)
+        .map(|_| ())
+        .ok_or_else(|| format!("No attached process: {}", process_id))
+}
@@ -0,0 +1,184 @@
+//! Advisory locking for files agents and the app both write.
+//!
+//! Agents edit `.ideate/prd.json` while the app updates it too, and a plain
+//! write lets the last writer silently win. Writers here take an exclusive
+//! `<file>.lock` next to the file (agents and scripts can honor it too), write
+//! through a temp file and rename, and check the file is unchanged before
+//! replacing it:
+//!
+//! - `update` re-reads and re-applies its change when the file moved under it,
+//!   so backend read-modify-write merges with other writers.
+//! - `write_checked` refuses to overwrite changes made since the app last read
+//!   or wrote the file, for whole-document saves from the UI.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+/// How long to wait for another writer's lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Locks older than this were left by a crashed writer and are broken.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Times `update` re-applies its change before giving up.
+const MAX_UPDATE_ATTEMPTS: usize = 3;
+
+lazy_static::lazy_static! {
+    /// Hash of each file as the app last read or wrote it.
+    static ref SEEN: Mutex<HashMap<PathBuf, String>> = Mutex::new(HashMap::new());
+}
+
+/// Held while writing; removes the lock file when dropped, unless it was
+/// broken as stale and someone else's lock took its place.
+pub struct FileLock {
+    path: PathBuf,
+    owner: String,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if fs::read_to_string(&self.path).is_ok_and(|owner| owner == self.owner) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!("{}.lock", file_name(path)))
+}
+
+fn is_stale(lock: &Path) -> bool {
+    fs::metadata(lock)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+/// Removes a stale lock. It is first renamed to a name of its own, which only
+/// one of several waiters breaking it at once can do, and checked for
+/// staleness again under that name: if another waiter broke it first and a
+/// new writer has taken the lock since, that fresh lock is put back.
+fn break_stale_lock(lock: &Path) {
+    let moved = lock.with_file_name(format!(
+        ".{}.{}.stale",
+        file_name(lock),
+        uuid::Uuid::new_v4().simple()
+    ));
+    if fs::rename(lock, &moved).is_err() {
+        return;
+    }
+    if is_stale(&moved) {
+        tracing::warn!("Breaking stale lock {}", lock.display());
+    } else {
+        // Fails only if yet another writer took the lock in between
+        let _ = fs::hard_link(&moved, lock);
+    }
+    let _ = fs::remove_file(&moved);
+}
+
+/// Takes the lock for `path`, waiting for other writers to finish. This can
+/// wait for seconds, so async code calls it, and everything that takes it,
+/// through `spawn_blocking`.
+pub fn acquire(path: &Path) -> Result<FileLock, String> {
+    let lock = lock_path(path);
+    let started = Instant::now();
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(&lock) {
+            Ok(mut file) => {
+                let owner = format!(
+                    "{} {} {}\n",
+                    std::process::id(),
+                    chrono::Utc::now().to_rfc3339(),
+                    uuid::Uuid::new_v4().simple()
+                );
+                let _ = file.write_all(owner.as_bytes());
+                return Ok(FileLock { path: lock, owner });
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                if is_stale(&lock) {
+                    break_stale_lock(&lock);
+                    continue;
+                }
+                if started.elapsed() >= LOCK_TIMEOUT {
+                    return Err(format!("Failed to lock {}: another process is writing it", file_name(path)));
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => return Err(format!("Failed to lock {}: {}", file_name(path), e)),
+        }
+    }
+}
+
+fn hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Records the content the app just read, for `write_checked`.
+pub fn record_read(path: &Path, content: &str) {
+    if let Ok(mut seen) = SEEN.lock() {
+        seen.insert(path.to_path_buf(), hash(content));
+    }
+}
+
+/// Writes through a temp file so readers never see a partial file.
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name(path)));
+    fs::write(&tmp, content).map_err(|e| format!("Failed to write {}: {}", file_name(path), e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to write {}: {}", file_name(path), e)
+    })?;
+    record_read(path, content);
+    Ok(())
+}
+
+/// Replaces the file unless it changed since the app last read or wrote it.
+#[cfg(feature = "gui")]
+pub fn write_checked(path: &Path, content: &str) -> Result<(), String> {
+    let _lock = acquire(path)?;
+    let expected = SEEN.lock().ok().and_then(|seen| seen.get(path).cloned());
+    if let (Some(expected), Ok(current)) = (expected, fs::read_to_string(path)) {
+        if hash(&current) != expected {
+            return Err(format!(
+                "Conflict: {} was changed by another process since it was loaded. Reload to see those changes, then save again.",
+                file_name(path)
+            ));
+        }
+    }
+    write_atomic(path, content)
+}
+
+/// Read-modify-write under the lock. `apply` gets the current content (`None`
+/// if the file doesn't exist) and returns the new content; it is re-run on the
+/// newer content if someone ignoring the lock wrote the file meanwhile.
+pub fn update<R>(path: &Path, mut apply: impl FnMut(Option<&str>) -> Result<(String, R), String>) -> Result<R, String> {
+    let _lock = acquire(path)?;
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+        let before = fs::read_to_string(path).ok();
+        let (content, result) = apply(before.as_deref())?;
+        if fs::read_to_string(path).ok() != before {
+            tracing::info!("{} changed while updating; merging", file_name(path));
+            continue;
+        }
+        write_atomic(path, &content)?;
+        return Ok(result);
+    }
+    Err(format!(
+        "Conflict: {} kept changing while it was being updated",
+        file_name(path)
+    ))
+}
@@ -0,0 +1,137 @@
+//! Opening projects from the OS: double-clicked `.ideateproj` markers, a
+//! project's `.ideate/config.json` via "Open With", or paths passed on the
+//! command line.
+//!
+//! Requests are queued until the frontend takes them, so files opened before
+//! the window has loaded are not lost.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::models::ProjectConfig;
+use crate::tray;
+
+/// Extension of project marker files.
+pub const MARKER_EXTENSION: &str = "ideateproj";
+
+lazy_static::lazy_static! {
+    static ref PENDING: Mutex<Vec<OpenProjectRequest>> = Mutex::new(Vec::new());
+}
+
+/// A project the OS asked the app to open.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenProjectRequest {
+    pub path: String,
+    pub name: String,
+}
+
+/// Contents of a `.ideateproj` marker. `path` is relative to the marker's
+/// directory and defaults to it.
+#[derive(Debug, Default, Deserialize)]
+struct ProjectMarker {
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// Maps an opened file to the project directory it belongs to.
+fn resolve_project_dir(path: &Path) -> Result<PathBuf, String> {
+    if path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("'{}' has no parent directory", path.display()))?;
+
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(MARKER_EXTENSION) => {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read project marker: {}", e))?;
+            // An empty marker simply points at its own directory
+            let marker: ProjectMarker = if content.trim().is_empty() {
+                ProjectMarker::default()
+            } else {
+                serde_json::from_str(&content).map_err(|e| format!("Failed to parse project marker: {}", e))?
+            };
+            Ok(match marker.path {
+                Some(relative) => parent.join(relative),
+                None => parent.to_path_buf(),
+            })
+        }
+        _ if path.file_name().is_some_and(|n| n == "config.json")
+            && parent.file_name().is_some_and(|n| n == ".ideate") =>
+        {
+            parent
+                .parent()
+                .map(Path::to_path_buf)
+                .ok_or_else(|| "Project directory not found".to_string())
+        }
+        _ => Err(format!("'{}' is not an Ideate project", path.display())),
+    }
+}
+
+/// Project name from `.ideate/config.json`, falling back to the directory name.
+fn project_name(project_dir: &Path) -> String {
+    fs::read_to_string(project_dir.join(".ideate").join("config.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectConfig>(&content).ok())
+        .map(|config| config.name)
+        .unwrap_or_else(|| {
+            project_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Imported Project")
+                .to_string()
+        })
+}
+
+/// Queues a project for the frontend to open and brings the main window
+/// forward.
+pub fn open_path(app: &AppHandle, path: &Path) -> Result<OpenProjectRequest, String> {
+    let project_dir = resolve_project_dir(path)?;
+    if !project_dir.exists() {
+        return Err(format!("Directory '{}' does not exist", project_dir.display()));
+    }
+
+    let request = OpenProjectRequest {
+        path: project_dir.to_string_lossy().to_string(),
+        name: project_name(&project_dir),
+    };
+    PENDING
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .push(request.clone());
+
+    tray::show_main_window(app);
+    let _ = app.emit("open-project-requested", request.clone());
+    Ok(request)
+}
+
+/// Opens any project files among command-line arguments, as passed by
+/// Windows and Linux file associations.
+pub fn open_from_args(app: &AppHandle, args: &[String]) {
+    // Deep links arrive as arguments too and are handled by the deep-link plugin
+    for arg in args.iter().skip(1).filter(|a| !a.starts_with('-') && !a.contains("://")) {
+        if let Err(e) = open_path(app, Path::new(arg)) {
+            tracing::warn!("Open file: {}", e);
+        }
+    }
+}
+
+/// Opens the project a file belongs to.
+#[tauri::command(rename_all = "camelCase")]
+pub fn handle_open_file(app: AppHandle, path: String) -> Result<OpenProjectRequest, String> {
+    open_path(&app, Path::new(&path))
+}
+
+/// Returns and clears projects waiting to be opened.
+#[tauri::command]
+pub fn take_pending_open_projects() -> Result<Vec<OpenProjectRequest>, String> {
+    let mut pending = PENDING.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(std::mem::take(&mut *pending))
+}
@@ -0,0 +1,318 @@
+//! Live views of files the process system doesn't own.
+//!
+//! `tail_file` streams the end of a file, and with `follow` every line
+//! appended after it, as `tail-file-lines` events; agent-written files like
+//! `progress.md` and server logs can be watched this way. The reader stays at
+//! most `MAX_IN_FLIGHT` batches ahead of the last batch the UI acknowledged
+//! with `ack_tail_file`; the file itself is the buffer, so a slow view falls
+//! behind instead of piling events up. Truncation and replacement (log
+//! rotation) restart reading from the top of the new file.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+/// How much of the end of the file is sent when a tail starts.
+const INITIAL_BYTES: u64 = 64 * 1024;
+
+/// Most bytes read into one batch of lines.
+const BATCH_BYTES: usize = 64 * 1024;
+
+/// A line longer than this is sent in pieces.
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+/// Unacknowledged batches allowed before reading pauses.
+const MAX_IN_FLIGHT: u64 = 4;
+
+/// How often a followed file is checked for new content.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often a paused reader checks for acknowledgements.
+const ACK_WAIT: Duration = Duration::from_millis(50);
+
+lazy_static::lazy_static! {
+    static ref TAILS: Mutex<HashMap<String, Arc<TailState>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Default)]
+struct TailState {
+    stopped: AtomicBool,
+    acked: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTail {
+    pub tail_id: String,
+    pub path: String,
+    /// Byte offset the first batch starts at.
+    pub offset: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailLinesEvent {
+    pub tail_id: String,
+    /// Batch number, starting at 1; pass it to `ack_tail_file`.
+    pub seq: u64,
+    pub lines: Vec<String>,
+    /// The file was truncated or replaced, and these lines start it afresh.
+    pub reset: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailEndEvent {
+    pub tail_id: String,
+    /// "eof" when not following, "stopped", or "error".
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Identifies the file at a path, so a replacement can be told apart from
+/// the same file growing.
+#[cfg(unix)]
+fn file_identity(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &fs::Metadata) -> u64 {
+    0
+}
+
+struct Tailer {
+    app: AppHandle,
+    tail_id: String,
+    path: PathBuf,
+    follow: bool,
+    state: Arc<TailState>,
+    file: File,
+    identity: u64,
+    offset: u64,
+    /// Bytes of a line whose newline hasn't been written yet.
+    partial: Vec<u8>,
+    seq: u64,
+    reset: bool,
+}
+
+impl Tailer {
+    fn stopped(&self) -> bool {
+        self.state.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Waits until the UI has caught up enough for another batch. Returns
+    /// false if the tail was stopped meanwhile.
+    fn wait_for_acks(&self) -> bool {
+        while self.seq.saturating_sub(self.state.acked.load(Ordering::SeqCst)) >= MAX_IN_FLIGHT {
+            if self.stopped() {
+                return false;
+            }
+            thread::sleep(ACK_WAIT);
+        }
+        !self.stopped()
+    }
+
+    fn emit_lines(&mut self, lines: Vec<String>) {
+        if lines.is_empty() {
+            return;
+        }
+        self.seq += 1;
+        let _ = self.app.emit(
+            "tail-file-lines",
+            TailLinesEvent {
+                tail_id: self.tail_id.clone(),
+                seq: self.seq,
+                lines,
+                reset: std::mem::take(&mut self.reset),
+            },
+        );
+    }
+
+    /// Splits the pending bytes into complete lines, keeping any trailing
+    /// partial line unless `flush` is set.
+    fn take_lines(&mut self, flush: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while let Some(newline) = self.partial[start..].iter().position(|&b| b == b'\n') {
+            let end = start + newline;
+            let line = self.partial[start..end].strip_suffix(b"\r").unwrap_or(&self.partial[start..end]);
+            lines.push(String::from_utf8_lossy(line).into_owned());
+            start = end + 1;
+        }
+        self.partial.drain(..start);
+        while self.partial.len() > MAX_LINE_BYTES {
+            let piece: Vec<u8> = self.partial.drain(..MAX_LINE_BYTES).collect();
+            lines.push(String::from_utf8_lossy(&piece).into_owned());
+        }
+        if flush && !self.partial.is_empty() {
+            lines.push(String::from_utf8_lossy(&self.partial).into_owned());
+            self.partial.clear();
+        }
+        lines
+    }
+
+    /// Reads the next batch. Returns the number of bytes read.
+    fn read_batch(&mut self) -> std::io::Result<usize> {
+        let mut buf = vec![0u8; BATCH_BYTES];
+        let read = self.file.read(&mut buf)?;
+        self.offset += read as u64;
+        self.partial.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+
+    /// Reopens the file if it was truncated or replaced. Returns true if
+    /// reading restarted from the top.
+    fn check_rotation(&mut self) -> bool {
+        let Ok(metadata) = fs::metadata(&self.path) else {
+            // Removed; wait for it to be recreated
+            return false;
+        };
+        if file_identity(&metadata) == self.identity && metadata.len() >= self.offset {
+            return false;
+        }
+        let Ok(file) = File::open(&self.path) else {
+            return false;
+        };
+        self.file = file;
+        self.identity = file_identity(&metadata);
+        self.offset = 0;
+        self.partial.clear();
+        self.reset = true;
+        true
+    }
+
+    fn run(mut self) -> Result<&'static str, String> {
+        loop {
+            if !self.wait_for_acks() {
+                return Ok("stopped");
+            }
+            let read = self
+                .read_batch()
+                .map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?;
+            if read > 0 {
+                let lines = self.take_lines(false);
+                self.emit_lines(lines);
+                continue;
+            }
+            if !self.follow {
+                let lines = self.take_lines(true);
+                self.emit_lines(lines);
+                return Ok("eof");
+            }
+            if !self.check_rotation() {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Opens `path` at the start of its last `INITIAL_BYTES`, skipping to the
+/// first full line.
+fn open_tail(path: &Path) -> Result<(File, u64, u64), String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let metadata = file
+        .metadata()
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if metadata.is_dir() {
+        return Err(format!("{} is a directory", path.display()));
+    }
+    let mut offset = metadata.len().saturating_sub(INITIAL_BYTES);
+    if offset > 0 {
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut byte = [0u8; 1];
+        while file.read(&mut byte).map_err(|e| format!("Failed to read {}: {}", path.display(), e))? == 1 {
+            offset += 1;
+            if byte[0] == b'\n' {
+                break;
+            }
+        }
+    }
+    Ok((file, file_identity(&metadata), offset))
+}
+
+/// Streams the end of a file as `tail-file-lines` events and, if `follow`,
+/// keeps streaming lines as they are appended until `stop_tail_file`. A
+/// `tail-file-end` event is emitted when the tail finishes.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn tail_file(app: AppHandle, path: String, follow: bool) -> Result<FileTail, String> {
+    let path_buf = PathBuf::from(&path);
+    let (file, identity, offset) = tokio::task::spawn_blocking({
+        let path_buf = path_buf.clone();
+        move || open_tail(&path_buf)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let tail_id = Uuid::new_v4().to_string();
+    let state = Arc::new(TailState::default());
+    TAILS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(tail_id.clone(), state.clone());
+
+    let tailer = Tailer {
+        app: app.clone(),
+        tail_id: tail_id.clone(),
+        path: path_buf,
+        follow,
+        state,
+        file,
+        identity,
+        offset,
+        partial: Vec::new(),
+        seq: 0,
+        reset: false,
+    };
+    let id = tail_id.clone();
+    thread::spawn(move || {
+        let (reason, error) = match tailer.run() {
+            Ok(reason) => (reason, None),
+            Err(e) => {
+                tracing::warn!("Tail {} ended: {}", id, e);
+                ("error", Some(e))
+            }
+        };
+        TAILS.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+        let _ = app.emit(
+            "tail-file-end",
+            TailEndEvent {
+                tail_id: id,
+                reason: reason.to_string(),
+                error,
+            },
+        );
+    });
+
+    Ok(FileTail { tail_id, path, offset })
+}
+
+/// Acknowledges every batch up to `seq`, letting the reader continue.
+#[tauri::command(rename_all = "camelCase")]
+pub fn ack_tail_file(tail_id: String, seq: u64) -> Result<(), String> {
+    let tails = TAILS.lock().unwrap_or_else(|e| e.into_inner());
+    let state = tails.get(&tail_id).ok_or_else(|| format!("No active tail: {}", tail_id))?;
+    state.acked.fetch_max(seq, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Stops a tail. Its `tail-file-end` event follows shortly.
+#[tauri::command(rename_all = "camelCase")]
+pub fn stop_tail_file(tail_id: String) -> Result<(), String> {
+    let tails = TAILS.lock().unwrap_or_else(|e| e.into_inner());
+    let state = tails.get(&tail_id).ok_or_else(|| format!("No active tail: {}", tail_id))?;
+    state.stopped.store(true, Ordering::SeqCst);
+    Ok(())
+}
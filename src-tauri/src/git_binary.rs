@@ -0,0 +1,240 @@
+//! Locating a usable git.
+//!
+//! Everything that runs git starts it with `command()`, which uses the git
+//! `detect` found: the `gitPath` preference, then git on PATH, then the
+//! usual install locations, then a git bundled with the app under
+//! `resources/git`. On macOS `/usr/bin/git` is a shim until the Command Line
+//! Tools are installed, and running it opens their installer instead, so it
+//! only counts once `xcode-select -p` succeeds. Without a git, builds run
+//! stories one at a time without worktrees or rollback snapshots, and git
+//! features fail with `require`'s guidance rather than a spawn error.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::RwLock;
+
+use serde::Serialize;
+#[cfg(feature = "gui")]
+use tauri::{AppHandle, Manager};
+
+#[cfg(feature = "gui")]
+use crate::models::Preferences;
+
+/// Where git is commonly installed when it isn't on the PATH the app was
+/// started with (apps launched from the Dock or Finder get a minimal one).
+#[cfg(unix)]
+const KNOWN_LOCATIONS: &[&str] = &[
+    "/opt/homebrew/bin/git",
+    "/usr/local/bin/git",
+    "/opt/local/bin/git",
+    "/usr/bin/git",
+];
+
+#[cfg(windows)]
+const KNOWN_LOCATIONS: &[&str] = &[
+    r"C:\Program Files\Git\cmd\git.exe",
+    r"C:\Program Files (x86)\Git\cmd\git.exe",
+];
+
+#[cfg(not(any(unix, windows)))]
+const KNOWN_LOCATIONS: &[&str] = &[];
+
+#[derive(Default)]
+struct Settings {
+    /// The `gitPath` preference.
+    preferred: Option<String>,
+    /// Where a bundled git would be, from the app's resource directory.
+    bundled: Option<PathBuf>,
+    /// Result of the last detection.
+    detected: Option<GitInfo>,
+}
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<Settings> = RwLock::new(Settings::default());
+}
+
+/// The git in use, or why there isn't one.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInfo {
+    pub available: bool,
+    pub path: Option<String>,
+    /// e.g. "git version 2.44.0"
+    pub version: Option<String>,
+    /// "preference", "path", "system", or "bundled"
+    pub source: Option<String>,
+    /// What went wrong and how to fix it, when git isn't available.
+    pub guidance: Option<String>,
+}
+
+/// The `--version` output of the git at `path`, if it runs.
+fn version_of(path: &Path) -> Option<String> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|version| version.starts_with("git version"))
+}
+
+/// Whether `path` is the macOS shim that needs the Command Line Tools, and
+/// they aren't installed.
+fn is_unready_shim(path: &Path) -> bool {
+    cfg!(target_os = "macos")
+        && path == Path::new("/usr/bin/git")
+        && !Command::new("xcode-select")
+            .arg("-p")
+            .output()
+            .is_ok_and(|output| output.status.success())
+}
+
+fn found(path: PathBuf, source: &str) -> Option<GitInfo> {
+    if !path.is_file() || is_unready_shim(&path) {
+        return None;
+    }
+    let version = version_of(&path)?;
+    Some(GitInfo {
+        available: true,
+        path: Some(path.to_string_lossy().to_string()),
+        version: Some(version),
+        source: Some(source.to_string()),
+        guidance: None,
+    })
+}
+
+fn guidance(preferred: Option<&str>, shim_pending: bool) -> String {
+    if let Some(path) = preferred {
+        return format!(
+            "Git was not found at {}, the path set in Settings. Fix or clear the path, then try again.",
+            path
+        );
+    }
+    if shim_pending {
+        return "Git needs the Xcode Command Line Tools. Run `xcode-select --install` in Terminal, finish the installer, then try again."
+            .to_string();
+    }
+    if cfg!(windows) {
+        "Git is not installed. Install it from https://git-scm.com/download/win, or set its path in Settings, then try again."
+            .to_string()
+    } else if cfg!(target_os = "macos") {
+        "Git is not installed. Run `xcode-select --install` or `brew install git`, or set its path in Settings, then try again."
+            .to_string()
+    } else {
+        "Git is not installed. Install it with your package manager, or set its path in Settings, then try again."
+            .to_string()
+    }
+}
+
+fn run_detection(preferred: Option<&str>, bundled: Option<&Path>) -> GitInfo {
+    let on_path = crate::editors::which("git");
+    // A preferred path that doesn't work is reported rather than worked around
+    let info = match preferred {
+        Some(path) => found(PathBuf::from(path), "preference"),
+        None => on_path
+            .clone()
+            .map(|path| (path, "path"))
+            .into_iter()
+            .chain(KNOWN_LOCATIONS.iter().map(|path| (PathBuf::from(path), "system")))
+            .chain(bundled.map(|path| (path.to_path_buf(), "bundled")))
+            .find_map(|(path, source)| found(path, source)),
+    };
+    if let Some(info) = info {
+        return info;
+    }
+
+    let shim = on_path.unwrap_or_else(|| PathBuf::from("/usr/bin/git"));
+    let shim_pending = shim.exists() && is_unready_shim(&shim);
+    GitInfo {
+        available: false,
+        path: None,
+        version: None,
+        source: None,
+        guidance: Some(guidance(preferred, shim_pending)),
+    }
+}
+
+/// Detects git, or returns the last detection unless `refresh` is set.
+pub fn detect(refresh: bool) -> GitInfo {
+    if !refresh {
+        if let Some(info) = SETTINGS.read().ok().and_then(|s| s.detected.clone()) {
+            return info;
+        }
+    }
+    let (preferred, bundled) = {
+        let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+        (settings.preferred.clone(), settings.bundled.clone())
+    };
+    let info = run_detection(preferred.as_deref(), bundled.as_deref());
+    match &info.guidance {
+        Some(guidance) => tracing::warn!("Git unavailable: {}", guidance),
+        None => tracing::info!(
+            "Using {} at {}",
+            info.version.as_deref().unwrap_or("git"),
+            info.path.as_deref().unwrap_or_default()
+        ),
+    }
+    SETTINGS.write().unwrap_or_else(|e| e.into_inner()).detected = Some(info.clone());
+    info
+}
+
+/// Whether a usable git was found.
+#[cfg(feature = "gui")]
+pub fn available() -> bool {
+    detect(false).available
+}
+
+/// Fails with guidance on getting git when there isn't a usable one.
+pub fn require() -> Result<(), String> {
+    let info = detect(false);
+    if info.available {
+        Ok(())
+    } else {
+        Err(info.guidance.unwrap_or_else(|| "Git is not available".to_string()))
+    }
+}
+
+/// A `Command` for the detected git; plain `git` when there is none, so
+/// the spawn fails the way it always has.
+pub fn command() -> Command {
+    match detect(false).path {
+        Some(path) => Command::new(path),
+        None => Command::new("git"),
+    }
+}
+
+/// Records where a bundled git would be. Called once at startup.
+#[cfg(feature = "gui")]
+pub fn init(app: &AppHandle) {
+    let exe = if cfg!(windows) { "git/cmd/git.exe" } else { "git/bin/git" };
+    let bundled = app
+        .path()
+        .resolve(exe, tauri::path::BaseDirectory::Resource)
+        .ok();
+    SETTINGS.write().unwrap_or_else(|e| e.into_inner()).bundled = bundled;
+}
+
+/// Applies the `gitPath` preference. Called at startup and when preferences
+/// are saved; git is detected again on next use.
+#[cfg(feature = "gui")]
+pub fn configure(prefs: &Preferences) {
+    let preferred = prefs
+        .git_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(str::to_string);
+    let mut settings = SETTINGS.write().unwrap_or_else(|e| e.into_inner());
+    if settings.preferred != preferred {
+        settings.preferred = preferred;
+        settings.detected = None;
+    }
+}
+
+/// Finds git again, e.g. after installing it, and reports what was found.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn detect_git() -> Result<GitInfo, String> {
+    tokio::task::spawn_blocking(|| detect(true))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
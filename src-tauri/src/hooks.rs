@@ -0,0 +1,332 @@
+//! Project hooks.
+//!
+//! Shell commands configured under `hooks` in `.ideate/config.json` that run
+//! around builds and stories:
+//!
+//! ```json
+//! "hooks": { "preBuild": "npm ci", "postStory": "./scripts/screenshot.sh" }
+//! ```
+//!
+//! Hooks run in the project directory through the platform shell, with the
+//! build and story in `IDEATE_*` environment variables. A failing `preBuild`
+//! aborts the build and a failing `preStory` fails that story attempt; post
+//! hooks only log a warning. Each run is saved to the process logs and shows
+//! up in process history as a "hook" entry.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::models::{
+    ProcessCommand, ProcessHistoryEntry, ProcessLogEntry, ProjectConfig, ProjectHooks, Story,
+};
+use crate::process::{append_process_history, save_process_log_blocking};
+use crate::utils::get_ideate_dir;
+
+/// Hooks still running after this are killed and count as failed.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Output lines kept for the build log.
+const OUTPUT_TAIL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy)]
+pub enum HookKind {
+    PreBuild,
+    PostBuild,
+    PreStory,
+    PostStory,
+}
+
+impl HookKind {
+    /// The key used in config.json.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookKind::PreBuild => "preBuild",
+            HookKind::PostBuild => "postBuild",
+            HookKind::PreStory => "preStory",
+            HookKind::PostStory => "postStory",
+        }
+    }
+
+    /// The hook with this config.json key.
+    #[cfg(feature = "gui")]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [HookKind::PreBuild, HookKind::PostBuild, HookKind::PreStory, HookKind::PostStory]
+            .into_iter()
+            .find(|kind| kind.name() == name)
+    }
+
+    fn command(self, hooks: &ProjectHooks) -> Option<&str> {
+        match self {
+            HookKind::PreBuild => hooks.pre_build.as_deref(),
+            HookKind::PostBuild => hooks.post_build.as_deref(),
+            HookKind::PreStory => hooks.pre_story.as_deref(),
+            HookKind::PostStory => hooks.post_story.as_deref(),
+        }
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+    }
+}
+
+/// The build a hook runs for, exposed to it as environment variables.
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub project_id: String,
+    pub project_name: String,
+    pub project_path: String,
+    pub build_id: String,
+    pub mode: String,
+    pub agent_id: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct HookResult {
+    pub success: bool,
+    /// Why the hook failed, if it did.
+    pub reason: Option<String>,
+    /// The last lines of output, for the build log.
+    pub output_tail: Vec<String>,
+}
+
+/// Reads the project's hooks, fresh each time so edits apply mid-build.
+/// The command the project configures for a hook, if any.
+#[cfg(feature = "gui")]
+pub fn configured_command(project_path: &str, kind: HookKind) -> Option<String> {
+    kind.command(&load_hooks(project_path)).map(str::to_string)
+}
+
+fn load_hooks(project_path: &str) -> ProjectHooks {
+    fs::read_to_string(get_ideate_dir(project_path).join("config.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectConfig>(&content).ok())
+        .map(|config| config.hooks)
+        .unwrap_or_default()
+}
+
+/// Builds the platform shell invocation and its process-history record.
+fn shell_command(command: &str, working_directory: &str) -> (Command, ProcessCommand) {
+    #[cfg(unix)]
+    let (shell, flag) = ("sh", "-c");
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+
+    let mut cmd = Command::new(shell);
+    cmd.args([flag, command]);
+    let recorded = ProcessCommand {
+        executable: shell.to_string(),
+        args: vec![flag.to_string(), command.to_string()],
+        working_directory: working_directory.to_string(),
+    };
+    (cmd, recorded)
+}
+
+fn collect_output(
+    stream: impl std::io::Read + Send + 'static,
+    log_type: &'static str,
+    logs: Arc<Mutex<Vec<ProcessLogEntry>>>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if let Ok(mut logs) = logs.lock() {
+                logs.push(ProcessLogEntry {
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                    log_type: log_type.to_string(),
+                    content: line,
+                });
+            }
+        }
+    })
+}
+
+/// A shell command run to completion with its output saved like a process's.
+pub(crate) struct ShellRun<'a> {
+    pub command: &'a str,
+    pub working_directory: &'a str,
+    pub env: Vec<(&'a str, String)>,
+    pub timeout: Duration,
+    pub project_id: &'a str,
+    /// Process type in history, e.g. "hook".
+    pub process_type: &'a str,
+    pub label: String,
+}
+
+pub(crate) struct ShellOutcome {
+    pub process_id: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Why the command failed, if it did.
+    pub reason: Option<String>,
+    pub duration_ms: u64,
+    pub logs: Vec<ProcessLogEntry>,
+}
+
+impl ShellOutcome {
+    /// The last `lines` lines of output.
+    pub fn output_tail(&self, lines: usize) -> Vec<String> {
+        self.logs
+            .iter()
+            .skip(self.logs.len().saturating_sub(lines))
+            .map(|entry| entry.content.clone())
+            .collect()
+    }
+}
+
+/// Runs a shell command through the platform shell, blocking until it exits
+/// or `timeout` passes.
+///
+/// With `app_data_dir`, output is saved as a process log and the run is added
+/// to process history.
+pub(crate) fn run_shell(app_data_dir: Option<&Path>, run: ShellRun) -> ShellOutcome {
+    let (mut cmd, recorded) = shell_command(run.command, run.working_directory);
+
+    cmd.current_dir(run.working_directory)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for (key, value) in &run.env {
+        cmd.env(key, value);
+    }
+
+    let process_id = uuid::Uuid::new_v4().to_string();
+    let started_at = chrono::Utc::now();
+    let started = Instant::now();
+    let logs = Arc::new(Mutex::new(Vec::new()));
+
+    let (success, exit_code, reason) = match cmd.spawn() {
+        Ok(mut child) => {
+            let readers = [
+                child.stdout.take().map(|s| collect_output(s, "stdout", logs.clone())),
+                child.stderr.take().map(|s| collect_output(s, "stderr", logs.clone())),
+            ];
+            let status = loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => break Ok(status),
+                    Ok(None) if started.elapsed() >= run.timeout => {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        break Err(format!("timed out after {} minutes", run.timeout.as_secs() / 60));
+                    }
+                    Ok(None) => thread::sleep(Duration::from_millis(100)),
+                    Err(e) => break Err(format!("Failed to wait for {}: {}", run.process_type, e)),
+                }
+            };
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.join();
+            }
+            match status {
+                Ok(status) if status.success() => (true, status.code(), None),
+                Ok(status) => (
+                    false,
+                    status.code(),
+                    Some(match status.code() {
+                        Some(code) => format!("exit code {}", code),
+                        None => "terminated by signal".to_string(),
+                    }),
+                ),
+                Err(reason) => (false, None, Some(reason)),
+            }
+        }
+        Err(e) => (false, None, Some(format!("Failed to spawn {}: {}", run.process_type, e))),
+    };
+
+    let logs = logs.lock().map(|l| l.clone()).unwrap_or_default();
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    if let Some(app_data_dir) = app_data_dir {
+        let log_file_path = save_process_log_blocking(
+            app_data_dir.to_path_buf(),
+            process_id.clone(),
+            run.project_id.to_string(),
+            run.process_type.to_string(),
+            run.label.clone(),
+            logs.clone(),
+        );
+        if let Err(e) = &log_file_path {
+            tracing::warn!("{}: {}", run.process_type, e);
+        }
+        let entry = ProcessHistoryEntry {
+            process_id: process_id.clone(),
+            project_id: run.project_id.to_string(),
+            process_type: run.process_type.to_string(),
+            label: run.label,
+            started_at: started_at.to_rfc3339(),
+            completed_at: chrono::Utc::now().to_rfc3339(),
+            duration_ms: duration_ms as i64,
+            exit_code,
+            success,
+            agent_id: None,
+            fallback_from: None,
+            command: Some(recorded),
+            log_file_path: log_file_path.ok(),
+        };
+        if let Err(e) = append_process_history(app_data_dir, entry) {
+            tracing::warn!("{}: {}", run.process_type, e);
+        }
+    }
+
+    ShellOutcome {
+        process_id,
+        success,
+        exit_code,
+        reason,
+        duration_ms,
+        logs,
+    }
+}
+
+/// Runs a hook if the project configures one, blocking until it exits.
+/// Returns `None` when there is no hook of this kind.
+///
+/// With `app_data_dir`, output is saved as a process log and the run is added
+/// to process history.
+pub fn run(
+    app_data_dir: Option<&Path>,
+    ctx: &HookContext,
+    kind: HookKind,
+    story: Option<&Story>,
+    extra_env: &[(&str, String)],
+) -> Option<HookResult> {
+    let hooks = load_hooks(&ctx.project_path);
+    let command = kind.command(&hooks)?;
+
+    let mut env = vec![
+        ("IDEATE_HOOK", kind.name().to_string()),
+        ("IDEATE_PROJECT_ID", ctx.project_id.clone()),
+        ("IDEATE_PROJECT_NAME", ctx.project_name.clone()),
+        ("IDEATE_PROJECT_PATH", ctx.project_path.clone()),
+        ("IDEATE_BUILD_ID", ctx.build_id.clone()),
+        ("IDEATE_BUILD_MODE", ctx.mode.clone()),
+        ("IDEATE_AGENT_ID", ctx.agent_id.clone()),
+    ];
+    if let Some(story) = story {
+        env.push(("IDEATE_STORY_ID", story.id.clone()));
+        env.push(("IDEATE_STORY_TITLE", story.title.clone()));
+    }
+    env.extend(extra_env.iter().map(|(key, value)| (*key, value.clone())));
+
+    let outcome = run_shell(
+        app_data_dir,
+        ShellRun {
+            command,
+            working_directory: &ctx.project_path,
+            env,
+            timeout: HOOK_TIMEOUT,
+            project_id: &ctx.project_id,
+            process_type: "hook",
+            label: match story {
+                Some(story) => format!("{} {}", kind.name(), story.id),
+                None => kind.name().to_string(),
+            },
+        },
+    );
+
+    Some(HookResult {
+        success: outcome.success,
+        output_tail: outcome.output_tail(OUTPUT_TAIL_LINES),
+        reason: outcome.reason,
+    })
+}
@@ -0,0 +1,350 @@
+//! Background expansion of ideas.
+//!
+//! Queued ideas are expanded one at a time by running the ideas agent
+//! headlessly: it turns a rough title and summary into a fuller summary, a
+//! description, and draft stories. Each result is merged into ideas.json as
+//! soon as it arrives, and every change of a job's status is emitted as
+//! `idea-job`, so a long list of ideas can be left to expand unattended.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::agents::get_build_command;
+use crate::file_lock;
+use crate::ideas::get_ideas_file_path;
+use crate::models::{DraftStory, Idea, ProcessPriority, TerminationReason};
+use crate::preferences::load_preferences_internal;
+use crate::process::{kill_agent_blocking, spawn_tracked_process, wait_for_process, OutputOptions, OutputSink};
+use crate::prompt_templates::PromptEngine;
+use crate::utils::{agent_reply_text, parse_reply_json};
+
+/// How long one expansion may run before it is killed.
+const EXPANSION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+pub(crate) const DEFAULT_EXPANSION_PROMPT: &str = r#"Expand this rough app idea into something ready to plan a build from.
+
+Title: {{title}}
+Summary: {{summary}}
+Notes: {{description}}
+
+Respond with only a JSON object, no other text:
+{
+  "summary": "one or two sentences describing the app",
+  "description": "a fuller description: who it is for, the core features, and anything out of scope",
+  "stories": [
+    { "title": "short story title", "description": "what to build", "acceptanceCriteria": ["..."] }
+  ]
+}
+
+Draft 5 to 12 stories, ordered so each builds on the ones before it."#;
+
+lazy_static::lazy_static! {
+    static ref JOBS: Mutex<Vec<IdeaJob>> = Mutex::new(Vec::new());
+}
+
+/// Whether a worker is draining the queue.
+static WORKER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeaJob {
+    pub id: String,
+    pub idea_id: String,
+    pub idea_title: String,
+    pub agent_id: String,
+    /// "queued", "running", "done", "failed", or "canceled".
+    pub status: String,
+    pub error: Option<String>,
+    pub process_id: Option<String>,
+    pub queued_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Payload of `idea-job`. `idea` is the updated idea once a job is done.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeaJobEvent {
+    #[serde(flatten)]
+    pub job: IdeaJob,
+    pub idea: Option<Idea>,
+}
+
+/// What the agent is asked to reply with.
+#[derive(Debug, Deserialize)]
+struct Expansion {
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    stories: Vec<DraftStory>,
+}
+
+fn emit(app: &AppHandle, job: &IdeaJob, idea: Option<Idea>) {
+    let _ = app.emit(
+        "idea-job",
+        IdeaJobEvent {
+            job: job.clone(),
+            idea,
+        },
+    );
+}
+
+/// Applies `change` to a job and emits it. Returns the updated job.
+fn update_job(app: &AppHandle, job_id: &str, change: impl FnOnce(&mut IdeaJob)) -> Option<IdeaJob> {
+    let job = {
+        let mut jobs = JOBS.lock().ok()?;
+        let job = jobs.iter_mut().find(|j| j.id == job_id)?;
+        change(job);
+        job.clone()
+    };
+    emit(app, &job, None);
+    Some(job)
+}
+
+fn load_ideas(app: &AppHandle) -> Result<Vec<Idea>, String> {
+    crate::ideas::load_ideas(app.clone())
+}
+
+fn render_prompt(app: &AppHandle, idea: &Idea) -> Result<String, String> {
+    let context = serde_json::json!({
+        "title": idea.title,
+        "summary": idea.summary,
+        "description": idea.description,
+    });
+    PromptEngine::load(app).render("ideaExpansion", None, &context)
+}
+
+/// Merges an expansion into ideas.json. The summary and description are only
+/// filled in if they were empty, so nothing the user wrote is overwritten.
+fn save_expansion(app: &AppHandle, idea_id: &str, expansion: Expansion) -> Result<Idea, String> {
+    let path = get_ideas_file_path(app)?;
+    file_lock::update(&path, |content| {
+        let mut ideas: Vec<Idea> = match content {
+            Some(content) => {
+                serde_json::from_str(content).map_err(|e| format!("Failed to parse ideas.json: {}", e))?
+            }
+            None => Vec::new(),
+        };
+        let idea = ideas
+            .iter_mut()
+            .find(|i| i.id == idea_id)
+            .ok_or_else(|| format!("Idea {} was deleted", idea_id))?;
+        if idea.summary.trim().is_empty() {
+            idea.summary = expansion.summary.clone();
+        }
+        if idea.description.trim().is_empty() {
+            idea.description = expansion.description.clone();
+        }
+        idea.draft_stories = expansion.stories.clone();
+        idea.updated_at = chrono::Utc::now().to_rfc3339();
+        let updated = idea.clone();
+        let json =
+            serde_json::to_string_pretty(&ideas).map_err(|e| format!("Failed to serialize ideas: {}", e))?;
+        Ok((json, updated))
+    })
+}
+
+/// Runs the agent for one job and saves what it drafts.
+async fn expand(app: &AppHandle, job: &IdeaJob) -> Result<Idea, String> {
+    let idea = load_ideas(app)?
+        .into_iter()
+        .find(|i| i.id == job.idea_id)
+        .ok_or_else(|| format!("Idea {} was deleted", job.idea_id))?;
+    let (executable, args) =
+        get_build_command(&job.agent_id).ok_or_else(|| format!("Unknown agent: {}", job.agent_id))?;
+    let prompt = render_prompt(app, &idea)?;
+    let args: Vec<String> = args.iter().map(|a| a.replace("{{prompt}}", &prompt)).collect();
+    let working_directory = dirs::home_dir()
+        .ok_or("Home directory not found")?
+        .to_string_lossy()
+        .to_string();
+
+    let lines: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink_lines = lines.clone();
+    let sink: OutputSink = Arc::new(move |stream, line| {
+        if stream == "stdout" {
+            if let Ok(mut lines) = sink_lines.lock() {
+                lines.push(line.to_string());
+            }
+        }
+    });
+
+    let process_id = spawn_tracked_process(
+        app.clone(),
+        executable,
+        args,
+        working_directory,
+        None,
+        OutputOptions {
+            sink: Some(sink),
+            ..Default::default()
+        },
+        ProcessPriority::Low,
+    )
+    .await?;
+    let pid = process_id.clone();
+    update_job(app, &job.id, |j| j.process_id = Some(pid));
+
+    let pid = process_id.clone();
+    let mut wait = tokio::task::spawn_blocking(move || wait_for_process(&pid));
+    let result = match tokio::time::timeout(EXPANSION_TIMEOUT, &mut wait).await {
+        Ok(joined) => joined.map_err(|e| format!("Task join error: {}", e))??,
+        Err(_) => {
+            let pid = process_id.clone();
+            let _ = tokio::task::spawn_blocking(move || kill_agent_blocking(&pid, TerminationReason::Timeout)).await;
+            let _ = wait.await;
+            return Err(format!("Timed out after {} minutes", EXPANSION_TIMEOUT.as_secs() / 60));
+        }
+    };
+    if !result.success {
+        return Err(format!("Agent exited with code {:?}", result.exit_code));
+    }
+
+    let reply = agent_reply_text(&lines.lock().map(|l| l.clone()).unwrap_or_default());
+    let expansion: Expansion = parse_reply_json(&reply, "the expansion")?;
+    let app = app.clone();
+    let idea_id = job.idea_id.clone();
+    tokio::task::spawn_blocking(move || save_expansion(&app, &idea_id, expansion))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Takes the next queued job, marking it running.
+fn next_job(app: &AppHandle) -> Option<IdeaJob> {
+    let job = {
+        let mut jobs = JOBS.lock().ok()?;
+        let job = jobs.iter_mut().find(|j| j.status == "queued")?;
+        job.status = "running".to_string();
+        job.clone()
+    };
+    emit(app, &job, None);
+    Some(job)
+}
+
+/// Runs queued jobs one after another until none are left.
+fn start_worker(app: AppHandle) {
+    if WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            while let Some(job) = next_job(&app) {
+                let result = expand(&app, &job).await;
+                let finished_at = chrono::Utc::now().to_rfc3339();
+                let Ok(mut jobs) = JOBS.lock() else { break };
+                let Some(current) = jobs.iter_mut().find(|j| j.id == job.id) else { continue };
+                // A canceled job keeps its status rather than reporting the kill
+                if current.status == "running" {
+                    match &result {
+                        Ok(_) => current.status = "done".to_string(),
+                        Err(e) => {
+                            current.status = "failed".to_string();
+                            current.error = Some(e.clone());
+                        }
+                    }
+                }
+                current.finished_at = Some(finished_at);
+                let current = current.clone();
+                drop(jobs);
+                emit(&app, &current, result.ok());
+            }
+            WORKER_RUNNING.store(false, Ordering::SeqCst);
+            // A job queued after the last check but before the flag cleared
+            let pending = JOBS.lock().map(|jobs| jobs.iter().any(|j| j.status == "queued")).unwrap_or(false);
+            if !pending || WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+                break;
+            }
+        }
+    });
+}
+
+/// Queues ideas to be expanded in the background by `agent_id`, or the
+/// ideas agent from preferences. Ideas already queued or running are skipped.
+#[tauri::command(rename_all = "camelCase")]
+pub fn queue_idea_expansions(
+    app: AppHandle,
+    idea_ids: Vec<String>,
+    agent_id: Option<String>,
+) -> Result<Vec<IdeaJob>, String> {
+    let agent_id = agent_id
+        .or_else(|| load_preferences_internal(&app).ok().and_then(|p| p.ideas_agent))
+        .unwrap_or_else(|| "amp".to_string());
+    if get_build_command(&agent_id).is_none() {
+        return Err(format!("Unknown agent: {}", agent_id));
+    }
+    let ideas = load_ideas(&app)?;
+
+    let queued: Vec<IdeaJob> = {
+        let mut jobs = JOBS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let mut queued = Vec::new();
+        for idea_id in idea_ids {
+            let Some(idea) = ideas.iter().find(|i| i.id == idea_id) else { continue };
+            let pending = jobs
+                .iter()
+                .any(|j| j.idea_id == idea_id && (j.status == "queued" || j.status == "running"));
+            if pending {
+                continue;
+            }
+            let job = IdeaJob {
+                id: uuid::Uuid::new_v4().to_string(),
+                idea_id,
+                idea_title: idea.title.clone(),
+                agent_id: agent_id.clone(),
+                status: "queued".to_string(),
+                error: None,
+                process_id: None,
+                queued_at: chrono::Utc::now().to_rfc3339(),
+                finished_at: None,
+            };
+            jobs.push(job.clone());
+            queued.push(job);
+        }
+        queued
+    };
+    for job in &queued {
+        emit(&app, job, None);
+    }
+    start_worker(app);
+    Ok(queued)
+}
+
+/// Lists expansion jobs, oldest first.
+#[tauri::command]
+pub fn list_idea_jobs() -> Result<Vec<IdeaJob>, String> {
+    JOBS.lock()
+        .map(|jobs| jobs.clone())
+        .map_err(|e| format!("Lock error: {}", e))
+}
+
+/// Cancels a queued job, or kills the agent of a running one.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn cancel_idea_job(app: AppHandle, job_id: String) -> Result<(), String> {
+    let job = update_job(&app, &job_id, |job| {
+        if job.status == "queued" || job.status == "running" {
+            job.status = "canceled".to_string();
+        }
+    })
+    .ok_or_else(|| format!("Job {} not found", job_id))?;
+    if job.status == "canceled" && job.finished_at.is_none() {
+        if let Some(process_id) = job.process_id {
+            // The agent may have exited on its own in the meantime
+            let _ = tokio::task::spawn_blocking(move || kill_agent_blocking(&process_id, TerminationReason::Canceled))
+                .await
+                .map_err(|e| format!("Task join error: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes jobs that are done, failed, or canceled.
+#[tauri::command]
+pub fn clear_finished_idea_jobs() -> Result<(), String> {
+    let mut jobs = JOBS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    jobs.retain(|j| j.status == "queued" || j.status == "running");
+    Ok(())
+}
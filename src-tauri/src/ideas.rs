@@ -2,15 +2,13 @@
 
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
+use crate::app_data::app_data_dir;
 use crate::models::Idea;
 
-fn get_ideas_file_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+pub(crate) fn get_ideas_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_data_dir(app)?;
     
     if !app_data_dir.exists() {
         fs::create_dir_all(&app_data_dir)
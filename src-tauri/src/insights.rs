@@ -0,0 +1,236 @@
+//! Build success and retry statistics across projects.
+//!
+//! Stories' outcomes and attempt counts come from every project's build
+//! reports, failed attempts' kinds from its story timings, and stories'
+//! final failures from its state.json. Build processes in the process history
+//! add how often each agent's runs exited cleanly and how long they took.
+//! Everything is grouped per agent so agents can be compared side by side.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::orchestrator::report::read_build_reports;
+use crate::orchestrator::timings::read_timings;
+use crate::process::read_process_history;
+use crate::projects::{load_project_state, load_projects};
+
+/// Sample failure messages kept per kind.
+const MAX_EXAMPLES: usize = 3;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryStats {
+    /// Story runs across builds; a story built twice counts twice.
+    pub stories: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub first_attempt_passes: usize,
+    /// Agent runs beyond each story's first.
+    pub retries: u64,
+    pub first_attempt_pass_rate: Option<f64>,
+    pub average_retries: Option<f64>,
+}
+
+impl StoryStats {
+    fn add(&mut self, status: &str, attempts: u32) {
+        self.stories += 1;
+        match status {
+            "passed" => {
+                self.passed += 1;
+                if attempts <= 1 {
+                    self.first_attempt_passes += 1;
+                }
+            }
+            "failed" => self.failed += 1,
+            _ => {}
+        }
+        self.retries += u64::from(attempts.saturating_sub(1));
+    }
+
+    fn with_rates(mut self) -> Self {
+        if self.stories > 0 {
+            self.first_attempt_pass_rate = Some(self.first_attempt_passes as f64 / self.stories as f64);
+            self.average_retries = Some(self.retries as f64 / self.stories as f64);
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentInsights {
+    pub agent_id: String,
+    pub builds: usize,
+    #[serde(flatten)]
+    pub stories: StoryStats,
+    /// Build processes in the process history.
+    pub processes: usize,
+    pub process_success_rate: Option<f64>,
+    pub average_process_ms: Option<u64>,
+    /// Failed attempts by failure kind.
+    pub failures: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureReason {
+    /// See `StoryFailure::kind`.
+    pub kind: String,
+    /// Failed attempts of this kind, retried or not.
+    pub attempts: usize,
+    /// Stories whose last failure was of this kind.
+    pub stories: usize,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInsights {
+    pub project_id: String,
+    pub project_name: String,
+    pub builds: usize,
+    #[serde(flatten)]
+    pub stories: StoryStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInsights {
+    pub generated_at: String,
+    pub builds: usize,
+    #[serde(flatten)]
+    pub stories: StoryStats,
+    /// Most stories first.
+    pub agents: Vec<AgentInsights>,
+    /// Most failed attempts first.
+    pub failure_reasons: Vec<FailureReason>,
+    /// Projects with at least one build, most stories first.
+    pub projects: Vec<ProjectInsights>,
+}
+
+fn agent_entry<'a>(agents: &'a mut HashMap<String, AgentInsights>, agent_id: &str) -> &'a mut AgentInsights {
+    agents.entry(agent_id.to_string()).or_insert_with(|| AgentInsights {
+        agent_id: agent_id.to_string(),
+        ..Default::default()
+    })
+}
+
+fn reason_entry<'a>(reasons: &'a mut HashMap<String, FailureReason>, kind: &str) -> &'a mut FailureReason {
+    reasons.entry(kind.to_string()).or_insert_with(|| FailureReason {
+        kind: kind.to_string(),
+        ..Default::default()
+    })
+}
+
+/// Aggregates build outcomes, retries, and failure reasons across every
+/// project, overall and per agent.
+#[tauri::command]
+pub fn get_build_insights(app: AppHandle) -> Result<BuildInsights, String> {
+    let mut builds = 0;
+    let mut overall = StoryStats::default();
+    let mut agents: HashMap<String, AgentInsights> = HashMap::new();
+    let mut reasons: HashMap<String, FailureReason> = HashMap::new();
+    let mut projects = Vec::new();
+
+    for project in load_projects(app.clone())? {
+        let reports = read_build_reports(&project.path);
+        let mut stats = StoryStats::default();
+        for report in &reports {
+            let agent = agent_entry(&mut agents, &report.agent_id);
+            agent.builds += 1;
+            for story in &report.stories {
+                stats.add(&story.status, story.attempts);
+                overall.add(&story.status, story.attempts);
+                agent.stories.add(&story.status, story.attempts);
+            }
+        }
+
+        for timing in read_timings(&project.path).unwrap_or_default() {
+            if timing.outcome == "passed" || timing.outcome == "canceled" {
+                continue;
+            }
+            *agent_entry(&mut agents, &timing.agent_id)
+                .failures
+                .entry(timing.outcome.clone())
+                .or_insert(0) += 1;
+            reason_entry(&mut reasons, &timing.outcome).attempts += 1;
+        }
+
+        let failures = load_project_state(project.path.clone())
+            .ok()
+            .flatten()
+            .and_then(|state| state.story_failures)
+            .unwrap_or_default();
+        for failure in failures.into_values() {
+            let reason = reason_entry(&mut reasons, &failure.kind);
+            reason.stories += 1;
+            if reason.examples.len() < MAX_EXAMPLES {
+                reason.examples.push(format!("{}: {}", project.name, failure.message));
+            }
+        }
+
+        if !reports.is_empty() {
+            builds += reports.len();
+            projects.push(ProjectInsights {
+                project_id: project.id,
+                project_name: project.name,
+                builds: reports.len(),
+                stories: stats.with_rates(),
+            });
+        }
+    }
+
+    let app_data_dir = app_data_dir(&app)?;
+    let history = read_process_history(&app_data_dir)?;
+    let mut process_totals: HashMap<String, (usize, usize, i64)> = HashMap::new();
+    for entry in history.entries.iter().filter(|e| e.process_type == "build") {
+        let Some(agent_id) = entry.agent_id.as_deref() else {
+            continue;
+        };
+        let totals = process_totals.entry(agent_id.to_string()).or_insert((0, 0, 0));
+        totals.0 += 1;
+        totals.1 += usize::from(entry.success);
+        totals.2 += entry.duration_ms.max(0);
+    }
+    for (agent_id, (count, succeeded, duration_ms)) in process_totals {
+        let agent = agent_entry(&mut agents, &agent_id);
+        agent.processes = count;
+        agent.process_success_rate = Some(succeeded as f64 / count as f64);
+        agent.average_process_ms = Some((duration_ms / count as i64) as u64);
+    }
+
+    let mut agents: Vec<AgentInsights> = agents
+        .into_values()
+        .map(|mut agent| {
+            agent.stories = agent.stories.with_rates();
+            agent
+        })
+        .collect();
+    agents.sort_by(|a, b| {
+        b.stories
+            .stories
+            .cmp(&a.stories.stories)
+            .then_with(|| b.processes.cmp(&a.processes))
+            .then_with(|| a.agent_id.cmp(&b.agent_id))
+    });
+    let mut failure_reasons: Vec<FailureReason> = reasons.into_values().collect();
+    failure_reasons.sort_by(|a, b| {
+        b.attempts
+            .cmp(&a.attempts)
+            .then_with(|| b.stories.cmp(&a.stories))
+            .then_with(|| a.kind.cmp(&b.kind))
+    });
+    projects.sort_by_key(|p| std::cmp::Reverse(p.stories.stories));
+
+    Ok(BuildInsights {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        builds,
+        stories: overall.with_rates(),
+        agents,
+        failure_reasons,
+        projects,
+    })
+}
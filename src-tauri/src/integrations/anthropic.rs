@@ -0,0 +1,221 @@
+//! Anthropic Admin API usage and cost reports.
+//!
+//! Local Claude Code session files only cover this machine and carry no
+//! prices. Teams using Claude through API keys can store an Admin API key as
+//! `anthropic.adminKey` to read the organization's usage by model and
+//! workspace, and what it was billed, from Anthropic directly.
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::secrets;
+
+const API_URL: &str = "https://api.anthropic.com/v1/organizations";
+const API_VERSION: &str = "2023-06-01";
+
+/// Secrets store key for the Anthropic Admin API key.
+const ADMIN_KEY_SECRET: &str = "anthropic.adminKey";
+
+/// Usage of one model in one workspace on one day.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnthropicUsageBucket {
+    /// RFC 3339 start of the day.
+    pub starting_at: String,
+    pub model: Option<String>,
+    /// `None` for the default workspace.
+    pub workspace_id: Option<String>,
+    pub input_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub output_tokens: i64,
+}
+
+/// Billed cost of one workspace on one day.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnthropicCostBucket {
+    pub starting_at: String,
+    pub workspace_id: Option<String>,
+    /// USD.
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnthropicUsageSummary {
+    /// Oldest first.
+    pub usage: Vec<AnthropicUsageBucket>,
+    /// Oldest first.
+    pub costs: Vec<AnthropicCostBucket>,
+    pub total_input_tokens: i64,
+    pub total_output_tokens: i64,
+    pub total_cache_creation_tokens: i64,
+    pub total_cache_read_tokens: i64,
+    pub total_tokens: i64,
+    /// USD, as billed.
+    pub total_cost: f64,
+}
+
+fn get_admin_key(app: &AppHandle) -> Result<String, String> {
+    secrets::get_secret(app, ADMIN_KEY_SECRET)?
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| "No Anthropic Admin API key configured. Add one in Settings.".to_string())
+}
+
+/// Fetches every page of a report and returns its buckets.
+async fn fetch_report(admin_key: &str, report: &str, query: &[(&str, String)]) -> Result<Vec<Value>, String> {
+    let client = reqwest::Client::new();
+    let mut buckets = Vec::new();
+    let mut page: Option<String> = None;
+    loop {
+        let mut query = query.to_vec();
+        if let Some(page) = &page {
+            query.push(("page", page.clone()));
+        }
+
+        let response = client
+            .get(format!("{}/{}", API_URL, report))
+            .header("x-api-key", admin_key)
+            .header("anthropic-version", API_VERSION)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Anthropic: {}", e))?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Anthropic response: {}", e))?;
+
+        if let Some(message) = body.pointer("/error/message").and_then(|m| m.as_str()) {
+            return Err(format!("Anthropic API error: {}", message));
+        }
+        if !status.is_success() {
+            return Err(format!("Anthropic API error: HTTP {}", status));
+        }
+
+        if let Some(data) = body.get("data").and_then(|d| d.as_array()) {
+            buckets.extend(data.iter().cloned());
+        }
+
+        page = body.get("next_page").and_then(|v| v.as_str()).map(String::from);
+        let has_more = body.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false);
+        if !has_more || page.is_none() {
+            break;
+        }
+    }
+    Ok(buckets)
+}
+
+/// Each result of each bucket, with the bucket's start.
+fn results(buckets: &[Value]) -> impl Iterator<Item = (String, &Value)> {
+    buckets.iter().flat_map(|bucket| {
+        let starting_at = bucket
+            .get("starting_at")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        bucket
+            .get("results")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .map(move |result| (starting_at.clone(), result))
+    })
+}
+
+fn count(value: &Value, pointer: &str) -> i64 {
+    value.pointer(pointer).and_then(|v| v.as_i64()).unwrap_or_default()
+}
+
+fn string(value: &Value, field: &str) -> Option<String> {
+    value.get(field).and_then(|v| v.as_str()).map(String::from)
+}
+
+/// Daily organization usage by model and workspace, and billed cost by
+/// workspace, since `since_timestamp` (Unix milliseconds) or over the last
+/// 30 days. With `workspace_id`, only that workspace's.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn anthropic_usage(
+    app: AppHandle,
+    since_timestamp: Option<i64>,
+    workspace_id: Option<String>,
+) -> Result<AnthropicUsageSummary, String> {
+    let admin_key = get_admin_key(&app)?;
+    let starting_at = since_timestamp
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .unwrap_or_else(|| chrono::Utc::now() - chrono::Duration::days(30))
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let mut usage_query = vec![
+        ("starting_at", starting_at.clone()),
+        ("bucket_width", "1d".to_string()),
+        ("group_by[]", "model".to_string()),
+        ("group_by[]", "workspace_id".to_string()),
+        ("limit", "31".to_string()),
+    ];
+    let mut cost_query = vec![
+        ("starting_at", starting_at),
+        ("group_by[]", "workspace_id".to_string()),
+        ("limit", "31".to_string()),
+    ];
+    if let Some(id) = &workspace_id {
+        usage_query.push(("workspace_ids[]", id.clone()));
+        cost_query.push(("workspace_ids[]", id.clone()));
+    }
+
+    let (usage, costs) = tokio::join!(
+        fetch_report(&admin_key, "usage_report/messages", &usage_query),
+        fetch_report(&admin_key, "cost_report", &cost_query),
+    );
+    let (usage, costs) = (usage?, costs?);
+
+    let mut summary = AnthropicUsageSummary::default();
+    for (starting_at, result) in results(&usage) {
+        let bucket = AnthropicUsageBucket {
+            starting_at,
+            model: string(result, "model"),
+            workspace_id: string(result, "workspace_id"),
+            input_tokens: count(result, "/uncached_input_tokens"),
+            cache_creation_tokens: count(result, "/cache_creation/ephemeral_5m_input_tokens")
+                + count(result, "/cache_creation/ephemeral_1h_input_tokens"),
+            cache_read_tokens: count(result, "/cache_read_input_tokens"),
+            output_tokens: count(result, "/output_tokens"),
+        };
+        summary.total_input_tokens += bucket.input_tokens;
+        summary.total_output_tokens += bucket.output_tokens;
+        summary.total_cache_creation_tokens += bucket.cache_creation_tokens;
+        summary.total_cache_read_tokens += bucket.cache_read_tokens;
+        summary.usage.push(bucket);
+    }
+    summary.total_tokens = summary.total_input_tokens
+        + summary.total_output_tokens
+        + summary.total_cache_creation_tokens
+        + summary.total_cache_read_tokens;
+
+    for (starting_at, result) in results(&costs) {
+        // Amounts are decimal strings in cents
+        let cents: f64 = result
+            .get("amount")
+            .and_then(|v| v.as_str().map(String::from).or_else(|| v.as_f64().map(|n| n.to_string())))
+            .and_then(|a| a.parse().ok())
+            .unwrap_or_default();
+        let workspace_id = string(result, "workspace_id");
+        match summary
+            .costs
+            .iter_mut()
+            .find(|c| c.starting_at == starting_at && c.workspace_id == workspace_id)
+        {
+            Some(bucket) => bucket.cost += cents / 100.0,
+            None => summary.costs.push(AnthropicCostBucket {
+                starting_at,
+                workspace_id,
+                cost: cents / 100.0,
+            }),
+        }
+        summary.total_cost += cents / 100.0;
+    }
+    Ok(summary)
+}
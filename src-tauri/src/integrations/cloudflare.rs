@@ -0,0 +1,93 @@
+//! Cloudflare Tunnel integration using the `cloudflared` CLI.
+//!
+//! Quick tunnels need no Cloudflare account: `cloudflared` assigns a random
+//! `*.trycloudflare.com` hostname that forwards to the local port.
+
+use std::process::Command;
+
+use regex::Regex;
+use tauri::AppHandle;
+
+use super::tunnels::{TunnelAuthStatus, TunnelCommand, TunnelProvider, TunnelRequest};
+
+lazy_static::lazy_static! {
+    static ref QUICK_TUNNEL_URL_RE: Regex =
+        Regex::new(r"https://[a-z0-9-]+\.trycloudflare\.com").unwrap();
+}
+
+/// Well-known install locations checked when `cloudflared` isn't on PATH
+/// (GUI apps on macOS often launch without the user's shell PATH).
+const FALLBACK_PATHS: &[&str] = &[
+    "/opt/homebrew/bin/cloudflared",
+    "/usr/local/bin/cloudflared",
+    "/usr/bin/cloudflared",
+];
+
+/// Locates the cloudflared binary, preferring PATH over well-known locations.
+fn find_cloudflared() -> Option<String> {
+    if let Ok(output) = Command::new("which").arg("cloudflared").output() {
+        if output.status.success() {
+            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !path.is_empty() {
+                return Some(path);
+            }
+        }
+    }
+
+    FALLBACK_PATHS
+        .iter()
+        .find(|p| std::path::Path::new(p).exists())
+        .map(|p| p.to_string())
+}
+
+/// Cloudflare quick-tunnel provider. No account or authentication is required.
+pub struct CloudflareProvider;
+
+impl TunnelProvider for CloudflareProvider {
+    fn id(&self) -> &'static str {
+        "cloudflare"
+    }
+
+    fn name(&self) -> &'static str {
+        "Cloudflare Tunnel"
+    }
+
+    fn url_pattern(&self) -> &'static Regex {
+        &QUICK_TUNNEL_URL_RE
+    }
+
+    fn detect(&self, _app: &AppHandle) -> Option<String> {
+        find_cloudflared()
+    }
+
+    fn auth_status(&self, _app: &AppHandle) -> Result<TunnelAuthStatus, String> {
+        Ok(TunnelAuthStatus {
+            required: false,
+            authenticated: true,
+        })
+    }
+
+    fn build_command(
+        &self,
+        _app: &AppHandle,
+        request: &TunnelRequest,
+    ) -> Result<TunnelCommand, String> {
+        let executable = match &request.custom_cli_path {
+            Some(path) if !path.is_empty() => path.clone(),
+            _ => find_cloudflared().ok_or_else(|| {
+                "cloudflared not found. Install it with `brew install cloudflared` or from https://developers.cloudflare.com/cloudflare-one/connections/connect-networks/downloads/".to_string()
+            })?,
+        };
+
+        Ok(TunnelCommand {
+            executable,
+            args: vec![
+                "tunnel".to_string(),
+                "--no-autoupdate".to_string(),
+                "--url".to_string(),
+                format!("http://localhost:{}", request.port),
+            ],
+            env: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,242 @@
+//! Preview deployments via the Vercel and Netlify CLIs.
+//!
+//! Deploys run as tracked processes (visible to `kill_agent` and streamed as
+//! `agent-output`). The preview URL is parsed from CLI output, stored in the
+//! project's `state.json`, and announced with a `deploy-complete` event.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::event_bus;
+use crate::models::{AgentExitEvent, DeployPreview, OutputMode, TerminationReason};
+use crate::process::{exit_signal, natural_termination, spawn_output_batcher, wait_for_output, PROCESSES};
+use crate::projects::update_project_state;
+
+lazy_static::lazy_static! {
+    static ref VERCEL_URL_RE: Regex = Regex::new(r"https://[a-zA-Z0-9.-]+\.vercel\.app").unwrap();
+    static ref NETLIFY_URL_RE: Regex = Regex::new(r"https://[a-zA-Z0-9.-]+\.netlify\.app").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployStartedResult {
+    pub process_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployCompleteEvent {
+    pub project_path: String,
+    pub provider: String,
+    pub process_id: String,
+    pub success: bool,
+    pub url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Returns the CLI name, deploy arguments, and URL pattern for a provider.
+fn provider_spec(provider: &str) -> Result<(&'static str, Vec<&'static str>, &'static Regex), String> {
+    match provider {
+        "vercel" => Ok(("vercel", vec!["deploy", "--yes"], &VERCEL_URL_RE)),
+        "netlify" => Ok(("netlify", vec!["deploy"], &NETLIFY_URL_RE)),
+        _ => Err(format!("Unknown deploy provider: {}", provider)),
+    }
+}
+
+fn is_on_path(cli: &str) -> bool {
+    let finder = if cfg!(windows) { "where" } else { "which" };
+    Command::new(finder)
+        .arg(cli)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn record_preview(project_path: &str, provider: &str, url: &str) -> Result<(), String> {
+    let deployed_at = chrono::Utc::now().to_rfc3339();
+    update_project_state(project_path, |state| {
+        state.deploy_previews.get_or_insert_with(HashMap::new).insert(
+            provider.to_string(),
+            DeployPreview {
+                provider: provider.to_string(),
+                url: url.to_string(),
+                deployed_at: deployed_at.clone(),
+            },
+        );
+    })
+}
+
+/// Deploys a preview of the project with the given provider ("vercel" or "netlify").
+/// Returns immediately with the tracked process ID; completion is reported via
+/// the `deploy-complete` event.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn deploy_preview(
+    app: AppHandle,
+    project_path: String,
+    provider: String,
+) -> Result<DeployStartedResult, String> {
+    let (cli, deploy_args, url_pattern) = provider_spec(&provider)?;
+    let process_id = Uuid::new_v4().to_string();
+
+    let working_directory = project_path.clone();
+    let mut child = tokio::task::spawn_blocking(move || {
+        // Prefer a globally installed CLI, otherwise run it through npx
+        let mut cmd = if is_on_path(cli) {
+            Command::new(cli)
+        } else {
+            let mut npx = Command::new("npx");
+            npx.args(["--yes", cli]);
+            npx
+        };
+        cmd.args(&deploy_args)
+            .current_dir(&working_directory)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        cmd.spawn()
+            .map_err(|e| format!("Failed to spawn {}: {}", cli, e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let url: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    let output = spawn_output_batcher(app.clone(), process_id.clone(), OutputMode::Lines);
+    let mut readers = Vec::new();
+    for (stream_type, reader) in [
+        ("stdout", child.stdout.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>)),
+        ("stderr", child.stderr.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>)),
+    ] {
+        let Some(reader) = reader else { continue };
+        let output = output.clone();
+        let url = url.clone();
+        readers.push(thread::spawn(move || {
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                // The last matching URL is the deployment itself; earlier
+                // matches may be aliases or inspection links.
+                if let Some(m) = url_pattern.find(&line) {
+                    if let Ok(mut url) = url.lock() {
+                        *url = Some(m.as_str().to_string());
+                    }
+                }
+                let _ = output.send((stream_type, line));
+            }
+        }));
+    }
+    drop(output);
+
+    {
+        let mut processes = PROCESSES.lock().map_err(|e| format!("Lock error: {}", e))?;
+        processes.insert(process_id.clone(), child);
+    }
+
+    let result = DeployStartedResult {
+        process_id: process_id.clone(),
+    };
+
+    let pid = process_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let exit = tokio::task::spawn_blocking(move || {
+            let exit = loop {
+                let status = {
+                    let mut processes = match PROCESSES.lock() {
+                        Ok(p) => p,
+                        Err(_) => break None,
+                    };
+                    let Some(child) = processes.get_mut(&pid) else {
+                        // Removed by kill_agent
+                        break None;
+                    };
+                    match child.try_wait() {
+                        Ok(Some(status)) => {
+                            processes.remove(&pid);
+                            Some(status)
+                        }
+                        Ok(None) => None,
+                        Err(_) => {
+                            processes.remove(&pid);
+                            break None;
+                        }
+                    }
+                };
+                if let Some(status) = status {
+                    break Some(status);
+                }
+                thread::sleep(Duration::from_millis(250));
+            };
+
+            // Let the readers drain so the URL is captured before reporting
+            for reader in readers {
+                let _ = reader.join();
+            }
+            wait_for_output(&pid);
+            exit
+        })
+        .await
+        .ok()
+        .flatten();
+
+        let success = exit.map(|s| s.success()).unwrap_or(false);
+        let url = url.lock().ok().and_then(|u| u.clone());
+
+        if exit.is_some() {
+            let _ = event_bus::emit(
+                &app,
+                "agent-exit",
+                AgentExitEvent {
+                    process_id: process_id.clone(),
+                    exit_code: exit.and_then(|s| s.code()),
+                    success,
+                    signal: exit.as_ref().and_then(exit_signal),
+                    termination_reason: exit.as_ref().map_or(TerminationReason::Exited, natural_termination),
+                },
+            );
+        }
+
+        let mut error = None;
+        if success {
+            match &url {
+                Some(url) => {
+                    if let Err(e) = record_preview(&project_path, &provider, url) {
+                        error = Some(e);
+                    }
+                }
+                None => error = Some("Deploy finished but no preview URL was found".to_string()),
+            }
+        } else {
+            error = Some(match exit {
+                Some(_) => "Deploy failed".to_string(),
+                None => "Deploy was canceled".to_string(),
+            });
+        }
+
+        let _ = app.emit(
+            "deploy-complete",
+            DeployCompleteEvent {
+                project_path,
+                provider,
+                process_id,
+                success: success && url.is_some(),
+                url,
+                error,
+            },
+        );
+    });
+
+    Ok(result)
+}
@@ -0,0 +1,372 @@
+//! Docker / dev-container runner.
+//!
+//! Runs agents and verification commands inside a container defined by the
+//! project, either via `.devcontainer/devcontainer.json` or an image configured
+//! in `.ideate/docker.json`. The worktree is bind-mounted into the container.
+//! Like hooks, the container definition is only read from those files, which
+//! are edited by hand rather than from the app.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::process::prepare_working_directory;
+use crate::utils::{get_ideate_dir, parse_lenient_json};
+
+const DEFAULT_WORKSPACE_FOLDER: &str = "/workspace";
+
+/// Contents of `.ideate/docker.json`. Used when the project has no devcontainer.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerConfig {
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub dockerfile: Option<String>,
+    #[serde(default)]
+    pub workspace_folder: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// Resolved container definition for a project.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerSpec {
+    /// "devcontainer" or "config"
+    pub source: String,
+    pub image: Option<String>,
+    pub dockerfile: Option<String>,
+    pub build_context: Option<String>,
+    pub workspace_folder: String,
+    pub env: HashMap<String, String>,
+    pub post_create_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerStatus {
+    pub installed: bool,
+    pub daemon_running: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerStatus {
+    pub name: String,
+    /// "missing", "running", or the Docker state (e.g. "exited")
+    pub state: String,
+    pub image: Option<String>,
+}
+
+fn run_docker(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run docker: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "docker {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Stable identifier derived from the project path, used for image tags and container names.
+fn project_slug(project_path: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    project_path.hash(&mut hasher);
+    format!("ideate-{:x}", hasher.finish())
+}
+
+fn container_name(project_path: &str) -> String {
+    project_slug(project_path)
+}
+
+fn find_devcontainer(project_path: &str) -> Option<PathBuf> {
+    let root = Path::new(project_path);
+    [
+        root.join(".devcontainer").join("devcontainer.json"),
+        root.join(".devcontainer.json"),
+    ]
+    .into_iter()
+    .find(|p| p.exists())
+}
+
+fn load_config(project_path: &str) -> Result<DockerConfig, String> {
+    let path = get_ideate_dir(project_path).join("docker.json");
+
+    if !path.exists() {
+        return Ok(DockerConfig::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read docker.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse docker.json: {}", e))
+}
+
+fn resolve_spec(project_path: &str) -> Result<ContainerSpec, String> {
+    if let Some(path) = find_devcontainer(project_path) {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read devcontainer.json: {}", e))?;
+        let json: Value = parse_lenient_json(&content, "devcontainer.json")?;
+
+        let config_dir = path.parent().unwrap_or(Path::new(project_path));
+        let build = json.get("build");
+        let dockerfile = build
+            .and_then(|b| b.get("dockerfile"))
+            .or_else(|| json.get("dockerFile"))
+            .and_then(|v| v.as_str())
+            .map(|f| config_dir.join(f).to_string_lossy().to_string());
+        let build_context = dockerfile.as_ref().map(|_| {
+            let context = build
+                .and_then(|b| b.get("context"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(".");
+            config_dir.join(context).to_string_lossy().to_string()
+        });
+
+        let env = json
+            .get("containerEnv")
+            .and_then(|v| v.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let post_create_command = match json.get("postCreateCommand") {
+            Some(Value::String(cmd)) => Some(cmd.clone()),
+            Some(Value::Array(parts)) => Some(
+                parts
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        };
+
+        return Ok(ContainerSpec {
+            source: "devcontainer".to_string(),
+            image: json.get("image").and_then(|v| v.as_str()).map(String::from),
+            dockerfile,
+            build_context,
+            workspace_folder: json
+                .get("workspaceFolder")
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_WORKSPACE_FOLDER)
+                .to_string(),
+            env,
+            post_create_command,
+        });
+    }
+
+    let config = load_config(project_path)?;
+    if config.image.is_none() && config.dockerfile.is_none() {
+        return Err("No devcontainer.json or Docker image configured for this project".to_string());
+    }
+
+    Ok(ContainerSpec {
+        source: "config".to_string(),
+        image: config.image,
+        build_context: config.dockerfile.as_ref().map(|_| project_path.to_string()),
+        dockerfile: config
+            .dockerfile
+            .map(|f| Path::new(project_path).join(f).to_string_lossy().to_string()),
+        workspace_folder: config
+            .workspace_folder
+            .unwrap_or_else(|| DEFAULT_WORKSPACE_FOLDER.to_string()),
+        env: config.env,
+        post_create_command: None,
+    })
+}
+
+/// Builds the image from a Dockerfile or pulls the configured image. Returns the image reference.
+fn prepare_image(project_path: &str, spec: &ContainerSpec) -> Result<String, String> {
+    match (&spec.dockerfile, &spec.build_context) {
+        (Some(dockerfile), Some(context)) => {
+            let tag = format!("{}:latest", project_slug(project_path));
+            run_docker(&["build", "-t", &tag, "-f", dockerfile, context])?;
+            Ok(tag)
+        }
+        _ => {
+            let image = spec.image.clone().ok_or("No image configured")?;
+            run_docker(&["pull", &image])?;
+            Ok(image)
+        }
+    }
+}
+
+fn inspect_container(name: &str) -> Option<(String, String)> {
+    run_docker(&["inspect", "-f", "{{.State.Status}}|{{.Config.Image}}", name])
+        .ok()
+        .and_then(|out| {
+            let (state, image) = out.split_once('|')?;
+            Some((state.to_string(), image.to_string()))
+        })
+}
+
+/// Reports whether Docker is installed and the daemon is reachable.
+#[tauri::command]
+pub async fn docker_status() -> Result<DockerStatus, String> {
+    tokio::task::spawn_blocking(|| {
+        let version = run_docker(&["--version"]).ok();
+        let daemon_running = version.is_some() && run_docker(&["info", "--format", "{{.ServerVersion}}"]).is_ok();
+        DockerStatus {
+            installed: version.is_some(),
+            daemon_running,
+            version,
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Loads `.ideate/docker.json`.
+#[tauri::command(rename_all = "camelCase")]
+pub fn load_docker_config(project_path: String) -> Result<DockerConfig, String> {
+    load_config(&project_path)
+}
+
+/// Returns the resolved container definition for a project.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_container_spec(project_path: String) -> Result<ContainerSpec, String> {
+    resolve_spec(&project_path)
+}
+
+/// Builds (or pulls) the project's image. Returns the image reference.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn docker_build_image(app: AppHandle, project_path: String) -> Result<String, String> {
+    prepare_working_directory(&app, &project_path, false)?;
+    tokio::task::spawn_blocking(move || {
+        let spec = resolve_spec(&project_path)?;
+        prepare_image(&project_path, &spec)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Starts the project's container with the worktree mounted at the workspace folder.
+/// An existing container is reused (and started if stopped). The project and
+/// worktree must be inside a registered project.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn docker_start_container(
+    app: AppHandle,
+    project_path: String,
+    worktree_path: Option<String>,
+) -> Result<ContainerStatus, String> {
+    prepare_working_directory(&app, &project_path, false)?;
+    if let Some(worktree_path) = &worktree_path {
+        prepare_working_directory(&app, worktree_path, false)?;
+    }
+    tokio::task::spawn_blocking(move || {
+        let name = container_name(&project_path);
+
+        if let Some((state, image)) = inspect_container(&name) {
+            if state != "running" {
+                run_docker(&["start", &name])?;
+            }
+            return Ok(ContainerStatus {
+                name,
+                state: "running".to_string(),
+                image: Some(image),
+            });
+        }
+
+        let spec = resolve_spec(&project_path)?;
+        let image = prepare_image(&project_path, &spec)?;
+        let mount_source = worktree_path.unwrap_or_else(|| project_path.clone());
+        let mount = format!("{}:{}", mount_source, spec.workspace_folder);
+        let label = format!("ideate.project={}", project_path);
+
+        let mut args: Vec<String> = vec![
+            "run".into(),
+            "-d".into(),
+            "--name".into(),
+            name.clone(),
+            "--label".into(),
+            label,
+            "-v".into(),
+            mount,
+            "-w".into(),
+            spec.workspace_folder.clone(),
+        ];
+        for (key, value) in &spec.env {
+            args.push("-e".into());
+            args.push(format!("{}={}", key, value));
+        }
+        // Keep the container alive so commands can be exec'd into it
+        args.extend([image.clone(), "sleep".into(), "infinity".into()]);
+
+        let args_ref: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_docker(&args_ref)?;
+
+        if let Some(cmd) = &spec.post_create_command {
+            run_docker(&["exec", &name, "sh", "-c", cmd])?;
+        }
+
+        Ok(ContainerStatus {
+            name,
+            state: "running".to_string(),
+            image: Some(image),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stops the project's container, optionally removing it.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn docker_stop_container(project_path: String, remove: Option<bool>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let name = container_name(&project_path);
+        if inspect_container(&name).is_none() {
+            return Ok(());
+        }
+
+        run_docker(&["stop", &name])?;
+        if remove.unwrap_or(false) {
+            run_docker(&["rm", &name])?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Returns the state of the project's container.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn docker_container_status(project_path: String) -> Result<ContainerStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let name = container_name(&project_path);
+        Ok(match inspect_container(&name) {
+            Some((state, image)) => ContainerStatus {
+                name,
+                state,
+                image: Some(image),
+            },
+            None => ContainerStatus {
+                name,
+                state: "missing".to_string(),
+                image: None,
+            },
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,364 @@
+//! GitHub Issues sync for PRD stories.
+//!
+//! Stories can be pushed to a repository as issues, and issue state can be
+//! pulled back into story status. The story-to-issue mapping is stored in
+//! `.ideate/github-sync.json` so repeated syncs update rather than duplicate.
+//! Each link records its repository, so pushing to a different repository
+//! creates issues there instead of editing same-numbered issues in it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::git_binary;
+use crate::models::Story;
+use crate::projects::{load_prd, save_prd};
+use crate::secrets;
+use crate::utils::get_ideate_dir;
+
+const API_URL: &str = "https://api.github.com";
+
+/// Secrets store key for a GitHub personal access token.
+const TOKEN_SECRET: &str = "github.token";
+
+/// Label added to every issue created by Ideate.
+const ISSUE_LABEL: &str = "ideate";
+
+/// Link between a story and its GitHub issue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryIssueLink {
+    /// "owner/repo" the issue is in. Links saved before this was recorded
+    /// are taken to be in the state's `repo`.
+    #[serde(default)]
+    pub repo: String,
+    pub issue_number: u64,
+    pub issue_url: String,
+    pub last_synced_at: String,
+}
+
+/// Contents of `.ideate/github-sync.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubSyncState {
+    #[serde(default)]
+    pub repo: Option<String>,
+    #[serde(default)]
+    pub stories: HashMap<String, StoryIssueLink>,
+}
+
+/// Summary of a sync run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubSyncResult {
+    pub repo: String,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubIssue {
+    number: u64,
+    html_url: String,
+    state: String,
+}
+
+fn get_sync_file_path(project_path: &str) -> std::path::PathBuf {
+    get_ideate_dir(project_path).join("github-sync.json")
+}
+
+fn load_sync_state(project_path: &str) -> Result<GitHubSyncState, String> {
+    let path = get_sync_file_path(project_path);
+
+    if !path.exists() {
+        return Ok(GitHubSyncState::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read github-sync.json: {}", e))?;
+
+    let mut state: GitHubSyncState =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse github-sync.json: {}", e))?;
+    if let Some(repo) = &state.repo {
+        for link in state.stories.values_mut().filter(|l| l.repo.is_empty()) {
+            link.repo = repo.clone();
+        }
+    }
+    Ok(state)
+}
+
+fn save_sync_state(project_path: &str, state: &GitHubSyncState) -> Result<(), String> {
+    let ideate_dir = get_ideate_dir(project_path);
+
+    if !ideate_dir.exists() {
+        fs::create_dir_all(&ideate_dir)
+            .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize GitHub sync state: {}", e))?;
+
+    fs::write(get_sync_file_path(project_path), json)
+        .map_err(|e| format!("Failed to write github-sync.json: {}", e))
+}
+
+/// Resolves a GitHub token from the secrets store, falling back to the `gh` CLI.
+pub(crate) fn resolve_token(app: &AppHandle) -> Result<String, String> {
+    if let Some(token) = secrets::get_secret(app, TOKEN_SECRET)?.filter(|t| !t.is_empty()) {
+        return Ok(token);
+    }
+
+    if let Ok(output) = Command::new("gh").args(["auth", "token"]).output() {
+        if output.status.success() {
+            let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !token.is_empty() {
+                return Ok(token);
+            }
+        }
+    }
+
+    Err("No GitHub token found. Add a token in Settings or run `gh auth login`.".to_string())
+}
+
+/// Parses "owner/repo" from a GitHub remote URL (HTTPS or SSH).
+fn parse_repo_from_remote(remote: &str) -> Option<String> {
+    let remote = remote.trim().trim_end_matches(".git");
+    let path = remote
+        .strip_prefix("git@github.com:")
+        .or_else(|| remote.strip_prefix("https://github.com/"))
+        .or_else(|| remote.strip_prefix("ssh://git@github.com/"))?;
+
+    let mut parts = path.split('/');
+    match (parts.next(), parts.next()) {
+        (Some(owner), Some(repo)) if !owner.is_empty() && !repo.is_empty() => {
+            Some(format!("{}/{}", owner, repo))
+        }
+        _ => None,
+    }
+}
+
+/// Determines the target repository: explicit argument, saved mapping, then `origin` remote.
+fn resolve_repo(project_path: &str, repo: Option<String>, state: &GitHubSyncState) -> Result<String, String> {
+    if let Some(repo) = repo.filter(|r| !r.is_empty()) {
+        return Ok(repo);
+    }
+
+    if let Some(repo) = &state.repo {
+        return Ok(repo.clone());
+    }
+
+    let output = git_binary::command()
+        .args(["remote", "get-url", "origin"])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to read git remote: {}", e))?;
+
+    if output.status.success() {
+        if let Some(repo) = parse_repo_from_remote(&String::from_utf8_lossy(&output.stdout)) {
+            return Ok(repo);
+        }
+    }
+
+    Err("Could not determine GitHub repository. Specify it as owner/repo.".to_string())
+}
+
+fn github_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(concat!("Ideate/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Renders a story as a GitHub issue body.
+fn story_issue_body(story: &Story) -> String {
+    let mut body = format!("{}\n\n### Acceptance Criteria\n\n", story.description);
+    for criterion in &story.acceptance_criteria {
+        let checkbox = if story.passes { "[x]" } else { "[ ]" };
+        body.push_str(&format!("- {} {}\n", checkbox, criterion));
+    }
+    if !story.notes.is_empty() {
+        body.push_str(&format!("\n### Notes\n\n{}\n", story.notes));
+    }
+    body.push_str(&format!("\n---\n_Synced from Ideate story {}_\n", story.id));
+    body
+}
+
+/// Pushes PRD stories to GitHub: creates issues for stories not yet linked to
+/// an issue in `repo` and updates title, body, and open/closed state for the
+/// rest. Pushing to another repository replaces the stories' links with ones
+/// to the new issues.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn github_push_stories(
+    app: AppHandle,
+    project_path: String,
+    repo: Option<String>,
+) -> Result<GitHubSyncResult, String> {
+    let prd = load_prd(project_path.clone())?.ok_or("No PRD found for this project")?;
+    let mut state = load_sync_state(&project_path)?;
+    let repo = resolve_repo(&project_path, repo, &state)?;
+    let token = resolve_token(&app)?;
+    let client = github_client()?;
+
+    let mut result = GitHubSyncResult {
+        repo: repo.clone(),
+        created: Vec::new(),
+        updated: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for story in &prd.user_stories {
+        let payload = serde_json::json!({
+            "title": format!("[{}] {}", story.id, story.title),
+            "body": story_issue_body(story),
+            "state": if story.passes { "closed" } else { "open" },
+        });
+
+        let existing = state
+            .stories
+            .get(&story.id)
+            .filter(|l| l.repo == repo)
+            .map(|l| l.issue_number);
+        let request = match existing {
+            Some(number) => client
+                .patch(format!("{}/repos/{}/issues/{}", API_URL, repo, number))
+                .json(&payload),
+            None => {
+                let mut create = payload;
+                create["labels"] = serde_json::json!([ISSUE_LABEL]);
+                client
+                    .post(format!("{}/repos/{}/issues", API_URL, repo))
+                    .json(&create)
+            }
+        };
+
+        let response = match request
+            .bearer_auth(&token)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                result.errors.push(format!("{}: {}", story.id, e));
+                continue;
+            }
+        };
+
+        if !response.status().is_success() {
+            result
+                .errors
+                .push(format!("{}: HTTP {}", story.id, response.status()));
+            continue;
+        }
+
+        match response.json::<GitHubIssue>().await {
+            Ok(issue) => {
+                state.stories.insert(
+                    story.id.clone(),
+                    StoryIssueLink {
+                        repo: repo.clone(),
+                        issue_number: issue.number,
+                        issue_url: issue.html_url,
+                        last_synced_at: chrono::Utc::now().to_rfc3339(),
+                    },
+                );
+                if existing.is_some() {
+                    result.updated.push(story.id.clone());
+                } else {
+                    result.created.push(story.id.clone());
+                }
+            }
+            Err(e) => result.errors.push(format!("{}: {}", story.id, e)),
+        }
+    }
+
+    state.repo = Some(repo);
+    save_sync_state(&project_path, &state)?;
+
+    Ok(result)
+}
+
+/// Pulls issue state from GitHub into story status. Only links to issues in
+/// the repository last pushed to are followed.
+/// Closed issues mark their story as passing; reopened issues reset it to pending.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn github_pull_story_status(
+    app: AppHandle,
+    project_path: String,
+) -> Result<GitHubSyncResult, String> {
+    let mut prd = load_prd(project_path.clone())?.ok_or("No PRD found for this project")?;
+    let mut state = load_sync_state(&project_path)?;
+    let repo = resolve_repo(&project_path, None, &state)?;
+    let token = resolve_token(&app)?;
+    let client = github_client()?;
+
+    let mut result = GitHubSyncResult {
+        repo: repo.clone(),
+        created: Vec::new(),
+        updated: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for story in prd.user_stories.iter_mut() {
+        let Some(link) = state.stories.get_mut(&story.id).filter(|l| l.repo == repo) else {
+            continue;
+        };
+
+        let response = match client
+            .get(format!("{}/repos/{}/issues/{}", API_URL, repo, link.issue_number))
+            .bearer_auth(&token)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                result.errors.push(format!("{}: {}", story.id, e));
+                continue;
+            }
+        };
+
+        if !response.status().is_success() {
+            result
+                .errors
+                .push(format!("{}: HTTP {}", story.id, response.status()));
+            continue;
+        }
+
+        let issue: GitHubIssue = match response.json().await {
+            Ok(issue) => issue,
+            Err(e) => {
+                result.errors.push(format!("{}: {}", story.id, e));
+                continue;
+            }
+        };
+
+        let closed = issue.state == "closed";
+        if closed != story.passes {
+            story.passes = closed;
+            story.status = Some(if closed { "complete" } else { "pending" }.to_string());
+            result.updated.push(story.id.clone());
+        }
+        link.last_synced_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    if !result.updated.is_empty() {
+        let path = project_path.clone();
+        tokio::task::spawn_blocking(move || save_prd(path, prd))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+    }
+    save_sync_state(&project_path, &state)?;
+
+    Ok(result)
+}
+
+/// Loads the story-to-issue mapping for a project.
+#[tauri::command(rename_all = "camelCase")]
+pub fn load_github_sync_state(project_path: String) -> Result<GitHubSyncState, String> {
+    load_sync_state(&project_path)
+}
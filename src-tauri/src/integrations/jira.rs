@@ -0,0 +1,242 @@
+//! Jira import.
+//!
+//! Maps issues returned by a JQL query to PRD stories so existing backlogs can
+//! seed a build. Imported issue keys are recorded in `.ideate/jira-sync.json`
+//! so re-running an import only adds new issues.
+
+use std::collections::HashMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{push_imported_story, split_acceptance_criteria};
+use crate::models::Prd;
+use crate::projects::{load_prd, save_prd};
+use crate::utils::get_ideate_dir;
+
+const PAGE_SIZE: u32 = 100;
+
+/// Connection details for a Jira Cloud or Server instance.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraCredentials {
+    /// Base URL, e.g. `https://acme.atlassian.net`.
+    pub base_url: String,
+    pub email: String,
+    pub api_token: String,
+    /// Field holding acceptance criteria (e.g. `customfield_10035`).
+    /// When unset, checklist items in the description are used instead.
+    #[serde(default)]
+    pub acceptance_criteria_field: Option<String>,
+}
+
+/// Contents of `.ideate/jira-sync.json`: story id → Jira issue key.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraSyncState {
+    #[serde(default)]
+    pub stories: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraImportResult {
+    pub imported: Vec<String>,
+    pub skipped: usize,
+}
+
+fn get_sync_file_path(project_path: &str) -> std::path::PathBuf {
+    get_ideate_dir(project_path).join("jira-sync.json")
+}
+
+fn load_sync_state(project_path: &str) -> Result<JiraSyncState, String> {
+    let path = get_sync_file_path(project_path);
+
+    if !path.exists() {
+        return Ok(JiraSyncState::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read jira-sync.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse jira-sync.json: {}", e))
+}
+
+fn save_sync_state(project_path: &str, state: &JiraSyncState) -> Result<(), String> {
+    let ideate_dir = get_ideate_dir(project_path);
+
+    if !ideate_dir.exists() {
+        fs::create_dir_all(&ideate_dir)
+            .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize Jira sync state: {}", e))?;
+
+    fs::write(get_sync_file_path(project_path), json)
+        .map_err(|e| format!("Failed to write jira-sync.json: {}", e))
+}
+
+/// Reads acceptance criteria from a custom field. Accepts plain text (one
+/// criterion per line, bullets stripped) or an array of strings.
+fn criteria_from_field(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().or_else(|| v.get("value").and_then(|v| v.as_str())))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Value::String(text) => text
+            .lines()
+            .map(|l| {
+                l.trim()
+                    .trim_start_matches(['*', '-', '#'])
+                    .trim()
+                    .trim_start_matches("[ ]")
+                    .trim_start_matches("[x]")
+                    .trim()
+                    .to_string()
+            })
+            .filter(|l| !l.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Imports Jira issues matching a JQL query into the PRD as stories.
+/// Summary becomes the title, description the description, and acceptance
+/// criteria are read from the configured field or the description checklist.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_from_jira(
+    project_path: String,
+    jql: String,
+    credentials: JiraCredentials,
+) -> Result<JiraImportResult, String> {
+    let base_url = credentials.base_url.trim_end_matches('/');
+    let client = reqwest::Client::new();
+
+    let mut fields = vec!["summary", "description", "status"];
+    if let Some(field) = &credentials.acceptance_criteria_field {
+        fields.push(field.as_str());
+    }
+
+    let mut issues: Vec<Value> = Vec::new();
+    let mut start_at = 0u32;
+    loop {
+        let response = client
+            .post(format!("{}/rest/api/2/search", base_url))
+            .basic_auth(&credentials.email, Some(&credentials.api_token))
+            .json(&serde_json::json!({
+                "jql": jql,
+                "startAt": start_at,
+                "maxResults": PAGE_SIZE,
+                "fields": fields,
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Jira: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Jira search failed: HTTP {} {}", status, body));
+        }
+
+        let page: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Jira response: {}", e))?;
+
+        let batch = page
+            .get("issues")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let total = page.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+
+        start_at += batch.len() as u32;
+        let done = batch.is_empty() || start_at as u64 >= total;
+        issues.extend(batch);
+
+        if done {
+            break;
+        }
+    }
+
+    let mut prd = load_prd(project_path.clone())?.unwrap_or(Prd {
+        project: None,
+        branch_name: None,
+        description: None,
+        user_stories: Vec::new(),
+    });
+    let mut state = load_sync_state(&project_path)?;
+
+    let mut result = JiraImportResult {
+        imported: Vec::new(),
+        skipped: 0,
+    };
+
+    for issue in issues {
+        let Some(key) = issue.get("key").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if state.stories.values().any(|k| k == key) {
+            result.skipped += 1;
+            continue;
+        }
+
+        let issue_fields = issue.get("fields").cloned().unwrap_or(Value::Null);
+        let title = issue_fields
+            .get("summary")
+            .and_then(|v| v.as_str())
+            .unwrap_or(key)
+            .to_string();
+        let raw_description = issue_fields
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        let (description, acceptance_criteria) = match credentials
+            .acceptance_criteria_field
+            .as_ref()
+            .and_then(|f| issue_fields.get(f))
+        {
+            Some(value) => (raw_description.trim().to_string(), criteria_from_field(value)),
+            None => split_acceptance_criteria(raw_description),
+        };
+
+        let story_id = push_imported_story(
+            &mut prd,
+            title,
+            description,
+            acceptance_criteria,
+            format!("Imported from Jira {}", key),
+        );
+
+        let done = issue_fields
+            .pointer("/status/statusCategory/key")
+            .and_then(|v| v.as_str())
+            == Some("done");
+        if done {
+            if let Some(story) = prd.user_stories.iter_mut().find(|s| s.id == story_id) {
+                story.passes = true;
+                story.status = Some("complete".to_string());
+            }
+        }
+
+        state.stories.insert(story_id.clone(), key.to_string());
+        result.imported.push(story_id);
+    }
+
+    if !result.imported.is_empty() {
+        let path = project_path.clone();
+        tokio::task::spawn_blocking(move || save_prd(path, prd))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+        save_sync_state(&project_path, &state)?;
+    }
+
+    Ok(result)
+}
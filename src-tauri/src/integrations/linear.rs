@@ -0,0 +1,392 @@
+//! Linear integration.
+//!
+//! Imports a Linear project's issues into the PRD as stories and posts
+//! progress comments back to Linear when stories pass. The story-to-issue
+//! mapping is stored in `.ideate/linear-sync.json`.
+
+use std::collections::HashMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use super::{push_imported_story, split_acceptance_criteria};
+use crate::models::Prd;
+use crate::projects::{load_prd, save_prd};
+use crate::secrets;
+use crate::utils::get_ideate_dir;
+
+const API_URL: &str = "https://api.linear.app/graphql";
+
+lazy_static::lazy_static! {
+    /// Serializes progress reports so stories passing together in a parallel
+    /// build don't comment on the same issue twice.
+    static ref REPORTING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// Secrets store key for the Linear API key.
+const API_KEY_SECRET: &str = "linear.apiKey";
+
+/// Link between a story and its Linear issue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearIssueLink {
+    pub issue_id: String,
+    pub identifier: String,
+    pub url: String,
+    /// Set once a "story passed" comment has been posted.
+    #[serde(default)]
+    pub reported_passed: bool,
+}
+
+/// Contents of `.ideate/linear-sync.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearSyncState {
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub stories: HashMap<String, LinearIssueLink>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearProject {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearImportResult {
+    pub imported: Vec<String>,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearReportResult {
+    pub commented: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn get_sync_file_path(project_path: &str) -> std::path::PathBuf {
+    get_ideate_dir(project_path).join("linear-sync.json")
+}
+
+fn load_sync_state(project_path: &str) -> Result<LinearSyncState, String> {
+    let path = get_sync_file_path(project_path);
+
+    if !path.exists() {
+        return Ok(LinearSyncState::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read linear-sync.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse linear-sync.json: {}", e))
+}
+
+fn save_sync_state(project_path: &str, state: &LinearSyncState) -> Result<(), String> {
+    let ideate_dir = get_ideate_dir(project_path);
+
+    if !ideate_dir.exists() {
+        fs::create_dir_all(&ideate_dir)
+            .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize Linear sync state: {}", e))?;
+
+    fs::write(get_sync_file_path(project_path), json)
+        .map_err(|e| format!("Failed to write linear-sync.json: {}", e))
+}
+
+fn get_api_key(app: &AppHandle) -> Result<String, String> {
+    secrets::get_secret(app, API_KEY_SECRET)?
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| "No Linear API key configured. Add one in Settings.".to_string())
+}
+
+/// Executes a GraphQL request against the Linear API and returns the `data` object.
+async fn graphql(api_key: &str, query: &str, variables: Value) -> Result<Value, String> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(API_URL)
+        .header("Authorization", api_key)
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Linear: {}", e))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Linear response: {}", e))?;
+
+    if let Some(message) = body
+        .pointer("/errors/0/message")
+        .and_then(|m| m.as_str())
+    {
+        return Err(format!("Linear API error: {}", message));
+    }
+
+    if !status.is_success() {
+        return Err(format!("Linear API error: HTTP {}", status));
+    }
+
+    Ok(body.get("data").cloned().unwrap_or(Value::Null))
+}
+
+/// Lists the Linear projects visible to the configured API key.
+#[tauri::command]
+pub async fn linear_list_projects(app: AppHandle) -> Result<Vec<LinearProject>, String> {
+    let api_key = get_api_key(&app)?;
+    let data = graphql(
+        &api_key,
+        "query { projects(first: 100) { nodes { id name } } }",
+        Value::Null,
+    )
+    .await?;
+
+    let projects = data
+        .pointer("/projects/nodes")
+        .and_then(|n| n.as_array())
+        .map(|nodes| {
+            nodes
+                .iter()
+                .filter_map(|n| {
+                    Some(LinearProject {
+                        id: n.get("id")?.as_str()?.to_string(),
+                        name: n.get("name")?.as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(projects)
+}
+
+/// Imports a Linear project's issues into the PRD as stories.
+/// Issues already linked to a story are skipped, so re-importing only adds new issues.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_from_linear(
+    app: AppHandle,
+    project_path: String,
+    linear_project_id: String,
+) -> Result<LinearImportResult, String> {
+    let api_key = get_api_key(&app)?;
+
+    let query = r#"
+        query ProjectIssues($id: String!, $after: String) {
+            project(id: $id) {
+                issues(first: 100, after: $after) {
+                    nodes { id identifier title description url state { type } }
+                    pageInfo { hasNextPage endCursor }
+                }
+            }
+        }
+    "#;
+
+    let mut issues = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let data = graphql(
+            &api_key,
+            query,
+            serde_json::json!({ "id": linear_project_id, "after": cursor }),
+        )
+        .await?;
+
+        let page = data
+            .pointer("/project/issues")
+            .ok_or("Linear project not found")?;
+
+        if let Some(nodes) = page.get("nodes").and_then(|n| n.as_array()) {
+            issues.extend(nodes.iter().cloned());
+        }
+
+        let has_next = page
+            .pointer("/pageInfo/hasNextPage")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        cursor = page
+            .pointer("/pageInfo/endCursor")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        if !has_next || cursor.is_none() {
+            break;
+        }
+    }
+
+    let mut prd = load_prd(project_path.clone())?.unwrap_or(Prd {
+        project: None,
+        branch_name: None,
+        description: None,
+        user_stories: Vec::new(),
+    });
+    let mut state = load_sync_state(&project_path)?;
+
+    let mut result = LinearImportResult {
+        imported: Vec::new(),
+        skipped: 0,
+    };
+
+    for issue in issues {
+        let (Some(issue_id), Some(identifier), Some(title)) = (
+            issue.get("id").and_then(|v| v.as_str()),
+            issue.get("identifier").and_then(|v| v.as_str()),
+            issue.get("title").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+
+        if state.stories.values().any(|l| l.issue_id == issue_id) {
+            result.skipped += 1;
+            continue;
+        }
+
+        let description = issue
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let (description, acceptance_criteria) = split_acceptance_criteria(description);
+        let url = issue
+            .get("url")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let story_id = push_imported_story(
+            &mut prd,
+            title.to_string(),
+            description,
+            acceptance_criteria,
+            format!("Imported from Linear {}", identifier),
+        );
+
+        // Issues already completed in Linear are imported as passing stories.
+        let completed = issue.pointer("/state/type").and_then(|v| v.as_str()) == Some("completed");
+        if completed {
+            if let Some(story) = prd.user_stories.iter_mut().find(|s| s.id == story_id) {
+                story.passes = true;
+                story.status = Some("complete".to_string());
+            }
+        }
+
+        state.stories.insert(
+            story_id.clone(),
+            LinearIssueLink {
+                issue_id: issue_id.to_string(),
+                identifier: identifier.to_string(),
+                url,
+                reported_passed: completed,
+            },
+        );
+        result.imported.push(story_id);
+    }
+
+    if !result.imported.is_empty() {
+        let path = project_path.clone();
+        tokio::task::spawn_blocking(move || save_prd(path, prd))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+    }
+    state.project_id = Some(linear_project_id);
+    save_sync_state(&project_path, &state)?;
+
+    Ok(result)
+}
+
+/// Reports progress to Linear after `story_id` passes in a build, if it is
+/// linked to a Linear issue and an API key is configured. Runs in the
+/// background; failures are only logged.
+pub(crate) fn story_passed(app: &AppHandle, project_path: &str, story_id: &str) {
+    let linked = load_sync_state(project_path)
+        .map(|state| state.stories.get(story_id).is_some_and(|link| !link.reported_passed))
+        .unwrap_or(false);
+    if !linked || get_api_key(app).is_err() {
+        return;
+    }
+
+    let app = app.clone();
+    let project_path = project_path.to_string();
+    tauri::async_runtime::spawn(async move {
+        match linear_report_progress(app, project_path).await {
+            Ok(result) => {
+                for error in result.errors {
+                    tracing::warn!("Linear: failed to report progress for {}", error);
+                }
+            }
+            Err(e) => tracing::warn!("Linear: failed to report progress: {}", e),
+        }
+    });
+}
+
+/// Posts a progress comment on the linked Linear issue for every passing story
+/// that has not been reported yet.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn linear_report_progress(
+    app: AppHandle,
+    project_path: String,
+) -> Result<LinearReportResult, String> {
+    let api_key = get_api_key(&app)?;
+    let _reporting = REPORTING.lock().await;
+    let prd = load_prd(project_path.clone())?.ok_or("No PRD found for this project")?;
+    let mut state = load_sync_state(&project_path)?;
+
+    let mut result = LinearReportResult {
+        commented: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let mutation = r#"
+        mutation CommentCreate($issueId: String!, $body: String!) {
+            commentCreate(input: { issueId: $issueId, body: $body }) { success }
+        }
+    "#;
+
+    for story in prd.user_stories.iter().filter(|s| s.passes) {
+        let Some(link) = state.stories.get_mut(&story.id) else {
+            continue;
+        };
+        if link.reported_passed {
+            continue;
+        }
+
+        let body = format!(
+            "✅ Story **{}: {}** passed in Ideate.\n\nAll {} acceptance criteria were met.",
+            story.id,
+            story.title,
+            story.acceptance_criteria.len()
+        );
+
+        match graphql(
+            &api_key,
+            mutation,
+            serde_json::json!({ "issueId": link.issue_id, "body": body }),
+        )
+        .await
+        {
+            Ok(_) => {
+                link.reported_passed = true;
+                result.commented.push(story.id.clone());
+            }
+            Err(e) => result.errors.push(format!("{}: {}", story.id, e)),
+        }
+    }
+
+    save_sync_state(&project_path, &state)?;
+
+    Ok(result)
+}
+
+/// Loads the story-to-issue mapping for a project.
+#[tauri::command(rename_all = "camelCase")]
+pub fn load_linear_sync_state(project_path: String) -> Result<LinearSyncState, String> {
+    load_sync_state(&project_path)
+}
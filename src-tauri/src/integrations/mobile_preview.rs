@@ -0,0 +1,106 @@
+//! Opening the in-progress app on a phone.
+//!
+//! `get_mobile_preview` tunnels the project's preview to a public URL, using
+//! the project's tunnel provider, and returns the URL with a QR code to scan.
+//! The preview proxy is tunneled when it is running, so the phone also reloads
+//! as stories are merged; otherwise the managed dev server is.
+
+use std::time::Duration;
+
+use base64::Engine;
+use qrcode::{Color, QrCode};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::tunnels;
+use crate::projects::{load_project_settings, load_projects};
+use crate::{dev_server, preview_proxy};
+
+/// How long a new tunnel may take to report its public URL.
+const TUNNEL_READY_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Pixels per QR module, and the blank border around the code in modules.
+const QR_SCALE: usize = 8;
+const QR_QUIET_ZONE: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MobilePreview {
+    pub url: String,
+    pub tunnel_id: String,
+    pub provider: String,
+    pub port: u16,
+    /// QR code for `url` as a `data:image/png;base64,...` URL.
+    pub qr_code: String,
+}
+
+/// Renders `text` as a black-on-white QR code PNG.
+fn qr_png(text: &str) -> Result<Vec<u8>, String> {
+    let code = QrCode::new(text.as_bytes()).map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + QR_QUIET_ZONE * 2) * QR_SCALE;
+
+    let mut pixels = vec![255u8; size * size];
+    for (i, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let (x, y) = ((i % modules + QR_QUIET_ZONE) * QR_SCALE, (i / modules + QR_QUIET_ZONE) * QR_SCALE);
+        for row in y..y + QR_SCALE {
+            pixels[row * size + x..row * size + x + QR_SCALE].fill(0);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|e| format!("Failed to write QR code image: {}", e))?;
+    Ok(png)
+}
+
+/// Ensures a tunnel to the project's running app and returns its public URL
+/// with a QR code.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_mobile_preview(app: AppHandle, project_id: String) -> Result<MobilePreview, String> {
+    let project = load_projects(app.clone())?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let port = preview_proxy::port_for(&project_id)
+        .or_else(|| dev_server::port_for(&project.path))
+        .ok_or("Start the project's dev server before opening it on a phone")?;
+    let provider = load_project_settings(project.path.clone())
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.tunnel_provider);
+
+    crate::telemetry::record(&app, "feature.mobile-preview");
+    let tunnel = tunnels::ensure_tunnel(
+        &app,
+        &project_id,
+        port,
+        provider.as_deref(),
+        "Mobile preview",
+        TUNNEL_READY_TIMEOUT,
+    )
+    .await?;
+    let url = tunnel.url.ok_or("Tunnel has no public URL")?;
+
+    let png = qr_png(&url)?;
+    Ok(MobilePreview {
+        qr_code: format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(png)
+        ),
+        url,
+        tunnel_id: tunnel.tunnel_id,
+        provider: tunnel.provider,
+        port,
+    })
+}
@@ -1,3 +1,72 @@
 //! Integrations with external services.
 
+pub mod anthropic;
+pub mod cloudflare;
+pub mod deploy;
+pub mod docker;
+pub mod github;
+pub mod jira;
+pub mod linear;
+pub mod mobile_preview;
+pub mod ngrok;
+pub mod openai;
 pub mod outray;
+pub mod tunnels;
+
+use crate::models::{Prd, Story};
+
+/// Splits an issue description into body text and acceptance criteria.
+/// Markdown checklist items (`- [ ] ...` / `- [x] ...`) are treated as criteria.
+pub(crate) fn split_acceptance_criteria(text: &str) -> (String, Vec<String>) {
+    let mut body = Vec::new();
+    let mut criteria = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let item = trimmed
+            .strip_prefix("- [ ]")
+            .or_else(|| trimmed.strip_prefix("- [x]"))
+            .or_else(|| trimmed.strip_prefix("- [X]"))
+            .or_else(|| trimmed.strip_prefix("* [ ]"))
+            .or_else(|| trimmed.strip_prefix("* [x]"));
+
+        match item {
+            Some(item) if !item.trim().is_empty() => criteria.push(item.trim().to_string()),
+            _ => body.push(line),
+        }
+    }
+
+    (body.join("\n").trim().to_string(), criteria)
+}
+
+/// Appends an imported story to the PRD, assigning the next `US-XXX` id and priority.
+/// Returns the new story id.
+pub(crate) fn push_imported_story(
+    prd: &mut Prd,
+    title: String,
+    description: String,
+    acceptance_criteria: Vec<String>,
+    notes: String,
+) -> String {
+    let priority = prd.user_stories.iter().map(|s| s.priority).max().unwrap_or(0) + 1;
+    let mut number = priority;
+    while prd.user_stories.iter().any(|s| s.id == format!("US-{:03}", number)) {
+        number += 1;
+    }
+    let id = format!("US-{:03}", number);
+
+    prd.user_stories.push(Story {
+        id: id.clone(),
+        title,
+        description,
+        acceptance_criteria,
+        priority,
+        passes: false,
+        status: Some("pending".to_string()),
+        notes,
+        agent: None,
+        model: None,
+    });
+
+    id
+}
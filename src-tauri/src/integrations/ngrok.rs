@@ -0,0 +1,121 @@
+//! ngrok tunnel integration.
+//!
+//! The ngrok auth token is kept in the secrets store and passed to the CLI
+//! on each start, so users don't need to run `ngrok config add-authtoken`.
+
+use std::process::Command;
+
+use regex::Regex;
+use tauri::AppHandle;
+
+use super::tunnels::{TunnelAuthStatus, TunnelCommand, TunnelProvider, TunnelRequest};
+use crate::secrets;
+
+/// Secrets store key for the ngrok auth token.
+const AUTH_TOKEN_SECRET: &str = "ngrok.authToken";
+
+lazy_static::lazy_static! {
+    // With `--log stdout --log-format logfmt`, ngrok logs `url=https://...` once the tunnel is up
+    static ref NGROK_URL_RE: Regex = Regex::new(r"url=(https://\S+)").unwrap();
+}
+
+/// Well-known install locations checked when `ngrok` isn't on PATH.
+const FALLBACK_PATHS: &[&str] = &[
+    "/opt/homebrew/bin/ngrok",
+    "/usr/local/bin/ngrok",
+    "/usr/bin/ngrok",
+    "/snap/bin/ngrok",
+];
+
+/// Locates the ngrok binary, preferring PATH over well-known locations.
+fn find_ngrok() -> Option<String> {
+    if let Ok(output) = Command::new("which").arg("ngrok").output() {
+        if output.status.success() {
+            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !path.is_empty() {
+                return Some(path);
+            }
+        }
+    }
+
+    FALLBACK_PATHS
+        .iter()
+        .find(|p| std::path::Path::new(p).exists())
+        .map(|p| p.to_string())
+}
+
+/// ngrok tunnel provider. The auth token is stored in the secrets store.
+pub struct NgrokProvider;
+
+impl TunnelProvider for NgrokProvider {
+    fn id(&self) -> &'static str {
+        "ngrok"
+    }
+
+    fn name(&self) -> &'static str {
+        "ngrok"
+    }
+
+    fn url_pattern(&self) -> &'static Regex {
+        &NGROK_URL_RE
+    }
+
+    fn detect(&self, _app: &AppHandle) -> Option<String> {
+        find_ngrok()
+    }
+
+    fn auth_status(&self, app: &AppHandle) -> Result<TunnelAuthStatus, String> {
+        Ok(TunnelAuthStatus {
+            required: true,
+            authenticated: secrets::has_secret(app.clone(), AUTH_TOKEN_SECRET.to_string())?,
+        })
+    }
+
+    fn set_auth_token(&self, app: &AppHandle, token: &str) -> Result<(), String> {
+        if token.trim().is_empty() {
+            return secrets::delete_secret(app.clone(), AUTH_TOKEN_SECRET.to_string());
+        }
+        secrets::set_secret(
+            app.clone(),
+            AUTH_TOKEN_SECRET.to_string(),
+            token.trim().to_string(),
+        )
+    }
+
+    /// `subdomain` selects a reserved ngrok domain; otherwise ngrok assigns one.
+    fn build_command(
+        &self,
+        app: &AppHandle,
+        request: &TunnelRequest,
+    ) -> Result<TunnelCommand, String> {
+        let executable = match &request.custom_cli_path {
+            Some(path) if !path.is_empty() => path.clone(),
+            _ => find_ngrok().ok_or_else(|| {
+                "ngrok not found. Install it with `brew install ngrok` or from https://ngrok.com/download".to_string()
+            })?,
+        };
+
+        let mut args = vec![
+            "http".to_string(),
+            request.port.to_string(),
+            "--log".to_string(),
+            "stdout".to_string(),
+            "--log-format".to_string(),
+            "logfmt".to_string(),
+        ];
+
+        // Passed in the environment rather than argv so it isn't visible in `ps`
+        let env = secrets::get_secret(app, AUTH_TOKEN_SECRET)?
+            .filter(|token| !token.is_empty())
+            .map(|token| ("NGROK_AUTHTOKEN".to_string(), token))
+            .into_iter()
+            .collect();
+
+        if let Some(domain) = request.subdomain.as_ref().filter(|d| !d.is_empty()) {
+            args.push("--domain".to_string());
+            args.push(domain.clone());
+        }
+
+        Ok(TunnelCommand { executable, args, env })
+    }
+}
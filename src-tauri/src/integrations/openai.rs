@@ -0,0 +1,229 @@
+//! OpenAI usage API.
+//!
+//! Agents driven by a raw `OPENAI_API_KEY`, such as Codex and custom agents,
+//! leave no local token logs to parse, so their usage is read from the
+//! organization usage API instead. That API needs an admin key, stored as
+//! `openai.apiKey`. Usage shows up there a few minutes after the requests, and
+//! is per organization, so a run is charged whatever the key's organization
+//! used while it ran.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::cost_attribution::TrackedRun;
+use crate::models::CostEntry;
+use crate::pricing::{self, Pricing};
+use crate::secrets;
+
+const USAGE_URL: &str = "https://api.openai.com/v1/organization/usage/completions";
+
+/// Secrets store key for the OpenAI admin API key.
+const API_KEY_SECRET: &str = "openai.apiKey";
+
+/// Agents whose usage is recorded in local logs rather than by the API.
+const LOGGED_AGENTS: &[&str] = &["amp", "claude-code"];
+
+/// Usage of one model in one time bucket.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAiUsageBucket {
+    /// RFC 3339 start of the bucket.
+    pub start_time: String,
+    pub end_time: String,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub requests: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAiUsageSummary {
+    /// Daily usage by model, oldest first.
+    pub buckets: Vec<OpenAiUsageBucket>,
+    pub total_input_tokens: i64,
+    pub total_output_tokens: i64,
+    pub total_tokens: i64,
+    pub request_count: i64,
+    /// USD at the configured model prices; `None` if no model has a price.
+    pub total_cost: Option<f64>,
+}
+
+fn get_api_key(app: &AppHandle) -> Result<String, String> {
+    secrets::get_secret(app, API_KEY_SECRET)?
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| "No OpenAI API key configured. Add one in Settings.".to_string())
+}
+
+/// Whether `agent_id`'s usage comes from the OpenAI API rather than local logs.
+pub(crate) fn tracks_agent(app: &AppHandle, agent_id: &str) -> bool {
+    !LOGGED_AGENTS.contains(&agent_id) && get_api_key(app).is_ok()
+}
+
+fn to_rfc3339(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Completions usage by model between two Unix times, in buckets of
+/// `bucket_width` ("1m", "1h", or "1d").
+async fn fetch_usage(
+    api_key: &str,
+    start_secs: i64,
+    end_secs: Option<i64>,
+    bucket_width: &str,
+) -> Result<Vec<OpenAiUsageBucket>, String> {
+    let client = reqwest::Client::new();
+    let limit = match bucket_width {
+        "1m" => "1440",
+        "1h" => "168",
+        _ => "31",
+    };
+    let mut buckets = Vec::new();
+    let mut page: Option<String> = None;
+    loop {
+        let mut query = vec![
+            ("start_time", start_secs.to_string()),
+            ("bucket_width", bucket_width.to_string()),
+            ("group_by", "model".to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(end) = end_secs {
+            query.push(("end_time", end.to_string()));
+        }
+        if let Some(page) = &page {
+            query.push(("page", page.clone()));
+        }
+
+        let response = client
+            .get(USAGE_URL)
+            .bearer_auth(api_key)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach OpenAI: {}", e))?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+
+        if let Some(message) = body.pointer("/error/message").and_then(|m| m.as_str()) {
+            return Err(format!("OpenAI API error: {}", message));
+        }
+        if !status.is_success() {
+            return Err(format!("OpenAI API error: HTTP {}", status));
+        }
+
+        for bucket in body.get("data").and_then(|d| d.as_array()).into_iter().flatten() {
+            let start = bucket.get("start_time").and_then(|v| v.as_i64()).unwrap_or_default();
+            let end = bucket.get("end_time").and_then(|v| v.as_i64()).unwrap_or_default();
+            for result in bucket.get("results").and_then(|r| r.as_array()).into_iter().flatten() {
+                let count = |field: &str| result.get(field).and_then(|v| v.as_i64()).unwrap_or_default();
+                buckets.push(OpenAiUsageBucket {
+                    start_time: to_rfc3339(start),
+                    end_time: to_rfc3339(end),
+                    model: result.get("model").and_then(|v| v.as_str()).map(String::from),
+                    input_tokens: count("input_tokens"),
+                    cached_input_tokens: count("input_cached_tokens"),
+                    output_tokens: count("output_tokens"),
+                    requests: count("num_model_requests"),
+                });
+            }
+        }
+
+        page = body.get("next_page").and_then(|v| v.as_str()).map(String::from);
+        let has_more = body.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false);
+        if !has_more || page.is_none() {
+            break;
+        }
+    }
+    Ok(buckets)
+}
+
+/// USD for `bucket` at the configured price of its model, if it has one.
+fn bucket_cost(bucket: &OpenAiUsageBucket, pricing: &Pricing) -> Option<f64> {
+    let price = pricing::price_for(pricing, bucket.model.as_deref()?)?;
+    let cache_read = price.cache_read_per_million.unwrap_or(price.input_per_million);
+    let uncached = (bucket.input_tokens - bucket.cached_input_tokens).max(0);
+    Some(
+        (uncached as f64 * price.input_per_million
+            + bucket.cached_input_tokens as f64 * cache_read
+            + bucket.output_tokens as f64 * price.output_per_million)
+            / 1_000_000.0,
+    )
+}
+
+/// Cost entries for what the organization used during `run`, one per model.
+/// Their thread ID is the run's label and the model, so reconciling the run
+/// again adds nothing.
+pub(crate) async fn run_entries(app: &AppHandle, run: &TrackedRun, pricing: &Pricing) -> Result<Vec<CostEntry>, String> {
+    let api_key = get_api_key(app)?;
+    let parse = |t: &str| chrono::DateTime::parse_from_rfc3339(t).map(|t| t.timestamp());
+    let (Ok(start), Ok(end)) = (parse(&run.started_at), parse(&run.finished_at)) else {
+        return Ok(Vec::new());
+    };
+    // Minute buckets containing the run
+    let buckets = fetch_usage(&api_key, start - start % 60, Some(end - end % 60 + 60), "1m").await?;
+
+    let mut by_model: BTreeMap<String, Vec<OpenAiUsageBucket>> = BTreeMap::new();
+    for bucket in buckets {
+        let model = bucket.model.clone().unwrap_or_else(|| "unknown".to_string());
+        by_model.entry(model).or_default().push(bucket);
+    }
+
+    Ok(by_model
+        .into_iter()
+        .map(|(model, buckets)| {
+            let sum = |f: fn(&OpenAiUsageBucket) -> i64| buckets.iter().map(f).sum::<i64>();
+            let input_tokens = sum(|b| b.input_tokens);
+            let output_tokens = sum(|b| b.output_tokens);
+            let costs: Vec<f64> = buckets.iter().filter_map(|b| bucket_cost(b, pricing)).collect();
+            CostEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                project_id: run.project_id.clone(),
+                timestamp: run.started_at.clone(),
+                agent_id: run.agent_id.clone(),
+                description: format!("{} (OpenAI usage, {})", run.label, model),
+                input_tokens: Some(input_tokens),
+                output_tokens: Some(output_tokens),
+                total_tokens: Some(input_tokens + output_tokens),
+                cost: (!costs.is_empty()).then(|| costs.iter().sum()),
+                credits: None,
+                model: Some(model.clone()),
+                thread_id: Some(format!("openai:{}:{}", run.label, model)),
+                duration_ms: None,
+            }
+        })
+        .collect())
+}
+
+/// Daily OpenAI usage by model since `since_timestamp` (Unix milliseconds),
+/// or over the last 30 days.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn openai_usage(app: AppHandle, since_timestamp: Option<i64>) -> Result<OpenAiUsageSummary, String> {
+    let api_key = get_api_key(&app)?;
+    let start = since_timestamp
+        .map(|ms| ms / 1000)
+        .unwrap_or_else(|| (chrono::Utc::now() - chrono::Duration::days(30)).timestamp());
+    let buckets = fetch_usage(&api_key, start, None, "1d").await?;
+    let pricing = pricing::load_pricing(app)?;
+
+    let mut summary = OpenAiUsageSummary::default();
+    for bucket in &buckets {
+        summary.total_input_tokens += bucket.input_tokens;
+        summary.total_output_tokens += bucket.output_tokens;
+        summary.request_count += bucket.requests;
+        if let Some(cost) = bucket_cost(bucket, &pricing) {
+            *summary.total_cost.get_or_insert(0.0) += cost;
+        }
+    }
+    summary.total_tokens = summary.total_input_tokens + summary.total_output_tokens;
+    summary.buckets = buckets;
+    Ok(summary)
+}
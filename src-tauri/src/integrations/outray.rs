@@ -6,12 +6,19 @@
 use std::fs;
 use std::process::Command;
 
+use regex::Regex;
 use tauri::AppHandle;
 use tauri_plugin_opener::OpenerExt;
 
+use super::tunnels::{TunnelAuthStatus, TunnelCommand, TunnelProvider, TunnelRequest};
+
 const WEB_URL: &str = "https://outray.dev";
 const DASHBOARD_URL: &str = "https://outray.dev/dashboard";
 
+lazy_static::lazy_static! {
+    static ref OUTRAY_URL_RE: Regex = Regex::new(r"https?://[^\s]+\.outray\.app[^\s]*").unwrap();
+}
+
 /// OutRay config structure (matches ~/.outray/config.json)
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -90,10 +97,17 @@ pub struct OutrayExecutable {
 /// This is async to avoid blocking the UI thread during shell command execution.
 #[tauri::command(rename_all = "camelCase")]
 pub async fn get_sidecar_path(app: AppHandle) -> Result<OutrayExecutable, String> {
+    // Run the which commands in a blocking task to avoid blocking the UI
+    tokio::task::spawn_blocking(move || resolve_executable(&app))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Resolves the OutRay executable (see `get_sidecar_path`). Blocking.
+fn resolve_executable(app: &AppHandle) -> Result<OutrayExecutable, String> {
     use tauri::Manager;
     
-    // Run the which commands in a blocking task to avoid blocking the UI
-    let result = tokio::task::spawn_blocking(|| {
+    let result = (|| {
         // First, check if 'outray' is in PATH (globally installed)
         if let Ok(output) = Command::new("which").arg("outray").output() {
             if output.status.success() {
@@ -118,9 +132,7 @@ pub async fn get_sidecar_path(app: AppHandle) -> Result<OutrayExecutable, String
         }
         
         None
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?;
+    })();
     
     if let Some(exec) = result {
         return Ok(exec);
@@ -330,6 +342,11 @@ pub async fn login(app: AppHandle, _custom_cli_path: Option<String>) -> Result<L
 /// Checks if the user is logged into OutRay by checking the config file.
 #[tauri::command(rename_all = "camelCase")]
 pub async fn check_auth(_app: AppHandle, _custom_cli_path: Option<String>) -> Result<bool, String> {
+    is_authenticated()
+}
+
+/// Reads ~/.outray/config.json and checks for a valid, unexpired org token.
+fn is_authenticated() -> Result<bool, String> {
     let config_path = get_config_path()?;
     
     if !config_path.exists() {
@@ -391,3 +408,74 @@ pub fn get_auth_token() -> Result<Option<String>, String> {
     
     Ok(Some(config.org_token))
 }
+
+// ============================================================================
+// Tunnel Provider
+// ============================================================================
+
+/// OutRay tunnel provider. Authentication uses the browser `login` flow.
+pub struct OutRayProvider;
+
+impl TunnelProvider for OutRayProvider {
+    fn id(&self) -> &'static str {
+        "outray"
+    }
+
+    fn name(&self) -> &'static str {
+        "OutRay"
+    }
+
+    fn url_pattern(&self) -> &'static Regex {
+        &OUTRAY_URL_RE
+    }
+
+    fn detect(&self, app: &AppHandle) -> Option<String> {
+        resolve_executable(app).ok().map(|exec| exec.path)
+    }
+
+    fn auth_status(&self, _app: &AppHandle) -> Result<TunnelAuthStatus, String> {
+        Ok(TunnelAuthStatus {
+            required: true,
+            authenticated: is_authenticated()?,
+        })
+    }
+
+    fn build_command(
+        &self,
+        app: &AppHandle,
+        request: &TunnelRequest,
+    ) -> Result<TunnelCommand, String> {
+        let exec = match &request.custom_cli_path {
+            Some(path) if !path.is_empty() => OutrayExecutable {
+                path: path.clone(),
+                needs_auth_token: false,
+            },
+            _ => resolve_executable(app)?,
+        };
+
+        let mut args: Vec<String> = Vec::new();
+        if exec.path == "npx" {
+            args.push("outray".to_string());
+        }
+        args.push(request.port.to_string());
+
+        // The bundled binary can't locate ~/.outray, so pass the key explicitly
+        if exec.needs_auth_token {
+            if let Some(token) = get_auth_token()? {
+                args.push("--key".to_string());
+                args.push(token);
+            }
+        }
+
+        if let Some(sub) = request.subdomain.as_ref().filter(|s| !s.is_empty()) {
+            args.push("--subdomain".to_string());
+            args.push(sub.clone());
+        }
+
+        Ok(TunnelCommand {
+            executable: exec.path,
+            args,
+            env: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,570 @@
+//! Tunnel providers and shared tunnel process management.
+//!
+//! Each provider (OutRay, Cloudflare, ngrok) implements `TunnelProvider`, which
+//! describes how to detect, authenticate, and invoke its CLI. This module owns
+//! the resulting processes, parses the public URL from their output, and emits
+//! `tunnel-ready`, `tunnel-output`, and `tunnel-closed` events. The frontend
+//! uses the generic commands here regardless of provider.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use tauri::AppHandle;
+
+use super::cloudflare::CloudflareProvider;
+use super::ngrok::NgrokProvider;
+use super::outray::OutRayProvider;
+use crate::event_bus;
+
+lazy_static::lazy_static! {
+    static ref TUNNELS: Mutex<HashMap<String, TunnelHandle>> = Mutex::new(HashMap::new());
+}
+
+/// Registered tunnel providers. The first entry is the default.
+static PROVIDERS: &[&dyn TunnelProvider] = &[&OutRayProvider, &CloudflareProvider, &NgrokProvider];
+
+/// Parameters for starting a tunnel, shared by all providers.
+#[derive(Debug, Clone)]
+pub struct TunnelRequest {
+    pub project_id: String,
+    pub port: u16,
+    /// Subdomain (OutRay) or reserved domain (ngrok); ignored by providers without one
+    pub subdomain: Option<String>,
+    pub custom_cli_path: Option<String>,
+}
+
+/// The CLI invocation a provider uses to open a tunnel.
+pub struct TunnelCommand {
+    pub executable: String,
+    pub args: Vec<String>,
+    /// Set in the CLI's environment, for credentials that shouldn't show up
+    /// in process listings
+    pub env: Vec<(String, String)>,
+}
+
+/// Whether a provider needs credentials and whether they are present.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelAuthStatus {
+    pub required: bool,
+    pub authenticated: bool,
+}
+
+/// A tunnel provider backed by an external CLI.
+/// Methods may block (shell lookups, file reads); callers run them off the UI thread.
+pub trait TunnelProvider: Send + Sync {
+    /// Stable identifier stored in project settings ("outray", "cloudflare", ...).
+    fn id(&self) -> &'static str;
+
+    /// Human-readable provider name.
+    fn name(&self) -> &'static str;
+
+    /// Matches the public URL in the CLI's stdout or stderr.
+    /// If the pattern has a capture group, group 1 is used as the URL.
+    fn url_pattern(&self) -> &'static Regex;
+
+    /// Returns the path of the provider's CLI, if installed.
+    fn detect(&self, app: &AppHandle) -> Option<String>;
+
+    /// Reports whether the provider is authenticated.
+    fn auth_status(&self, app: &AppHandle) -> Result<TunnelAuthStatus, String>;
+
+    /// Stores an auth token for the provider.
+    fn set_auth_token(&self, _app: &AppHandle, _token: &str) -> Result<(), String> {
+        Err(format!("{} does not support auth tokens", self.name()))
+    }
+
+    /// Builds the CLI invocation that starts a tunnel.
+    fn build_command(&self, app: &AppHandle, request: &TunnelRequest)
+        -> Result<TunnelCommand, String>;
+}
+
+/// Looks up a provider by ID, falling back to the default when none is given.
+fn get_provider(provider_id: Option<&str>) -> Result<&'static dyn TunnelProvider, String> {
+    match provider_id.filter(|id| !id.is_empty()) {
+        Some(id) => PROVIDERS
+            .iter()
+            .copied()
+            .find(|p| p.id() == id)
+            .ok_or_else(|| format!("Unknown tunnel provider: {}", id)),
+        None => Ok(PROVIDERS[0]),
+    }
+}
+
+/// Provider summary for the settings UI.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelProviderInfo {
+    pub id: String,
+    pub name: String,
+    pub installed: bool,
+    pub cli_path: Option<String>,
+    pub auth: TunnelAuthStatus,
+}
+
+/// A running tunnel CLI process owned by the backend.
+struct TunnelHandle {
+    child: Child,
+    provider: String,
+    project_id: String,
+    label: String,
+    port: u16,
+    subdomain: Option<String>,
+    url: Option<String>,
+    url_pattern: &'static Regex,
+    started_at: String,
+}
+
+impl TunnelHandle {
+    fn status(&self, tunnel_id: &str) -> TunnelStatus {
+        TunnelStatus {
+            tunnel_id: tunnel_id.to_string(),
+            provider: self.provider.clone(),
+            project_id: self.project_id.clone(),
+            label: self.label.clone(),
+            status: if self.url.is_some() { "running" } else { "starting" }.to_string(),
+            port: self.port,
+            subdomain: self.subdomain.clone(),
+            url: self.url.clone(),
+            started_at: self.started_at.clone(),
+        }
+    }
+}
+
+/// Current state of a running tunnel.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelStatus {
+    pub tunnel_id: String,
+    pub provider: String,
+    pub project_id: String,
+    pub label: String,
+    pub status: String, // "starting" or "running"
+    pub port: u16,
+    pub subdomain: Option<String>,
+    pub url: Option<String>,
+    pub started_at: String,
+}
+
+/// Emitted once the tunnel's public URL has been parsed from CLI output.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelReadyEvent {
+    pub tunnel_id: String,
+    pub provider: String,
+    pub project_id: String,
+    pub label: String,
+    pub port: u16,
+    pub url: String,
+}
+
+/// Emitted when a tunnel process exits or is stopped.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelClosedEvent {
+    pub tunnel_id: String,
+    pub project_id: String,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+}
+
+/// A line of tunnel CLI output, forwarded for display in logs.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelOutputEvent {
+    pub tunnel_id: String,
+    pub project_id: String,
+    pub stream_type: String,
+    pub content: String,
+}
+
+/// Spawns a provider's tunnel CLI and registers it with the manager.
+async fn spawn_tunnel(
+    app: AppHandle,
+    provider: &'static dyn TunnelProvider,
+    request: TunnelRequest,
+    label: Option<String>,
+) -> Result<TunnelStatus, String> {
+    {
+        let tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if tunnels
+            .values()
+            .any(|t| t.project_id == request.project_id && t.port == request.port)
+        {
+            return Err(format!("A tunnel is already running for port {}", request.port));
+        }
+    }
+
+    // Resolving the CLI may shell out, so build and spawn off the UI thread
+    let app_for_spawn = app.clone();
+    let request_for_spawn = request.clone();
+    let mut child = tokio::task::spawn_blocking(move || {
+        let command = provider.build_command(&app_for_spawn, &request_for_spawn)?;
+
+        let mut cmd = Command::new(&command.executable);
+        cmd.args(&command.args)
+            .envs(command.env.iter().map(|(k, v)| (k, v)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        cmd.spawn().map_err(|e| {
+            format!("Failed to start {} '{}': {}", provider.name(), command.executable, e)
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let tunnel_id = uuid::Uuid::new_v4().to_string();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let handle = TunnelHandle {
+        child,
+        provider: provider.id().to_string(),
+        project_id: request.project_id,
+        label: label
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| format!("Port {}", request.port)),
+        port: request.port,
+        subdomain: request.subdomain,
+        url: None,
+        url_pattern: provider.url_pattern(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let status = handle.status(&tunnel_id);
+
+    {
+        let mut tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        tunnels.insert(tunnel_id.clone(), handle);
+    }
+
+    if let Some(stdout) = stdout {
+        watch_tunnel_output(app.clone(), tunnel_id.clone(), "stdout", stdout);
+    }
+    if let Some(stderr) = stderr {
+        watch_tunnel_output(app.clone(), tunnel_id.clone(), "stderr", stderr);
+    }
+    watch_tunnel_exit(app, tunnel_id);
+
+    Ok(status)
+}
+
+/// Reads tunnel CLI output, forwarding each line and capturing the public URL.
+fn watch_tunnel_output<R: std::io::Read + Send + 'static>(
+    app: AppHandle,
+    tunnel_id: String,
+    stream_type: &'static str,
+    stream: R,
+) {
+    thread::spawn(move || {
+        let reader = BufReader::new(stream);
+        for line in reader.lines().map_while(Result::ok) {
+            let project_id = {
+                let Ok(mut tunnels) = TUNNELS.lock() else {
+                    break;
+                };
+                let Some(handle) = tunnels.get_mut(&tunnel_id) else {
+                    break;
+                };
+
+                // Record the public URL the first time it appears
+                if handle.url.is_none() {
+                    if let Some(m) = handle
+                        .url_pattern
+                        .captures(&line)
+                        .and_then(|caps| caps.get(1).or_else(|| caps.get(0)))
+                    {
+                        let url = m.as_str().to_string();
+                        handle.url = Some(url.clone());
+                        let _ = event_bus::emit(
+                            &app,
+                            "tunnel-ready",
+                            TunnelReadyEvent {
+                                tunnel_id: tunnel_id.clone(),
+                                provider: handle.provider.clone(),
+                                project_id: handle.project_id.clone(),
+                                label: handle.label.clone(),
+                                port: handle.port,
+                                url,
+                            },
+                        );
+                    }
+                }
+
+                handle.project_id.clone()
+            };
+
+            let _ = event_bus::emit(
+                &app,
+                "tunnel-output",
+                TunnelOutputEvent {
+                    tunnel_id: tunnel_id.clone(),
+                    project_id,
+                    stream_type: stream_type.to_string(),
+                    content: line,
+                },
+            );
+        }
+    });
+}
+
+/// Polls the tunnel process and emits `tunnel-closed` when it exits on its own.
+fn watch_tunnel_exit(app: AppHandle, tunnel_id: String) {
+    thread::spawn(move || loop {
+        let exited = {
+            let mut tunnels = match TUNNELS.lock() {
+                Ok(t) => t,
+                Err(_) => return,
+            };
+
+            let handle = match tunnels.get_mut(&tunnel_id) {
+                Some(handle) => handle,
+                // Removed by stop_tunnel, which emits its own event
+                None => return,
+            };
+
+            let result = match handle.child.try_wait() {
+                Ok(Some(status)) => Some((status.code(), status.success())),
+                Ok(None) => None,
+                Err(_) => Some((None, false)),
+            };
+
+            result.and_then(|(exit_code, success)| {
+                tunnels
+                    .remove(&tunnel_id)
+                    .map(|handle| (handle.project_id, exit_code, success))
+            })
+        };
+
+        if let Some((project_id, exit_code, success)) = exited {
+            let _ = event_bus::emit(
+                &app,
+                "tunnel-closed",
+                TunnelClosedEvent {
+                    tunnel_id,
+                    project_id,
+                    exit_code,
+                    success,
+                },
+            );
+            return;
+        }
+
+        thread::sleep(Duration::from_millis(250));
+    });
+}
+
+/// Terminates the given tunnels and emits `tunnel-closed` for each.
+async fn stop_tunnels(app: &AppHandle, tunnel_ids: Vec<String>) -> Result<(), String> {
+    let handles: Vec<(String, TunnelHandle)> = {
+        let mut tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        tunnel_ids
+            .into_iter()
+            .filter_map(|id| tunnels.remove(&id).map(|handle| (id, handle)))
+            .collect()
+    };
+
+    for (tunnel_id, mut handle) in handles {
+        let project_id = handle.project_id.clone();
+        let exit_code = tokio::task::spawn_blocking(move || {
+            crate::process::terminate_child(&mut handle.child, Duration::from_secs(3))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+
+        let _ = event_bus::emit(
+            app,
+            "tunnel-closed",
+            TunnelClosedEvent {
+                tunnel_id,
+                project_id,
+                exit_code,
+                success: true,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the project's tunnel to `port`, starting one with the given provider
+/// if none is running, once its public URL is known.
+pub(crate) async fn ensure_tunnel(
+    app: &AppHandle,
+    project_id: &str,
+    port: u16,
+    provider_id: Option<&str>,
+    label: &str,
+    timeout: Duration,
+) -> Result<TunnelStatus, String> {
+    let existing = {
+        let tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        tunnels
+            .iter()
+            .find(|(_, t)| t.project_id == project_id && t.port == port)
+            .map(|(id, t)| t.status(id))
+    };
+    let tunnel_id = match existing {
+        Some(status) if status.url.is_some() => return Ok(status),
+        Some(status) => status.tunnel_id,
+        None => {
+            let provider = get_provider(provider_id)?;
+            crate::telemetry::record(app, "feature.tunnel");
+            let request = TunnelRequest {
+                project_id: project_id.to_string(),
+                port,
+                subdomain: None,
+                custom_cli_path: None,
+            };
+            spawn_tunnel(app.clone(), provider, request, Some(label.to_string()))
+                .await?
+                .tunnel_id
+        }
+    };
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match get_tunnel_status(tunnel_id.clone())? {
+            Some(status) if status.url.is_some() => return Ok(status),
+            Some(_) if Instant::now() < deadline => {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+            Some(_) => return Err("Timed out waiting for the tunnel's public URL".to_string()),
+            None => return Err("Tunnel exited before reporting its public URL".to_string()),
+        }
+    }
+}
+
+fn provider_info(app: &AppHandle, provider: &dyn TunnelProvider) -> Result<TunnelProviderInfo, String> {
+    let cli_path = provider.detect(app);
+    Ok(TunnelProviderInfo {
+        id: provider.id().to_string(),
+        name: provider.name().to_string(),
+        installed: cli_path.is_some(),
+        cli_path,
+        auth: provider.auth_status(app)?,
+    })
+}
+
+/// Install and auth status of one provider, the default if `provider_id` is
+/// empty. Blocks on shell lookups.
+pub(crate) fn provider_status(app: &AppHandle, provider_id: Option<&str>) -> Result<TunnelProviderInfo, String> {
+    provider_info(app, get_provider(provider_id)?)
+}
+
+/// Lists registered tunnel providers with install and auth status.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_tunnel_providers(app: AppHandle) -> Result<Vec<TunnelProviderInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        PROVIDERS
+            .iter()
+            .map(|provider| provider_info(&app, *provider))
+            .collect::<Result<Vec<_>, String>>()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stores an auth token for a provider that supports token authentication.
+#[tauri::command(rename_all = "camelCase")]
+pub fn set_tunnel_auth_token(
+    app: AppHandle,
+    provider: String,
+    auth_token: String,
+) -> Result<(), String> {
+    get_provider(Some(&provider))?.set_auth_token(&app, &auth_token)
+}
+
+/// Starts a tunnel for a project's local port using the given provider.
+/// A project may run several tunnels (e.g. web and API) as long as their ports differ.
+/// `tunnel-ready` is emitted once the public URL appears in the CLI output and
+/// `tunnel-closed` when the process exits. A `custom_cli_path` must pass the
+/// `allowlist` check, so one the user hasn't approved is rejected.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_tunnel(
+    app: AppHandle,
+    project_id: String,
+    port: u16,
+    provider: Option<String>,
+    subdomain: Option<String>,
+    label: Option<String>,
+    custom_cli_path: Option<String>,
+) -> Result<TunnelStatus, String> {
+    let provider = get_provider(provider.as_deref())?;
+    if let Some(path) = custom_cli_path.as_deref().filter(|path| !path.is_empty()) {
+        crate::allowlist::check(&app, path, &[], None)?;
+    }
+    crate::telemetry::record(&app, "feature.tunnel");
+    let request = TunnelRequest {
+        project_id,
+        port,
+        subdomain,
+        custom_cli_path,
+    };
+    spawn_tunnel(app, provider, request, label).await
+}
+
+/// Stops a single tunnel, if it is running.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_tunnel(app: AppHandle, tunnel_id: String) -> Result<(), String> {
+    stop_tunnels(&app, vec![tunnel_id]).await
+}
+
+/// Stops every tunnel belonging to a project.
+/// Called when a project's build stops so no tunnel outlives its dev server.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_project_tunnels(app: AppHandle, project_id: String) -> Result<(), String> {
+    let tunnel_ids: Vec<String> = {
+        let tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        tunnels
+            .iter()
+            .filter(|(_, t)| t.project_id == project_id)
+            .map(|(id, _)| id.clone())
+            .collect()
+    };
+
+    stop_tunnels(&app, tunnel_ids).await
+}
+
+/// Returns the status of a single tunnel, or None if it is not running.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_tunnel_status(tunnel_id: String) -> Result<Option<TunnelStatus>, String> {
+    let tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(tunnels.get(&tunnel_id).map(|handle| handle.status(&tunnel_id)))
+}
+
+/// Lists all tunnels running for a project, ordered by port.
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_project_tunnels(project_id: String) -> Result<Vec<TunnelStatus>, String> {
+    let tunnels = TUNNELS.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut statuses: Vec<TunnelStatus> = tunnels
+        .iter()
+        .filter(|(_, t)| t.project_id == project_id)
+        .map(|(id, t)| t.status(id))
+        .collect();
+    statuses.sort_by_key(|s| s.port);
+
+    Ok(statuses)
+}
+
+/// Stops all tunnels. Called on app shutdown.
+pub fn stop_all_tunnels() {
+    if let Ok(mut tunnels) = TUNNELS.lock() {
+        for (tunnel_id, mut handle) in tunnels.drain() {
+            crate::process::terminate_child(&mut handle.child, Duration::from_millis(500));
+            tracing::info!("Stopped {} tunnel {} ({})", handle.provider, tunnel_id, handle.label);
+        }
+    }
+}
@@ -0,0 +1,1273 @@
+//! LAN sync of project state between machines.
+//!
+//! With LAN sync on in Preferences, Ideate advertises itself over mDNS as
+//! `_ideate-sync._tcp` and connects to the copies of Ideate it finds on the
+//! local network that share its sync key. Connected machines replicate the
+//! `.ideate` documents of the projects both have marked for sync, matched by
+//! the `syncId` in project metadata rather than by path, and forward those
+//! projects' build events, so a build started at the office can be followed
+//! from a laptop at home.
+//!
+//! Peers prove they hold the sync key with an HMAC challenge in each
+//! direction. Every message after that is encrypted with ChaCha20 under a
+//! key derived from the session key and carries an HMAC of the ciphertext
+//! and a sequence number.
+//!
+//! The commands in `.ideate/config.json` (hooks, the verification command,
+//! and the e2e command) are never taken from a peer: an incoming copy keeps
+//! this machine's, so holding the sync key isn't enough to run a command here.
+//!
+//! A changed file goes to the machine with the older copy, so the last writer
+//! wins. When both copies changed since the machines last agreed on the file,
+//! the older one is saved under `.ideate/sync-conflicts/` and reported as a
+//! conflict. Deletions are not replicated, and a project with a build running
+//! locally takes no changes until the build ends.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use hmac::{Hmac, Mac};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, watch};
+
+use crate::event_bridge::{self, BridgeEvent};
+use crate::models::Preferences;
+use crate::orchestrator;
+use crate::preferences::load_preferences_internal;
+use crate::projects::{get_project_metadata, load_projects, set_project_metadata};
+use crate::secrets;
+use crate::utils::get_ideate_dir;
+
+const SERVICE_TYPE: &str = "_ideate-sync._tcp.local.";
+const PROTOCOL_VERSION: u32 = 2;
+const KEY_SECRET_KEY: &str = "lan-sync-key";
+const DEVICE_ID_SECRET_KEY: &str = "lan-sync-device-id";
+
+/// Project metadata entry pairing a project with its copies elsewhere.
+const SYNC_ID_KEY: &str = "syncId";
+
+/// Under `.ideate`; where the losing copies of conflicting files are kept.
+const CONFLICTS_DIR: &str = "sync-conflicts";
+
+/// Under `.ideate`; the project config.
+const CONFIG_FILE: &str = "config.json";
+
+/// Keys of the project config holding commands Ideate runs, which always
+/// keep this machine's values.
+const LOCAL_CONFIG_KEYS: &[&str] = &["hooks", "verification", "e2e"];
+
+const SCAN_INTERVAL: Duration = Duration::from_secs(3);
+const DIAL_INTERVAL: Duration = Duration::from_secs(15);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Larger files are not synced.
+const MAX_FILE_BYTES: u64 = 8 * 1024 * 1024;
+/// Longest message accepted from a peer; a file is sent as base64.
+const MAX_FRAME_BYTES: u64 = MAX_FILE_BYTES * 2;
+const MAX_HANDSHAKE_BYTES: u64 = 4096;
+const MAX_CONFLICTS: usize = 100;
+
+/// Build events forwarded to peers.
+const FORWARDED_EVENTS: &[&str] = &[
+    "build-status",
+    "build-log",
+    "build-story-status",
+    "build-story-finished",
+    "build-story-failed",
+    "build-merge-conflict",
+    "build-finished",
+];
+
+type HmacSha256 = Hmac<Sha256>;
+
+lazy_static::lazy_static! {
+    static ref SERVICE: Mutex<Option<ServiceHandle>> = Mutex::new(None);
+    /// Peers by device ID.
+    static ref PEERS: Mutex<HashMap<String, PeerInfo>> = Mutex::new(HashMap::new());
+    static ref CONFLICTS: Mutex<Vec<SyncConflict>> = Mutex::new(Vec::new());
+    /// Path -> (modified, size, hash), so unchanged files aren't rehashed.
+    static ref HASHES: Mutex<HashMap<PathBuf, (SystemTime, u64, String)>> = Mutex::new(HashMap::new());
+}
+
+struct ServiceHandle {
+    port: u16,
+    shutdown_tx: watch::Sender<bool>,
+    mdns: ServiceDaemon,
+}
+
+/// This machine, as peers see it.
+#[derive(Clone)]
+struct Identity {
+    device_id: String,
+    name: String,
+    key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteProject {
+    pub sync_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub device_id: String,
+    pub name: String,
+    /// Where it was last found.
+    pub address: Option<String>,
+    pub connected: bool,
+    pub last_seen: String,
+    pub last_sync: Option<String>,
+    /// Projects it has marked for sync.
+    pub projects: Vec<RemoteProject>,
+    /// Why the last connection failed, e.g. a different sync key.
+    pub error: Option<String>,
+    #[serde(skip)]
+    sessions: usize,
+    #[serde(skip)]
+    dialing: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub id: String,
+    pub project_id: String,
+    pub project_name: String,
+    /// Relative to `.ideate`.
+    pub path: String,
+    pub peer_name: String,
+    /// This machine's copy, which lost to the peer's newer one; relative to
+    /// the project.
+    pub saved_copy: String,
+    pub detected_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSyncStatus {
+    pub running: bool,
+    pub port: u16,
+    pub device_id: Option<String>,
+    pub device_name: String,
+    pub key: Option<String>,
+    pub peers: Vec<PeerInfo>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppliedPayload {
+    project_id: String,
+    path: String,
+    peer_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteEventPayload {
+    project_id: String,
+    peer_name: String,
+    event: String,
+    payload: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Handshake {
+    #[serde(rename_all = "camelCase")]
+    Challenge {
+        version: u32,
+        device_id: String,
+        name: String,
+        nonce: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Hello {
+        version: u32,
+        device_id: String,
+        name: String,
+        nonce: String,
+        proof: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Welcome { proof: String },
+    #[serde(rename_all = "camelCase")]
+    Rejected { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileEntry {
+    /// Relative to `.ideate`, with `/` separators.
+    path: String,
+    hash: String,
+    modified_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectManifest {
+    sync_id: String,
+    name: String,
+    files: Vec<FileEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Message {
+    #[serde(rename_all = "camelCase")]
+    Manifest { projects: Vec<ProjectManifest> },
+    #[serde(rename_all = "camelCase")]
+    Fetch { sync_id: String, path: String },
+    #[serde(rename_all = "camelCase")]
+    File {
+        sync_id: String,
+        path: String,
+        content: String,
+        modified_ms: i64,
+        hash: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Event {
+        sync_id: String,
+        event: String,
+        payload: Value,
+    },
+}
+
+/// An encrypted, signed message.
+#[derive(Debug, Serialize, Deserialize)]
+struct Frame {
+    seq: u64,
+    body: String,
+    mac: String,
+}
+
+/// A project on this machine marked for sync.
+#[derive(Debug, Clone)]
+struct SharedProject {
+    id: String,
+    name: String,
+    path: String,
+    sync_id: String,
+}
+
+fn hmac(key: &[u8], parts: &[&str]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part.as_bytes());
+        mac.update(&[0]);
+    }
+    mac
+}
+
+fn sign(key: &[u8], parts: &[&str]) -> String {
+    hex::encode(hmac(key, parts).finalize().into_bytes())
+}
+
+/// Checks a signature in constant time.
+fn verify(key: &[u8], parts: &[&str], signature: &str) -> bool {
+    hex::decode(signature).is_ok_and(|signature| hmac(key, parts).verify_slice(&signature).is_ok())
+}
+
+/// Encrypts or decrypts a frame body in place. Each direction has its own
+/// key, from the session key and the sender's device ID, and each frame its
+/// own sequence number, so no keystream is used twice.
+fn apply_keystream(session_key: &[u8], sender: &str, seq: u64, data: &mut [u8]) {
+    let key = hmac(session_key, &["encrypt", sender]).finalize().into_bytes();
+    let mut nonce = [0u8; 12];
+    nonce[..8].copy_from_slice(&seq.to_le_bytes());
+    ChaCha20::new(&key, &nonce.into()).apply_keystream(data);
+}
+
+fn new_nonce() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+/// This computer's name, without the `.local` suffix macOS adds.
+fn device_name() -> String {
+    Command::new("hostname")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().trim_end_matches(".local").to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Ideate".to_string())
+}
+
+/// Returns a secret, creating it with `generate` on first use.
+fn ensure_secret(app: &AppHandle, key: &str, generate: fn() -> String) -> Result<String, String> {
+    if let Some(value) = secrets::get_secret(app, key)?.filter(|v| !v.is_empty()) {
+        return Ok(value);
+    }
+    let value = generate();
+    secrets::set_secret(app.clone(), key.to_string(), value.clone())?;
+    Ok(value)
+}
+
+fn identity(app: &AppHandle) -> Result<Identity, String> {
+    Ok(Identity {
+        // Short enough to be an mDNS instance name
+        device_id: ensure_secret(app, DEVICE_ID_SECRET_KEY, || uuid::Uuid::new_v4().simple().to_string())?,
+        name: device_name(),
+        key: ensure_secret(app, KEY_SECRET_KEY, new_nonce)?,
+    })
+}
+
+fn update_peer(device_id: &str, apply: impl FnOnce(&mut PeerInfo)) {
+    if let Ok(mut peers) = PEERS.lock() {
+        let peer = peers.entry(device_id.to_string()).or_insert_with(|| PeerInfo {
+            device_id: device_id.to_string(),
+            name: device_id.to_string(),
+            address: None,
+            connected: false,
+            last_seen: chrono::Utc::now().to_rfc3339(),
+            last_sync: None,
+            projects: Vec::new(),
+            error: None,
+            sessions: 0,
+            dialing: false,
+        });
+        apply(peer);
+        peer.connected = peer.sessions > 0;
+    }
+}
+
+/// A peer's project config with the command keys replaced by this machine's
+/// (or removed, if it has none), so syncing can't change what commands run.
+fn keep_local_commands(remote: &[u8], local: Option<&[u8]>) -> Result<Vec<u8>, String> {
+    let mut config: serde_json::Map<String, Value> =
+        serde_json::from_slice(remote).map_err(|e| format!("not a JSON object: {}", e))?;
+    let local: serde_json::Map<String, Value> = local
+        .and_then(|bytes| serde_json::from_slice(bytes).ok())
+        .unwrap_or_default();
+    for key in LOCAL_CONFIG_KEYS {
+        match local.get(*key) {
+            Some(value) => config.insert(key.to_string(), value.clone()),
+            None => config.remove(*key),
+        };
+    }
+    serde_json::to_vec_pretty(&config).map_err(|e| format!("Failed to serialize config: {}", e))
+}
+
+/// A path relative to `.ideate` from a peer, refusing any that would escape
+/// it or that are never synced.
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let relative = PathBuf::from(path);
+    let normal = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    (normal && is_synced(path)).then_some(relative)
+}
+
+/// Locks and temp files belong to writers on one machine, and conflict
+/// copies stay on the machine that lost them.
+fn is_synced(relative: &str) -> bool {
+    !relative.ends_with(".lock")
+        && !relative.ends_with(".tmp")
+        && relative != CONFLICTS_DIR
+        && !relative.starts_with(&format!("{}/", CONFLICTS_DIR))
+}
+
+fn shared_projects(app: &AppHandle) -> Vec<SharedProject> {
+    load_projects(app.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|project| {
+            let sync_id = get_project_metadata(project.path.clone(), Some(SYNC_ID_KEY.to_string())).ok()?;
+            Some(SharedProject {
+                sync_id: sync_id.as_str().filter(|id| !id.is_empty())?.to_string(),
+                id: project.id,
+                name: project.name,
+                path: project.path,
+            })
+        })
+        .collect()
+}
+
+fn build_running(project: &SharedProject) -> bool {
+    orchestrator::get_build_status(project.id.clone()).ok().flatten().is_some()
+}
+
+fn file_hash(path: &Path, modified: SystemTime, size: u64) -> Option<String> {
+    if let Some((cached_modified, cached_size, hash)) = HASHES.lock().ok()?.get(path) {
+        if *cached_modified == modified && *cached_size == size {
+            return Some(hash.clone());
+        }
+    }
+    let hash = sha256_hex(&fs::read(path).ok()?);
+    HASHES
+        .lock()
+        .ok()?
+        .insert(path.to_path_buf(), (modified, size, hash.clone()));
+    Some(hash)
+}
+
+fn scan_dir(root: &Path, dir: &Path, files: &mut Vec<FileEntry>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        if !is_synced(&relative) {
+            continue;
+        }
+        if metadata.is_dir() {
+            scan_dir(root, &path, files);
+            continue;
+        }
+        if metadata.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        let Ok(modified) = metadata.modified() else {
+            continue;
+        };
+        if let Some(hash) = file_hash(&path, modified, metadata.len()) {
+            files.push(FileEntry {
+                path: relative,
+                hash,
+                modified_ms: millis(modified),
+            });
+        }
+    }
+}
+
+fn scan_project(project: &SharedProject) -> ProjectManifest {
+    let root = get_ideate_dir(&project.path);
+    let mut files = Vec::new();
+    scan_dir(&root, &root, &mut files);
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    ProjectManifest {
+        sync_id: project.sync_id.clone(),
+        name: project.name.clone(),
+        files,
+    }
+}
+
+/// Writes `bytes` to `target` with the peer's modification time, so the two
+/// copies compare as the same version afterwards.
+fn write_synced_file(target: &Path, bytes: &[u8], modified_ms: i64) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let file_name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = target.with_file_name(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4().simple()));
+    fs::write(&temp, bytes).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    let modified = UNIX_EPOCH + Duration::from_millis(modified_ms.max(0) as u64);
+    if let Ok(file) = fs::File::options().write(true).open(&temp) {
+        let _ = file.set_modified(modified);
+    }
+    fs::rename(&temp, target).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to replace {}: {}", target.display(), e)
+    })
+}
+
+async fn read_line<R: AsyncRead + Unpin>(reader: &mut BufReader<R>, limit: u64) -> Result<Option<String>, String> {
+    let mut line = String::new();
+    let read = (&mut *reader)
+        .take(limit)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| format!("Failed to read from peer: {}", e))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') {
+        return Err("Peer sent an oversized message".to_string());
+    }
+    Ok(Some(line.trim_end().to_string()))
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, value: &impl Serialize) -> Result<(), String> {
+    let mut line = serde_json::to_string(value).map_err(|e| format!("Failed to serialize message: {}", e))?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write to peer: {}", e))
+}
+
+async fn read_handshake<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> Result<Handshake, String> {
+    let line = read_line(reader, MAX_HANDSHAKE_BYTES)
+        .await?
+        .ok_or_else(|| "Peer closed the connection".to_string())?;
+    serde_json::from_str(&line).map_err(|e| format!("Invalid handshake: {}", e))
+}
+
+/// A peer that passed the handshake.
+struct Peer {
+    device_id: String,
+    name: String,
+    session_key: Vec<u8>,
+}
+
+/// Handshake for a connection this machine accepted.
+async fn accept_handshake<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: &mut BufReader<R>,
+    writer: &mut W,
+    me: &Identity,
+) -> Result<Peer, String> {
+    let nonce = new_nonce();
+    write_line(
+        writer,
+        &Handshake::Challenge {
+            version: PROTOCOL_VERSION,
+            device_id: me.device_id.clone(),
+            name: me.name.clone(),
+            nonce: nonce.clone(),
+        },
+    )
+    .await?;
+    let Handshake::Hello {
+        version,
+        device_id,
+        name,
+        nonce: peer_nonce,
+        proof,
+    } = read_handshake(reader).await?
+    else {
+        return Err("Unexpected handshake message".to_string());
+    };
+    let rejection = if version != PROTOCOL_VERSION {
+        Some(format!("{} uses sync protocol {}, this machine {}", name, version, PROTOCOL_VERSION))
+    } else if !verify(me.key.as_bytes(), &["hello", &nonce, &device_id], &proof) {
+        Some(format!("{} has a different sync key", name))
+    } else {
+        None
+    };
+    if let Some(reason) = rejection {
+        let _ = write_line(writer, &Handshake::Rejected { reason: reason.clone() }).await;
+        update_peer(&device_id, |peer| peer.error = Some(reason.clone()));
+        return Err(reason);
+    }
+    write_line(
+        writer,
+        &Handshake::Welcome {
+            proof: sign(me.key.as_bytes(), &["welcome", &peer_nonce, &me.device_id]),
+        },
+    )
+    .await?;
+    Ok(Peer {
+        session_key: hmac(me.key.as_bytes(), &["session", &nonce, &peer_nonce])
+            .finalize()
+            .into_bytes()
+            .to_vec(),
+        device_id,
+        name,
+    })
+}
+
+/// Handshake for a connection this machine made.
+async fn dial_handshake<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: &mut BufReader<R>,
+    writer: &mut W,
+    me: &Identity,
+) -> Result<Peer, String> {
+    let Handshake::Challenge {
+        version,
+        device_id,
+        name,
+        nonce: peer_nonce,
+    } = read_handshake(reader).await?
+    else {
+        return Err("Unexpected handshake message".to_string());
+    };
+    if version != PROTOCOL_VERSION {
+        return Err(format!("{} uses sync protocol {}, this machine {}", name, version, PROTOCOL_VERSION));
+    }
+    let nonce = new_nonce();
+    write_line(
+        writer,
+        &Handshake::Hello {
+            version: PROTOCOL_VERSION,
+            device_id: me.device_id.clone(),
+            name: me.name.clone(),
+            nonce: nonce.clone(),
+            proof: sign(me.key.as_bytes(), &["hello", &peer_nonce, &me.device_id]),
+        },
+    )
+    .await?;
+    match read_handshake(reader).await? {
+        Handshake::Welcome { proof } if verify(me.key.as_bytes(), &["welcome", &nonce, &device_id], &proof) => {
+            Ok(Peer {
+                session_key: hmac(me.key.as_bytes(), &["session", &peer_nonce, &nonce])
+                    .finalize()
+                    .into_bytes()
+                    .to_vec(),
+                device_id,
+                name,
+            })
+        }
+        Handshake::Rejected { reason } => Err(reason),
+        _ => Err(format!("{} has a different sync key", name)),
+    }
+}
+
+/// One connection to a peer, from either side.
+struct Session {
+    app: AppHandle,
+    me: Identity,
+    peer: Peer,
+    send_seq: u64,
+    recv_seq: u64,
+    shared: Vec<SharedProject>,
+    /// What was last sent, so unchanged manifests aren't resent.
+    sent: Option<Vec<ProjectManifest>>,
+    /// What the peer last sent.
+    remote: Vec<ProjectManifest>,
+    /// Hash both machines last had for (sync ID, path).
+    bases: HashMap<(String, String), String>,
+    /// Files asked for and not yet received.
+    pending: HashSet<(String, String)>,
+}
+
+impl Session {
+    fn project(&self, sync_id: &str) -> Option<&SharedProject> {
+        self.shared.iter().find(|p| p.sync_id == sync_id)
+    }
+
+    async fn send(&mut self, writer: &mut OwnedWriteHalf, message: &Message) -> Result<(), String> {
+        let mut plaintext = serde_json::to_vec(message).map_err(|e| format!("Failed to serialize message: {}", e))?;
+        apply_keystream(&self.peer.session_key, &self.me.device_id, self.send_seq, &mut plaintext);
+        let body = base64::engine::general_purpose::STANDARD.encode(plaintext);
+        let seq = self.send_seq.to_string();
+        let frame = Frame {
+            seq: self.send_seq,
+            mac: sign(&self.peer.session_key, &[&self.me.device_id, &seq, &body]),
+            body,
+        };
+        self.send_seq += 1;
+        write_line(writer, &frame).await
+    }
+
+    fn open(&mut self, line: &str) -> Result<Message, String> {
+        let frame: Frame = serde_json::from_str(line).map_err(|e| format!("Invalid frame: {}", e))?;
+        let seq = frame.seq.to_string();
+        if frame.seq != self.recv_seq
+            || !verify(&self.peer.session_key, &[&self.peer.device_id, &seq, &frame.body], &frame.mac)
+        {
+            return Err(format!("Rejected a message from {} with a bad signature", self.peer.name));
+        }
+        let mut body = base64::engine::general_purpose::STANDARD
+            .decode(&frame.body)
+            .map_err(|e| format!("Invalid frame: {}", e))?;
+        apply_keystream(&self.peer.session_key, &self.peer.device_id, frame.seq, &mut body);
+        self.recv_seq += 1;
+        serde_json::from_slice(&body).map_err(|e| format!("Invalid message: {}", e))
+    }
+
+    /// Rescans the shared projects; returns the manifest when it changed.
+    fn scan(&mut self) -> Option<Vec<ProjectManifest>> {
+        self.shared = shared_projects(&self.app);
+        let manifest: Vec<ProjectManifest> = self.shared.iter().map(scan_project).collect();
+        if self.sent.as_ref() == Some(&manifest) {
+            return None;
+        }
+        self.sent = Some(manifest.clone());
+        Some(manifest)
+    }
+
+    /// Files the peer has newer copies of.
+    fn reconcile(&mut self) -> Vec<Message> {
+        let local = self.sent.clone().unwrap_or_default();
+        let mut fetches = Vec::new();
+        for remote in &self.remote {
+            let Some(project) = self.shared.iter().find(|p| p.sync_id == remote.sync_id) else {
+                continue;
+            };
+            if build_running(project) {
+                continue;
+            }
+            let local_files: HashMap<&str, &FileEntry> = local
+                .iter()
+                .find(|m| m.sync_id == remote.sync_id)
+                .map(|m| m.files.iter().map(|f| (f.path.as_str(), f)).collect())
+                .unwrap_or_default();
+            for file in &remote.files {
+                let key = (remote.sync_id.clone(), file.path.clone());
+                match local_files.get(file.path.as_str()) {
+                    Some(ours) if ours.hash == file.hash => {
+                        self.bases.insert(key, file.hash.clone());
+                    }
+                    Some(ours) if ours.modified_ms >= file.modified_ms => {}
+                    _ if self.pending.contains(&key) || safe_relative_path(&file.path).is_none() => {}
+                    _ => {
+                        self.pending.insert(key);
+                        fetches.push(Message::Fetch {
+                            sync_id: remote.sync_id.clone(),
+                            path: file.path.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        fetches
+    }
+
+    fn read_file(&self, sync_id: &str, path: &str) -> Option<Message> {
+        let project = self.project(sync_id)?;
+        let target = get_ideate_dir(&project.path).join(safe_relative_path(path)?);
+        let metadata = fs::metadata(&target).ok()?;
+        if metadata.len() > MAX_FILE_BYTES {
+            return None;
+        }
+        let bytes = fs::read(&target).ok()?;
+        Some(Message::File {
+            sync_id: sync_id.to_string(),
+            path: path.to_string(),
+            hash: sha256_hex(&bytes),
+            content: base64::engine::general_purpose::STANDARD.encode(bytes),
+            modified_ms: metadata.modified().map(millis).unwrap_or(0),
+        })
+    }
+
+    fn apply_file(&mut self, sync_id: String, path: String, content: &str, modified_ms: i64, hash: String) -> Result<(), String> {
+        let key = (sync_id.clone(), path.clone());
+        self.pending.remove(&key);
+        let Some(project) = self.project(&sync_id).cloned() else {
+            return Ok(());
+        };
+        if build_running(&project) {
+            return Ok(());
+        }
+        let relative = safe_relative_path(&path).ok_or_else(|| format!("Invalid path from peer: {}", path))?;
+        let mut bytes = base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .map_err(|e| format!("Failed to decode {}: {}", path, e))?;
+        if sha256_hex(&bytes) != hash {
+            return Err(format!("{} arrived corrupted", path));
+        }
+
+        let ideate_dir = get_ideate_dir(&project.path);
+        let target = ideate_dir.join(&relative);
+        let local = fs::read(&target).ok();
+        if path == CONFIG_FILE {
+            bytes = keep_local_commands(&bytes, local.as_deref())
+                .map_err(|e| format!("Refused {} from {}: {}", path, self.peer.name, e))?;
+        }
+        let local_hash = local.as_deref().map(sha256_hex);
+        if local_hash.as_deref() == Some(hash.as_str()) {
+            self.bases.insert(key, hash);
+            return Ok(());
+        }
+        // Both sides changed the file since they last agreed on it
+        if let (Some(local), Some(local_hash), Some(base)) = (&local, &local_hash, self.bases.get(&key)) {
+            if local_hash != base && &hash != base {
+                self.save_conflict(&project, &relative, &path, local)?;
+            }
+        }
+        write_synced_file(&target, &bytes, modified_ms)?;
+        self.bases.insert(key, hash);
+        tracing::info!("LAN sync: updated {}/.ideate/{} from {}", project.name, path, self.peer.name);
+        let _ = self.app.emit(
+            "lan-sync-applied",
+            AppliedPayload {
+                project_id: project.id.clone(),
+                path,
+                peer_name: self.peer.name.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    fn save_conflict(&self, project: &SharedProject, relative: &Path, path: &str, local: &[u8]) -> Result<(), String> {
+        let file_name = relative.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let copy = Path::new(CONFLICTS_DIR)
+            .join(relative.parent().unwrap_or(Path::new("")))
+            .join(format!("{}.{}.conflict", file_name, stamp));
+        let target = get_ideate_dir(&project.path).join(&copy);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&target, local).map_err(|e| format!("Failed to save conflicting copy: {}", e))?;
+
+        let conflict = SyncConflict {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: project.id.clone(),
+            project_name: project.name.clone(),
+            path: path.to_string(),
+            peer_name: self.peer.name.clone(),
+            saved_copy: Path::new(".ideate").join(&copy).to_string_lossy().to_string(),
+            detected_at: chrono::Utc::now().to_rfc3339(),
+        };
+        tracing::warn!(
+            "LAN sync: {}/.ideate/{} changed here and on {}; kept theirs, saved this machine's as {}",
+            project.name,
+            path,
+            self.peer.name,
+            conflict.saved_copy
+        );
+        if let Ok(mut conflicts) = CONFLICTS.lock() {
+            conflicts.push(conflict.clone());
+            let excess = conflicts.len().saturating_sub(MAX_CONFLICTS);
+            conflicts.drain(..excess);
+        }
+        let _ = self.app.emit("lan-sync-conflict", conflict);
+        Ok(())
+    }
+
+    /// Handles a message from the peer, returning the replies.
+    fn receive(&mut self, message: Message) -> Result<Vec<Message>, String> {
+        match message {
+            Message::Manifest { projects } => {
+                let offered = projects
+                    .iter()
+                    .map(|p| RemoteProject {
+                        sync_id: p.sync_id.clone(),
+                        name: p.name.clone(),
+                    })
+                    .collect();
+                update_peer(&self.peer.device_id, |peer| {
+                    peer.projects = offered;
+                    peer.last_sync = Some(chrono::Utc::now().to_rfc3339());
+                });
+                self.remote = projects;
+                Ok(self.reconcile())
+            }
+            Message::Fetch { sync_id, path } => Ok(self.read_file(&sync_id, &path).into_iter().collect()),
+            Message::File {
+                sync_id,
+                path,
+                content,
+                modified_ms,
+                hash,
+            } => {
+                if let Err(e) = self.apply_file(sync_id, path, &content, modified_ms, hash) {
+                    tracing::warn!("LAN sync: {}", e);
+                }
+                Ok(Vec::new())
+            }
+            Message::Event {
+                sync_id,
+                event,
+                payload,
+            } => {
+                if let Some(project) = self.project(&sync_id) {
+                    let _ = self.app.emit(
+                        "lan-sync-event",
+                        RemoteEventPayload {
+                            project_id: project.id.clone(),
+                            peer_name: self.peer.name.clone(),
+                            event,
+                            payload,
+                        },
+                    );
+                }
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// A local build event for the peer, if it belongs to a shared project.
+    fn forward(&self, event: BridgeEvent) -> Option<Message> {
+        if !FORWARDED_EVENTS.contains(&event.event.as_str()) {
+            return None;
+        }
+        let project_id = event.project_id?;
+        let project = self.shared.iter().find(|p| p.id == project_id)?;
+        Some(Message::Event {
+            sync_id: project.sync_id.clone(),
+            event: event.event,
+            payload: event.payload,
+        })
+    }
+}
+
+async fn run_session(
+    app: AppHandle,
+    me: Identity,
+    peer: Peer,
+    mut reader: BufReader<OwnedReadHalf>,
+    mut writer: OwnedWriteHalf,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let device_id = peer.device_id.clone();
+    let peer_name = peer.name.clone();
+    update_peer(&device_id, |info| {
+        info.name = peer_name.clone();
+        info.sessions += 1;
+        info.error = None;
+    });
+    tracing::info!("LAN sync: connected to {}", peer_name);
+
+    // Lines are read on their own task so a half-read line survives select!
+    let (line_tx, mut lines) = mpsc::channel::<String>(64);
+    let reader_task = tokio::spawn(async move {
+        loop {
+            match read_line(&mut reader, MAX_FRAME_BYTES).await {
+                Ok(Some(line)) => {
+                    if line_tx.send(line).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::warn!("LAN sync: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut events = event_bridge::subscribe();
+    let mut session = Session {
+        app,
+        me,
+        peer,
+        send_seq: 0,
+        recv_seq: 0,
+        shared: Vec::new(),
+        sent: None,
+        remote: Vec::new(),
+        bases: HashMap::new(),
+        pending: HashSet::new(),
+    };
+    let mut ticker = tokio::time::interval(SCAN_INTERVAL);
+    let result: Result<(), String> = async {
+        loop {
+            let outgoing = tokio::select! {
+                _ = shutdown.changed() => return Ok(()),
+                line = lines.recv() => {
+                    let Some(line) = line else {
+                        return Ok(());
+                    };
+                    let message = session.open(&line)?;
+                    session.receive(message)?
+                }
+                _ = ticker.tick() => {
+                    let mut outgoing: Vec<Message> =
+                        session.scan().map(|projects| Message::Manifest { projects }).into_iter().collect();
+                    outgoing.extend(session.reconcile());
+                    outgoing
+                }
+                event = events.recv() => match event {
+                    Ok(event) => session.forward(event).into_iter().collect(),
+                    Err(broadcast::error::RecvError::Lagged(_)) => Vec::new(),
+                    Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                },
+            };
+            for message in &outgoing {
+                session.send(&mut writer, message).await?;
+            }
+        }
+    }
+    .await;
+
+    reader_task.abort();
+    if let Err(e) = &result {
+        tracing::warn!("LAN sync: {}: {}", peer_name, e);
+    }
+    update_peer(&device_id, |info| {
+        info.sessions = info.sessions.saturating_sub(1);
+        info.error = result.err();
+    });
+    tracing::info!("LAN sync: disconnected from {}", peer_name);
+}
+
+async fn handle_incoming(app: AppHandle, me: Identity, stream: TcpStream, shutdown: watch::Receiver<bool>) {
+    let (read, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    match tokio::time::timeout(HANDSHAKE_TIMEOUT, accept_handshake(&mut reader, &mut writer, &me)).await {
+        Ok(Ok(peer)) => run_session(app, me, peer, reader, writer, shutdown).await,
+        Ok(Err(e)) => tracing::warn!("LAN sync: refused a connection: {}", e),
+        Err(_) => tracing::warn!("LAN sync: a connection timed out during the handshake"),
+    }
+}
+
+async fn dial(app: AppHandle, me: Identity, device_id: String, address: SocketAddr, shutdown: watch::Receiver<bool>) {
+    let connected = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
+        let stream = TcpStream::connect(address)
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+        let (read, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(read);
+        let peer = dial_handshake(&mut reader, &mut writer, &me).await?;
+        Ok::<_, String>((peer, reader, writer))
+    })
+    .await
+    .unwrap_or_else(|_| Err(format!("Timed out connecting to {}", address)));
+
+    update_peer(&device_id, |peer| peer.dialing = false);
+    match connected {
+        Ok((peer, reader, writer)) => run_session(app, me, peer, reader, writer, shutdown).await,
+        Err(e) => {
+            tracing::debug!("LAN sync: {}", e);
+            update_peer(&device_id, |peer| peer.error = Some(e));
+        }
+    }
+}
+
+/// Connects to the peers this machine is responsible for dialing: of any
+/// two machines, the one with the lower device ID dials, so each pair has
+/// one connection.
+fn dial_peers(app: &AppHandle, me: &Identity, shutdown: &watch::Receiver<bool>) {
+    let Ok(mut peers) = PEERS.lock() else {
+        return;
+    };
+    for peer in peers.values_mut() {
+        if peer.sessions > 0 || peer.dialing || me.device_id >= peer.device_id {
+            continue;
+        }
+        let Some(address) = peer.address.as_deref().and_then(|a| a.parse::<SocketAddr>().ok()) else {
+            continue;
+        };
+        peer.dialing = true;
+        tauri::async_runtime::spawn(dial(
+            app.clone(),
+            me.clone(),
+            peer.device_id.clone(),
+            address,
+            shutdown.clone(),
+        ));
+    }
+}
+
+fn advertise(me: &Identity, port: u16) -> Result<ServiceDaemon, String> {
+    let mdns = ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS: {}", e))?;
+    let host: String = me
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+        .collect();
+    let properties = [
+        ("id", me.device_id.as_str()),
+        ("name", me.name.as_str()),
+        ("v", "1"),
+    ];
+    let info = ServiceInfo::new(SERVICE_TYPE, &me.device_id, &format!("{}.local.", host), "", port, &properties[..])
+        .map_err(|e| format!("Failed to describe the sync service: {}", e))?
+        .enable_addr_auto();
+    mdns.register(info)
+        .map_err(|e| format!("Failed to advertise the sync service: {}", e))?;
+    Ok(mdns)
+}
+
+/// Records the peers mDNS finds, on a thread of its own since the browse
+/// channel blocks.
+fn discover(app: AppHandle, me: Identity, mdns: ServiceDaemon, shutdown: watch::Receiver<bool>) -> Result<(), String> {
+    let found = mdns
+        .browse(SERVICE_TYPE)
+        .map_err(|e| format!("Failed to browse for peers: {}", e))?;
+    std::thread::spawn(move || {
+        while !*shutdown.borrow() {
+            let Ok(event) = found.recv_timeout(Duration::from_secs(1)) else {
+                continue;
+            };
+            let ServiceEvent::ServiceResolved(info) = event else {
+                continue;
+            };
+            let Some(device_id) = info.get_property_val_str("id").map(str::to_string) else {
+                continue;
+            };
+            if device_id == me.device_id {
+                continue;
+            }
+            let name = info.get_property_val_str("name").unwrap_or(device_id.as_str()).to_string();
+            let addresses = info.get_addresses();
+            let address = addresses
+                .iter()
+                .find(|ip| ip.is_ipv4())
+                .or_else(|| addresses.iter().next())
+                .map(|ip| SocketAddr::new(*ip, info.get_port()).to_string());
+            update_peer(&device_id, |peer| {
+                peer.name = name;
+                peer.address = address;
+                peer.last_seen = chrono::Utc::now().to_rfc3339();
+            });
+            dial_peers(&app, &me, &shutdown);
+        }
+    });
+    Ok(())
+}
+
+async fn serve(app: AppHandle, me: Identity, listener: TcpListener, mut shutdown: watch::Receiver<bool>) {
+    let mut redial = tokio::time::interval(DIAL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            _ = redial.tick() => dial_peers(&app, &me, &shutdown),
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_incoming(app.clone(), me.clone(), stream, shutdown.clone()));
+                }
+                Err(e) => tracing::warn!("LAN sync: failed to accept a connection: {}", e),
+            },
+        }
+    }
+}
+
+fn running_port() -> Option<u16> {
+    SERVICE.lock().ok().and_then(|s| s.as_ref().map(|h| h.port))
+}
+
+async fn start(app: AppHandle, port: u16) -> Result<(), String> {
+    let me = identity(&app)?;
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .map_err(|e| format!("Failed to bind to port {}: {}", port, e))?;
+    let mdns = advertise(&me, port)?;
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    discover(app.clone(), me.clone(), mdns.clone(), shutdown_rx.clone())?;
+    tokio::spawn(serve(app, me, listener, shutdown_rx));
+
+    let mut service = SERVICE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    *service = Some(ServiceHandle {
+        port,
+        shutdown_tx,
+        mdns,
+    });
+    Ok(())
+}
+
+/// Stops LAN sync and disconnects from every peer.
+pub fn stop() {
+    if let Ok(mut service) = SERVICE.lock() {
+        if let Some(handle) = service.take() {
+            let _ = handle.shutdown_tx.send(true);
+            let _ = handle.mdns.shutdown();
+        }
+    }
+    if let Ok(mut peers) = PEERS.lock() {
+        peers.clear();
+    }
+}
+
+fn spawn_start(app: &AppHandle, port: u16) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match start(app, port).await {
+            Ok(()) => tracing::info!("LAN sync listening on port {}", port),
+            Err(e) => tracing::error!("LAN sync: {}", e),
+        }
+    });
+}
+
+/// Starts, stops, or restarts LAN sync to match preferences.
+pub fn apply_preferences(app: &AppHandle, prefs: &Preferences) {
+    if !prefs.lan_sync_enabled {
+        stop();
+        return;
+    }
+    if running_port() == Some(prefs.lan_sync_port) {
+        return;
+    }
+    stop();
+    spawn_start(app, prefs.lan_sync_port);
+}
+
+/// Reconnects with a new key.
+fn restart(app: &AppHandle) {
+    if let Some(port) = running_port() {
+        stop();
+        spawn_start(app, port);
+    }
+}
+
+/// Returns whether LAN sync is running, this machine's sync key, its peers,
+/// and the conflicts found so far.
+#[tauri::command]
+pub fn get_lan_sync_status(app: AppHandle) -> Result<LanSyncStatus, String> {
+    let prefs = load_preferences_internal(&app).unwrap_or_default();
+    let mut peers: Vec<PeerInfo> = PEERS.lock().map_err(|e| format!("Lock error: {}", e))?.values().cloned().collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(LanSyncStatus {
+        running: running_port().is_some(),
+        port: running_port().unwrap_or(prefs.lan_sync_port),
+        device_id: secrets::get_secret(&app, DEVICE_ID_SECRET_KEY)?,
+        device_name: device_name(),
+        key: secrets::get_secret(&app, KEY_SECRET_KEY)?,
+        peers,
+        conflicts: CONFLICTS.lock().map_err(|e| format!("Lock error: {}", e))?.clone(),
+    })
+}
+
+/// Replaces the sync key. Other machines need the new one to connect.
+#[tauri::command]
+pub fn regenerate_lan_sync_key(app: AppHandle) -> Result<LanSyncStatus, String> {
+    secrets::set_secret(app.clone(), KEY_SECRET_KEY.to_string(), new_nonce())?;
+    restart(&app);
+    get_lan_sync_status(app)
+}
+
+/// Uses the sync key copied from another machine.
+#[tauri::command]
+pub fn set_lan_sync_key(app: AppHandle, key: String) -> Result<LanSyncStatus, String> {
+    let key = key.trim().to_string();
+    if key.len() < 16 {
+        return Err("The sync key must be at least 16 characters".to_string());
+    }
+    secrets::set_secret(app.clone(), KEY_SECRET_KEY.to_string(), key)?;
+    restart(&app);
+    get_lan_sync_status(app)
+}
+
+/// Marks a project for LAN sync, or stops syncing it. Returns its sync ID.
+#[tauri::command(rename_all = "camelCase")]
+pub fn set_project_lan_sync(project_path: String, enabled: bool) -> Result<Option<String>, String> {
+    let current = get_project_metadata(project_path.clone(), Some(SYNC_ID_KEY.to_string()))?;
+    if !enabled {
+        set_project_metadata(project_path, SYNC_ID_KEY.to_string(), Value::Null)?;
+        return Ok(None);
+    }
+    if let Some(sync_id) = current.as_str().filter(|id| !id.is_empty()) {
+        return Ok(Some(sync_id.to_string()));
+    }
+    let sync_id = uuid::Uuid::new_v4().to_string();
+    set_project_metadata(project_path, SYNC_ID_KEY.to_string(), Value::String(sync_id.clone()))?;
+    Ok(Some(sync_id))
+}
+
+/// Pairs a project with one a peer offers, for copies of a project that
+/// were set up separately on each machine.
+#[tauri::command(rename_all = "camelCase")]
+pub fn link_lan_sync_project(project_path: String, sync_id: String) -> Result<(), String> {
+    if sync_id.trim().is_empty() {
+        return Err("Sync ID is required".to_string());
+    }
+    set_project_metadata(project_path, SYNC_ID_KEY.to_string(), Value::String(sync_id))
+}
+
+/// Removes a conflict from the list. The saved copy stays on disk.
+#[tauri::command]
+pub fn dismiss_lan_sync_conflict(id: String) -> Result<(), String> {
+    CONFLICTS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .retain(|c| c.id != id);
+    Ok(())
+}
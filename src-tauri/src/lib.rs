@@ -1,42 +1,200 @@
 //! Ideate - A desktop app for managing AI coding agent workflows.
 //!
-//! This is the main library crate that orchestrates all modules.
+//! This is the main library crate that orchestrates all modules. Built
+//! without the `gui` feature it holds only what the headless `ideate` CLI
+//! needs, with no Tauri.
 
 // Module declarations
+#[cfg(feature = "gui")]
+mod agent_updates;
 mod agents;
+#[cfg(feature = "gui")]
+mod allowlist;
+#[cfg(feature = "gui")]
+mod app_data;
+#[cfg(feature = "gui")]
+mod backlog_import;
+mod bindings;
+mod cli;
+#[cfg(feature = "gui")]
+mod control_api;
+mod coverage;
+#[cfg(feature = "gui")]
+mod cost_attribution;
+#[cfg(feature = "gui")]
+mod cost_capture;
+#[cfg(feature = "gui")]
+mod crash;
+mod currency;
+#[cfg(feature = "gui")]
+mod deep_link;
+#[cfg(feature = "gui")]
+mod dev_server;
+#[cfg(feature = "gui")]
+mod diagnostics;
+#[cfg(feature = "gui")]
+mod e2e;
+mod editors;
+#[cfg(feature = "gui")]
+mod event_bridge;
+#[cfg(feature = "gui")]
+mod event_bus;
+#[cfg(feature = "gui")]
+mod external_process;
+mod hooks;
+mod file_lock;
+#[cfg(feature = "gui")]
+mod file_open;
+#[cfg(feature = "gui")]
+mod file_tail;
+mod git_binary;
+#[cfg(feature = "gui")]
+mod idea_jobs;
+#[cfg(feature = "gui")]
 mod ideas;
+#[cfg(feature = "gui")]
+mod insights;
+#[cfg(feature = "gui")]
 mod integrations;
+#[cfg(feature = "gui")]
+mod lan_sync;
+mod log_compression;
+#[cfg(feature = "gui")]
+mod log_search;
+#[cfg(feature = "gui")]
+mod logging;
+#[cfg(feature = "gui")]
 mod macos;
+#[cfg(feature = "gui")]
+mod mcp;
+mod meta_history;
+mod migrations;
 mod models;
+mod notifier;
+mod orchestrator;
+mod perf;
+#[cfg(feature = "gui")]
+mod permissions;
+#[cfg(feature = "gui")]
+mod plugins;
+mod prd_recovery;
 mod preferences;
+#[cfg(feature = "gui")]
+mod preview_proxy;
+#[cfg(feature = "gui")]
 mod preview_server;
+#[cfg(feature = "gui")]
+mod pricing;
 mod process;
+#[cfg(feature = "gui")]
+mod progress;
+#[cfg(feature = "gui")]
+mod prompt_library;
+mod prompt_templates;
+#[cfg(feature = "gui")]
+mod project_bundle;
+#[cfg(feature = "gui")]
+mod project_clone;
+mod project_env;
+mod project_registry;
 mod projects;
+mod redact;
+mod scheduler;
+#[cfg(feature = "gui")]
+mod screenshot;
+mod secrets;
+#[cfg(feature = "gui")]
+mod spectator;
+#[cfg(feature = "gui")]
+mod spending;
 mod stacks;
+#[cfg(feature = "gui")]
+mod story_ids;
+#[cfg(feature = "gui")]
+mod sync_conflicts;
+#[cfg(feature = "gui")]
 mod terminal;
+#[cfg(feature = "gui")]
+mod terminal_audit;
+#[cfg(feature = "gui")]
+mod telemetry;
+mod throttle;
+#[cfg(feature = "gui")]
+mod tray;
+#[cfg(feature = "gui")]
 mod ui_state;
+#[cfg(feature = "gui")]
+mod updater;
 mod usage;
+#[cfg(feature = "gui")]
+mod usage_index;
+#[cfg(feature = "gui")]
+mod usage_inspect;
 mod utils;
+mod verification;
+#[cfg(feature = "gui")]
+mod webhooks;
 mod worktree;
 
+#[cfg(feature = "gui")]
 use tauri::Emitter;
+#[cfg(feature = "gui")]
+use tauri_plugin_deep_link::DeepLinkExt;
 
 // Re-export models for use by other modules
 pub use models::*;
 
+/// Runs the MCP server over stdio instead of the GUI. Returns the process exit code.
+#[cfg(feature = "gui")]
+pub fn run_mcp_stdio(args: &[String]) -> i32 {
+    mcp::run_stdio(args)
+}
+
+/// Runs a headless CLI command. Returns the process exit code.
+pub fn run_cli(args: &[String]) -> i32 {
+    cli::run(args)
+}
+
 /// Main entry point for the Tauri application.
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     use tauri::menu::{AboutMetadata, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
     use tauri::RunEvent;
 
     tauri::Builder::default()
+        // Must be registered first: a second launch (e.g. from a file
+        // association) hands its arguments to the running instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            file_open::open_from_args(app, &args);
+            tray::show_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
         .setup(|app| {
+            if let Err(e) = logging::init(app.handle()) {
+                eprintln!("{}", e);
+            }
+            crash::install(app.handle());
+            throttle::install(app.handle());
+            let prefs = preferences::load_preferences_internal(app.handle()).unwrap_or_default();
+            if let Err(e) = redact::configure(&prefs) {
+                tracing::warn!("Redaction: {}", e);
+            }
+            terminal_audit::configure(&prefs);
+            process::configure(&prefs);
+            git_binary::init(app.handle());
+            git_binary::configure(&prefs);
+            // Find git now so the first build doesn't wait on it
+            std::thread::spawn(|| {
+                git_binary::detect(false);
+            });
+            log_compression::start(app.handle(), &prefs);
             macos::apply_icon_from_preferences(&app.handle());
 
             // Create custom menu item for welcome guide
@@ -130,17 +288,58 @@ pub fn run() {
             // This is a workaround for a bug in tao/macOS 26 where the fullscreen transition
             // crashes when trying to capture a window snapshot
             macos::disable_native_fullscreen(&app.handle());
+
+            tray::init(app.handle())?;
+
+            // Files passed on launch by Windows and Linux file associations
+            file_open::open_from_args(app.handle(), &std::env::args().collect::<Vec<_>>());
+
+            // ideate:// links, including the one that launched the app
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            app.deep_link().register_all()?;
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| deep_link::handle_urls(&handle, &event.urls()));
+            if let Some(urls) = app.deep_link().get_current()? {
+                deep_link::handle_urls(app.handle(), &urls);
+            }
+
+            // Check builtin and custom stacks against registry versions
+            stacks::spawn_stack_update_check(app.handle().clone());
+            agent_updates::spawn_agent_update_watcher(app.handle().clone());
+            telemetry::spawn_uploader(app.handle().clone());
+            currency::spawn_rate_refresher(app.handle().clone());
+            event_bridge::init(app.handle());
+            std::thread::spawn(usage_index::start_watcher);
+            let plugin_handle = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = plugins::discover(&plugin_handle) {
+                    tracing::warn!("Plugins: {}", e);
+                }
+            });
+            let startup_prefs = preferences::load_preferences_internal(app.handle()).unwrap_or_default();
+            control_api::apply_preferences(app.handle(), &startup_prefs);
+            lan_sync::apply_preferences(app.handle(), &startup_prefs);
+            preferences::start_watcher(app.handle().clone());
             
             Ok(())
         })
+        .on_window_event(tray::handle_window_event)
         .invoke_handler(tauri::generate_handler![
             // Projects
             projects::create_project,
             projects::import_project,
             projects::load_projects,
-            projects::save_projects,
+            projects::add_project,
+            projects::update_project,
+            projects::remove_project,
+            projects::reorder_projects,
             projects::load_prd,
             projects::save_prd,
+            prd_recovery::recover_prd,
+            story_ids::check_story_ids,
+            story_ids::fix_story_ids,
+            backlog_import::preview_backlog_import,
+            backlog_import::import_backlog,
             projects::load_project_idea,
             projects::save_project_idea,
             projects::load_design,
@@ -151,6 +350,9 @@ pub fn run() {
             projects::list_directory,
             projects::load_project_settings,
             projects::save_project_settings,
+            projects::get_project_metadata,
+            projects::set_project_metadata,
+            projects::merge_project_metadata,
             projects::load_project_state,
             projects::save_project_state,
             projects::load_cost_history,
@@ -160,43 +362,217 @@ pub fn run() {
             preferences::save_preferences,
             preferences::set_app_icon_command,
             preferences::open_full_disk_access_settings,
+            permissions::check_permissions,
+            // Prompts
+            prompt_templates::render_prompt,
+            prompt_templates::build_story_prompt,
+            prompt_library::list_library_prompts,
+            prompt_library::get_library_prompt,
+            prompt_library::save_library_prompt,
+            prompt_library::delete_library_prompt,
+            prompt_library::rollback_library_prompt,
+            prompt_library::rollback_prompts_since,
+            // Notifier
+            notifier::load_notifier_config,
+            notifier::save_notifier_config,
+            notifier::send_build_notification,
+            notifier::test_webhook,
+            // Webhooks
+            webhooks::list_webhooks,
+            webhooks::register_webhook,
+            webhooks::update_webhook,
+            webhooks::delete_webhook,
+            webhooks::ping_webhook,
+            webhooks::dispatch_webhook_event,
+            // Secrets
+            secrets::set_secret,
+            secrets::delete_secret,
+            secrets::has_secret,
+            // Project environment
+            project_env::load_project_env,
+            project_env::save_project_env,
+            project_env::sync_project_env,
+            // Project bundles
+            project_bundle::export_project_bundle,
+            project_bundle::import_project_bundle,
+            project_bundle::inspect_project_bundle,
             // Ideas
             ideas::load_ideas,
             ideas::save_ideas,
+            idea_jobs::queue_idea_expansions,
+            idea_jobs::list_idea_jobs,
+            idea_jobs::cancel_idea_job,
+            idea_jobs::clear_finished_idea_jobs,
             // Agents
             agents::list_agents,
             agents::detect_agents,
+            agent_updates::check_agent_updates,
+            agent_updates::get_agent_updates,
             // Usage
             usage::load_amp_usage,
             usage::load_claude_usage,
+            usage::load_all_usage,
+            usage_inspect::inspect_amp_thread,
+            usage_inspect::inspect_claude_session,
             usage::get_recent_amp_thread_duration,
             usage::get_recent_claude_session_duration,
+            throttle::get_provider_throttles,
             // Process management
             process::spawn_agent,
+            process::spawn_shell_command,
+            allowlist::approve_executable,
             process::wait_agent,
             process::kill_agent,
             process::save_process_log,
             process::save_process_history_entry,
             process::load_process_history,
             process::read_process_log_file,
+            process::get_log_file_info,
+            log_search::search_process_logs,
+            external_process::attach_external_process,
+            external_process::list_external_processes,
+            external_process::detach_external_process,
+            file_tail::tail_file,
+            file_tail::ack_tail_file,
+            file_tail::stop_tail_file,
+            event_bus::replay_events,
+            // Build orchestration
+            orchestrator::start_build,
+            orchestrator::stop_build,
+            orchestrator::pause_build,
+            orchestrator::resume_build,
+            orchestrator::get_build_status,
+            orchestrator::list_active_builds,
+            orchestrator::get_interrupted_build,
+            orchestrator::recover_build,
+            orchestrator::discard_interrupted_build,
+            orchestrator::modes::list_build_modes,
+            orchestrator::plan::plan_build,
+            orchestrator::batch::start_batch_build,
+            orchestrator::batch::stop_batch_build,
+            orchestrator::batch::list_batch_builds,
+            orchestrator::batch::list_batch_reports,
+            orchestrator::preflight::run_preflight,
+            orchestrator::forecast::forecast_costs,
+            orchestrator::report::get_build_report,
+            orchestrator::report::list_build_reports,
+            orchestrator::journal::read_build_events,
+            orchestrator::timings::get_story_timings,
+            coverage::get_coverage_trend,
+            // Opening projects from the OS
+            file_open::handle_open_file,
+            file_open::take_pending_open_projects,
+            deep_link::take_pending_deep_links,
+            // Updates
+            updater::check_for_update,
+            updater::download_update,
+            updater::install_update,
+            // Local control API
+            control_api::get_control_api_info,
+            control_api::regenerate_control_api_token,
+            // LAN sync
+            lan_sync::get_lan_sync_status,
+            lan_sync::regenerate_lan_sync_key,
+            lan_sync::set_lan_sync_key,
+            lan_sync::set_project_lan_sync,
+            lan_sync::link_lan_sync_project,
+            lan_sync::dismiss_lan_sync_conflict,
+            // Spectator
+            spectator::start_spectator_server,
+            spectator::get_spectator_server,
+            spectator::stop_spectator_server,
+            // Telemetry
+            telemetry::get_pending_telemetry,
+            telemetry::clear_pending_telemetry,
+            telemetry::record_feature_usage,
+            // Logs and crash reports
+            logging::query_app_logs,
+            plugins::list_plugins,
+            plugins::reload_plugins,
+            plugins::invoke_plugin_command,
+            crash::get_crash_reports,
+            crash::delete_crash_report,
+            crash::submit_crash_report,
+            diagnostics::generate_diagnostics_bundle,
+            perf::get_performance_report,
+            app_data::export_app_data,
+            app_data::import_app_data,
+            app_data::get_app_data_location,
+            app_data::move_app_data,
+            sync_conflicts::detect_data_conflicts,
+            sync_conflicts::resolve_data_conflict,
+            pricing::load_pricing,
+            pricing::set_model_price,
+            pricing::delete_model_price,
+            pricing::reset_pricing,
+            pricing::recompute_costs,
+            spending::generate_spending_report,
+            insights::get_build_insights,
+            cost_attribution::reconcile_build_costs,
+            currency::get_display_currency,
+            currency::refresh_exchange_rates,
             // Integrations - OutRay
             integrations::outray::get_sidecar_path,
             integrations::outray::get_auth_token,
             integrations::outray::login,
             integrations::outray::check_auth,
             integrations::outray::open_dashboard,
+            // Integrations - Tunnels
+            integrations::tunnels::list_tunnel_providers,
+            integrations::tunnels::set_tunnel_auth_token,
+            integrations::tunnels::start_tunnel,
+            integrations::tunnels::stop_tunnel,
+            integrations::tunnels::get_tunnel_status,
+            integrations::tunnels::stop_project_tunnels,
+            integrations::tunnels::list_project_tunnels,
+            integrations::mobile_preview::get_mobile_preview,
+            // Integrations - GitHub
+            integrations::github::github_push_stories,
+            integrations::github::github_pull_story_status,
+            integrations::github::load_github_sync_state,
+            // Integrations - Linear
+            integrations::linear::linear_list_projects,
+            integrations::linear::import_from_linear,
+            integrations::linear::linear_report_progress,
+            integrations::linear::load_linear_sync_state,
+            // Integrations - Jira
+            integrations::jira::import_from_jira,
+            // Integrations - Anthropic
+            integrations::anthropic::anthropic_usage,
+            // Integrations - OpenAI
+            integrations::openai::openai_usage,
+            // Integrations - Deploy
+            integrations::deploy::deploy_preview,
+            // Integrations - Docker
+            integrations::docker::docker_status,
+            integrations::docker::load_docker_config,
+            integrations::docker::get_container_spec,
+            integrations::docker::docker_build_image,
+            integrations::docker::docker_start_container,
+            integrations::docker::docker_stop_container,
+            integrations::docker::docker_container_status,
             // Terminal
             terminal::spawn_terminal,
+            terminal::spawn_agent_interactive,
             terminal::write_terminal,
             terminal::resize_terminal,
             terminal::kill_terminal,
+            terminal::search_terminal_buffer,
+            terminal::get_terminal_cwd,
+            terminal_audit::query_terminal_audit,
             // UI State
             ui_state::load_ui_state,
             ui_state::save_ui_state,
             ui_state::save_panel_states,
             ui_state::save_window_state,
+            ui_state::save_current_window_state,
+            ui_state::restore_current_window_state,
+            ui_state::save_layout_preset,
+            ui_state::list_layout_presets,
+            ui_state::apply_layout_preset,
             ui_state::open_process_viewer_command,
             ui_state::open_story_manager_command,
+            ui_state::open_app_window,
             ui_state::open_project_window,
             // Worktree
             worktree::prepare_story_worktree,
@@ -206,6 +582,8 @@ pub fn run() {
             worktree::delete_story_branch,
             worktree::checkout_story_branch,
             worktree::force_merge_story_branch,
+            worktree::get_worktree_usage,
+            worktree::prune_worktrees,
             // Snapshot/Rollback
             worktree::create_story_snapshot,
             worktree::rollback_story_changes,
@@ -214,13 +592,22 @@ pub fn run() {
             // Git commit/rollback for stories
             worktree::check_git_initialized,
             worktree::init_git_repo,
+            git_binary::detect_git,
             worktree::git_commit_story,
             worktree::git_rollback_last_commit,
             worktree::git_discard_changes,
             // Conflict resolution
             worktree::analyze_merge_conflicts,
             worktree::merge_with_resolutions,
+            worktree::get_merge_preview,
+            worktree::apply_manual_resolution,
             worktree::abort_merge,
+            // Metadata history
+            meta_history::get_metadata_history_config,
+            meta_history::enable_metadata_history,
+            meta_history::disable_metadata_history,
+            meta_history::get_metadata_history,
+            meta_history::read_metadata_snapshot,
             // Utils
             utils::write_binary_file,
             utils::list_project_files,
@@ -230,20 +617,68 @@ pub fn run() {
             preview_server::start_preview_server,
             preview_server::stop_preview_server,
             preview_server::get_preview_server_info,
+            // Editors
+            editors::detect_editors,
+            editors::open_in_editor,
+            // Dev server
+            dev_server::start_dev_server,
+            dev_server::stop_dev_server,
+            dev_server::get_dev_server_status,
+            dev_server::list_dev_servers,
+            dev_server::start_story_preview,
+            dev_server::stop_story_preview,
+            dev_server::list_story_previews,
+            // Preview proxy
+            preview_proxy::start_preview_proxy,
+            preview_proxy::stop_preview_proxy,
+            preview_proxy::get_preview_proxy_info,
+            // Screenshots
+            screenshot::capture_preview_screenshot,
+            screenshot::list_story_screenshots,
+            // MCP server
+            mcp::start_mcp_server,
+            mcp::stop_mcp_server,
+            mcp::get_mcp_server_info,
             // Stacks
             stacks::load_stacks,
             stacks::save_stacks,
-            stacks::delete_stack
+            stacks::delete_stack,
+            stacks::check_stack_updates,
+            stacks::detect_project_stack,
+            stacks::get_project_stack
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app_handle, event| {
-            if let RunEvent::Exit = event {
+        .run(|_app_handle, event| match event {
+            // macOS delivers opened files as Apple Events rather than arguments
+            #[cfg(target_os = "macos")]
+            RunEvent::Opened { urls } => {
+                for path in urls.iter().filter_map(|url| url.to_file_path().ok()) {
+                    if let Err(e) = file_open::open_path(_app_handle, &path) {
+                        tracing::warn!("Open file: {}", e);
+                    }
+                }
+            }
+            RunEvent::Exit => {
+                // Flag running builds to stop before their agents are killed
+                orchestrator::stop_all_builds();
                 // Kill all spawned processes when the app exits
                 process::kill_all_processes();
                 // Stop all preview servers
                 preview_server::stop_all_servers();
+                // Stop all tunnels
+                integrations::tunnels::stop_all_tunnels();
+                // Stop all dev servers
+                dev_server::stop_all_dev_servers();
+                // Stop all preview proxies
+                preview_proxy::stop_all_proxies();
+                // Stop all MCP servers
+                mcp::stop_all_mcp_servers();
+                control_api::stop();
+                lan_sync::stop();
+                spectator::stop_all();
             }
+            _ => {}
         });
 }
 
@@ -0,0 +1,135 @@
+//! Gzip compression of old process logs.
+//!
+//! At startup, process logs older than the `compressLogsAfterDays` preference
+//! are compressed in place, `<name>.log` becoming `<name>.log.gz`. Process
+//! history keeps pointing at the `.log` path; readers go through `open_log`,
+//! which falls back to the compressed copy.
+
+#[cfg(feature = "gui")]
+use std::fs;
+#[cfg(feature = "gui")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "gui")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "gui")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "gui")]
+use flate2::Compression;
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
+use crate::models::Preferences;
+
+#[cfg(feature = "gui")]
+const GZ_EXTENSION: &str = "gz";
+
+/// Where a log's content lives if it was compressed: the path itself if it
+/// names a `.gz` file, or its `.gz` sibling if the original is gone.
+#[cfg(feature = "gui")]
+pub fn compressed_path(path: &Path) -> Option<PathBuf> {
+    if path.extension().is_some_and(|ext| ext == GZ_EXTENSION) {
+        return Some(path.to_path_buf());
+    }
+    if path.exists() {
+        return None;
+    }
+    let mut name = path.file_name()?.to_os_string();
+    name.push(".");
+    name.push(GZ_EXTENSION);
+    let gz_path = path.with_file_name(name);
+    gz_path.exists().then_some(gz_path)
+}
+
+/// Opens a process log for reading, decompressing it if it was compressed.
+#[cfg(feature = "gui")]
+pub fn open_log(path: &Path) -> io::Result<Box<dyn Read + Send>> {
+    match compressed_path(path) {
+        Some(gz_path) => Ok(Box::new(GzDecoder::new(fs::File::open(gz_path)?))),
+        None => Ok(Box::new(fs::File::open(path)?)),
+    }
+}
+
+/// Reads a whole compressed log into memory.
+#[cfg(feature = "gui")]
+pub fn read_compressed(gz_path: &Path) -> io::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    GzDecoder::new(fs::File::open(gz_path)?).read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Process logs are named `<timestamp>_<type>_<label>_<id>.log`; the app's
+/// own logs in the same directory are left to their rotation.
+#[cfg(feature = "gui")]
+fn is_process_log(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "log")
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[cfg(feature = "gui")]
+fn compress_file(path: &Path) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(GZ_EXTENSION);
+    let gz_path = path.with_file_name(&name);
+    name.push(".tmp");
+    let tmp_path = path.with_file_name(name);
+
+    let mut encoder = GzEncoder::new(fs::File::create(&tmp_path)?, Compression::default());
+    io::copy(&mut fs::File::open(path)?, &mut encoder)?;
+    encoder.finish()?.flush()?;
+    fs::rename(&tmp_path, &gz_path)?;
+    fs::remove_file(path)
+}
+
+/// Compresses process logs in `logs_dir` last modified more than `max_age`
+/// ago. Returns how many were compressed.
+#[cfg(feature = "gui")]
+pub fn compress_old_logs(logs_dir: &Path, max_age: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(logs_dir) else {
+        return 0;
+    };
+    let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut compressed = 0;
+    for path in entries.flatten().map(|entry| entry.path()).filter(|p| is_process_log(p)) {
+        let old = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if !old {
+            continue;
+        }
+        match compress_file(&path) {
+            Ok(()) => compressed += 1,
+            Err(e) => tracing::warn!("Failed to compress {}: {}", path.display(), e),
+        }
+    }
+    compressed
+}
+
+/// Compresses old process logs in the background, per the preferences.
+#[cfg(feature = "gui")]
+pub fn start(app: &AppHandle, prefs: &Preferences) {
+    if prefs.compress_logs_after_days == 0 {
+        return;
+    }
+    let Ok(logs_dir) = app_data_dir(app).map(|dir| dir.join("logs")) else {
+        return;
+    };
+    let max_age = Duration::from_secs(u64::from(prefs.compress_logs_after_days) * 24 * 60 * 60);
+    std::thread::spawn(move || {
+        let compressed = compress_old_logs(&logs_dir, max_age);
+        if compressed > 0 {
+            tracing::info!("Compressed {} old process logs", compressed);
+        }
+    });
+}
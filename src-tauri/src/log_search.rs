@@ -0,0 +1,178 @@
+//! Full-text search across saved process logs.
+//!
+//! Scans the log files that a project's process history points to, newest
+//! run first, for lines containing the query (ignoring case), and returns
+//! each hit with its line number and the lines around it.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::log_compression;
+use crate::models::ProcessHistoryEntry;
+use crate::process::read_process_history;
+
+/// Lines of context kept on each side of a match.
+const CONTEXT_LINES: usize = 2;
+
+/// Matches returned when the caller doesn't set a limit.
+const DEFAULT_LIMIT: usize = 200;
+
+/// Longest line returned; longer ones are cut.
+const MAX_LINE_CHARS: usize = 500;
+
+/// Restricts a search to runs started in a range; either end may be open.
+/// Dates are RFC 3339 timestamps or `YYYY-MM-DD`, which covers the whole day.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchMatch {
+    pub process_id: String,
+    pub process_type: String,
+    pub label: String,
+    pub started_at: String,
+    pub log_file_path: String,
+    /// 1-based.
+    pub line_number: usize,
+    pub line: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchResult {
+    pub matches: Vec<LogSearchMatch>,
+    /// Log files read.
+    pub files_searched: usize,
+    /// Whether the search stopped at the limit, so there may be more.
+    pub truncated: bool,
+}
+
+/// Parses one end of a date range. A bare date is the start of that day, or
+/// with `end_of_day` the start of the next.
+fn parse_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", value))?;
+    let date = if end_of_day { date.succ_opt().unwrap_or(date) } else { date };
+    Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+fn truncate(line: &str) -> String {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// Searches one log file, adding at most `limit - matches.len()` matches.
+/// Unreadable files are skipped.
+fn search_file(entry: &ProcessHistoryEntry, path: &str, needle: &str, limit: usize, matches: &mut Vec<LogSearchMatch>) {
+    let Ok(file) = log_compression::open_log(Path::new(path)) else {
+        return;
+    };
+    let mut before: VecDeque<String> = VecDeque::with_capacity(CONTEXT_LINES);
+    // Matches still collecting lines after them
+    let mut open: Vec<usize> = Vec::new();
+
+    for (index, line) in BufReader::new(file).lines().map_while(Result::ok).enumerate() {
+        let line = truncate(&line);
+        open.retain(|&i| {
+            matches[i].context_after.push(line.clone());
+            matches[i].context_after.len() < CONTEXT_LINES
+        });
+
+        if matches.len() >= limit {
+            if open.is_empty() {
+                break;
+            }
+        } else if line.to_lowercase().contains(needle) {
+            open.push(matches.len());
+            matches.push(LogSearchMatch {
+                process_id: entry.process_id.clone(),
+                process_type: entry.process_type.clone(),
+                label: entry.label.clone(),
+                started_at: entry.started_at.clone(),
+                log_file_path: path.to_string(),
+                line_number: index + 1,
+                line: line.clone(),
+                context_before: before.iter().cloned().collect(),
+                context_after: Vec::new(),
+            });
+        }
+
+        if before.len() == CONTEXT_LINES {
+            before.pop_front();
+        }
+        before.push_back(line);
+    }
+}
+
+/// Searches the saved logs of a project's processes for `query`, ignoring
+/// case, newest run first. `date_range` limits the runs by start time.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn search_process_logs(
+    app: AppHandle,
+    project_id: String,
+    query: String,
+    date_range: Option<DateRange>,
+    limit: Option<usize>,
+) -> Result<LogSearchResult, String> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Err("Search query cannot be empty".to_string());
+    }
+    let range = date_range.unwrap_or_default();
+    let from = range.from.as_deref().map(|v| parse_bound(v, false)).transpose()?;
+    let to = range.to.as_deref().map(|v| parse_bound(v, true)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).max(1);
+    let app_data_dir = app_data_dir(&app)?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut entries: Vec<ProcessHistoryEntry> = read_process_history(&app_data_dir)?
+            .entries
+            .into_iter()
+            .filter(|e| e.project_id == project_id && e.log_file_path.is_some())
+            .filter(|e| {
+                let Ok(started) = DateTime::parse_from_rfc3339(&e.started_at) else {
+                    return from.is_none() && to.is_none();
+                };
+                from.is_none_or(|from| started >= from) && to.is_none_or(|to| started < to)
+            })
+            .collect();
+        entries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+
+        let mut matches = Vec::new();
+        let mut files_searched = 0;
+        for entry in &entries {
+            if matches.len() >= limit {
+                break;
+            }
+            if let Some(path) = entry.log_file_path.as_deref() {
+                files_searched += 1;
+                search_file(entry, path, &needle, limit, &mut matches);
+            }
+        }
+
+        Ok(LogSearchResult {
+            truncated: matches.len() >= limit,
+            matches,
+            files_searched,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,233 @@
+//! Application logging.
+//!
+//! Backend logs go through `tracing`. Events are written as JSON lines to
+//! daily-rotated files in `<app data>/logs/`, mirrored to stderr for
+//! development, and kept in the crash reporter's recent-log buffer.
+//! `query_app_logs` reads the files back so problems can be diagnosed from
+//! inside the app. Set `RUST_LOG` to change the level (default `info`).
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::app_data::app_data_dir;
+use crate::crash;
+
+const LOG_FILE_PREFIX: &str = "ideate";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Days of log files to keep.
+const MAX_LOG_FILES: usize = 7;
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+/// Flushes buffered log lines on exit; dropping it stops file logging.
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+fn get_logs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_data_dir(app)?.join("logs");
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    }
+    Ok(dir)
+}
+
+/// Collects an event's message and fields into one line.
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+/// Feeds events into the crash reporter's recent-log buffer.
+struct CrashBufferLayer;
+
+impl<S: Subscriber> Layer<S> for CrashBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let meta = event.metadata();
+        crash::record_log(format!(
+            "{} {}: {}{}",
+            meta.level(),
+            meta.target(),
+            visitor.message,
+            visitor.fields
+        ));
+    }
+}
+
+/// Installs the global subscriber. Called once during setup.
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(get_logs_dir(app)?)
+        .map_err(|e| format!("Failed to create log file: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let _ = GUARD.set(guard);
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(writer)
+                .with_filter(filter()),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(filter()),
+        )
+        .with(CrashBufferLayer.with_filter(filter()))
+        .try_init()
+        .map_err(|e| format!("Failed to initialize logging: {}", e))
+}
+
+/// A line from the log files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    /// Module path the event came from, e.g. `ideate_lib::orchestrator`.
+    pub target: String,
+    pub message: String,
+    /// Structured fields other than the message.
+    #[serde(default)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let mut value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let mut fields = match value.get_mut("fields").map(serde_json::Value::take) {
+        Some(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let message = match fields.remove("message") {
+        Some(serde_json::Value::String(s)) => s,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp: value.get("timestamp")?.as_str()?.to_string(),
+        level: value.get("level")?.as_str()?.to_string(),
+        target: value.get("target").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+        message,
+        fields,
+    })
+}
+
+/// The app's log files, oldest first.
+pub(crate) fn app_log_files(app: &AppHandle) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(get_logs_dir(app)?)
+        .map_err(|e| format!("Failed to read logs directory: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    // File names embed the date, so name order is chronological
+    files.sort();
+    Ok(files)
+}
+
+/// Searches the log files, oldest first, returning at most `limit` of the
+/// most recent matches. `level` is a minimum severity, `module` matches part
+/// of the target, `since` is an RFC 3339 timestamp, and `text` is matched
+/// case-insensitively against the message and fields.
+#[tauri::command]
+pub fn query_app_logs(
+    app: AppHandle,
+    level: Option<String>,
+    module: Option<String>,
+    since: Option<String>,
+    text: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = level
+        .map(|l| l.parse::<Level>().map_err(|_| format!("Invalid log level: {}", l)))
+        .transpose()?;
+    let since = since
+        .map(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .map_err(|e| format!("Invalid timestamp: {}", e))
+        })
+        .transpose()?;
+    let text = text.map(|t| t.to_lowercase()).filter(|t| !t.is_empty());
+
+    let mut entries = Vec::new();
+    for file in app_log_files(&app)? {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for entry in content.lines().filter_map(parse_line) {
+            // Level's ordering is by verbosity: TRACE > DEBUG > ... > ERROR
+            if let Some(min) = min_level {
+                if entry.level.parse::<Level>().map_or(true, |l| l > min) {
+                    continue;
+                }
+            }
+            if let Some(module) = &module {
+                if !entry.target.contains(module.as_str()) {
+                    continue;
+                }
+            }
+            if let Some(since) = since {
+                let after = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+                    .is_ok_and(|t| t.with_timezone(&chrono::Utc) >= since);
+                if !after {
+                    continue;
+                }
+            }
+            if let Some(text) = &text {
+                let haystack = format!("{} {:?}", entry.message, entry.fields).to_lowercase();
+                if !haystack.contains(text.as_str()) {
+                    continue;
+                }
+            }
+            entries.push(entry);
+        }
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(entries)
+}
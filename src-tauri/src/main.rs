@@ -1,5 +1,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // `Ideate --mcp --project <path>` serves project data to coding agents over stdio
+    if args.iter().any(|a| a == "--mcp") {
+        std::process::exit(ideate_lib::run_mcp_stdio(&args));
+    }
+
     ideate_lib::run()
 }
@@ -0,0 +1,494 @@
+//! MCP (Model Context Protocol) server for Ideate project data.
+//!
+//! Lets coding agents read and update Ideate state through structured tools
+//! instead of editing `.ideate/*.json` by hand. Two transports are supported:
+//!
+//! - stdio: `ideate --mcp --project <path>` speaks newline-delimited JSON-RPC
+//!   on stdin/stdout without starting the GUI.
+//! - HTTP: `start_mcp_server` serves the Streamable HTTP transport on
+//!   `http://127.0.0.1:<port>/mcp` while the app is running. Requests must
+//!   send `Authorization: Bearer <token>` with the token the server was
+//!   started with, and are rejected unless their `Host` is the server's own
+//!   address and any `Origin` is local, so web pages can't reach it through
+//!   DNS rebinding.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+use crate::control_api::{request_token, tokens_match};
+use crate::models::CostEntry;
+use crate::projects::{load_cost_history, load_design, load_prd, save_cost_history, update_prd};
+
+const PROTOCOL_VERSION: &str = "2025-03-26";
+
+const VALID_STATUSES: &[&str] = &["pending", "in-progress", "complete", "failed", "canceled"];
+
+lazy_static::lazy_static! {
+    static ref MCP_SERVERS: Mutex<HashMap<String, McpServerHandle>> = Mutex::new(HashMap::new());
+}
+
+struct McpServerHandle {
+    port: u16,
+    token: String,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerInfo {
+    pub project_path: String,
+    pub port: u16,
+    pub url: String,
+    /// Bearer token clients must send.
+    pub token: String,
+}
+
+impl McpServerHandle {
+    fn info(&self, project_path: &str) -> McpServerInfo {
+        McpServerInfo {
+            project_path: project_path.to_string(),
+            port: self.port,
+            url: format!("http://127.0.0.1:{}/mcp", self.port),
+            token: self.token.clone(),
+        }
+    }
+}
+
+/// What an HTTP request must match to reach the server.
+struct HttpAccess {
+    port: u16,
+    token: String,
+}
+
+/// Emitted when an MCP tool changes project data, so open views can reload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProjectUpdatedEvent {
+    pub project_path: String,
+    /// "prd" or "costs"
+    pub resource: String,
+}
+
+/// Project the server is bound to.
+struct McpContext {
+    project_path: String,
+    project_id: Option<String>,
+    app: Option<AppHandle>,
+}
+
+impl McpContext {
+    fn notify_updated(&self, resource: &str) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(
+                "mcp-project-updated",
+                McpProjectUpdatedEvent {
+                    project_path: self.project_path.clone(),
+                    resource: resource.to_string(),
+                },
+            );
+        }
+    }
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "get_prd",
+            "description": "Returns the project's PRD, including all user stories and their status.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "update_story_status",
+            "description": "Updates a story's status. Setting status to \"complete\" marks the story as passing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "storyId": { "type": "string" },
+                    "status": { "type": "string", "enum": VALID_STATUSES },
+                    "notes": { "type": "string", "description": "Replaces the story notes when provided." }
+                },
+                "required": ["storyId", "status"]
+            }
+        },
+        {
+            "name": "get_design",
+            "description": "Returns the project's design document, if one exists.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "append_cost_entry",
+            "description": "Records token usage and cost for work done on this project.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agentId": { "type": "string" },
+                    "description": { "type": "string" },
+                    "inputTokens": { "type": "integer" },
+                    "outputTokens": { "type": "integer" },
+                    "cost": { "type": "number" },
+                    "model": { "type": "string" },
+                    "durationMs": { "type": "integer" }
+                },
+                "required": ["agentId", "description"]
+            }
+        }
+    ])
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(|v| v.as_str())
+}
+
+fn call_tool(ctx: &McpContext, name: &str, args: &Value) -> Result<Value, String> {
+    match name {
+        "get_prd" => {
+            let prd = load_prd(ctx.project_path.clone())?;
+            serde_json::to_value(prd).map_err(|e| format!("Failed to serialize PRD: {}", e))
+        }
+        "get_design" => {
+            let design = load_design(ctx.project_path.clone())?;
+            serde_json::to_value(design).map_err(|e| format!("Failed to serialize design: {}", e))
+        }
+        "update_story_status" => {
+            let story_id = str_arg(args, "storyId").ok_or("Missing storyId")?;
+            let status = str_arg(args, "status").ok_or("Missing status")?;
+            if !VALID_STATUSES.contains(&status) {
+                return Err(format!("Invalid status: {}", status));
+            }
+
+            if load_prd(ctx.project_path.clone())?.is_none() {
+                return Err("No PRD found for this project".to_string());
+            }
+            let updated = update_prd(&ctx.project_path, |prd| {
+                let story = prd
+                    .user_stories
+                    .iter_mut()
+                    .find(|s| s.id == story_id)
+                    .ok_or_else(|| format!("Story not found: {}", story_id))?;
+
+                story.status = Some(status.to_string());
+                story.passes = status == "complete";
+                if let Some(notes) = str_arg(args, "notes") {
+                    story.notes = notes.to_string();
+                }
+                Ok(story.clone())
+            })?;
+            ctx.notify_updated("prd");
+
+            serde_json::to_value(updated).map_err(|e| format!("Failed to serialize story: {}", e))
+        }
+        "append_cost_entry" => {
+            let agent_id = str_arg(args, "agentId").ok_or("Missing agentId")?;
+            let description = str_arg(args, "description").ok_or("Missing description")?;
+            let input_tokens = args.get("inputTokens").and_then(|v| v.as_i64());
+            let output_tokens = args.get("outputTokens").and_then(|v| v.as_i64());
+            let total_tokens = match (input_tokens, output_tokens) {
+                (None, None) => None,
+                (i, o) => Some(i.unwrap_or(0) + o.unwrap_or(0)),
+            };
+
+            let entry = CostEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                project_id: ctx.project_id.clone().unwrap_or_default(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                agent_id: agent_id.to_string(),
+                description: description.to_string(),
+                input_tokens,
+                output_tokens,
+                total_tokens,
+                cost: args.get("cost").and_then(|v| v.as_f64()),
+                credits: None,
+                model: str_arg(args, "model").map(String::from),
+                thread_id: None,
+                duration_ms: args.get("durationMs").and_then(|v| v.as_i64()),
+            };
+
+            let mut history = load_cost_history(ctx.project_path.clone())?;
+            history.entries.push(entry.clone());
+            save_cost_history(ctx.project_path.clone(), history)?;
+            ctx.notify_updated("costs");
+
+            serde_json::to_value(entry).map_err(|e| format!("Failed to serialize cost entry: {}", e))
+        }
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+fn rpc_result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Handles one JSON-RPC message. Returns `None` for notifications.
+fn handle_message(ctx: &McpContext, message: &Value) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let response = match method {
+        "initialize" => rpc_result(
+            id,
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "ideate", "version": env!("CARGO_PKG_VERSION") }
+            }),
+        ),
+        "ping" => rpc_result(id, json!({})),
+        "tools/list" => rpc_result(id, json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+            // Tool failures are reported in the result so the agent can see them.
+            let result = match call_tool(ctx, name, &args) {
+                Ok(value) => json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string_pretty(&value).unwrap_or_default()
+                    }]
+                }),
+                Err(e) => json!({
+                    "content": [{ "type": "text", "text": e }],
+                    "isError": true
+                }),
+            };
+            rpc_result(id, result)
+        }
+        _ => rpc_error(id, -32601, &format!("Method not found: {}", method)),
+    };
+
+    Some(response)
+}
+
+/// Handles a single message or a batch array.
+fn handle_payload(ctx: &McpContext, payload: &Value) -> Option<Value> {
+    match payload {
+        Value::Array(messages) => {
+            let responses: Vec<Value> = messages
+                .iter()
+                .filter_map(|m| handle_message(ctx, m))
+                .collect();
+            if responses.is_empty() {
+                None
+            } else {
+                Some(Value::Array(responses))
+            }
+        }
+        message => handle_message(ctx, message),
+    }
+}
+
+/// Runs the MCP server over stdio until stdin closes.
+/// Arguments: `--project <path>` (defaults to the current directory) and
+/// optional `--project-id <id>` used when recording cost entries.
+pub fn run_stdio(args: &[String]) -> i32 {
+    let arg_value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    };
+
+    let project_path = arg_value("--project").unwrap_or_else(|| {
+        std::env::current_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| ".".to_string())
+    });
+
+    let ctx = McpContext {
+        project_path,
+        project_id: arg_value("--project-id"),
+        app: None,
+    };
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                // No logging is set up in stdio mode; stderr goes to the agent's logs
+                eprintln!("MCP: failed to read stdin: {}", e);
+                return 1;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(payload) => handle_payload(&ctx, &payload),
+            Err(e) => Some(rpc_error(Value::Null, -32700, &format!("Parse error: {}", e))),
+        };
+
+        if let Some(response) = response {
+            if writeln!(stdout, "{}", response).and_then(|_| stdout.flush()).is_err() {
+                return 1;
+            }
+        }
+    }
+
+    0
+}
+
+/// Whether `host` (a `Host` value or an origin's authority) names this
+/// machine, on `port` when one is given.
+fn is_local_host(host: &str, port: Option<u16>) -> bool {
+    let (name, host_port) = match host.rsplit_once(':') {
+        Some((name, host_port)) if !host_port.ends_with(']') => (name, host_port.parse::<u16>().ok()),
+        _ => (host, None),
+    };
+    matches!(name, "127.0.0.1" | "localhost" | "[::1]") && port.is_none_or(|port| host_port == Some(port))
+}
+
+/// Rejects requests from other hosts, from web pages that aren't local, and
+/// without the server's token.
+async fn require_local_client(State(access): State<Arc<HttpAccess>>, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let header_value = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    let host_ok = header_value(header::HOST).is_some_and(|host| is_local_host(host, Some(access.port)));
+    let origin_ok = header_value(header::ORIGIN).is_none_or(|origin| {
+        origin
+            .strip_prefix("http://")
+            .or_else(|| origin.strip_prefix("https://"))
+            .is_some_and(|authority| is_local_host(authority, None))
+    });
+    if !host_ok || !origin_ok {
+        return (StatusCode::FORBIDDEN, "Requests must come from this machine").into_response();
+    }
+    if !request_token(&request).is_some_and(|token| tokens_match(&token, &access.token)) {
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response();
+    }
+    next.run(request).await
+}
+
+async fn http_handler(
+    State(ctx): State<Arc<McpContext>>,
+    Json(payload): Json<Value>,
+) -> impl IntoResponse {
+    let response = tokio::task::spawn_blocking(move || handle_payload(&ctx, &payload))
+        .await
+        .ok()
+        .flatten();
+
+    match response {
+        Some(body) => (StatusCode::OK, Json(body)).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// Starts an HTTP MCP server for a project. Returns the existing server if one is running.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_mcp_server(
+    app: AppHandle,
+    project_path: String,
+    project_id: Option<String>,
+) -> Result<McpServerInfo, String> {
+    {
+        let servers = MCP_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(handle) = servers.get(&project_path) {
+            return Ok(handle.info(&project_path));
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to bind to port: {}", e))?;
+
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to get local address: {}", e))?
+        .port();
+
+    let ctx = Arc::new(McpContext {
+        project_path: project_path.clone(),
+        project_id,
+        app: Some(app),
+    });
+
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let access = Arc::new(HttpAccess {
+        port,
+        token: token.clone(),
+    });
+    let router = Router::new()
+        .route("/mcp", post(http_handler))
+        .layer(middleware::from_fn_with_state(access, require_local_client))
+        .with_state(ctx);
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let path_clone = project_path.clone();
+    tokio::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+
+        if let Err(e) = server.await {
+            tracing::error!("MCP server for {} error: {}", path_clone, e);
+        }
+    });
+
+    let handle = McpServerHandle {
+        port,
+        token,
+        shutdown_tx: Some(shutdown_tx),
+    };
+    let info = handle.info(&project_path);
+    MCP_SERVERS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .insert(project_path, handle);
+
+    Ok(info)
+}
+
+/// Stops the HTTP MCP server for a project.
+#[tauri::command(rename_all = "camelCase")]
+pub fn stop_mcp_server(project_path: String) -> Result<(), String> {
+    let mut servers = MCP_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    if let Some(mut handle) = servers.remove(&project_path) {
+        if let Some(tx) = handle.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns info about a project's running MCP server.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_mcp_server_info(project_path: String) -> Result<Option<McpServerInfo>, String> {
+    let servers = MCP_SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    Ok(servers.get(&project_path).map(|handle| handle.info(&project_path)))
+}
+
+/// Stop all MCP servers. Called on app shutdown.
+pub fn stop_all_mcp_servers() {
+    if let Ok(mut servers) = MCP_SERVERS.lock() {
+        for (_, mut handle) in servers.drain() {
+            if let Some(tx) = handle.shutdown_tx.take() {
+                let _ = tx.send(());
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Keeping `.ideate` history out of the project's own commits.
+//!
+//! Story commits stage everything, so the PRD, state, and cost files would
+//! otherwise churn through the project's history. With metadata history
+//! enabled, `.ideate/` is excluded from the project's commits and recorded
+//! after each story commit instead, either as a commit on a dedicated branch
+//! (`ideate-meta` by default) or as a git note on the story's commit under
+//! `refs/notes/ideate`. Neither touches the working tree or the index.
+//!
+//! ```json
+//! "metadataHistory": { "mode": "branch" }
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+
+use crate::git_binary;
+use crate::models::{MetadataHistoryConfig, ProjectConfig};
+use crate::utils::get_ideate_dir;
+
+pub const DEFAULT_BRANCH: &str = "ideate-meta";
+const NOTES_REF: &str = "ideate";
+
+/// Line added to `.git/info/exclude` while metadata history is enabled.
+#[cfg(feature = "gui")]
+const EXCLUDE_LINE: &str = "/.ideate/";
+
+/// Files captured in notes mode; branch mode snapshots the whole directory.
+const NOTE_FILES: &[&str] = &["prd.json", "state.json", "costs.json"];
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataCommit {
+    /// The metadata commit in branch mode, or the annotated story commit in notes mode.
+    pub hash: String,
+    pub message: String,
+    pub committed_at: String,
+}
+
+fn git(project_path: &str, args: &[&str]) -> Result<String, String> {
+    git_with_env(project_path, args, &[])
+}
+
+fn git_with_env(project_path: &str, args: &[&str], env: &[(&str, &str)]) -> Result<String, String> {
+    let output = git_binary::command()
+        .args(args)
+        .envs(env.iter().copied())
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn read_config(project_path: &str) -> Result<ProjectConfig, String> {
+    let content = fs::read_to_string(get_ideate_dir(project_path).join("config.json"))
+        .map_err(|e| format!("Failed to read config.json: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config.json: {}", e))
+}
+
+#[cfg(feature = "gui")]
+fn write_config(project_path: &str, config: &ProjectConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(get_ideate_dir(project_path).join("config.json"), json)
+        .map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// Reads the project's metadata history settings. Returns `None` when disabled.
+pub fn load_config(project_path: &str) -> Option<MetadataHistoryConfig> {
+    read_config(project_path).ok()?.metadata_history
+}
+
+fn branch_name(config: &MetadataHistoryConfig) -> &str {
+    config.branch.as_deref().filter(|b| !b.is_empty()).unwrap_or(DEFAULT_BRANCH)
+}
+
+#[cfg(feature = "gui")]
+fn exclude_path(project_path: &str) -> Result<String, String> {
+    let path = git(project_path, &["rev-parse", "--git-path", "info/exclude"])?;
+    Ok(Path::new(project_path).join(path).to_string_lossy().to_string())
+}
+
+#[cfg(feature = "gui")]
+fn set_excluded(project_path: &str, excluded: bool) -> Result<(), String> {
+    let path = exclude_path(project_path)?;
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let mut lines: Vec<&str> = content.lines().filter(|l| l.trim() != EXCLUDE_LINE).collect();
+    if excluded {
+        lines.push(EXCLUDE_LINE);
+    }
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, format!("{}\n", lines.join("\n"))).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Commits a snapshot of `.ideate/` to the metadata branch, using a private
+/// index so the project's staged changes are left alone.
+fn commit_snapshot(project_path: &str, branch: &str, message: &str) -> Result<Option<String>, String> {
+    let index = git(project_path, &["rev-parse", "--git-path", "ideate-meta.index"])?;
+    let index = Path::new(project_path).join(index).to_string_lossy().to_string();
+    let env = [("GIT_INDEX_FILE", index.as_str())];
+
+    git_with_env(project_path, &["read-tree", "--empty"], &env)?;
+    git_with_env(project_path, &["add", "--force", "--", ".ideate"], &env)?;
+    let tree = git_with_env(project_path, &["write-tree"], &env);
+    let _ = fs::remove_file(&index);
+    let tree = tree?;
+
+    let branch_ref = format!("refs/heads/{}", branch);
+    let parent = git(project_path, &["rev-parse", "--verify", "-q", &format!("{}^{{commit}}", branch_ref)]).ok();
+    if let Some(parent) = &parent {
+        if git(project_path, &["rev-parse", &format!("{}^{{tree}}", parent)])? == tree {
+            return Ok(None);
+        }
+    }
+
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+    if let Some(parent) = &parent {
+        args.extend(["-p", parent.as_str()]);
+    }
+    let commit = git(project_path, &args)?;
+    let mut update = vec!["update-ref", branch_ref.as_str(), commit.as_str()];
+    if let Some(parent) = &parent {
+        update.push(parent.as_str());
+    }
+    git(project_path, &update)?;
+    Ok(Some(commit))
+}
+
+/// Attaches the PRD, state, and costs to `commit` as a note.
+fn write_note(project_path: &str, commit: &str) -> Result<(), String> {
+    let dir = get_ideate_dir(project_path);
+    let files: serde_json::Map<String, serde_json::Value> = NOTE_FILES
+        .iter()
+        .filter_map(|name| {
+            let content = fs::read_to_string(dir.join(name)).ok()?;
+            let value = serde_json::from_str(&content).unwrap_or(serde_json::Value::String(content));
+            Some((name.to_string(), value))
+        })
+        .collect();
+    let note = serde_json::to_string_pretty(&files).map_err(|e| format!("Failed to serialize note: {}", e))?;
+
+    // Passed as a file since the PRD can outgrow a command-line argument
+    let note_path = git(project_path, &["rev-parse", "--git-path", "ideate-note.json"])?;
+    let note_path = Path::new(project_path).join(note_path).to_string_lossy().to_string();
+    fs::write(&note_path, note).map_err(|e| format!("Failed to write note: {}", e))?;
+    let result = git(
+        project_path,
+        &["notes", &format!("--ref={}", NOTES_REF), "add", "--force", "-F", &note_path, commit],
+    );
+    let _ = fs::remove_file(&note_path);
+    result.map(|_| ())
+}
+
+/// Records `.ideate/` after a story commit, if metadata history is enabled.
+/// Failures are returned for the caller to log; they never fail the story.
+pub fn record(project_path: &str, story_id: &str, story_title: &str, commit: &str) -> Result<(), String> {
+    let Some(config) = load_config(project_path) else {
+        return Ok(());
+    };
+    if config.mode == "notes" {
+        write_note(project_path, commit)
+    } else {
+        let message = format!("[Story {}] {}\n\nProject commit: {}", story_id, story_title, commit);
+        commit_snapshot(project_path, branch_name(&config), &message).map(|_| ())
+    }
+}
+
+/// Returns the project's metadata history settings, or `None` when disabled.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_metadata_history_config(project_path: String) -> Option<MetadataHistoryConfig> {
+    load_config(&project_path)
+}
+
+/// Enables metadata history in "branch" or "notes" mode. `.ideate/` is
+/// excluded and untracked so later story commits leave it out.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn enable_metadata_history(project_path: String, mode: String, branch: Option<String>) -> Result<(), String> {
+    if mode != "branch" && mode != "notes" {
+        return Err(format!("Unknown metadata history mode: {}", mode));
+    }
+    if let Some(branch) = branch.as_deref().filter(|b| !b.is_empty()) {
+        git(&project_path, &["check-ref-format", "--branch", branch])
+            .map_err(|_| format!("Invalid branch name: {}", branch))?;
+    }
+
+    set_excluded(&project_path, true)?;
+    // Already-tracked files ignore excludes; the removal lands with the next story commit
+    git(&project_path, &["rm", "-r", "--cached", "--quiet", "--ignore-unmatch", "--", ".ideate"])?;
+
+    let history = MetadataHistoryConfig { mode, branch };
+    let mut config = read_config(&project_path)?;
+    config.metadata_history = Some(history.clone());
+    write_config(&project_path, &config)?;
+
+    // Start the history from the current state
+    match git(&project_path, &["rev-parse", "HEAD"]) {
+        Ok(head) if history.mode == "notes" => write_note(&project_path, &head),
+        Ok(_) => commit_snapshot(&project_path, branch_name(&history), "Start Ideate metadata history").map(|_| ()),
+        // No commits yet; the first story commit starts it
+        Err(_) => Ok(()),
+    }
+}
+
+/// Disables metadata history. `.ideate/` is included in story commits again;
+/// the recorded branch or notes are kept.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn disable_metadata_history(project_path: String) -> Result<(), String> {
+    let mut config = read_config(&project_path)?;
+    config.metadata_history = None;
+    write_config(&project_path, &config)?;
+    set_excluded(&project_path, false)
+}
+
+/// Lists recorded metadata, newest first.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_metadata_history(project_path: String, limit: Option<u32>) -> Result<Vec<MetadataCommit>, String> {
+    let Some(config) = load_config(&project_path) else {
+        return Ok(Vec::new());
+    };
+    let limit = format!("-n{}", limit.unwrap_or(50).max(1));
+    const FORMAT: &str = "--format=%H%x1f%s%x1f%cI";
+
+    let output = if config.mode == "notes" {
+        // Notes are keyed by the commits they annotate
+        let annotated: Vec<String> = git(&project_path, &["notes", &format!("--ref={}", NOTES_REF), "list"])
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1).map(str::to_string))
+            .collect();
+        if annotated.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut args = vec!["log", "--no-walk=sorted", FORMAT, limit.as_str()];
+        args.extend(annotated.iter().map(String::as_str));
+        git(&project_path, &args)?
+    } else {
+        let branch_ref = format!("refs/heads/{}", branch_name(&config));
+        if git(&project_path, &["rev-parse", "--verify", "-q", &branch_ref]).is_err() {
+            return Ok(Vec::new());
+        }
+        git(&project_path, &["log", FORMAT, limit.as_str(), &branch_ref])?
+    };
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\u{1f}');
+            Some(MetadataCommit {
+                hash: fields.next()?.to_string(),
+                message: fields.next()?.to_string(),
+                committed_at: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Reads a metadata file as recorded at `hash`, e.g. `prd.json`.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn read_metadata_snapshot(project_path: String, hash: String, file: String) -> Result<String, String> {
+    if file.contains("..") || file.starts_with('/') {
+        return Err(format!("Invalid metadata file: {}", file));
+    }
+    let config = load_config(&project_path).ok_or("Metadata history is not enabled")?;
+    if config.mode == "notes" {
+        let note = git(&project_path, &["notes", &format!("--ref={}", NOTES_REF), "show", &hash])?;
+        let files: serde_json::Value =
+            serde_json::from_str(&note).map_err(|e| format!("Failed to parse note: {}", e))?;
+        let value = files.get(&file).ok_or_else(|| format!("{} was not recorded in this note", file))?;
+        return match value {
+            serde_json::Value::String(content) => Ok(content.clone()),
+            other => serde_json::to_string_pretty(other).map_err(|e| format!("Failed to serialize {}: {}", file, e)),
+        };
+    }
+    git(&project_path, &["show", &format!("{}:.ideate/{}", hash, file)])
+}
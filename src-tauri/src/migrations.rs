@@ -0,0 +1,208 @@
+//! Versioned migrations for the JSON documents Ideate stores.
+//!
+//! Each document kind has a `Schema` listing its migrations in order:
+//! `migrations[n]` upgrades a document from version `n` to `n + 1`, so the
+//! current version is the number of migrations. Documents record the version
+//! they were written with in `schemaVersion`; documents from before
+//! versioning count as version 0. Migrations operate on raw JSON so they can
+//! rename or reshape fields the structs no longer know about.
+//!
+//! A format change adds a migration here rather than another
+//! `#[serde(default)]` or fix-up after parsing. Loading a document from an
+//! older version upgrades it, keeps the original as `<name>.v<N>.bak`, and
+//! writes the upgraded one back. Documents from a newer Ideate are rejected
+//! rather than rewritten, so a downgrade can't drop fields it doesn't
+//! understand.
+//!
+//! The legacy projects.json is a bare list with nowhere to record a version;
+//! it is only read to split it into per-project files, which are versioned
+//! as `PROJECT`.
+
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::file_lock;
+use crate::utils::parse_lenient_json;
+
+/// Field documents record their version in.
+const VERSION_FIELD: &str = "schemaVersion";
+
+pub type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// A stored document kind and its migrations.
+pub struct Schema {
+    /// File name used in messages and backups.
+    pub name: &'static str,
+    /// `migrations[n]` upgrades a document from version `n` to `n + 1`.
+    pub migrations: &'static [Migration],
+    /// Whether documents may be loosely written JSON; see `parse_lenient_json`.
+    pub lenient: bool,
+}
+
+pub const PREFERENCES: Schema = Schema {
+    name: "preferences.json",
+    migrations: &[],
+    lenient: false,
+};
+
+pub const PROJECTS: Schema = Schema {
+    name: "projects.json",
+    migrations: &[],
+    lenient: false,
+};
+
+/// One project's registry entry, `projects/<id>.json`.
+pub const PROJECT: Schema = Schema {
+    name: "project",
+    migrations: &[],
+    lenient: false,
+};
+
+/// Agents edit prd.json too, so it is parsed leniently.
+pub const PRD: Schema = Schema {
+    name: "prd.json",
+    migrations: &[],
+    lenient: true,
+};
+
+pub const STATE: Schema = Schema {
+    name: "state.json",
+    migrations: &[state_v0_fill_required_fields],
+    lenient: false,
+};
+
+#[cfg(feature = "gui")]
+pub const UI_STATE: Schema = Schema {
+    name: "ui-state.json",
+    migrations: &[ui_state_v0_window_states],
+    lenient: false,
+};
+
+fn object<'a>(doc: &'a mut Value, name: &str) -> Result<&'a mut Map<String, Value>, String> {
+    doc.as_object_mut()
+        .ok_or_else(|| format!("Failed to parse {}: expected an object", name))
+}
+
+/// Early projects predate build retries; give them the fields `ProjectState`
+/// requires so they parse.
+fn state_v0_fill_required_fields(doc: &mut Value) -> Result<(), String> {
+    let state = object(doc, STATE.name)?;
+    state.entry("currentStoryId").or_insert(Value::Null);
+    state.entry("storyStatuses").or_insert_with(|| json!({}));
+    state.entry("storyRetries").or_insert_with(|| json!({}));
+    state.entry("buildPhase").or_insert_with(|| json!("idle"));
+    Ok(())
+}
+
+/// Before multiple windows, ui-state.json kept one `windowState`; it becomes
+/// the main window's entry in `windowStates`.
+#[cfg(feature = "gui")]
+fn ui_state_v0_window_states(doc: &mut Value) -> Result<(), String> {
+    let state = object(doc, UI_STATE.name)?;
+    let Some(legacy) = state.remove("windowState").filter(|v| !v.is_null()) else {
+        return Ok(());
+    };
+    let windows = state.entry("windowStates").or_insert_with(|| json!({}));
+    if let Some(windows) = windows.as_object_mut() {
+        windows.entry("main").or_insert(legacy);
+    }
+    Ok(())
+}
+
+impl Schema {
+    /// Version written by this build.
+    pub const fn current_version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    /// The version a document was written with.
+    fn version_of(doc: &Value) -> u32 {
+        doc.get(VERSION_FIELD)
+            .and_then(Value::as_u64)
+            .map_or(0, |v| v as u32)
+    }
+
+    /// Upgrades `doc` to the current version. Returns the version it started at.
+    pub fn migrate(&self, doc: &mut Value) -> Result<u32, String> {
+        let from = Self::version_of(doc);
+        let current = self.current_version();
+        if from > current {
+            return Err(format!(
+                "{} uses schema version {}, but this version of Ideate only supports up to {}. Update Ideate to open it.",
+                self.name, from, current
+            ));
+        }
+        for (version, migration) in self.migrations.iter().enumerate().skip(from as usize) {
+            migration(doc).map_err(|e| format!("Failed to migrate {} from v{}: {}", self.name, version, e))?;
+        }
+        self.stamp(doc);
+        Ok(from)
+    }
+
+    /// Records the current version in a document about to be written.
+    /// Versionless kinds are left untouched.
+    pub fn stamp(&self, doc: &mut Value) {
+        if self.current_version() == 0 {
+            return;
+        }
+        if let Some(map) = doc.as_object_mut() {
+            map.insert(VERSION_FIELD.to_string(), json!(self.current_version()));
+        }
+    }
+
+    /// Serializes a document for writing, with its version.
+    pub fn to_json<T: Serialize>(&self, value: &T) -> Result<String, String> {
+        let mut doc = serde_json::to_value(value).map_err(|e| format!("Failed to serialize {}: {}", self.name, e))?;
+        self.stamp(&mut doc);
+        serde_json::to_string_pretty(&doc).map_err(|e| format!("Failed to serialize {}: {}", self.name, e))
+    }
+
+    /// Parses a document, upgrading it in memory. Also returns the version it
+    /// was stored at.
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<(T, u32), String> {
+        let mut doc: Value = if self.lenient {
+            parse_lenient_json(content, self.name)?
+        } else {
+            serde_json::from_str(content).map_err(|e| format!("Failed to parse {}: {}", self.name, e))?
+        };
+        let from = self.migrate(&mut doc)?;
+        let value = serde_json::from_value(doc).map_err(|e| format!("Failed to parse {}: {}", self.name, e))?;
+        Ok((value, from))
+    }
+
+    /// Loads the document at `path`, or `None` if there isn't one. A document
+    /// from an older version is upgraded and written back under its lock,
+    /// keeping the original as `<name>.v<N>.bak`.
+    pub fn load<T: DeserializeOwned + Serialize>(&self, path: &Path) -> Result<Option<T>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", self.name, e))?;
+        let (value, from) = self.parse::<T>(&content)?;
+        if from == self.current_version() {
+            return Ok(Some(value));
+        }
+
+        let value = file_lock::update(path, |content| {
+            let content = content.ok_or_else(|| format!("Failed to migrate {}: file disappeared", self.name))?;
+            let (value, from) = self.parse::<T>(content)?;
+            if from != self.current_version() {
+                let backup_path = path.with_file_name(format!("{}.v{}.bak", self.name, from));
+                fs::write(&backup_path, content)
+                    .map_err(|e| format!("Failed to back up {}: {}", self.name, e))?;
+                tracing::info!(
+                    "Migrated {} from schema v{} to v{}",
+                    path.display(),
+                    from,
+                    self.current_version()
+                );
+            }
+            Ok((self.to_json(&value)?, value))
+        })?;
+        Ok(Some(value))
+    }
+}
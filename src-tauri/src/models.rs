@@ -2,12 +2,13 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use ts_rs::TS;
 
 // ============================================================================
 // Project Models
 // ============================================================================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectConfig {
     pub name: String,
@@ -16,26 +17,168 @@ pub struct ProjectConfig {
     pub autonomy: String,
     #[serde(default)]
     pub build_mode: Option<String>,
+    #[serde(default)]
+    pub tunnel_provider: Option<String>,
+    /// Suppresses desktop notifications for this project's builds.
+    #[serde(default)]
+    pub mute_notifications: bool,
+    /// Leaves the stack's prompt snippets out of this project's builds.
+    #[serde(default)]
+    pub exclude_stack_snippets: bool,
+    /// Shell commands run around builds and stories; see `hooks`.
+    #[serde(default, skip_serializing_if = "ProjectHooks::is_empty")]
+    pub hooks: ProjectHooks,
+    /// End-to-end suite run after each story; see `e2e`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e2e: Option<E2eConfig>,
+    /// Checks a story must pass before it is committed or merged; see
+    /// `verification`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationConfig>,
+    /// Records `.ideate/` outside the project's commits; see `meta_history`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_history: Option<MetadataHistoryConfig>,
+    /// Picks the agent and model per story; see `orchestrator::routing`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<RoutingConfig>,
+    /// Free-form data other tools and features keep with the project; see
+    /// `projects::set_project_metadata`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[ts(type = "Record<string, unknown>")]
+    pub metadata: HashMap<String, serde_json::Value>,
     pub created_at: String,
+    /// Keys this version doesn't know, kept so that saving doesn't drop them.
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectHooks {
+    #[serde(default)]
+    pub pre_build: Option<String>,
+    #[serde(default)]
+    pub post_build: Option<String>,
+    #[serde(default)]
+    pub pre_story: Option<String>,
+    #[serde(default)]
+    pub post_story: Option<String>,
+}
+
+impl ProjectHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_build.is_none()
+            && self.post_build.is_none()
+            && self.pre_story.is_none()
+            && self.post_story.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct E2eConfig {
+    /// "playwright" or "cypress"; detected from the project's config files
+    /// when omitted.
+    #[serde(default)]
+    pub runner: Option<String>,
+    /// Replaces the runner's default command. It must print the JSON report
+    /// to stdout.
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationConfig {
+    /// Shell command run in the story's working directory, e.g.
+    /// `npm run lint && npm test`.
+    #[serde(default)]
+    pub command: String,
+    /// Runs still going after this are killed and fail the story.
+    #[serde(default)]
+    pub timeout_minutes: Option<u32>,
+    /// LCOV or Cobertura report the command writes, relative to the working
+    /// directory; see `coverage`.
+    #[serde(default)]
+    pub coverage_report: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataHistoryConfig {
+    /// "branch" commits snapshots to a dedicated branch; "notes" attaches
+    /// them to story commits as git notes.
+    #[serde(default)]
+    pub mode: String,
+    /// Branch used in "branch" mode; defaults to `ideate-meta`.
+    #[serde(default)]
+    pub branch: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingConfig {
+    /// Checked in order; the first rule a story matches picks its agent.
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// Conditions on a story's estimated tokens and the build's remaining
+/// budget, and the agent/model to use when they all hold. Unset conditions
+/// always match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingRule {
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub min_tokens: Option<u64>,
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub max_tokens: Option<u64>,
+    /// USD left of `maxCostPerBuild`. Without a build budget the remaining
+    /// budget is unlimited.
+    #[serde(default)]
+    pub min_budget_remaining: Option<f64>,
+    #[serde(default)]
+    pub max_budget_remaining: Option<f64>,
+    /// Agent to use; the build's agent when unset.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Model passed to the agent, e.g. "haiku" or "opus".
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectSettings {
     pub agent: Option<String>,
     pub autonomy: String,
     #[serde(default)]
     pub build_mode: Option<String>,
+    /// Tunnel provider for this project ("outray", "cloudflare", "ngrok")
+    #[serde(default)]
+    pub tunnel_provider: Option<String>,
+    /// Left unchanged on save when omitted.
+    #[serde(default)]
+    pub mute_notifications: Option<bool>,
+    /// Left unchanged on save when omitted.
+    #[serde(default)]
+    pub exclude_stack_snippets: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateProjectResult {
     pub path: String,
     pub config_path: String,
+    /// Built-in stack detected in an imported project's manifests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct StoredProject {
     pub id: String,
@@ -46,13 +189,36 @@ pub struct StoredProject {
     pub created_at: String,
     #[serde(default)]
     pub stack_id: Option<String>,
+    /// Stacks composed with `stack_id` into the project's effective stack;
+    /// see `stacks::compose_stacks`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_stack_ids: Vec<String>,
+}
+
+impl StoredProject {
+    /// `stack_id` followed by `extra_stack_ids`.
+    pub fn stack_ids(&self) -> Vec<String> {
+        self.stack_id.iter().chain(&self.extra_stack_ids).cloned().collect()
+    }
+}
+
+/// Fields to change on a stored project; omitted fields are left as they are.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub path: Option<String>,
+    pub status: Option<String>,
+    pub stack_id: Option<String>,
+    pub extra_stack_ids: Option<Vec<String>>,
 }
 
 // ============================================================================
 // PRD / Story Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct Story {
     pub id: String,
@@ -64,10 +230,16 @@ pub struct Story {
     #[serde(default)]
     pub status: Option<String>,
     pub notes: String,
+    /// Agent to build this story with, over routing and project defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Model to build this story with, e.g. "opus".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 /// Project idea - stored in .ideate/idea.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectIdea {
     pub title: String,
@@ -75,7 +247,7 @@ pub struct ProjectIdea {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct Prd {
     #[serde(default)]
@@ -87,7 +259,7 @@ pub struct Prd {
     pub user_stories: Vec<Story>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct StoryRetryInfo {
     pub retry_count: i32,
@@ -97,7 +269,7 @@ pub struct StoryRetryInfo {
 // Design Document Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct DesignComponent {
     pub name: String,
@@ -106,7 +278,7 @@ pub struct DesignComponent {
     pub responsibilities: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct DesignArchitecture {
     #[serde(default)]
@@ -117,7 +289,7 @@ pub struct DesignArchitecture {
     pub data_flow: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct DesignTechStack {
     #[serde(default)]
@@ -130,7 +302,7 @@ pub struct DesignTechStack {
     pub infrastructure: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct DesignApiEndpoint {
     pub endpoint: String,
@@ -138,7 +310,7 @@ pub struct DesignApiEndpoint {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct DesignDataModel {
     pub name: String,
@@ -146,7 +318,7 @@ pub struct DesignDataModel {
     pub fields: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct DesignConsiderations {
     #[serde(default)]
@@ -157,7 +329,7 @@ pub struct DesignConsiderations {
     pub scalability: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct Design {
     pub project: String,
@@ -179,20 +351,160 @@ pub struct Design {
     pub considerations: Option<DesignConsiderations>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectState {
+    /// Layout version of the document; see `migrations`.
+    #[serde(default)]
+    pub schema_version: u32,
     pub current_story_id: Option<String>,
     pub story_statuses: HashMap<String, String>,
     pub story_retries: HashMap<String, StoryRetryInfo>,
     pub build_phase: String,
+    /// Latest preview deployment per provider. `None` when the caller did not
+    /// send it, in which case the stored value is preserved on save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deploy_previews: Option<HashMap<String, DeployPreview>>,
+    /// Why each story last failed for good. Backend-managed; preserved on save
+    /// like `deploy_previews`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story_failures: Option<HashMap<String, StoryFailure>>,
+    /// Checks run on each story's latest successful attempt. Backend-managed;
+    /// preserved on save like `deploy_previews`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story_verifications: Option<HashMap<String, StoryVerification>>,
+}
+
+impl Default for ProjectState {
+    fn default() -> Self {
+        Self {
+            schema_version: crate::migrations::STATE.current_version(),
+            current_story_id: None,
+            story_statuses: HashMap::new(),
+            story_retries: HashMap::new(),
+            build_phase: "idle".to_string(),
+            deploy_previews: None,
+            story_failures: None,
+            story_verifications: None,
+        }
+    }
+}
+
+/// Final failure of a story after its retries ran out.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryFailure {
+    /// "timeout", "exit-code", "agent-error", "process-error", "spawn-error",
+    /// "worktree-error", "merge-conflict", "hook", "verification", "auth",
+    /// "budget", "signal", or "throttled"
+    pub kind: String,
+    pub message: String,
+    pub attempts: u32,
+    pub failed_at: String,
+}
+
+/// What was checked after a story's agent finished.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryVerification {
+    pub verified_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checks: Option<VerificationRun>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<StoryCoverage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e2e: Option<E2eRun>,
+}
+
+/// One run of a project's verification command.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationRun {
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Why the command failed, if it did.
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    /// The last lines of output.
+    pub output_tail: Vec<String>,
+    /// The run's entry in process history.
+    pub process_id: String,
+}
+
+/// Line coverage measured after a story's verification run.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryCoverage {
+    /// "lcov" or "cobertura"
+    pub format: String,
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub lines_covered: Option<u64>,
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub lines_total: Option<u64>,
+    /// Line coverage, 0-100.
+    pub percent: f64,
+    /// Change from the story measured before this one, in percentage points.
+    #[serde(default)]
+    pub delta: Option<f64>,
+    pub measured_at: String,
+}
+
+/// One run of a project's end-to-end suite.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct E2eRun {
+    /// "playwright" or "cypress"
+    pub runner: String,
+    /// The suite exited successfully with no failing tests.
+    pub success: bool,
+    pub passed: u32,
+    pub failed: u32,
+    pub flaky: u32,
+    pub skipped: u32,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    /// Why the run produced no results, if it didn't.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// The tracked process, for finding its log in process history.
+    #[serde(default)]
+    pub process_id: Option<String>,
+    pub tests: Vec<E2eTestResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct E2eTestResult {
+    /// Suite and test titles joined with " › ".
+    pub title: String,
+    #[serde(default)]
+    pub file: Option<String>,
+    /// "passed", "failed", "flaky", or "skipped"
+    pub status: String,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployPreview {
+    pub provider: String,
+    pub url: String,
+    pub deployed_at: String,
 }
 
 // ============================================================================
 // Cost Tracking Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct CostEntry {
     pub id: String,
@@ -201,10 +513,13 @@ pub struct CostEntry {
     pub agent_id: String,
     pub description: String,
     #[serde(default)]
+    #[ts(type = "number | null")]
     pub input_tokens: Option<i64>,
     #[serde(default)]
+    #[ts(type = "number | null")]
     pub output_tokens: Option<i64>,
     #[serde(default)]
+    #[ts(type = "number | null")]
     pub total_tokens: Option<i64>,
     #[serde(default)]
     pub cost: Option<f64>,
@@ -215,10 +530,11 @@ pub struct CostEntry {
     #[serde(default)]
     pub thread_id: Option<String>,
     #[serde(default)]
+    #[ts(type = "number | null")]
     pub duration_ms: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct CostHistory {
     pub entries: Vec<CostEntry>,
 }
@@ -227,7 +543,7 @@ pub struct CostHistory {
 // Ideas Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct Idea {
     pub id: String,
@@ -236,49 +552,133 @@ pub struct Idea {
     pub description: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Stories drafted by an idea expansion, for the project's PRD.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub draft_stories: Vec<DraftStory>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftStory {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
 }
 
 // ============================================================================
 // Process / Agent Execution Models
 // ============================================================================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct SpawnAgentResult {
     pub process_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct KillAgentResult {
     pub success: bool,
     pub message: String,
+    /// How the process was stopped; `None` if it wasn't running.
+    #[serde(default)]
+    pub path: Option<KillPath>,
+}
+
+/// Which path `kill_agent` took to stop a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+pub enum KillPath {
+    /// Exited within the grace period after SIGTERM.
+    Graceful,
+    /// Still running when the grace period ran out, so it got SIGKILL.
+    Escalated,
+    /// Killed outright, with no grace period.
+    Immediate,
+}
+
+/// Why a process stopped, so a crash can be told apart from a cancel or a kill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+pub enum TerminationReason {
+    /// The process exited on its own, successfully or not.
+    Exited,
+    /// Stopped by `kill_agent` or by stopping the build.
+    Canceled,
+    /// Stopped by the story timeout.
+    Timeout,
+    /// Stopped for going past the story's token limit or the build's cost limit.
+    Budget,
+    /// Killed by a signal Ideate did not send, e.g. the OOM killer or a crash.
+    Signal,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct WaitAgentResult {
     pub process_id: String,
     pub exit_code: Option<i32>,
     pub success: bool,
+    /// Signal that terminated the process (Unix only).
+    pub signal: Option<i32>,
+    pub termination_reason: TerminationReason,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
-pub struct AgentOutputEvent {
-    pub process_id: String,
+pub struct AgentOutputLine {
     pub stream_type: String,
     pub content: String,
 }
 
-#[derive(Clone, Serialize)]
+/// Payload of `agent-output`: lines a process printed since the last event, in order.
+#[derive(Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentOutputEvent {
+    pub process_id: String,
+    pub lines: Vec<AgentOutputLine>,
+    /// Set for `OutputMode::Raw`: each entry is a chunk as read, which may
+    /// end mid-line and contain newlines and carriage returns.
+    pub raw: bool,
+}
+
+/// CPU priority agent processes run at, and on Linux their disk priority,
+/// so parallel builds leave the machine usable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    /// nice 10 and the lowest best-effort I/O level; below normal on Windows.
+    Low,
+    /// nice 19 and idle I/O; the idle priority class on Windows.
+    Lowest,
+}
+
+/// How a tracked process's output is read and emitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// One entry per line, emitted once the line ends.
+    #[default]
+    Lines,
+    /// Chunks as they arrive, so progress bars and spinners that redraw with
+    /// carriage returns show up live.
+    Raw,
+}
+
+#[derive(Clone, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentExitEvent {
     pub process_id: String,
     pub exit_code: Option<i32>,
     pub success: bool,
+    pub signal: Option<i32>,
+    pub termination_reason: TerminationReason,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessLogEntry {
     pub timestamp: String,
@@ -287,7 +687,7 @@ pub struct ProcessLogEntry {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessCommand {
     pub executable: String,
@@ -295,7 +695,7 @@ pub struct ProcessCommand {
     pub working_directory: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessHistoryEntry {
     pub process_id: String,
@@ -304,28 +704,56 @@ pub struct ProcessHistoryEntry {
     pub label: String,
     pub started_at: String,
     pub completed_at: String,
+    #[ts(type = "number")]
     pub duration_ms: i64,
     pub exit_code: Option<i32>,
     pub success: bool,
     #[serde(default)]
     pub agent_id: Option<String>,
+    /// Agent this process's agent replaced after it failed or could not run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_from: Option<String>,
     #[serde(default)]
     pub command: Option<ProcessCommand>,
     #[serde(default)]
     pub log_file_path: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessHistory {
     pub entries: Vec<ProcessHistoryEntry>,
 }
 
+/// A line-aligned byte range of a process log file.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct LogChunk {
+    pub content: String,
+    /// Byte offset where `content` starts.
+    #[ts(type = "number")]
+    pub offset: u64,
+    /// Byte offset just past the end of `content`.
+    #[ts(type = "number")]
+    pub end_offset: u64,
+    #[ts(type = "number")]
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileInfo {
+    #[ts(type = "number")]
+    pub size: u64,
+    #[ts(type = "number")]
+    pub line_count: u64,
+}
+
 // ============================================================================
 // Agent Plugin Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentModel {
     pub id: String,
@@ -334,7 +762,7 @@ pub struct AgentModel {
     pub provider: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentPlugin {
     pub id: String,
@@ -352,7 +780,7 @@ pub struct AgentPlugin {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentPluginStatus {
     #[serde(flatten)]
@@ -368,21 +796,36 @@ pub struct AgentPluginStatus {
 // Preferences Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One way of reading progress from an agent's output lines: a regex with
+/// named groups, or JSON pointers into lines that are JSON objects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressRule {
+    /// Named groups `step`, `percent`, `current`, `total`, and `file` fill
+    /// the matching progress fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Progress field to the JSON pointer it is read from,
+    /// e.g. `{ "file": "/tool_input/file_path" }`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub json: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentCliPath {
     pub agent_id: String,
     pub path: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct OutRayCredentials {
     #[serde(default)]
     pub api_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct OutRayConfig {
     #[serde(default)]
@@ -399,7 +842,64 @@ pub struct OutRayConfig {
     pub per_project: HashMap<String, OutRayCredentials>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudflareTunnelConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub use_custom_path: bool,
+    #[serde(default)]
+    pub cli_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct NgrokConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub use_custom_path: bool,
+    #[serde(default)]
+    pub cli_path: Option<String>,
+    #[serde(default)]
+    pub default_domain: Option<String>,
+}
+
+/// Currency costs are displayed in. Costs are always recorded in USD.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyConfig {
+    /// ISO 4217 code, e.g. "EUR".
+    #[serde(default = "default_currency_code")]
+    pub code: String,
+    /// "manual" uses `manual_rate`; "fetch" downloads rates periodically.
+    #[serde(default = "default_rate_source")]
+    pub rate_source: String,
+    /// Units of `code` per USD.
+    #[serde(default)]
+    pub manual_rate: Option<f64>,
+}
+
+fn default_currency_code() -> String {
+    "USD".to_string()
+}
+
+fn default_rate_source() -> String {
+    "fetch".to_string()
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            code: default_currency_code(),
+            rate_source: default_rate_source(),
+            manual_rate: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct Preferences {
     #[serde(default)]
@@ -431,12 +931,26 @@ pub struct Preferences {
     pub has_accepted_disclaimer: bool,
     #[serde(default)]
     pub outray: OutRayConfig,
+    #[serde(default)]
+    pub cloudflare: CloudflareTunnelConfig,
+    #[serde(default)]
+    pub ngrok: NgrokConfig,
     #[serde(default = "default_build_notifications")]
     pub build_notifications: bool,
+    /// Silences all desktop notifications without changing per-event settings.
+    #[serde(default)]
+    pub do_not_disturb: bool,
     #[serde(default)]
+    #[ts(type = "number | null")]
     pub max_tokens_per_story: Option<i64>,
     #[serde(default)]
     pub max_cost_per_build: Option<f64>,
+    /// Percentages of the token and cost limits at which builds warn before
+    /// the limit itself is reached.
+    #[serde(default = "default_budget_warning_thresholds")]
+    pub budget_warning_thresholds: Vec<u32>,
+    #[serde(default)]
+    pub currency: CurrencyConfig,
     #[serde(default = "default_warn_on_large_story")]
     pub warn_on_large_story: bool,
     #[serde(default)]
@@ -447,12 +961,131 @@ pub struct Preferences {
     pub specs_agent: Option<String>,
     #[serde(default)]
     pub design_agent: Option<String>,
+    /// Wall-clock limit for one agent run, in minutes. 0 means no limit.
+    #[serde(default = "default_story_timeout_minutes")]
+    pub story_timeout_minutes: u32,
+    /// Automatic retries after a story fails, before it is marked failed.
+    #[serde(default = "default_max_story_retries")]
+    pub max_story_retries: u32,
+    /// Delay before the first automatic retry; doubles with each attempt.
+    #[serde(default = "default_retry_backoff_seconds")]
+    pub retry_backoff_seconds: u32,
+    /// Agents a story switches to, in order, when its agent can't run or
+    /// keeps failing, e.g. `["claude-code", "amp", "opencode"]`.
+    #[serde(default)]
+    pub fallback_agents: Vec<String>,
+    /// Rules that derive structured progress from agent output, by agent ID.
+    /// See `progress`.
+    #[serde(default)]
+    pub progress_rules: HashMap<String, Vec<ProgressRule>>,
+    /// Priority agent processes are started with, unless a spawn asks for another.
+    #[serde(default)]
+    pub agent_priority: ProcessPriority,
+    /// Release channel for in-app updates: "stable" or "beta".
+    #[serde(default = "default_update_channel")]
+    pub update_channel: String,
+    /// Opt-in anonymous usage counters. Off by default.
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+    /// Serves the local HTTP control API on 127.0.0.1.
+    #[serde(default)]
+    pub control_api_enabled: bool,
+    #[serde(default = "default_control_api_port")]
+    pub control_api_port: u16,
+    /// Syncs `.ideate` state and build events with other machines on the
+    /// local network; see `lan_sync`.
+    #[serde(default)]
+    pub lan_sync_enabled: bool,
+    #[serde(default = "default_lan_sync_port")]
+    pub lan_sync_port: u16,
+    /// Masks API keys and tokens in saved process logs.
+    #[serde(default = "default_redact_secrets")]
+    pub redact_secrets: bool,
+    /// Also masks them in live agent output.
+    #[serde(default)]
+    pub redact_agent_output: bool,
+    /// Extra regexes whose matches are masked.
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+    /// Executables the user allowed `spawn_agent` to run beyond the built-in
+    /// allowlist.
+    #[serde(default)]
+    pub approved_executables: Vec<String>,
+    /// Records commands typed into embedded terminals to an audit log.
+    #[serde(default)]
+    pub terminal_audit_enabled: bool,
+    /// Token budget for the project files included in a story prompt, most
+    /// relevant first. 0 includes none.
+    #[serde(default = "default_story_context_tokens")]
+    pub story_context_tokens: u32,
+    /// How long a killed agent gets to exit after SIGTERM before SIGKILL.
+    /// 0 kills immediately.
+    #[serde(default = "default_kill_grace_period_ms")]
+    pub kill_grace_period_ms: u64,
+    /// Process logs older than this are gzipped at startup. 0 keeps them
+    /// uncompressed.
+    #[serde(default = "default_compress_logs_after_days")]
+    pub compress_logs_after_days: u32,
+    /// Hours between checks for newer agent CLI versions. 0 never checks.
+    #[serde(default = "default_agent_update_check_hours")]
+    pub agent_update_check_hours: u32,
+    /// Git executable to use instead of the one found automatically.
+    #[serde(default)]
+    pub git_path: Option<String>,
+}
+
+fn default_compress_logs_after_days() -> u32 {
+    7
+}
+
+fn default_agent_update_check_hours() -> u32 {
+    24
+}
+
+fn default_kill_grace_period_ms() -> u64 {
+    5000
+}
+
+fn default_story_context_tokens() -> u32 {
+    12_000
+}
+
+fn default_story_timeout_minutes() -> u32 {
+    30
+}
+
+fn default_max_story_retries() -> u32 {
+    1
+}
+
+fn default_retry_backoff_seconds() -> u32 {
+    30
+}
+
+fn default_update_channel() -> String {
+    "stable".to_string()
+}
+
+fn default_control_api_port() -> u16 {
+    7457
+}
+
+fn default_lan_sync_port() -> u16 {
+    7458
+}
+
+fn default_redact_secrets() -> bool {
+    true
 }
 
 fn default_warn_on_large_story() -> bool {
     true
 }
 
+fn default_budget_warning_thresholds() -> Vec<u32> {
+    vec![50, 80]
+}
+
 fn default_autonomy() -> String {
     "autonomous".to_string()
 }
@@ -502,14 +1135,41 @@ impl Default for Preferences {
             has_seen_welcome_guide: false,
             has_accepted_disclaimer: false,
             outray: OutRayConfig::default(),
+            cloudflare: CloudflareTunnelConfig::default(),
+            ngrok: NgrokConfig::default(),
             build_notifications: default_build_notifications(),
+            do_not_disturb: false,
             max_tokens_per_story: None,
             max_cost_per_build: None,
+            budget_warning_thresholds: default_budget_warning_thresholds(),
+            currency: CurrencyConfig::default(),
             warn_on_large_story: default_warn_on_large_story(),
             ideas_agent: None,
             prd_agent: None,
             specs_agent: None,
             design_agent: None,
+            story_timeout_minutes: default_story_timeout_minutes(),
+            max_story_retries: default_max_story_retries(),
+            retry_backoff_seconds: default_retry_backoff_seconds(),
+            fallback_agents: Vec::new(),
+            progress_rules: HashMap::new(),
+            agent_priority: ProcessPriority::default(),
+            update_channel: default_update_channel(),
+            telemetry_enabled: false,
+            control_api_enabled: false,
+            control_api_port: default_control_api_port(),
+            lan_sync_enabled: false,
+            lan_sync_port: default_lan_sync_port(),
+            redact_secrets: default_redact_secrets(),
+            redact_agent_output: false,
+            redaction_patterns: Vec::new(),
+            approved_executables: Vec::new(),
+            terminal_audit_enabled: false,
+            story_context_tokens: default_story_context_tokens(),
+            kill_grace_period_ms: default_kill_grace_period_ms(),
+            compress_logs_after_days: default_compress_logs_after_days(),
+            agent_update_check_hours: default_agent_update_check_hours(),
+            git_path: None,
         }
     }
 }
@@ -518,10 +1178,11 @@ impl Default for Preferences {
 // Duration Tracking Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct RecentThreadDuration {
     pub thread_id: Option<String>,
+    #[ts(type = "number")]
     pub duration_ms: i64,
 }
 
@@ -529,7 +1190,7 @@ pub struct RecentThreadDuration {
 // Stacks Models
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct StackTool {
     pub name: String,
@@ -542,7 +1203,7 @@ pub struct StackTool {
     pub website: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct Stack {
     pub id: String,
@@ -552,6 +1213,10 @@ pub struct Stack {
     pub tools: Vec<StackTool>,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Guidance added to the build prompts of projects using this stack,
+    /// such as coding conventions, preferred libraries, or lint rules.
+    #[serde(default)]
+    pub prompt_snippets: Vec<String>,
     #[serde(default)]
     pub is_builtin: bool,
     #[serde(default)]
@@ -563,3 +1228,16 @@ pub struct Stack {
     pub created_at: String,
     pub updated_at: String,
 }
+
+/// A stack tool whose pinned version lags behind the latest published release.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct StackToolUpdate {
+    pub stack_id: String,
+    pub stack_name: String,
+    pub tool_name: String,
+    pub registry: String,
+    pub package: String,
+    pub current_version: String,
+    pub latest_version: String,
+}
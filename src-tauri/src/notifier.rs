@@ -0,0 +1,409 @@
+//! Build event notifications via Slack/Discord webhooks and native desktop
+//! notifications.
+//!
+//! Each project configures its webhooks in `.ideate/notifications.json`.
+//! Notifications are posted from the backend so they are delivered even when
+//! the window is closed or minimized.
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+#[cfg(feature = "gui")]
+use tauri_plugin_notification::NotificationExt;
+
+#[cfg(feature = "gui")]
+use crate::preferences::load_preferences_internal;
+use crate::currency::DisplayCurrency;
+#[cfg(feature = "gui")]
+use crate::projects::load_project_settings;
+use crate::utils::get_ideate_dir;
+
+/// A single webhook target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTarget {
+    pub id: String,
+    /// "slack" or "discord"
+    pub kind: String,
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Event types to deliver (e.g. "story-failed"). Empty means all events.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Contents of `.ideate/notifications.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifierConfig {
+    #[serde(default)]
+    pub webhooks: Vec<WebhookTarget>,
+}
+
+/// A build event that can be sent to webhooks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum BuildNotification {
+    #[serde(rename_all = "camelCase")]
+    BuildStarted {
+        project_name: String,
+        story_count: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    StoryPassed {
+        project_name: String,
+        story_id: String,
+        story_title: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StoryFailed {
+        project_name: String,
+        story_id: String,
+        story_title: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    BudgetThreshold {
+        project_name: String,
+        /// In USD, like every recorded cost.
+        spent: f64,
+        limit: f64,
+        /// Shown alongside USD; USD only when omitted.
+        #[serde(default)]
+        currency: Option<DisplayCurrency>,
+    },
+    #[serde(rename_all = "camelCase")]
+    AgentWaiting {
+        project_name: String,
+        story_id: String,
+        story_title: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    BuildCompleted {
+        project_name: String,
+        passed: usize,
+        failed: usize,
+        #[serde(default)]
+        duration_secs: Option<u64>,
+    },
+}
+
+impl BuildNotification {
+    /// Event type key used for per-webhook filtering.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            BuildNotification::BuildStarted { .. } => "build-started",
+            BuildNotification::StoryPassed { .. } => "story-passed",
+            BuildNotification::StoryFailed { .. } => "story-failed",
+            BuildNotification::BudgetThreshold { .. } => "budget-threshold",
+            BuildNotification::AgentWaiting { .. } => "agent-waiting",
+            BuildNotification::BuildCompleted { .. } => "build-completed",
+        }
+    }
+
+    /// Message text marked up for `markup`. Names, titles, and reasons are
+    /// escaped so they show as written.
+    fn message(&self, markup: Markup) -> String {
+        match self {
+            BuildNotification::BuildStarted {
+                project_name,
+                story_count,
+            } => format!(
+                "🚀 Build started for {} ({} stories)",
+                markup.bold(project_name),
+                story_count
+            ),
+            BuildNotification::StoryPassed {
+                project_name,
+                story_id,
+                story_title,
+            } => format!(
+                "✅ {}: {} {} passed",
+                markup.bold(project_name),
+                markup.text(story_id),
+                markup.text(story_title)
+            ),
+            BuildNotification::StoryFailed {
+                project_name,
+                story_id,
+                story_title,
+                reason,
+            } => {
+                let reason = reason
+                    .as_deref()
+                    .map(|reason| format!(": {}", markup.text(reason)))
+                    .unwrap_or_default();
+                format!(
+                    "❌ {}: {} {} failed{}",
+                    markup.bold(project_name),
+                    markup.text(story_id),
+                    markup.text(story_title),
+                    reason
+                )
+            }
+            BuildNotification::BudgetThreshold {
+                project_name,
+                spent,
+                limit,
+                currency,
+            } => {
+                let percent = if *limit > 0.0 { spent / limit * 100.0 } else { 0.0 };
+                let currency = currency.clone().unwrap_or_else(DisplayCurrency::usd);
+                format!(
+                    "💸 {} has used {} of its {} budget ({:.0}%)",
+                    markup.bold(project_name),
+                    markup.text(&currency.format(*spent)),
+                    markup.text(&currency.format(*limit)),
+                    percent
+                )
+            }
+            BuildNotification::AgentWaiting {
+                project_name,
+                story_id,
+                story_title,
+            } => format!(
+                "⏳ {}: the agent for {} {} is waiting for input",
+                markup.bold(project_name),
+                markup.text(story_id),
+                markup.text(story_title)
+            ),
+            BuildNotification::BuildCompleted {
+                project_name,
+                passed,
+                failed,
+                duration_secs,
+            } => {
+                let duration = duration_secs
+                    .map(|s| format!(" in {}m {}s", s / 60, s % 60))
+                    .unwrap_or_default();
+                format!(
+                    "🏁 Build finished for {}{}: {} passed, {} failed",
+                    markup.bold(project_name),
+                    duration,
+                    passed,
+                    failed
+                )
+            }
+        }
+    }
+}
+
+/// How a channel formats message text.
+#[derive(Debug, Clone, Copy)]
+enum Markup {
+    /// Slack mrkdwn: `*bold*`, with `&`, `<`, and `>` escaped.
+    Slack,
+    /// Discord markdown: `**bold**`, with markdown characters escaped.
+    Discord,
+    /// Desktop notifications, which show text as is.
+    #[cfg(feature = "gui")]
+    Plain,
+}
+
+impl Markup {
+    fn for_webhook(kind: &str) -> Self {
+        match kind {
+            "discord" => Markup::Discord,
+            _ => Markup::Slack,
+        }
+    }
+
+    /// `text` escaped so it shows literally.
+    fn text(self, text: &str) -> String {
+        match self {
+            Markup::Slack => text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"),
+            Markup::Discord => text.chars().fold(String::with_capacity(text.len()), |mut out, c| {
+                if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '>') {
+                    out.push('\\');
+                }
+                out.push(c);
+                out
+            }),
+            #[cfg(feature = "gui")]
+            Markup::Plain => text.to_string(),
+        }
+    }
+
+    fn bold(self, text: &str) -> String {
+        match self {
+            Markup::Slack => format!("*{}*", self.text(text)),
+            Markup::Discord => format!("**{}**", self.text(text)),
+            #[cfg(feature = "gui")]
+            Markup::Plain => text.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl BuildNotification {
+    /// Title for a native desktop notification, or `None` for events not
+    /// worth interrupting the user for.
+    fn desktop_title(&self) -> Option<&'static str> {
+        match self {
+            BuildNotification::BuildStarted { .. } => None,
+            BuildNotification::StoryPassed { .. } => Some("Story Complete"),
+            BuildNotification::StoryFailed { .. } => Some("Story Failed"),
+            BuildNotification::BudgetThreshold { .. } => Some("Budget Threshold Reached"),
+            BuildNotification::AgentWaiting { .. } => Some("Agent Waiting for Input"),
+            BuildNotification::BuildCompleted { .. } => Some("Build Finished"),
+        }
+    }
+}
+
+fn get_config_path(project_path: &str) -> std::path::PathBuf {
+    get_ideate_dir(project_path).join("notifications.json")
+}
+
+fn read_config(project_path: &str) -> Result<NotifierConfig, String> {
+    let path = get_config_path(project_path);
+
+    if !path.exists() {
+        return Ok(NotifierConfig::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read notifications.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse notifications.json: {}", e))
+}
+
+/// Builds the JSON payload for a webhook kind.
+fn webhook_payload(kind: &str, message: &str) -> serde_json::Value {
+    match kind {
+        "discord" => serde_json::json!({ "content": message }),
+        _ => serde_json::json!({ "text": message }),
+    }
+}
+
+async fn post_webhook(
+    client: &reqwest::Client,
+    target: &WebhookTarget,
+    message: &str,
+) -> Result<(), String> {
+    let response = client
+        .post(&target.url)
+        .json(&webhook_payload(&target.kind, message))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send webhook: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+
+    Ok(())
+}
+
+/// Sends a notification to every enabled webhook subscribed to its event type.
+/// Delivery failures are logged and do not fail the caller.
+pub async fn notify(project_path: &str, notification: &BuildNotification) -> Result<(), String> {
+    let config = read_config(project_path)?;
+    let event_type = notification.event_type();
+    let client = reqwest::Client::new();
+
+    for target in config
+        .webhooks
+        .iter()
+        .filter(|w| w.enabled && (w.events.is_empty() || w.events.iter().any(|e| e == event_type)))
+    {
+        let message = notification.message(Markup::for_webhook(&target.kind));
+        if let Err(e) = post_webhook(&client, target, &message).await {
+            tracing::warn!("Notifier: {} webhook {} failed: {}", target.kind, target.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Shows a native desktop notification unless build notifications are turned
+/// off, Do Not Disturb is on, or the project is muted.
+#[cfg(feature = "gui")]
+pub fn notify_desktop(app: &AppHandle, project_path: &str, notification: &BuildNotification) {
+    let Some(title) = notification.desktop_title() else {
+        return;
+    };
+
+    let prefs = load_preferences_internal(app).unwrap_or_default();
+    if !prefs.build_notifications || prefs.do_not_disturb {
+        return;
+    }
+    let muted = load_project_settings(project_path.to_string())
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.mute_notifications)
+        .unwrap_or(false);
+    if muted {
+        return;
+    }
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(title)
+        .body(notification.message(Markup::Plain))
+        .show()
+    {
+        tracing::warn!("Notifier: desktop notification failed: {}", e);
+    }
+}
+
+/// Loads the project's webhook configuration.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn load_notifier_config(project_path: String) -> Result<NotifierConfig, String> {
+    read_config(&project_path)
+}
+
+/// Saves the project's webhook configuration.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn save_notifier_config(project_path: String, config: NotifierConfig) -> Result<(), String> {
+    let ideate_dir = get_ideate_dir(&project_path);
+
+    if !ideate_dir.exists() {
+        fs::create_dir_all(&ideate_dir)
+            .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize notifier config: {}", e))?;
+
+    fs::write(get_config_path(&project_path), json)
+        .map_err(|e| format!("Failed to write notifications.json: {}", e))
+}
+
+/// Sends a build notification for the project. Webhook delivery runs in the
+/// background so the command returns immediately.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn send_build_notification(app: AppHandle, project_path: String, notification: BuildNotification) {
+    notify_desktop(&app, &project_path, &notification);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = notify(&project_path, &notification).await {
+            tracing::warn!("Notifier: {}", e);
+        }
+    });
+}
+
+/// Sends a test message to a single webhook.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn test_webhook(kind: String, url: String) -> Result<(), String> {
+    let target = WebhookTarget {
+        id: "test".to_string(),
+        kind,
+        url,
+        enabled: true,
+        events: Vec::new(),
+    };
+
+    post_webhook(&reqwest::Client::new(), &target, "👋 Test notification from Ideate").await
+}
@@ -0,0 +1,411 @@
+//! Batch builds across several projects.
+//!
+//! `start_batch_build` queues a build for each project and runs them one after
+//! another, or up to `maxParallel` at once. Each is an ordinary build, so
+//! per-build budgets and the UI's `build-*` events work as usual; on top of
+//! that the batch can have a budget of its own, and once it is spent the
+//! running builds are stopped and the rest skipped. Progress is emitted as
+//! `batch-build-progress`, and when the batch ends a combined report is
+//! written to `batch-reports/` in the app data directory and emitted as
+//! `batch-build-finished`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::report::read_build_reports;
+use super::{get_build, launch_build, live_build, stop_build, BuildOptions};
+use crate::app_data::app_data_dir;
+use crate::currency::{self, DisplayCurrency};
+use crate::projects::load_projects;
+
+/// How often running builds are checked on.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Polls to wait for an ended build's report before giving up on it.
+const MAX_REPORT_WAITS: u32 = 5;
+
+lazy_static::lazy_static! {
+    static ref BATCHES: Mutex<HashMap<String, Arc<BatchControl>>> = Mutex::new(HashMap::new());
+}
+
+/// How a batch runs its builds.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchPolicy {
+    /// Builds run at once; 1 or unset runs them one after another.
+    #[serde(default)]
+    pub max_parallel: Option<usize>,
+    /// Skip the remaining projects once a build doesn't complete.
+    #[serde(default)]
+    pub stop_on_failure: bool,
+    /// USD the whole batch may spend. Each build's own `maxCostPerBuild`
+    /// still applies.
+    #[serde(default)]
+    pub max_total_cost: Option<f64>,
+    /// Options every build is started with, e.g. an agent override.
+    #[serde(default)]
+    pub options: Option<BuildOptions>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProjectResult {
+    pub project_id: String,
+    pub project_name: String,
+    /// "pending", "running", "complete", "incomplete", "canceled", "skipped",
+    /// or "error"
+    pub status: String,
+    pub build_id: Option<String>,
+    pub passed: usize,
+    pub failed: usize,
+    pub total_tokens: Option<u64>,
+    pub total_cost: Option<f64>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// A batch's progress, and its report once finished.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    pub batch_id: String,
+    /// "running", "complete", "incomplete", "canceled", or "over-budget"
+    pub outcome: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: u64,
+    pub max_total_cost: Option<f64>,
+    pub projects: Vec<BatchProjectResult>,
+    pub passed: usize,
+    pub failed: usize,
+    pub total_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<DisplayCurrency>,
+}
+
+struct BatchControl {
+    report: Mutex<BatchReport>,
+    stop_requested: AtomicBool,
+}
+
+impl BatchControl {
+    fn snapshot(&self) -> BatchReport {
+        self.report.lock().map(|r| r.clone()).unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut BatchReport)) {
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        apply(&mut report);
+        report.passed = report.projects.iter().map(|p| p.passed).sum();
+        report.failed = report.projects.iter().map(|p| p.failed).sum();
+        let costs: Vec<f64> = report.projects.iter().filter_map(|p| p.total_cost).collect();
+        report.total_cost = (!costs.is_empty()).then(|| costs.iter().sum());
+    }
+}
+
+impl BatchReport {
+    fn to_markdown(&self) -> String {
+        let currency = self.display_currency.clone().unwrap_or_else(DisplayCurrency::usd);
+        let fmt_cost = |cost: Option<f64>| cost.map(|c| currency.format(c)).unwrap_or_else(|| "—".to_string());
+
+        let mut md = format!("# Batch Build Report: {}\n\n", self.batch_id);
+        md.push_str(&format!("- **Outcome:** {}\n", self.outcome));
+        md.push_str(&format!("- **Started:** {}\n", self.started_at));
+        md.push_str(&format!("- **Duration:** {}s\n", self.duration_ms / 1000));
+        md.push_str(&format!("- **Stories:** {} passed, {} failed\n", self.passed, self.failed));
+        md.push_str(&format!("- **Cost:** {}", fmt_cost(self.total_cost)));
+        if let Some(limit) = self.max_total_cost {
+            md.push_str(&format!(" of {}", currency.format(limit)));
+        }
+        md.push_str("\n\n| Project | Status | Build | Passed | Failed | Cost |\n");
+        md.push_str("|---|---|---|---|---|---|\n");
+        for project in &self.projects {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                project.project_name.replace('|', "\\|"),
+                project.status,
+                project.build_id.as_deref().unwrap_or("—"),
+                project.passed,
+                project.failed,
+                fmt_cost(project.total_cost)
+            ));
+        }
+
+        let errors: Vec<&BatchProjectResult> = self.projects.iter().filter(|p| p.error.is_some()).collect();
+        if !errors.is_empty() {
+            md.push_str("\n## Errors\n\n");
+            for project in errors {
+                md.push_str(&format!(
+                    "- **{}**: {}\n",
+                    project.project_name,
+                    project.error.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        md
+    }
+}
+
+fn reports_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?.join("batch-reports"))
+}
+
+fn write_report(app: &AppHandle, report: &BatchReport) -> Result<(), String> {
+    let dir = reports_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create batch reports directory: {}", e))?;
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize batch report: {}", e))?;
+    fs::write(dir.join(format!("{}.json", report.batch_id)), json)
+        .map_err(|e| format!("Failed to write batch report: {}", e))?;
+    fs::write(dir.join(format!("{}.md", report.batch_id)), report.to_markdown())
+        .map_err(|e| format!("Failed to write batch report: {}", e))
+}
+
+/// Cost so far of the build running in `project_path`.
+fn live_cost(project_path: &str) -> Option<f64> {
+    let (_, _, stories) = live_build(project_path)?;
+    let costs: Vec<f64> = stories.iter().filter_map(|s| s.cost).collect();
+    (!costs.is_empty()).then(|| costs.iter().sum())
+}
+
+/// Fills in a finished build's result from its report. Returns false if the
+/// report hasn't been written yet.
+fn finish_project(result: &mut BatchProjectResult, project_path: &str) -> bool {
+    let report = result
+        .build_id
+        .as_ref()
+        .and_then(|id| read_build_reports(project_path).into_iter().find(|r| r.build_id == *id));
+    let Some(report) = report else {
+        return false;
+    };
+    result.status = report.outcome;
+    result.passed = report.passed;
+    result.failed = report.failed;
+    result.total_tokens = report.total_tokens;
+    result.total_cost = report.total_cost;
+    result.duration_ms = report.duration_ms;
+    true
+}
+
+async fn run_batch(app: AppHandle, ctrl: Arc<BatchControl>, paths: Vec<String>, policy: BatchPolicy) {
+    let started = Instant::now();
+    let max_parallel = policy.max_parallel.unwrap_or(1).max(1);
+    let options = policy.options.clone().unwrap_or_default();
+    let mut next = 0;
+    let mut running: Vec<usize> = Vec::new();
+    let mut halted: Option<&str> = None;
+    // Polls each ended build has waited for its report
+    let mut report_waits: HashMap<usize, u32> = HashMap::new();
+
+    loop {
+        // Builds that have ended; their report is written just after
+        let ended: Vec<usize> = {
+            let report = ctrl.snapshot();
+            running
+                .iter()
+                .copied()
+                .filter(|&i| get_build(&report.projects[i].project_id).map_or(true, |b| b.is_none()))
+                .collect()
+        };
+        let mut finished = Vec::new();
+        for i in ended {
+            let mut found = false;
+            ctrl.update(|r| found = finish_project(&mut r.projects[i], &paths[i]));
+            let waits = report_waits.entry(i).or_insert(0);
+            *waits += 1;
+            if !found && *waits < MAX_REPORT_WAITS {
+                continue;
+            }
+            if !found {
+                ctrl.update(|r| {
+                    r.projects[i].status = "incomplete".to_string();
+                    r.projects[i].error = Some("The build left no report".to_string());
+                });
+            }
+            if policy.stop_on_failure && halted.is_none() && ctrl.snapshot().projects[i].status != "complete" {
+                halted = Some("incomplete");
+            }
+            finished.push(i);
+        }
+        running.retain(|i| !finished.contains(i));
+
+        // The batch budget covers finished builds and what running ones have spent
+        if let Some(limit) = policy.max_total_cost.filter(|l| *l > 0.0) {
+            let spent = ctrl.snapshot().total_cost.unwrap_or(0.0)
+                + running.iter().filter_map(|&i| live_cost(&paths[i])).sum::<f64>();
+            if spent >= limit && halted != Some("over-budget") {
+                tracing::info!("Batch budget of ${:.2} reached", limit);
+                halted = Some("over-budget");
+                for &i in &running {
+                    let _ = stop_build(app.clone(), ctrl.snapshot().projects[i].project_id.clone()).await;
+                }
+            }
+        }
+        if ctrl.stop_requested.load(Ordering::SeqCst) && halted != Some("canceled") {
+            halted = Some("canceled");
+            for &i in &running {
+                let _ = stop_build(app.clone(), ctrl.snapshot().projects[i].project_id.clone()).await;
+            }
+        }
+
+        // Start builds while there is room
+        while halted.is_none() && running.len() < max_parallel && next < paths.len() {
+            let i = next;
+            next += 1;
+            let project_id = ctrl.snapshot().projects[i].project_id.clone();
+            match launch_build(app.clone(), project_id, paths[i].clone(), options.clone(), None, HashMap::new()).await {
+                Ok(info) => {
+                    ctrl.update(|r| {
+                        r.projects[i].status = "running".to_string();
+                        r.projects[i].build_id = Some(info.build_id);
+                    });
+                    running.push(i);
+                }
+                Err(e) => ctrl.update(|r| {
+                    r.projects[i].status = "error".to_string();
+                    r.projects[i].error = Some(e);
+                }),
+            }
+        }
+
+        if !finished.is_empty() || halted.is_some() || running.is_empty() {
+            let _ = app.emit("batch-build-progress", ctrl.snapshot());
+        }
+        if running.is_empty() && (halted.is_some() || next >= paths.len()) {
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    ctrl.update(|r| {
+        for project in r.projects.iter_mut().filter(|p| p.status == "pending") {
+            project.status = "skipped".to_string();
+        }
+        let all_complete = r.projects.iter().all(|p| p.status == "complete");
+        r.outcome = halted
+            .unwrap_or(if all_complete { "complete" } else { "incomplete" })
+            .to_string();
+        r.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        r.duration_ms = started.elapsed().as_millis() as u64;
+    });
+    let report = ctrl.snapshot();
+    if let Err(e) = write_report(&app, &report) {
+        tracing::error!("Batch build: {}", e);
+    }
+    if let Ok(mut batches) = BATCHES.lock() {
+        batches.remove(&report.batch_id);
+    }
+    let _ = app.emit("batch-build-finished", report);
+}
+
+/// Builds several projects as one batch, in the order given. See the module
+/// docs for how `policy` shapes it.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_batch_build(
+    app: AppHandle,
+    project_ids: Vec<String>,
+    policy: Option<BatchPolicy>,
+) -> Result<BatchReport, String> {
+    if project_ids.is_empty() {
+        return Err("No projects selected".to_string());
+    }
+    let projects = load_projects(app.clone())?;
+    let mut paths = Vec::new();
+    let mut results = Vec::new();
+    for id in &project_ids {
+        let project = projects
+            .iter()
+            .find(|p| p.id == *id)
+            .ok_or_else(|| format!("Project not found: {}", id))?;
+        if get_build(id)?.is_some() {
+            return Err(format!("A build is already running for {}", project.name));
+        }
+        paths.push(project.path.clone());
+        results.push(BatchProjectResult {
+            project_id: project.id.clone(),
+            project_name: project.name.clone(),
+            status: "pending".to_string(),
+            build_id: None,
+            passed: 0,
+            failed: 0,
+            total_tokens: None,
+            total_cost: None,
+            duration_ms: 0,
+            error: None,
+        });
+    }
+
+    let policy = policy.unwrap_or_default();
+    let batch_id = format!(
+        "batch-{}-{}",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let ctrl = Arc::new(BatchControl {
+        report: Mutex::new(BatchReport {
+            batch_id: batch_id.clone(),
+            outcome: "running".to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            duration_ms: 0,
+            max_total_cost: policy.max_total_cost,
+            projects: results,
+            passed: 0,
+            failed: 0,
+            total_cost: None,
+            display_currency: Some(currency::display_currency(&app)),
+        }),
+        stop_requested: AtomicBool::new(false),
+    });
+    BATCHES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .insert(batch_id, ctrl.clone());
+
+    let report = ctrl.snapshot();
+    tauri::async_runtime::spawn(run_batch(app, ctrl, paths, policy));
+    Ok(report)
+}
+
+/// Stops a batch: its running builds are stopped and the rest skipped.
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
+pub fn stop_batch_build(batch_id: String) -> Result<(), String> {
+    let batches = BATCHES.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let ctrl = batches
+        .get(&batch_id)
+        .ok_or_else(|| format!("No batch is running with ID {}", batch_id))?;
+    ctrl.stop_requested.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Lists running batches.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn list_batch_builds() -> Result<Vec<BatchReport>, String> {
+    let batches = BATCHES.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(batches.values().map(|ctrl| ctrl.snapshot()).collect())
+}
+
+/// Lists finished batches' reports, newest first.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn list_batch_reports(app: AppHandle) -> Result<Vec<BatchReport>, String> {
+    let Ok(entries) = fs::read_dir(reports_dir(&app)?) else {
+        return Ok(Vec::new());
+    };
+    let mut reports: Vec<BatchReport> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())
+        .collect();
+    reports.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(reports)
+}
@@ -0,0 +1,306 @@
+//! Project files for story prompts.
+//!
+//! The story prompt gets the project files most relevant to the story, most
+//! relevant first, until a token budget is spent:
+//!
+//! 1. Files the story mentions, by path or by name.
+//! 2. Files the design mentions.
+//! 3. Files with uncommitted changes, then files changed in recent commits.
+//!
+//! A file the story mentions that doesn't fit whole is cut to what does; any
+//! other file that doesn't fit is left out. Left-out files are listed in the
+//! prompt and the build log, so a prompt never silently outgrows the budget.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use regex::Regex;
+use serde::Serialize;
+
+use super::plan::TOKENS_PER_CHAR;
+use crate::git_binary;
+use crate::models::{Design, Story};
+
+/// Commits whose changed files count as recent.
+const RECENT_COMMITS: usize = 5;
+
+/// Larger files are never included.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Least of the budget worth cutting a mentioned file down to.
+const MIN_PARTIAL_TOKENS: u64 = 400;
+
+/// A bare file name is looked up when it names at most this many files.
+const MAX_NAME_MATCHES: usize = 3;
+
+/// Directories never included, beyond what .gitignore excludes.
+const SKIPPED_DIRS: &[&str] = &[".git", ".ideate", ".ideate-worktrees", "node_modules", "target", "dist"];
+
+lazy_static::lazy_static! {
+    static ref PATH_RE: Regex =
+        Regex::new(r"[A-Za-z0-9_@\-][A-Za-z0-9_@.\-/]*\.[A-Za-z][A-Za-z0-9]{0,7}\b").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextFile {
+    /// Relative to the project.
+    pub path: String,
+    /// "story", "design", "uncommitted", or "recent".
+    pub reason: String,
+    pub tokens: u64,
+    pub content: String,
+    /// Only the start of the file is included.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+    pub tokens: u64,
+}
+
+/// Files chosen for one story prompt.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryFiles {
+    pub files: Vec<ContextFile>,
+    /// Relevant files that were over the budget or too large.
+    pub skipped: Vec<SkippedFile>,
+    pub tokens: u64,
+    pub budget: u64,
+}
+
+impl StoryFiles {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.skipped.is_empty()
+    }
+
+    /// One line for the build log.
+    pub(crate) fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} file{}, ~{} of {} tokens",
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" },
+            self.tokens,
+            self.budget
+        );
+        let truncated = self.files.iter().filter(|f| f.truncated).count();
+        if truncated > 0 {
+            summary.push_str(&format!(", {} cut short", truncated));
+        }
+        if !self.skipped.is_empty() {
+            let mut names: Vec<&str> = self.skipped.iter().take(5).map(|f| f.path.as_str()).collect();
+            let more = format!("{} more", self.skipped.len().saturating_sub(5));
+            if self.skipped.len() > 5 {
+                names.push(&more);
+            }
+            summary.push_str(&format!("; left out {}", names.join(", ")));
+        }
+        summary
+    }
+}
+
+pub(crate) fn estimate_tokens(chars: usize) -> u64 {
+    (chars as f64 * TOKENS_PER_CHAR).ceil() as u64
+}
+
+fn git_lines(working_directory: &str, args: &[&str]) -> Vec<String> {
+    let Ok(output) = git_binary::command().args(args).current_dir(working_directory).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Skips ignored directories, and `.env` files, which may hold secrets.
+fn is_skipped(path: &str) -> bool {
+    path.split('/').any(|part| part == ".." || SKIPPED_DIRS.contains(&part))
+        || path.rsplit('/').next().is_some_and(|name| name.starts_with(".env"))
+}
+
+/// Project files, listed lazily for looking up bare file names.
+struct ProjectFiles<'a> {
+    root: &'a str,
+    listed: Option<Vec<String>>,
+}
+
+impl ProjectFiles<'_> {
+    fn list(&mut self) -> &[String] {
+        let root = self.root;
+        self.listed.get_or_insert_with(|| {
+            git_lines(root, &["ls-files", "--cached", "--others", "--exclude-standard"])
+        })
+    }
+
+    /// Files a mention in prose refers to: the path itself if it exists, else
+    /// the files with that name, unless there are too many to be sure.
+    fn resolve(&mut self, mention: &str) -> Vec<String> {
+        let mention = mention.trim_start_matches("./").trim_matches('/');
+        if mention.is_empty() || is_skipped(mention) {
+            return Vec::new();
+        }
+        if Path::new(self.root).join(mention).is_file() {
+            return vec![mention.to_string()];
+        }
+        if mention.contains('/') {
+            let suffix = format!("/{}", mention);
+            return self.list().iter().filter(|f| f.ends_with(&suffix)).take(1).cloned().collect();
+        }
+        let suffix = format!("/{}", mention);
+        let matches: Vec<String> = self
+            .list()
+            .iter()
+            .filter(|f| *f == mention || f.ends_with(&suffix))
+            .cloned()
+            .collect();
+        if matches.len() <= MAX_NAME_MATCHES {
+            matches
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn mentioned_in(&mut self, text: &str) -> Vec<String> {
+        PATH_RE
+            .find_iter(text)
+            .flat_map(|m| self.resolve(m.as_str()))
+            .collect()
+    }
+}
+
+fn story_text(story: &Story) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        story.title,
+        story.description,
+        story.acceptance_criteria.join("\n"),
+        story.notes
+    )
+}
+
+/// Files changed but not committed, then files changed by recent commits.
+fn changed_files(working_directory: &str) -> (Vec<String>, Vec<String>) {
+    let uncommitted = git_lines(working_directory, &["status", "--porcelain", "--untracked-files=all"])
+        .into_iter()
+        .filter_map(|line| {
+            // "XY path", or "XY old -> new" for renames
+            let path = line.get(3..)?;
+            Some(path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"').to_string())
+        })
+        .collect();
+    let recent = git_lines(
+        working_directory,
+        &["log", "-n", &RECENT_COMMITS.to_string(), "--name-only", "--pretty=format:"],
+    );
+    (uncommitted, recent)
+}
+
+/// Picks the files to show the agent with `story`, reading them from
+/// `working_directory`. A `budget` of 0 picks none.
+pub(crate) fn assemble(working_directory: &str, story: &Story, design: Option<&Design>, budget: u64) -> StoryFiles {
+    let mut result = StoryFiles {
+        budget,
+        ..Default::default()
+    };
+    if budget == 0 {
+        return result;
+    }
+
+    let mut project = ProjectFiles {
+        root: working_directory,
+        listed: None,
+    };
+    let mut candidates: Vec<(String, &str)> = Vec::new();
+    for path in project.mentioned_in(&story_text(story)) {
+        candidates.push((path, "story"));
+    }
+    if let Some(design) = design {
+        let text = serde_json::to_string(design).unwrap_or_default().replace("\\n", "\n");
+        for path in project.mentioned_in(&text) {
+            candidates.push((path, "design"));
+        }
+    }
+    let (uncommitted, recent) = changed_files(working_directory);
+    candidates.extend(uncommitted.into_iter().map(|p| (p, "uncommitted")));
+    candidates.extend(recent.into_iter().map(|p| (p, "recent")));
+
+    let mut seen = HashSet::new();
+    for (path, reason) in candidates {
+        if is_skipped(&path) || !seen.insert(path.clone()) {
+            continue;
+        }
+        let full_path = Path::new(working_directory).join(&path);
+        let Ok(metadata) = std::fs::metadata(&full_path) else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        if metadata.len() > MAX_FILE_BYTES {
+            result.skipped.push(SkippedFile {
+                path,
+                reason: "too large".to_string(),
+                tokens: estimate_tokens(metadata.len() as usize),
+            });
+            continue;
+        }
+        // Binary files aren't worth any of the budget
+        let Ok(content) = std::fs::read(&full_path) else {
+            continue;
+        };
+        if content.contains(&0) {
+            continue;
+        }
+        let content = String::from_utf8_lossy(&content).into_owned();
+        let tokens = estimate_tokens(content.len());
+        let remaining = budget - result.tokens;
+
+        if tokens <= remaining {
+            result.tokens += tokens;
+            result.files.push(ContextFile {
+                path,
+                reason: reason.to_string(),
+                tokens,
+                content,
+                truncated: false,
+            });
+        } else if reason == "story" && remaining >= MIN_PARTIAL_TOKENS {
+            let max_chars = (remaining as f64 / TOKENS_PER_CHAR) as usize;
+            let mut cut = (0..=max_chars.min(content.len()))
+                .rev()
+                .find(|i| content.is_char_boundary(*i))
+                .unwrap_or(0);
+            // End on a whole line when there is one
+            if let Some(newline) = content[..cut].rfind('\n') {
+                cut = newline + 1;
+            }
+            let content = content[..cut].to_string();
+            let tokens = estimate_tokens(content.len());
+            result.tokens += tokens;
+            result.files.push(ContextFile {
+                path,
+                reason: reason.to_string(),
+                tokens,
+                content,
+                truncated: true,
+            });
+        } else {
+            result.skipped.push(SkippedFile {
+                path,
+                reason: "over budget".to_string(),
+                tokens,
+            });
+        }
+    }
+    result
+}
@@ -0,0 +1,234 @@
+//! Cost and completion forecasts for a build.
+//!
+//! Earlier builds' spend and duration, divided by the estimated size of the
+//! stories they ran, give a burn rate per estimated token. The stories still
+//! to go are projected at that rate. While a build runs, its finished stories
+//! count towards the rate too, and a fresh forecast is emitted as
+//! `build-forecast` after each one. Without any history the plan's per-agent
+//! price estimates are used, and there is no completion time.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::plan::{estimate_cost, estimate_tokens};
+use super::report::{read_build_reports, StoryReport};
+use super::{live_build, read_session, resolve_agent, BuildOptions};
+use crate::models::{CostHistory, Preferences};
+use crate::preferences::load_preferences_internal;
+use crate::projects::{load_cost_history, load_prd, load_project_settings};
+use crate::scheduler::StoryGraph;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryForecast {
+    pub story_id: String,
+    pub title: String,
+    pub estimated_tokens: u64,
+    pub cost: Option<f64>,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostForecast {
+    /// The running build, if there is one; otherwise the forecast is for a
+    /// build of every story that hasn't passed.
+    pub build_id: Option<String>,
+    /// Stories of the running build that passed or failed.
+    pub completed_stories: usize,
+    pub remaining_stories: usize,
+    /// What the running build has spent so far.
+    pub spent_cost: f64,
+    pub elapsed_ms: u64,
+    pub remaining_cost: Option<f64>,
+    /// Spent plus remaining.
+    pub total_cost: Option<f64>,
+    pub remaining_ms: Option<u64>,
+    pub estimated_completion_at: Option<String>,
+    /// "history" when earlier builds give the rates, "estimates" otherwise.
+    pub basis: String,
+    /// Builds, including the running one, the rates are drawn from.
+    pub sample_builds: usize,
+    pub stories: Vec<StoryForecast>,
+}
+
+/// Payload of `build-forecast`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildForecastEvent {
+    pub project_id: String,
+    #[serde(flatten)]
+    pub forecast: CostForecast,
+}
+
+/// Spend and time against the estimated tokens they went to.
+#[derive(Debug, Default)]
+struct BurnRate {
+    cost: f64,
+    cost_tokens: u64,
+    ms: u64,
+    ms_tokens: u64,
+    builds: usize,
+}
+
+impl BurnRate {
+    fn add(&mut self, cost: Option<f64>, ms: Option<u64>, tokens: u64) {
+        if tokens == 0 {
+            return;
+        }
+        if let Some(cost) = cost.filter(|c| *c > 0.0) {
+            self.cost += cost;
+            self.cost_tokens += tokens;
+        }
+        if let Some(ms) = ms.filter(|ms| *ms > 0) {
+            self.ms += ms;
+            self.ms_tokens += tokens;
+        }
+        self.builds += 1;
+    }
+
+    fn cost_per_token(&self) -> Option<f64> {
+        (self.cost_tokens > 0).then(|| self.cost / self.cost_tokens as f64)
+    }
+
+    fn ms_per_token(&self) -> Option<f64> {
+        (self.ms_tokens > 0).then(|| self.ms as f64 / self.ms_tokens as f64)
+    }
+}
+
+/// Cost attributed to a build in costs.json, from its agents' logs.
+fn attributed_cost(history: &CostHistory, build_id: &str) -> Option<f64> {
+    let label = format!("Build {}", build_id);
+    let costs: Vec<f64> = history
+        .entries
+        .iter()
+        .filter(|e| {
+            e.description
+                .strip_prefix(&label)
+                .is_some_and(|rest| rest.starts_with([':', ' ']))
+        })
+        .filter_map(|e| e.cost)
+        .collect();
+    (!costs.is_empty()).then(|| costs.iter().sum())
+}
+
+fn reported_cost(stories: &[StoryReport]) -> Option<f64> {
+    stories.iter().map(|s| s.cost).sum::<Option<f64>>().filter(|c| *c > 0.0)
+}
+
+/// Forecasts the running build of `project_path`, or a build of its
+/// remaining stories if none is running.
+pub(crate) fn forecast(app: &AppHandle, project_path: &str) -> Result<CostForecast, String> {
+    let prd = load_prd(project_path.to_string())?.ok_or("prd.json not found")?;
+    let graph = StoryGraph::analyze(&prd.user_stories);
+    let estimates: HashMap<&str, u64> = prd
+        .user_stories
+        .iter()
+        .map(|s| {
+            let dependencies = graph.get(&s.id).map_or(0, |d| d.prerequisites.len());
+            (s.id.as_str(), estimate_tokens(s, dependencies))
+        })
+        .collect();
+    // Stories since removed from the PRD count as an average one
+    let average = match estimates.len() {
+        0 => 0,
+        n => estimates.values().sum::<u64>() / n as u64,
+    };
+    let estimate = |id: &str| estimates.get(id).copied().unwrap_or(average);
+
+    let history = load_cost_history(project_path.to_string()).unwrap_or(CostHistory { entries: Vec::new() });
+    let mut rate = BurnRate::default();
+    for report in read_build_reports(project_path) {
+        let tokens = report.stories.iter().map(|s| estimate(&s.story_id)).sum();
+        let cost = attributed_cost(&history, &report.build_id)
+            .or_else(|| reported_cost(&report.stories))
+            .or(report.total_cost);
+        rate.add(cost, Some(report.duration_ms), tokens);
+    }
+
+    let live = live_build(project_path);
+    let now = chrono::Utc::now();
+    let (build_id, elapsed_ms, finished, spent_cost) = match &live {
+        Some((build_id, started_at, reports)) => {
+            let elapsed_ms = chrono::DateTime::parse_from_rfc3339(started_at)
+                .map(|t| (now - t.with_timezone(&chrono::Utc)).num_milliseconds().max(0) as u64)
+                .unwrap_or(0);
+            let finished: Vec<&StoryReport> =
+                reports.iter().filter(|r| r.status == "passed" || r.status == "failed").collect();
+            let spent = reports.iter().filter_map(|r| r.cost).sum::<f64>();
+            (Some(build_id.clone()), elapsed_ms, finished, spent)
+        }
+        None => (None, 0, Vec::new(), 0.0),
+    };
+    if !finished.is_empty() {
+        // Elapsed time also covers stories still running, so this errs long
+        let tokens = finished.iter().map(|r| estimate(&r.story_id)).sum();
+        let cost = finished.iter().map(|r| r.cost).sum::<Option<f64>>();
+        rate.add(cost, Some(elapsed_ms), tokens);
+    }
+
+    let done: HashSet<&str> = finished.iter().map(|r| r.story_id.as_str()).collect();
+    let selected: Option<Vec<String>> = live
+        .as_ref()
+        .and_then(|_| read_session(project_path).ok().flatten())
+        .map(|session| session.story_ids);
+    let remaining: Vec<_> = prd
+        .user_stories
+        .iter()
+        .filter(|s| !s.passes && !done.contains(s.id.as_str()))
+        .filter(|s| selected.as_ref().is_none_or(|ids| ids.contains(&s.id)))
+        .collect();
+
+    let (cost_per_token, ms_per_token) = (rate.cost_per_token(), rate.ms_per_token());
+    let agent_id = cost_per_token.is_none().then(|| {
+        let prefs: Preferences = load_preferences_internal(app).unwrap_or_default();
+        let settings = load_project_settings(project_path.to_string()).ok().flatten();
+        resolve_agent(settings.as_ref(), &prefs, &BuildOptions::default())
+    });
+    let stories: Vec<StoryForecast> = remaining
+        .iter()
+        .map(|story| {
+            let tokens = estimate(&story.id);
+            let agent = story.agent.as_deref().or(agent_id.as_deref()).unwrap_or_default();
+            StoryForecast {
+                story_id: story.id.clone(),
+                title: story.title.clone(),
+                estimated_tokens: tokens,
+                cost: cost_per_token
+                    .map(|rate| rate * tokens as f64)
+                    .or_else(|| estimate_cost(agent, story.model.as_deref(), tokens)),
+                duration_ms: ms_per_token.map(|rate| (rate * tokens as f64).round() as u64),
+            }
+        })
+        .collect();
+
+    let remaining_cost = stories.iter().map(|s| s.cost).sum::<Option<f64>>();
+    let remaining_ms = stories.iter().map(|s| s.duration_ms).sum::<Option<u64>>();
+    Ok(CostForecast {
+        build_id,
+        completed_stories: finished.len(),
+        remaining_stories: stories.len(),
+        spent_cost,
+        elapsed_ms,
+        remaining_cost,
+        total_cost: remaining_cost.map(|c| c + spent_cost),
+        remaining_ms,
+        estimated_completion_at: remaining_ms
+            .map(|ms| (now + chrono::Duration::milliseconds(ms as i64)).to_rfc3339()),
+        basis: if cost_per_token.is_some() { "history" } else { "estimates" }.to_string(),
+        sample_builds: rate.builds,
+        stories,
+    })
+}
+
+/// Projects the total cost and completion time of the running build, or of
+/// a build of the remaining stories, from earlier builds' burn rate.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn forecast_costs(app: AppHandle, project_path: String) -> Result<CostForecast, String> {
+    tokio::task::spawn_blocking(move || forecast(&app, &project_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,121 @@
+//! Append-only build event journal.
+//!
+//! Every significant step of a build (agent spawn and exit, snapshots,
+//! merges, rollbacks, retries, budget warnings) is appended as one JSON line
+//! to `.ideate/events.jsonl`, so what an unattended run did can be
+//! reconstructed afterwards.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(feature = "gui")]
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::projects::load_projects;
+use crate::utils::get_ideate_dir;
+
+lazy_static::lazy_static! {
+    /// Keeps concurrent stories from interleaving partial lines.
+    static ref JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEvent {
+    pub timestamp: String,
+    #[serde(default)]
+    pub build_id: Option<String>,
+    /// e.g. "spawn", "exit", "snapshot", "merge", "rollback", "budget-warning"
+    pub kind: String,
+    #[serde(default)]
+    pub story_id: Option<String>,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+fn get_journal_path(project_path: &str) -> PathBuf {
+    get_ideate_dir(project_path).join("events.jsonl")
+}
+
+/// Appends an event to the project's journal.
+pub fn append(
+    project_path: &str,
+    build_id: Option<&str>,
+    kind: &str,
+    story_id: Option<&str>,
+    data: serde_json::Value,
+) -> Result<(), String> {
+    let event = JournalEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        build_id: build_id.map(str::to_string),
+        kind: kind.to_string(),
+        story_id: story_id.map(str::to_string),
+        data,
+    };
+    let line = serde_json::to_string(&event).map_err(|e| format!("Failed to serialize event: {}", e))?;
+
+    let _guard = JOURNAL_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let dir = get_ideate_dir(project_path);
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_journal_path(project_path))
+        .map_err(|e| format!("Failed to open events.jsonl: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write events.jsonl: {}", e))
+}
+
+/// Reads every event in a project's journal, oldest first. Unparseable lines
+/// are skipped.
+#[cfg(feature = "gui")]
+pub(crate) fn read_events(project_path: &str) -> Result<Vec<JournalEvent>, String> {
+    let path = get_journal_path(project_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to read events.jsonl: {}", e))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<JournalEvent>(&line).ok())
+        .collect())
+}
+
+/// Reads journal events for a project, oldest first. `since` is an RFC 3339
+/// timestamp; only later events are returned.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn read_build_events(
+    app: AppHandle,
+    project_id: String,
+    since: Option<String>,
+    build_id: Option<String>,
+) -> Result<Vec<JournalEvent>, String> {
+    let project = load_projects(app)?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| format!("Project {} not found", project_id))?;
+
+    let since = since
+        .map(|s| chrono::DateTime::parse_from_rfc3339(&s).map_err(|e| format!("Invalid timestamp: {}", e)))
+        .transpose()?;
+
+    Ok(read_events(&project.path)?
+        .into_iter()
+        .filter(|event| build_id.is_none() || event.build_id == build_id)
+        .filter(|event| match since {
+            Some(since) => chrono::DateTime::parse_from_rfc3339(&event.timestamp)
+                .map(|t| t > since)
+                .unwrap_or(false),
+            None => true,
+        })
+        .collect())
+}
@@ -0,0 +1,2788 @@
+//! Backend build orchestration.
+//!
+//! Owns the story build loop — select a story, prepare a snapshot or worktree,
+//! spawn the agent, verify its output, and finalize — so a build keeps running
+//! when the window is closed. The UI starts and stops builds with commands and
+//! observes progress through `build-*` events.
+//!
+//! In-progress state is journaled to `.ideate/build-session.json` so a build
+//! interrupted by a crash or quit can be picked up with `recover_build`.
+//!
+//! Each story goes through `story_loop`, which the headless CLI shares; the
+//! rest of this module is the app's side of it and needs the `gui` feature.
+
+#[cfg(feature = "gui")]
+pub mod batch;
+pub mod context;
+#[cfg(feature = "gui")]
+pub mod forecast;
+pub mod journal;
+pub mod modes;
+pub mod plan;
+#[cfg(feature = "gui")]
+pub mod preflight;
+pub mod report;
+pub mod routing;
+pub mod story_loop;
+#[cfg(feature = "gui")]
+pub mod swarm;
+pub mod timings;
+
+use std::collections::HashMap;
+#[cfg(feature = "gui")]
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+#[cfg(feature = "gui")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "gui")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "gui")]
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+#[cfg(feature = "gui")]
+use tokio::sync::Notify;
+#[cfg(feature = "gui")]
+use tokio::task::JoinSet;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
+use crate::hooks::{HookContext, HookKind};
+use crate::models::{Design, Preferences, ProjectSettings, Story};
+#[cfg(feature = "gui")]
+use crate::models::{AgentExitEvent, E2eRun, OutputMode, ProjectState, RoutingRule, StoredProject, StoryFailure, StoryRetryInfo, TerminationReason};
+#[cfg(feature = "gui")]
+use crate::notifier::{self, BuildNotification};
+#[cfg(feature = "gui")]
+use crate::preferences::load_preferences_internal;
+use crate::prompt_templates::{story_context, PromptEngine};
+#[cfg(feature = "gui")]
+use crate::process::{kill_agent_blocking, wait_for_process, OutputSink};
+#[cfg(feature = "gui")]
+use crate::process::{spawn_tracked_process, OutputOptions};
+use crate::projects::load_prd;
+#[cfg(feature = "gui")]
+use crate::projects::{load_project_settings, update_prd, update_project_state};
+#[cfg(feature = "gui")]
+use crate::projects::load_projects;
+#[cfg(feature = "gui")]
+use crate::scheduler::StoryGraph;
+use context::StoryFiles;
+#[cfg(feature = "gui")]
+use forecast::BuildForecastEvent;
+use modes::{get_mode, BuildMode};
+#[cfg(feature = "gui")]
+use modes::FailureAction;
+#[cfg(feature = "gui")]
+use report::{parse_usage, BuildReport, RunUsage, StoryReport};
+#[cfg(feature = "gui")]
+use timings::AttemptTimer;
+use crate::utils::get_ideate_dir;
+#[cfg(feature = "gui")]
+use crate::worktree::WorktreeResult;
+#[cfg(feature = "gui")]
+use crate::throttle::{self, ThrottleSignal};
+#[cfg(feature = "gui")]
+use crate::{currency, git_binary, worktree};
+#[cfg(feature = "gui")]
+use story_loop::{AttemptFailure, StoryAgent, StoryHost, StoryLimits};
+#[cfg(feature = "gui")]
+use crate::cost_attribution::{self, TrackedRun};
+#[cfg(feature = "gui")]
+use crate::cost_capture::CostCapture;
+#[cfg(feature = "gui")]
+use crate::screenshot::{self, Viewport};
+#[cfg(feature = "gui")]
+use crate::{e2e, event_bus, preview_proxy, progress, telemetry, tray, webhooks};
+#[cfg(feature = "gui")]
+use crate::integrations::{linear, tunnels};
+
+/// Lines of agent output kept for error detection and cost parsing.
+pub(crate) const RECENT_OUTPUT_LINES: usize = 50;
+
+/// How often a running agent's reported usage is checked against the limits.
+#[cfg(feature = "gui")]
+const BUDGET_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+const DEFAULT_AGENT_ID: &str = "claude-code";
+
+/// Mirrors the frontend's `storyImplementation` default prompt.
+pub(crate) const DEFAULT_STORY_PROMPT: &str = "Implement the following user story:
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+{{notes}}
+{% if stack %}
+
+{% include \"stack\" %}
+{% endif %}
+{% if design %}
+
+{% include \"design\" %}
+{% endif %}
+{% if files %}
+
+## Relevant Files
+{% include \"files\" %}
+{% endif %}
+
+Please implement this user story following the acceptance criteria. When done, ensure all quality checks pass (typecheck, lint, build).";
+
+/// Set on app shutdown so stopping builds leaves their sessions for recovery.
+#[cfg(feature = "gui")]
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "gui")]
+lazy_static::lazy_static! {
+    static ref BUILDS: Mutex<HashMap<String, Arc<BuildControl>>> = Mutex::new(HashMap::new());
+}
+
+lazy_static::lazy_static! {
+    static ref RESULT_ERROR_RE: Regex =
+        Regex::new(r#""type"\s*:\s*"result"[^}]*"is_error"\s*:\s*true"#).unwrap();
+    static ref AGENT_ERROR_RES: Vec<Regex> = [
+        r"(?m)^❌\s*Failed",
+        r"(?mi)^Failed:\s*Unknown error",
+        r"(?i)stream ended without producing any output",
+        r"(?mi)^amp error:",
+        r"(?mi)^claude error:",
+        r"(?mi)^Error:\s*stream ended",
+        r"(?i)\bconnection refused\b",
+        r"(?i)\bauthentication failed\b",
+        r"(?i)\brate limit exceeded\b",
+        r"(?i)\bapi key invalid\b",
+        r"\b(SIGTERM|SIGKILL)\b",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).unwrap())
+    .collect();
+    static ref AUTH_ERROR_RES: Vec<Regex> = [
+        r"(?i)\bauthentication failed\b",
+        r"(?i)\bapi key invalid\b",
+        r"(?i)\binvalid api key\b",
+        r"(?i)\bnot (?:logged|signed) in\b",
+        r"(?i)please (?:run )?(?:/login|\S+ login)\b",
+        r"(?i)\b401 unauthorized\b",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).unwrap())
+    .collect();
+    static ref WAITING_FOR_INPUT_RES: Vec<Regex> = [
+        r"(?i)waiting for (?:your |user )?input",
+        r"(?i)\((?:y/n|yes/no)\)\s*:?\s*$",
+        r"(?i)\[y/n\]\s*:?\s*$",
+        r"(?i)press enter to continue",
+        r"(?i)do you want to (?:proceed|continue)\?",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).unwrap())
+    .collect();
+}
+
+/// Shared state for one running build.
+#[cfg(feature = "gui")]
+struct BuildControl {
+    /// Identifies this run's report in `.ideate/reports/`.
+    build_id: String,
+    project_id: String,
+    project_path: String,
+    mode: &'static dyn BuildMode,
+    started_at: String,
+    started: Instant,
+    stop_requested: AtomicBool,
+    /// Set by `pause_build`; the loop stops at the next story boundary.
+    pause_requested: AtomicBool,
+    /// True while the loop is idle waiting for `resume_build`.
+    paused: AtomicBool,
+    /// Wakes a paused loop on resume or stop.
+    wake: Notify,
+    /// Failure context for stories that will be retried, keyed by story ID.
+    retries: Mutex<HashMap<String, StoryRetryContext>>,
+    /// Running agent process IDs keyed by story ID.
+    running: Mutex<HashMap<String, String>>,
+    /// Worktrees left by an interrupted build, reused when their story reruns.
+    recovered_worktrees: Mutex<HashMap<String, WorktreeResult>>,
+    /// Serializes PRD read-modify-write between concurrent stories.
+    prd_lock: tokio::sync::Mutex<()>,
+    /// Serializes state.json read-modify-write between concurrent stories.
+    state_lock: Mutex<()>,
+    /// Per-story results for the build report, in the order stories started.
+    story_reports: Mutex<Vec<StoryReport>>,
+    /// Highest budget threshold already warned about, keyed by story ID for
+    /// token limits and `"build"` for the cost limit.
+    budget_warnings: Mutex<HashMap<String, u32>>,
+    /// Phase times of each story's running attempt, keyed by story ID.
+    story_timers: Mutex<HashMap<String, AttemptTimer>>,
+    /// When each story's last attempt ended, so its next one's queue wait
+    /// counts from there rather than from the build's start.
+    queued_since: Mutex<HashMap<String, Instant>>,
+    /// Limits from preferences; replaced when preferences change mid-build.
+    limits: Mutex<StoryLimits>,
+}
+
+#[cfg(feature = "gui")]
+impl BuildControl {
+    fn stopping(&self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+
+    fn pause_requested(&self) -> bool {
+        self.pause_requested.load(Ordering::SeqCst)
+    }
+
+    fn to_info(&self) -> BuildStatusInfo {
+        // Swarm workers run under "<story>#<task>"
+        let mut running_story_ids: Vec<String> = self
+            .running
+            .lock()
+            .map(|r| r.keys().map(|k| k.split('#').next().unwrap_or(k).to_string()).collect())
+            .unwrap_or_default();
+        running_story_ids.sort();
+        running_story_ids.dedup();
+
+        BuildStatusInfo {
+            build_id: self.build_id.clone(),
+            project_id: self.project_id.clone(),
+            project_path: self.project_path.clone(),
+            mode: self.mode.id().to_string(),
+            status: if self.stopping() {
+                "stopping"
+            } else if self.paused.load(Ordering::SeqCst) {
+                "paused"
+            } else if self.pause_requested() {
+                "pausing"
+            } else {
+                "running"
+            }
+            .to_string(),
+            started_at: self.started_at.clone(),
+            running_story_ids,
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStatusInfo {
+    pub build_id: String,
+    pub project_id: String,
+    pub project_path: String,
+    /// Build mode id, e.g. "ralph" or "parallel"
+    pub mode: String,
+    /// "running", "pausing", "paused", or "stopping"
+    pub status: String,
+    pub started_at: String,
+    pub running_story_ids: Vec<String>,
+}
+
+/// Options for `start_build`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildOptions {
+    /// Start at this story (in priority order), skipping earlier ones.
+    #[serde(default)]
+    pub from_story_id: Option<String>,
+    /// Only build these stories.
+    #[serde(default)]
+    pub story_ids: Option<Vec<String>>,
+    /// Agent to use instead of the project's configured agent.
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Failure context from earlier attempts, keyed by story ID.
+    #[serde(default)]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub retry_contexts: HashMap<String, StoryRetryContext>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryRetryContext {
+    pub attempt: u32,
+    pub error_logs: String,
+}
+
+/// Journal of a build in progress - stored in .ideate/build-session.json.
+/// Removed when the build ends; its presence on startup means the build was
+/// interrupted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSession {
+    pub project_id: String,
+    pub mode: String,
+    /// Agent override the build was started with.
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Stories selected when the build started, in order.
+    pub story_ids: Vec<String>,
+    #[serde(default)]
+    pub in_flight: Vec<InFlightStory>,
+    #[serde(default)]
+    pub retries: HashMap<String, StoryRetryContext>,
+    #[serde(default)]
+    pub paused: bool,
+    pub started_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InFlightStory {
+    pub story_id: String,
+    pub attempt: u32,
+    #[serde(default)]
+    pub worktree_path: Option<String>,
+    #[serde(default)]
+    pub branch_name: Option<String>,
+    #[serde(default)]
+    pub snapshot_ref: Option<String>,
+    #[serde(default)]
+    pub snapshot_type: Option<String>,
+    pub started_at: String,
+}
+
+fn get_session_path(project_path: &str) -> PathBuf {
+    get_ideate_dir(project_path).join("build-session.json")
+}
+
+pub(crate) fn read_session(project_path: &str) -> Result<Option<BuildSession>, String> {
+    let path = get_session_path(project_path);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read build-session.json: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse build-session.json: {}", e))
+}
+
+#[cfg(feature = "gui")]
+fn write_session(project_path: &str, session: &BuildSession) -> Result<(), String> {
+    let ideate_dir = get_ideate_dir(project_path);
+
+    if !ideate_dir.exists() {
+        fs::create_dir_all(&ideate_dir)
+            .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize build session: {}", e))?;
+
+    fs::write(get_session_path(project_path), json)
+        .map_err(|e| format!("Failed to write build-session.json: {}", e))
+}
+
+#[cfg(feature = "gui")]
+fn clear_session(project_path: &str) {
+    let _ = fs::remove_file(get_session_path(project_path));
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStatusEvent {
+    pub project_id: String,
+    /// "running", "paused", or "idle"
+    pub status: String,
+    pub current_story_id: Option<String>,
+    pub current_story_title: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildLogEvent {
+    pub project_id: String,
+    pub log_type: String,
+    pub content: String,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStoryStatusEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub status: String,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildProcessEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub story_title: String,
+    pub process_id: String,
+    pub agent_id: String,
+    /// Agent this one replaced after it failed or could not run.
+    pub fallback_from: Option<String>,
+    pub executable: String,
+    pub args: Vec<String>,
+    pub working_directory: String,
+    /// Parallel agents run alongside others and aren't the build's current process.
+    pub parallel: bool,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSnapshotEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub snapshot_ref: String,
+    pub snapshot_type: String,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStoryFinishedEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub story_title: String,
+    pub process_id: Option<String>,
+    pub agent_id: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub reason: Option<String>,
+    pub failure_kind: Option<String>,
+    pub duration_ms: u64,
+    /// Tail of the agent's output, for cost parsing and retry context.
+    pub recent_output: String,
+}
+
+/// Sent once a story has failed for good, after any automatic retries.
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStoryFailedEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub story_title: String,
+    pub failure: StoryFailure,
+}
+
+/// Sent when a story's tokens or the build's cost cross a warning threshold
+/// or the limit itself.
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetWarningEvent {
+    pub project_id: String,
+    pub build_id: String,
+    /// "story" for `max_tokens_per_story`, "build" for `max_cost_per_build`.
+    pub scope: String,
+    pub story_id: Option<String>,
+    /// Percentage of the limit crossed; 100 once the limit is reached.
+    pub threshold_percent: u32,
+    /// Tokens for story scope, USD for build scope.
+    pub used: f64,
+    pub limit: f64,
+    pub build_tokens: u64,
+    pub build_cost: f64,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildMergeConflictEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub story_title: String,
+    pub branch_name: String,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildFinishedEvent {
+    pub build_id: String,
+    pub project_id: String,
+    /// "complete", "incomplete", or "canceled"
+    pub outcome: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub duration_ms: u64,
+}
+
+/// Result of running an agent on one story.
+#[cfg(feature = "gui")]
+struct AgentRun {
+    success: bool,
+    exit_code: Option<i32>,
+    reason: Option<String>,
+    /// See `StoryFailure::kind`; "canceled" when the build was stopped.
+    failure_kind: Option<&'static str>,
+    recent_output: String,
+}
+
+/// Everything a story run needs, cheap to clone into parallel tasks.
+#[cfg(feature = "gui")]
+#[derive(Clone)]
+struct BuildContext {
+    app: AppHandle,
+    ctrl: Arc<BuildControl>,
+    project_name: String,
+    agent: StoryAgent,
+    prompts: Arc<PromptEngine>,
+    /// The project's routing rules; see `routing`.
+    routing: Arc<Vec<RoutingRule>>,
+}
+
+#[cfg(feature = "gui")]
+impl BuildContext {
+    /// The highest threshold `used` has newly crossed for `key`, if any.
+    /// Each threshold warns once per build.
+    fn crossed_threshold(&self, key: &str, used: f64, limit: f64) -> Option<u32> {
+        let percent = used / limit * 100.0;
+        let highest = self
+            .limits()
+            .budget_warning_thresholds
+            .iter()
+            .copied()
+            .chain([100])
+            .filter(|t| percent >= *t as f64)
+            .max()?;
+        let mut warned = self.ctrl.budget_warnings.lock().ok()?;
+        let previous = warned.entry(key.to_string()).or_insert(0);
+        if highest <= *previous {
+            return None;
+        }
+        *previous = highest;
+        Some(highest)
+    }
+
+    /// Whether the running attempt at `story`, going by the usage in its
+    /// output so far, has gone past the story's token limit or the build's
+    /// cost limit. Returns the reason for the log.
+    fn over_budget(&self, story: &Story, recent: &Mutex<VecDeque<String>>) -> Option<String> {
+        let output = recent.lock().ok()?.iter().cloned().collect::<Vec<_>>().join("\n");
+        let usage = parse_usage(&output);
+
+        if let (Some(limit), Some(tokens)) = (self.limits().max_tokens_per_story.filter(|l| *l > 0), usage.tokens) {
+            let earlier = self
+                .ctrl
+                .story_reports
+                .lock()
+                .ok()
+                .and_then(|reports| reports.iter().find(|r| r.story_id == story.id).and_then(|r| r.tokens))
+                .unwrap_or(0);
+            if earlier + tokens >= limit as u64 {
+                return Some(format!("used {} tokens, past its {} token limit", earlier + tokens, limit));
+            }
+        }
+        if let (Some(limit), Some(cost)) = (self.limits().max_cost_per_build.filter(|l| *l > 0.0), usage.cost) {
+            let spent = self.build_cost() + cost;
+            if spent >= limit {
+                return Some(format!("brought the build to ${:.2}, past its ${:.2} budget", spent, limit));
+            }
+        }
+        None
+    }
+
+    /// Warns as a story's tokens or the build's total cost approach and pass
+    /// the configured budget. The build keeps running; `over_budget` stops
+    /// the agent that goes past a limit.
+    fn check_budget(&self, story: &Story) {
+        let (story_tokens, build_tokens, build_cost) = match self.ctrl.story_reports.lock() {
+            Ok(reports) => (
+                reports.iter().find(|r| r.story_id == story.id).and_then(|r| r.tokens),
+                reports.iter().filter_map(|r| r.tokens).sum::<u64>(),
+                reports.iter().filter_map(|r| r.cost).sum::<f64>(),
+            ),
+            Err(_) => return,
+        };
+        let emit = |scope: &str, story_id: Option<&str>, threshold: u32, used: f64, limit: f64| {
+            self.journal(
+                "budget-warning",
+                Some(&story.id),
+                json!({ "scope": scope, "thresholdPercent": threshold, "used": used, "limit": limit }),
+            );
+            let _ = event_bus::emit(
+                &self.app,
+                "budget-warning",
+                BudgetWarningEvent {
+                    project_id: self.project_id().to_string(),
+                    build_id: self.ctrl.build_id.clone(),
+                    scope: scope.to_string(),
+                    story_id: story_id.map(String::from),
+                    threshold_percent: threshold,
+                    used,
+                    limit,
+                    build_tokens,
+                    build_cost,
+                },
+            );
+        };
+
+        if let (Some(tokens), Some(limit)) = (story_tokens, self.limits().max_tokens_per_story) {
+            if limit > 0 {
+                if let Some(threshold) = self.crossed_threshold(&story.id, tokens as f64, limit as f64) {
+                    self.log(format!(
+                        "⚠ Story {} has used {} tokens, past {}% of its {} token limit",
+                        story.id, tokens, threshold, limit
+                    ));
+                    emit("story", Some(&story.id), threshold, tokens as f64, limit as f64);
+                }
+            }
+        }
+
+        if let Some(limit) = self.limits().max_cost_per_build {
+            if limit > 0.0 {
+                if let Some(threshold) = self.crossed_threshold("build", build_cost, limit) {
+                    self.log(format!("⚠ Build has spent ${:.2} of its ${:.2} budget", build_cost, limit));
+                    emit("build", None, threshold, build_cost, limit);
+                    self.notify(BuildNotification::BudgetThreshold {
+                        project_name: self.project_name.clone(),
+                        spent: build_cost,
+                        limit,
+                        currency: Some(currency::display_currency(&self.app)),
+                    });
+                }
+            }
+        }
+    }
+
+    fn emit_status(&self, status: &str, story: Option<&Story>) {
+        let _ = event_bus::emit(
+            &self.app,
+            "build-status",
+            BuildStatusEvent {
+                project_id: self.project_id().to_string(),
+                status: status.to_string(),
+                current_story_id: story.map(|s| s.id.clone()),
+                current_story_title: story.map(|s| s.title.clone()),
+            },
+        );
+        tray::refresh(&self.app);
+    }
+
+    /// Applies a change to the build's session journal.
+    fn update_session(&self, apply: impl FnOnce(&mut BuildSession)) {
+        let _guard = self.ctrl.state_lock.lock();
+        let Ok(Some(mut session)) = read_session(self.project_path()) else {
+            return;
+        };
+        apply(&mut session);
+        session.updated_at = chrono::Utc::now().to_rfc3339();
+        if let Err(e) = write_session(self.project_path(), &session) {
+            tracing::error!("Orchestrator: {}", e);
+        }
+    }
+
+    /// Journals a story as in flight. Returns its attempt number.
+    fn begin_in_flight(&self, story_id: &str) -> u32 {
+        let attempt = self
+            .ctrl
+            .retries
+            .lock()
+            .ok()
+            .and_then(|r| r.get(story_id).map(|c| c.attempt))
+            .unwrap_or(0)
+            + 1;
+        self.update_session(|session| {
+            session.in_flight.retain(|f| f.story_id != story_id);
+            session.in_flight.push(InFlightStory {
+                story_id: story_id.to_string(),
+                attempt,
+                worktree_path: None,
+                branch_name: None,
+                snapshot_ref: None,
+                snapshot_type: None,
+                started_at: chrono::Utc::now().to_rfc3339(),
+            });
+        });
+        attempt
+    }
+
+    fn update_in_flight(&self, story_id: &str, apply: impl FnOnce(&mut InFlightStory)) {
+        self.update_session(|session| {
+            if let Some(entry) = session.in_flight.iter_mut().find(|f| f.story_id == story_id) {
+                apply(entry);
+            }
+        });
+    }
+
+    fn end_in_flight(&self, story_id: &str) {
+        self.update_session(|session| session.in_flight.retain(|f| f.story_id != story_id));
+    }
+
+    fn set_phase(&self, phase: &str, story: Option<&Story>) {
+        self.update_state(|state| {
+            state.build_phase = phase.to_string();
+            state.current_story_id = story.map(|s| s.id.clone());
+        });
+        self.emit_status(phase, story);
+    }
+
+    /// Sends a notification to the desktop and the project's webhooks.
+    fn notify(&self, notification: BuildNotification) {
+        notifier::notify_desktop(&self.app, self.project_path(), &notification);
+        self.notify_webhooks(notification);
+    }
+
+    fn notify_webhooks(&self, notification: BuildNotification) {
+        let project_path = self.project_path().to_string();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = notifier::notify(&project_path, &notification).await {
+                tracing::warn!("Notifier: {}", e);
+            }
+        });
+    }
+
+    /// Spawns the agent for a story in `working_directory` and waits for it.
+    async fn run_agent(&self, story: &Story, working_directory: &str, parallel: bool) -> AgentRun {
+        let prompt = story_loop::render_prompt(self, story, working_directory);
+        self.run_agent_prompt(story, &prompt, working_directory, parallel, &story.id)
+            .await
+    }
+
+    /// Spawns the agent with `prompt` for work on a story and waits for it.
+    /// `slot` keys the process among the build's running ones, so a story can
+    /// have several agents at once.
+    async fn run_agent_prompt(
+        &self,
+        story: &Story,
+        prompt: &str,
+        working_directory: &str,
+        parallel: bool,
+        slot: &str,
+    ) -> AgentRun {
+        let args = self.agent.args(prompt);
+
+        let recent: Arc<Mutex<VecDeque<String>>> = Arc::new(Mutex::new(VecDeque::new()));
+        let sink_buffer = recent.clone();
+        let waiting_ctx = self.clone();
+        let waiting_story = story.clone();
+        let waiting_notified = AtomicBool::new(false);
+        let throttled: Arc<Mutex<Option<ThrottleSignal>>> = Arc::new(Mutex::new(None));
+        let sink_throttled = throttled.clone();
+        let throttle_agent = self.agent.agent_id.clone();
+        let sink: OutputSink = Arc::new(move |stream, line| {
+            if let Ok(mut buffer) = sink_buffer.lock() {
+                if buffer.len() == RECENT_OUTPUT_LINES {
+                    buffer.pop_front();
+                }
+                buffer.push_back(line.to_string());
+            }
+            if let Some(signal) = throttle::detect_in_output(stream, line) {
+                throttle::record(&throttle_agent, &signal, "output", chrono::Utc::now());
+                if let Ok(mut throttled) = sink_throttled.lock() {
+                    *throttled = Some(signal);
+                }
+            }
+            if detect_waiting_for_input(line) && !waiting_notified.swap(true, Ordering::SeqCst) {
+                waiting_ctx.log(format!("⏳ Agent for {} appears to be waiting for input", waiting_story.id));
+                waiting_ctx.notify(BuildNotification::AgentWaiting {
+                    project_name: waiting_ctx.project_name.clone(),
+                    story_id: waiting_story.id.clone(),
+                    story_title: waiting_story.title.clone(),
+                });
+            }
+        });
+
+        let started = Instant::now();
+        let process_id = match spawn_tracked_process(
+            self.app.clone(),
+            self.agent.executable.clone(),
+            args.clone(),
+            working_directory.to_string(),
+            None,
+            OutputOptions {
+                sink: Some(sink),
+                mode: OutputMode::Lines,
+                progress: progress::tracker_for(&self.app, &self.agent.agent_id)
+                    .map(|tracker| tracker.for_story(self.project_id(), &story.id)),
+                costs: Some(CostCapture {
+                    agent_id: self.agent.agent_id.clone(),
+                    description: format!("Story: {}", story.title),
+                }),
+            },
+            self.limits().priority,
+        )
+        .await
+        {
+            Ok(id) => id,
+            Err(e) => {
+                self.journal("spawn-error", Some(&story.id), json!({ "error": e }));
+                self.update_story_report(story, |report| report.add_attempt(0, RunUsage::default()));
+                self.finish_story_event(
+                    story,
+                    None,
+                    None,
+                    Some(e.clone()),
+                    Some("spawn-error"),
+                    0,
+                    String::new(),
+                );
+                return AgentRun {
+                    success: false,
+                    exit_code: None,
+                    reason: Some(e.clone()),
+                    failure_kind: Some("spawn-error"),
+                    recent_output: e,
+                };
+            }
+        };
+
+        if let Ok(mut running) = self.ctrl.running.lock() {
+            running.insert(slot.to_string(), process_id.clone());
+        }
+        self.journal(
+            "spawn",
+            Some(&story.id),
+            json!({
+                "processId": process_id,
+                "agentId": self.agent.agent_id,
+                "model": self.agent.model,
+                "fallbackFrom": self.agent.fallback_from,
+                "executable": self.agent.executable,
+                "workingDirectory": working_directory,
+                "parallel": parallel,
+            }),
+        );
+        let _ = event_bus::emit(
+            &self.app,
+            "build-process-started",
+            BuildProcessEvent {
+                project_id: self.project_id().to_string(),
+                story_id: story.id.clone(),
+                story_title: story.title.clone(),
+                process_id: process_id.clone(),
+                agent_id: self.agent.agent_id.clone(),
+                fallback_from: self.agent.fallback_from.clone(),
+                executable: self.agent.executable.clone(),
+                args,
+                working_directory: working_directory.to_string(),
+                parallel,
+            },
+        );
+
+        // stop_build may have fired between spawning and registering the process
+        if self.ctrl.stopping() {
+            let pid = process_id.clone();
+            let _ = tokio::task::spawn_blocking(move || kill_agent_blocking(&pid, TerminationReason::Canceled)).await;
+        }
+
+        let pid = process_id.clone();
+        let mut wait = tokio::task::spawn_blocking(move || wait_for_process(&pid));
+        let timeout_minutes = self.limits().timeout_minutes;
+        let deadline = (timeout_minutes > 0)
+            .then(|| tokio::time::Instant::now() + Duration::from_secs(u64::from(timeout_minutes) * 60));
+        let mut budget_check = tokio::time::interval(BUDGET_CHECK_INTERVAL);
+        // Set when this build stops the agent itself, as (failure kind, reason)
+        let mut stopped: Option<(&'static str, String)> = None;
+        let joined = loop {
+            tokio::select! {
+                joined = &mut wait => break joined,
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)),
+                    if deadline.is_some() && stopped.is_none() =>
+                {
+                    self.log(format!(
+                        "⏱ Story {} exceeded {} min - stopping agent",
+                        story.id, timeout_minutes
+                    ));
+                    stopped = Some(("timeout", format!("timed out after {} min", timeout_minutes)));
+                    let pid = process_id.clone();
+                    let _ = tokio::task::spawn_blocking(move || kill_agent_blocking(&pid, TerminationReason::Timeout)).await;
+                }
+                _ = budget_check.tick(), if stopped.is_none() => {
+                    if let Some(reason) = self.over_budget(story, &recent) {
+                        self.log(format!("💸 Story {} {} - stopping agent", story.id, reason));
+                        stopped = Some(("budget", reason));
+                        let pid = process_id.clone();
+                        let _ = tokio::task::spawn_blocking(move || kill_agent_blocking(&pid, TerminationReason::Budget)).await;
+                    }
+                }
+            }
+        };
+        let wait_result = joined
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|r| r);
+
+        if let Ok(mut running) = self.ctrl.running.lock() {
+            running.remove(slot);
+        }
+
+        let (exit_code, exited_ok, signal, termination_reason) = match &wait_result {
+            Ok(result) => (result.exit_code, result.success, result.signal, result.termination_reason),
+            Err(_) => (None, false, None, TerminationReason::Exited),
+        };
+        let _ = event_bus::emit(
+            &self.app,
+            "agent-exit",
+            AgentExitEvent {
+                process_id: process_id.clone(),
+                exit_code,
+                success: exited_ok,
+                signal,
+                termination_reason,
+            },
+        );
+
+        let recent_output = recent
+            .lock()
+            .map(|b| b.iter().cloned().collect::<Vec<_>>().join("\n"))
+            .unwrap_or_default();
+
+        let failure = match wait_result {
+            _ if self.ctrl.stopping() => Some(("canceled", "canceled".to_string())),
+            _ if stopped.is_some() => stopped,
+            Err(e) => Some(("process-error", e)),
+            Ok(result) if result.termination_reason == TerminationReason::Signal => Some((
+                "signal",
+                format!("killed by signal {}", result.signal.unwrap_or_default()),
+            )),
+            Ok(result) if !result.success => Some((
+                "exit-code",
+                format!(
+                    "exit code: {}",
+                    result
+                        .exit_code
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "unknown".to_string())
+                ),
+            )),
+            Ok(_) if detect_agent_error(&recent_output) => {
+                Some(("agent-error", "agent error detected in output".to_string()))
+            }
+            Ok(_) => None,
+        };
+        let throttle = throttled.lock().ok().and_then(|t| t.clone());
+        let (failure_kind, reason) = match failure {
+            // Signed-out agents fail the same way every time; fall back instead
+            Some(("exit-code" | "agent-error", _)) if detect_auth_error(&recent_output) => {
+                (Some("auth"), Some(format!("{} is not authenticated", self.agent.agent_id)))
+            }
+            // The provider turned the agent away; waiting it out doesn't cost a retry
+            Some(("exit-code" | "agent-error", _)) if throttle.is_some() => (
+                Some("throttled"),
+                throttle.map(|t| format!("provider {}: {}", t.kind.replace('-', " "), t.message)),
+            ),
+            Some((kind, reason)) => (Some(kind), Some(reason)),
+            None => (None, None),
+        };
+
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let usage = parse_usage(&recent_output);
+        self.update_story_report(story, |report| report.add_attempt(duration_ms, usage));
+        self.time_phase(&story.id, |timer| timer.agent_ms += duration_ms);
+        self.journal(
+            "exit",
+            Some(&story.id),
+            json!({
+                "processId": process_id,
+                "exitCode": exit_code,
+                "signal": signal,
+                "terminationReason": termination_reason,
+                "failureKind": failure_kind,
+                "reason": reason,
+                "durationMs": duration_ms,
+                "tokens": usage.tokens,
+                "cost": usage.cost,
+            }),
+        );
+        self.check_budget(story);
+
+        self.finish_story_event(
+            story,
+            Some(process_id),
+            exit_code,
+            reason.clone(),
+            failure_kind,
+            duration_ms,
+            recent_output.clone(),
+        );
+
+        AgentRun {
+            success: failure_kind.is_none(),
+            exit_code,
+            reason,
+            failure_kind,
+            recent_output,
+        }
+    }
+
+    /// Reloads the preview after a story lands and, when the preview proxy is
+    /// running, screenshots it into the story's evidence in the background.
+    fn refresh_preview(&self, story: &Story) {
+        preview_proxy::reload(self.project_id());
+        let Some(url) = preview_proxy::url_for(self.project_id()) else {
+            return;
+        };
+
+        let ctx = self.clone();
+        let story_id = story.id.clone();
+        tokio::spawn(async move {
+            // Give the dev server a moment to rebuild
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            let project_path = ctx.project_path().to_string();
+            let id = story_id.clone();
+            let captured = tokio::task::spawn_blocking(move || {
+                screenshot::capture(&project_path, &id, &url, Viewport::default())
+            })
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|result| result);
+            match captured {
+                Ok(shot) => {
+                    ctx.log(format!("✓ Captured preview screenshot for {}", story_id));
+                    ctx.journal("evidence", Some(&story_id), json!({ "path": shot.path, "url": shot.url }));
+                }
+                Err(e) => ctx.log(format!("Warning: Could not capture preview screenshot: {}", e)),
+            }
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn finish_story_event(
+        &self,
+        story: &Story,
+        process_id: Option<String>,
+        exit_code: Option<i32>,
+        reason: Option<String>,
+        failure_kind: Option<&'static str>,
+        duration_ms: u64,
+        recent_output: String,
+    ) {
+        let _ = event_bus::emit(
+            &self.app,
+            "build-story-finished",
+            BuildStoryFinishedEvent {
+                project_id: self.project_id().to_string(),
+                story_id: story.id.clone(),
+                story_title: story.title.clone(),
+                process_id,
+                agent_id: self.agent.agent_id.clone(),
+                success: failure_kind.is_none(),
+                exit_code,
+                reason,
+                failure_kind: failure_kind.map(str::to_string),
+                duration_ms,
+                recent_output,
+            },
+        );
+    }
+
+    /// Marks a story as passing in prd.json.
+    async fn mark_story_passed(&self, story_id: &str) -> Result<(), String> {
+        let _guard = self.ctrl.prd_lock.lock().await;
+        let project_path = self.project_path().to_string();
+        let story_id = story_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            update_prd(&project_path, |prd| {
+                if let Some(story) = prd.user_stories.iter_mut().find(|s| s.id == story_id) {
+                    story.passes = true;
+                    story.status = Some("complete".to_string());
+                }
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Emits `build-forecast` with the remaining stories re-projected.
+    fn emit_forecast(&self) {
+        let app = self.app.clone();
+        let project_id = self.project_id().to_string();
+        let project_path = self.project_path().to_string();
+        tauri::async_runtime::spawn_blocking(move || match forecast::forecast(&app, &project_path) {
+            Ok(forecast) => {
+                let _ = event_bus::emit(&app, "build-forecast", BuildForecastEvent { project_id, forecast });
+            }
+            Err(e) => tracing::warn!("Failed to forecast build costs: {}", e),
+        });
+    }
+
+    /// Runs one story in the project directory with a rollback snapshot.
+    async fn run_story_sequential(&self, story: &Story) -> Result<(), AttemptFailure> {
+        let project_path = self.project_path().to_string();
+
+        self.set_phase("running", Some(story));
+        self.set_story_status(&story.id, "in-progress");
+        self.begin_in_flight(&story.id);
+        self.log(format!("Starting story {}: {}", story.id, story.title));
+
+        let snapshot = match worktree::create_story_snapshot(
+            self.app.clone(),
+            project_path.clone(),
+            story.id.clone(),
+        )
+        .await
+        {
+            Ok(snapshot) => {
+                let _ = event_bus::emit(
+                    &self.app,
+                    "build-story-snapshot",
+                    BuildSnapshotEvent {
+                        project_id: self.project_id().to_string(),
+                        story_id: story.id.clone(),
+                        snapshot_ref: snapshot.snapshot_ref.clone(),
+                        snapshot_type: snapshot.snapshot_type.clone(),
+                    },
+                );
+                self.log(format!("Created snapshot for rollback ({})", snapshot.snapshot_type));
+                self.journal(
+                    "snapshot",
+                    Some(&story.id),
+                    json!({ "snapshotRef": snapshot.snapshot_ref, "snapshotType": snapshot.snapshot_type }),
+                );
+                self.update_in_flight(&story.id, |entry| {
+                    entry.snapshot_ref = Some(snapshot.snapshot_ref.clone());
+                    entry.snapshot_type = Some(snapshot.snapshot_type.clone());
+                });
+                Some(snapshot)
+            }
+            Err(e) => {
+                self.log(format!("Warning: Could not create snapshot: {}", e));
+                None
+            }
+        };
+
+        self.log(format!("Spawning {} agent...", self.agent.agent_id));
+        let run = self.run_agent(story, &project_path, false).await;
+
+        if self.ctrl.stopping() {
+            self.set_story_status(&story.id, "pending");
+            return Err(AttemptFailure::canceled());
+        }
+
+        if !run.success {
+            let reason = run.reason.unwrap_or_else(|| "unknown error".to_string());
+            let kind = run.failure_kind.unwrap_or("exit-code");
+            self.log(format!("✗ Story {} failed ({})", story.id, reason));
+            match snapshot {
+                // A stopped agent may have left work half-done; start over
+                Some(snapshot) if kind == "timeout" || kind == "budget" => {
+                    let result = worktree::rollback_story_changes(
+                        self.app.clone(),
+                        project_path,
+                        snapshot.snapshot_ref.clone(),
+                        snapshot.snapshot_type,
+                    )
+                    .await;
+                    self.journal(
+                        "rollback",
+                        Some(&story.id),
+                        json!({ "snapshotRef": snapshot.snapshot_ref, "error": result.as_ref().err() }),
+                    );
+                    match result {
+                        Ok(()) => self.log(format!("  Rolled back {} to its snapshot", story.id)),
+                        Err(e) => self.log(format!("  Warning: Could not roll back: {}", e)),
+                    }
+                }
+                _ => self.log("  Rollback available - use the Rollback button to discard uncommitted changes"),
+            }
+            self.set_story_status(&story.id, "failed");
+            self.record_failure(&story.id, &run.recent_output);
+            return Err(AttemptFailure::new(kind, reason));
+        }
+
+        let verified = story_loop::verify(self, story, &project_path).await;
+        if self.ctrl.stopping() {
+            self.set_story_status(&story.id, "pending");
+            return Err(AttemptFailure::canceled());
+        }
+        if let Err(failure) = verified {
+            self.log(format!("✗ Story {} failed ({})", story.id, failure.message));
+            self.log("  Rollback available - use the Rollback button to discard uncommitted changes");
+            self.set_story_status(&story.id, "failed");
+            return Err(failure);
+        }
+
+        self.log(format!(
+            "✓ Story {} completed successfully (exit code: {})",
+            story.id,
+            run.exit_code.unwrap_or(0)
+        ));
+        if let Err(e) = self.mark_story_passed(&story.id).await {
+            self.log(format!("Warning: Could not save PRD: {}", e));
+        }
+        self.set_story_status(&story.id, "complete");
+
+        if worktree::check_git_initialized(self.app.clone(), project_path.clone())
+            .await
+            .unwrap_or(false)
+        {
+            let committing = Instant::now();
+            match worktree::git_commit_story(
+                self.app.clone(),
+                project_path.clone(),
+                story.id.clone(),
+                story.title.clone(),
+            )
+            .await
+            {
+                Ok(hash) => {
+                    self.log(format!("✓ Committed changes: {}", short_hash(&hash)));
+                    story_loop::record_commit(self, story, &hash);
+                }
+                Err(e) => self.log(format!("Warning: Could not commit changes: {}", e)),
+            }
+            let merge_ms = committing.elapsed().as_millis() as u64;
+            self.time_phase(&story.id, |timer| timer.merge_ms += merge_ms);
+        }
+
+        self.refresh_preview(story);
+
+        if let Some(snapshot) = snapshot {
+            let _ = worktree::discard_story_snapshot(
+                self.app.clone(),
+                project_path,
+                snapshot.snapshot_ref,
+                snapshot.snapshot_type,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Runs one story in its own worktree and merges it back (parallel mode).
+    async fn run_story_parallel(&self, story: &Story) -> Result<(), AttemptFailure> {
+        let project_path = self.project_path().to_string();
+
+        self.set_story_status(&story.id, "in-progress");
+        self.begin_in_flight(&story.id);
+        self.log(format!("[Parallel] Starting story {}: {}", story.id, story.title));
+
+        let recovered = self
+            .ctrl
+            .recovered_worktrees
+            .lock()
+            .ok()
+            .and_then(|mut w| w.remove(&story.id));
+        let prepared = match recovered {
+            Some(existing) => {
+                self.log(format!(
+                    "[Parallel] Resuming {} in its existing worktree",
+                    story.id
+                ));
+                Ok(existing)
+            }
+            None => {
+                worktree::prepare_story_worktree(
+                    self.app.clone(),
+                    project_path.clone(),
+                    story.id.clone(),
+                )
+                .await
+            }
+        };
+        let prepared = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                self.log(format!("✗ [Parallel] Error running story {}: {}", story.id, e));
+                self.set_story_status(&story.id, "failed");
+                self.record_failure(&story.id, &e);
+                return Err(AttemptFailure::new("worktree-error", e));
+            }
+        };
+        self.log(format!(
+            "[Parallel] Using worktree for {} at {}",
+            story.id, prepared.worktree_path
+        ));
+        self.update_in_flight(&story.id, |entry| {
+            entry.worktree_path = Some(prepared.worktree_path.clone());
+            entry.branch_name = Some(prepared.branch_name.clone());
+        });
+        self.journal(
+            "worktree",
+            Some(&story.id),
+            json!({ "worktreePath": prepared.worktree_path, "branchName": prepared.branch_name }),
+        );
+
+        let run = self.run_agent(story, &prepared.worktree_path, true).await;
+        // Verify in the worktree so a failing story never reaches the base branch
+        let verified = if run.success && !self.ctrl.stopping() {
+            story_loop::verify(self, story, &prepared.worktree_path).await
+        } else {
+            Ok(())
+        };
+        let success = run.success && verified.is_ok() && !self.ctrl.stopping();
+
+        let merging = Instant::now();
+        let finalized = worktree::finalize_story_worktree(
+            self.app.clone(),
+            project_path.clone(),
+            story.id.clone(),
+            prepared.worktree_path.clone(),
+            prepared.branch_name.clone(),
+            success,
+        )
+        .await;
+        if success {
+            let merge_ms = merging.elapsed().as_millis() as u64;
+            self.time_phase(&story.id, |timer| timer.merge_ms += merge_ms);
+            self.journal(
+                "merge",
+                Some(&story.id),
+                json!({
+                    "branchName": prepared.branch_name,
+                    "merged": finalized.is_ok(),
+                    "error": finalized.as_ref().err(),
+                }),
+            );
+        }
+
+        if self.ctrl.stopping() {
+            self.set_story_status(&story.id, "pending");
+            return Err(AttemptFailure::canceled());
+        }
+
+        if let Err(e) = finalized {
+            let conflict = e.contains("Merge conflict");
+            if conflict {
+                let _ = event_bus::emit(
+                    &self.app,
+                    "build-merge-conflict",
+                    BuildMergeConflictEvent {
+                        project_id: self.project_id().to_string(),
+                        story_id: story.id.clone(),
+                        story_title: story.title.clone(),
+                        branch_name: prepared.branch_name.clone(),
+                    },
+                );
+                self.log(format!(
+                    "⚠ [Parallel] Merge conflict for story {} - changes kept in branch: {}",
+                    story.id, prepared.branch_name
+                ));
+            } else {
+                self.log(format!("✗ [Parallel] Error running story {}: {}", story.id, e));
+            }
+            self.set_story_status(&story.id, "failed");
+            self.record_failure(&story.id, &e);
+            let kind = if conflict { "merge-conflict" } else { "worktree-error" };
+            return Err(AttemptFailure::new(kind, e));
+        }
+
+        if let Err(failure) = verified {
+            self.log(format!("✗ [Parallel] Story {} failed ({})", story.id, failure.message));
+            self.set_story_status(&story.id, "failed");
+            return Err(failure);
+        }
+
+        if !success {
+            let reason = run.reason.unwrap_or_else(|| "unknown error".to_string());
+            self.log(format!("✗ [Parallel] Story {} failed ({})", story.id, reason));
+            self.set_story_status(&story.id, "failed");
+            self.record_failure(&story.id, &run.recent_output);
+            return Err(AttemptFailure::new(run.failure_kind.unwrap_or("exit-code"), reason));
+        }
+
+        self.log(format!("✓ [Parallel] Story {} completed and merged", story.id));
+        if let Err(e) = self.mark_story_passed(&story.id).await {
+            self.log(format!("Warning: Could not save PRD: {}", e));
+        }
+        self.set_story_status(&story.id, "complete");
+
+        if worktree::check_git_initialized(self.app.clone(), project_path.clone())
+            .await
+            .unwrap_or(false)
+        {
+            let committing = Instant::now();
+            match worktree::git_commit_story(
+                self.app.clone(),
+                project_path,
+                story.id.clone(),
+                story.title.clone(),
+            )
+            .await
+            {
+                Ok(hash) => {
+                    self.log(format!("✓ [Parallel] Committed changes: {}", short_hash(&hash)));
+                    story_loop::record_commit(self, story, &hash);
+                }
+                Err(e) => self.log(format!("Warning: Could not commit changes: {}", e)),
+            }
+            let merge_ms = committing.elapsed().as_millis() as u64;
+            self.time_phase(&story.id, |timer| timer.merge_ms += merge_ms);
+        }
+
+        self.refresh_preview(story);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gui")]
+impl StoryHost for BuildContext {
+    fn project_id(&self) -> &str {
+        &self.ctrl.project_id
+    }
+
+    fn project_path(&self) -> &str {
+        &self.ctrl.project_path
+    }
+
+    fn app_data_dir(&self) -> Option<PathBuf> {
+        app_data_dir(&self.app).ok()
+    }
+
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            project_id: self.project_id().to_string(),
+            project_name: self.project_name.clone(),
+            project_path: self.project_path().to_string(),
+            build_id: self.ctrl.build_id.clone(),
+            mode: self.ctrl.mode.id().to_string(),
+            agent_id: self.agent.agent_id.clone(),
+        }
+    }
+
+    fn agent(&self) -> &StoryAgent {
+        &self.agent
+    }
+
+    fn with_agent(&self, agent: StoryAgent) -> Self {
+        BuildContext {
+            agent,
+            ..self.clone()
+        }
+    }
+
+    /// The build's current limits.
+    fn limits(&self) -> StoryLimits {
+        self.ctrl.limits.lock().map(|l| l.clone()).unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    fn routing_rules(&self) -> &[RoutingRule] {
+        &self.routing
+    }
+
+    fn prompts(&self) -> &PromptEngine {
+        &self.prompts
+    }
+
+    /// What the build has spent so far, from the costs agents reported.
+    fn build_cost(&self) -> f64 {
+        self.ctrl
+            .story_reports
+            .lock()
+            .map(|reports| reports.iter().filter_map(|r| r.cost).sum())
+            .unwrap_or(0.0)
+    }
+
+    fn stopping(&self) -> bool {
+        self.ctrl.stopping()
+    }
+
+    fn log(&self, content: impl Into<String>) {
+        let content = content.into();
+        tracing::info!(project = %self.project_name, "{}", content);
+        let _ = event_bus::emit(
+            &self.app,
+            "build-log",
+            BuildLogEvent {
+                project_id: self.project_id().to_string(),
+                log_type: "system".to_string(),
+                content,
+            },
+        );
+    }
+
+    /// Appends an event to `.ideate/events.jsonl`. Journal failures never
+    /// interrupt a build.
+    fn journal(&self, kind: &str, story_id: Option<&str>, data: serde_json::Value) {
+        let _ = journal::append(self.project_path(), Some(&self.ctrl.build_id), kind, story_id, data);
+    }
+
+    /// Applies a change to state.json so a reopened window sees the build's progress.
+    fn update_state(&self, apply: impl FnMut(&mut ProjectState)) {
+        let _guard = self.ctrl.state_lock.lock();
+        if let Err(e) = update_project_state(self.project_path(), apply) {
+            tracing::error!("Orchestrator: {}", e);
+        }
+    }
+
+    /// Updates a story's entry in the build report, creating it if needed.
+    fn update_story_report(&self, story: &Story, apply: impl FnOnce(&mut StoryReport)) {
+        if let Ok(mut reports) = self.ctrl.story_reports.lock() {
+            let index = match reports.iter().position(|r| r.story_id == story.id) {
+                Some(index) => index,
+                None => {
+                    reports.push(StoryReport::new(&story.id, &story.title));
+                    reports.len() - 1
+                }
+            };
+            apply(&mut reports[index]);
+        }
+    }
+
+    fn set_story_status(&self, story_id: &str, status: &str) {
+        let _ = event_bus::emit(
+            &self.app,
+            "build-story-status",
+            BuildStoryStatusEvent {
+                project_id: self.project_id().to_string(),
+                story_id: story_id.to_string(),
+                status: status.to_string(),
+            },
+        );
+        self.update_state(|state| {
+            state
+                .story_statuses
+                .insert(story_id.to_string(), status.to_string());
+        });
+        tray::refresh(&self.app);
+    }
+
+    fn retry_context(&self, story_id: &str) -> Option<StoryRetryContext> {
+        self.ctrl.retries.lock().ok().and_then(|r| r.get(story_id).cloned())
+    }
+
+    /// Keeps failure output so the story's next attempt can include it in its
+    /// prompt, and bumps its persisted retry count.
+    fn record_failure(&self, story_id: &str, output: &str) {
+        let attempt = match self.ctrl.retries.lock() {
+            Ok(mut retries) => {
+                let attempt = retries.get(story_id).map(|r| r.attempt).unwrap_or(0) + 1;
+                retries.insert(
+                    story_id.to_string(),
+                    StoryRetryContext {
+                        attempt,
+                        error_logs: relevant_error_lines(output),
+                    },
+                );
+                attempt
+            }
+            Err(_) => return,
+        };
+        self.update_state(|state| {
+            state.story_retries.insert(
+                story_id.to_string(),
+                StoryRetryInfo {
+                    retry_count: attempt as i32,
+                },
+            );
+        });
+        if let Some(context) = self.ctrl.retries.lock().ok().and_then(|r| r.get(story_id).cloned()) {
+            self.update_session(|session| {
+                session.retries.insert(story_id.to_string(), context);
+            });
+        }
+    }
+
+    async fn attempt(&self, story: &Story, parallel: bool) -> Result<(), AttemptFailure> {
+        if self.ctrl.mode.swarm() && git_binary::available() {
+            self.run_story_swarm(story).await
+        } else if parallel {
+            self.run_story_parallel(story).await
+        } else {
+            self.run_story_sequential(story).await
+        }
+    }
+
+    /// Sleeps for a retry backoff. Returns false if the build was stopped.
+    async fn backoff(&self, delay: Duration) -> bool {
+        let deadline = Instant::now() + delay;
+        while Instant::now() < deadline {
+            if self.ctrl.stopping() {
+                return false;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            tokio::time::sleep(remaining.min(Duration::from_secs(1))).await;
+        }
+        !self.ctrl.stopping()
+    }
+
+    /// Runs the project's end-to-end suite against a story's changes, if it
+    /// configures one. A failing suite is logged but doesn't fail the story.
+    async fn run_e2e(&self, story: &Story, working_directory: &str) -> Option<E2eRun> {
+        let config = e2e::load_config(self.project_path())?;
+        let (runner, command) = match e2e::resolve_command(&config, working_directory) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                self.log(format!("Warning: Could not run end-to-end tests: {}", e));
+                return None;
+            }
+        };
+
+        self.log(format!("Running {} tests for {}...", runner, story.id));
+        let run = e2e::run(
+            &self.app,
+            self.project_id(),
+            &format!("e2e {}", story.id),
+            &runner,
+            &command,
+            working_directory,
+            |process_id| {
+                if let Ok(mut running) = self.ctrl.running.lock() {
+                    running.insert(story.id.clone(), process_id.to_string());
+                }
+                if self.ctrl.stopping() {
+                    // Runs off the async thread; the test's wait sees it exit
+                    let pid = process_id.to_string();
+                    tokio::task::spawn_blocking(move || kill_agent_blocking(&pid, TerminationReason::Canceled));
+                }
+            },
+        )
+        .await;
+        if let Ok(mut running) = self.ctrl.running.lock() {
+            running.remove(&story.id);
+        }
+        if self.ctrl.stopping() {
+            return None;
+        }
+
+        match &run.error {
+            Some(e) => self.log(format!("⚠ {} tests for {} did not finish ({})", runner, story.id, e)),
+            None if run.success => self.log(format!(
+                "✓ {} tests passed for {} ({} passed, {} flaky, {} skipped)",
+                runner, story.id, run.passed, run.flaky, run.skipped
+            )),
+            None => {
+                self.log(format!(
+                    "⚠ {} of {} {} tests failed for {}",
+                    run.failed,
+                    run.tests.len(),
+                    runner,
+                    story.id
+                ));
+                for test in run.tests.iter().filter(|t| t.status == "failed").take(5) {
+                    self.log(format!("  ✗ {}", test.title));
+                }
+            }
+        }
+        self.journal(
+            "e2e",
+            Some(&story.id),
+            json!({
+                "runner": run.runner,
+                "success": run.success,
+                "passed": run.passed,
+                "failed": run.failed,
+                "flaky": run.flaky,
+                "skipped": run.skipped,
+                "error": run.error,
+                "processId": run.process_id,
+            }),
+        );
+        Some(run)
+    }
+
+    /// Starts timing an attempt at a story; see `timings`.
+    fn begin_timing(&self, story_id: &str, attempt: u32) {
+        let queued_since = self
+            .ctrl
+            .queued_since
+            .lock()
+            .ok()
+            .and_then(|q| q.get(story_id).copied())
+            .unwrap_or(self.ctrl.started);
+        if let Ok(mut timers) = self.ctrl.story_timers.lock() {
+            timers.insert(story_id.to_string(), AttemptTimer::new(attempt, queued_since));
+        }
+    }
+
+    /// Adds to a phase of a story's running attempt.
+    fn time_phase(&self, story_id: &str, apply: impl FnOnce(&mut AttemptTimer)) {
+        if let Ok(mut timers) = self.ctrl.story_timers.lock() {
+            if let Some(timer) = timers.get_mut(story_id) {
+                apply(timer);
+            }
+        }
+    }
+
+    /// Records a story's finished attempt in the project's timings.
+    fn finish_timing(&self, story_id: &str, outcome: &str) {
+        let timer = self.ctrl.story_timers.lock().ok().and_then(|mut t| t.remove(story_id));
+        if let Ok(mut queued) = self.ctrl.queued_since.lock() {
+            queued.insert(story_id.to_string(), Instant::now());
+        }
+        let Some(timer) = timer else {
+            return;
+        };
+        let timing = timer.finish(&self.ctrl.build_id, story_id, &self.agent.agent_id, outcome);
+        if let Err(e) = timings::append(self.project_path(), &timing) {
+            tracing::warn!("Orchestrator: {}", e);
+        }
+    }
+
+    fn story_passed(&self, story: &Story) {
+        self.notify(BuildNotification::StoryPassed {
+            project_name: self.project_name.clone(),
+            story_id: story.id.clone(),
+            story_title: story.title.clone(),
+        });
+        webhooks::dispatch(
+            &self.app,
+            "story-passed",
+            json!({ "projectId": self.project_id(), "storyId": story.id, "storyTitle": story.title }),
+        );
+        linear::story_passed(&self.app, self.project_path(), &story.id);
+        self.emit_forecast();
+    }
+
+    fn story_failed(&self, story: &Story, failure: &StoryFailure) {
+        let _ = event_bus::emit(
+            &self.app,
+            "build-story-failed",
+            BuildStoryFailedEvent {
+                project_id: self.project_id().to_string(),
+                story_id: story.id.clone(),
+                story_title: story.title.clone(),
+                failure: failure.clone(),
+            },
+        );
+        webhooks::dispatch(
+            &self.app,
+            "story-failed",
+            json!({
+                "projectId": self.project_id(),
+                "storyId": story.id,
+                "storyTitle": story.title,
+                "reason": failure.message,
+                "kind": failure.kind,
+                "attempts": failure.attempts,
+            }),
+        );
+        self.notify(BuildNotification::StoryFailed {
+            project_name: self.project_name.clone(),
+            story_id: story.id.clone(),
+            story_title: story.title.clone(),
+            reason: Some(failure.message.clone()),
+        });
+        self.emit_forecast();
+    }
+}
+
+pub(crate) fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(7)]
+}
+
+/// Detects agent-side failures in output that may occur even with exit code 0.
+/// JSON lines are ignored (apart from `result` messages flagged `is_error`) so
+/// field values and the agent's discussion of errors don't cause false positives.
+pub(crate) fn detect_agent_error(recent_output: &str) -> bool {
+    if RESULT_ERROR_RE.is_match(recent_output) {
+        return true;
+    }
+
+    let plain_text = recent_output
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !(trimmed.starts_with('{') && trimmed.ends_with('}'))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    AGENT_ERROR_RES.iter().any(|re| re.is_match(&plain_text))
+}
+
+/// Detects an agent that failed because it is signed out or its API key is
+/// missing or rejected. JSON lines are ignored as in `detect_agent_error`.
+pub(crate) fn detect_auth_error(recent_output: &str) -> bool {
+    recent_output
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !(trimmed.starts_with('{') && trimmed.ends_with('}'))
+        })
+        .any(|line| AUTH_ERROR_RES.iter().any(|re| re.is_match(line)))
+}
+
+/// Detects an interactive prompt the agent is blocked on. JSON lines are
+/// ignored for the same reason as in `detect_agent_error`.
+#[cfg(feature = "gui")]
+fn detect_waiting_for_input(line: &str) -> bool {
+    let trimmed = line.trim();
+    if trimmed.starts_with('{') && trimmed.ends_with('}') {
+        return false;
+    }
+    WAITING_FOR_INPUT_RES.iter().any(|re| re.is_match(trimmed))
+}
+
+/// Picks the lines of a failed attempt's output worth showing the next attempt:
+/// stderr-like error lines, or the tail of the output if there are none.
+pub(crate) fn relevant_error_lines(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    let errors: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| {
+            l.contains("error") || l.contains("Error") || l.contains("failed") || l.contains("Failed")
+        })
+        .collect();
+
+    let selected = if errors.is_empty() {
+        &lines[lines.len().saturating_sub(20)..]
+    } else {
+        &errors[errors.len().saturating_sub(25)..]
+    };
+    selected.join("\n")
+}
+
+/// Renders the story prompt, adding the previous attempt's errors when
+/// retrying, the project's stack and `design`, and the project `files`
+/// chosen for the story.
+pub(crate) fn render_story_prompt(
+    prompts: &PromptEngine,
+    story: &Story,
+    retry: Option<&StoryRetryContext>,
+    design: Option<&Design>,
+    files: &StoryFiles,
+) -> String {
+    let mut context = story_context(story, story_notes(story, retry));
+    if let Some(stack) = prompts.stack() {
+        context.insert("stack".to_string(), json!(stack));
+    }
+    if let Some(design) = design {
+        context.insert("design".to_string(), json!(design));
+    }
+    context.insert("files".to_string(), json!(files.files));
+    context.insert("skippedFiles".to_string(), json!(files.skipped));
+    let context = serde_json::Value::Object(context);
+    let prompt = prompts
+        .render("storyImplementation", None, &context)
+        .unwrap_or_else(|e| {
+            // The built-in default always renders
+            tracing::error!("Story prompt: {}", e);
+            String::new()
+        });
+    prompts.with_stack_snippets(prompt)
+}
+
+/// A story's notes section, with the previous attempt's errors when retrying.
+fn story_notes(story: &Story, retry: Option<&StoryRetryContext>) -> String {
+    let mut notes = if story.notes.is_empty() {
+        String::new()
+    } else {
+        format!("### Notes:\n{}", story.notes)
+    };
+    if let Some(retry) = retry.filter(|r| !r.error_logs.trim().is_empty()) {
+        notes.push_str(&format!(
+            "\n\n### Previous Attempt Failed (Attempt {}):\nThe previous implementation attempt failed. Here are the relevant error logs:\n```\n{}\n```\nPlease analyze these errors and fix the issues in your implementation.",
+            retry.attempt, retry.error_logs
+        ));
+    }
+    notes
+}
+
+/// Selects incomplete stories to build, in priority order.
+pub(crate) fn select_stories(project_path: &str, options: &BuildOptions) -> Result<Vec<Story>, String> {
+    let prd = load_prd(project_path.to_string())?
+        .ok_or_else(|| "No prd.json found for this project".to_string())?;
+
+    let mut stories = prd.user_stories;
+    stories.sort_by_key(|s| s.priority);
+
+    if let Some(from_id) = &options.from_story_id {
+        let start = stories
+            .iter()
+            .position(|s| &s.id == from_id)
+            .ok_or_else(|| format!("Story {} not found", from_id))?;
+        stories.drain(..start);
+    }
+    if let Some(ids) = &options.story_ids {
+        stories.retain(|s| ids.contains(&s.id));
+    }
+
+    Ok(stories.into_iter().filter(|s| !s.passes).collect())
+}
+
+/// Re-reads a queued story so edits made while the build runs are picked up.
+/// Returns `None` if it was removed or already passes.
+pub(crate) fn reload_story(project_path: &str, story_id: &str) -> Option<Story> {
+    load_prd(project_path.to_string())
+        .ok()
+        .flatten()
+        .and_then(|prd| prd.user_stories.into_iter().find(|s| s.id == story_id))
+        .filter(|s| !s.passes)
+}
+
+/// Current autonomy level, re-read each story so changes apply mid-build.
+#[cfg(feature = "gui")]
+fn current_autonomy(project_path: &str) -> String {
+    load_project_settings(project_path.to_string())
+        .ok()
+        .flatten()
+        .map(|s| s.autonomy)
+        .unwrap_or_else(|| "autonomous".to_string())
+}
+
+/// Waits at a story boundary while a pause is requested. Returns false if the
+/// build was stopped instead of resumed.
+#[cfg(feature = "gui")]
+async fn wait_while_paused(ctx: &BuildContext, next: Option<&Story>) -> bool {
+    if ctx.ctrl.stopping() {
+        return false;
+    }
+    if !ctx.ctrl.pause_requested() {
+        return true;
+    }
+
+    ctx.ctrl.paused.store(true, Ordering::SeqCst);
+    ctx.update_session(|session| session.paused = true);
+    ctx.set_phase("paused", next);
+    ctx.log("Build paused - Resume to continue");
+    ctx.journal("paused", next.map(|s| s.id.as_str()), json!({}));
+
+    while ctx.ctrl.pause_requested() && !ctx.ctrl.stopping() {
+        ctx.ctrl.wake.notified().await;
+    }
+    ctx.ctrl.paused.store(false, Ordering::SeqCst);
+
+    if ctx.ctrl.stopping() {
+        return false;
+    }
+    ctx.update_session(|session| session.paused = false);
+    ctx.log("Build resumed");
+    ctx.journal("resumed", next.map(|s| s.id.as_str()), json!({}));
+    ctx.set_phase("running", next);
+    true
+}
+
+/// Runs stories one at a time in the project directory. The build mode picks
+/// each next story and decides what a failure does; a paused failure stays
+/// queued, so resuming retries it.
+#[cfg(feature = "gui")]
+async fn run_sequential(ctx: &BuildContext, stories: Vec<Story>, rescan: bool) -> (String, usize, usize) {
+    let mode = ctx.ctrl.mode;
+    let mut selected: Vec<String> = stories.into_iter().map(|s| s.id).collect();
+    let mut failures: HashMap<String, u32> = HashMap::new();
+    let mut failed_ids: HashSet<String> = HashSet::new();
+    let mut skipped: HashSet<String> = HashSet::new();
+    let mut passed = 0;
+
+    loop {
+        if rescan && mode.until_prd_complete() {
+            // Pick up stories added to the PRD since the build started
+            if let Ok(all) = select_stories(ctx.project_path(), &BuildOptions::default()) {
+                for story in all {
+                    if !selected.contains(&story.id) {
+                        selected.push(story.id);
+                    }
+                }
+            }
+        }
+
+        let candidates: Vec<Story> = selected
+            .iter()
+            .filter(|id| !skipped.contains(*id))
+            .filter_map(|id| reload_story(ctx.project_path(), id))
+            .collect();
+        let Some(story) = mode.next_story(&candidates, &failures).cloned() else {
+            break;
+        };
+
+        if !wait_while_paused(ctx, Some(&story)).await {
+            return ("canceled".to_string(), passed, failed_ids.len());
+        }
+
+        let success = story_loop::run_story(ctx, &story, false).await;
+        if SHUTTING_DOWN.load(Ordering::SeqCst) {
+            // Leave the story journaled as in flight for recovery
+            return ("canceled".to_string(), passed, failed_ids.len());
+        }
+        ctx.end_in_flight(&story.id);
+
+        if !success {
+            if ctx.ctrl.stopping() {
+                return ("canceled".to_string(), passed, failed_ids.len());
+            }
+            failed_ids.insert(story.id.clone());
+            let count = failures.entry(story.id.clone()).or_insert(0);
+            *count += 1;
+            match mode.on_failure(*count) {
+                FailureAction::Pause => {
+                    ctx.log("Build paused due to story failure");
+                    ctx.ctrl.pause_requested.store(true, Ordering::SeqCst);
+                }
+                FailureAction::Retry => {
+                    ctx.log(format!("Retrying {} (attempt {})", story.id, *count + 1));
+                }
+                FailureAction::Skip => {
+                    ctx.log(format!("Skipping {} after {} failed attempt(s)", story.id, count));
+                    skipped.insert(story.id.clone());
+                }
+            }
+            continue;
+        }
+
+        failed_ids.remove(&story.id);
+        passed += 1;
+
+        let has_remaining = selected
+            .iter()
+            .any(|id| !skipped.contains(id) && reload_story(ctx.project_path(), id).is_some());
+        if has_remaining {
+            match current_autonomy(ctx.project_path()).as_str() {
+                "manual" => ctx.log("Pausing before next story (manual mode)"),
+                "pause-between" => ctx.log("Pausing for review (pause-between mode)"),
+                _ => continue,
+            }
+            ctx.ctrl.pause_requested.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let outcome = if failed_ids.is_empty() { "complete" } else { "incomplete" };
+    (outcome.to_string(), passed, failed_ids.len())
+}
+
+/// Runs stories concurrently in worktrees, up to `limit` at once. A story
+/// starts only once its prerequisites have passed and no running story
+/// mentions the same files. Pausing stops new stories from starting; in-flight
+/// ones finish first. Stories that failed are re-queued when the build resumes.
+#[cfg(feature = "gui")]
+async fn run_parallel(ctx: &BuildContext, stories: Vec<Story>, limit: usize) -> (String, usize, usize) {
+    ctx.log(format!("Running up to {} stories in parallel", limit));
+
+    let graph = StoryGraph::analyze(&stories);
+    let dependent = graph.dependent_count();
+    if dependent > 0 {
+        ctx.log(format!(
+            "{} {} will wait on prerequisites",
+            dependent,
+            if dependent == 1 { "story" } else { "stories" }
+        ));
+    }
+
+    let mut queue: VecDeque<Story> = stories.into();
+    let mut running: HashSet<String> = HashSet::new();
+    let mut failed: Vec<Story> = Vec::new();
+    let mut passed = 0;
+    let mut tasks = JoinSet::new();
+
+    loop {
+        while tasks.len() < limit && !ctx.ctrl.pause_requested() && !ctx.ctrl.stopping() {
+            let pending: HashSet<String> = queue
+                .iter()
+                .map(|s| s.id.clone())
+                .chain(running.iter().cloned())
+                .chain(failed.iter().map(|s| s.id.clone()))
+                .collect();
+            let ready = queue
+                .iter()
+                .position(|s| graph.is_ready(&s.id, &pending, &running));
+
+            let index = match ready {
+                Some(index) => index,
+                // Nothing can make progress without breaking a dependency cycle
+                None if tasks.is_empty() && failed.is_empty() && !queue.is_empty() => {
+                    ctx.log(format!(
+                        "Dependency cycle detected - starting {} anyway",
+                        queue[0].id
+                    ));
+                    0
+                }
+                None => break,
+            };
+            let Some(queued) = queue.remove(index) else {
+                break;
+            };
+            let Some(story) = reload_story(ctx.project_path(), &queued.id) else {
+                continue;
+            };
+
+            if let Some(others) = graph.get(&story.id).map(|d| &d.prerequisites) {
+                if !others.is_empty() {
+                    let mut ids: Vec<&str> = others.iter().map(String::as_str).collect();
+                    ids.sort();
+                    ctx.log(format!("[Parallel] {} follows {}", story.id, ids.join(", ")));
+                }
+            }
+
+            running.insert(story.id.clone());
+            let task_ctx = ctx.clone();
+            tasks.spawn(async move {
+                let success = story_loop::run_story(&task_ctx, &story, true).await;
+                if !SHUTTING_DOWN.load(Ordering::SeqCst) {
+                    task_ctx.end_in_flight(&story.id);
+                }
+                (story, success)
+            });
+        }
+
+        if tasks.is_empty() {
+            if ctx.ctrl.stopping() {
+                break;
+            }
+            if ctx.ctrl.pause_requested() {
+                if !wait_while_paused(ctx, None).await {
+                    break;
+                }
+                // Retry this session's failures ahead of the remaining queue
+                for story in failed.drain(..).rev() {
+                    queue.push_front(story);
+                }
+                continue;
+            }
+            if !queue.is_empty() && !failed.is_empty() {
+                // Everything left waits on a story that failed
+                let pending: HashSet<String> = queue
+                    .iter()
+                    .chain(failed.iter())
+                    .map(|s| s.id.clone())
+                    .collect();
+                for story in &queue {
+                    let mut blockers: Vec<&str> = graph
+                        .blocking_prerequisites(&story.id, &pending)
+                        .map(String::as_str)
+                        .collect();
+                    blockers.sort();
+                    ctx.log(format!(
+                        "Skipping {}: waiting on {}",
+                        story.id,
+                        blockers.join(", ")
+                    ));
+                }
+                break;
+            }
+            if queue.is_empty() {
+                break;
+            }
+            continue;
+        }
+
+        match tasks.join_next().await {
+            Some(Ok((story, success))) => {
+                running.remove(&story.id);
+                if success {
+                    passed += 1;
+                } else if !ctx.ctrl.stopping() {
+                    failed.push(story);
+                }
+            }
+            Some(Err(e)) => ctx.log(format!("✗ [Parallel] Task join error: {}", e)),
+            None => {}
+        }
+    }
+
+    if ctx.ctrl.stopping() {
+        if !SHUTTING_DOWN.load(Ordering::SeqCst) {
+            let _ = worktree::cleanup_all_story_worktrees(
+                ctx.app.clone(),
+                ctx.project_path().to_string(),
+            )
+            .await;
+        }
+        return ("canceled".to_string(), passed, failed.len());
+    }
+
+    let outcome = if failed.is_empty() && queue.is_empty() { "complete" } else { "incomplete" };
+    (outcome.to_string(), passed, failed.len())
+}
+
+/// Drives a build to completion and reports the outcome.
+/// `rescan` lets modes that run until the PRD is complete pick up stories
+/// beyond the initial selection.
+#[cfg(feature = "gui")]
+async fn run_build(ctx: BuildContext, stories: Vec<Story>, parallel_limit: usize, rescan: bool) {
+    let started = Instant::now();
+    let story_count = stories.len();
+    let git = git_binary::detect(true);
+    // Worktrees need git; without it stories run one at a time in the project
+    let parallel = ctx.ctrl.mode.parallel() && git.available;
+
+    ctx.log(if parallel {
+        "🚀 Parallel build started".to_string()
+    } else {
+        format!("Build loop started ({} mode)", ctx.ctrl.mode.label())
+    });
+    if !git.available {
+        let guidance = git.guidance.as_deref().unwrap_or_default();
+        if ctx.ctrl.mode.parallel() || ctx.ctrl.mode.swarm() {
+            ctx.log("⚠️ Git was not found, so stories will run one at a time without worktrees");
+        }
+        ctx.log(format!("⚠️ Changes will not be snapshotted or committed. {}", guidance));
+    }
+    ctx.notify(BuildNotification::BuildStarted {
+        project_name: ctx.project_name.clone(),
+        story_count,
+    });
+    ctx.journal(
+        "build-started",
+        None,
+        json!({ "mode": ctx.ctrl.mode.id(), "agentId": ctx.agent.agent_id, "storyCount": story_count }),
+    );
+    telemetry::record(&ctx.app, "build.started");
+    telemetry::record(&ctx.app, &format!("build-mode.{}", ctx.ctrl.mode.id()));
+    telemetry::record(&ctx.app, &format!("agent.{}", ctx.agent.agent_id));
+    webhooks::dispatch(
+        &ctx.app,
+        "build-started",
+        json!({ "projectId": ctx.project_id(), "mode": ctx.ctrl.mode.id(), "storyCount": story_count }),
+    );
+
+    let pre_build = story_loop::run_hook(&ctx, HookKind::PreBuild, None, Vec::new()).await;
+    let (outcome, passed, failed) = if pre_build.is_some_and(|hook| !hook.success) {
+        ctx.log("Build aborted: the preBuild hook failed");
+        ("incomplete".to_string(), 0, 0)
+    } else if parallel {
+        run_parallel(&ctx, stories, parallel_limit).await
+    } else {
+        run_sequential(&ctx, stories, rescan).await
+    };
+
+    let all_complete = load_prd(ctx.project_path().to_string())
+        .ok()
+        .flatten()
+        .map(|prd| prd.user_stories.iter().all(|s| s.passes))
+        .unwrap_or(false);
+
+    ctx.journal(
+        "build-finished",
+        None,
+        json!({ "outcome": outcome, "passed": passed, "failed": failed }),
+    );
+
+    if SHUTTING_DOWN.load(Ordering::SeqCst) {
+        // Keep the session and phase so the build can be recovered on next launch
+        return;
+    }
+    clear_session(ctx.project_path());
+
+    if outcome == "canceled" {
+        ctx.log("Build cancelled by user");
+    } else if all_complete {
+        ctx.log("🎉 All stories completed successfully!");
+    }
+    ctx.set_phase("idle", None);
+
+    if let Ok(mut builds) = BUILDS.lock() {
+        builds.remove(ctx.project_id());
+    }
+    tray::refresh(&ctx.app);
+    if let Err(e) = tunnels::stop_project_tunnels(ctx.app.clone(), ctx.project_id().to_string()).await {
+        tracing::warn!("Orchestrator: failed to stop tunnels: {}", e);
+    }
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let report = BuildReport {
+        build_id: ctx.ctrl.build_id.clone(),
+        project_id: ctx.project_id().to_string(),
+        project_name: ctx.project_name.clone(),
+        mode: ctx.ctrl.mode.id().to_string(),
+        agent_id: ctx.agent.agent_id.clone(),
+        outcome: outcome.clone(),
+        started_at: ctx.ctrl.started_at.clone(),
+        finished_at: chrono::Utc::now().to_rfc3339(),
+        duration_ms,
+        passed,
+        failed,
+        retries: 0,
+        total_tokens: None,
+        total_cost: None,
+        files_changed: 0,
+        insertions: 0,
+        deletions: 0,
+        stories: ctx.ctrl.story_reports.lock().map(|r| r.clone()).unwrap_or_default(),
+        display_currency: Some(currency::display_currency(&ctx.app)),
+    }
+    .with_totals();
+    match report::write_report(ctx.project_path(), &report) {
+        Ok(()) => ctx.log(format!("Build report saved to .ideate/reports/{}.md", report.build_id)),
+        Err(e) => tracing::error!("Orchestrator: {}", e),
+    }
+    cost_attribution::spawn_for_run(ctx.app.clone(), ctx.project_path().to_string(), TrackedRun::from(&report));
+
+    let completed = BuildNotification::BuildCompleted {
+        project_name: ctx.project_name.clone(),
+        passed,
+        failed,
+        duration_secs: Some(duration_ms / 1000),
+    };
+    // The user already knows about a build they stopped
+    if outcome == "canceled" {
+        ctx.notify_webhooks(completed);
+    } else {
+        ctx.notify(completed);
+    }
+    webhooks::dispatch(
+        &ctx.app,
+        "build-finished",
+        json!({
+            "projectId": ctx.project_id(),
+            "buildId": ctx.ctrl.build_id,
+            "outcome": outcome,
+            "passed": passed,
+            "failed": failed,
+            "allComplete": all_complete,
+        }),
+    );
+
+    let _ = event_bus::emit(
+        &ctx.app,
+        "build-finished",
+        BuildFinishedEvent {
+            build_id: ctx.ctrl.build_id.clone(),
+            project_id: ctx.project_id().to_string(),
+            outcome: outcome.clone(),
+            passed,
+            failed,
+            duration_ms,
+        },
+    );
+
+    // After build-finished so a slow hook doesn't hold up the UI
+    story_loop::run_hook(&ctx, 
+        HookKind::PostBuild,
+        None,
+        vec![
+            ("IDEATE_BUILD_OUTCOME", outcome),
+            ("IDEATE_BUILD_PASSED", passed.to_string()),
+            ("IDEATE_BUILD_FAILED", failed.to_string()),
+        ],
+    )
+    .await;
+}
+
+/// Resolves the build mode: an explicit override, else the project's setting.
+pub(crate) fn resolve_mode(
+    settings: Option<&ProjectSettings>,
+    options: &BuildOptions,
+    mode: Option<String>,
+) -> Result<&'static dyn BuildMode, String> {
+    let mode_id = match mode {
+        Some(mode) => mode,
+        None => {
+            let mode = settings
+                .and_then(|s| s.build_mode.clone())
+                .unwrap_or_else(|| "ralph".to_string());
+            if mode == "none" {
+                return Err("Build mode is set to \"None\" - no automatic building".to_string());
+            }
+            // A targeted retry always runs in place, whatever the project's mode
+            if options.story_ids.is_some() {
+                "ralph".to_string()
+            } else {
+                mode
+            }
+        }
+    };
+    get_mode(&mode_id).ok_or_else(|| format!("Unknown build mode: {}", mode_id))
+}
+
+/// Resolves the agent: options, then project settings, then preferences.
+pub(crate) fn resolve_agent(settings: Option<&ProjectSettings>, prefs: &Preferences, options: &BuildOptions) -> String {
+    options
+        .agent_id
+        .clone()
+        .or_else(|| settings.and_then(|s| s.agent.clone()))
+        .or_else(|| prefs.default_agent.clone())
+        .unwrap_or_else(|| DEFAULT_AGENT_ID.to_string())
+}
+
+/// Validates options, registers the build, and spawns its loop.
+/// `mode` overrides the project's build mode (used by recovery).
+#[cfg(feature = "gui")]
+async fn launch_build(
+    app: AppHandle,
+    project_id: String,
+    project_path: String,
+    options: BuildOptions,
+    mode: Option<String>,
+    recovered_worktrees: HashMap<String, WorktreeResult>,
+) -> Result<BuildStatusInfo, String> {
+    let settings = load_project_settings(project_path.clone())?;
+    let prefs: Preferences = load_preferences_internal(&app).unwrap_or_default();
+
+    let mode = resolve_mode(settings.as_ref(), &options, mode)?;
+    let agent_id = resolve_agent(settings.as_ref(), &prefs, &options);
+    let agent = StoryAgent::new(&agent_id, None).ok_or_else(|| format!("Unknown agent: {}", agent_id))?;
+
+    let stories = select_stories(&project_path, &options)?;
+    if stories.is_empty() {
+        return Err("No stories to build".to_string());
+    }
+
+    let ctrl = {
+        let mut builds = BUILDS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if builds.contains_key(&project_id) {
+            return Err("A build is already running for this project".to_string());
+        }
+        let ctrl = Arc::new(BuildControl {
+            project_id: project_id.clone(),
+            project_path: project_path.clone(),
+            mode,
+            build_id: format!(
+                "{}-{}",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            ),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            started: Instant::now(),
+            stop_requested: AtomicBool::new(false),
+            pause_requested: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            wake: Notify::new(),
+            retries: Mutex::new(options.retry_contexts.clone()),
+            running: Mutex::new(HashMap::new()),
+            recovered_worktrees: Mutex::new(recovered_worktrees),
+            budget_warnings: Mutex::new(HashMap::new()),
+            prd_lock: tokio::sync::Mutex::new(()),
+            state_lock: Mutex::new(()),
+            story_reports: Mutex::new(Vec::new()),
+            story_timers: Mutex::new(HashMap::new()),
+            queued_since: Mutex::new(HashMap::new()),
+            limits: Mutex::new(StoryLimits::from_preferences(&prefs)),
+        });
+        builds.insert(project_id.clone(), ctrl.clone());
+        ctrl
+    };
+
+    let project = load_projects(app.clone())
+        .ok()
+        .and_then(|projects| projects.into_iter().find(|p| p.id == project_id));
+    let prompts = PromptEngine::for_project(
+        &prefs.prompt_overrides,
+        app_data_dir(&app).ok().as_deref(),
+        &project_path,
+        &project.as_ref().map(StoredProject::stack_ids).unwrap_or_default(),
+    );
+    let project_name = project.map(|p| p.name).unwrap_or_else(|| project_id.clone());
+
+    let ctx = BuildContext {
+        app,
+        ctrl: ctrl.clone(),
+        project_name,
+        agent,
+        prompts: Arc::new(prompts),
+        routing: Arc::new(routing::load_rules(&project_path)),
+    };
+    let parallel_limit = prefs.max_parallel_agents.max(1) as usize;
+    let rescan = options.from_story_id.is_none() && options.story_ids.is_none();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let _ = write_session(
+        &project_path,
+        &BuildSession {
+            project_id: project_id.clone(),
+            mode: ctrl.mode.id().to_string(),
+            agent_id: options.agent_id,
+            story_ids: stories.iter().map(|s| s.id.clone()).collect(),
+            in_flight: Vec::new(),
+            retries: options.retry_contexts,
+            paused: false,
+            started_at: now.clone(),
+            updated_at: now,
+        },
+    );
+
+    ctx.set_phase("running", None);
+    tauri::async_runtime::spawn(run_build(ctx, stories, parallel_limit, rescan));
+
+    Ok(ctrl.to_info())
+}
+
+/// Starts a build for a project in the background. The build runs until all
+/// selected stories are done or `stop_build`; it waits in place while paused.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_build(
+    app: AppHandle,
+    project_id: String,
+    project_path: String,
+    options: Option<BuildOptions>,
+) -> Result<BuildStatusInfo, String> {
+    launch_build(
+        app,
+        project_id,
+        project_path,
+        options.unwrap_or_default(),
+        None,
+        HashMap::new(),
+    )
+    .await
+}
+
+/// Returns the journal of a build that was interrupted by a crash or quit,
+/// or `None` if the project has no interrupted build.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_interrupted_build(
+    project_id: String,
+    project_path: String,
+) -> Result<Option<BuildSession>, String> {
+    if get_build(&project_id)?.is_some() {
+        return Ok(None);
+    }
+    read_session(&project_path)
+}
+
+/// Continues an interrupted build. Stories that were in flight are rerun,
+/// either on top of their partial work (the default) or after discarding it
+/// when `discard_changes` is set.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn recover_build(
+    app: AppHandle,
+    project_id: String,
+    project_path: String,
+    discard_changes: Option<bool>,
+) -> Result<BuildStatusInfo, String> {
+    if get_build(&project_id)?.is_some() {
+        return Err("A build is already running for this project".to_string());
+    }
+    let session = read_session(&project_path)?
+        .ok_or_else(|| "No interrupted build found for this project".to_string())?;
+    let discard = discard_changes.unwrap_or(false);
+
+    let mut retries = session.retries.clone();
+    let mut recovered_worktrees = HashMap::new();
+
+    for entry in &session.in_flight {
+        if let (Some(worktree_path), Some(branch_name)) = (&entry.worktree_path, &entry.branch_name) {
+            let exists = PathBuf::from(worktree_path).exists();
+            if exists && !discard {
+                recovered_worktrees.insert(
+                    entry.story_id.clone(),
+                    WorktreeResult {
+                        worktree_path: worktree_path.clone(),
+                        branch_name: branch_name.clone(),
+                    },
+                );
+            } else {
+                let _ = worktree::finalize_story_worktree(
+                    app.clone(),
+                    project_path.clone(),
+                    entry.story_id.clone(),
+                    worktree_path.clone(),
+                    branch_name.clone(),
+                    false,
+                )
+                .await;
+            }
+        } else if discard {
+            if let (Some(snapshot_ref), Some(snapshot_type)) = (&entry.snapshot_ref, &entry.snapshot_type) {
+                worktree::rollback_story_changes(
+                    app.clone(),
+                    project_path.clone(),
+                    snapshot_ref.clone(),
+                    snapshot_type.clone(),
+                )
+                .await?;
+                let _ = journal::append(
+                    &project_path,
+                    None,
+                    "rollback",
+                    Some(&entry.story_id),
+                    json!({ "snapshotRef": snapshot_ref, "recovery": true }),
+                );
+            }
+        }
+
+        retries.entry(entry.story_id.clone()).or_insert(StoryRetryContext {
+            attempt: entry.attempt,
+            error_logs: "The previous attempt was interrupted before it finished (the app quit or crashed).".to_string(),
+        });
+    }
+
+    let options = BuildOptions {
+        from_story_id: None,
+        story_ids: Some(session.story_ids.clone()),
+        agent_id: session.agent_id.clone(),
+        retry_contexts: retries,
+    };
+
+    let info = launch_build(
+        app.clone(),
+        project_id.clone(),
+        project_path,
+        options,
+        Some(session.mode.clone()),
+        recovered_worktrees,
+    )
+    .await?;
+
+    let _ = event_bus::emit(
+        &app,
+        "build-log",
+        BuildLogEvent {
+            project_id,
+            log_type: "system".to_string(),
+            content: format!(
+                "Recovered interrupted build ({} stor{} were in progress{})",
+                session.in_flight.len(),
+                if session.in_flight.len() == 1 { "y" } else { "ies" },
+                if discard { ", partial changes discarded" } else { "" }
+            ),
+        },
+    );
+
+    Ok(info)
+}
+
+/// Forgets an interrupted build without resuming it, cleaning up its worktrees.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn discard_interrupted_build(app: AppHandle, project_path: String) -> Result<(), String> {
+    let Some(session) = read_session(&project_path)? else {
+        return Ok(());
+    };
+
+    for entry in session.in_flight {
+        if let (Some(worktree_path), Some(branch_name)) = (entry.worktree_path, entry.branch_name) {
+            let _ = worktree::finalize_story_worktree(
+                app.clone(),
+                project_path.clone(),
+                entry.story_id,
+                worktree_path,
+                branch_name,
+                false,
+            )
+            .await;
+        }
+    }
+
+    clear_session(&project_path);
+    Ok(())
+}
+
+/// Stops a running build, killing its agents and the project's tunnels.
+/// Parallel worktrees are cleaned up by the build task once its stories wind
+/// down.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_build(app: AppHandle, project_id: String) -> Result<(), String> {
+    let ctrl = {
+        let builds = BUILDS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        match builds.get(&project_id) {
+            Some(ctrl) => ctrl.clone(),
+            None => return Ok(()),
+        }
+    };
+
+    ctrl.stop_requested.store(true, Ordering::SeqCst);
+    ctrl.wake.notify_one();
+    let _ = journal::append(&ctrl.project_path, Some(&ctrl.build_id), "stop-requested", None, json!({}));
+
+    let process_ids: Vec<String> = ctrl
+        .running
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .values()
+        .cloned()
+        .collect();
+
+    tokio::task::spawn_blocking(move || {
+        for process_id in process_ids {
+            let _ = kill_agent_blocking(&process_id, TerminationReason::Canceled);
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    tunnels::stop_project_tunnels(app, project_id).await
+}
+
+#[cfg(feature = "gui")]
+fn get_build(project_id: &str) -> Result<Option<Arc<BuildControl>>, String> {
+    let builds = BUILDS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(builds.get(project_id).cloned())
+}
+
+/// Pauses a build at the next story boundary. In-flight stories finish first;
+/// the paused phase is persisted to state.json once the loop is idle.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn pause_build(app: AppHandle, project_id: String) -> Result<BuildStatusInfo, String> {
+    let ctrl = get_build(&project_id)?
+        .ok_or_else(|| "No build is running for this project".to_string())?;
+
+    if !ctrl.pause_requested.swap(true, Ordering::SeqCst) {
+        let _ = event_bus::emit(
+            &app,
+            "build-log",
+            BuildLogEvent {
+                project_id,
+                log_type: "system".to_string(),
+                content: "Pausing after the current story finishes...".to_string(),
+            },
+        );
+        tray::refresh(&app);
+    }
+
+    Ok(ctrl.to_info())
+}
+
+/// Resumes a paused build where it left off, retrying any story whose failure
+/// paused it. Returns false if the project has no build to resume.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn resume_build(project_id: String) -> Result<bool, String> {
+    let Some(ctrl) = get_build(&project_id)? else {
+        return Ok(false);
+    };
+
+    ctrl.pause_requested.store(false, Ordering::SeqCst);
+    ctrl.wake.notify_one();
+    Ok(true)
+}
+
+/// Returns the project's running build, if any.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_build_status(project_id: String) -> Result<Option<BuildStatusInfo>, String> {
+    let builds = BUILDS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(builds.get(&project_id).map(|ctrl| ctrl.to_info()))
+}
+
+/// Lists all running builds.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn list_active_builds() -> Result<Vec<BuildStatusInfo>, String> {
+    let builds = BUILDS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(builds.values().map(|ctrl| ctrl.to_info()).collect())
+}
+
+/// The running build in `project_path`: its ID, when it started, and its
+/// story results so far.
+#[cfg(feature = "gui")]
+pub(crate) fn live_build(project_path: &str) -> Option<(String, String, Vec<StoryReport>)> {
+    let builds = BUILDS.lock().ok()?;
+    let ctrl = builds.values().find(|ctrl| ctrl.project_path == project_path)?;
+    let reports = ctrl.story_reports.lock().map(|r| r.clone()).unwrap_or_default();
+    Some((ctrl.build_id.clone(), ctrl.started_at.clone(), reports))
+}
+
+/// Whether a build is running in the project at `project_path`.
+#[cfg(feature = "gui")]
+pub(crate) fn is_building(project_path: &str) -> bool {
+    BUILDS
+        .lock()
+        .is_ok_and(|builds| builds.values().any(|ctrl| ctrl.project_path == project_path))
+}
+
+/// Gives running builds the limits in changed preferences; stories already
+/// running keep the timeout they started with.
+#[cfg(feature = "gui")]
+pub(crate) fn apply_preferences(prefs: &Preferences) {
+    if let Ok(builds) = BUILDS.lock() {
+        for ctrl in builds.values() {
+            *ctrl.limits.lock().unwrap_or_else(|e| e.into_inner()) = StoryLimits::from_preferences(prefs);
+        }
+    }
+}
+
+/// Flags all builds to stop. Called on app shutdown, before agents are killed.
+/// Their sessions are left in place so they can be recovered on next launch.
+#[cfg(feature = "gui")]
+pub fn stop_all_builds() {
+    SHUTTING_DOWN.store(true, Ordering::SeqCst);
+    if let Ok(builds) = BUILDS.lock() {
+        for ctrl in builds.values() {
+            ctrl.stop_requested.store(true, Ordering::SeqCst);
+            ctrl.wake.notify_one();
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Build modes.
+//!
+//! A build mode decides which story runs next, what happens when one fails,
+//! and when the build is done. Projects pick a mode by id in their settings
+//! (`buildMode`); the frontend lists available modes with `list_build_modes`,
+//! so adding a mode here needs no UI changes.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+
+use crate::models::Story;
+
+/// What the build does after a story fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureAction {
+    /// Pause the build; the story is retried when it resumes.
+    Pause,
+    /// Run the story again straight away.
+    Retry,
+    /// Give up on the story and move on to the next one.
+    Skip,
+}
+
+pub trait BuildMode: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn label(&self) -> &'static str;
+    #[cfg(feature = "gui")]
+    fn description(&self) -> &'static str;
+
+    /// Whether stories run concurrently in worktrees instead of one at a time
+    /// in the project directory.
+    fn parallel(&self) -> bool {
+        false
+    }
+
+    /// Whether each story is split between several agents; see `swarm`.
+    fn swarm(&self) -> bool {
+        false
+    }
+
+    /// Picks the next story from the remaining candidates (in priority order),
+    /// given how many times each has failed this build.
+    fn next_story<'a>(&self, candidates: &'a [Story], _failures: &HashMap<String, u32>) -> Option<&'a Story> {
+        candidates.first()
+    }
+
+    /// Decides what to do after a story's `failures`-th failed attempt.
+    fn on_failure(&self, _failures: u32) -> FailureAction {
+        FailureAction::Pause
+    }
+
+    /// Whether the build keeps going until every story in the PRD passes,
+    /// picking up stories added while it runs, rather than stopping once the
+    /// stories selected at start are done.
+    #[cfg(feature = "gui")]
+    fn until_prd_complete(&self) -> bool {
+        false
+    }
+}
+
+/// Each selected story once, in priority order. Failures are skipped so one
+/// broken story doesn't hold up the rest.
+struct Sequential;
+
+impl BuildMode for Sequential {
+    fn id(&self) -> &'static str {
+        "sequential"
+    }
+    fn label(&self) -> &'static str {
+        "Sequential"
+    }
+    #[cfg(feature = "gui")]
+    fn description(&self) -> &'static str {
+        "Run each story once in order, skipping failures"
+    }
+    fn on_failure(&self, _failures: u32) -> FailureAction {
+        FailureAction::Skip
+    }
+}
+
+/// The Ralph loop: keep taking the highest-priority incomplete story until the
+/// PRD is done, pausing on failures for review.
+struct Ralph;
+
+impl BuildMode for Ralph {
+    fn id(&self) -> &'static str {
+        "ralph"
+    }
+    fn label(&self) -> &'static str {
+        "Ralph"
+    }
+    #[cfg(feature = "gui")]
+    fn description(&self) -> &'static str {
+        "Loop through stories in order until all pass, pausing on failures"
+    }
+    #[cfg(feature = "gui")]
+    fn until_prd_complete(&self) -> bool {
+        true
+    }
+}
+
+/// Always works on the highest-priority story that has failed least, retrying
+/// failures automatically a few times before skipping them.
+struct PriorityGreedy;
+
+/// Attempts a story gets in priority-greedy mode before it is skipped.
+const GREEDY_MAX_ATTEMPTS: u32 = 3;
+
+impl BuildMode for PriorityGreedy {
+    fn id(&self) -> &'static str {
+        "priority-greedy"
+    }
+    fn label(&self) -> &'static str {
+        "Priority Greedy"
+    }
+    #[cfg(feature = "gui")]
+    fn description(&self) -> &'static str {
+        "Favor high-priority stories, retrying failures automatically"
+    }
+    fn next_story<'a>(&self, candidates: &'a [Story], failures: &HashMap<String, u32>) -> Option<&'a Story> {
+        candidates
+            .iter()
+            .min_by_key(|s| (failures.get(&s.id).copied().unwrap_or(0), s.priority))
+    }
+    fn on_failure(&self, failures: u32) -> FailureAction {
+        if failures < GREEDY_MAX_ATTEMPTS {
+            FailureAction::Retry
+        } else {
+            FailureAction::Skip
+        }
+    }
+    #[cfg(feature = "gui")]
+    fn until_prd_complete(&self) -> bool {
+        true
+    }
+}
+
+/// Independent stories side by side in worktrees; see `run_parallel`.
+struct Parallel;
+
+impl BuildMode for Parallel {
+    fn id(&self) -> &'static str {
+        "parallel"
+    }
+    fn label(&self) -> &'static str {
+        "Parallel"
+    }
+    #[cfg(feature = "gui")]
+    fn description(&self) -> &'static str {
+        "Run independent stories concurrently"
+    }
+    fn parallel(&self) -> bool {
+        true
+    }
+}
+
+/// One story at a time, each planned into tasks that several agents work on
+/// at once and reviewed before it is merged; see `swarm`.
+struct Swarm;
+
+impl BuildMode for Swarm {
+    fn id(&self) -> &'static str {
+        "swarm"
+    }
+    fn label(&self) -> &'static str {
+        "Swarm"
+    }
+    #[cfg(feature = "gui")]
+    fn description(&self) -> &'static str {
+        "Split each story between agents working in parallel, with a reviewer"
+    }
+    fn swarm(&self) -> bool {
+        true
+    }
+    fn on_failure(&self, _failures: u32) -> FailureAction {
+        FailureAction::Skip
+    }
+}
+
+static MODES: &[&dyn BuildMode] = &[&Ralph, &Sequential, &PriorityGreedy, &Parallel, &Swarm];
+
+/// Looks up a build mode by id.
+pub fn get_mode(id: &str) -> Option<&'static dyn BuildMode> {
+    MODES.iter().copied().find(|m| m.id() == id)
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildModeInfo {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    pub parallel: bool,
+}
+
+/// Lists the available build modes. "none" (no automatic building) is a
+/// project setting rather than a mode and is not included.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn list_build_modes() -> Vec<BuildModeInfo> {
+    MODES
+        .iter()
+        .map(|m| BuildModeInfo {
+            id: m.id().to_string(),
+            label: m.label().to_string(),
+            description: m.description().to_string(),
+            parallel: m.parallel(),
+        })
+        .collect()
+}
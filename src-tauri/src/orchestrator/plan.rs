@@ -0,0 +1,263 @@
+//! Dry-run build planning.
+//!
+//! `plan_build` resolves a build exactly as `start_build` would — mode, agent,
+//! story selection and order — and describes it without spawning anything.
+
+#[cfg(feature = "gui")]
+use std::collections::HashMap;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use super::modes::BuildMode;
+#[cfg(feature = "gui")]
+use super::routing;
+#[cfg(feature = "gui")]
+use super::{resolve_agent, resolve_mode, select_stories, BuildOptions};
+use crate::models::Story;
+#[cfg(feature = "gui")]
+use crate::models::Preferences;
+#[cfg(feature = "gui")]
+use crate::preferences::load_preferences_internal;
+#[cfg(feature = "gui")]
+use crate::projects::load_project_settings;
+#[cfg(feature = "gui")]
+use crate::scheduler::StoryGraph;
+#[cfg(feature = "gui")]
+use crate::worktree;
+
+// Mirrors the frontend's `estimateStoryComplexity` token heuristic
+pub(super) const TOKENS_PER_CHAR: f64 = 0.25;
+const BASE_TOKENS: u64 = 500;
+const TOKENS_PER_CRITERION: u64 = 200;
+const TOKENS_PER_DEPENDENCY: u64 = 100;
+
+/// Input/output USD per 1M tokens for an agent's model, or its default model.
+#[cfg(feature = "gui")]
+fn agent_rates(agent_id: &str, model: Option<&str>) -> Option<(f64, f64)> {
+    match model.unwrap_or_default() {
+        m if m.contains("haiku") => return Some((0.8, 4.0)),
+        m if m.contains("opus") && m != "opusplan" => return Some((15.0, 75.0)),
+        _ => {}
+    }
+    match agent_id {
+        "claude-code" | "amp" | "opencode" | "droid" | "cursor" | "continue" => Some((3.0, 15.0)),
+        "codex" | "copilot" => Some((2.5, 10.0)),
+        _ => None,
+    }
+}
+
+pub(super) fn estimate_tokens(story: &Story, dependency_count: usize) -> u64 {
+    let mut tokens = BASE_TOKENS;
+    tokens += (story.description.len() as f64 * TOKENS_PER_CHAR).round() as u64;
+    tokens += story.acceptance_criteria.len() as u64 * TOKENS_PER_CRITERION;
+    tokens += dependency_count as u64 * TOKENS_PER_DEPENDENCY;
+    if story.notes.len() > 300 {
+        tokens += (story.notes.len() as f64 * TOKENS_PER_CHAR).round() as u64;
+    }
+    tokens
+}
+
+/// Rough cost assuming the agent produces about as many tokens as it reads.
+#[cfg(feature = "gui")]
+pub(super) fn estimate_cost(agent_id: &str, model: Option<&str>, tokens: u64) -> Option<f64> {
+    agent_rates(agent_id, model).map(|(input, output)| tokens as f64 / 1_000_000.0 * (input + output))
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedStory {
+    pub story_id: String,
+    pub title: String,
+    /// Position in the run order, starting at 1.
+    pub order: usize,
+    /// For parallel builds, the wave the story can start in (0 = immediately).
+    pub wave: Option<usize>,
+    pub prerequisites: Vec<String>,
+    /// Stories that mention the same files and will not run at the same time.
+    pub serialized_with: Vec<String>,
+    /// Agent and model the story runs with, after its override and routing rules.
+    pub agent_id: String,
+    pub model: Option<String>,
+    pub estimated_tokens: u64,
+    pub estimated_cost: Option<f64>,
+    pub worktree_path: Option<String>,
+    pub branch_name: Option<String>,
+    pub verification_steps: Vec<String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildPlan {
+    pub project_id: String,
+    pub mode: String,
+    pub mode_label: String,
+    pub agent_id: String,
+    pub parallel_limit: Option<usize>,
+    /// Branch parallel stories merge into.
+    pub base_branch: Option<String>,
+    pub stories: Vec<PlannedStory>,
+    pub total_estimated_tokens: u64,
+    pub total_estimated_cost: Option<f64>,
+    pub warnings: Vec<String>,
+}
+
+/// Orders stories the way a sequential mode would pick them, assuming every
+/// story passes on its first attempt.
+#[cfg(feature = "gui")]
+fn sequential_order(mode: &dyn BuildMode, mut remaining: Vec<Story>) -> Vec<Story> {
+    let failures = HashMap::new();
+    let mut ordered = Vec::with_capacity(remaining.len());
+    while let Some(next) = mode.next_story(&remaining, &failures).map(|s| s.id.clone()) {
+        let index = remaining.iter().position(|s| s.id == next).unwrap_or(0);
+        ordered.push(remaining.remove(index));
+    }
+    ordered
+}
+
+/// Describes the build `start_build` would run with the same options,
+/// without spawning agents or touching git.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn plan_build(
+    app: AppHandle,
+    project_id: String,
+    project_path: String,
+    options: Option<BuildOptions>,
+) -> Result<BuildPlan, String> {
+    let options = options.unwrap_or_default();
+    let settings = load_project_settings(project_path.clone())?;
+    let prefs: Preferences = load_preferences_internal(&app).unwrap_or_default();
+
+    let mode = resolve_mode(settings.as_ref(), &options, None)?;
+    let agent_id = resolve_agent(settings.as_ref(), &prefs, &options);
+    let mut warnings = Vec::new();
+    if crate::agents::get_build_command(&agent_id).is_none() {
+        warnings.push(format!("Unknown agent: {}", agent_id));
+    }
+
+    let stories = select_stories(&project_path, &options)?;
+    let graph = StoryGraph::analyze(&stories);
+    let waves = graph.waves(&stories);
+    if mode.parallel() && waves.len() < stories.len() {
+        warnings.push("Some stories depend on each other in a cycle; one will be started anyway".to_string());
+    }
+
+    let ordered = if mode.parallel() {
+        let mut ordered = stories.clone();
+        ordered.sort_by_key(|s| (waves.get(&s.id).copied().unwrap_or(usize::MAX), s.priority));
+        ordered
+    } else {
+        sequential_order(mode, stories.clone())
+    };
+
+    let git_ready = std::path::Path::new(&project_path).join(".git").exists();
+    if !git_ready {
+        warnings.push("Project is not a git repository; changes will not be snapshotted or committed".to_string());
+    }
+    let base_branch = if mode.parallel() && git_ready {
+        worktree::get_base_ref(&project_path).ok()
+    } else {
+        None
+    };
+
+    let rules = routing::load_rules(&project_path);
+    // Routing sees the budget as if each earlier story cost its estimate
+    let mut budget_remaining = prefs.max_cost_per_build.filter(|limit| *limit > 0.0);
+
+    let planned: Vec<PlannedStory> = ordered
+        .iter()
+        .enumerate()
+        .map(|(i, story)| {
+            let mut prerequisites: Vec<String> = graph
+                .get(&story.id)
+                .map(|d| d.prerequisites.iter().cloned().collect())
+                .unwrap_or_default();
+            prerequisites.sort();
+            let mut serialized_with: Vec<String> = stories
+                .iter()
+                .filter(|other| other.id != story.id && graph.overlaps(&story.id, &other.id))
+                .map(|other| other.id.clone())
+                .collect();
+            serialized_with.sort();
+
+            let estimated_tokens = estimate_tokens(story, prerequisites.len());
+            let route = routing::story_override(story, &agent_id, estimated_tokens)
+                .or_else(|| routing::route(&rules, &agent_id, estimated_tokens, budget_remaining));
+            let (story_agent, model) = match route {
+                Some(route) => (route.agent_id, route.model),
+                None => (agent_id.clone(), None),
+            };
+            let estimated_cost = estimate_cost(&story_agent, model.as_deref(), estimated_tokens);
+            if let (Some(remaining), Some(cost)) = (budget_remaining.as_mut(), estimated_cost) {
+                *remaining -= cost;
+            }
+            let (worktree_path, branch_name) = if mode.parallel() {
+                let (path, branch) = worktree::story_worktree_names(&project_path, &story.id);
+                (Some(path.to_string_lossy().to_string()), Some(branch))
+            } else {
+                (None, None)
+            };
+
+            let mut verification_steps = vec![
+                format!("{} exits successfully", story_agent),
+                "Output contains no agent error markers".to_string(),
+                "Agent runs the project's quality checks (typecheck, lint, build)".to_string(),
+            ];
+            verification_steps.extend(
+                story
+                    .acceptance_criteria
+                    .iter()
+                    .map(|c| format!("Acceptance: {}", c)),
+            );
+            if let (Some(branch), Some(base)) = (&branch_name, &base_branch) {
+                verification_steps.push(format!("Merge {} into {} without conflicts", branch, base));
+            } else if git_ready && !mode.parallel() {
+                verification_steps.push("Commit the story's changes".to_string());
+            }
+
+            PlannedStory {
+                story_id: story.id.clone(),
+                title: story.title.clone(),
+                order: i + 1,
+                wave: if mode.parallel() { waves.get(&story.id).copied() } else { None },
+                prerequisites,
+                serialized_with,
+                agent_id: story_agent,
+                model,
+                estimated_tokens,
+                estimated_cost,
+                worktree_path,
+                branch_name,
+                verification_steps,
+            }
+        })
+        .collect();
+
+    for story in &planned {
+        if story.agent_id != agent_id && crate::agents::get_build_command(&story.agent_id).is_none() {
+            warnings.push(format!("{} is set to build with unknown agent: {}", story.story_id, story.agent_id));
+        }
+    }
+    let total_estimated_tokens = planned.iter().map(|s| s.estimated_tokens).sum();
+    let total_estimated_cost = planned.iter().map(|s| s.estimated_cost).sum::<Option<f64>>();
+    Ok(BuildPlan {
+        project_id,
+        mode: mode.id().to_string(),
+        mode_label: mode.label().to_string(),
+        agent_id: agent_id.clone(),
+        parallel_limit: mode
+            .parallel()
+            .then(|| prefs.max_parallel_agents.max(1) as usize),
+        base_branch,
+        stories: planned,
+        total_estimated_tokens,
+        total_estimated_cost,
+        warnings,
+    })
+}
@@ -0,0 +1,277 @@
+//! Build preflight checks.
+//!
+//! `run_preflight` checks what a build of a project needs before it starts —
+//! git and a committer identity, the agent `start_build` would run and its
+//! sign-in, an initial commit for modes that use worktrees, free disk space,
+//! and the project's tunnel provider — and returns a checklist for the UI to
+//! show next to Start Build. Checks only read; nothing is changed.
+
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::{resolve_agent, resolve_mode, BuildOptions};
+use crate::agents::{detect_agent_status, get_build_command, get_built_in_agents, has_credentials};
+use crate::git_binary;
+use crate::integrations::tunnels;
+use crate::models::Preferences;
+use crate::preferences::load_preferences_internal;
+use crate::project_env;
+use crate::projects::load_project_settings;
+
+/// Free space below which a build is likely to fail.
+const MIN_FREE_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Free space below which a build gets a warning.
+const LOW_FREE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about a warning or failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+    /// No check failed.
+    pub ready: bool,
+}
+
+fn check(id: &str, label: &str, status: CheckStatus, message: impl Into<String>, fix: Option<&str>) -> PreflightCheck {
+    PreflightCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        message: message.into(),
+        fix: fix.map(str::to_string),
+    }
+}
+
+/// Trimmed stdout of a successful git command in `project_path`.
+fn git(project_path: &str, args: &[&str]) -> Option<String> {
+    let output = git_binary::command().args(args).current_dir(project_path).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn git_checks(project_path: &str, needs_commit: bool, checks: &mut Vec<PreflightCheck>) {
+    let info = git_binary::detect(true);
+    if !info.available {
+        // Builds still run, one story at a time and without snapshots
+        let message = if needs_commit {
+            "Git was not found; stories will run one at a time, without worktrees or snapshots"
+        } else {
+            "Git was not found; changes will not be snapshotted or committed"
+        };
+        checks.push(check("git", "Git", CheckStatus::Warn, message, info.guidance.as_deref()));
+        return;
+    }
+    let version = info.version.unwrap_or_else(|| "git".to_string());
+    checks.push(check("git", "Git", CheckStatus::Pass, version, None));
+
+    let name = git(project_path, &["config", "user.name"]).filter(|v| !v.is_empty());
+    let email = git(project_path, &["config", "user.email"]).filter(|v| !v.is_empty());
+    checks.push(match (name, email) {
+        (Some(name), Some(email)) => check(
+            "git-identity",
+            "Git identity",
+            CheckStatus::Pass,
+            format!("{} <{}>", name, email),
+            None,
+        ),
+        _ => check(
+            "git-identity",
+            "Git identity",
+            CheckStatus::Fail,
+            "No committer name and email, so stories can't be committed",
+            Some("Run git config --global user.name and user.email"),
+        ),
+    });
+
+    if !Path::new(project_path).join(".git").exists() {
+        checks.push(check(
+            "git-repo",
+            "Repository",
+            if needs_commit { CheckStatus::Fail } else { CheckStatus::Warn },
+            "Not a git repository; changes will not be snapshotted or committed",
+            Some("Run git init in the project folder"),
+        ));
+    } else if git(project_path, &["rev-parse", "--verify", "HEAD"]).is_none() {
+        checks.push(check(
+            "git-repo",
+            "Repository",
+            if needs_commit { CheckStatus::Fail } else { CheckStatus::Warn },
+            "The repository has no commits yet",
+            Some("Make an initial commit"),
+        ));
+    } else {
+        checks.push(check("git-repo", "Repository", CheckStatus::Pass, "Has an initial commit", None));
+    }
+}
+
+fn agent_checks(app: &AppHandle, project_path: &str, agent_id: &str, checks: &mut Vec<PreflightCheck>) {
+    let Some((executable, _)) = get_build_command(agent_id) else {
+        checks.push(check(
+            "agent",
+            "Agent",
+            CheckStatus::Fail,
+            format!("Unknown agent: {}", agent_id),
+            Some("Choose another agent in the project settings"),
+        ));
+        return;
+    };
+    let installed = match get_built_in_agents().iter().find(|a| a.id == agent_id) {
+        Some(agent) => {
+            let status = detect_agent_status(agent);
+            (status.status == "available").then(|| status.installed_version.unwrap_or(executable.clone()))
+        }
+        None => which(&executable).then(|| executable.clone()),
+    };
+    let Some(version) = installed else {
+        checks.push(check(
+            "agent",
+            "Agent",
+            CheckStatus::Fail,
+            format!("{} is not installed ({} not found)", agent_id, executable),
+            Some("Install the agent's CLI, or choose another agent"),
+        ));
+        return;
+    };
+    checks.push(check("agent", "Agent", CheckStatus::Pass, format!("{} ({})", agent_id, version), None));
+
+    let env = project_env::resolve(app, project_path);
+    match has_credentials(agent_id, &env) {
+        Some(true) => checks.push(check("agent-auth", "Agent sign-in", CheckStatus::Pass, "Signed in", None)),
+        Some(false) => checks.push(check(
+            "agent-auth",
+            "Agent sign-in",
+            CheckStatus::Warn,
+            format!("No credentials found for {}", agent_id),
+            Some("Sign in with the agent's CLI, or set its API key in the project environment"),
+        )),
+        None => {}
+    }
+}
+
+fn which(executable: &str) -> bool {
+    Command::new("which")
+        .arg(executable)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(unix)]
+fn free_bytes(path: &str) -> Option<u64> {
+    let path = std::ffi::CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out-pointer
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn free_bytes(_path: &str) -> Option<u64> {
+    None
+}
+
+fn disk_check(project_path: &str) -> PreflightCheck {
+    let Some(free) = free_bytes(project_path) else {
+        return check("disk", "Disk space", CheckStatus::Warn, "Couldn't read free disk space", None);
+    };
+    let message = format!("{:.1} GB free", free as f64 / (1024.0 * 1024.0 * 1024.0));
+    if free < MIN_FREE_BYTES {
+        check("disk", "Disk space", CheckStatus::Fail, message, Some("Free up disk space"))
+    } else if free < LOW_FREE_BYTES {
+        check("disk", "Disk space", CheckStatus::Warn, message, Some("Free up disk space"))
+    } else {
+        check("disk", "Disk space", CheckStatus::Pass, message, None)
+    }
+}
+
+fn tunnel_check(app: &AppHandle, provider_id: &str) -> PreflightCheck {
+    let info = match tunnels::provider_status(app, Some(provider_id)) {
+        Ok(info) => info,
+        Err(e) => return check("tunnel", "Tunnel", CheckStatus::Fail, e, Some("Choose another tunnel provider")),
+    };
+    if !info.installed {
+        check(
+            "tunnel",
+            "Tunnel",
+            CheckStatus::Warn,
+            format!("{} is not installed", info.name),
+            Some("Install its CLI in Settings → Integrations"),
+        )
+    } else if info.auth.required && !info.auth.authenticated {
+        check(
+            "tunnel",
+            "Tunnel",
+            CheckStatus::Warn,
+            format!("{} is not signed in", info.name),
+            Some("Add its auth token in Settings → Integrations"),
+        )
+    } else {
+        check("tunnel", "Tunnel", CheckStatus::Pass, format!("{} is ready", info.name), None)
+    }
+}
+
+/// Checks that a build of the project can run, with the agent and mode
+/// `start_build` would use for `options`.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn run_preflight(
+    app: AppHandle,
+    project_path: String,
+    options: Option<BuildOptions>,
+) -> Result<PreflightReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let options = options.unwrap_or_default();
+        let settings = load_project_settings(project_path.clone())?;
+        let prefs: Preferences = load_preferences_internal(&app).unwrap_or_default();
+        let mut checks = Vec::new();
+
+        let mode = resolve_mode(settings.as_ref(), &options, None);
+        if let Err(e) = &mode {
+            checks.push(check("mode", "Build mode", CheckStatus::Fail, e.clone(), None));
+        }
+        // Parallel and swarm builds branch worktrees off HEAD
+        let needs_commit = mode.is_ok_and(|mode| mode.parallel() || mode.swarm());
+
+        git_checks(&project_path, needs_commit, &mut checks);
+        let agent_id = resolve_agent(settings.as_ref(), &prefs, &options);
+        agent_checks(&app, &project_path, &agent_id, &mut checks);
+        checks.push(disk_check(&project_path));
+        if let Some(provider) = settings.as_ref().and_then(|s| s.tunnel_provider.as_deref()) {
+            checks.push(tunnel_check(&app, provider));
+        }
+
+        Ok(PreflightReport {
+            ready: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,294 @@
+//! Build summary reports.
+//!
+//! While a build runs, each agent attempt and story outcome is recorded. When
+//! it finishes, the summary is written to `.ideate/reports/<build-id>.json`
+//! and a readable `.md` alongside it.
+
+use std::fs;
+use std::path::PathBuf;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::currency::DisplayCurrency;
+use crate::utils::get_ideate_dir;
+use crate::worktree::DiffStat;
+
+lazy_static::lazy_static! {
+    // Mirrors the frontend cost store's patterns, plus Claude's stream-json fields
+    static ref COST_RE: Regex = Regex::new(r#"(?i)(?:"total_cost_usd"\s*:\s*|(?:total\s+)?cost[:\s]+\$?)([\d.]+)"#).unwrap();
+    static ref INPUT_TOKENS_RE: Regex = Regex::new(r#"(?i)(?:"input_tokens"\s*:\s*|input\s*tokens?[:\s]+)([\d,]+)"#).unwrap();
+    static ref OUTPUT_TOKENS_RE: Regex = Regex::new(r#"(?i)(?:"output_tokens"\s*:\s*|output\s*tokens?[:\s]+)([\d,]+)"#).unwrap();
+    static ref TOTAL_TOKENS_RE: Regex = Regex::new(r"(?i)(?:total\s*tokens?|tokens?\s*used)[:\s]+([\d,]+)").unwrap();
+}
+
+/// Token and cost figures an agent printed, where it printed any.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunUsage {
+    pub tokens: Option<u64>,
+    pub cost: Option<f64>,
+}
+
+fn last_number(re: &Regex, output: &str) -> Option<u64> {
+    re.captures_iter(output)
+        .last()
+        .and_then(|c| c[1].replace(',', "").parse().ok())
+}
+
+/// Extracts usage from the tail of an agent's output. Later figures win, since
+/// agents print running totals.
+pub fn parse_usage(output: &str) -> RunUsage {
+    let cost = COST_RE
+        .captures_iter(output)
+        .last()
+        .and_then(|c| c[1].trim_end_matches('.').parse().ok());
+    let tokens = last_number(&TOTAL_TOKENS_RE, output).or_else(|| {
+        match (last_number(&INPUT_TOKENS_RE, output), last_number(&OUTPUT_TOKENS_RE, output)) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+        }
+    });
+    RunUsage { tokens, cost }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryReport {
+    pub story_id: String,
+    pub title: String,
+    /// "passed", "failed", or "canceled"
+    pub status: String,
+    pub attempts: u32,
+    pub duration_ms: u64,
+    pub tokens: Option<u64>,
+    pub cost: Option<f64>,
+    pub diff: Option<DiffStat>,
+    pub commit: Option<String>,
+    pub failure_reason: Option<String>,
+}
+
+impl StoryReport {
+    pub fn new(story_id: &str, title: &str) -> Self {
+        Self {
+            story_id: story_id.to_string(),
+            title: title.to_string(),
+            status: "canceled".to_string(),
+            attempts: 0,
+            duration_ms: 0,
+            tokens: None,
+            cost: None,
+            diff: None,
+            commit: None,
+            failure_reason: None,
+        }
+    }
+
+    /// Adds one agent attempt's duration and usage.
+    pub fn add_attempt(&mut self, duration_ms: u64, usage: RunUsage) {
+        self.attempts += 1;
+        self.duration_ms += duration_ms;
+        if let Some(tokens) = usage.tokens {
+            self.tokens = Some(self.tokens.unwrap_or(0) + tokens);
+        }
+        if let Some(cost) = usage.cost {
+            self.cost = Some(self.cost.unwrap_or(0.0) + cost);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+    pub build_id: String,
+    pub project_id: String,
+    pub project_name: String,
+    pub mode: String,
+    pub agent_id: String,
+    /// "complete", "incomplete", or "canceled"
+    pub outcome: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub passed: usize,
+    pub failed: usize,
+    /// Agent runs beyond each story's first.
+    pub retries: u32,
+    pub total_tokens: Option<u64>,
+    pub total_cost: Option<f64>,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub stories: Vec<StoryReport>,
+    /// Currency the report's costs are also shown in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<DisplayCurrency>,
+}
+
+/// Short listing entry for `list_build_reports`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReportSummary {
+    pub build_id: String,
+    pub outcome: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub passed: usize,
+    pub failed: usize,
+    pub total_cost: Option<f64>,
+}
+
+impl BuildReport {
+    /// Fills in the totals from the story entries.
+    pub fn with_totals(mut self) -> Self {
+        let tokens: Vec<u64> = self.stories.iter().filter_map(|s| s.tokens).collect();
+        self.total_tokens = (!tokens.is_empty()).then(|| tokens.iter().sum());
+        let costs: Vec<f64> = self.stories.iter().filter_map(|s| s.cost).collect();
+        self.total_cost = (!costs.is_empty()).then(|| costs.iter().sum());
+        self.retries = self.stories.iter().map(|s| s.attempts.saturating_sub(1)).sum();
+        for diff in self.stories.iter().filter_map(|s| s.diff.as_ref()) {
+            self.files_changed += diff.files_changed;
+            self.insertions += diff.insertions;
+            self.deletions += diff.deletions;
+        }
+        self
+    }
+
+    fn summary(&self) -> BuildReportSummary {
+        BuildReportSummary {
+            build_id: self.build_id.clone(),
+            outcome: self.outcome.clone(),
+            started_at: self.started_at.clone(),
+            duration_ms: self.duration_ms,
+            passed: self.passed,
+            failed: self.failed,
+            total_cost: self.total_cost,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let fmt_duration = |ms: u64| {
+            let secs = ms / 1000;
+            if secs >= 3600 {
+                format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+            } else if secs >= 60 {
+                format!("{}m {}s", secs / 60, secs % 60)
+            } else {
+                format!("{}s", secs)
+            }
+        };
+        let currency = self.display_currency.clone().unwrap_or_else(DisplayCurrency::usd);
+        let fmt_cost = |cost: Option<f64>| cost.map(|c| currency.format(c)).unwrap_or_else(|| "—".to_string());
+        let fmt_tokens = |tokens: Option<u64>| tokens.map(|t| t.to_string()).unwrap_or_else(|| "—".to_string());
+
+        let mut md = format!("# Build Report: {}\n\n", self.project_name);
+        md.push_str(&format!("- **Build:** {}\n", self.build_id));
+        md.push_str(&format!("- **Outcome:** {}\n", self.outcome));
+        md.push_str(&format!("- **Mode:** {} · **Agent:** {}\n", self.mode, self.agent_id));
+        md.push_str(&format!("- **Started:** {}\n", self.started_at));
+        md.push_str(&format!("- **Duration:** {}\n", fmt_duration(self.duration_ms)));
+        md.push_str(&format!(
+            "- **Stories:** {} passed, {} failed, {} retries\n",
+            self.passed, self.failed, self.retries
+        ));
+        md.push_str(&format!(
+            "- **Changes:** {} files, +{} / -{}\n",
+            self.files_changed, self.insertions, self.deletions
+        ));
+        md.push_str(&format!(
+            "- **Tokens:** {} · **Cost:** {}\n\n",
+            fmt_tokens(self.total_tokens),
+            fmt_cost(self.total_cost)
+        ));
+
+        md.push_str("| Story | Status | Attempts | Duration | Diff | Tokens | Cost |\n");
+        md.push_str("|---|---|---|---|---|---|---|\n");
+        for story in &self.stories {
+            let diff = story
+                .diff
+                .as_ref()
+                .map(|d| format!("{} files, +{}/-{}", d.files_changed, d.insertions, d.deletions))
+                .unwrap_or_else(|| "—".to_string());
+            md.push_str(&format!(
+                "| {}: {} | {} | {} | {} | {} | {} | {} |\n",
+                story.story_id,
+                story.title.replace('|', "\\|"),
+                story.status,
+                story.attempts,
+                fmt_duration(story.duration_ms),
+                diff,
+                fmt_tokens(story.tokens),
+                fmt_cost(story.cost)
+            ));
+        }
+
+        let failures: Vec<&StoryReport> = self.stories.iter().filter(|s| s.failure_reason.is_some()).collect();
+        if !failures.is_empty() {
+            md.push_str("\n## Failures\n\n");
+            for story in failures {
+                md.push_str(&format!(
+                    "- **{}**: {}\n",
+                    story.story_id,
+                    story.failure_reason.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        md
+    }
+}
+
+fn get_reports_dir(project_path: &str) -> PathBuf {
+    get_ideate_dir(project_path).join("reports")
+}
+
+/// Writes the report as JSON and markdown.
+pub fn write_report(project_path: &str, report: &BuildReport) -> Result<(), String> {
+    let dir = get_reports_dir(project_path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create reports directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize build report: {}", e))?;
+    fs::write(dir.join(format!("{}.json", report.build_id)), json)
+        .map_err(|e| format!("Failed to write build report: {}", e))?;
+    fs::write(dir.join(format!("{}.md", report.build_id)), report.to_markdown())
+        .map_err(|e| format!("Failed to write build report: {}", e))
+}
+
+fn read_report(path: &PathBuf) -> Result<BuildReport, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read build report: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse build report: {}", e))
+}
+
+/// Loads one build's report.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_build_report(project_path: String, build_id: String) -> Result<BuildReport, String> {
+    if build_id.contains(['/', '\\']) || build_id.contains("..") {
+        return Err("Invalid build ID".to_string());
+    }
+    read_report(&get_reports_dir(&project_path).join(format!("{}.json", build_id)))
+}
+
+/// All of a project's readable build reports, oldest first.
+pub fn read_build_reports(project_path: &str) -> Vec<BuildReport> {
+    let Ok(entries) = fs::read_dir(get_reports_dir(project_path)) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<BuildReport> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| read_report(&path).ok())
+        .collect();
+    reports.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    reports
+}
+
+/// Lists a project's build reports, newest first.
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
+pub fn list_build_reports(project_path: String) -> Result<Vec<BuildReportSummary>, String> {
+    Ok(read_build_reports(&project_path)
+        .iter()
+        .rev()
+        .map(BuildReport::summary)
+        .collect())
+}
@@ -0,0 +1,153 @@
+//! Cost-aware agent routing.
+//!
+//! Rules in the project's config pick the agent and model for each story from
+//! its estimated size and what is left of the build budget, e.g. Haiku for
+//! small stories and Opus for large ones while the budget allows:
+//!
+//! ```json
+//! "routing": {
+//!   "rules": [
+//!     { "maxTokens": 1500, "model": "haiku" },
+//!     { "minTokens": 4000, "minBudgetRemaining": 5.0, "model": "opus" }
+//!   ]
+//! }
+//! ```
+//!
+//! Stories no rule matches run with the build's agent and default model. A
+//! story's own `agent` or `model` takes precedence over every rule.
+//!
+//! When a story's agent can't run (missing or not signed in) or keeps
+//! failing, the build moves on to the next agent in the `fallbackAgents`
+//! preference, e.g. `["claude-code", "amp", "opencode"]`.
+
+use std::collections::VecDeque;
+use std::fs;
+
+use super::plan::estimate_tokens;
+use crate::models::{ProjectConfig, RoutingRule, Story};
+use crate::projects::load_prd;
+use crate::scheduler::StoryGraph;
+use crate::utils::get_ideate_dir;
+
+/// Agent and model picked for a story.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub agent_id: String,
+    pub model: Option<String>,
+    /// Index of the rule that matched; `None` for a story's own override.
+    pub rule: Option<usize>,
+    pub estimated_tokens: u64,
+}
+
+impl Route {
+    /// What picked this route, for logs: "rule 2" or "story override".
+    pub fn source(&self) -> String {
+        match self.rule {
+            Some(index) => format!("rule {}", index + 1),
+            None => "story override".to_string(),
+        }
+    }
+}
+
+/// Reads the project's routing rules. Empty when routing is not configured.
+pub fn load_rules(project_path: &str) -> Vec<RoutingRule> {
+    fs::read_to_string(get_ideate_dir(project_path).join("config.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectConfig>(&content).ok())
+        .and_then(|config| config.routing)
+        .map(|routing| routing.rules)
+        .unwrap_or_default()
+}
+
+fn matches(rule: &RoutingRule, tokens: u64, budget_remaining: Option<f64>) -> bool {
+    let remaining = budget_remaining.unwrap_or(f64::INFINITY);
+    rule.min_tokens.is_none_or(|min| tokens >= min)
+        && rule.max_tokens.is_none_or(|max| tokens <= max)
+        && rule.min_budget_remaining.is_none_or(|min| remaining >= min)
+        && rule.max_budget_remaining.is_none_or(|max| remaining <= max)
+}
+
+/// The first rule matching a story of `tokens` estimated tokens with
+/// `budget_remaining` USD left (`None` without a build budget).
+pub fn route(rules: &[RoutingRule], default_agent: &str, tokens: u64, budget_remaining: Option<f64>) -> Option<Route> {
+    rules
+        .iter()
+        .position(|rule| matches(rule, tokens, budget_remaining))
+        .map(|index| Route {
+            agent_id: rules[index].agent.clone().unwrap_or_else(|| default_agent.to_string()),
+            model: rules[index].model.clone(),
+            rule: Some(index),
+            estimated_tokens: tokens,
+        })
+}
+
+/// The story's own agent/model override, if it has one.
+pub fn story_override(story: &Story, default_agent: &str, tokens: u64) -> Option<Route> {
+    let agent = story.agent.as_deref().filter(|a| !a.is_empty());
+    let model = story.model.as_deref().filter(|m| !m.is_empty());
+    if agent.is_none() && model.is_none() {
+        return None;
+    }
+    Some(Route {
+        agent_id: agent.unwrap_or(default_agent).to_string(),
+        model: model.map(String::from),
+        rule: None,
+        estimated_tokens: tokens,
+    })
+}
+
+/// Routes a story during a build that has spent `spent` USD of `budget`:
+/// the story's override, else the first matching rule.
+pub fn route_story(
+    project_path: &str,
+    rules: &[RoutingRule],
+    default_agent: &str,
+    story: &Story,
+    spent: f64,
+    budget: Option<f64>,
+) -> Option<Route> {
+    if rules.is_empty() && story.agent.is_none() && story.model.is_none() {
+        return None;
+    }
+    let tokens = estimate_tokens(story, dependency_count(project_path, story));
+    if let Some(route) = story_override(story, default_agent, tokens) {
+        return Some(route);
+    }
+    let remaining = budget.filter(|limit| *limit > 0.0).map(|limit| limit - spent);
+    route(rules, default_agent, tokens, remaining)
+}
+
+/// Number of stories in the PRD that `story` depends on, for token estimates.
+fn dependency_count(project_path: &str, story: &Story) -> usize {
+    load_prd(project_path.to_string())
+        .ok()
+        .flatten()
+        .and_then(|prd| {
+            StoryGraph::analyze(&prd.user_stories)
+                .get(&story.id)
+                .map(|deps| deps.prerequisites.len())
+        })
+        .unwrap_or(0)
+}
+
+/// Agents to try after `agent_id`, in order. The chain continues after
+/// `agent_id` when it is part of it, and starts from the top otherwise.
+pub fn fallback_chain(fallback_agents: &[String], agent_id: &str) -> VecDeque<String> {
+    let start = fallback_agents.iter().position(|a| a == agent_id).map_or(0, |i| i + 1);
+    fallback_agents[start..]
+        .iter()
+        .filter(|a| !a.is_empty() && *a != agent_id)
+        .cloned()
+        .collect()
+}
+
+/// Whether a failure should move the story to a fallback agent: at once when
+/// the agent could not run at all, otherwise once its retries are used up.
+pub fn should_fall_back(failure_kind: &str, retries_exhausted: bool) -> bool {
+    match failure_kind {
+        "spawn-error" | "auth" => true,
+        // Neither would go differently with another agent
+        "merge-conflict" | "hook" | "canceled" | "budget" => false,
+        _ => retries_exhausted,
+    }
+}
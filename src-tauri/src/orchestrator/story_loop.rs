@@ -0,0 +1,612 @@
+//! The story loop shared by the app's builds and the headless CLI.
+//!
+//! `run_story` takes a story through attempts until it passes or runs out of
+//! retries and fallback agents: routing it to an agent, the preStory and
+//! postStory hooks, waiting out a throttled provider, backing off between
+//! retries, and recording the outcome in state.json, the build report, and
+//! the event journal. `verify` runs the project's checks on an attempt's
+//! changes. What differs is behind `StoryHost`: the app runs each attempt as
+//! a tracked process in a snapshot or worktree and emits events; the CLI runs
+//! the agent in the project directory and prints.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::json;
+
+use super::report::StoryReport;
+use super::timings::AttemptTimer;
+use super::{context, render_story_prompt, routing, StoryRetryContext};
+use crate::agents::{get_build_command, get_build_command_with_model, supports_model};
+use crate::hooks::{self, HookContext, HookKind, HookResult};
+use crate::models::{
+    E2eRun, Preferences, ProcessPriority, ProjectState, RoutingRule, Story, StoryFailure, StoryVerification,
+    VerificationConfig, VerificationRun,
+};
+use crate::projects::load_design;
+use crate::prompt_templates::PromptEngine;
+use crate::{coverage, meta_history, throttle, verification, worktree};
+
+/// Times a story waits out a throttled provider before throttling counts
+/// against its retries.
+const MAX_THROTTLE_WAITS: u32 = 5;
+
+/// Upper bound for the retry backoff delay.
+pub(crate) const MAX_BACKOFF_SECS: u64 = 600;
+
+/// Why one attempt at a story failed.
+pub(crate) struct AttemptFailure {
+    /// See `StoryFailure::kind`.
+    pub kind: &'static str,
+    pub message: String,
+}
+
+impl AttemptFailure {
+    pub(crate) fn new(kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn canceled() -> Self {
+        Self::new("canceled", "canceled")
+    }
+}
+
+/// Per-story limits from preferences.
+#[derive(Clone)]
+pub(crate) struct StoryLimits {
+    pub timeout_minutes: u32,
+    pub max_retries: u32,
+    pub backoff_seconds: u32,
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub max_tokens_per_story: Option<i64>,
+    pub max_cost_per_build: Option<f64>,
+    /// Sorted percentages below 100 to warn at.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub budget_warning_thresholds: Vec<u32>,
+    /// Agents to switch to when a story's agent fails; see `routing`.
+    pub fallback_agents: Vec<String>,
+    pub priority: ProcessPriority,
+    /// Token budget for project files in story prompts; see `context`.
+    pub context_tokens: u64,
+}
+
+impl StoryLimits {
+    pub(crate) fn from_preferences(prefs: &Preferences) -> Self {
+        StoryLimits {
+            timeout_minutes: prefs.story_timeout_minutes,
+            max_retries: prefs.max_story_retries,
+            backoff_seconds: prefs.retry_backoff_seconds,
+            max_tokens_per_story: prefs.max_tokens_per_story,
+            max_cost_per_build: prefs.max_cost_per_build,
+            budget_warning_thresholds: {
+                let mut thresholds: Vec<u32> = prefs
+                    .budget_warning_thresholds
+                    .iter()
+                    .copied()
+                    .filter(|t| (1..100).contains(t))
+                    .collect();
+                thresholds.sort_unstable();
+                thresholds.dedup();
+                thresholds
+            },
+            fallback_agents: prefs.fallback_agents.clone(),
+            priority: prefs.agent_priority,
+            context_tokens: u64::from(prefs.story_context_tokens),
+        }
+    }
+}
+
+/// The agent a story runs with.
+#[derive(Debug, Clone)]
+pub(crate) struct StoryAgent {
+    pub agent_id: String,
+    /// Model passed to the agent; its default when `None`.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub model: Option<String>,
+    /// Agent this one replaced, once a story has fallen back.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fallback_from: Option<String>,
+    pub executable: String,
+    pub args_template: Vec<String>,
+}
+
+impl StoryAgent {
+    /// The agent with its build command, or `None` if it has none.
+    pub(crate) fn new(agent_id: &str, model: Option<&str>) -> Option<Self> {
+        let (executable, args_template) = get_build_command_with_model(agent_id, model)?;
+        Some(StoryAgent {
+            agent_id: agent_id.to_string(),
+            model: model.map(str::to_string),
+            fallback_from: None,
+            executable,
+            args_template,
+        })
+    }
+
+    /// The agent's arguments for `prompt`.
+    pub(crate) fn args(&self, prompt: &str) -> Vec<String> {
+        self.args_template.iter().map(|a| a.replace("{{prompt}}", prompt)).collect()
+    }
+}
+
+/// Where a build's stories run: the app's orchestrator or the headless CLI.
+pub(crate) trait StoryHost: Clone + Send + Sync {
+    fn project_id(&self) -> &str;
+    fn project_path(&self) -> &str;
+    /// Where hooks and checks read the project's environment from.
+    fn app_data_dir(&self) -> Option<PathBuf>;
+    /// The build as hooks see it.
+    fn hook_context(&self) -> HookContext;
+    fn agent(&self) -> &StoryAgent;
+    /// This host, running stories with `agent` instead.
+    fn with_agent(&self, agent: StoryAgent) -> Self;
+    fn limits(&self) -> StoryLimits;
+    fn routing_rules(&self) -> &[RoutingRule];
+    fn prompts(&self) -> &PromptEngine;
+    /// What the build has spent so far, from the costs agents reported.
+    fn build_cost(&self) -> f64;
+    fn stopping(&self) -> bool;
+    fn log(&self, content: impl Into<String>);
+    /// Appends an event to `.ideate/events.jsonl`.
+    fn journal(&self, kind: &str, story_id: Option<&str>, data: serde_json::Value);
+    /// Applies a change to state.json.
+    fn update_state(&self, apply: impl FnMut(&mut ProjectState));
+    /// Updates a story's entry in the build report, creating it if needed.
+    fn update_story_report(&self, story: &Story, apply: impl FnOnce(&mut StoryReport));
+    fn set_story_status(&self, story_id: &str, status: &str);
+    /// The errors of a story's last failed attempt, for its next prompt.
+    fn retry_context(&self, story_id: &str) -> Option<StoryRetryContext>;
+    /// Keeps a failed attempt's output for the story's next prompt.
+    fn record_failure(&self, story_id: &str, output: &str);
+    /// Makes one attempt at a story: runs its agent, `verify`s the changes,
+    /// and lands them. `parallel` when other stories run at the same time.
+    fn attempt(&self, story: &Story, parallel: bool) -> impl Future<Output = Result<(), AttemptFailure>> + Send;
+    /// Sleeps before trying again. Returns false if the build was stopped.
+    fn backoff(&self, delay: Duration) -> impl Future<Output = bool> + Send;
+
+    /// Runs the project's end-to-end suite, where the host can.
+    fn run_e2e(&self, _story: &Story, _working_directory: &str) -> impl Future<Output = Option<E2eRun>> + Send {
+        async { None }
+    }
+
+    /// Starts timing an attempt; see `timings`.
+    fn begin_timing(&self, _story_id: &str, _attempt: u32) {}
+
+    /// Adds to a phase of a story's running attempt.
+    fn time_phase(&self, _story_id: &str, _apply: impl FnOnce(&mut AttemptTimer)) {}
+
+    /// Records a story's finished attempt.
+    fn finish_timing(&self, _story_id: &str, _outcome: &str) {}
+
+    /// Reports a story that passed beyond the journal, e.g. in notifications.
+    fn story_passed(&self, _story: &Story) {}
+
+    /// Reports a story that failed for good beyond the journal.
+    fn story_failed(&self, _story: &Story, _failure: &StoryFailure) {}
+}
+
+/// Runs a project hook off the async runtime and logs how it went.
+/// Returns `None` when the project has no hook of this kind.
+pub(crate) async fn run_hook<H: StoryHost>(
+    host: &H,
+    kind: HookKind,
+    story: Option<&Story>,
+    extra_env: Vec<(&'static str, String)>,
+) -> Option<HookResult> {
+    let app_data_dir = host.app_data_dir();
+    let hook_ctx = host.hook_context();
+    let story = story.cloned();
+    let result = tokio::task::spawn_blocking(move || {
+        hooks::run(app_data_dir.as_deref(), &hook_ctx, kind, story.as_ref(), &extra_env)
+    })
+    .await
+    .ok()
+    .flatten()?;
+
+    if result.success {
+        host.log(format!("✓ {} hook finished", kind.name()));
+    } else {
+        let reason = result.reason.as_deref().unwrap_or("unknown error");
+        host.log(format!("✗ {} hook failed ({})", kind.name(), reason));
+        for line in &result.output_tail {
+            host.log(format!("  {}", line));
+        }
+    }
+    host.journal(
+        "hook",
+        None,
+        json!({ "hook": kind.name(), "success": result.success, "reason": result.reason }),
+    );
+    Some(result)
+}
+
+/// The host to run `story` with: the story's own agent and model, else what
+/// the project's routing rules pick for it, else the host's own.
+fn routed<H: StoryHost>(host: &H, story: &Story) -> H {
+    let Some(route) = routing::route_story(
+        host.project_path(),
+        host.routing_rules(),
+        &host.agent().agent_id,
+        story,
+        host.build_cost(),
+        host.limits().max_cost_per_build,
+    ) else {
+        return host.clone();
+    };
+    let Some(agent) = StoryAgent::new(&route.agent_id, route.model.as_deref()) else {
+        host.log(format!(
+            "⚠ Unknown agent {} ({}); using {}",
+            route.agent_id,
+            route.source(),
+            host.agent().agent_id
+        ));
+        return host.clone();
+    };
+    if route.model.is_some() && !supports_model(&route.agent_id) {
+        host.log(format!("⚠ {} cannot select a model; using its default", route.agent_id));
+    }
+
+    let label = match &route.model {
+        Some(model) => format!("{} ({})", route.agent_id, model),
+        None => route.agent_id.clone(),
+    };
+    host.log(format!(
+        "Routing {} to {} (~{} tokens, {})",
+        story.id,
+        label,
+        route.estimated_tokens,
+        route.source()
+    ));
+    host.journal(
+        "route",
+        Some(&story.id),
+        json!({
+            "agentId": route.agent_id,
+            "model": route.model,
+            "rule": route.rule,
+            "estimatedTokens": route.estimated_tokens,
+        }),
+    );
+    host.with_agent(agent)
+}
+
+/// Switches a story from the host's agent to the next agent in `chain` that
+/// has a build command, recording the substitution.
+fn fall_back<H: StoryHost>(host: &H, story: &Story, chain: &mut VecDeque<String>, failure: &AttemptFailure) -> Option<H> {
+    let current = &host.agent().agent_id;
+    while let Some(agent_id) = chain.pop_front() {
+        let Some((executable, args_template)) = get_build_command(&agent_id) else {
+            host.log(format!("⚠ Skipping unknown fallback agent {}", agent_id));
+            continue;
+        };
+        host.log(format!(
+            "↪ Falling back from {} to {} for {} ({})",
+            current, agent_id, story.id, failure.message
+        ));
+        host.journal(
+            "agent-fallback",
+            Some(&story.id),
+            json!({
+                "from": current,
+                "to": agent_id,
+                "kind": failure.kind,
+                "reason": failure.message,
+            }),
+        );
+        return Some(host.with_agent(StoryAgent {
+            fallback_from: Some(current.clone()),
+            agent_id,
+            // Models are specific to the agent that was replaced
+            model: None,
+            executable,
+            args_template,
+        }));
+    }
+    None
+}
+
+/// Runs a story, retrying failures with exponential backoff up to the
+/// configured limit. A story that still fails is recorded in state.json
+/// with a structured reason. Returns whether it passed.
+pub(crate) async fn run_story<H: StoryHost>(host: &H, story: &Story, parallel: bool) -> bool {
+    let mut routed = routed(host, story);
+    let mut fallbacks = routing::fallback_chain(&host.limits().fallback_agents, &routed.agent().agent_id);
+    let mut retries = 0;
+    let mut attempts = 0;
+    let mut throttle_waits = 0;
+    loop {
+        if let Some(wait) = throttle::remaining(&routed.agent().agent_id) {
+            host.log(format!(
+                "⏸ {} is throttled by its provider - waiting {}s before {}",
+                routed.agent().agent_id,
+                wait.as_secs().max(1),
+                story.id
+            ));
+            host.journal(
+                "throttle-wait",
+                Some(&story.id),
+                json!({ "agentId": routed.agent().agent_id, "waitSecs": wait.as_secs() }),
+            );
+            if !host.backoff(wait).await {
+                return false;
+            }
+        }
+        attempts += 1;
+        routed.begin_timing(&story.id, attempts);
+        let result = match run_hook(&routed, HookKind::PreStory, Some(story), Vec::new()).await {
+            Some(hook) if !hook.success => {
+                host.set_story_status(&story.id, "failed");
+                Err(AttemptFailure::new(
+                    "hook",
+                    format!("preStory hook failed: {}", hook.reason.unwrap_or_default()),
+                ))
+            }
+            _ => routed.attempt(story, parallel).await,
+        };
+        routed.finish_timing(
+            &story.id,
+            match &result {
+                Ok(()) => "passed",
+                Err(failure) => failure.kind,
+            },
+        );
+        let failure = match result {
+            Ok(()) => {
+                host.update_story_report(story, |report| {
+                    report.status = "passed".to_string();
+                    report.failure_reason = None;
+                });
+                host.update_state(|state| {
+                    if let Some(failures) = state.story_failures.as_mut() {
+                        failures.remove(&story.id);
+                    }
+                });
+                host.journal(
+                    "story-passed",
+                    Some(&story.id),
+                    json!({ "attempts": attempts, "agentId": routed.agent().agent_id }),
+                );
+                host.story_passed(story);
+                run_hook(&routed, HookKind::PostStory, Some(story), vec![("IDEATE_STORY_STATUS", "passed".to_string())])
+                    .await;
+                return true;
+            }
+            Err(failure) => failure,
+        };
+        if failure.kind == "canceled" || host.stopping() {
+            return false;
+        }
+
+        // Try again once the provider's cooldown is over, without using a retry
+        if failure.kind == "throttled" && throttle_waits < MAX_THROTTLE_WAITS {
+            throttle_waits += 1;
+            if throttle::remaining(&routed.agent().agent_id).is_none() && !host.backoff(throttle::DEFAULT_COOLDOWN).await {
+                return false;
+            }
+            continue;
+        }
+
+        if routing::should_fall_back(failure.kind, retries >= host.limits().max_retries) {
+            if let Some(next) = fall_back(&routed, story, &mut fallbacks, &failure) {
+                // The new agent gets its own retries
+                routed = next;
+                retries = 0;
+                continue;
+            }
+        }
+
+        // Merge conflicts need a human; retrying would only repeat them,
+        // and retrying past a budget would only spend more
+        if failure.kind != "merge-conflict" && failure.kind != "budget" && retries < host.limits().max_retries {
+            retries += 1;
+            let delay = Duration::from_secs(
+                (u64::from(host.limits().backoff_seconds) << (retries - 1).min(10)).min(MAX_BACKOFF_SECS),
+            );
+            host.log(format!(
+                "↻ Retrying {} in {}s (retry {}/{})",
+                story.id,
+                delay.as_secs(),
+                retries,
+                host.limits().max_retries
+            ));
+            host.journal(
+                "retry",
+                Some(&story.id),
+                json!({
+                    "retry": retries,
+                    "delaySecs": delay.as_secs(),
+                    "kind": failure.kind,
+                    "reason": failure.message,
+                }),
+            );
+            if !host.backoff(delay).await {
+                return false;
+            }
+            continue;
+        }
+
+        host.update_story_report(story, |report| {
+            report.status = "failed".to_string();
+            report.failure_reason = Some(failure.message.clone());
+        });
+        let failure = StoryFailure {
+            kind: failure.kind.to_string(),
+            message: failure.message,
+            attempts,
+            failed_at: chrono::Utc::now().to_rfc3339(),
+        };
+        host.update_state(|state| {
+            state
+                .story_failures
+                .get_or_insert_with(HashMap::new)
+                .insert(story.id.clone(), failure.clone());
+        });
+        host.journal("story-failed", Some(&story.id), json!(failure));
+        host.story_failed(story, &failure);
+        run_hook(&routed, HookKind::PostStory, Some(story), vec![("IDEATE_STORY_STATUS", "failed".to_string())])
+            .await;
+        return false;
+    }
+}
+
+/// Renders the story prompt with the project files relevant to the story,
+/// as they are in `working_directory`; see `context`.
+pub(crate) fn render_prompt<H: StoryHost>(host: &H, story: &Story, working_directory: &str) -> String {
+    let retry = host.retry_context(&story.id);
+    let design = load_design(host.project_path().to_string()).ok().flatten();
+    let files = context::assemble(working_directory, story, design.as_ref(), host.limits().context_tokens);
+    if !files.is_empty() {
+        host.log(format!("Context for {}: {}", story.id, files.summary()));
+        host.journal(
+            "context",
+            Some(&story.id),
+            json!({
+                "files": files
+                    .files
+                    .iter()
+                    .map(|f| json!({ "path": f.path, "reason": f.reason, "tokens": f.tokens, "truncated": f.truncated }))
+                    .collect::<Vec<_>>(),
+                "skipped": files.skipped,
+                "tokens": files.tokens,
+                "budget": files.budget,
+            }),
+        );
+    }
+    render_story_prompt(host.prompts(), story, retry.as_ref(), design.as_ref(), &files)
+}
+
+/// Runs the project's verification command and end-to-end suite on a
+/// story's changes and records the results, with test coverage if the
+/// command reports it. Only a failing verification command fails the
+/// attempt.
+pub(crate) async fn verify<H: StoryHost>(host: &H, story: &Story, working_directory: &str) -> Result<(), AttemptFailure> {
+    let started = Instant::now();
+    let mut coverage = None;
+    let checks = match verification::load_config(host.project_path()) {
+        Some(config) => {
+            let started = SystemTime::now();
+            let checks = run_verification(host, story, &config, working_directory).await;
+            if let (true, Some(report)) = (checks.success, config.coverage_report.as_deref()) {
+                match coverage::collect(working_directory, report, started) {
+                    Ok(measured) => coverage = Some(measured),
+                    Err(e) => host.log(format!("Warning: Could not read coverage for {}: {}", story.id, e)),
+                }
+            }
+            Some(checks)
+        }
+        None => None,
+    };
+    let passed = checks.as_ref().is_none_or(|checks| checks.success);
+    let e2e = if passed && !host.stopping() {
+        host.run_e2e(story, working_directory).await
+    } else {
+        None
+    };
+
+    if checks.is_some() || e2e.is_some() {
+        let mut measured = None;
+        host.update_state(|state| {
+            let verifications = state.story_verifications.get_or_insert_with(HashMap::new);
+            measured = coverage.clone().map(|c| coverage::with_delta(c, verifications, &story.id));
+            verifications.insert(
+                story.id.clone(),
+                StoryVerification {
+                    verified_at: chrono::Utc::now().to_rfc3339(),
+                    checks: checks.clone(),
+                    coverage: measured.clone(),
+                    e2e: e2e.clone(),
+                },
+            );
+        });
+        if let Some(measured) = &measured {
+            host.log(format!("Coverage after {}: {}", story.id, coverage::describe(measured)));
+            host.journal(
+                "coverage",
+                Some(&story.id),
+                json!({ "percent": measured.percent, "delta": measured.delta }),
+            );
+        }
+    }
+
+    let verification_ms = started.elapsed().as_millis() as u64;
+    host.time_phase(&story.id, |timer| timer.verification_ms += verification_ms);
+    match checks {
+        Some(checks) if !checks.success => {
+            host.record_failure(&story.id, &checks.output_tail.join("\n"));
+            Err(AttemptFailure::new(
+                "verification",
+                format!("verification failed: {}", checks.reason.as_deref().unwrap_or("unknown error")),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Runs the verification command off the async runtime and logs how it went.
+async fn run_verification<H: StoryHost>(
+    host: &H,
+    story: &Story,
+    config: &VerificationConfig,
+    working_directory: &str,
+) -> VerificationRun {
+    host.log(format!("Verifying {}: {}", story.id, config.command.trim()));
+    let app_data_dir = host.app_data_dir();
+    let project_id = host.project_id().to_string();
+    let (task_story, task_config, task_directory) = (story.clone(), config.clone(), working_directory.to_string());
+    let run = tokio::task::spawn_blocking(move || {
+        verification::run(app_data_dir.as_deref(), &project_id, &task_story, &task_config, &task_directory)
+    })
+    .await
+    .unwrap_or_else(|e| VerificationRun {
+        command: config.command.trim().to_string(),
+        success: false,
+        exit_code: None,
+        reason: Some(format!("Task join error: {}", e)),
+        duration_ms: 0,
+        output_tail: Vec::new(),
+        process_id: String::new(),
+    });
+
+    if run.success {
+        host.log(format!("✓ Verification passed for {} ({}s)", story.id, run.duration_ms / 1000));
+    } else {
+        let reason = run.reason.as_deref().unwrap_or("unknown error");
+        host.log(format!("✗ Verification failed for {} ({})", story.id, reason));
+        for line in run.output_tail.iter().skip(run.output_tail.len().saturating_sub(10)) {
+            host.log(format!("  {}", line));
+        }
+    }
+    host.journal(
+        "verification",
+        Some(&story.id),
+        json!({
+            "success": run.success,
+            "exitCode": run.exit_code,
+            "reason": run.reason,
+            "durationMs": run.duration_ms,
+            "processId": run.process_id,
+        }),
+    );
+    run
+}
+
+/// Records a story commit and its diff size in the build report.
+pub(crate) fn record_commit<H: StoryHost>(host: &H, story: &Story, hash: &str) {
+    host.journal("commit", Some(&story.id), json!({ "commit": hash }));
+    if let Err(e) = meta_history::record(host.project_path(), &story.id, &story.title, hash) {
+        host.log(format!("Warning: Could not record metadata history: {}", e));
+    }
+    let Some(diff) = worktree::story_commit_stat(host.project_path(), hash, &story.id) else {
+        return;
+    };
+    host.update_story_report(story, |report| {
+        report.diff = Some(diff);
+        report.commit = Some(hash.to_string());
+    });
+}
@@ -0,0 +1,533 @@
+//! Swarm mode: several agents on one story.
+//!
+//! A planner agent splits the story into tasks that can be done side by side.
+//! Each task gets a worker agent in its own worktree, and the workers' commits
+//! are merged into the story's worktree. A reviewer agent then checks the
+//! combined diff against the acceptance criteria; once it approves and the
+//! story passes verification, the story's branch is merged into the project.
+//! A rejected review fails the attempt with the reviewer's issues, so the
+//! retry is planned with them in mind.
+//!
+//! Progress is emitted as `build-swarm` after every step.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::task::JoinSet;
+
+use super::story_loop::{self, StoryHost};
+use super::{short_hash, story_notes, AttemptFailure, BuildContext, BuildMergeConflictEvent};
+use crate::event_bus;
+use crate::models::Story;
+use crate::prompt_templates::story_context;
+use crate::utils::{agent_reply_text, parse_reply_json};
+use crate::worktree::{self, WorktreeResult};
+
+/// Most tasks a story is split into.
+const MAX_TASKS: usize = 6;
+
+/// Most workers running at once.
+const MAX_WORKERS: usize = 4;
+
+/// Characters of the combined diff shown to the reviewer.
+const MAX_DIFF_CHARS: usize = 60_000;
+
+pub(crate) const DEFAULT_PLANNER_PROMPT: &str = r#"You are planning work for a team of coding agents who will implement one user story at the same time.
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+{{notes}}
+
+Explore the codebase, but do not modify any files. Split the story into at most {{maxTasks}} tasks that can be done at the same time without editing the same files. A small story can be a single task.
+
+Respond with only a JSON object, no other text:
+{
+  "tasks": [
+    { "title": "short task title", "description": "what to do, in enough detail to do it without seeing the other tasks", "files": ["files this task creates or changes"] }
+  ]
+}"#;
+
+pub(crate) const DEFAULT_WORKER_PROMPT: &str = r#"You are one of several coding agents implementing a user story together. The others are working on the other tasks at the same time in separate copies of the repository, and your changes will be merged with theirs.
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+## Your task: {{task.title}}
+
+{{task.description}}
+{% if task.files %}
+
+Files: {{ task.files | join(", ") }}
+{% endif %}
+
+### The other tasks
+{% for other in tasks if other.id != task.id %}
+- {{ other.title }}
+{% else %}
+- None
+{% endfor %}
+
+Do only your task, and leave the files of the other tasks alone. Do not commit; your changes are committed for you."#;
+
+pub(crate) const DEFAULT_REVIEWER_PROMPT: &str = r#"You are reviewing the combined work of several coding agents on one user story before it is merged.
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+### Tasks
+{% for task in tasks %}
+- {{ task.title }}
+{% endfor %}
+
+### Combined diff
+```diff
+{{diff}}
+```
+
+Check that the changes meet every acceptance criterion, fit together, and have no obvious bugs. You may read the code, but do not modify any files.
+
+Respond with only a JSON object, no other text:
+{ "approved": true, "summary": "one paragraph", "issues": ["problems that must be fixed before merging"] }"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwarmTask {
+    #[serde(default)]
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// "pending", "running", "done", or "failed".
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub branch_name: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwarmReview {
+    pub approved: bool,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub issues: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SwarmPlan {
+    tasks: Vec<SwarmTask>,
+}
+
+/// Payload of `build-swarm`.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSwarmEvent {
+    pub project_id: String,
+    pub story_id: String,
+    pub story_title: String,
+    /// "planning", "working", "merging", "reviewing", "verifying", "done", or
+    /// "failed".
+    pub phase: String,
+    pub tasks: Vec<SwarmTask>,
+    pub review: Option<SwarmReview>,
+    pub message: Option<String>,
+}
+
+impl BuildContext {
+    fn emit_swarm(&self, story: &Story, phase: &str, tasks: &[SwarmTask], review: Option<&SwarmReview>, message: Option<String>) {
+        let _ = event_bus::emit(
+            &self.app,
+            "build-swarm",
+            BuildSwarmEvent {
+                project_id: self.project_id().to_string(),
+                story_id: story.id.clone(),
+                story_title: story.title.clone(),
+                phase: phase.to_string(),
+                tasks: tasks.to_vec(),
+                review: review.cloned(),
+                message,
+            },
+        );
+    }
+
+    /// Variables for a swarm prompt: the story's, plus `extra`.
+    fn swarm_context(&self, story: &Story, extra: Value) -> Value {
+        let retry = self.ctrl.retries.lock().ok().and_then(|r| r.get(&story.id).cloned());
+        let mut context = story_context(story, story_notes(story, retry.as_ref()));
+        if let Value::Object(extra) = extra {
+            context.extend(extra);
+        }
+        Value::Object(context)
+    }
+
+    /// Fails a swarm attempt: logs and emits it, and removes the worktrees.
+    async fn fail_swarm(
+        &self,
+        story: &Story,
+        tasks: &[SwarmTask],
+        worktrees: &[WorktreeResult],
+        failure: AttemptFailure,
+    ) -> Result<(), AttemptFailure> {
+        self.remove_worktrees(worktrees).await;
+        if failure.kind == "canceled" {
+            self.set_story_status(&story.id, "pending");
+        } else {
+            self.log(format!("✗ [Swarm] Story {} failed ({})", story.id, failure.message));
+            self.set_story_status(&story.id, "failed");
+            self.emit_swarm(story, "failed", tasks, None, Some(failure.message.clone()));
+        }
+        Err(failure)
+    }
+
+    async fn remove_worktrees(&self, worktrees: &[WorktreeResult]) {
+        for prepared in worktrees {
+            // Unsuccessful finalizing only removes the worktree and its branch
+            let _ = worktree::finalize_story_worktree(
+                self.app.clone(),
+                self.project_path().to_string(),
+                String::new(),
+                prepared.worktree_path.clone(),
+                prepared.branch_name.clone(),
+                false,
+            )
+            .await;
+        }
+    }
+
+    /// Asks the planner to split the story into tasks, working read-only in
+    /// `working_directory`.
+    async fn plan_swarm(&self, story: &Story, working_directory: &str) -> Result<Vec<SwarmTask>, AttemptFailure> {
+        let context = self.swarm_context(story, json!({ "maxTasks": MAX_TASKS }));
+        let prompt = self
+            .prompts
+            .render("swarmPlanner", None, &context)
+            .map_err(|e| AttemptFailure::new("swarm-plan", e))?;
+        self.log(format!("[Swarm] Planning {}", story.id));
+        let run = self.run_agent_prompt(story, &prompt, working_directory, true, &story.id).await;
+        if !run.success {
+            let reason = run.reason.unwrap_or_else(|| "unknown error".to_string());
+            self.record_failure(&story.id, &run.recent_output);
+            return Err(AttemptFailure::new(
+                run.failure_kind.unwrap_or("exit-code"),
+                format!("planner failed: {}", reason),
+            ));
+        }
+
+        let lines: Vec<&str> = run.recent_output.lines().collect();
+        let plan: SwarmPlan = parse_reply_json(&agent_reply_text(&lines), "the plan")
+            .map_err(|e| AttemptFailure::new("swarm-plan", e))?;
+        let tasks: Vec<SwarmTask> = plan
+            .tasks
+            .into_iter()
+            .filter(|t| !t.title.trim().is_empty())
+            .take(MAX_TASKS)
+            .enumerate()
+            .map(|(i, task)| SwarmTask {
+                id: format!("task-{}", i + 1),
+                status: "pending".to_string(),
+                branch_name: None,
+                error: None,
+                ..task
+            })
+            .collect();
+        if tasks.is_empty() {
+            return Err(AttemptFailure::new("swarm-plan", "the planner returned no tasks"));
+        }
+        Ok(tasks)
+    }
+
+    /// Runs one worker in its own worktree and commits what it did.
+    async fn run_swarm_worker(&self, story: &Story, task: &SwarmTask, tasks: &[SwarmTask]) -> Result<Option<WorktreeResult>, String> {
+        let context = self.swarm_context(story, json!({ "task": task, "tasks": tasks }));
+        let prompt = self.prompts.with_stack_snippets(self.prompts.render("swarmWorker", None, &context)?);
+        let prepared = worktree::prepare_story_worktree(
+            self.app.clone(),
+            self.project_path().to_string(),
+            format!("{}-{}", story.id, task.id),
+        )
+        .await?;
+        self.journal(
+            "swarm-worker",
+            Some(&story.id),
+            json!({ "taskId": task.id, "title": task.title, "branchName": prepared.branch_name }),
+        );
+
+        let slot = format!("{}#{}", story.id, task.id);
+        let run = self
+            .run_agent_prompt(story, &prompt, &prepared.worktree_path, true, &slot)
+            .await;
+        let committed = if run.success {
+            worktree::commit_all(
+                &prepared.worktree_path,
+                &format!("Story {} {}: {}", story.id, task.id, task.title),
+            )
+        } else {
+            Err(run.reason.unwrap_or_else(|| "unknown error".to_string()))
+        };
+        match committed {
+            Ok(true) => Ok(Some(prepared)),
+            Ok(false) => {
+                self.remove_worktrees(std::slice::from_ref(&prepared)).await;
+                Ok(None)
+            }
+            Err(e) => {
+                self.remove_worktrees(std::slice::from_ref(&prepared)).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Runs a story in swarm mode; see the module docs.
+    pub(super) async fn run_story_swarm(&self, story: &Story) -> Result<(), AttemptFailure> {
+        let project_path = self.project_path().to_string();
+        self.set_story_status(&story.id, "in-progress");
+        self.begin_in_flight(&story.id);
+        self.log(format!("[Swarm] Starting story {}: {}", story.id, story.title));
+        self.emit_swarm(story, "planning", &[], None, None);
+
+        let base_ref = match worktree::get_base_ref(&project_path) {
+            Ok(base_ref) => base_ref,
+            Err(e) => return self.fail_swarm(story, &[], &[], AttemptFailure::new("worktree-error", e)).await,
+        };
+        let integration =
+            match worktree::prepare_story_worktree(self.app.clone(), project_path.clone(), story.id.clone()).await {
+                Ok(prepared) => prepared,
+                Err(e) => return self.fail_swarm(story, &[], &[], AttemptFailure::new("worktree-error", e)).await,
+            };
+        self.update_in_flight(&story.id, |entry| {
+            entry.worktree_path = Some(integration.worktree_path.clone());
+            entry.branch_name = Some(integration.branch_name.clone());
+        });
+        let integration_only = std::slice::from_ref(&integration);
+
+        // Plan in the story's worktree, discarding anything the planner wrote
+        let planned = self.plan_swarm(story, &integration.worktree_path).await;
+        let _ = worktree::git_discard_changes(self.app.clone(), integration.worktree_path.clone()).await;
+        let mut tasks = match planned {
+            Ok(tasks) => tasks,
+            Err(failure) => return self.fail_swarm(story, &[], integration_only, failure).await,
+        };
+        self.log(format!(
+            "[Swarm] {} split into {} task{}: {}",
+            story.id,
+            tasks.len(),
+            if tasks.len() == 1 { "" } else { "s" },
+            tasks.iter().map(|t| t.title.as_str()).collect::<Vec<_>>().join("; ")
+        ));
+        self.journal("swarm-plan", Some(&story.id), json!({ "tasks": tasks }));
+        if self.ctrl.stopping() {
+            return self.fail_swarm(story, &tasks, integration_only, AttemptFailure::canceled()).await;
+        }
+
+        // Work
+        let mut workers = JoinSet::new();
+        let mut next = 0;
+        let mut branches: Vec<(usize, WorktreeResult)> = Vec::new();
+        loop {
+            while workers.len() < MAX_WORKERS && next < tasks.len() && !self.ctrl.stopping() {
+                tasks[next].status = "running".to_string();
+                let (ctx, story, task, all) = (self.clone(), story.clone(), tasks[next].clone(), tasks.clone());
+                let index = next;
+                workers.spawn(async move { (index, ctx.run_swarm_worker(&story, &task, &all).await) });
+                next += 1;
+            }
+            self.emit_swarm(story, "working", &tasks, None, None);
+            let Some(joined) = workers.join_next().await else { break };
+            let Ok((index, result)) = joined else { continue };
+            match result {
+                Ok(prepared) => {
+                    tasks[index].status = "done".to_string();
+                    tasks[index].branch_name = prepared.as_ref().map(|p| p.branch_name.clone());
+                    self.log(format!("[Swarm] {} {} done: {}", story.id, tasks[index].id, tasks[index].title));
+                    if let Some(prepared) = prepared {
+                        branches.push((index, prepared));
+                    }
+                }
+                Err(e) => {
+                    tasks[index].status = "failed".to_string();
+                    tasks[index].error = Some(e.clone());
+                    self.log(format!("✗ [Swarm] {} {} failed: {}", story.id, tasks[index].id, e));
+                }
+            }
+        }
+        let mut worktrees: Vec<WorktreeResult> = branches.iter().map(|(_, p)| p.clone()).collect();
+        worktrees.push(integration.clone());
+        if self.ctrl.stopping() {
+            return self.fail_swarm(story, &tasks, &worktrees, AttemptFailure::canceled()).await;
+        }
+        let failed: Vec<String> = tasks
+            .iter()
+            .filter(|t| t.status == "failed")
+            .map(|t| format!("{}: {}", t.title, t.error.clone().unwrap_or_default()))
+            .collect();
+        if !failed.is_empty() {
+            self.record_failure(&story.id, &failed.join("\n"));
+            let failure = AttemptFailure::new("swarm-task", format!("tasks failed: {}", failed.join("; ")));
+            return self.fail_swarm(story, &tasks, &worktrees, failure).await;
+        }
+        if branches.is_empty() {
+            let failure = AttemptFailure::new("swarm-task", "no task changed anything");
+            return self.fail_swarm(story, &tasks, &worktrees, failure).await;
+        }
+
+        // Merge the workers' branches into the story's
+        self.emit_swarm(story, "merging", &tasks, None, None);
+        branches.sort_by_key(|(index, _)| *index);
+        for (index, prepared) in &branches {
+            if let Err(e) = worktree::merge_branch(&integration.worktree_path, &prepared.branch_name) {
+                // Tasks that overlap get planned apart on the retry
+                self.record_failure(&story.id, &format!("{} conflicted with an earlier task: {}", tasks[*index].title, e));
+                let failure = AttemptFailure::new("swarm-conflict", format!("{} conflicted", tasks[*index].title));
+                return self.fail_swarm(story, &tasks, &worktrees, failure).await;
+            }
+        }
+        let worker_worktrees: Vec<WorktreeResult> = branches.into_iter().map(|(_, p)| p).collect();
+        self.remove_worktrees(&worker_worktrees).await;
+
+        // Review
+        self.emit_swarm(story, "reviewing", &tasks, None, None);
+        let review = self.review_swarm(story, &integration, &base_ref, &tasks).await;
+        let _ = worktree::git_discard_changes(self.app.clone(), integration.worktree_path.clone()).await;
+        let review = match review {
+            Ok(review) => review,
+            Err(failure) => return self.fail_swarm(story, &tasks, integration_only, failure).await,
+        };
+        self.journal("swarm-review", Some(&story.id), json!(review));
+        if !review.approved {
+            let issues = if review.issues.is_empty() { review.summary.clone() } else { review.issues.join("\n") };
+            self.log(format!("✗ [Swarm] Reviewer rejected {}: {}", story.id, issues.replace('\n', "; ")));
+            self.record_failure(&story.id, &format!("The reviewer rejected the changes:\n{}", issues));
+            self.emit_swarm(story, "failed", &tasks, Some(&review), Some("review rejected".to_string()));
+            self.remove_worktrees(integration_only).await;
+            self.set_story_status(&story.id, "failed");
+            return Err(AttemptFailure::new("review-rejected", format!("review rejected: {}", review.summary)));
+        }
+        self.log(format!("[Swarm] Reviewer approved {}", story.id));
+
+        // Verify in the story's worktree, then merge it
+        self.emit_swarm(story, "verifying", &tasks, Some(&review), None);
+        if let Err(failure) = story_loop::verify(self, story, &integration.worktree_path).await {
+            return self.fail_swarm(story, &tasks, integration_only, failure).await;
+        }
+        if self.ctrl.stopping() {
+            return self.fail_swarm(story, &tasks, integration_only, AttemptFailure::canceled()).await;
+        }
+        let merging = Instant::now();
+        let merged = worktree::merge_branch(&project_path, &integration.branch_name);
+        let merge_ms = merging.elapsed().as_millis() as u64;
+        self.time_phase(&story.id, |timer| timer.merge_ms += merge_ms);
+        self.journal(
+            "merge",
+            Some(&story.id),
+            json!({ "branchName": integration.branch_name, "merged": merged.is_ok(), "error": merged.as_ref().err() }),
+        );
+        // Removes the worktree; an unmerged branch is kept
+        let _ = worktree::finalize_story_worktree(
+            self.app.clone(),
+            project_path.clone(),
+            story.id.clone(),
+            integration.worktree_path.clone(),
+            integration.branch_name.clone(),
+            true,
+        )
+        .await;
+        if let Err(e) = merged {
+            let _ = event_bus::emit(
+                &self.app,
+                "build-merge-conflict",
+                BuildMergeConflictEvent {
+                    project_id: self.project_id().to_string(),
+                    story_id: story.id.clone(),
+                    story_title: story.title.clone(),
+                    branch_name: integration.branch_name.clone(),
+                },
+            );
+            self.log(format!(
+                "⚠ [Swarm] Merge conflict for story {} - changes kept in branch: {}",
+                story.id, integration.branch_name
+            ));
+            self.set_story_status(&story.id, "failed");
+            self.record_failure(&story.id, &e);
+            self.emit_swarm(story, "failed", &tasks, Some(&review), Some(e.clone()));
+            return Err(AttemptFailure::new("merge-conflict", e));
+        }
+
+        self.log(format!("✓ [Swarm] Story {} completed and merged", story.id));
+        self.emit_swarm(story, "done", &tasks, Some(&review), None);
+        if let Err(e) = self.mark_story_passed(&story.id).await {
+            self.log(format!("Warning: Could not save PRD: {}", e));
+        }
+        self.set_story_status(&story.id, "complete");
+        if worktree::check_git_initialized(self.app.clone(), project_path.clone())
+            .await
+            .unwrap_or(false)
+        {
+            let committing = Instant::now();
+            match worktree::git_commit_story(self.app.clone(), project_path, story.id.clone(), story.title.clone()).await {
+                Ok(hash) => {
+                    self.log(format!("✓ [Swarm] Committed changes: {}", short_hash(&hash)));
+                    story_loop::record_commit(self, story, &hash);
+                }
+                Err(e) => self.log(format!("Warning: Could not commit changes: {}", e)),
+            }
+            let merge_ms = committing.elapsed().as_millis() as u64;
+            self.time_phase(&story.id, |timer| timer.merge_ms += merge_ms);
+        }
+        self.refresh_preview(story);
+        Ok(())
+    }
+
+    /// Asks the reviewer to check the story's combined changes.
+    async fn review_swarm(
+        &self,
+        story: &Story,
+        integration: &WorktreeResult,
+        base_ref: &str,
+        tasks: &[SwarmTask],
+    ) -> Result<SwarmReview, AttemptFailure> {
+        let mut diff = worktree::branch_diff(&integration.worktree_path, base_ref)
+            .map_err(|e| AttemptFailure::new("worktree-error", e))?;
+        if diff.len() > MAX_DIFF_CHARS {
+            let cut = (0..=MAX_DIFF_CHARS).rev().find(|i| diff.is_char_boundary(*i)).unwrap_or(0);
+            diff.truncate(cut);
+            diff.push_str("\n... (diff truncated; read the files for the rest)");
+        }
+        let context = self.swarm_context(story, json!({ "tasks": tasks, "diff": diff }));
+        let prompt = self
+            .prompts
+            .render("swarmReviewer", None, &context)
+            .map_err(|e| AttemptFailure::new("swarm-review", e))?;
+        self.log(format!("[Swarm] Reviewing {}", story.id));
+        let run = self
+            .run_agent_prompt(story, &prompt, &integration.worktree_path, true, &story.id)
+            .await;
+        if !run.success {
+            let reason = run.reason.unwrap_or_else(|| "unknown error".to_string());
+            return Err(AttemptFailure::new(
+                run.failure_kind.unwrap_or("exit-code"),
+                format!("reviewer failed: {}", reason),
+            ));
+        }
+        let lines: Vec<&str> = run.recent_output.lines().collect();
+        parse_reply_json(&agent_reply_text(&lines), "the review").map_err(|e| AttemptFailure::new("swarm-review", e))
+    }
+}
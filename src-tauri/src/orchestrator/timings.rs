@@ -0,0 +1,216 @@
+//! Per-story timing analytics.
+//!
+//! Each agent attempt at a story is split into the time it spent queued
+//! (behind other stories, prerequisites, a pause, or a retry backoff), in the
+//! agent, in verification, and landing its changes: the worktree merge, where
+//! there is one, and the commit. Finished attempts are appended as JSON lines
+//! to `.ideate/story-timings.jsonl`, and `get_story_timings` sums them per
+//! story and per phase.
+
+#[cfg(feature = "gui")]
+use std::collections::HashMap;
+#[cfg(feature = "gui")]
+use std::fs::{self, OpenOptions};
+#[cfg(feature = "gui")]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "gui")]
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+#[cfg(feature = "gui")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "gui")]
+use crate::utils::get_ideate_dir;
+
+lazy_static::lazy_static! {
+    /// Keeps concurrent stories from interleaving partial lines.
+    static ref TIMINGS_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// One finished attempt at a story.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryTiming {
+    pub build_id: String,
+    pub story_id: String,
+    pub attempt: u32,
+    pub agent_id: String,
+    pub started_at: String,
+    pub finished_at: String,
+    /// "passed", or the failure kind, e.g. "verification" or "timeout"
+    pub outcome: String,
+    pub queue_wait_ms: u64,
+    /// Summed across agents, so a swarm story can exceed its wall time.
+    pub agent_ms: u64,
+    pub verification_ms: u64,
+    pub merge_ms: u64,
+    /// From the attempt's start to its end; snapshots, hooks, and worktree
+    /// setup make up whatever the phases don't.
+    pub total_ms: u64,
+}
+
+/// Phase times of an attempt while it runs.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub(crate) struct AttemptTimer {
+    pub attempt: u32,
+    pub started: Instant,
+    pub started_at: String,
+    pub queue_wait_ms: u64,
+    pub agent_ms: u64,
+    pub verification_ms: u64,
+    pub merge_ms: u64,
+}
+
+#[cfg(feature = "gui")]
+impl AttemptTimer {
+    pub fn new(attempt: u32, queued_since: Instant) -> Self {
+        let started = Instant::now();
+        Self {
+            attempt,
+            started,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            queue_wait_ms: started.saturating_duration_since(queued_since).as_millis() as u64,
+            agent_ms: 0,
+            verification_ms: 0,
+            merge_ms: 0,
+        }
+    }
+
+    pub fn finish(self, build_id: &str, story_id: &str, agent_id: &str, outcome: &str) -> StoryTiming {
+        StoryTiming {
+            build_id: build_id.to_string(),
+            story_id: story_id.to_string(),
+            attempt: self.attempt,
+            agent_id: agent_id.to_string(),
+            started_at: self.started_at,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            outcome: outcome.to_string(),
+            queue_wait_ms: self.queue_wait_ms,
+            agent_ms: self.agent_ms,
+            verification_ms: self.verification_ms,
+            merge_ms: self.merge_ms,
+            total_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Time spent in each phase, summed over attempts.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTotals {
+    pub attempts: u32,
+    pub queue_wait_ms: u64,
+    pub agent_ms: u64,
+    pub verification_ms: u64,
+    pub merge_ms: u64,
+    pub total_ms: u64,
+}
+
+#[cfg(feature = "gui")]
+impl PhaseTotals {
+    fn add(&mut self, timing: &StoryTiming) {
+        self.attempts += 1;
+        self.queue_wait_ms += timing.queue_wait_ms;
+        self.agent_ms += timing.agent_ms;
+        self.verification_ms += timing.verification_ms;
+        self.merge_ms += timing.merge_ms;
+        self.total_ms += timing.total_ms;
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryTimingSummary {
+    pub story_id: String,
+    /// Outcome of the latest attempt.
+    pub last_outcome: String,
+    #[serde(flatten)]
+    pub totals: PhaseTotals,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryTimings {
+    /// Every recorded attempt, oldest first.
+    pub attempts: Vec<StoryTiming>,
+    /// One entry per story, slowest first.
+    pub stories: Vec<StoryTimingSummary>,
+    pub totals: PhaseTotals,
+}
+
+#[cfg(feature = "gui")]
+fn get_timings_path(project_path: &str) -> PathBuf {
+    get_ideate_dir(project_path).join("story-timings.jsonl")
+}
+
+/// Appends a finished attempt to the project's timings.
+#[cfg(feature = "gui")]
+pub fn append(project_path: &str, timing: &StoryTiming) -> Result<(), String> {
+    let line = serde_json::to_string(timing).map_err(|e| format!("Failed to serialize timing: {}", e))?;
+
+    let _guard = TIMINGS_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let dir = get_ideate_dir(project_path);
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_timings_path(project_path))
+        .map_err(|e| format!("Failed to open story-timings.jsonl: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write story-timings.jsonl: {}", e))
+}
+
+/// Reads a project's recorded attempts, oldest first. Unparseable lines are
+/// skipped.
+#[cfg(feature = "gui")]
+pub fn read_timings(project_path: &str) -> Result<Vec<StoryTiming>, String> {
+    let path = get_timings_path(project_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to read story-timings.jsonl: {}", e))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<StoryTiming>(&line).ok())
+        .collect())
+}
+
+/// Returns every recorded story attempt in a project with per-story and
+/// overall phase totals, so the slow part of a build stands out.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_story_timings(project_path: String) -> Result<StoryTimings, String> {
+    let attempts = read_timings(&project_path)?;
+
+    let mut totals = PhaseTotals::default();
+    let mut by_story: HashMap<&str, StoryTimingSummary> = HashMap::new();
+    for timing in &attempts {
+        totals.add(timing);
+        let summary = by_story
+            .entry(&timing.story_id)
+            .or_insert_with(|| StoryTimingSummary {
+                story_id: timing.story_id.clone(),
+                last_outcome: String::new(),
+                totals: PhaseTotals::default(),
+            });
+        summary.totals.add(timing);
+        summary.last_outcome = timing.outcome.clone();
+    }
+    let mut stories: Vec<StoryTimingSummary> = by_story.into_values().collect();
+    stories.sort_by(|a, b| b.totals.total_ms.cmp(&a.totals.total_ms).then_with(|| a.story_id.cmp(&b.story_id)));
+
+    Ok(StoryTimings {
+        attempts,
+        stories,
+        totals,
+    })
+}
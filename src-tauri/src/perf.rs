@@ -0,0 +1,212 @@
+//! Timings of heavy backend operations.
+//!
+//! Usage scans, diffs, and worktree operations hold a `Timer` while they run.
+//! When it drops, the duration and the number of files the operation touched
+//! are added to per-operation statistics kept in memory since launch.
+//! `get_performance_report` summarizes them with the slowest individual runs,
+//! so someone whose dashboard is slow can see which operation is at fault and
+//! on how many files. The report is also included in diagnostics bundles.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::Serialize;
+
+/// Durations kept per operation for percentiles.
+const RECENT_SAMPLES: usize = 200;
+
+/// Slowest individual runs kept across all operations.
+const SLOWEST_SAMPLES: usize = 25;
+
+/// Runs at least this long are also written to the app log.
+const SLOW_LOG_MS: u64 = 2000;
+
+#[derive(Default)]
+struct OperationStats {
+    count: u64,
+    total_ms: u64,
+    max_ms: u64,
+    last_ms: u64,
+    files_total: u64,
+    files_max: u64,
+    recent_ms: VecDeque<u64>,
+}
+
+struct Stats {
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    started_at: String,
+    operations: HashMap<&'static str, OperationStats>,
+    slowest: Vec<Sample>,
+}
+
+lazy_static::lazy_static! {
+    static ref STATS: Mutex<Stats> = Mutex::new(Stats {
+        started_at: chrono::Utc::now().to_rfc3339(),
+        operations: HashMap::new(),
+        slowest: Vec::new(),
+    });
+}
+
+/// One run of an operation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sample {
+    pub operation: String,
+    /// What it ran on, such as a project path.
+    pub subject: Option<String>,
+    pub duration_ms: u64,
+    /// Files it read or changed; worktrees for `worktree.usage`.
+    pub files: Option<u64>,
+    pub finished_at: String,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationReport {
+    pub operation: String,
+    pub count: u64,
+    pub total_ms: u64,
+    pub mean_ms: u64,
+    /// Over the most recent runs.
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+    pub files_total: u64,
+    pub files_max: u64,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceReport {
+    /// When measuring started.
+    pub since: String,
+    /// Most total time first.
+    pub operations: Vec<OperationReport>,
+    /// Slowest first.
+    pub slowest: Vec<Sample>,
+}
+
+/// Measures an operation until dropped.
+pub struct Timer {
+    operation: &'static str,
+    subject: Option<String>,
+    files: Option<u64>,
+    started: Instant,
+}
+
+/// Starts timing `operation`, named like `worktree.diff`.
+pub fn start(operation: &'static str) -> Timer {
+    Timer {
+        operation,
+        subject: None,
+        files: None,
+        started: Instant::now(),
+    }
+}
+
+impl Timer {
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Records how many files the operation read or changed.
+    pub fn set_files(&mut self, files: usize) {
+        self.files = Some(files as u64);
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let duration_ms = self.started.elapsed().as_millis() as u64;
+        if duration_ms >= SLOW_LOG_MS {
+            tracing::info!(
+                operation = self.operation,
+                subject = self.subject.as_deref().unwrap_or_default(),
+                files = self.files.unwrap_or_default(),
+                "Slow operation took {}ms",
+                duration_ms
+            );
+        }
+        let sample = Sample {
+            operation: self.operation.to_string(),
+            subject: self.subject.take(),
+            duration_ms,
+            files: self.files,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+        };
+        record(self.operation, sample);
+    }
+}
+
+fn record(operation: &'static str, sample: Sample) {
+    let mut stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = stats.operations.entry(operation).or_default();
+    entry.count += 1;
+    entry.total_ms += sample.duration_ms;
+    entry.max_ms = entry.max_ms.max(sample.duration_ms);
+    entry.last_ms = sample.duration_ms;
+    if let Some(files) = sample.files {
+        entry.files_total += files;
+        entry.files_max = entry.files_max.max(files);
+    }
+    entry.recent_ms.push_back(sample.duration_ms);
+    if entry.recent_ms.len() > RECENT_SAMPLES {
+        entry.recent_ms.pop_front();
+    }
+
+    let fastest_kept = stats.slowest.last().map_or(0, |s| s.duration_ms);
+    if stats.slowest.len() < SLOWEST_SAMPLES || sample.duration_ms > fastest_kept {
+        let at = stats.slowest.partition_point(|s| s.duration_ms >= sample.duration_ms);
+        stats.slowest.insert(at, sample);
+        stats.slowest.truncate(SLOWEST_SAMPLES);
+    }
+}
+
+#[cfg(feature = "gui")]
+fn percentile(recent: &VecDeque<u64>, pct: usize) -> u64 {
+    let mut sorted: Vec<u64> = recent.iter().copied().collect();
+    sorted.sort_unstable();
+    match sorted.len() {
+        0 => 0,
+        len => sorted[((len * pct).div_ceil(100)).clamp(1, len) - 1],
+    }
+}
+
+/// Summarizes everything measured since launch.
+#[cfg(feature = "gui")]
+pub fn report() -> PerformanceReport {
+    let stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut operations: Vec<OperationReport> = stats
+        .operations
+        .iter()
+        .map(|(name, op)| OperationReport {
+            operation: name.to_string(),
+            count: op.count,
+            total_ms: op.total_ms,
+            mean_ms: op.total_ms / op.count.max(1),
+            p95_ms: percentile(&op.recent_ms, 95),
+            max_ms: op.max_ms,
+            last_ms: op.last_ms,
+            files_total: op.files_total,
+            files_max: op.files_max,
+        })
+        .collect();
+    operations.sort_by_key(|o| std::cmp::Reverse(o.total_ms));
+    PerformanceReport {
+        since: stats.started_at.clone(),
+        operations,
+        slowest: stats.slowest.clone(),
+    }
+}
+
+/// Timings of usage scans, diffs, and worktree operations since launch,
+/// slowest first.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn get_performance_report() -> Result<PerformanceReport, String> {
+    Ok(report())
+}
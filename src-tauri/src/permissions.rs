@@ -0,0 +1,144 @@
+//! Permission status detection.
+//!
+//! `open_full_disk_access_settings` can send the user to System Settings,
+//! but macOS doesn't tell an app whether it was then granted access. This
+//! probes for it instead: by reading a file only Full Disk Access unlocks,
+//! and the agents' data directories that usage tracking reads, and asks the
+//! notification plugin for its permission state.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// Not decided yet; the system asks the first time it's needed.
+    Prompt,
+    /// What the permission guards doesn't exist, such as an agent that isn't
+    /// installed.
+    NotFound,
+    /// Not a permission on this platform.
+    NotApplicable,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathPermission {
+    pub path: String,
+    pub status: PermissionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionReport {
+    pub full_disk_access: PermissionStatus,
+    /// Claude Code's data directory, `~/.claude`.
+    pub claude_data: PathPermission,
+    /// Amp's data directory, `~/.local/share/amp`.
+    pub amp_data: PathPermission,
+    pub notifications: PermissionStatus,
+}
+
+impl PermissionReport {
+    /// Whether everything that exists on this platform is granted.
+    fn all_granted(&self) -> bool {
+        let ok = |status: PermissionStatus| {
+            matches!(
+                status,
+                PermissionStatus::Granted | PermissionStatus::NotFound | PermissionStatus::NotApplicable
+            )
+        };
+        ok(self.full_disk_access) && ok(self.claude_data.status) && ok(self.amp_data.status) && ok(self.notifications)
+    }
+}
+
+/// Checks that a directory can be listed.
+fn probe_dir(path: &Path) -> PathPermission {
+    let (status, error) = match fs::read_dir(path) {
+        Ok(_) => (PermissionStatus::Granted, None),
+        Err(e) if e.kind() == ErrorKind::NotFound => (PermissionStatus::NotFound, None),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => (PermissionStatus::Denied, Some(e.to_string())),
+        Err(e) => (PermissionStatus::Unknown, Some(e.to_string())),
+    };
+    PathPermission {
+        path: path.to_string_lossy().to_string(),
+        status,
+        error,
+    }
+}
+
+fn home_path(parts: &[&str]) -> Option<PathBuf> {
+    let mut path = dirs::home_dir()?;
+    path.extend(parts);
+    Some(path)
+}
+
+fn probe_home_dir(parts: &[&str]) -> PathPermission {
+    match home_path(parts) {
+        Some(path) => probe_dir(&path),
+        None => PathPermission {
+            path: format!("~/{}", parts.join("/")),
+            status: PermissionStatus::Unknown,
+            error: Some("Home directory not found".to_string()),
+        },
+    }
+}
+
+/// The user's TCC database can only be opened with Full Disk Access.
+#[cfg(target_os = "macos")]
+fn full_disk_access() -> PermissionStatus {
+    let Some(path) = home_path(&["Library", "Application Support", "com.apple.TCC", "TCC.db"]) else {
+        return PermissionStatus::Unknown;
+    };
+    match fs::File::open(path) {
+        Ok(_) => PermissionStatus::Granted,
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => PermissionStatus::Denied,
+        Err(_) => PermissionStatus::Unknown,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn full_disk_access() -> PermissionStatus {
+    PermissionStatus::NotApplicable
+}
+
+fn notifications(app: &AppHandle) -> PermissionStatus {
+    match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => PermissionStatus::Granted,
+        Ok(PermissionState::Denied) => PermissionStatus::Denied,
+        Ok(_) => PermissionStatus::Prompt,
+        Err(e) => {
+            tracing::debug!("Failed to read notification permission: {}", e);
+            PermissionStatus::Unknown
+        }
+    }
+}
+
+/// Reports which of the permissions the app relies on are granted.
+#[tauri::command]
+pub async fn check_permissions(app: AppHandle) -> Result<PermissionReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let report = PermissionReport {
+            full_disk_access: full_disk_access(),
+            claude_data: probe_home_dir(&[".claude"]),
+            amp_data: probe_home_dir(&[".local", "share", "amp"]),
+            notifications: notifications(&app),
+        };
+        if !report.all_granted() {
+            tracing::info!("Missing permissions: {:?}", report);
+        }
+        report
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
@@ -0,0 +1,265 @@
+//! External process plugins.
+//!
+//! Any executable in `<app data>/plugins/` is a plugin. Ideate runs it with a
+//! single JSON request on stdin and reads a single JSON response from stdout
+//! (the last non-empty line, so stray output is tolerated); stderr goes to the
+//! app log. There are two requests:
+//!
+//! - `{"type":"describe"}` → `{"name":"…","version":"…","commands":[{"name":"…","description":"…"}]}`
+//! - `{"type":"invoke","command":"…","args":{…}}` → `{"ok":true,"result":…}`
+//!   or `{"ok":false,"error":"…"}`
+//!
+//! Plugins are described at startup and on `reload_plugins`. Every invocation
+//! starts a fresh process, so plugins don't need to manage a lifecycle.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+
+/// How long a plugin gets to describe itself.
+const DESCRIBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a command may run before it is killed.
+const INVOKE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+lazy_static::lazy_static! {
+    static ref PLUGINS: Mutex<Vec<PluginInfo>> = Mutex::new(Vec::new());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCommand {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    /// The executable's file name.
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub path: String,
+    pub commands: Vec<PluginCommand>,
+    /// Why the plugin couldn't be described; it has no commands if set.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginsInfo {
+    pub directory: String,
+    pub plugins: Vec<PluginInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DescribeResponse {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    commands: Vec<PluginCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InvokeResponse {
+    ok: bool,
+    #[serde(default)]
+    result: serde_json::Value,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn plugins_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app_data_dir(app).map(|dir| dir.join("plugins"))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "exe" | "bat" | "cmd"))
+}
+
+fn read_all(mut stream: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stream.read_to_string(&mut buf);
+        buf
+    })
+}
+
+/// Runs the plugin with one request and returns its response.
+fn call(app: &AppHandle, path: &Path, request: &serde_json::Value, timeout: Duration) -> Result<serde_json::Value, String> {
+    let mut child = Command::new(path)
+        .current_dir(path.parent().unwrap_or(path))
+        .env("IDEATE_VERSION", app.package_info().version.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start plugin: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "{}", request);
+    }
+    let stdout = child.stdout.take().map(read_all);
+    let stderr = child.stderr.take().map(read_all);
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Plugin timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("Failed to wait for plugin: {}", e)),
+        }
+    }
+
+    let plugin = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+    let output = |reader: Option<thread::JoinHandle<String>>| reader.and_then(|r| r.join().ok()).unwrap_or_default();
+    for line in output(stderr).lines().filter(|l| !l.trim().is_empty()) {
+        tracing::info!(plugin = %plugin, "{}", line);
+    }
+    let stdout = output(stdout);
+    let response = stdout
+        .lines()
+        .rev()
+        .find(|l| !l.trim().is_empty())
+        .ok_or_else(|| "Plugin sent no response".to_string())?;
+    serde_json::from_str(response).map_err(|e| format!("Failed to parse plugin response: {}", e))
+}
+
+fn describe(app: &AppHandle, path: &Path) -> PluginInfo {
+    let id = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+    let described = call(app, path, &json!({ "type": "describe" }), DESCRIBE_TIMEOUT).and_then(|value| {
+        serde_json::from_value::<DescribeResponse>(value).map_err(|e| format!("Invalid describe response: {}", e))
+    });
+    match described {
+        Ok(response) => PluginInfo {
+            name: response.name.unwrap_or_else(|| id.clone()),
+            version: response.version,
+            path: path.to_string_lossy().to_string(),
+            commands: response.commands,
+            error: None,
+            id,
+        },
+        Err(e) => {
+            tracing::warn!("Plugin {}: {}", id, e);
+            PluginInfo {
+                name: id.clone(),
+                version: None,
+                path: path.to_string_lossy().to_string(),
+                commands: Vec::new(),
+                error: Some(e),
+                id,
+            }
+        }
+    }
+}
+
+/// Describes every plugin in the plugins directory and replaces the registry.
+pub fn discover(app: &AppHandle) -> Result<Vec<PluginInfo>, String> {
+    let dir = plugins_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create plugins directory: {}", e))?;
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read plugins directory: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            !path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.')) && is_executable(path)
+        })
+        .collect();
+    paths.sort();
+
+    let plugins: Vec<PluginInfo> = paths.iter().map(|path| describe(app, path)).collect();
+    if !plugins.is_empty() {
+        tracing::info!("Loaded {} plugin(s)", plugins.len());
+    }
+    *PLUGINS.lock().map_err(|e| format!("Lock error: {}", e))? = plugins.clone();
+    Ok(plugins)
+}
+
+/// Lists registered plugins and where to install new ones.
+#[tauri::command]
+pub fn list_plugins(app: AppHandle) -> Result<PluginsInfo, String> {
+    Ok(PluginsInfo {
+        directory: plugins_dir(&app)?.to_string_lossy().to_string(),
+        plugins: PLUGINS.lock().map_err(|e| format!("Lock error: {}", e))?.clone(),
+    })
+}
+
+/// Rescans the plugins directory.
+#[tauri::command]
+pub async fn reload_plugins(app: AppHandle) -> Result<PluginsInfo, String> {
+    let handle = app.clone();
+    tokio::task::spawn_blocking(move || discover(&handle))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    list_plugins(app)
+}
+
+/// Runs a command a plugin declared and returns its result.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn invoke_plugin_command(
+    app: AppHandle,
+    plugin_id: String,
+    command: String,
+    args: Option<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let plugin = PLUGINS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .iter()
+        .find(|p| p.id == plugin_id)
+        .cloned()
+        .ok_or_else(|| format!("Plugin {} not found", plugin_id))?;
+    if !plugin.commands.iter().any(|c| c.name == command) {
+        return Err(format!("Plugin {} has no command {}", plugin.name, command));
+    }
+
+    let request = json!({
+        "type": "invoke",
+        "command": command,
+        "args": args.unwrap_or_else(|| json!({})),
+    });
+    let response = tokio::task::spawn_blocking(move || {
+        call(&app, Path::new(&plugin.path), &request, INVOKE_TIMEOUT)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let response: InvokeResponse =
+        serde_json::from_value(response).map_err(|e| format!("Invalid plugin response: {}", e))?;
+    if response.ok {
+        Ok(response.result)
+    } else {
+        Err(response.error.unwrap_or_else(|| "Plugin command failed".to_string()))
+    }
+}
@@ -0,0 +1,166 @@
+//! Recovering a project whose prd.json can't be parsed.
+//!
+//! `recover_prd` moves the unreadable file to `.ideate/quarantine/`, restores
+//! the newest readable earlier version, and merges in any stories that can
+//! still be read from the damaged file, which are newer than the restored
+//! ones. Earlier versions are tried in order:
+//!
+//! 1. `.ideate/prd.json.bak`, the version replaced by the last save
+//! 2. the latest metadata history entry, when that is enabled
+//! 3. prd.json as committed at `HEAD`
+
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+
+#[cfg(feature = "gui")]
+use crate::file_lock;
+#[cfg(feature = "gui")]
+use crate::git_binary;
+#[cfg(feature = "gui")]
+use crate::meta_history::{get_metadata_history, read_metadata_snapshot};
+#[cfg(feature = "gui")]
+use crate::models::{Prd, Story};
+use crate::projects::parse_prd;
+#[cfg(feature = "gui")]
+use crate::utils::{get_ideate_dir, repair_json};
+
+const BACKUP_FILE: &str = "prd.json.bak";
+#[cfg(feature = "gui")]
+const QUARANTINE_DIR: &str = "quarantine";
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrdRecoveryReport {
+    /// Why prd.json could not be loaded.
+    pub error: String,
+    /// Where the unreadable file was moved.
+    pub quarantined_path: String,
+    /// "backup", "metadata history", or "git", when an earlier version was restored.
+    pub restored_from: Option<String>,
+    /// Stories in the restored version.
+    pub restored_stories: usize,
+    /// Stories read from the damaged file, replacing restored ones with the same ID.
+    pub extracted_story_ids: Vec<String>,
+    /// The recovered PRD, now saved as prd.json.
+    pub prd: Prd,
+}
+
+/// Keeps `content` as the backup `recover_prd` restores, if it is a readable PRD.
+pub fn keep_backup(prd_path: &Path, content: &str) {
+    if parse_prd(content).is_ok() {
+        if let Err(e) = fs::write(prd_path.with_file_name(BACKUP_FILE), content) {
+            tracing::warn!("Failed to back up prd.json: {}", e);
+        }
+    }
+}
+
+/// Stories that can still be read from damaged PRD content: every object
+/// that parses as a story on its own, wherever it sits.
+#[cfg(feature = "gui")]
+fn extract_stories(content: &str) -> Vec<Story> {
+    let (repaired, _) = repair_json(content);
+    let mut stories: Vec<Story> = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = repaired[offset..].find('{').map(|i| offset + i) {
+        let mut stream = serde_json::Deserializer::from_str(&repaired[start..]).into_iter::<Story>();
+        match stream.next() {
+            Some(Ok(story)) if !story.id.is_empty() => {
+                stories.retain(|s| s.id != story.id);
+                stories.push(story);
+                offset = start + stream.byte_offset();
+            }
+            _ => offset = start + 1,
+        }
+    }
+    stories
+}
+
+/// The newest readable earlier version of the PRD and where it came from.
+#[cfg(feature = "gui")]
+fn latest_revision(project_path: &str) -> Option<(Prd, &'static str)> {
+    let backup = || fs::read_to_string(get_ideate_dir(project_path).join(BACKUP_FILE)).ok();
+    let metadata = || {
+        let entry = get_metadata_history(project_path.to_string(), Some(1)).ok()?.into_iter().next()?;
+        read_metadata_snapshot(project_path.to_string(), entry.hash, "prd.json".to_string()).ok()
+    };
+    let committed = || {
+        let output = git_binary::command()
+            .args(["show", "HEAD:.ideate/prd.json"])
+            .current_dir(project_path)
+            .output()
+            .ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let sources: [(&'static str, &dyn Fn() -> Option<String>); 3] =
+        [("backup", &backup), ("metadata history", &metadata), ("git", &committed)];
+    sources
+        .iter()
+        .find_map(|(source, read)| Some((parse_prd(&read()?).ok()?, *source)))
+}
+
+/// Quarantines an unreadable prd.json and replaces it with what can be
+/// recovered. Fails if prd.json is missing or parses fine.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn recover_prd(project_path: String) -> Result<PrdRecoveryReport, String> {
+    let ideate_dir = get_ideate_dir(&project_path);
+    let prd_path = ideate_dir.join("prd.json");
+    let content = fs::read_to_string(&prd_path).map_err(|e| format!("Failed to read prd.json: {}", e))?;
+    let error = match parse_prd(&content) {
+        Ok(_) => return Err("prd.json is readable; there is nothing to recover".to_string()),
+        Err(e) => e,
+    };
+
+    let quarantine_dir = ideate_dir.join(QUARANTINE_DIR);
+    fs::create_dir_all(&quarantine_dir).map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+    let quarantined_path =
+        quarantine_dir.join(format!("prd-{}.json", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    fs::rename(&prd_path, &quarantined_path).map_err(|e| format!("Failed to quarantine prd.json: {}", e))?;
+
+    let extracted = extract_stories(&content);
+    let (mut prd, restored_from) = match latest_revision(&project_path) {
+        Some((prd, source)) => (prd, Some(source.to_string())),
+        None => (
+            Prd {
+                project: None,
+                branch_name: None,
+                description: None,
+                user_stories: Vec::new(),
+            },
+            None,
+        ),
+    };
+    let restored_stories = prd.user_stories.len();
+    for story in &extracted {
+        match prd.user_stories.iter_mut().find(|s| s.id == story.id) {
+            Some(existing) => *existing = story.clone(),
+            None => prd.user_stories.push(story.clone()),
+        }
+    }
+
+    let prd_json = serde_json::to_string_pretty(&prd).map_err(|e| format!("Failed to serialize PRD: {}", e))?;
+    file_lock::write_checked(&prd_path, &prd_json)?;
+
+    let report = PrdRecoveryReport {
+        error,
+        quarantined_path: quarantined_path.to_string_lossy().to_string(),
+        restored_from,
+        restored_stories,
+        extracted_story_ids: extracted.into_iter().map(|s| s.id).collect(),
+        prd,
+    };
+    tracing::warn!(
+        "Recovered prd.json in {}: quarantined to {}, restored {} stories from {}, extracted {}",
+        project_path,
+        report.quarantined_path,
+        report.restored_stories,
+        report.restored_from.as_deref().unwrap_or("nothing"),
+        report.extracted_story_ids.len()
+    );
+    Ok(report)
+}
@@ -1,18 +1,85 @@
 //! User preferences management.
+//!
+//! preferences.json is watched, so edits made outside the app (by hand, or
+//! by a sync tool) are validated and applied like ones saved from the UI.
+//! Either way every window is told with `preferences-changed`, which lists
+//! the fields that changed.
 
+#[cfg(feature = "gui")]
 use std::fs;
+use std::path::Path;
+#[cfg(feature = "gui")]
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+#[cfg(feature = "gui")]
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+#[cfg(feature = "gui")]
+use std::time::Duration;
 
+use notify::RecommendedWatcher;
+#[cfg(feature = "gui")]
+use notify::{RecursiveMode, Watcher};
+#[cfg(feature = "gui")]
+use serde::Serialize;
+#[cfg(feature = "gui")]
+use serde_json::Value;
+#[cfg(feature = "gui")]
+use tauri::{AppHandle, Emitter};
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
 use crate::macos::set_app_icon;
+use crate::migrations;
 use crate::models::Preferences;
+use crate::utils::headless_app_data_dir;
+
+/// Quiet time after a change to preferences.json before it is reloaded, so
+/// an editor's save is read once it's complete.
+#[cfg(feature = "gui")]
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+lazy_static::lazy_static! {
+    /// Preferences as last applied, to tell real changes from our own writes.
+    static ref APPLIED: Mutex<Option<Preferences>> = Mutex::new(None);
+    static ref WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+}
+
+/// A preference whose value changed.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferenceChange {
+    pub key: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+/// Payload of `preferences-changed`.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesChangedEvent {
+    /// "app" when saved through `save_preferences`, "file" when
+    /// preferences.json was edited outside the app.
+    pub source: String,
+    pub changes: Vec<PreferenceChange>,
+    pub preferences: Preferences,
+}
+
+/// Payload of `preferences-invalid`, emitted when an outside edit leaves
+/// preferences.json unreadable; the previous preferences stay in effect.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesInvalidEvent {
+    pub error: String,
+}
 
 /// Gets the path to the preferences file in the app data directory.
+#[cfg(feature = "gui")]
 pub fn get_preferences_file_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir = app_data_dir(app)?;
     
     if !app_data_dir.exists() {
         fs::create_dir_all(&app_data_dir)
@@ -23,52 +90,216 @@ pub fn get_preferences_file_path(app: &AppHandle) -> Result<PathBuf, String> {
 }
 
 /// Loads preferences from disk (internal function, not a command).
+#[cfg(feature = "gui")]
 pub fn load_preferences_internal(app: &AppHandle) -> Result<Preferences, String> {
-    let prefs_path = get_preferences_file_path(app)?;
-    
-    if !prefs_path.exists() {
-        return Ok(Preferences::default());
-    }
-    
-    let content = fs::read_to_string(&prefs_path)
-        .map_err(|e| format!("Failed to read preferences.json: {}", e))?;
-    
-    let prefs: Preferences = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse preferences.json: {}", e))?;
-    
-    Ok(prefs)
+    read_preferences_file(&get_preferences_file_path(app)?)
+}
+
+/// Loads preferences without an `AppHandle`, for the headless CLI.
+pub fn load_preferences_headless() -> Result<Preferences, String> {
+    read_preferences_file(&headless_app_data_dir()?.join("preferences.json"))
+}
+
+fn read_preferences_file(prefs_path: &Path) -> Result<Preferences, String> {
+    Ok(migrations::PREFERENCES.load(prefs_path)?.unwrap_or_default())
 }
 
 /// Loads user preferences from the app data directory.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn load_preferences(app: AppHandle) -> Result<Preferences, String> {
     load_preferences_internal(&app)
 }
 
 /// Saves user preferences to the app data directory.
+///
+/// Executables can be removed from `approvedExecutables` here but not added;
+/// only `allowlist::approve_executable` adds them, after asking the user.
+#[cfg(feature = "gui")]
 #[tauri::command]
-pub fn save_preferences(app: AppHandle, preferences: Preferences) -> Result<(), String> {
-    let prefs_path = get_preferences_file_path(&app)?;
+pub fn save_preferences(app: AppHandle, mut preferences: Preferences) -> Result<(), String> {
+    let approved = load_preferences_internal(&app)
+        .map(|prefs| prefs.approved_executables)
+        .unwrap_or_default();
+    preferences.approved_executables.retain(|e| approved.contains(e));
+    write_preferences(&app, preferences)
+}
+
+/// Writes and applies preferences as given.
+#[cfg(feature = "gui")]
+pub(crate) fn write_preferences(app: &AppHandle, preferences: Preferences) -> Result<(), String> {
+    crate::redact::validate(&preferences)?;
+    let prefs_path = get_preferences_file_path(app)?;
+    let previous = load_preferences_internal(app).ok();
     
-    let prefs_json = serde_json::to_string_pretty(&preferences)
-        .map_err(|e| format!("Failed to serialize preferences: {}", e))?;
+    let prefs_json = migrations::PREFERENCES.to_json(&preferences)?;
     
     fs::write(&prefs_path, prefs_json)
         .map_err(|e| format!("Failed to write preferences.json: {}", e))?;
+    // Before anything slow, so the watcher doesn't take this for an outside edit
+    *APPLIED.lock().unwrap_or_else(|e| e.into_inner()) = Some(preferences.clone());
     
-    set_app_icon(&preferences.app_icon);
+    apply(app, previous.as_ref(), &preferences)?;
+    publish(app, "app", previous.as_ref(), &preferences);
     
     Ok(())
 }
 
+/// Puts preferences into effect across the backend.
+#[cfg(feature = "gui")]
+fn apply(app: &AppHandle, previous: Option<&Preferences>, preferences: &Preferences) -> Result<(), String> {
+    set_app_icon(&preferences.app_icon);
+    crate::control_api::apply_preferences(app, preferences);
+    crate::lan_sync::apply_preferences(app, preferences);
+    crate::redact::configure(preferences)?;
+    crate::terminal_audit::configure(preferences);
+    crate::process::configure(preferences);
+    crate::git_binary::configure(preferences);
+    crate::orchestrator::apply_preferences(preferences);
+    if let Some(previous) = previous {
+        if let Err(e) =
+            crate::prompt_library::record_overrides(app, &previous.prompt_overrides, &preferences.prompt_overrides)
+        {
+            tracing::warn!("Prompt library: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// The top-level fields that differ between two sets of preferences.
+#[cfg(feature = "gui")]
+fn diff(previous: Option<&Preferences>, current: &Preferences) -> Vec<PreferenceChange> {
+    let as_map = |prefs: Option<&Preferences>| match prefs.and_then(|p| serde_json::to_value(p).ok()) {
+        Some(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let old = as_map(previous);
+    let new = as_map(Some(current));
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| PreferenceChange {
+            key: key.clone(),
+            old_value: old.get(key).cloned().unwrap_or(Value::Null),
+            new_value: new.get(key).cloned().unwrap_or(Value::Null),
+        })
+        .collect()
+}
+
+/// Records `preferences` as applied and tells every window what changed.
+#[cfg(feature = "gui")]
+fn publish(app: &AppHandle, source: &str, previous: Option<&Preferences>, preferences: &Preferences) {
+    *APPLIED.lock().unwrap_or_else(|e| e.into_inner()) = Some(preferences.clone());
+    let changes = diff(previous, preferences);
+    if changes.is_empty() {
+        return;
+    }
+    let _ = app.emit(
+        "preferences-changed",
+        PreferencesChangedEvent {
+            source: source.to_string(),
+            changes,
+            preferences: preferences.clone(),
+        },
+    );
+}
+
+/// Applies preferences.json after an outside edit, unless it is what was
+/// last applied (as after our own saves).
+#[cfg(feature = "gui")]
+fn reload(app: &AppHandle) {
+    // Removed, or mid-replace; defaults aren't applied for a missing file
+    if !get_preferences_file_path(app).is_ok_and(|path| path.exists()) {
+        return;
+    }
+    let loaded = load_preferences_internal(app).and_then(|prefs| {
+        crate::redact::validate(&prefs)?;
+        Ok(prefs)
+    });
+    let preferences = match loaded {
+        Ok(preferences) => preferences,
+        Err(error) => {
+            tracing::warn!("Ignoring edited preferences.json: {}", error);
+            let _ = app.emit("preferences-invalid", PreferencesInvalidEvent { error });
+            return;
+        }
+    };
+    let previous = APPLIED.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if diff(previous.as_ref(), &preferences).is_empty() {
+        return;
+    }
+    tracing::info!("preferences.json changed on disk; applying it");
+    if let Err(e) = apply(app, previous.as_ref(), &preferences) {
+        tracing::warn!("Failed to apply edited preferences: {}", e);
+    }
+    publish(app, "file", previous.as_ref(), &preferences);
+}
+
+/// Watches preferences.json for outside edits. Called once at startup.
+#[cfg(feature = "gui")]
+pub fn start_watcher(app: AppHandle) {
+    let prefs_path = match get_preferences_file_path(&app) {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::warn!("Preferences watcher unavailable: {}", e);
+            return;
+        }
+    };
+    *APPLIED.lock().unwrap_or_else(|e| e.into_inner()) = load_preferences_internal(&app).ok();
+
+    let (tx, rx) = mpsc::channel();
+    let file_name = prefs_path.file_name().map(|name| name.to_os_string());
+    let mut watcher = match notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        // Editors often save by replacing the file, so the directory is watched
+        let touched = result.map_or(true, |event| {
+            event.paths.iter().any(|path| path.file_name() == file_name.as_deref())
+        });
+        if touched {
+            let _ = tx.send(());
+        }
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Preferences watcher unavailable: {}", e);
+            return;
+        }
+    };
+    let Some(dir) = prefs_path.parent() else { return };
+    if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+        tracing::warn!("Failed to watch {}: {}", dir.display(), e);
+        return;
+    }
+    if let Ok(mut slot) = WATCHER.lock() {
+        *slot = Some(watcher);
+    }
+
+    std::thread::spawn(move || {
+        while rx.recv().is_ok() {
+            // Wait for the writes to settle
+            loop {
+                match rx.recv_timeout(RELOAD_DEBOUNCE) {
+                    Ok(()) => continue,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            reload(&app);
+        }
+    });
+}
+
 /// Sets the application icon variant.
-#[tauri::command]
+#[cfg(feature = "gui")]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn set_app_icon_command(icon_variant: String) -> Result<(), String> {
     set_app_icon(&icon_variant);
     Ok(())
 }
 
 /// Opens the Full Disk Access settings panel on macOS.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn open_full_disk_access_settings() -> Result<(), String> {
     #[cfg(target_os = "macos")]
@@ -0,0 +1,412 @@
+//! Reverse proxy in front of a project's dev server.
+//!
+//! The preview panel loads the app from a localhost port derived from the
+//! project ID, so its URL survives dev server restarts and port changes. The
+//! upstream port is read from the dev server's output, either the managed dev
+//! server's or a tracked process's `agent-output`. HTML responses get a small
+//! script that reloads the page when the orchestrator merges a story.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Body;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::Serialize;
+use tauri::{AppHandle, EventId, Listener};
+use tokio::sync::{broadcast, oneshot};
+
+use crate::dev_server;
+
+/// Proxy ports are picked from this range by hashing the project ID.
+const PORT_RANGE_START: u16 = 41000;
+const PORT_RANGE_SIZE: u32 = 1000;
+
+/// Largest request body forwarded to the dev server.
+const MAX_REQUEST_BODY: usize = 32 * 1024 * 1024;
+
+const RELOAD_PATH: &str = "/__ideate/reload";
+
+const RELOAD_SCRIPT: &str = r#"<script>(function(){function connect(){var ws=new WebSocket((location.protocol==="https:"?"wss://":"ws://")+location.host+"/__ideate/reload");ws.onmessage=function(){location.reload()};ws.onclose=function(){setTimeout(connect,2000)}}connect()})();</script>"#;
+
+lazy_static::lazy_static! {
+    static ref PROXIES: Mutex<HashMap<String, ProxyHandle>> = Mutex::new(HashMap::new());
+}
+
+struct ProxyHandle {
+    app: AppHandle,
+    port: u16,
+    state: ProxyState,
+    /// Process whose output is watched for the upstream port.
+    watched: Option<(String, EventId)>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl ProxyHandle {
+    fn to_info(&self, project_id: &str) -> PreviewProxyInfo {
+        PreviewProxyInfo {
+            project_id: project_id.to_string(),
+            port: self.port,
+            url: format!("http://localhost:{}", self.port),
+            target_port: self.state.upstream_port(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ProxyState {
+    project_path: String,
+    /// Port last seen in the watched process's output.
+    target: Arc<Mutex<Option<u16>>>,
+    reloads: broadcast::Sender<()>,
+    client: reqwest::Client,
+}
+
+impl ProxyState {
+    fn upstream_port(&self) -> Option<u16> {
+        self.target
+            .lock()
+            .ok()
+            .and_then(|target| *target)
+            .or_else(|| dev_server::port_for(&self.project_path))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewProxyInfo {
+    pub project_id: String,
+    pub port: u16,
+    pub url: String,
+    /// Dev server port requests are forwarded to, once detected.
+    pub target_port: Option<u16>,
+}
+
+fn preferred_port(project_id: &str) -> u16 {
+    let hash = project_id
+        .bytes()
+        .fold(0u32, |hash, b| hash.wrapping_mul(31).wrapping_add(u32::from(b)));
+    PORT_RANGE_START + (hash % PORT_RANGE_SIZE) as u16
+}
+
+/// Follows a tracked process's output, taking the latest port it reports as
+/// the upstream so "port in use, trying another" moves the proxy along.
+fn watch_process(app: &AppHandle, process_id: String, target: Arc<Mutex<Option<u16>>>) -> EventId {
+    app.listen_any("agent-output", move |event| {
+        let payload = event.payload();
+        if !payload.contains(&process_id) {
+            return;
+        }
+        let Ok(output) = serde_json::from_str::<serde_json::Value>(payload) else {
+            return;
+        };
+        if output["processId"].as_str() != Some(process_id.as_str()) {
+            return;
+        }
+        let port = output["lines"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|line| line["content"].as_str())
+            .filter_map(dev_server::detect_port)
+            .next_back();
+        if let (Some(port), Ok(mut target)) = (port, target.lock()) {
+            *target = Some(port);
+        }
+    })
+}
+
+/// Inserts the reload script before `</body>`, or at the end of the document.
+fn inject_reload_script(html: &[u8]) -> Vec<u8> {
+    let html = String::from_utf8_lossy(html);
+    let lower = html.to_ascii_lowercase();
+    let at = lower
+        .rfind("</body>")
+        .or_else(|| lower.rfind("</html>"))
+        .unwrap_or(html.len());
+    let mut out = String::with_capacity(html.len() + RELOAD_SCRIPT.len());
+    out.push_str(&html[..at]);
+    out.push_str(RELOAD_SCRIPT);
+    out.push_str(&html[at..]);
+    out.into_bytes()
+}
+
+fn waiting_page() -> Response {
+    let html = format!(
+        "<!doctype html><html><head><meta http-equiv=\"refresh\" content=\"2\"></head>\
+         <body style=\"font-family:sans-serif;color:#888;padding:2em\">Waiting for the dev server to start…{}</body></html>",
+        RELOAD_SCRIPT
+    );
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        html,
+    )
+        .into_response()
+}
+
+async fn reload_socket(State(state): State<ProxyState>, ws: WebSocketUpgrade) -> Response {
+    let reloads = state.reloads.subscribe();
+    ws.on_upgrade(move |socket| push_reloads(socket, reloads))
+}
+
+async fn push_reloads(mut socket: WebSocket, mut reloads: broadcast::Receiver<()>) {
+    loop {
+        tokio::select! {
+            received = reloads.recv() => match received {
+                Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                    if socket.send(Message::Text("reload".into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => {
+                if !matches!(message, Some(Ok(_))) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn forward(State(state): State<ProxyState>, req: Request) -> Response {
+    let Some(port) = state.upstream_port() else {
+        return waiting_page();
+    };
+
+    let (parts, body) = req.into_parts();
+    if parts.headers.contains_key(header::UPGRADE) {
+        // HMR sockets aren't proxied; Vite and webpack fall back to
+        // connecting to the dev server's own port.
+        return StatusCode::NOT_IMPLEMENTED.into_response();
+    }
+    let body = match axum::body::to_bytes(body, MAX_REQUEST_BODY).await {
+        Ok(body) => body,
+        Err(e) => return (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()).into_response(),
+    };
+
+    let mut headers = parts.headers;
+    // Ask for an uncompressed body so HTML can be rewritten
+    for name in [header::HOST, header::ACCEPT_ENCODING, header::CONNECTION] {
+        headers.remove(name);
+    }
+    let path = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let upstream = format!("http://localhost:{}", port);
+
+    let response = match state
+        .client
+        .request(parts.method, format!("{}{}", upstream, path))
+        .headers(headers)
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            return (
+                StatusCode::BAD_GATEWAY,
+                format!("Dev server on port {} did not respond: {}", port, e),
+            )
+                .into_response()
+        }
+    };
+
+    let status = response.status();
+    let mut headers = response.headers().clone();
+    let is_html = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            return (StatusCode::BAD_GATEWAY, format!("Failed to read dev server response: {}", e))
+                .into_response()
+        }
+    };
+
+    for name in [header::CONTENT_LENGTH, header::TRANSFER_ENCODING, header::CONNECTION] {
+        headers.remove(name);
+    }
+    // Keep redirects on the proxy rather than the dev server's own port
+    let location = headers
+        .get(header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix(&upstream))
+        .and_then(|rest| HeaderValue::from_str(rest).ok());
+    if let Some(location) = location {
+        headers.insert(header::LOCATION, location);
+    }
+
+    let body = if is_html { Body::from(inject_reload_script(&body)) } else { Body::from(body) };
+    let mut out = Response::new(body);
+    *out.status_mut() = status;
+    *out.headers_mut() = headers;
+    out
+}
+
+/// Starts the project's preview proxy, or updates the running one.
+///
+/// `process_id` names a tracked dev server process to watch for its port and
+/// `target_port` seeds the upstream when the port was already printed.
+/// Without either, the project's managed dev server is followed.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_preview_proxy(
+    app: AppHandle,
+    project_id: String,
+    project_path: String,
+    process_id: Option<String>,
+    target_port: Option<u16>,
+) -> Result<PreviewProxyInfo, String> {
+    {
+        let mut proxies = PROXIES.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(handle) = proxies.get_mut(&project_id) {
+            let watching = handle.watched.as_ref().map(|(id, _)| id.clone());
+            if process_id.is_some() && process_id != watching {
+                if let Some((_, event_id)) = handle.watched.take() {
+                    handle.app.unlisten(event_id);
+                }
+                if let Ok(mut target) = handle.state.target.lock() {
+                    *target = None;
+                }
+            }
+            if let Some(port) = target_port {
+                if let Ok(mut target) = handle.state.target.lock() {
+                    *target = Some(port);
+                }
+            }
+            if let (Some(id), None) = (&process_id, &handle.watched) {
+                let event_id = watch_process(&app, id.clone(), handle.state.target.clone());
+                handle.watched = Some((id.clone(), event_id));
+            }
+            return Ok(handle.to_info(&project_id));
+        }
+    }
+
+    let listener = match tokio::net::TcpListener::bind(("127.0.0.1", preferred_port(&project_id))).await {
+        Ok(listener) => listener,
+        // Taken by another app; any port will do for this session
+        Err(_) => tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| format!("Failed to bind to port: {}", e))?,
+    };
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to get local address: {}", e))?
+        .port();
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let (reloads, _) = broadcast::channel(8);
+    let state = ProxyState {
+        project_path,
+        target: Arc::new(Mutex::new(target_port)),
+        reloads,
+        client,
+    };
+    let watched = process_id.map(|id| {
+        let event_id = watch_process(&app, id.clone(), state.target.clone());
+        (id, event_id)
+    });
+
+    let router = Router::new()
+        .route(RELOAD_PATH, get(reload_socket))
+        .fallback(forward)
+        .with_state(state.clone());
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let project_id_clone = project_id.clone();
+    tokio::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        if let Err(e) = server.await {
+            tracing::error!("Preview proxy for {} error: {}", project_id_clone, e);
+        }
+    });
+    crate::telemetry::record(&app, "feature.preview-proxy");
+
+    let handle = ProxyHandle {
+        app,
+        port,
+        state,
+        watched,
+        shutdown_tx: Some(shutdown_tx),
+    };
+    let info = handle.to_info(&project_id);
+    PROXIES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .insert(project_id, handle);
+
+    Ok(info)
+}
+
+fn shutdown(mut handle: ProxyHandle) {
+    if let Some((_, event_id)) = handle.watched.take() {
+        handle.app.unlisten(event_id);
+    }
+    if let Some(tx) = handle.shutdown_tx.take() {
+        let _ = tx.send(());
+    }
+}
+
+/// Stops the project's preview proxy.
+#[tauri::command(rename_all = "camelCase")]
+pub fn stop_preview_proxy(project_id: String) -> Result<(), String> {
+    let handle = PROXIES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .remove(&project_id);
+    if let Some(handle) = handle {
+        shutdown(handle);
+    }
+    Ok(())
+}
+
+/// Returns the project's preview proxy, if one is running.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_preview_proxy_info(project_id: String) -> Result<Option<PreviewProxyInfo>, String> {
+    let proxies = PROXIES.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(proxies.get(&project_id).map(|h| h.to_info(&project_id)))
+}
+
+/// Returns the port of the project's preview proxy once its dev server port is known.
+pub fn port_for(project_id: &str) -> Option<u16> {
+    let proxies = PROXIES.lock().ok()?;
+    let handle = proxies.get(project_id)?;
+    handle.state.upstream_port()?;
+    Some(handle.port)
+}
+
+/// Returns the URL of the project's preview proxy once its dev server port is known.
+pub fn url_for(project_id: &str) -> Option<String> {
+    port_for(project_id).map(|port| format!("http://localhost:{}", port))
+}
+
+/// Reloads every page open through the project's preview proxy.
+pub fn reload(project_id: &str) {
+    if let Ok(proxies) = PROXIES.lock() {
+        if let Some(handle) = proxies.get(project_id) {
+            let _ = handle.state.reloads.send(());
+        }
+    }
+}
+
+/// Stops all preview proxies. Called on app shutdown.
+pub fn stop_all_proxies() {
+    let handles: Vec<ProxyHandle> = match PROXIES.lock() {
+        Ok(mut proxies) => proxies.drain().map(|(_, h)| h).collect(),
+        Err(_) => return,
+    };
+    for handle in handles {
+        shutdown(handle);
+    }
+}
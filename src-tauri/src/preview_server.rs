@@ -75,7 +75,7 @@ pub async fn start_preview_server(
             });
         
         if let Err(e) = server.await {
-            eprintln!("Preview server {} error: {}", server_id_clone, e);
+            tracing::error!("Preview server {} error: {}", server_id_clone, e);
         }
     });
 
@@ -121,7 +121,7 @@ pub fn stop_all_servers() {
             if let Some(tx) = handle.shutdown_tx.take() {
                 let _ = tx.send(());
             }
-            println!("Stopped preview server: {}", id);
+            tracing::info!("Stopped preview server: {}", id);
         }
     }
 }
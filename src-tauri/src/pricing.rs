@@ -0,0 +1,201 @@
+//! Editable model pricing and cost recomputation.
+//!
+//! Prices live in `pricing.json` in the app data directory, keyed by model
+//! name, in USD per million tokens. Until the user edits them, the built-in
+//! defaults below apply (they mirror `src/utils/agentPricing.ts`). When prices
+//! change, `recompute_costs` rebuilds the `cost` of a project's recorded runs
+//! from their token counts.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::file_lock;
+use crate::models::CostHistory;
+use crate::utils::get_ideate_dir;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    #[serde(default)]
+    pub cache_read_per_million: Option<f64>,
+    #[serde(default)]
+    pub cache_write_per_million: Option<f64>,
+}
+
+pub type Pricing = BTreeMap<String, ModelPrice>;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeSummary {
+    /// Entries whose cost changed.
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Entries without a model, token counts, or a matching price.
+    pub skipped: usize,
+}
+
+fn default_pricing() -> Pricing {
+    let price = |input: f64, output: f64, cache_read: Option<f64>| ModelPrice {
+        input_per_million: input,
+        output_per_million: output,
+        cache_read_per_million: cache_read,
+        cache_write_per_million: None,
+    };
+    [
+        ("claude-3-5-sonnet", price(3.0, 15.0, Some(0.3))),
+        ("claude-sonnet-4", price(3.0, 15.0, Some(0.3))),
+        ("claude-3-5-haiku", price(0.8, 4.0, Some(0.08))),
+        ("claude-3-opus", price(15.0, 75.0, Some(1.5))),
+        ("claude-opus-4", price(15.0, 75.0, Some(1.5))),
+        ("claude-opus-4-5", price(15.0, 75.0, Some(1.5))),
+        ("gpt-4o", price(2.5, 10.0, Some(1.25))),
+        ("gpt-4o-mini", price(0.15, 0.6, Some(0.075))),
+        ("gpt-4-turbo", price(10.0, 30.0, None)),
+        ("o1", price(15.0, 60.0, Some(7.5))),
+        ("o1-mini", price(1.1, 4.4, Some(0.55))),
+        ("o3-mini", price(1.1, 4.4, Some(0.55))),
+    ]
+    .into_iter()
+    .map(|(model, price)| (model.to_string(), price))
+    .collect()
+}
+
+fn get_pricing_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_data_dir(app)?;
+
+    if !app_data_dir.exists() {
+        fs::create_dir_all(&app_data_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    Ok(app_data_dir.join("pricing.json"))
+}
+
+fn parse_pricing(content: Option<&str>) -> Result<Pricing, String> {
+    match content {
+        Some(content) => serde_json::from_str(content).map_err(|e| format!("Failed to parse pricing.json: {}", e)),
+        None => Ok(default_pricing()),
+    }
+}
+
+/// Applies a change to pricing.json under its lock, starting from the defaults.
+fn update_pricing(app: &AppHandle, apply: impl Fn(&mut Pricing)) -> Result<Pricing, String> {
+    let path = get_pricing_file_path(app)?;
+    file_lock::update(&path, |content| {
+        let mut pricing = parse_pricing(content)?;
+        apply(&mut pricing);
+        let json = serde_json::to_string_pretty(&pricing)
+            .map_err(|e| format!("Failed to serialize pricing: {}", e))?;
+        Ok((json, pricing))
+    })
+}
+
+/// The price for `model`: an exact match, or else the longest priced name the
+/// model starts with, so `claude-sonnet-4-20250514` uses `claude-sonnet-4`.
+pub fn price_for<'a>(pricing: &'a Pricing, model: &str) -> Option<&'a ModelPrice> {
+    let model = model.trim().to_lowercase();
+    pricing.get(&model).or_else(|| {
+        pricing
+            .iter()
+            .filter(|(name, _)| model.starts_with(&name.to_lowercase()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, price)| price)
+    })
+}
+
+/// Loads model prices, falling back to the built-in defaults.
+#[tauri::command]
+pub fn load_pricing(app: AppHandle) -> Result<Pricing, String> {
+    let path = get_pricing_file_path(&app)?;
+    parse_pricing(fs::read_to_string(&path).ok().as_deref())
+}
+
+/// Adds or replaces the price of one model.
+#[tauri::command]
+pub fn set_model_price(app: AppHandle, model: String, price: ModelPrice) -> Result<Pricing, String> {
+    let model = model.trim().to_lowercase();
+    if model.is_empty() {
+        return Err("Failed to save price: model name is empty".to_string());
+    }
+    let rates = [
+        Some(price.input_per_million),
+        Some(price.output_per_million),
+        price.cache_read_per_million,
+        price.cache_write_per_million,
+    ];
+    if rates.into_iter().flatten().any(|p| !p.is_finite() || p < 0.0) {
+        return Err("Failed to save price: prices must be zero or more".to_string());
+    }
+    update_pricing(&app, |pricing| {
+        pricing.insert(model.clone(), price.clone());
+    })
+}
+
+/// Removes the price of one model.
+#[tauri::command]
+pub fn delete_model_price(app: AppHandle, model: String) -> Result<Pricing, String> {
+    update_pricing(&app, |pricing| {
+        pricing.remove(&model);
+    })
+}
+
+/// Restores the built-in prices.
+#[tauri::command]
+pub fn reset_pricing(app: AppHandle) -> Result<Pricing, String> {
+    update_pricing(&app, |pricing| *pricing = default_pricing())
+}
+
+/// Rebuilds the `cost` of each entry in a project's costs.json from its token
+/// counts and current prices. Entries that can't be priced keep their cost.
+#[tauri::command(rename_all = "camelCase")]
+pub fn recompute_costs(app: AppHandle, project_path: String) -> Result<RecomputeSummary, String> {
+    let pricing = load_pricing(app)?;
+    let cost_path = get_ideate_dir(&project_path).join("costs.json");
+    if !cost_path.exists() {
+        return Ok(RecomputeSummary::default());
+    }
+
+    let summary = file_lock::update(&cost_path, |content| {
+        let mut history: CostHistory = serde_json::from_str(content.unwrap_or("{\"entries\":[]}"))
+            .map_err(|e| format!("Failed to parse costs.json: {}", e))?;
+        let mut summary = RecomputeSummary::default();
+
+        for entry in &mut history.entries {
+            let has_tokens = entry.input_tokens.is_some() || entry.output_tokens.is_some();
+            let price = entry.model.as_deref().and_then(|model| price_for(&pricing, model));
+            let Some(price) = price.filter(|_| has_tokens) else {
+                summary.skipped += 1;
+                continue;
+            };
+            let cost = (entry.input_tokens.unwrap_or(0) as f64 * price.input_per_million
+                + entry.output_tokens.unwrap_or(0) as f64 * price.output_per_million)
+                / 1_000_000.0;
+            if entry.cost.is_some_and(|c| (c - cost).abs() < 1e-9) {
+                summary.unchanged += 1;
+            } else {
+                entry.cost = Some(cost);
+                summary.updated += 1;
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&history)
+            .map_err(|e| format!("Failed to serialize cost history: {}", e))?;
+        Ok((json, summary))
+    })?;
+
+    tracing::info!(
+        "Recomputed costs for {}: {} updated, {} unchanged, {} skipped",
+        project_path,
+        summary.updated,
+        summary.unchanged,
+        summary.skipped
+    );
+    Ok(summary)
+}
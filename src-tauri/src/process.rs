@@ -2,30 +2,121 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::io::{BufRead, BufReader, Write};
-use std::process::{Child, Command, Stdio};
+use std::io::Write;
+#[cfg(feature = "gui")]
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus};
+#[cfg(feature = "gui")]
+use std::process::Stdio;
+use std::sync::mpsc::{self};
+#[cfg(feature = "gui")]
+use std::sync::mpsc::RecvTimeoutError;
+#[cfg(feature = "gui")]
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
+#[cfg(feature = "gui")]
+use std::sync::Arc;
+#[cfg(feature = "gui")]
 use std::thread;
-use std::time::Duration;
+#[cfg(feature = "gui")]
+use std::time::{Duration, Instant};
 
-use tauri::{AppHandle, Emitter, Manager};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+#[cfg(feature = "gui")]
 use uuid::Uuid;
 
-use crate::models::{
-    AgentExitEvent, AgentOutputEvent, KillAgentResult, ProcessHistory, ProcessHistoryEntry,
-    ProcessLogEntry, SpawnAgentResult, WaitAgentResult,
-};
+#[cfg(feature = "gui")]
+use crate::allowlist;
+#[cfg(feature = "gui")]
+use crate::cost_capture::{self, CostCapture};
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
+use crate::event_bus;
+#[cfg(feature = "gui")]
+use crate::log_compression;
+#[cfg(feature = "gui")]
+use crate::progress::{self, ProgressTracker};
+#[cfg(feature = "gui")]
+use crate::project_env;
+use crate::models::{ProcessHistory, ProcessHistoryEntry, ProcessPriority, ProcessLogEntry, TerminationReason};
+#[cfg(feature = "gui")]
+use crate::models::{AgentExitEvent, AgentOutputEvent, AgentOutputLine, KillAgentResult, KillPath, LogChunk, LogFileInfo, OutputMode, Preferences, SpawnAgentResult, WaitAgentResult};
+use crate::redact;
+#[cfg(feature = "gui")]
+use crate::terminal;
 
 lazy_static::lazy_static! {
     pub static ref PROCESSES: Mutex<HashMap<String, Child>> = Mutex::new(HashMap::new());
+    /// How processes stopped by `kill_agent_blocking` ended, kept for
+    /// `wait_for_process` since they are gone from `PROCESSES` by then.
+    static ref TERMINATIONS: Mutex<HashMap<String, (TerminationReason, Option<i32>)>> = Mutex::new(HashMap::new());
+}
+
+/// Time between SIGTERM and SIGKILL when a kill doesn't give its own; from
+/// `Preferences::kill_grace_period_ms`.
+#[cfg(feature = "gui")]
+static KILL_GRACE_PERIOD_MS: AtomicU64 = AtomicU64::new(5000);
+
+#[cfg(feature = "gui")]
+pub fn configure(prefs: &Preferences) {
+    KILL_GRACE_PERIOD_MS.store(prefs.kill_grace_period_ms, Ordering::Relaxed);
+}
+
+#[cfg(feature = "gui")]
+fn default_grace_period() -> Duration {
+    Duration::from_millis(KILL_GRACE_PERIOD_MS.load(Ordering::Relaxed))
+}
+
+/// Signal that terminated a process. Always `None` on Windows.
+pub fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = status;
+        None
+    }
+}
+
+/// Why a process that nobody killed stopped: a signal means it crashed or
+/// was killed from outside, e.g. by the OOM killer.
+#[cfg(feature = "gui")]
+pub fn natural_termination(status: &ExitStatus) -> TerminationReason {
+    if exit_signal(status).is_some() {
+        TerminationReason::Signal
+    } else {
+        TerminationReason::Exited
+    }
+}
+
+#[cfg(feature = "gui")]
+fn record_termination(process_id: &str, reason: TerminationReason, status: Option<ExitStatus>) {
+    if let Ok(mut terminations) = TERMINATIONS.lock() {
+        terminations.insert(process_id.to_string(), (reason, status.as_ref().and_then(exit_signal)));
+    }
+}
+
+/// Reason and signal recorded when `process_id` was killed, if it was.
+#[cfg(feature = "gui")]
+pub fn recorded_termination(process_id: &str) -> Option<(TerminationReason, Option<i32>)> {
+    TERMINATIONS.lock().ok()?.get(process_id).copied()
 }
 
 /// Kills all spawned processes. Called on app shutdown.
+#[cfg(feature = "gui")]
 pub fn kill_all_processes() {
     let mut processes = match PROCESSES.lock() {
         Ok(p) => p,
         Err(e) => {
-            eprintln!("Failed to lock processes for cleanup: {}", e);
+            tracing::error!("Failed to lock processes for cleanup: {}", e);
             return;
         }
     };
@@ -37,7 +128,7 @@ pub fn kill_all_processes() {
         return;
     }
 
-    println!("Cleaning up {} spawned process(es)...", count);
+    tracing::info!("Cleaning up {} spawned process(es)...", count);
 
     for process_id in process_ids {
         if let Some(child) = processes.get_mut(&process_id) {
@@ -84,22 +175,136 @@ pub fn kill_all_processes() {
     }
 
     processes.clear();
-    println!("All processes cleaned up.");
+    tracing::info!("All processes cleaned up.");
 }
 
-/// Spawns an agent process and returns its ID.
-/// This is async to avoid blocking the UI thread during process startup.
-#[tauri::command(rename_all = "camelCase")]
-pub async fn spawn_agent(
+/// Terminates a child spawned in its own process group.
+/// Sends SIGTERM to the group, escalating to SIGKILL after `timeout`.
+/// Returns the exit code if one was reported.
+#[cfg(feature = "gui")]
+pub fn terminate_child(child: &mut Child, timeout: Duration) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        let pgid = -(child.id() as i32);
+
+        unsafe {
+            libc::kill(pgid, libc::SIGTERM);
+        }
+
+        let start = std::time::Instant::now();
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return status.code(),
+                Ok(None) => {
+                    if start.elapsed() >= timeout {
+                        unsafe {
+                            libc::kill(pgid, libc::SIGKILL);
+                        }
+                        return child.wait().ok().and_then(|s| s.code());
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = timeout;
+        let _ = child.kill();
+        child.wait().ok().and_then(|s| s.code())
+    }
+}
+
+/// Lowers the CPU priority of `cmd`, and on Linux its I/O priority, for the
+/// process it starts and everything that process starts.
+pub(crate) fn apply_priority(cmd: &mut Command, priority: ProcessPriority) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        // ioprio_set(2) values: class in the top bits, level 0-7 below
+        const IOPRIO_CLASS_SHIFT: i32 = 13;
+        let (nice, _ioprio) = match priority {
+            ProcessPriority::Normal => return,
+            ProcessPriority::Low => (10, (2 << IOPRIO_CLASS_SHIFT) | 7),
+            ProcessPriority::Lowest => (19, 3 << IOPRIO_CLASS_SHIFT),
+        };
+        // Runs in the child between fork and exec, where only async-signal-safe
+        // calls are allowed. A failure leaves the default priority.
+        unsafe {
+            cmd.pre_exec(move || {
+                libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+                #[cfg(target_os = "linux")]
+                libc::syscall(libc::SYS_ioprio_set, 1 /* IOPRIO_WHO_PROCESS */, 0, _ioprio);
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+        const IDLE_PRIORITY_CLASS: u32 = 0x0000_0040;
+        match priority {
+            ProcessPriority::Normal => {}
+            ProcessPriority::Low => {
+                cmd.creation_flags(BELOW_NORMAL_PRIORITY_CLASS);
+            }
+            ProcessPriority::Lowest => {
+                cmd.creation_flags(IDLE_PRIORITY_CLASS);
+            }
+        }
+    }
+}
+
+/// Callback invoked with `(stream_type, line)` for each line of process output.
+#[cfg(feature = "gui")]
+pub type OutputSink = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// Where a tracked process's output goes besides `agent-output` events.
+#[cfg(feature = "gui")]
+#[derive(Default)]
+pub struct OutputOptions {
+    /// Gets each line of output.
+    pub sink: Option<OutputSink>,
+    pub mode: OutputMode,
+    /// Emits `agent-progress` from the output.
+    pub progress: Option<ProgressTracker>,
+    /// Records the usage in structured output as cost entries.
+    pub costs: Option<CostCapture>,
+}
+
+/// Spawns a tracked process and returns its ID.
+/// Output is emitted as `agent-output` events and handled as `output` says.
+/// The process is registered in `PROCESSES` so `wait_agent`/`kill_agent` work on it.
+#[cfg(feature = "gui")]
+pub async fn spawn_tracked_process(
     app: AppHandle,
     executable: String,
     args: Vec<String>,
     working_directory: String,
     env: Option<HashMap<String, String>>,
-) -> Result<SpawnAgentResult, String> {
+    output: OutputOptions,
+    priority: ProcessPriority,
+) -> Result<String, String> {
+    check_working_directory(&working_directory)?;
     let process_id = Uuid::new_v4().to_string();
+    let OutputOptions { sink, mode, progress, costs } = output;
+    let sink = match progress {
+        Some(tracker) => Some(progress::observe(app.clone(), process_id.clone(), tracker, sink)),
+        None => sink,
+    };
+    let sink = match costs {
+        Some(capture) => Some(cost_capture::observe(app.clone(), working_directory.clone(), capture, sink)),
+        None => sink,
+    };
 
     // Spawn the process in a blocking task to avoid blocking the UI
+    let project_app = app.clone();
     let child = tokio::task::spawn_blocking(move || {
         let mut cmd = Command::new(&executable);
         cmd.args(&args)
@@ -107,7 +312,10 @@ pub async fn spawn_agent(
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        // Add custom environment variables if provided
+        // The project's environment, then any custom variables over it
+        for (key, value) in project_env::resolve(&project_app, &working_directory) {
+            cmd.env(key, value);
+        }
         if let Some(env_vars) = env {
             for (key, value) in env_vars {
                 cmd.env(key, value);
@@ -120,6 +328,7 @@ pub async fn spawn_agent(
             use std::os::unix::process::CommandExt;
             cmd.process_group(0); // Create new process group with pgid = pid
         }
+        apply_priority(&mut cmd, priority);
 
         cmd.spawn()
             .map_err(|e| format!("Failed to spawn process '{}': {}", executable, e))
@@ -128,129 +337,546 @@ pub async fn spawn_agent(
     .map_err(|e| format!("Task join error: {}", e))??;
 
     let mut child = child;
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-
-    let pid_clone = process_id.clone();
-    let app_clone = app.clone();
-    if let Some(stdout) = stdout {
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let event = AgentOutputEvent {
-                        process_id: pid_clone.clone(),
-                        stream_type: "stdout".to_string(),
-                        content: line,
-                    };
-                    let _ = app_clone.emit("agent-output", event);
+    let output = spawn_output_batcher(app.clone(), process_id.clone(), mode);
+    let forward = match mode {
+        OutputMode::Lines => forward_output,
+        OutputMode::Raw => forward_raw_output,
+    };
+    if let Some(stdout) = child.stdout.take() {
+        forward(output.clone(), "stdout", Box::new(stdout), sink.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward(output, "stderr", Box::new(stderr), sink);
+    }
+
+    let mut processes = PROCESSES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    processes.insert(process_id.clone(), child);
+
+    Ok(process_id)
+}
+
+/// Lines of process output and the stream each came from.
+#[cfg(feature = "gui")]
+pub(crate) type OutputSender = mpsc::Sender<(&'static str, String)>;
+
+/// Longest a line waits before its batch is emitted.
+#[cfg(feature = "gui")]
+const OUTPUT_BATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long an exited process's remaining output may take to flush.
+/// Descendants that inherited its pipes can hold them open indefinitely.
+#[cfg(feature = "gui")]
+const OUTPUT_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+lazy_static::lazy_static! {
+    /// Signalled by each process's batcher once its last batch is emitted.
+    static ref OUTPUT_DONE: Mutex<HashMap<String, mpsc::Receiver<()>>> = Mutex::new(HashMap::new());
+}
+
+/// Lines per batch: a tenth of the log buffer preference, so one batch
+/// never replaces most of what the UI keeps.
+#[cfg(feature = "gui")]
+fn output_batch_lines(app: &AppHandle) -> usize {
+    let buffer_size = crate::preferences::load_preferences_internal(app)
+        .map(|prefs| prefs.log_buffer_size)
+        .unwrap_or(1000);
+    (buffer_size.max(0) as usize / 10).clamp(1, 1000)
+}
+
+/// Starts the thread that coalesces a process's output into `agent-output`
+/// events, emitted every 50ms or when a batch fills. It flushes and exits once
+/// every sender is dropped.
+#[cfg(feature = "gui")]
+pub(crate) fn spawn_output_batcher(app: AppHandle, process_id: String, mode: OutputMode) -> OutputSender {
+    let (tx, rx) = mpsc::channel::<(&'static str, String)>();
+    let (done_tx, done_rx) = mpsc::channel();
+    if let Ok(mut done) = OUTPUT_DONE.lock() {
+        done.insert(process_id.clone(), done_rx);
+    }
+    let max_lines = output_batch_lines(&app);
+
+    thread::spawn(move || {
+        let mut lines: Vec<AgentOutputLine> = Vec::new();
+        let mut deadline: Option<Instant> = None;
+        let flush = |lines: &mut Vec<AgentOutputLine>| {
+            if !lines.is_empty() {
+                let event = AgentOutputEvent {
+                    process_id: process_id.clone(),
+                    lines: std::mem::take(lines),
+                    raw: mode == OutputMode::Raw,
+                };
+                let _ = event_bus::emit(&app, "agent-output", event);
+            }
+        };
+
+        loop {
+            let received = match deadline {
+                Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok((stream_type, content)) => {
+                    lines.push(AgentOutputLine {
+                        stream_type: stream_type.to_string(),
+                        content,
+                    });
+                    deadline.get_or_insert_with(|| Instant::now() + OUTPUT_BATCH_INTERVAL);
+                    if lines.len() >= max_lines {
+                        flush(&mut lines);
+                        deadline = None;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    flush(&mut lines);
+                    deadline = None;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    flush(&mut lines);
+                    break;
                 }
             }
-        });
+        }
+        let _ = done_tx.send(());
+    });
+
+    tx
+}
+
+/// Waits briefly for an exited process's last output batch, so `agent-exit`
+/// follows its output.
+#[cfg(feature = "gui")]
+pub(crate) fn wait_for_output(process_id: &str) {
+    let done = OUTPUT_DONE.lock().ok().and_then(|mut done| done.remove(process_id));
+    if let Some(done) = done {
+        let _ = done.recv_timeout(OUTPUT_FLUSH_TIMEOUT);
     }
+}
+
+/// A child's stdout or stderr.
+#[cfg(feature = "gui")]
+type OutputStream = Box<dyn Read + Send>;
+
+/// Reads a process stream line by line into its output batcher.
+#[cfg(feature = "gui")]
+fn forward_output(output: OutputSender, stream_type: &'static str, stream: OutputStream, sink: Option<OutputSink>) {
+    thread::spawn(move || {
+        let reader = BufReader::new(stream);
+        for line in reader.lines().map_while(Result::ok) {
+            if let Some(sink) = &sink {
+                sink(stream_type, &redact::mask_literals(&line));
+            }
+            if output.send((stream_type, redact::for_output(line))).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Reads a process stream into its output batcher a chunk at a time, as soon
+/// as anything arrives. `sink` still gets whole lines.
+#[cfg(feature = "gui")]
+fn forward_raw_output(
+    output: OutputSender,
+    stream_type: &'static str,
+    mut stream: OutputStream,
+    sink: Option<OutputSink>,
+) {
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut line = String::new();
+        loop {
+            let read = match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            pending.extend_from_slice(&buf[..read]);
+
+            // Hold back a character split across reads; anything else invalid is replaced
+            let complete = match std::str::from_utf8(&pending) {
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                _ => pending.len(),
+            };
+            let chunk = String::from_utf8_lossy(&pending[..complete]).into_owned();
+            pending.drain(..complete);
+            if chunk.is_empty() {
+                continue;
+            }
 
-    let pid_clone2 = process_id.clone();
-    let app_clone2 = app.clone();
-    if let Some(stderr) = stderr {
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let event = AgentOutputEvent {
-                        process_id: pid_clone2.clone(),
-                        stream_type: "stderr".to_string(),
-                        content: line,
-                    };
-                    let _ = app_clone2.emit("agent-output", event);
+            if let Some(sink) = &sink {
+                line.push_str(&chunk);
+                while let Some(end) = line.find('\n') {
+                    let rest = line.split_off(end + 1);
+                    sink(stream_type, &redact::mask_literals(line.trim_end_matches(['\r', '\n'])));
+                    line = rest;
                 }
             }
-        });
+            if output.send((stream_type, redact::for_output(chunk))).is_err() {
+                return;
+            }
+        }
+
+        if !pending.is_empty() {
+            let _ = output.send((stream_type, redact::for_output(String::from_utf8_lossy(&pending).into_owned())));
+        }
+        if let (Some(sink), false) = (&sink, line.is_empty()) {
+            sink(stream_type, &redact::mask_literals(&line));
+        }
+    });
+}
+
+/// Fails with a clear error when a process's working directory is missing,
+/// rather than the spawn failing with "No such file or directory".
+#[cfg(feature = "gui")]
+fn check_working_directory(working_directory: &str) -> Result<(), String> {
+    let path = Path::new(working_directory);
+    if !path.exists() {
+        return Err(format!(
+            "Working directory does not exist: {}. If it was a story worktree, it may have been removed.",
+            working_directory
+        ));
     }
+    if !path.is_dir() {
+        return Err(format!("Working directory is not a directory: {}", working_directory));
+    }
+    Ok(())
+}
 
-    let mut processes = PROCESSES
-        .lock()
-        .map_err(|e| format!("Lock error: {}", e))?;
-    processes.insert(process_id.clone(), child);
+/// Resolves `path` to an absolute path with symlinks resolved, for a path
+/// that may not exist yet: the deepest existing ancestor is canonicalized and
+/// the rest appended.
+#[cfg(feature = "gui")]
+fn resolve_path(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            break;
+        };
+        missing.push(name);
+        existing = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve working directory {}: {}", path.display(), e))?;
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+    Ok(resolved)
+}
+
+/// Checks that `spawn_agent` runs inside a registered project; story
+/// worktrees live under their project's `.ideate-worktrees`. With
+/// `ensure_exists`, a missing directory inside a project is created.
+#[cfg(feature = "gui")]
+pub(crate) fn prepare_working_directory(app: &AppHandle, working_directory: &str, ensure_exists: bool) -> Result<(), String> {
+    let resolved = resolve_path(Path::new(working_directory))?;
+    let inside_project = crate::projects::load_projects(app.clone())?
+        .iter()
+        .filter_map(|project| Path::new(&project.path).canonicalize().ok())
+        .any(|project_path| resolved.starts_with(project_path));
+    if !inside_project {
+        return Err(format!(
+            "Working directory is outside every registered project: {}",
+            working_directory
+        ));
+    }
+
+    if ensure_exists && !resolved.exists() {
+        fs::create_dir_all(&resolved)
+            .map_err(|e| format!("Failed to create working directory {}: {}", working_directory, e))?;
+    }
+    check_working_directory(working_directory)
+}
+
+/// Variables that make a program load or run other code, like a preloaded
+/// library, an interpreter's startup options, or where executables are found.
+#[cfg(feature = "gui")]
+const BLOCKED_ENV: &[&str] = &[
+    "PATH",
+    "NODE_OPTIONS",
+    "NODE_PATH",
+    "PYTHONPATH",
+    "PYTHONHOME",
+    "PYTHONSTARTUP",
+    "PERL5OPT",
+    "PERL5LIB",
+    "PERLLIB",
+    "RUBYOPT",
+    "RUBYLIB",
+    "JAVA_TOOL_OPTIONS",
+    "_JAVA_OPTIONS",
+    "JDK_JAVA_OPTIONS",
+    "DOTNET_STARTUP_HOOKS",
+    "BASH_ENV",
+    "ENV",
+    "ZDOTDIR",
+    "SHELLOPTS",
+    "PS4",
+    "GIT_SSH",
+    "GIT_SSH_COMMAND",
+    "GIT_EXEC_PATH",
+    "COMSPEC",
+];
+
+/// Prefixes of variables blocked for the same reason: the dynamic loaders'
+/// `LD_*` and `DYLD_*`, exported bash functions, and git config overrides.
+#[cfg(feature = "gui")]
+const BLOCKED_ENV_PREFIXES: &[&str] = &["LD_", "DYLD_", "BASH_FUNC_", "GIT_CONFIG_"];
+
+/// Rejects environment variables from the webview that could make the
+/// spawned program run code it wasn't asked to. Project variables from
+/// `project_env` are added by the backend and aren't checked.
+#[cfg(feature = "gui")]
+fn check_env(env: Option<&HashMap<String, String>>) -> Result<(), String> {
+    let Some(blocked) = env.into_iter().flat_map(HashMap::keys).find(|key| {
+        let key = key.to_ascii_uppercase();
+        BLOCKED_ENV.contains(&key.as_str()) || BLOCKED_ENV_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+    }) else {
+        return Ok(());
+    };
+    tracing::warn!("Blocked spawn setting environment variable {}", blocked);
+    Err(format!("Environment variable not allowed: {}", blocked))
+}
+
+/// Spawns an agent process and returns its ID. The executable must pass the
+/// `allowlist` check. Output is emitted line by line unless `output_mode` is
+/// `raw`. With `agent_id`, the agent's progress rules are applied to it and
+/// the usage it reports in structured output is recorded as a cost entry
+/// described as `cost_description`.
+/// `priority` defaults to the `agentPriority` preference.
+/// The working directory must be inside a registered project, and is created
+/// if missing with `ensure_exists`, unless `allowlist::may_run_outside_projects`
+/// lets the executable run outside them, as tunnels and idea generation do.
+/// `env` may not set variables that inject code, such as `LD_PRELOAD` or
+/// `NODE_OPTIONS`; see `check_env`.
+/// This is async to avoid blocking the UI thread during process startup.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+#[allow(clippy::too_many_arguments)]
+pub async fn spawn_agent(
+    app: AppHandle,
+    executable: String,
+    args: Vec<String>,
+    working_directory: String,
+    env: Option<HashMap<String, String>>,
+    output_mode: Option<OutputMode>,
+    agent_id: Option<String>,
+    priority: Option<ProcessPriority>,
+    ensure_exists: Option<bool>,
+    cost_description: Option<String>,
+) -> Result<SpawnAgentResult, String> {
+    allowlist::check(&app, &executable, &args, Some(&working_directory))?;
+    check_env(env.as_ref())?;
+    if allowlist::may_run_outside_projects(&executable, &working_directory) {
+        check_working_directory(&working_directory)?;
+    } else {
+        prepare_working_directory(&app, &working_directory, ensure_exists.unwrap_or(false))?;
+    }
+    let output = OutputOptions {
+        mode: output_mode.unwrap_or_default(),
+        progress: agent_id.as_deref().and_then(|id| progress::tracker_for(&app, id)),
+        costs: agent_id.map(|agent_id| CostCapture {
+            description: cost_description.unwrap_or_else(|| format!("{} run", agent_id)),
+            agent_id,
+        }),
+        ..Default::default()
+    };
+    let priority = priority.unwrap_or_else(|| {
+        crate::preferences::load_preferences_internal(&app)
+            .map(|prefs| prefs.agent_priority)
+            .unwrap_or_default()
+    });
+    let process_id =
+        spawn_tracked_process(app, executable, args, working_directory, env, output, priority).await?;
 
     Ok(SpawnAgentResult { process_id })
 }
 
-/// Waits for an agent process to complete.
-/// Uses try_wait in a loop to avoid holding the mutex lock, allowing kill_agent to work.
+/// The user's shell and the arguments that make it run `command_line`:
+/// `$SHELL -c` on Unix, falling back to `sh`, and `%ComSpec% /C` on Windows.
+#[cfg(feature = "gui")]
+pub fn shell_invocation(command_line: &str) -> (String, Vec<String>) {
+    #[cfg(unix)]
+    let (shell, flag) = (
+        std::env::var("SHELL").ok().filter(|s| !s.is_empty()).unwrap_or_else(|| "sh".to_string()),
+        "-c",
+    );
+    #[cfg(windows)]
+    let (shell, flag) = (
+        std::env::var("ComSpec").ok().filter(|s| !s.is_empty()).unwrap_or_else(|| "cmd".to_string()),
+        "/C",
+    );
+    (shell, vec![flag.to_string(), command_line.to_string()])
+}
+
+/// The command line a project configures under `name`: `verification` for
+/// its verification command, or a hook's key such as `preBuild`. Command
+/// lines only ever come from the project's config, never from the webview.
+#[cfg(feature = "gui")]
+fn configured_command_line(project_path: &str, name: &str) -> Result<String, String> {
+    let command = if name == "verification" {
+        crate::verification::load_config(project_path).map(|config| config.command.trim().to_string())
+    } else {
+        let kind = crate::hooks::HookKind::from_name(name)
+            .ok_or_else(|| format!("Unknown command: {}", name))?;
+        crate::hooks::configured_command(project_path, kind)
+    };
+    command.ok_or_else(|| format!("The project has no {} command configured", name))
+}
+
+/// Runs a command the project configures, such as its verification command
+/// or a hook, through the user's shell so pipes, `&&`, redirection, and
+/// variable expansion work. `command` names it: `verification`, `preBuild`,
+/// `postBuild`, `preStory`, or `postStory`. Output and exit are reported like
+/// `spawn_agent`'s, under the returned process ID, and `env` is checked the
+/// same way.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
-pub async fn wait_agent(app: AppHandle, process_id: String) -> Result<WaitAgentResult, String> {
-    let result = tokio::task::spawn_blocking(move || {
-        loop {
-            // Acquire lock, check process status, then release lock
-            let wait_result = {
-                let mut processes = PROCESSES
-                    .lock()
-                    .map_err(|e| format!("Lock error: {}", e))?;
-
-                let child = match processes.get_mut(&process_id) {
-                    Some(child) => child,
-                    None => {
-                        // Process was removed (likely killed by kill_agent)
-                        return Ok(WaitAgentResult {
-                            process_id: process_id.clone(),
-                            exit_code: None,
-                            success: false,
-                        });
-                    }
-                };
+pub async fn spawn_shell_command(
+    app: AppHandle,
+    project_path: String,
+    command: String,
+    env: Option<HashMap<String, String>>,
+    output_mode: Option<OutputMode>,
+) -> Result<SpawnAgentResult, String> {
+    prepare_working_directory(&app, &project_path, false)?;
+    check_env(env.as_ref())?;
+    let command_line = configured_command_line(&project_path, &command)?;
+    let output = OutputOptions {
+        mode: output_mode.unwrap_or_default(),
+        ..Default::default()
+    };
+    let priority = crate::preferences::load_preferences_internal(&app)
+        .map(|prefs| prefs.agent_priority)
+        .unwrap_or_default();
+    let (shell, args) = shell_invocation(&command_line);
+    let process_id = spawn_tracked_process(app, shell, args, project_path, env, output, priority).await?;
 
-                match child.try_wait() {
-                    Ok(Some(status)) => {
-                        // Process has exited
-                        let exit_code = status.code();
-                        let success = status.success();
-                        processes.remove(&process_id);
-                        Some(Ok(WaitAgentResult {
-                            process_id: process_id.clone(),
-                            exit_code,
-                            success,
-                        }))
-                    }
-                    Ok(None) => {
-                        // Process still running, will check again after sleep
-                        None
-                    }
-                    Err(e) => {
-                        processes.remove(&process_id);
-                        Some(Err(format!("Failed to wait for process: {}", e)))
-                    }
+    Ok(SpawnAgentResult { process_id })
+}
+
+/// Blocks until a tracked process exits.
+/// Uses try_wait in a loop to avoid holding the mutex lock, allowing kill_agent to work.
+#[cfg(feature = "gui")]
+pub fn wait_for_process(process_id: &str) -> Result<WaitAgentResult, String> {
+    loop {
+        // Acquire lock, check process status, then release lock
+        let wait_result = {
+            let mut processes = PROCESSES
+                .lock()
+                .map_err(|e| format!("Lock error: {}", e))?;
+
+            let child = match processes.get_mut(process_id) {
+                Some(child) => child,
+                None => {
+                    // Process was removed (likely killed by kill_agent)
+                    let (termination_reason, signal) = TERMINATIONS
+                        .lock()
+                        .ok()
+                        .and_then(|mut terminations| terminations.remove(process_id))
+                        .unwrap_or((TerminationReason::Canceled, None));
+                    return Ok(WaitAgentResult {
+                        process_id: process_id.to_string(),
+                        exit_code: None,
+                        success: false,
+                        signal,
+                        termination_reason,
+                    });
                 }
-            }; // Lock is released here
+            };
 
-            if let Some(result) = wait_result {
-                return result;
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    // Process has exited, possibly during a kill's grace period
+                    let exit_code = status.code();
+                    let success = status.success();
+                    processes.remove(process_id);
+                    let killed = TERMINATIONS
+                        .lock()
+                        .ok()
+                        .and_then(|mut terminations| terminations.remove(process_id));
+                    Some(Ok(WaitAgentResult {
+                        process_id: process_id.to_string(),
+                        exit_code,
+                        success,
+                        signal: exit_signal(&status),
+                        termination_reason: killed.map_or_else(|| natural_termination(&status), |(reason, _)| reason),
+                    }))
+                }
+                Ok(None) => {
+                    // Process still running, will check again after sleep
+                    None
+                }
+                Err(e) => {
+                    processes.remove(process_id);
+                    Some(Err(format!("Failed to wait for process: {}", e)))
+                }
             }
+        }; // Lock is released here
 
-            // Sleep before checking again - this allows kill_agent to acquire the lock
-            thread::sleep(Duration::from_millis(50));
+        if let Some(result) = wait_result {
+            wait_for_output(process_id);
+            return result;
         }
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))??;
+
+        // Sleep before checking again - this allows kill_agent to acquire the lock
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Waits for an agent process to complete.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn wait_agent(app: AppHandle, process_id: String) -> Result<WaitAgentResult, String> {
+    let result = tokio::task::spawn_blocking(move || wait_for_process(&process_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
 
     let event = AgentExitEvent {
         process_id: result.process_id.clone(),
         exit_code: result.exit_code,
         success: result.success,
+        signal: result.signal,
+        termination_reason: result.termination_reason,
     };
-    let _ = app.emit("agent-exit", event);
+    let _ = event_bus::emit(&app, "agent-exit", event);
 
     Ok(result)
 }
 
 /// Kills an agent process asynchronously to avoid blocking the UI.
+/// `grace_period_ms` is how long it gets to exit after SIGTERM before
+/// SIGKILL, defaulting to the preference; 0 kills it immediately.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
-pub async fn kill_agent(app: AppHandle, process_id: String) -> Result<KillAgentResult, String> {
+pub async fn kill_agent(
+    app: AppHandle,
+    process_id: String,
+    grace_period_ms: Option<u64>,
+) -> Result<KillAgentResult, String> {
+    // Interactive agent sessions run in a terminal rather than as tracked processes
+    if terminal::is_terminal(&process_id) {
+        terminal::kill_terminal(app, process_id)?;
+        return Ok(KillAgentResult {
+            success: true,
+            message: "Agent session closed".to_string(),
+            path: Some(KillPath::Immediate),
+        });
+    }
+
     let pid = process_id.clone();
+    let grace_period = grace_period_ms.map_or_else(default_grace_period, Duration::from_millis);
 
-    let result = tokio::task::spawn_blocking(move || kill_agent_blocking(&pid))
-        .await
-        .map_err(|e| format!("Task join error: {}", e))??;
+    let result = tokio::task::spawn_blocking(move || {
+        let result = kill_agent_with_grace(&pid, TerminationReason::Canceled, grace_period);
+        wait_for_output(&pid);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
 
     // Emit exit event if process was killed successfully
     if result.success {
@@ -258,72 +884,98 @@ pub async fn kill_agent(app: AppHandle, process_id: String) -> Result<KillAgentR
             process_id: process_id.clone(),
             exit_code: None,
             success: false, // Killed, not natural exit
+            signal: recorded_termination(&process_id).and_then(|(_, signal)| signal),
+            termination_reason: TerminationReason::Canceled,
         };
-        let _ = app.emit("agent-exit", event);
+        let _ = event_bus::emit(&app, "agent-exit", event);
     }
 
     Ok(result)
 }
 
-/// Blocking implementation of kill_agent for use in spawn_blocking.
-fn kill_agent_blocking(process_id: &str) -> Result<KillAgentResult, String> {
-    let mut processes = PROCESSES
-        .lock()
-        .map_err(|e| format!("Lock error: {}", e))?;
+/// Blocking implementation of kill_agent for use in spawn_blocking, with the
+/// preferred grace period.
+/// `reason` is reported by `wait_for_process` for the killed process.
+#[cfg(feature = "gui")]
+pub fn kill_agent_blocking(process_id: &str, reason: TerminationReason) -> Result<KillAgentResult, String> {
+    kill_agent_with_grace(process_id, reason, default_grace_period())
+}
 
-    let child = match processes.get_mut(process_id) {
-        Some(child) => child,
-        None => {
+/// Sends SIGTERM to the process group, escalating to SIGKILL if it is still
+/// running after `grace_period`. A zero grace period sends SIGKILL right away.
+/// `PROCESSES` is only locked to signal and poll, not across the grace period.
+#[cfg(feature = "gui")]
+pub fn kill_agent_with_grace(
+    process_id: &str,
+    reason: TerminationReason,
+    grace_period: Duration,
+) -> Result<KillAgentResult, String> {
+    #[cfg(unix)]
+    {
+        let first_signal = if grace_period.is_zero() { libc::SIGKILL } else { libc::SIGTERM };
+        if !signal_process_group(process_id, first_signal)? {
             return Ok(KillAgentResult {
                 success: false,
                 message: format!("Process {} not found", process_id),
+                path: None,
             });
         }
-    };
+        // Recorded now so a `wait_for_process` that reaps the process during
+        // the grace period still reports why it stopped
+        record_termination(process_id, reason, None);
 
-    #[cfg(unix)]
-    {
-        let pid = child.id();
-        // Use negative pid to kill the entire process group
-        let pgid = -(pid as i32);
-
-        unsafe {
-            // Send SIGTERM to the entire process group
-            libc::kill(pgid, libc::SIGTERM);
+        if grace_period.is_zero() {
+            return Ok(match reap(process_id) {
+                Ok(status) => {
+                    record_termination(process_id, reason, status);
+                    KillAgentResult {
+                        success: true,
+                        message: "Process group killed with SIGKILL".to_string(),
+                        path: Some(KillPath::Immediate),
+                    }
+                }
+                Err(e) => KillAgentResult {
+                    success: false,
+                    message: format!("Error waiting for process: {}", e),
+                    path: None,
+                },
+            });
         }
 
-        let start = std::time::Instant::now();
-        let timeout = Duration::from_secs(5);
+        let start = Instant::now();
 
         loop {
-            match child.try_wait() {
-                Ok(Some(_status)) => {
-                    processes.remove(process_id);
+            match try_reap(process_id) {
+                Ok(Some(status)) => {
+                    record_termination(process_id, reason, status);
                     return Ok(KillAgentResult {
                         success: true,
                         message: "Process group terminated gracefully with SIGTERM".to_string(),
+                        path: Some(KillPath::Graceful),
                     });
                 }
                 Ok(None) => {
-                    if start.elapsed() >= timeout {
-                        unsafe {
-                            // Force kill the entire process group
-                            libc::kill(pgid, libc::SIGKILL);
-                        }
-                        let _ = child.wait();
-                        processes.remove(process_id);
+                    if start.elapsed() >= grace_period {
+                        // Force kill the entire process group
+                        signal_process_group(process_id, libc::SIGKILL)?;
+                        let status = reap(process_id)?;
+                        record_termination(process_id, reason, status);
                         return Ok(KillAgentResult {
                             success: true,
-                            message: "Process group killed with SIGKILL after timeout".to_string(),
+                            message: format!(
+                                "Process group killed with SIGKILL after {} ms grace period",
+                                grace_period.as_millis()
+                            ),
+                            path: Some(KillPath::Escalated),
                         });
                     }
-                    thread::sleep(Duration::from_millis(100));
+                    thread::sleep(Duration::from_millis(100).min(grace_period));
                 }
                 Err(e) => {
-                    processes.remove(process_id);
                     return Ok(KillAgentResult {
                         success: false,
                         message: format!("Error waiting for process: {}", e),
+                        path: None,
                     });
                 }
             }
@@ -332,28 +984,102 @@ fn kill_agent_blocking(process_id: &str) -> Result<KillAgentResult, String> {
 
     #[cfg(windows)]
     {
+        // Windows has no SIGTERM equivalent for console processes
+        let _ = grace_period;
+        let mut processes = PROCESSES
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+
+        let Some(child) = processes.get_mut(process_id) else {
+            return Ok(KillAgentResult {
+                success: false,
+                message: format!("Process {} not found", process_id),
+                path: None,
+            });
+        };
+
         match child.kill() {
             Ok(()) => {
-                let _ = child.wait();
+                let status = child.wait().ok();
+                record_termination(process_id, reason, status);
                 processes.remove(process_id);
                 Ok(KillAgentResult {
                     success: true,
                     message: "Process killed".to_string(),
+                    path: Some(KillPath::Immediate),
                 })
             }
             Err(e) => {
+                record_termination(process_id, reason, None);
                 processes.remove(process_id);
                 Ok(KillAgentResult {
                     success: false,
                     message: format!("Failed to kill process: {}", e),
+                    path: None,
                 })
             }
         }
     }
 }
 
+/// Sends `signal` to a tracked process's group. Signals under the lock so the
+/// process can't be reaped, and its pid reused, in between. Returns false if
+/// it isn't tracked.
+#[cfg(feature = "gui")]
+#[cfg(unix)]
+fn signal_process_group(process_id: &str, signal: libc::c_int) -> Result<bool, String> {
+    let processes = PROCESSES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let Some(child) = processes.get(process_id) else {
+        return Ok(false);
+    };
+    // Use negative pid to signal the entire process group
+    unsafe {
+        libc::kill(-(child.id() as i32), signal);
+    }
+    Ok(true)
+}
+
+/// Checks whether a tracked process has exited, removing it if so.
+/// `None` while it runs; `Some(None)` if something else already reaped it.
+#[cfg(feature = "gui")]
+#[cfg(unix)]
+fn try_reap(process_id: &str) -> Result<Option<Option<ExitStatus>>, String> {
+    let mut processes = PROCESSES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let Some(child) = processes.get_mut(process_id) else {
+        return Ok(Some(None));
+    };
+    match child.try_wait() {
+        Ok(Some(status)) => {
+            processes.remove(process_id);
+            Ok(Some(Some(status)))
+        }
+        Ok(None) => Ok(None),
+        Err(e) => {
+            processes.remove(process_id);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Polls until a process sent SIGKILL is reaped.
+#[cfg(feature = "gui")]
+#[cfg(unix)]
+fn reap(process_id: &str) -> Result<Option<ExitStatus>, String> {
+    loop {
+        if let Some(status) = try_reap(process_id)? {
+            return Ok(status);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
 /// Saves process logs to a file in the app data directory.
 /// Uses spawn_blocking to avoid blocking the main thread.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub async fn save_process_log(
     app: AppHandle,
@@ -363,10 +1089,7 @@ pub async fn save_process_log(
     label: String,
     logs: Vec<ProcessLogEntry>,
 ) -> Result<String, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir = app_data_dir(&app)?;
 
     tokio::task::spawn_blocking(move || {
         save_process_log_blocking(app_data_dir, process_id, project_id, process_type, label, logs)
@@ -375,7 +1098,7 @@ pub async fn save_process_log(
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
-fn save_process_log_blocking(
+pub(crate) fn save_process_log_blocking(
     app_data_dir: std::path::PathBuf,
     process_id: String,
     project_id: String,
@@ -433,7 +1156,7 @@ fn save_process_log_blocking(
         writeln!(
             file,
             "[{}] {} {}",
-            entry.timestamp, type_prefix, entry.content
+            entry.timestamp, type_prefix, redact::for_log(&entry.content)
         )
         .map_err(|e| format!("Write error: {}", e))?;
     }
@@ -443,79 +1166,86 @@ fn save_process_log_blocking(
 
 /// Saves a process history entry.
 /// Uses spawn_blocking to avoid blocking the main thread.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub async fn save_process_history_entry(
     app: AppHandle,
     entry: ProcessHistoryEntry,
 ) -> Result<(), String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir = app_data_dir(&app)?;
 
-    tokio::task::spawn_blocking(move || {
-        let history_path = app_data_dir.join("process-history.json");
-
-        // Load existing history
-        let mut history = if history_path.exists() {
-            let content = fs::read_to_string(&history_path)
-                .map_err(|e| format!("Failed to read process history: {}", e))?;
-            serde_json::from_str::<ProcessHistory>(&content).unwrap_or(ProcessHistory {
-                entries: Vec::new(),
-            })
-        } else {
-            ProcessHistory {
-                entries: Vec::new(),
-            }
-        };
+    tokio::task::spawn_blocking(move || append_process_history(&app_data_dir, entry))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
 
-        // Add new entry at the beginning (most recent first)
-        history.entries.insert(0, entry);
+/// Prepends an entry to `process-history.json`, keeping the newest 500.
+pub(crate) fn append_process_history(
+    app_data_dir: &std::path::Path,
+    entry: ProcessHistoryEntry,
+) -> Result<(), String> {
+    let history_path = app_data_dir.join("process-history.json");
 
-        // Keep only the last 500 entries
-        if history.entries.len() > 500 {
-            history.entries.truncate(500);
+    // Load existing history
+    let mut history = if history_path.exists() {
+        let content = fs::read_to_string(&history_path)
+            .map_err(|e| format!("Failed to read process history: {}", e))?;
+        serde_json::from_str::<ProcessHistory>(&content).unwrap_or(ProcessHistory {
+            entries: Vec::new(),
+        })
+    } else {
+        ProcessHistory {
+            entries: Vec::new(),
         }
+    };
 
-        // Save back
-        let json = serde_json::to_string_pretty(&history)
-            .map_err(|e| format!("Failed to serialize process history: {}", e))?;
+    // Add new entry at the beginning (most recent first)
+    history.entries.insert(0, entry);
 
-        fs::write(&history_path, json)
-            .map_err(|e| format!("Failed to write process history: {}", e))?;
+    // Keep only the last 500 entries
+    if history.entries.len() > 500 {
+        history.entries.truncate(500);
+    }
 
-        Ok(())
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    // Save back
+    let json = serde_json::to_string_pretty(&history)
+        .map_err(|e| format!("Failed to serialize process history: {}", e))?;
+
+    fs::write(&history_path, json)
+        .map_err(|e| format!("Failed to write process history: {}", e))?;
+
+    Ok(())
+}
+
+/// Reads `process-history.json`, most recent first.
+#[cfg(feature = "gui")]
+pub(crate) fn read_process_history(app_data_dir: &std::path::Path) -> Result<ProcessHistory, String> {
+    let history_path = app_data_dir.join("process-history.json");
+
+    if !history_path.exists() {
+        return Ok(ProcessHistory {
+            entries: Vec::new(),
+        });
+    }
+
+    let content = fs::read_to_string(&history_path)
+        .map_err(|e| format!("Failed to read process history: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse process history: {}", e))
 }
 
 /// Loads process history for a specific project.
 /// Uses spawn_blocking to avoid blocking the main thread.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub async fn load_process_history(
     app: AppHandle,
     project_id: String,
 ) -> Result<ProcessHistory, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir = app_data_dir(&app)?;
 
     tokio::task::spawn_blocking(move || {
-        let history_path = app_data_dir.join("process-history.json");
-
-        if !history_path.exists() {
-            return Ok(ProcessHistory {
-                entries: Vec::new(),
-            });
-        }
-
-        let content = fs::read_to_string(&history_path)
-            .map_err(|e| format!("Failed to read process history: {}", e))?;
-
-        let history: ProcessHistory = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse process history: {}", e))?;
+        let history = read_process_history(&app_data_dir)?;
 
         // Filter by project ID
         let filtered = ProcessHistory {
@@ -532,14 +1262,167 @@ pub async fn load_process_history(
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
-/// Reads a log file's contents.
+/// Bytes returned by `read_process_log_file` when no limit is given.
+#[cfg(feature = "gui")]
+const LOG_CHUNK_DEFAULT_BYTES: u64 = 256 * 1024;
+
+/// Largest chunk `read_process_log_file` returns in one call.
+#[cfg(feature = "gui")]
+const LOG_CHUNK_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Block size for scanning log files.
+#[cfg(feature = "gui")]
+const LOG_SCAN_BLOCK: usize = 64 * 1024;
+
+/// Reads up to `limit` bytes from `offset`, trimmed to whole lines: a partial
+/// first line is skipped and a partial last line left for the next read, unless
+/// the chunk holds no line break at all.
+#[cfg(feature = "gui")]
+fn read_log_range(file: &mut (impl Read + Seek), total_size: u64, offset: u64, limit: u64) -> std::io::Result<LogChunk> {
+    let offset = offset.min(total_size);
+    let end = offset.saturating_add(limit).min(total_size);
+    // Read one byte early to tell whether `offset` starts a line
+    let read_from = offset.saturating_sub(1);
+    let mut buf = vec![0u8; (end - read_from) as usize];
+    file.seek(SeekFrom::Start(read_from))?;
+    file.read_exact(&mut buf)?;
+
+    let mut start = (offset - read_from) as usize;
+    if offset > 0 && buf[0] != b'\n' {
+        if let Some(i) = buf[1..].iter().position(|&b| b == b'\n') {
+            start = i + 2;
+        }
+    }
+    let mut stop = buf.len();
+    if end < total_size {
+        if let Some(i) = buf[start..].iter().rposition(|&b| b == b'\n') {
+            stop = start + i + 1;
+        }
+    }
+
+    Ok(LogChunk {
+        content: String::from_utf8_lossy(&buf[start..stop]).into_owned(),
+        offset: read_from + start as u64,
+        end_offset: read_from + stop as u64,
+        total_size,
+    })
+}
+
+/// Finds where the last `lines` lines start, looking back at most `max_bytes`.
+#[cfg(feature = "gui")]
+fn tail_offset(file: &mut (impl Read + Seek), total_size: u64, lines: usize, max_bytes: u64) -> std::io::Result<u64> {
+    if lines == 0 {
+        return Ok(total_size);
+    }
+    let floor = total_size.saturating_sub(max_bytes);
+    let mut block = vec![0u8; LOG_SCAN_BLOCK];
+    let mut pos = total_size;
+    let mut newlines = 0;
+    while pos > floor {
+        let len = (pos - floor).min(LOG_SCAN_BLOCK as u64) as usize;
+        pos -= len as u64;
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut block[..len])?;
+        for (i, &byte) in block[..len].iter().enumerate().rev() {
+            let at = pos + i as u64;
+            // A trailing newline ends the last line rather than starting a new one
+            if byte != b'\n' || at + 1 == total_size {
+                continue;
+            }
+            newlines += 1;
+            if newlines == lines {
+                return Ok(at + 1);
+            }
+        }
+    }
+    Ok(floor)
+}
+
+#[cfg(feature = "gui")]
+fn read_log_chunk(
+    path: &Path,
+    offset: Option<u64>,
+    limit: Option<u64>,
+    tail_lines: Option<usize>,
+) -> std::io::Result<LogChunk> {
+    let limit = limit.unwrap_or(LOG_CHUNK_DEFAULT_BYTES).clamp(1, LOG_CHUNK_MAX_BYTES);
+    // Compressed logs can't be seeked, so they are read whole
+    match log_compression::compressed_path(path) {
+        Some(gz_path) => {
+            let content = log_compression::read_compressed(&gz_path)?;
+            let total_size = content.len() as u64;
+            read_log_window(&mut Cursor::new(content), total_size, offset, limit, tail_lines)
+        }
+        None => {
+            let mut file = fs::File::open(path)?;
+            let total_size = file.metadata()?.len();
+            read_log_window(&mut file, total_size, offset, limit, tail_lines)
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+fn read_log_window(
+    file: &mut (impl Read + Seek),
+    total_size: u64,
+    offset: Option<u64>,
+    limit: u64,
+    tail_lines: Option<usize>,
+) -> std::io::Result<LogChunk> {
+    let offset = match tail_lines {
+        Some(lines) => tail_offset(file, total_size, lines, limit)?,
+        None => offset.unwrap_or(0),
+    };
+    read_log_range(file, total_size, offset, limit)
+}
+
+/// Reads part of a log file: `limit` bytes (256 KiB by default, 8 MiB at most)
+/// from `offset`, or the last `tail_lines` lines within that limit. Chunks are
+/// aligned to whole lines so successive reads can be joined.
 /// Uses spawn_blocking to avoid blocking the main thread.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
-pub async fn read_process_log_file(log_file_path: String) -> Result<String, String> {
+pub async fn read_process_log_file(
+    log_file_path: String,
+    offset: Option<u64>,
+    limit: Option<u64>,
+    tail_lines: Option<usize>,
+) -> Result<LogChunk, String> {
     tokio::task::spawn_blocking(move || {
-        fs::read_to_string(&log_file_path)
+        read_log_chunk(Path::new(&log_file_path), offset, limit, tail_lines)
             .map_err(|e| format!("Failed to read log file: {}", e))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
+
+/// Returns a log file's size and line count without loading it into memory.
+/// For a compressed log, the size is of its uncompressed content.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_log_file_info(log_file_path: String) -> Result<LogFileInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut file = log_compression::open_log(Path::new(&log_file_path))
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        let mut block = vec![0u8; LOG_SCAN_BLOCK];
+        let mut size: u64 = 0;
+        let mut newlines: u64 = 0;
+        let mut last_byte = b'\n';
+        loop {
+            let len = file
+                .read(&mut block)
+                .map_err(|e| format!("Failed to read log file: {}", e))?;
+            if len == 0 {
+                break;
+            }
+            size += len as u64;
+            newlines += block[..len].iter().filter(|&&b| b == b'\n').count() as u64;
+            last_byte = block[len - 1];
+        }
+        // Count an unterminated last line too
+        let line_count = newlines + u64::from(last_byte != b'\n');
+        Ok(LogFileInfo { size, line_count })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
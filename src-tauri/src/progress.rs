@@ -0,0 +1,206 @@
+//! Structured progress from agent output.
+//!
+//! Rules in the `progressRules` preference, keyed by agent ID, turn an
+//! agent's output lines into its current step, a percentage, and the file it
+//! is working on. Changes are emitted as `agent-progress` events.
+//!
+//! ```json
+//! "progressRules": {
+//!   "claude-code": [
+//!     { "pattern": "^Step (?P<current>\\d+) of (?P<total>\\d+): (?P<step>.+)$" },
+//!     { "json": { "step": "/tool_name", "file": "/tool_input/file_path" } }
+//!   ]
+//! }
+//! ```
+//!
+//! A pattern's named groups `step`, `percent`, `current`, `total`, and `file`
+//! fill the matching fields, with `current` and `total` giving the percentage
+//! when there is no `percent`. JSON rules read the same fields by JSON pointer
+//! from lines that are JSON objects. The first rule that yields anything for
+//! a line is used; fields it leaves out keep their last value.
+
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::ProgressRule;
+use crate::preferences::load_preferences_internal;
+use crate::process::OutputSink;
+use crate::redact;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentProgress {
+    pub step: Option<String>,
+    /// 0 to 100.
+    pub percent: Option<f64>,
+    pub file: Option<String>,
+}
+
+/// Payload of `agent-progress`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentProgressEvent {
+    pub process_id: String,
+    /// Set for build agents.
+    pub project_id: Option<String>,
+    pub story_id: Option<String>,
+    #[serde(flatten)]
+    pub progress: AgentProgress,
+}
+
+enum Matcher {
+    Pattern(Regex),
+    /// (field, JSON pointer) pairs
+    Json(Vec<(String, String)>),
+}
+
+/// Fields a rule read from one line, by name.
+type Captured = Vec<(String, String)>;
+
+impl Matcher {
+    fn capture(&self, line: &str) -> Captured {
+        match self {
+            Matcher::Pattern(re) => re
+                .captures(line)
+                .map(|caps| {
+                    re.capture_names()
+                        .flatten()
+                        .filter_map(|name| Some((name.to_string(), caps.name(name)?.as_str().trim().to_string())))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Matcher::Json(pointers) => {
+                if !line.trim_start().starts_with('{') {
+                    return Vec::new();
+                }
+                let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+                    return Vec::new();
+                };
+                pointers
+                    .iter()
+                    .filter_map(|(field, pointer)| {
+                        let text = match value.pointer(pointer)? {
+                            serde_json::Value::String(s) => s.clone(),
+                            serde_json::Value::Null => return None,
+                            other => other.to_string(),
+                        };
+                        Some((field.clone(), text))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Applies one agent's progress rules to its output.
+pub struct ProgressTracker {
+    matchers: Vec<Matcher>,
+    progress: AgentProgress,
+    project_id: Option<String>,
+    story_id: Option<String>,
+}
+
+impl ProgressTracker {
+    /// Compiles `rules`, skipping invalid ones. `None` when none are usable.
+    pub fn new(rules: &[ProgressRule]) -> Option<Self> {
+        let matchers: Vec<Matcher> = rules
+            .iter()
+            .filter_map(|rule| match (&rule.pattern, rule.json.is_empty()) {
+                (Some(pattern), _) => match Regex::new(pattern) {
+                    Ok(re) => Some(Matcher::Pattern(re)),
+                    Err(e) => {
+                        tracing::warn!("Ignoring invalid progress pattern {:?}: {}", pattern, e);
+                        None
+                    }
+                },
+                (None, false) => Some(Matcher::Json(
+                    rule.json.iter().map(|(field, pointer)| (field.clone(), pointer.clone())).collect(),
+                )),
+                (None, true) => None,
+            })
+            .collect();
+        (!matchers.is_empty()).then(|| Self {
+            matchers,
+            progress: AgentProgress::default(),
+            project_id: None,
+            story_id: None,
+        })
+    }
+
+    /// Tags this tracker's events with the build story the agent is running.
+    pub fn for_story(mut self, project_id: &str, story_id: &str) -> Self {
+        self.project_id = Some(project_id.to_string());
+        self.story_id = Some(story_id.to_string());
+        self
+    }
+
+    /// Applies the rules to `line`. Returns the progress if it changed.
+    pub fn apply(&mut self, line: &str) -> Option<AgentProgress> {
+        let captured = self
+            .matchers
+            .iter()
+            .map(|matcher| matcher.capture(line))
+            .find(|captured| !captured.is_empty())?;
+        let get = |name: &str| {
+            captured
+                .iter()
+                .find(|(field, value)| field == name && !value.is_empty())
+                .map(|(_, value)| value.as_str())
+        };
+        let number = |name: &str| get(name).and_then(|v| v.trim_end_matches('%').trim().parse::<f64>().ok());
+
+        let mut next = self.progress.clone();
+        if let Some(step) = get("step") {
+            next.step = Some(redact::for_output(step.to_string()));
+        }
+        if let Some(file) = get("file") {
+            next.file = Some(file.to_string());
+        }
+        let percent = number("percent").or_else(|| match (number("current"), number("total")) {
+            (Some(current), Some(total)) if total > 0.0 => Some(current / total * 100.0),
+            _ => None,
+        });
+        if let Some(percent) = percent {
+            next.percent = Some(percent.clamp(0.0, 100.0));
+        }
+
+        if next == self.progress {
+            return None;
+        }
+        self.progress = next.clone();
+        Some(next)
+    }
+}
+
+/// A tracker for `agent_id`'s configured rules, if it has any.
+pub fn tracker_for(app: &AppHandle, agent_id: &str) -> Option<ProgressTracker> {
+    let prefs = load_preferences_internal(app).ok()?;
+    ProgressTracker::new(prefs.progress_rules.get(agent_id)?)
+}
+
+/// Wraps `sink` so each line also goes through `tracker`, emitting
+/// `agent-progress` for `process_id` when the progress changes.
+pub fn observe(app: AppHandle, process_id: String, tracker: ProgressTracker, sink: Option<OutputSink>) -> OutputSink {
+    let tracker = Mutex::new(tracker);
+    Arc::new(move |stream, line| {
+        if let Ok(mut tracker) = tracker.lock() {
+            if let Some(progress) = tracker.apply(line) {
+                let _ = app.emit(
+                    "agent-progress",
+                    AgentProgressEvent {
+                        process_id: process_id.clone(),
+                        project_id: tracker.project_id.clone(),
+                        story_id: tracker.story_id.clone(),
+                        progress,
+                    },
+                );
+            }
+        }
+        if let Some(sink) = &sink {
+            sink(stream, line);
+        }
+    })
+}
@@ -0,0 +1,357 @@
+//! Portable project bundles (`.ideatepkg`).
+//!
+//! A bundle is one JSON file holding what a teammate needs to pick up an
+//! autonomous build: every document in `.ideate` (PRD, design, state, costs,
+//! the build journal, and reports), a `git bundle` of the project's branches
+//! including the story branches, and where and by whom it was exported.
+//!
+//! Importing creates a new project from a bundle: the repository is cloned
+//! from the git bundle, its branches are restored, the `.ideate` documents
+//! are written back, and the project is added to the project list. Secret
+//! values are never bundled; env.json only names them.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::git_binary;
+use crate::models::StoredProject;
+use crate::projects::{add_project, load_projects};
+use crate::utils::get_ideate_dir;
+
+/// Bumped when the bundle layout changes incompatibly.
+const BUNDLE_VERSION: u32 = 1;
+
+/// Larger `.ideate` files are left out.
+const MAX_FILE_BYTES: u64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleProvenance {
+    pub app_version: String,
+    pub exported_at: String,
+    /// `user.name <user.email>` from the project's git config.
+    #[serde(default)]
+    pub exported_by: Option<String>,
+    pub source_path: String,
+    /// Branch checked out at export.
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub head: Option<String>,
+    /// Local branches in the git bundle.
+    #[serde(default)]
+    pub branches: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBundle {
+    pub bundle_version: u32,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub stack_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_stack_ids: Vec<String>,
+    pub provenance: BundleProvenance,
+    /// Base64 contents of the `.ideate` files, by path relative to it.
+    pub files: BTreeMap<String, String>,
+    /// Base64 `git bundle` of the branches, when the project has commits.
+    #[serde(default)]
+    pub git_bundle: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleExportSummary {
+    pub path: String,
+    pub files: usize,
+    pub branches: Vec<String>,
+    /// Files over the size limit.
+    pub skipped: Vec<String>,
+    pub bytes: u64,
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = git_binary::command()
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run git {}: {}", args.first().unwrap_or(&""), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Collects the files under `dir` into `files`, keyed by path relative to
+/// `root` with `/` separators.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut BTreeMap<String, String>,
+    skipped: &mut Vec<String>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_files(root, &path, files, skipped)?;
+            continue;
+        }
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        // Locks and half-written temp files belong to writers on this machine
+        if relative.ends_with(".lock") || relative.ends_with(".tmp") {
+            continue;
+        }
+        if metadata.len() > MAX_FILE_BYTES {
+            skipped.push(relative);
+            continue;
+        }
+        let content = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", relative, e))?;
+        files.insert(relative, base64::engine::general_purpose::STANDARD.encode(content));
+    }
+    Ok(())
+}
+
+/// A git bundle and the names of the branches in it.
+type GitBundle = (Vec<u8>, Vec<String>);
+
+/// Bundles every local branch. Returns the bundle and the branch names, or
+/// `None` when the project isn't a repository with commits.
+fn create_git_bundle(project_path: &Path) -> Result<Option<GitBundle>, String> {
+    if git(project_path, &["rev-parse", "--verify", "HEAD"]).is_err() {
+        return Ok(None);
+    }
+    let branches: Vec<String> = git(project_path, &["for-each-ref", "--format=%(refname:short)", "refs/heads"])?
+        .lines()
+        .map(String::from)
+        .collect();
+    let bundle_path = std::env::temp_dir().join(format!("ideate-{}.bundle", uuid::Uuid::new_v4().simple()));
+    let bundle_arg = bundle_path.to_string_lossy().to_string();
+    let created = git(project_path, &["bundle", "create", &bundle_arg, "--branches"]);
+    let bytes = created.and_then(|_| fs::read(&bundle_path).map_err(|e| format!("Failed to read git bundle: {}", e)));
+    let _ = fs::remove_file(&bundle_path);
+    Ok(Some((bytes?, branches)))
+}
+
+fn find_project(app: &AppHandle, project_id: &str) -> Result<StoredProject, String> {
+    load_projects(app.clone())?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| format!("Project {} not found", project_id))
+}
+
+/// Writes a project, its `.ideate` documents, and its branches to a single
+/// `.ideatepkg` file at `path`.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_project_bundle(
+    app: AppHandle,
+    project_id: String,
+    path: String,
+) -> Result<BundleExportSummary, String> {
+    let project = find_project(&app, &project_id)?;
+    let app_version = app.package_info().version.to_string();
+    tokio::task::spawn_blocking(move || {
+        let project_path = PathBuf::from(&project.path);
+        let ideate_dir = get_ideate_dir(&project.path);
+        if !ideate_dir.is_dir() {
+            return Err(format!("{} has no .ideate directory", project.path));
+        }
+
+        let mut files = BTreeMap::new();
+        let mut skipped = Vec::new();
+        collect_files(&ideate_dir, &ideate_dir, &mut files, &mut skipped)?;
+        let git_bundle = create_git_bundle(&project_path)?;
+        let branches = git_bundle.as_ref().map(|(_, b)| b.clone()).unwrap_or_default();
+
+        let name = git(&project_path, &["config", "user.name"]).ok().filter(|n| !n.is_empty());
+        let email = git(&project_path, &["config", "user.email"]).ok().filter(|e| !e.is_empty());
+        let exported_by = match (name, email) {
+            (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+            (name, email) => name.or(email),
+        };
+        let bundle = ProjectBundle {
+            bundle_version: BUNDLE_VERSION,
+            name: project.name.clone(),
+            description: project.description.clone(),
+            stack_id: project.stack_id.clone(),
+            extra_stack_ids: project.extra_stack_ids.clone(),
+            provenance: BundleProvenance {
+                app_version,
+                exported_at: chrono::Utc::now().to_rfc3339(),
+                exported_by,
+                source_path: project.path.clone(),
+                branch: git(&project_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok(),
+                head: git(&project_path, &["rev-parse", "HEAD"]).ok(),
+                branches: branches.clone(),
+            },
+            files,
+            git_bundle: git_bundle.map(|(bytes, _)| base64::engine::general_purpose::STANDARD.encode(bytes)),
+        };
+        let json = serde_json::to_string(&bundle).map_err(|e| format!("Failed to serialize bundle: {}", e))?;
+        fs::write(&path, &json).map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+        tracing::info!("Exported project {} to {}", project.name, path);
+        Ok(BundleExportSummary {
+            path,
+            files: bundle.files.len(),
+            branches,
+            skipped,
+            bytes: json.len() as u64,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// A bundled file's path under `.ideate`, refusing any that would escape it.
+fn safe_relative_path(path: &str) -> Result<PathBuf, String> {
+    let relative = PathBuf::from(path);
+    if relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(relative)
+    } else {
+        Err(format!("Invalid path in bundle: {}", path))
+    }
+}
+
+/// Clones the bundled repository into `project_dir` and recreates its local
+/// branches.
+fn restore_repository(project_dir: &Path, git_bundle: &[u8], bundle: &ProjectBundle) -> Result<(), String> {
+    let bundle_path = std::env::temp_dir().join(format!("ideate-{}.bundle", uuid::Uuid::new_v4().simple()));
+    fs::write(&bundle_path, git_bundle).map_err(|e| format!("Failed to write git bundle: {}", e))?;
+    let parent = project_dir.parent().unwrap_or(Path::new("."));
+    let mut args = vec!["clone".to_string()];
+    if let Some(branch) = bundle.provenance.branch.as_deref().filter(|b| *b != "HEAD") {
+        args.extend(["--branch".to_string(), branch.to_string()]);
+    }
+    args.push(bundle_path.to_string_lossy().to_string());
+    args.push(project_dir.to_string_lossy().to_string());
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let cloned = git(parent, &args);
+    let _ = fs::remove_file(&bundle_path);
+    cloned?;
+
+    let current = git(project_dir, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap_or_default();
+    for branch in bundle.provenance.branches.iter().filter(|b| **b != current) {
+        if let Err(e) = git(project_dir, &["branch", "--no-track", branch, &format!("origin/{}", branch)]) {
+            tracing::warn!("Could not restore branch {}: {}", branch, e);
+        }
+    }
+    // The remote was a temp file
+    let _ = git(project_dir, &["remote", "remove", "origin"]);
+    Ok(())
+}
+
+/// Creates a project in a new directory under `destination` from a bundle
+/// written by `export_project_bundle`, and adds it to the project list.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_project_bundle(app: AppHandle, path: String, destination: String) -> Result<StoredProject, String> {
+    let project = tokio::task::spawn_blocking(move || {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+        let bundle: ProjectBundle =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse bundle: {}", e))?;
+        if bundle.bundle_version > BUNDLE_VERSION {
+            return Err(format!(
+                "Failed to import: bundle version {} is newer than this version of Ideate supports",
+                bundle.bundle_version
+            ));
+        }
+
+        let dir_name: String = bundle
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect();
+        let dir_name = dir_name.trim_matches('-');
+        let project_dir = Path::new(&destination).join(if dir_name.is_empty() { "imported-project" } else { dir_name });
+        if project_dir.exists() {
+            return Err(format!("Directory '{}' already exists", project_dir.display()));
+        }
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        match &bundle.git_bundle {
+            Some(encoded) => {
+                let bytes = engine
+                    .decode(encoded)
+                    .map_err(|e| format!("Failed to decode git bundle: {}", e))?;
+                restore_repository(&project_dir, &bytes, &bundle)?;
+            }
+            None => {
+                fs::create_dir_all(&project_dir).map_err(|e| format!("Failed to create project directory: {}", e))?;
+                git(&project_dir, &["init"])?;
+            }
+        }
+
+        let project_path = project_dir.to_string_lossy().to_string();
+        let ideate_dir = get_ideate_dir(&project_path);
+        for (relative, encoded) in &bundle.files {
+            let target = ideate_dir.join(safe_relative_path(relative)?);
+            let bytes = engine
+                .decode(encoded)
+                .map_err(|e| format!("Failed to decode {}: {}", relative, e))?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::write(&target, bytes).map_err(|e| format!("Failed to write {}: {}", relative, e))?;
+        }
+
+        tracing::info!(
+            "Imported project {} from {} (exported {} by {})",
+            bundle.name,
+            path,
+            bundle.provenance.exported_at,
+            bundle.provenance.exported_by.as_deref().unwrap_or("unknown")
+        );
+        Ok(StoredProject {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: bundle.name,
+            description: bundle.description,
+            path: project_path,
+            status: "idle".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            stack_id: bundle.stack_id,
+            extra_stack_ids: bundle.extra_stack_ids,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    add_project(app, project.clone())?;
+    Ok(project)
+}
+
+/// Reads a bundle's provenance without importing it.
+#[tauri::command]
+pub fn inspect_project_bundle(path: String) -> Result<BundleProvenance, String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Header {
+        bundle_version: u32,
+        provenance: BundleProvenance,
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let header: Header = serde_json::from_str(&content).map_err(|e| format!("Failed to parse bundle: {}", e))?;
+    if header.bundle_version > BUNDLE_VERSION {
+        return Err(format!("Bundle version {} is newer than this version of Ideate supports", header.bundle_version));
+    }
+    Ok(header.provenance)
+}
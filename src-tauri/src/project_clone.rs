@@ -0,0 +1,183 @@
+//! Cloning a repository as a new project.
+//!
+//! `import_project` clones when given a URL. HTTPS remotes authenticate with a
+//! stored token, the GitHub token for github.com or `git.<host>.token` for
+//! other hosts, handed to git through its environment so it never lands in
+//! the clone's config. SSH remotes use the user's ssh-agent. While git runs,
+//! its progress is emitted as `clone-progress` events.
+
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::process::Stdio;
+
+use base64::Engine;
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::git_binary;
+use crate::secrets;
+
+lazy_static::lazy_static! {
+    static ref PROGRESS_RE: Regex = Regex::new(r"^(?:remote:\s*)?([A-Za-z][A-Za-z ]+):\s+(\d{1,3})%").unwrap();
+    static ref SCP_LIKE_RE: Regex = Regex::new(r"^[\w.-]+@([\w.-]+):").unwrap();
+}
+
+/// Progress of a clone, emitted as `clone-progress`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneProgressEvent {
+    pub destination: String,
+    /// Git's phase, e.g. "Receiving objects" or "Resolving deltas"
+    pub phase: String,
+    pub percent: Option<u32>,
+    pub message: String,
+}
+
+fn is_ssh(url: &str) -> bool {
+    url.starts_with("ssh://") || SCP_LIKE_RE.is_match(url)
+}
+
+/// Host of an HTTPS, `ssh://`, or `user@host:path` remote.
+fn host_of(url: &str) -> Option<String> {
+    if let Some(caps) = SCP_LIKE_RE.captures(url) {
+        return caps.get(1).map(|m| m.as_str().to_lowercase());
+    }
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// Environment that authenticates git against the remote without prompting.
+fn credential_env(app: &AppHandle, url: &str) -> Result<Vec<(String, String)>, String> {
+    // Never block on a username/password prompt nobody can see
+    let mut env = vec![("GIT_TERMINAL_PROMPT".to_string(), "0".to_string())];
+
+    if is_ssh(url) {
+        // The inherited SSH_AUTH_SOCK lets ssh use the user's agent
+        env.push((
+            "GIT_SSH_COMMAND".to_string(),
+            "ssh -o BatchMode=yes -o StrictHostKeyChecking=accept-new".to_string(),
+        ));
+        return Ok(env);
+    }
+
+    let Some(host) = host_of(url) else {
+        return Ok(env);
+    };
+    let token = if host == "github.com" {
+        crate::integrations::github::resolve_token(app).ok()
+    } else {
+        secrets::get_secret(app, &format!("git.{}.token", host))?.filter(|t| !t.is_empty())
+    };
+    if let Some(token) = token {
+        let basic = base64::engine::general_purpose::STANDARD.encode(format!("x-access-token:{}", token));
+        env.extend([
+            ("GIT_CONFIG_COUNT".to_string(), "1".to_string()),
+            ("GIT_CONFIG_KEY_0".to_string(), "http.extraHeader".to_string()),
+            ("GIT_CONFIG_VALUE_0".to_string(), format!("Authorization: Basic {}", basic)),
+        ]);
+    }
+    Ok(env)
+}
+
+/// Clones `url` into `destination`, which must not exist or be empty.
+/// Blocks until git exits; a failed clone leaves nothing behind.
+pub fn clone(app: &AppHandle, url: &str, destination: &str) -> Result<(), String> {
+    git_binary::require()?;
+    let dest = Path::new(destination);
+    let existed = dest.exists();
+    if existed
+        && fs::read_dir(dest)
+            .map_err(|e| format!("Failed to read '{}': {}", destination, e))?
+            .next()
+            .is_some()
+    {
+        return Err(format!("'{}' already exists and is not empty", destination));
+    }
+
+    let mut child = git_binary::command()
+        .args(["clone", "--progress", "--", url, destination])
+        .envs(credential_env(app, url)?)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    // Git redraws progress with carriage returns, so split on those too
+    let mut output = Vec::new();
+    let mut line = Vec::new();
+    let mut last_reported: Option<(String, u32)> = None;
+    if let Some(stderr) = child.stderr.take() {
+        for byte in BufReader::new(stderr).bytes().map_while(Result::ok) {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line).trim().to_string();
+            line.clear();
+            if text.is_empty() {
+                continue;
+            }
+
+            let progress = PROGRESS_RE.captures(&text).and_then(|caps| {
+                Some((caps.get(1)?.as_str().trim().to_string(), caps.get(2)?.as_str().parse::<u32>().ok()?))
+            });
+            match progress {
+                Some(progress) if last_reported.as_ref() == Some(&progress) => continue,
+                Some((phase, percent)) => {
+                    last_reported = Some((phase.clone(), percent));
+                    let _ = app.emit(
+                        "clone-progress",
+                        CloneProgressEvent {
+                            destination: destination.to_string(),
+                            phase,
+                            percent: Some(percent),
+                            message: text,
+                        },
+                    );
+                }
+                None => {
+                    let _ = app.emit(
+                        "clone-progress",
+                        CloneProgressEvent {
+                            destination: destination.to_string(),
+                            phase: String::new(),
+                            percent: None,
+                            message: text.clone(),
+                        },
+                    );
+                    output.push(text);
+                }
+            }
+        }
+    }
+
+    let status = child.wait().map_err(|e| format!("Failed to wait for git: {}", e))?;
+    if status.success() {
+        return Ok(());
+    }
+
+    if existed {
+        if let Ok(entries) = fs::read_dir(dest) {
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                let _ = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+            }
+        }
+    } else {
+        let _ = fs::remove_dir_all(dest);
+    }
+
+    let reason = output
+        .iter()
+        .rev()
+        .find(|l| l.starts_with("fatal:") || l.starts_with("ERROR:"))
+        .or(output.last())
+        .cloned()
+        .unwrap_or_else(|| format!("git exited with {}", status));
+    Err(format!("Failed to clone {}: {}", url, reason))
+}
@@ -0,0 +1,305 @@
+//! Project environment variables.
+//!
+//! `.ideate/env.json` lists the variables that agents, dev servers, and
+//! terminals started in a project get. A variable marked secret keeps only
+//! its name there; its value lives in the app's secret store (see `secrets`),
+//! so env.json is safe to commit. Secret values are masked in logs and output
+//! once they have been injected (see `redact::register_literals`).
+//!
+//! With `syncDotenv` on, saving also writes the variables to the project's
+//! `.env` for tools that read one, and makes sure git ignores it.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
+use crate::file_lock;
+use crate::redact;
+use crate::secrets;
+#[cfg(feature = "gui")]
+use crate::utils::get_ideate_dir;
+
+#[cfg(feature = "gui")]
+const HEADER: &str = "# Written by Ideate from .ideate/env.json. Edit the variables in Ideate; changes here are overwritten.";
+
+lazy_static::lazy_static! {
+    static ref NAME_RE: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnvFile {
+    /// Names this project's secrets in the secret store, so they follow the
+    /// project when it moves.
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    sync_dotenv: bool,
+    #[serde(default)]
+    variables: Vec<StoredVariable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredVariable {
+    name: String,
+    /// Unset for secrets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(default)]
+    secret: bool,
+}
+
+/// A variable as the frontend sees it. Secret values are never sent.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVariable {
+    pub name: String,
+    pub value: Option<String>,
+    pub secret: bool,
+    /// Whether a value is set; for a secret, whether the store has one.
+    pub has_value: bool,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEnv {
+    pub sync_dotenv: bool,
+    pub variables: Vec<EnvVariable>,
+}
+
+/// A variable to save. A secret saved without a value keeps the stored one.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVariableInput {
+    pub name: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+#[cfg(feature = "gui")]
+fn env_path(project_path: &str) -> PathBuf {
+    get_ideate_dir(project_path).join("env.json")
+}
+
+fn secret_key(env_id: &str, name: &str) -> String {
+    format!("env.{}.{}", env_id, name)
+}
+
+fn read_env_file(path: &Path) -> Result<Option<EnvFile>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read env.json: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse env.json: {}", e))
+}
+
+/// Values of the variables in `file`, secrets read with `get_secret`.
+fn resolve_file(file: &EnvFile, get_secret: impl Fn(&str) -> Option<String>) -> Vec<(String, String)> {
+    file.variables
+        .iter()
+        .filter_map(|v| {
+            let value = if v.secret {
+                get_secret(&secret_key(&file.id, &v.name))
+            } else {
+                v.value.clone()
+            };
+            value.map(|value| (v.name.clone(), value))
+        })
+        .collect()
+}
+
+/// Finds the env.json of the project `directory` is in, which may be one of
+/// its worktrees.
+fn find_env_file(directory: &str) -> Option<PathBuf> {
+    Path::new(directory)
+        .ancestors()
+        .map(|dir| dir.join(".ideate").join("env.json"))
+        .find(|path| path.is_file())
+}
+
+/// Variables for a process started in `directory`, with secrets read from
+/// the store in `app_data_dir`. Secret values are registered for masking.
+pub(crate) fn resolve_in(app_data_dir: Option<&Path>, directory: &str) -> HashMap<String, String> {
+    let Some(file) = find_env_file(directory).and_then(|path| read_env_file(&path).ok().flatten()) else {
+        return HashMap::new();
+    };
+    let secret_names: HashSet<&str> = file.variables.iter().filter(|v| v.secret).map(|v| v.name.as_str()).collect();
+    let resolved = resolve_file(&file, |key| {
+        app_data_dir.and_then(|dir| secrets::get_secret_in(dir, key).ok().flatten())
+    });
+    redact::register_literals(
+        resolved
+            .iter()
+            .filter(|(name, _)| secret_names.contains(name.as_str()))
+            .map(|(_, value)| value.clone()),
+    );
+    resolved.into_iter().collect()
+}
+
+/// Variables for a process the app starts in `directory`.
+#[cfg(feature = "gui")]
+pub(crate) fn resolve(app: &AppHandle, directory: &str) -> HashMap<String, String> {
+    resolve_in(app_data_dir(app).ok().as_deref(), directory)
+}
+
+#[cfg(feature = "gui")]
+fn dotenv_value(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:@+,".contains(c)) {
+        value.to_string()
+    } else {
+        format!(
+            "\"{}\"",
+            value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+        )
+    }
+}
+
+/// Adds `.env` to the project's .gitignore unless it's already there.
+#[cfg(feature = "gui")]
+fn ignore_dotenv(project_path: &str) -> Result<(), String> {
+    let path = Path::new(project_path).join(".gitignore");
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    if content.lines().any(|l| matches!(l.trim(), ".env" | "/.env" | ".env*" | ".env.*")) {
+        return Ok(());
+    }
+    let separator = if content.is_empty() || content.ends_with('\n') { "" } else { "\n" };
+    fs::write(&path, format!("{}{}.env\n", content, separator))
+        .map_err(|e| format!("Failed to update .gitignore: {}", e))
+}
+
+/// Writes the variables to the project's `.env`, after making sure git
+/// ignores it.
+#[cfg(feature = "gui")]
+fn write_dotenv(app: &AppHandle, project_path: &str, file: &EnvFile) -> Result<(), String> {
+    ignore_dotenv(project_path)?;
+    let lines: Vec<String> = resolve_file(file, |key| secrets::get_secret(app, key).ok().flatten())
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, dotenv_value(&value)))
+        .collect();
+    let path = Path::new(project_path).join(".env");
+    fs::write(&path, format!("{}\n{}\n", HEADER, lines.join("\n")))
+        .map_err(|e| format!("Failed to write .env: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict .env permissions: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Loads the project's variables. Secret values are not included.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn load_project_env(app: AppHandle, project_path: String) -> Result<ProjectEnv, String> {
+    let file = read_env_file(&env_path(&project_path))?.unwrap_or_default();
+    let variables = file
+        .variables
+        .iter()
+        .map(|v| EnvVariable {
+            name: v.name.clone(),
+            value: if v.secret { None } else { v.value.clone() },
+            secret: v.secret,
+            has_value: if v.secret {
+                secrets::has_secret(app.clone(), secret_key(&file.id, &v.name)).unwrap_or(false)
+            } else {
+                v.value.is_some()
+            },
+        })
+        .collect();
+    Ok(ProjectEnv {
+        sync_dotenv: file.sync_dotenv,
+        variables,
+    })
+}
+
+/// Replaces the project's variables. Secret values go to the secret store,
+/// and the secrets of removed variables are deleted from it.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn save_project_env(
+    app: AppHandle,
+    project_path: String,
+    variables: Vec<EnvVariableInput>,
+    sync_dotenv: bool,
+) -> Result<ProjectEnv, String> {
+    let mut names = HashSet::new();
+    for variable in &variables {
+        if !NAME_RE.is_match(&variable.name) {
+            return Err(format!("Invalid variable name: {}", variable.name));
+        }
+        if !names.insert(variable.name.as_str()) {
+            return Err(format!("Duplicate variable: {}", variable.name));
+        }
+    }
+
+    let path = env_path(&project_path);
+    fs::create_dir_all(get_ideate_dir(&project_path))
+        .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+    let (file, stale) = file_lock::update(&path, |content| {
+        let mut file: EnvFile = match content {
+            Some(content) => serde_json::from_str(content).map_err(|e| format!("Failed to parse env.json: {}", e))?,
+            None => EnvFile::default(),
+        };
+        if file.id.is_empty() {
+            file.id = uuid::Uuid::new_v4().simple().to_string();
+        }
+        // Secrets of variables that are gone or no longer secret
+        let stale: Vec<String> = file
+            .variables
+            .iter()
+            .filter(|old| old.secret && !variables.iter().any(|v| v.secret && v.name == old.name))
+            .map(|old| secret_key(&file.id, &old.name))
+            .collect();
+        file.sync_dotenv = sync_dotenv;
+        file.variables = variables
+            .iter()
+            .map(|v| StoredVariable {
+                name: v.name.clone(),
+                value: if v.secret { None } else { v.value.clone() },
+                secret: v.secret,
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize env.json: {}", e))?;
+        Ok((json, (file.clone(), stale)))
+    })?;
+
+    for variable in variables.iter().filter(|v| v.secret) {
+        if let Some(value) = &variable.value {
+            secrets::set_secret(app.clone(), secret_key(&file.id, &variable.name), value.clone())?;
+        }
+    }
+    for key in stale {
+        secrets::delete_secret(app.clone(), key)?;
+    }
+    if file.sync_dotenv {
+        write_dotenv(&app, &project_path, &file)?;
+    }
+    load_project_env(app, project_path)
+}
+
+/// Writes the project's variables to its `.env` now, making sure git
+/// ignores it.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn sync_project_env(app: AppHandle, project_path: String) -> Result<(), String> {
+    let file = read_env_file(&env_path(&project_path))?
+        .ok_or_else(|| "This project has no environment variables".to_string())?;
+    write_dotenv(&app, &project_path, &file)
+}
@@ -0,0 +1,194 @@
+//! Storage for the registry of projects the app knows about.
+//!
+//! Each project is one small file, `projects/<id>.json` in the app data
+//! directory, so a corrupted write or a sync conflict only ever affects one
+//! project. `projects-index.json` records the sidebar order; it is derived
+//! data, rebuilt from the project files whenever it is missing, unreadable,
+//! or out of step with them.
+//!
+//! The registry used to be a single `projects.json`. The first load after
+//! upgrading splits it into project files and keeps the original as
+//! `projects.json.migrated`.
+
+#[cfg(feature = "gui")]
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_lock;
+use crate::migrations;
+use crate::models::StoredProject;
+
+const PROJECTS_DIR: &str = "projects";
+const INDEX_FILE: &str = "projects-index.json";
+const LEGACY_FILE: &str = "projects.json";
+
+/// Contents of `projects-index.json`.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryIndex {
+    /// Project IDs in display order.
+    order: Vec<String>,
+}
+
+fn projects_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(PROJECTS_DIR)
+}
+
+fn index_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(INDEX_FILE)
+}
+
+/// The file for a project. IDs are UUIDs; anything else is made file-safe.
+fn project_path(app_data_dir: &Path, id: &str) -> PathBuf {
+    let name: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    projects_dir(app_data_dir).join(format!("{}.json", name))
+}
+
+/// Reads every project file, skipping (and logging) any that can't be parsed.
+fn read_project_files(app_data_dir: &Path) -> Vec<StoredProject> {
+    let Ok(entries) = fs::read_dir(projects_dir(app_data_dir)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "json")
+                && !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| tracing::warn!("Failed to read {}: {}", path.display(), e))
+                .ok()?;
+            migrations::PROJECT
+                .parse::<StoredProject>(&content)
+                .map(|(project, _)| project)
+                .map_err(|e| tracing::warn!("Skipping project file {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect()
+}
+
+fn read_index(app_data_dir: &Path) -> Option<RegistryIndex> {
+    let content = fs::read_to_string(index_path(app_data_dir)).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| tracing::warn!("Rebuilding {}: {}", INDEX_FILE, e))
+        .ok()
+}
+
+/// Sorts projects by the index. Projects it doesn't list, such as ones
+/// another machine synced in, follow in creation order.
+fn apply_order(projects: &mut [StoredProject], index: &RegistryIndex) {
+    projects.sort_by(|a, b| {
+        let position = |p: &StoredProject| index.order.iter().position(|id| *id == p.id).unwrap_or(usize::MAX);
+        position(a)
+            .cmp(&position(b))
+            .then_with(|| a.created_at.cmp(&b.created_at))
+    });
+}
+
+fn index_of(projects: &[StoredProject]) -> RegistryIndex {
+    RegistryIndex {
+        order: projects.iter().map(|p| p.id.clone()).collect(),
+    }
+}
+
+fn write_index(app_data_dir: &Path, index: &RegistryIndex) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize {}: {}", INDEX_FILE, e))?;
+    file_lock::write_atomic(&index_path(app_data_dir), &json)
+}
+
+/// Writes a project's file unless it already holds the same content.
+fn write_project(app_data_dir: &Path, project: &StoredProject) -> Result<(), String> {
+    let path = project_path(app_data_dir, &project.id);
+    let json = migrations::PROJECT.to_json(project)?;
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == json) {
+        return Ok(());
+    }
+    file_lock::write_atomic(&path, &json)
+}
+
+/// Splits a legacy `projects.json` into project files. Runs under the index
+/// lock.
+fn migrate_legacy(app_data_dir: &Path) -> Result<(), String> {
+    let legacy = app_data_dir.join(LEGACY_FILE);
+    if !legacy.exists() {
+        return Ok(());
+    }
+    let projects: Vec<StoredProject> = migrations::PROJECTS.load(&legacy)?.unwrap_or_default();
+    fs::create_dir_all(projects_dir(app_data_dir))
+        .map_err(|e| format!("Failed to create projects directory: {}", e))?;
+    for project in &projects {
+        write_project(app_data_dir, project)?;
+    }
+    let mut merged = read_project_files(app_data_dir);
+    apply_order(&mut merged, &index_of(&projects));
+    write_index(app_data_dir, &index_of(&merged))?;
+    fs::rename(&legacy, app_data_dir.join(format!("{}.migrated", LEGACY_FILE)))
+        .map_err(|e| format!("Failed to retire {}: {}", LEGACY_FILE, e))?;
+    tracing::info!("Split {} into {} project files", LEGACY_FILE, projects.len());
+    Ok(())
+}
+
+/// Loads all registered projects in display order, rebuilding the index if
+/// it no longer matches the project files.
+pub fn load(app_data_dir: &Path) -> Result<Vec<StoredProject>, String> {
+    if app_data_dir.join(LEGACY_FILE).exists() {
+        let _lock = file_lock::acquire(&index_path(app_data_dir))?;
+        migrate_legacy(app_data_dir)?;
+    }
+
+    let mut projects = read_project_files(app_data_dir);
+    let index = read_index(app_data_dir);
+    apply_order(&mut projects, index.as_ref().unwrap_or(&RegistryIndex::default()));
+
+    let rebuilt = index_of(&projects);
+    if index.as_ref() != Some(&rebuilt) && (index.is_some() || !projects.is_empty()) {
+        // Best effort: the order is already right in memory
+        if let Ok(_lock) = file_lock::acquire(&index_path(app_data_dir)) {
+            if let Err(e) = write_index(app_data_dir, &rebuilt) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+    Ok(projects)
+}
+
+/// Applies a change to the registry under its lock, re-reading it first so
+/// edits from other windows or the CLI are kept. Only the project files that
+/// changed are written. Returns the updated list.
+#[cfg(feature = "gui")]
+pub fn update(
+    app_data_dir: &Path,
+    apply: impl FnOnce(&mut Vec<StoredProject>) -> Result<(), String>,
+) -> Result<Vec<StoredProject>, String> {
+    let _lock = file_lock::acquire(&index_path(app_data_dir))?;
+    migrate_legacy(app_data_dir)?;
+
+    let mut projects = read_project_files(app_data_dir);
+    if let Some(index) = read_index(app_data_dir) {
+        apply_order(&mut projects, &index);
+    }
+    let before: HashSet<String> = projects.iter().map(|p| p.id.clone()).collect();
+    apply(&mut projects)?;
+
+    fs::create_dir_all(projects_dir(app_data_dir))
+        .map_err(|e| format!("Failed to create projects directory: {}", e))?;
+    for project in &projects {
+        write_project(app_data_dir, project)?;
+    }
+    let after: HashSet<&str> = projects.iter().map(|p| p.id.as_str()).collect();
+    for id in before.iter().filter(|id| !after.contains(id.as_str())) {
+        let path = project_path(app_data_dir, id);
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+    write_index(app_data_dir, &index_of(&projects))?;
+    Ok(projects)
+}
@@ -1,23 +1,40 @@
 //! Project, PRD, and state management commands.
 
+#[cfg(feature = "gui")]
+use std::collections::HashMap;
 use std::fs;
+#[cfg(feature = "gui")]
 use std::path::PathBuf;
+#[cfg(feature = "gui")]
 use std::process::Command;
-use tauri::{AppHandle, Manager};
-
-use crate::models::{
-    CostHistory, CreateProjectResult, Design, Prd, ProjectConfig, ProjectIdea, ProjectSettings,
-    ProjectState, StoredProject,
-};
-use crate::utils::{get_ideate_dir, sanitize_json};
+#[cfg(feature = "gui")]
+use serde_json::{Map, Value};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+use crate::models::{CostHistory, Design, Prd, ProjectConfig, ProjectSettings, ProjectState};
+#[cfg(feature = "gui")]
+use crate::models::{CreateProjectResult, ProjectIdea, ProjectUpdate, StoredProject};
+use crate::file_lock;
+#[cfg(feature = "gui")]
+use crate::git_binary;
+use crate::migrations;
+use crate::prd_recovery;
+#[cfg(feature = "gui")]
+use crate::project_registry;
+use crate::utils::{get_ideate_dir, parse_lenient_json};
 
 // ============================================================================
 // Project Management
 // ============================================================================
 
 /// Creates a new project with the given name and description.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn create_project(
+    app: AppHandle,
     name: String,
     description: String,
     parent_path: String,
@@ -44,7 +61,17 @@ pub fn create_project(
         agent: None,
         autonomy: "autonomous".to_string(),
         build_mode: Some("ralph".to_string()),
+        tunnel_provider: None,
+        mute_notifications: false,
+        exclude_stack_snippets: false,
+        hooks: Default::default(),
+        e2e: None,
+        verification: None,
+        metadata_history: None,
+        routing: None,
+        metadata: HashMap::new(),
         created_at: chrono::Utc::now().to_rfc3339(),
+        extra: Default::default(),
     };
     
     let config_path = ideate_dir.join("config.json");
@@ -55,7 +82,7 @@ pub fn create_project(
         .map_err(|e| format!("Failed to write config: {}", e))?;
     
     // Initialize git repository
-    Command::new("git")
+    git_binary::command()
         .args(["init"])
         .current_dir(&project_dir)
         .output()
@@ -86,12 +113,18 @@ target/
 # Logs
 *.log
 npm-debug.log*
+
+# Ideate write locks
+.ideate/*.lock
+
+# Ideate state backups from schema upgrades
+.ideate/*.bak
 "#;
     fs::write(project_dir.join(".gitignore"), gitignore_content)
         .map_err(|e| format!("Failed to create .gitignore: {}", e))?;
     
     // Stage and create initial commit (required for git worktrees)
-    Command::new("git")
+    git_binary::command()
         .args(["add", "-A"])
         .current_dir(&project_dir)
         .output()
@@ -99,29 +132,58 @@ npm-debug.log*
     
     // Try to commit - this may fail if git user is not configured, which is okay
     // The user can commit manually later
-    let commit_result = Command::new("git")
+    let commit_result = git_binary::command()
         .args(["commit", "-m", "Initial commit"])
         .current_dir(&project_dir)
         .output();
     
     if let Err(e) = commit_result {
-        eprintln!("Warning: Could not create initial commit: {}. Git user may not be configured.", e);
+        tracing::warn!("Could not create initial commit: {}. Git user may not be configured.", e);
     } else if let Ok(output) = commit_result {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("Warning: Initial commit failed: {}", stderr);
+            tracing::warn!("Initial commit failed: {}", stderr);
         }
     }
     
+    crate::webhooks::dispatch(
+        &app,
+        "project-created",
+        serde_json::json!({
+            "name": name,
+            "path": project_dir.to_string_lossy(),
+        }),
+    );
+
     Ok(CreateProjectResult {
         path: project_dir.to_string_lossy().to_string(),
         config_path: config_path.to_string_lossy().to_string(),
+        stack_id: None,
     })
 }
 
-/// Imports an existing directory as a project.
+/// Imports an existing directory as a project. With `clone_url`, the
+/// repository is first cloned into `project_path`; see `project_clone`.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
-pub fn import_project(name: String, project_path: String) -> Result<CreateProjectResult, String> {
+pub async fn import_project(
+    app: AppHandle,
+    name: String,
+    project_path: String,
+    clone_url: Option<String>,
+) -> Result<CreateProjectResult, String> {
+    tokio::task::spawn_blocking(move || {
+        if let Some(url) = clone_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+            crate::project_clone::clone(&app, url, &project_path)?;
+        }
+        import_directory(name, project_path)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(feature = "gui")]
+fn import_directory(name: String, project_path: String) -> Result<CreateProjectResult, String> {
     let project_dir = PathBuf::from(&project_path);
     
     if !project_dir.exists() {
@@ -154,7 +216,17 @@ pub fn import_project(name: String, project_path: String) -> Result<CreateProjec
             agent: None,
             autonomy: "autonomous".to_string(),
             build_mode: Some("ralph".to_string()),
+            tunnel_provider: None,
+            mute_notifications: false,
+            exclude_stack_snippets: false,
+            hooks: Default::default(),
+            e2e: None,
+            verification: None,
+            metadata_history: None,
+            routing: None,
+            metadata: HashMap::new(),
             created_at: chrono::Utc::now().to_rfc3339(),
+            extra: Default::default(),
         };
         
         let config_json = serde_json::to_string_pretty(&config)
@@ -167,53 +239,100 @@ pub fn import_project(name: String, project_path: String) -> Result<CreateProjec
     Ok(CreateProjectResult {
         path: project_dir.to_string_lossy().to_string(),
         config_path: config_path.to_string_lossy().to_string(),
+        stack_id: crate::stacks::detect_stack(&project_dir).map(str::to_string),
     })
 }
 
-fn get_projects_file_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    if !app_data_dir.exists() {
-        fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    }
-    
-    Ok(app_data_dir.join("projects.json"))
-}
-
 /// Loads the list of projects from the app data directory.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn load_projects(app: AppHandle) -> Result<Vec<StoredProject>, String> {
-    let projects_path = get_projects_file_path(&app)?;
-    
-    if !projects_path.exists() {
-        return Ok(Vec::new());
-    }
-    
-    let content = fs::read_to_string(&projects_path)
-        .map_err(|e| format!("Failed to read projects.json: {}", e))?;
-    
-    let projects: Vec<StoredProject> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse projects.json: {}", e))?;
-    
-    Ok(projects)
+    project_registry::load(&app_data_dir(&app)?)
+}
+
+/// Applies a change to the project registry; see `project_registry::update`.
+/// Returns the updated list.
+#[cfg(feature = "gui")]
+fn update_projects(
+    app: &AppHandle,
+    apply: impl FnOnce(&mut Vec<StoredProject>) -> Result<(), String>,
+) -> Result<Vec<StoredProject>, String> {
+    project_registry::update(&app_data_dir(app)?, apply)
+}
+
+/// Replaces the whole registry, for imports and sync conflict resolution.
+#[cfg(feature = "gui")]
+pub(crate) fn replace_projects(app: &AppHandle, projects: Vec<StoredProject>) -> Result<Vec<StoredProject>, String> {
+    update_projects(app, |existing| {
+        *existing = projects;
+        Ok(())
+    })
 }
 
-/// Saves the list of projects to the app data directory.
+/// Adds a project to the registry, replacing any entry with the same id.
+#[cfg(feature = "gui")]
 #[tauri::command]
-pub fn save_projects(app: AppHandle, projects: Vec<StoredProject>) -> Result<(), String> {
-    let projects_path = get_projects_file_path(&app)?;
-    
-    let projects_json = serde_json::to_string_pretty(&projects)
-        .map_err(|e| format!("Failed to serialize projects: {}", e))?;
-    
-    fs::write(&projects_path, projects_json)
-        .map_err(|e| format!("Failed to write projects.json: {}", e))?;
-    
-    Ok(())
+pub fn add_project(app: AppHandle, project: StoredProject) -> Result<Vec<StoredProject>, String> {
+    update_projects(&app, |projects| {
+        match projects.iter_mut().find(|p| p.id == project.id) {
+            Some(existing) => *existing = project.clone(),
+            None => projects.push(project.clone()),
+        }
+        Ok(())
+    })
+}
+
+/// Changes the given fields of a registered project.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn update_project(app: AppHandle, id: String, updates: ProjectUpdate) -> Result<Vec<StoredProject>, String> {
+    update_projects(&app, |projects| {
+        let project = projects
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Project not found: {}", id))?;
+        let updates = updates.clone();
+        if let Some(name) = updates.name {
+            project.name = name;
+        }
+        if let Some(description) = updates.description {
+            project.description = description;
+        }
+        if let Some(path) = updates.path {
+            project.path = path;
+        }
+        if let Some(status) = updates.status {
+            project.status = status;
+        }
+        if updates.stack_id.is_some() {
+            project.stack_id = updates.stack_id;
+        }
+        if let Some(extra_stack_ids) = updates.extra_stack_ids {
+            project.extra_stack_ids = extra_stack_ids;
+        }
+        Ok(())
+    })
+}
+
+/// Removes a project from the registry. Its directory is left alone.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn remove_project(app: AppHandle, id: String) -> Result<Vec<StoredProject>, String> {
+    update_projects(&app, |projects| {
+        projects.retain(|p| p.id != id);
+        Ok(())
+    })
+}
+
+/// Reorders the registry to follow `ids`. Projects missing from `ids`, such as
+/// ones another window just added, keep their relative order at the end.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn reorder_projects(app: AppHandle, ids: Vec<String>) -> Result<Vec<StoredProject>, String> {
+    update_projects(&app, |projects| {
+        projects.sort_by_key(|p| ids.iter().position(|id| *id == p.id).unwrap_or(ids.len()));
+        Ok(())
+    })
 }
 
 // ============================================================================
@@ -221,7 +340,7 @@ pub fn save_projects(app: AppHandle, projects: Vec<StoredProject>) -> Result<(),
 // ============================================================================
 
 /// Loads the PRD (Product Requirements Document) for a project.
-#[tauri::command(rename_all = "camelCase")]
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
 pub fn load_prd(project_path: String) -> Result<Option<Prd>, String> {
     let prd_path = get_ideate_dir(&project_path).join("prd.json");
     
@@ -231,23 +350,18 @@ pub fn load_prd(project_path: String) -> Result<Option<Prd>, String> {
     
     let content = fs::read_to_string(&prd_path)
         .map_err(|e| format!("Failed to read prd.json: {}", e))?;
+    file_lock::record_read(&prd_path, &content);
     
-    // First try parsing the JSON directly (most common case)
-    // Only fall back to sanitization if direct parsing fails
-    // This avoids issues where sanitization can break valid JSON (e.g., removing // in URLs)
-    match serde_json::from_str::<Prd>(&content) {
-        Ok(prd) => Ok(Some(prd)),
-        Err(first_error) => {
-            // Try sanitizing and parsing again
-            let sanitized = sanitize_json(&content);
-            serde_json::from_str(&sanitized)
-                .map(Some)
-                .map_err(|_| format!("Failed to parse prd.json: {}", first_error))
-        }
-    }
+    parse_prd(&content).map(Some)
 }
 
-/// Saves the PRD for a project.
+pub(crate) fn parse_prd(content: &str) -> Result<Prd, String> {
+    migrations::PRD.parse(content).map(|(prd, _)| prd)
+}
+
+/// Saves the PRD for a project. Fails with a conflict if another process
+/// changed prd.json since it was loaded.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_prd(project_path: String, prd: Prd) -> Result<(), String> {
     let ideate_dir = get_ideate_dir(&project_path);
@@ -259,13 +373,26 @@ pub fn save_prd(project_path: String, prd: Prd) -> Result<(), String> {
     
     let prd_path = ideate_dir.join("prd.json");
     
-    let prd_json = serde_json::to_string_pretty(&prd)
-        .map_err(|e| format!("Failed to serialize PRD: {}", e))?;
+    let prd_json = migrations::PRD.to_json(&prd)?;
     
-    fs::write(&prd_path, prd_json)
-        .map_err(|e| format!("Failed to write prd.json: {}", e))?;
-    
-    Ok(())
+    if let Ok(previous) = fs::read_to_string(&prd_path) {
+        prd_recovery::keep_backup(&prd_path, &previous);
+    }
+    file_lock::write_checked(&prd_path, &prd_json)
+}
+
+/// Applies a change to prd.json under its lock, merging with concurrent
+/// writers by re-applying the change to their version.
+pub fn update_prd<R>(project_path: &str, mut apply: impl FnMut(&mut Prd) -> Result<R, String>) -> Result<R, String> {
+    let prd_path = get_ideate_dir(project_path).join("prd.json");
+    file_lock::update(&prd_path, |content| {
+        let content = content.ok_or_else(|| "prd.json not found".to_string())?;
+        let mut prd = parse_prd(content)?;
+        prd_recovery::keep_backup(&prd_path, content);
+        let result = apply(&mut prd)?;
+        let prd_json = migrations::PRD.to_json(&prd)?;
+        Ok((prd_json, result))
+    })
 }
 
 // ============================================================================
@@ -273,6 +400,7 @@ pub fn save_prd(project_path: String, prd: Prd) -> Result<(), String> {
 // ============================================================================
 
 /// Loads the project idea from .ideate/idea.json
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn load_project_idea(project_path: String) -> Result<Option<ProjectIdea>, String> {
     let idea_path = get_ideate_dir(&project_path).join("idea.json");
@@ -291,6 +419,7 @@ pub fn load_project_idea(project_path: String) -> Result<Option<ProjectIdea>, St
 }
 
 /// Saves the project idea to .ideate/idea.json
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_project_idea(project_path: String, idea: ProjectIdea) -> Result<(), String> {
     let ideate_dir = get_ideate_dir(&project_path);
@@ -316,7 +445,7 @@ pub fn save_project_idea(project_path: String, idea: ProjectIdea) -> Result<(),
 // ============================================================================
 
 /// Loads the Design document for a project.
-#[tauri::command(rename_all = "camelCase")]
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
 pub fn load_design(project_path: String) -> Result<Option<Design>, String> {
     let design_path = get_ideate_dir(&project_path).join("design.json");
     
@@ -327,22 +456,11 @@ pub fn load_design(project_path: String) -> Result<Option<Design>, String> {
     let content = fs::read_to_string(&design_path)
         .map_err(|e| format!("Failed to read design.json: {}", e))?;
     
-    // First try parsing the JSON directly (most common case)
-    // Only fall back to sanitization if direct parsing fails
-    // This avoids issues where sanitization can break valid JSON (e.g., removing // in URLs)
-    match serde_json::from_str::<Design>(&content) {
-        Ok(design) => Ok(Some(design)),
-        Err(first_error) => {
-            // Try sanitizing and parsing again
-            let sanitized = sanitize_json(&content);
-            serde_json::from_str(&sanitized)
-                .map(Some)
-                .map_err(|_| format!("Failed to parse design.json: {}", first_error))
-        }
-    }
+    parse_lenient_json(&content, "design.json").map(Some)
 }
 
 /// Saves the Design document for a project.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_design(project_path: String, design: Design) -> Result<(), String> {
     let ideate_dir = get_ideate_dir(&project_path);
@@ -368,6 +486,7 @@ pub fn save_design(project_path: String, design: Design) -> Result<(), String> {
 // ============================================================================
 
 /// Deletes a project directory and all its contents.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn delete_project_directory(path: String) -> Result<(), String> {
     let project_dir = PathBuf::from(&path);
@@ -387,6 +506,7 @@ pub fn delete_project_directory(path: String) -> Result<(), String> {
 }
 
 /// Lists files in a directory (non-recursive).
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn list_directory(path: String) -> Result<Vec<String>, String> {
     let dir = PathBuf::from(&path);
@@ -413,6 +533,7 @@ pub fn list_directory(path: String) -> Result<Vec<String>, String> {
 }
 
 /// Checks if a directory exists at the given path.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn check_directory_exists(path: String) -> Result<bool, String> {
     let path = PathBuf::from(&path);
@@ -420,6 +541,7 @@ pub fn check_directory_exists(path: String) -> Result<bool, String> {
 }
 
 /// Checks if a command exists in the system PATH.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn check_command_exists(command: String) -> Result<bool, String> {
     let result = Command::new("which")
@@ -447,7 +569,7 @@ pub fn check_command_exists(command: String) -> Result<bool, String> {
 // ============================================================================
 
 /// Loads project-specific settings.
-#[tauri::command(rename_all = "camelCase")]
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
 pub fn load_project_settings(project_path: String) -> Result<Option<ProjectSettings>, String> {
     let config_path = get_ideate_dir(&project_path).join("config.json");
     
@@ -465,10 +587,14 @@ pub fn load_project_settings(project_path: String) -> Result<Option<ProjectSetti
         agent: config.agent,
         autonomy: config.autonomy,
         build_mode: config.build_mode,
+        tunnel_provider: config.tunnel_provider,
+        mute_notifications: Some(config.mute_notifications),
+        exclude_stack_snippets: Some(config.exclude_stack_snippets),
     }))
 }
 
 /// Saves project-specific settings.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_project_settings(
     project_path: String,
@@ -490,6 +616,13 @@ pub fn save_project_settings(
     config.agent = settings.agent;
     config.autonomy = settings.autonomy;
     config.build_mode = settings.build_mode;
+    config.tunnel_provider = settings.tunnel_provider;
+    if let Some(muted) = settings.mute_notifications {
+        config.mute_notifications = muted;
+    }
+    if let Some(excluded) = settings.exclude_stack_snippets {
+        config.exclude_stack_snippets = excluded;
+    }
     
     let config_json = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
@@ -501,28 +634,110 @@ pub fn save_project_settings(
 }
 
 // ============================================================================
-// Project State
+// Project Metadata
 // ============================================================================
 
-/// Loads the build state for a project.
+/// Applies `apply` to config.json under its file lock and saves it. Keys the
+/// config doesn't know are written back unchanged.
+#[cfg(feature = "gui")]
+fn update_config<R>(project_path: &str, mut apply: impl FnMut(&mut ProjectConfig) -> R) -> Result<R, String> {
+    let config_path = get_ideate_dir(project_path).join("config.json");
+    file_lock::update(&config_path, |content| {
+        let content = content.ok_or_else(|| "Config file does not exist".to_string())?;
+        let mut config: ProjectConfig = serde_json::from_str(content)
+            .map_err(|e| format!("Failed to parse config.json: {}", e))?;
+        let result = apply(&mut config);
+        let config_json = serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        Ok((config_json, result))
+    })
+}
+
+/// Merges `patch` into `target` as a JSON merge patch (RFC 7386): objects
+/// merge key by key, `null` removes a key, and anything else replaces.
+#[cfg(feature = "gui")]
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Gets the project's metadata, or with a `key`, that entry (`null` when
+/// it isn't set).
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
-pub fn load_project_state(project_path: String) -> Result<Option<ProjectState>, String> {
-    let state_path = get_ideate_dir(&project_path).join("state.json");
-    
-    if !state_path.exists() {
-        return Ok(None);
+pub fn get_project_metadata(project_path: String, key: Option<String>) -> Result<Value, String> {
+    let config_path = get_ideate_dir(&project_path).join("config.json");
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read config.json: {}", e))?;
+    let config: ProjectConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse config.json: {}", e))?;
+    Ok(match key {
+        Some(key) => config.metadata.get(&key).cloned().unwrap_or(Value::Null),
+        None => Value::Object(config.metadata.into_iter().collect()),
+    })
+}
+
+/// Sets one metadata entry, replacing its value. `null` removes it.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn set_project_metadata(project_path: String, key: String, value: Value) -> Result<(), String> {
+    if key.trim().is_empty() {
+        return Err("Metadata key is required".to_string());
     }
-    
-    let content = fs::read_to_string(&state_path)
-        .map_err(|e| format!("Failed to read state.json: {}", e))?;
-    
-    let state: ProjectState = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse state.json: {}", e))?;
-    
-    Ok(Some(state))
+    update_config(&project_path, |config| {
+        if value.is_null() {
+            config.metadata.remove(&key);
+        } else {
+            config.metadata.insert(key.clone(), value.clone());
+        }
+    })
+}
+
+/// Merges `metadata` into the project's as a JSON merge patch, so nested
+/// objects keep the keys the patch doesn't mention. Returns the result.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn merge_project_metadata(
+    project_path: String,
+    metadata: Map<String, Value>,
+) -> Result<HashMap<String, Value>, String> {
+    update_config(&project_path, |config| {
+        let mut merged = Value::Object(config.metadata.drain().collect());
+        merge_patch(&mut merged, Value::Object(metadata.clone()));
+        if let Value::Object(merged) = merged {
+            config.metadata = merged.into_iter().collect();
+        }
+        config.metadata.clone()
+    })
+}
+
+// ============================================================================
+// Project State
+// ============================================================================
+
+/// Loads the build state for a project. Documents from an older schema are
+/// upgraded and written back, keeping the original as `state.json.v<N>.bak`.
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
+pub fn load_project_state(project_path: String) -> Result<Option<ProjectState>, String> {
+    migrations::STATE.load(&get_ideate_dir(&project_path).join("state.json"))
 }
 
 /// Saves the build state for a project.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_project_state(project_path: String, state: ProjectState) -> Result<(), String> {
     let ideate_dir = get_ideate_dir(&project_path);
@@ -533,14 +748,43 @@ pub fn save_project_state(project_path: String, state: ProjectState) -> Result<(
     }
     
     let state_path = ideate_dir.join("state.json");
-    
-    let state_json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize state: {}", e))?;
-    
-    fs::write(&state_path, state_json)
-        .map_err(|e| format!("Failed to write state.json: {}", e))?;
-    
-    Ok(())
+
+    file_lock::update(&state_path, |content| {
+        // Preserve backend-managed fields the caller didn't send
+        let mut state = state.clone();
+        state.schema_version = migrations::STATE.current_version();
+        if let Some((existing, _)) = content.and_then(|c| migrations::STATE.parse::<ProjectState>(c).ok()) {
+            if state.deploy_previews.is_none() {
+                state.deploy_previews = existing.deploy_previews;
+            }
+            if state.story_failures.is_none() {
+                state.story_failures = existing.story_failures;
+            }
+            if state.story_verifications.is_none() {
+                state.story_verifications = existing.story_verifications;
+            }
+        }
+        let state_json = migrations::STATE.to_json(&state)?;
+        Ok((state_json, ()))
+    })
+}
+
+/// Applies a change to state.json under its lock, starting from an idle
+/// state if there is none yet.
+pub fn update_project_state(project_path: &str, mut apply: impl FnMut(&mut ProjectState)) -> Result<(), String> {
+    let ideate_dir = get_ideate_dir(project_path);
+    fs::create_dir_all(&ideate_dir)
+        .map_err(|e| format!("Failed to create .ideate directory: {}", e))?;
+
+    file_lock::update(&ideate_dir.join("state.json"), |content| {
+        let mut state = match content {
+            Some(content) => migrations::STATE.parse(content)?.0,
+            None => ProjectState::default(),
+        };
+        apply(&mut state);
+        let state_json = migrations::STATE.to_json(&state)?;
+        Ok((state_json, ()))
+    })
 }
 
 // ============================================================================
@@ -548,7 +792,7 @@ pub fn save_project_state(project_path: String, state: ProjectState) -> Result<(
 // ============================================================================
 
 /// Loads the cost history for a project.
-#[tauri::command(rename_all = "camelCase")]
+#[cfg_attr(feature = "gui", tauri::command(rename_all = "camelCase"))]
 pub fn load_cost_history(project_path: String) -> Result<CostHistory, String> {
     let cost_path = get_ideate_dir(&project_path).join("costs.json");
     
@@ -568,6 +812,7 @@ pub fn load_cost_history(project_path: String) -> Result<CostHistory, String> {
 }
 
 /// Saves the cost history for a project.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_cost_history(project_path: String, history: CostHistory) -> Result<(), String> {
     let ideate_dir = get_ideate_dir(&project_path);
@@ -0,0 +1,279 @@
+//! Versioned prompt library.
+//!
+//! Every named prompt keeps its versions in `prompt-library.json` in the app
+//! data directory. The latest version of each is the prompt override in
+//! preferences, so prompts edited in Settings are versioned too, and rolling
+//! back writes the older text to the override again. `rollback_prompts_since`
+//! undoes every edit since a point in time, such as the start of a build
+//! that went wrong.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::file_lock;
+use crate::preferences::{load_preferences_internal, save_preferences};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptVersion {
+    /// Starts at 1.
+    pub version: u32,
+    /// Empty when the prompt was reset to its default.
+    pub content: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// "library", "settings", or "rollback".
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryPrompt {
+    /// The prompt's name, which is also its prompt overrides key.
+    pub id: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Oldest first; the last is current.
+    pub versions: Vec<PromptVersion>,
+}
+
+impl LibraryPrompt {
+    fn content(&self) -> &str {
+        self.versions.last().map_or("", |v| v.content.as_str())
+    }
+
+    /// Content as of `time`, empty if the prompt had no override then.
+    fn content_at(&self, time: chrono::DateTime<chrono::Utc>) -> &str {
+        self.versions
+            .iter()
+            .rev()
+            .find(|v| parse_time(&v.created_at).is_some_and(|t| t <= time))
+            .map_or("", |v| v.content.as_str())
+    }
+}
+
+fn parse_time(time: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc))
+}
+
+fn library_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?.join("prompt-library.json"))
+}
+
+fn load_library(app: &AppHandle) -> Result<Vec<LibraryPrompt>, String> {
+    let path = library_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read prompt-library.json: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse prompt-library.json: {}", e))
+}
+
+/// Applies `apply` to the library under its file lock and saves it.
+fn update_library<R>(
+    app: &AppHandle,
+    mut apply: impl FnMut(&mut Vec<LibraryPrompt>) -> Result<R, String>,
+) -> Result<R, String> {
+    let path = library_path(app)?;
+    file_lock::update(&path, |content| {
+        let mut prompts: Vec<LibraryPrompt> = match content {
+            Some(content) => serde_json::from_str(content)
+                .map_err(|e| format!("Failed to parse prompt-library.json: {}", e))?,
+            None => Vec::new(),
+        };
+        let result = apply(&mut prompts)?;
+        let json = serde_json::to_string_pretty(&prompts)
+            .map_err(|e| format!("Failed to serialize prompt library: {}", e))?;
+        Ok((json, result))
+    })
+}
+
+/// Adds a version of `name` unless it already reads `content`. Returns the
+/// prompt if it changed.
+fn push_version(
+    prompts: &mut Vec<LibraryPrompt>,
+    name: &str,
+    content: &str,
+    note: Option<String>,
+    source: &str,
+) -> Option<LibraryPrompt> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let index = match prompts.iter().position(|p| p.id == name) {
+        Some(index) => index,
+        None => {
+            prompts.push(LibraryPrompt {
+                id: name.to_string(),
+                created_at: now.clone(),
+                updated_at: now.clone(),
+                versions: Vec::new(),
+            });
+            prompts.len() - 1
+        }
+    };
+    let prompt = &mut prompts[index];
+    if !prompt.versions.is_empty() && prompt.content() == content {
+        return None;
+    }
+    prompt.versions.push(PromptVersion {
+        version: prompt.versions.last().map_or(1, |v| v.version + 1),
+        content: content.to_string(),
+        created_at: now.clone(),
+        note,
+        source: source.to_string(),
+    });
+    prompt.updated_at = now;
+    Some(prompt.clone())
+}
+
+/// Writes prompt overrides to preferences; empty content removes one.
+fn apply_overrides(app: &AppHandle, changes: &[(String, String)]) -> Result<(), String> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut prefs = load_preferences_internal(app)?;
+    for (name, content) in changes {
+        if content.is_empty() {
+            prefs.prompt_overrides.remove(name);
+        } else {
+            prefs.prompt_overrides.insert(name.clone(), content.clone());
+        }
+    }
+    save_preferences(app.clone(), prefs)
+}
+
+/// Records a version for each override that differs between `before` and
+/// `after`. Called when preferences are saved. An override set before the
+/// library existed gets its earlier text as a first version.
+pub(crate) fn record_overrides(
+    app: &AppHandle,
+    before: &HashMap<String, String>,
+    after: &HashMap<String, String>,
+) -> Result<(), String> {
+    let changed: Vec<&String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|name| before.get(*name) != after.get(*name))
+        .collect();
+    if changed.is_empty() {
+        return Ok(());
+    }
+    update_library(app, |prompts| {
+        for name in &changed {
+            let known = prompts.iter().any(|p| &p.id == *name && !p.versions.is_empty());
+            if let (false, Some(old)) = (known, before.get(*name)) {
+                push_version(prompts, name, old, Some("Before versioning".to_string()), "settings");
+            }
+            let new = after.get(*name).map_or("", String::as_str);
+            push_version(prompts, name, new, None, "settings");
+        }
+        Ok(())
+    })
+}
+
+/// Lists the prompts in the library.
+#[tauri::command]
+pub fn list_library_prompts(app: AppHandle) -> Result<Vec<LibraryPrompt>, String> {
+    load_library(&app)
+}
+
+/// Gets a prompt and its versions.
+#[tauri::command]
+pub fn get_library_prompt(app: AppHandle, name: String) -> Result<Option<LibraryPrompt>, String> {
+    Ok(load_library(&app)?.into_iter().find(|p| p.id == name))
+}
+
+/// Saves a new version of a prompt and makes it the prompt's override.
+#[tauri::command]
+pub fn save_library_prompt(
+    app: AppHandle,
+    name: String,
+    content: String,
+    note: Option<String>,
+) -> Result<LibraryPrompt, String> {
+    if name.trim().is_empty() {
+        return Err("Prompt name is required".to_string());
+    }
+    let changed = update_library(&app, |prompts| {
+        Ok(push_version(prompts, &name, &content, note.clone(), "library"))
+    })?;
+    apply_overrides(&app, &[(name.clone(), content)])?;
+    match changed {
+        Some(prompt) => Ok(prompt),
+        None => get_library_prompt(app, name.clone())?.ok_or_else(|| format!("Prompt {} not found", name)),
+    }
+}
+
+/// Removes a prompt, its history, and its override.
+#[tauri::command]
+pub fn delete_library_prompt(app: AppHandle, name: String) -> Result<(), String> {
+    update_library(&app, |prompts| {
+        prompts.retain(|p| p.id != name);
+        Ok(())
+    })?;
+    let mut prefs = load_preferences_internal(&app)?;
+    if prefs.prompt_overrides.remove(&name).is_some() {
+        save_preferences(app.clone(), prefs)?;
+        // Saving recorded the removal as a version; the prompt is gone
+        update_library(&app, |prompts| {
+            prompts.retain(|p| p.id != name);
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Restores an earlier version of a prompt as a new version.
+#[tauri::command]
+pub fn rollback_library_prompt(app: AppHandle, name: String, version: u32) -> Result<LibraryPrompt, String> {
+    let (prompt, content) = update_library(&app, |prompts| {
+        let content = prompts
+            .iter()
+            .find(|p| p.id == name)
+            .ok_or_else(|| format!("Prompt {} not found", name))?
+            .versions
+            .iter()
+            .find(|v| v.version == version)
+            .ok_or_else(|| format!("Prompt {} has no version {}", name, version))?
+            .content
+            .clone();
+        let note = Some(format!("Rolled back to version {}", version));
+        Ok((push_version(prompts, &name, &content, note, "rollback"), content))
+    })?;
+    apply_overrides(&app, &[(name.clone(), content)])?;
+    match prompt {
+        Some(prompt) => Ok(prompt),
+        None => get_library_prompt(app, name.clone())?.ok_or_else(|| format!("Prompt {} not found", name)),
+    }
+}
+
+/// Rolls every prompt edited after `since` (RFC 3339) back to what it was
+/// then. Returns the prompts that changed.
+#[tauri::command]
+pub fn rollback_prompts_since(app: AppHandle, since: String) -> Result<Vec<LibraryPrompt>, String> {
+    let time = parse_time(&since).ok_or_else(|| format!("Invalid time: {}", since))?;
+    let note = Some(format!("Rolled back to {}", since));
+    let changed = update_library(&app, |prompts| {
+        let targets: Vec<(String, String)> = prompts
+            .iter()
+            .filter(|p| p.versions.iter().any(|v| parse_time(&v.created_at).is_some_and(|t| t > time)))
+            .map(|p| (p.id.clone(), p.content_at(time).to_string()))
+            .collect();
+        Ok(targets
+            .into_iter()
+            .filter_map(|(name, content)| {
+                push_version(prompts, &name, &content, note.clone(), "rollback").map(|p| (p, content))
+            })
+            .collect::<Vec<_>>())
+    })?;
+    let overrides: Vec<(String, String)> = changed.iter().map(|(p, c)| (p.id.clone(), c.clone())).collect();
+    apply_overrides(&app, &overrides)?;
+    Ok(changed.into_iter().map(|(p, _)| p).collect())
+}
@@ -0,0 +1,445 @@
+//! Prompt templates.
+//!
+//! Prompts are minijinja templates, so the `{{variable}}` placeholders that
+//! prompt overrides already use keep working, and templates can also use
+//! conditionals, loops, and `{% include "story" %}` to pull in a partial.
+//! Partials can be overridden like prompts, under `partials/<name>`.
+//!
+//! Templates rendered for a project get `project`, its composed `stack`,
+//! and `design` from its files, and with a `storyId`, that `story` and its
+//! `criteria`. The story prompt of a build also gets the `files` relevant to
+//! the story; see `orchestrator::context`. `build_story_prompt` assembles
+//! that prompt as a build would, for previews. Prompts that agents build
+//! from also end with the prompt snippets of the project's stack, through
+//! the `stackSnippets` partial, whatever the template.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use minijinja::{Environment, UndefinedBehavior};
+#[cfg(feature = "gui")]
+use serde::Serialize;
+use serde_json::{Map, Value};
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
+use crate::idea_jobs::DEFAULT_EXPANSION_PROMPT;
+use crate::models::{Stack, Story};
+#[cfg(feature = "gui")]
+use crate::orchestrator::swarm::{DEFAULT_PLANNER_PROMPT, DEFAULT_REVIEWER_PROMPT, DEFAULT_WORKER_PROMPT};
+use crate::orchestrator::DEFAULT_STORY_PROMPT;
+#[cfg(feature = "gui")]
+use crate::orchestrator::{context, render_story_prompt};
+#[cfg(feature = "gui")]
+use crate::preferences::load_preferences_internal;
+#[cfg(feature = "gui")]
+use crate::projects::{load_design, load_prd, load_project_idea};
+#[cfg(feature = "gui")]
+use crate::projects::load_projects;
+use crate::stacks::{effective_stack_in, prompt_snippets_in};
+#[cfg(feature = "gui")]
+use crate::stacks::effective_stack;
+
+/// Templates whose default lives in the backend. Defaults of the others are
+/// passed in by the frontend.
+#[cfg(feature = "gui")]
+const DEFAULTS: &[(&str, &str)] = &[
+    ("storyImplementation", DEFAULT_STORY_PROMPT),
+    ("ideaExpansion", DEFAULT_EXPANSION_PROMPT),
+    ("swarmPlanner", DEFAULT_PLANNER_PROMPT),
+    ("swarmWorker", DEFAULT_WORKER_PROMPT),
+    ("swarmReviewer", DEFAULT_REVIEWER_PROMPT),
+];
+
+/// The headless CLI only renders story prompts.
+#[cfg(not(feature = "gui"))]
+const DEFAULTS: &[(&str, &str)] = &[("storyImplementation", DEFAULT_STORY_PROMPT)];
+
+const PARTIALS: &[(&str, &str)] = &[
+    (
+        "criteria",
+        r#"{% for criterion in story.acceptanceCriteria %}
+{{ loop.index }}. {{ criterion }}
+{% endfor %}"#,
+    ),
+    (
+        "story",
+        r#"## {{ story.id }}: {{ story.title }}
+
+{{ story.description }}
+
+### Acceptance Criteria:
+{% include "criteria" %}
+{% if story.notes %}
+
+### Notes:
+{{ story.notes }}
+{% endif %}"#,
+    ),
+    (
+        "files",
+        r#"{% for file in files %}
+
+### {{ file.path }}{% if file.truncated %} (beginning only){% endif %}
+
+```
+{{ file.content | trim }}
+```
+{% endfor %}
+{% if skippedFiles %}
+
+Left out to stay within the context budget: {{ skippedFiles | map(attribute="path") | join(", ") }}
+{% endif %}"#,
+    ),
+    (
+        "project",
+        r#"{% if project %}
+# {{ project.name }}
+{% if project.description %}
+
+{{ project.description }}
+{% endif %}
+{% endif %}"#,
+    ),
+    (
+        "stack",
+        r#"{% if stack %}
+Stack: {{ stack.name }}
+{% for tool in stack.tools %}
+- {{ tool.name }}{% if tool.version %} {{ tool.version }}{% endif %} ({{ tool.category }})
+{% endfor %}
+{% endif %}"#,
+    ),
+    (
+        "stackSnippets",
+        r#"{% if stackSnippets %}
+## Stack Conventions
+{% for snippet in stackSnippets %}
+
+{{ snippet | trim }}
+{% endfor %}
+{% endif %}"#,
+    ),
+    (
+        "design",
+        r#"{% if design.architecture.overview %}
+## Architecture
+
+{{ design.architecture.overview }}
+{% endif %}
+{% if design.architecture.components %}
+
+### Components
+
+{% for component in design.architecture.components %}
+- {{ component.name }}: {{ component.description }}
+{% endfor %}
+{% endif %}
+{% if design.dataModels %}
+
+## Data Models
+
+{% for model in design.dataModels %}
+- {{ model.name }}{{ ": " ~ (model.fields | join(", ")) if model.fields else "" }}
+{% endfor %}
+{% endif %}
+{% if design.techStack %}
+
+## Tech Stack
+
+{% for layer in ["frontend", "backend", "database", "infrastructure"] %}
+{% if design.techStack[layer] %}
+- {{ layer | capitalize }}: {{ design.techStack[layer] | join(", ") }}
+{% endif %}
+{% endfor %}
+{% endif %}"#,
+    ),
+];
+
+/// Prompt overrides key of a partial.
+fn partial_key(name: &str) -> String {
+    format!("partials/{}", name)
+}
+
+/// Renders prompt templates with the user's overrides and partials.
+#[derive(Clone)]
+pub(crate) struct PromptEngine {
+    env: Environment<'static>,
+    overrides: HashMap<String, String>,
+    /// The project's composed stack, for story prompts.
+    stack: Option<Stack>,
+    /// Appended by `with_stack_snippets`; see `stacks::prompt_snippets_in`.
+    stack_snippets: Vec<String>,
+}
+
+impl PromptEngine {
+    pub(crate) fn new(overrides: &HashMap<String, String>) -> Self {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_keep_trailing_newline(true);
+        // Lets templates test `design.techStack` when there is no design
+        env.set_undefined_behavior(UndefinedBehavior::Chainable);
+        for (name, default) in PARTIALS {
+            let source = overrides
+                .get(&partial_key(name))
+                .filter(|p| !p.trim().is_empty())
+                .cloned();
+            if let Some(source) = source {
+                match env.add_template_owned(name.to_string(), source) {
+                    Ok(()) => continue,
+                    Err(e) => tracing::warn!("Ignoring the override of partial {}: {}", name, e),
+                }
+            }
+            if let Err(e) = env.add_template(name, default) {
+                tracing::error!("Built-in partial {} does not parse: {}", name, e);
+            }
+        }
+        Self {
+            env,
+            overrides: overrides.clone(),
+            stack: None,
+            stack_snippets: Vec::new(),
+        }
+    }
+
+    /// An engine for the builds of the project at `project_path`, whose
+    /// stacks are `stack_ids`: with the composed stack and, unless the
+    /// project opted out, its prompt snippets. Custom stacks are read from
+    /// `app_data_dir`.
+    pub(crate) fn for_project(
+        overrides: &HashMap<String, String>,
+        app_data_dir: Option<&Path>,
+        project_path: &str,
+        stack_ids: &[String],
+    ) -> Self {
+        Self {
+            stack: effective_stack_in(app_data_dir, stack_ids),
+            stack_snippets: prompt_snippets_in(app_data_dir, project_path, stack_ids),
+            ..Self::new(overrides)
+        }
+    }
+
+    /// The project's composed stack, if the engine is for a project.
+    pub(crate) fn stack(&self) -> Option<&Stack> {
+        self.stack.as_ref()
+    }
+
+    /// Appends the stack's prompt snippets to a rendered prompt.
+    pub(crate) fn with_stack_snippets(&self, prompt: String) -> String {
+        if self.stack_snippets.is_empty() {
+            return prompt;
+        }
+        let context = serde_json::json!({ "stackSnippets": self.stack_snippets });
+        let rendered = self
+            .env
+            .get_template("stackSnippets")
+            .and_then(|template| template.render(&context))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to render stack snippets: {}", e);
+                String::new()
+            });
+        if rendered.trim().is_empty() {
+            return prompt;
+        }
+        format!("{}\n\n{}\n", prompt.trim_end(), rendered.trim())
+    }
+
+    /// An engine with the overrides from preferences.
+    #[cfg(feature = "gui")]
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let prefs = load_preferences_internal(app).unwrap_or_default();
+        Self::new(&prefs.prompt_overrides)
+    }
+
+    fn default_source(template_id: &str, fallback: Option<&str>) -> Option<String> {
+        DEFAULTS
+            .iter()
+            .find(|(id, _)| *id == template_id)
+            .map(|(_, source)| source.to_string())
+            .or_else(|| fallback.map(String::from))
+    }
+
+    /// Renders `source` against `context`.
+    pub(crate) fn render_str(&self, source: &str, context: &Value) -> Result<String, String> {
+        self.env
+            .render_str(source, context)
+            .map_err(|e| format!("Failed to render prompt: {}", e))
+    }
+
+    /// Renders the user's override of `template_id`, else its default, which
+    /// is built in or `fallback`. An override that fails to render falls back
+    /// to the default, so a typo in Settings can't stop builds.
+    pub(crate) fn render(&self, template_id: &str, fallback: Option<&str>, context: &Value) -> Result<String, String> {
+        let default = Self::default_source(template_id, fallback);
+        let Some(source) = self
+            .overrides
+            .get(template_id)
+            .filter(|p| !p.trim().is_empty())
+        else {
+            let default = default.ok_or_else(|| format!("Unknown prompt template: {}", template_id))?;
+            return self.render_str(&default, context);
+        };
+        match (self.render_str(source, context), default) {
+            (Ok(prompt), _) => Ok(prompt),
+            (Err(e), Some(default)) => {
+                tracing::warn!("Override of prompt {} failed, using the default: {}", template_id, e);
+                self.render_str(&default, context)
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+}
+
+/// Variables for a story: the `story` itself, its numbered `criteria`, and
+/// the flat `storyId`, `storyTitle`, `storyDescription`,
+/// `acceptanceCriteria`, and `notes` that older templates use.
+pub(crate) fn story_context(story: &Story, notes: String) -> Map<String, Value> {
+    let criteria = story
+        .acceptance_criteria
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("{}. {}", i + 1, c))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut context = Map::new();
+    context.insert("story".to_string(), serde_json::to_value(story).unwrap_or_default());
+    context.insert("storyId".to_string(), story.id.clone().into());
+    context.insert("storyTitle".to_string(), story.title.clone().into());
+    context.insert("storyDescription".to_string(), story.description.clone().into());
+    context.insert("acceptanceCriteria".to_string(), criteria.clone().into());
+    context.insert("criteria".to_string(), criteria.into());
+    context.insert("notes".to_string(), notes.into());
+    context
+}
+
+/// Variables from a project's files: `project`, `stack`, `design`, and the
+/// story `story_id` names.
+#[cfg(feature = "gui")]
+fn project_context(app: &AppHandle, project_path: &str, story_id: Option<&str>) -> Map<String, Value> {
+    let mut context = Map::new();
+    let stored = load_projects(app.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .find(|p| p.path == project_path);
+    let idea = load_project_idea(project_path.to_string()).ok().flatten();
+    if stored.is_some() || idea.is_some() {
+        let mut project = Map::new();
+        project.insert("path".to_string(), project_path.into());
+        if let Some(stored) = &stored {
+            project.insert("name".to_string(), stored.name.clone().into());
+            project.insert("description".to_string(), stored.description.clone().into());
+        }
+        if let Some(idea) = idea {
+            project.entry("name").or_insert_with(|| idea.title.clone().into());
+            project.insert("idea".to_string(), serde_json::to_value(idea).unwrap_or_default());
+        }
+        if let Some(name) = project.get("name").cloned() {
+            context.insert("projectName".to_string(), name);
+        }
+        context.insert("project".to_string(), Value::Object(project));
+    }
+    if let Some(stack) = stored.as_ref().and_then(|p| effective_stack(app, p)) {
+        context.insert("stack".to_string(), serde_json::to_value(stack).unwrap_or_default());
+    }
+    if let Some(design) = load_design(project_path.to_string()).ok().flatten() {
+        context.insert("design".to_string(), serde_json::to_value(design).unwrap_or_default());
+    }
+    let story = story_id.and_then(|id| {
+        load_prd(project_path.to_string())
+            .ok()
+            .flatten()
+            .and_then(|prd| prd.user_stories.into_iter().find(|s| s.id == id))
+    });
+    if let Some(story) = story {
+        let notes = if story.notes.is_empty() {
+            String::new()
+        } else {
+            format!("### Notes:\n{}", story.notes)
+        };
+        context.extend(story_context(&story, notes));
+    }
+    context
+}
+
+/// Renders a prompt template with `context`. With a `projectPath`, and
+/// optionally a `storyId`, the project's variables are added; those in
+/// `context` take precedence. `fallback` is the default of a template the
+/// backend doesn't know.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn render_prompt(
+    app: AppHandle,
+    template_id: String,
+    context: Value,
+    fallback: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let given = match context {
+            Value::Object(map) => map,
+            Value::Null => Map::new(),
+            _ => return Err("Prompt context must be an object".to_string()),
+        };
+        let mut context = match given.get("projectPath").and_then(Value::as_str) {
+            Some(project_path) => {
+                let story_id = given.get("storyId").and_then(Value::as_str);
+                project_context(&app, project_path, story_id)
+            }
+            None => Map::new(),
+        };
+        context.extend(given);
+        PromptEngine::load(&app).render(&template_id, fallback.as_deref(), &Value::Object(context))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// A story prompt as a build would send it.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryPrompt {
+    pub prompt: String,
+    /// Rough size of the prompt, at four characters per token.
+    pub estimated_tokens: u64,
+}
+
+/// Assembles the prompt a build would give the agent for a story: the story,
+/// the project's stack, its design, the files relevant to the story, and the
+/// user's overrides, with the stack's prompt snippets.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn build_story_prompt(app: AppHandle, project_path: String, story_id: String) -> Result<StoryPrompt, String> {
+    tokio::task::spawn_blocking(move || {
+        let story = load_prd(project_path.clone())?
+            .ok_or_else(|| "No prd.json found for this project".to_string())?
+            .user_stories
+            .into_iter()
+            .find(|s| s.id == story_id)
+            .ok_or_else(|| format!("Story {} not found", story_id))?;
+        let prefs = load_preferences_internal(&app).unwrap_or_default();
+        let stack_ids = load_projects(app.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .find(|p| p.path == project_path)
+            .map(|p| p.stack_ids())
+            .unwrap_or_default();
+        let prompts = PromptEngine::for_project(
+            &prefs.prompt_overrides,
+            app_data_dir(&app).ok().as_deref(),
+            &project_path,
+            &stack_ids,
+        );
+        let design = load_design(project_path.clone()).ok().flatten();
+        let files = context::assemble(&project_path, &story, design.as_ref(), u64::from(prefs.story_context_tokens));
+        let prompt = render_story_prompt(&prompts, &story, None, design.as_ref(), &files);
+        Ok(StoryPrompt {
+            estimated_tokens: context::estimate_tokens(prompt.len()),
+            prompt,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
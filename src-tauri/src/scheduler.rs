@@ -0,0 +1,162 @@
+//! Story scheduling for parallel builds.
+//!
+//! Infers which stories depend on each other (mirroring the frontend's
+//! `analyzeStoryDependencies`) and which are likely to edit the same files, so
+//! the orchestrator only runs stories side by side when they are independent.
+
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+
+use crate::models::Story;
+
+lazy_static::lazy_static! {
+    static ref FILE_PATH_RE: Regex = Regex::new(
+        r"(?i)(?:^|[\s`'(\[])((?:\./)?[\w@.-]+(?:/[\w@.-]+)*\.(?:rs|ts|tsx|js|jsx|mjs|cjs|py|go|rb|php|java|kt|swift|c|h|cpp|cs|css|scss|sass|less|html|vue|svelte|json|toml|ya?ml|sql|sh|md|prisma|graphql|proto))\b"
+    )
+    .unwrap();
+}
+
+/// Dependencies and file footprint of one story.
+#[derive(Debug, Clone, Default)]
+pub struct StoryDeps {
+    /// Stories that must pass before this one starts.
+    pub prerequisites: HashSet<String>,
+    /// Files the story mentions, normalized to lowercase relative paths.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub files: HashSet<String>,
+}
+
+/// Dependency graph for the stories of a build.
+#[derive(Debug, Clone, Default)]
+pub struct StoryGraph {
+    deps: HashMap<String, StoryDeps>,
+}
+
+fn story_text(story: &Story) -> String {
+    [
+        story.title.as_str(),
+        story.description.as_str(),
+        &story.acceptance_criteria.join("\n"),
+        story.notes.as_str(),
+    ]
+    .join("\n")
+}
+
+/// Extracts file paths mentioned in free text, e.g. `src/api/users.ts`.
+fn mentioned_files(text: &str) -> HashSet<String> {
+    FILE_PATH_RE
+        .captures_iter(text)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str().trim_start_matches("./").to_lowercase())
+        .collect()
+}
+
+impl StoryGraph {
+    /// Builds the graph from story text. Prerequisites are detected from
+    /// mentions of another story's ID or phrases like "after <title>".
+    pub fn analyze(stories: &[Story]) -> Self {
+        let mut deps = HashMap::new();
+
+        for story in stories {
+            let text = story_text(story).to_lowercase();
+            let mut prerequisites = HashSet::new();
+
+            for other in stories {
+                if other.id == story.id {
+                    continue;
+                }
+                let other_title = other.title.to_lowercase();
+                let mentions_id = text.contains(&other.id.to_lowercase());
+                let mentions_title = ["after", "depends on", "requires", "following", "once"]
+                    .iter()
+                    .any(|prefix| text.contains(&format!("{} {}", prefix, other_title)));
+                if mentions_id || mentions_title {
+                    prerequisites.insert(other.id.clone());
+                }
+            }
+
+            deps.insert(
+                story.id.clone(),
+                StoryDeps {
+                    prerequisites,
+                    files: mentioned_files(&story_text(story)),
+                },
+            );
+        }
+
+        Self { deps }
+    }
+
+    pub fn get(&self, story_id: &str) -> Option<&StoryDeps> {
+        self.deps.get(story_id)
+    }
+
+    /// Prerequisites of `story_id` that are still in `pending`.
+    #[cfg(feature = "gui")]
+    pub fn blocking_prerequisites<'a>(
+        &'a self,
+        story_id: &str,
+        pending: &'a HashSet<String>,
+    ) -> impl Iterator<Item = &'a String> + 'a {
+        self.deps
+            .get(story_id)
+            .into_iter()
+            .flat_map(|d| d.prerequisites.iter())
+            .filter(move |id| pending.contains(*id))
+    }
+
+    /// True if the two stories mention at least one common file.
+    #[cfg(feature = "gui")]
+    pub fn overlaps(&self, a: &str, b: &str) -> bool {
+        match (self.deps.get(a), self.deps.get(b)) {
+            (Some(a), Some(b)) => !a.files.is_disjoint(&b.files),
+            _ => false,
+        }
+    }
+
+    /// Whether `story_id` can start now: none of its prerequisites are still
+    /// pending and it shares no files with a running story.
+    #[cfg(feature = "gui")]
+    pub fn is_ready(&self, story_id: &str, pending: &HashSet<String>, running: &HashSet<String>) -> bool {
+        self.blocking_prerequisites(story_id, pending).next().is_none()
+            && !running.iter().any(|other| self.overlaps(story_id, other))
+    }
+
+    /// Wave each story can start in if every story passes: 0 for stories
+    /// with no prerequisites among `stories`, otherwise one after its latest
+    /// prerequisite. Stories caught in a dependency cycle are left out.
+    #[cfg(feature = "gui")]
+    pub fn waves(&self, stories: &[Story]) -> HashMap<String, usize> {
+        let ids: HashSet<&str> = stories.iter().map(|s| s.id.as_str()).collect();
+        let mut waves: HashMap<String, usize> = HashMap::new();
+
+        loop {
+            let mut changed = false;
+            for story in stories {
+                if waves.contains_key(&story.id) {
+                    continue;
+                }
+                let prerequisites: Vec<&String> = self
+                    .deps
+                    .get(&story.id)
+                    .map(|d| d.prerequisites.iter().filter(|p| ids.contains(p.as_str())).collect())
+                    .unwrap_or_default();
+                if prerequisites.iter().all(|p| waves.contains_key(*p)) {
+                    let wave = prerequisites.iter().map(|p| waves[*p] + 1).max().unwrap_or(0);
+                    waves.insert(story.id.clone(), wave);
+                    changed = true;
+                }
+            }
+            if !changed {
+                return waves;
+            }
+        }
+    }
+
+    /// Number of stories that have at least one prerequisite.
+    #[cfg(feature = "gui")]
+    pub fn dependent_count(&self) -> usize {
+        self.deps.values().filter(|d| !d.prerequisites.is_empty()).count()
+    }
+}
@@ -0,0 +1,219 @@
+//! Screenshots of the running app as story evidence.
+//!
+//! Pages are captured with a locally installed Chrome, Chromium, or Edge in
+//! headless mode and saved under `.ideate/evidence/<story>/`. After a story is
+//! merged, builds capture the project's preview proxy when one is running, so
+//! each story has visual proof to check its acceptance criteria against.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::editors::which;
+use crate::process::terminate_child;
+use crate::utils::get_ideate_dir;
+
+const BROWSER_CLIS: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "chrome",
+    "microsoft-edge",
+    "msedge",
+];
+
+/// Browser executables inside app bundles and default install locations,
+/// used when none is on PATH.
+#[cfg(target_os = "macos")]
+const BROWSER_FALLBACKS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser",
+];
+#[cfg(windows)]
+const BROWSER_FALLBACKS: &[&str] = &[
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+];
+#[cfg(not(any(target_os = "macos", windows)))]
+const BROWSER_FALLBACKS: &[&str] = &[];
+
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long the page may load before it is captured, in virtual time.
+const LOAD_BUDGET_MS: u32 = 5000;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self { width: 1280, height: 800 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Screenshot {
+    pub path: String,
+    pub story_id: String,
+    pub url: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub captured_at: String,
+}
+
+fn find_browser() -> Option<PathBuf> {
+    BROWSER_CLIS.iter().find_map(|cli| which(cli)).or_else(|| {
+        BROWSER_FALLBACKS
+            .iter()
+            .map(PathBuf::from)
+            .find(|p| p.exists())
+    })
+}
+
+/// Story IDs become directory names, so anything but a plain name is replaced.
+fn evidence_dir(project_path: &str, story_id: &str) -> PathBuf {
+    let name: String = story_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('.');
+    get_ideate_dir(project_path)
+        .join("evidence")
+        .join(if name.is_empty() { "story" } else { name })
+}
+
+/// Captures `url` at the given viewport, blocking until the browser exits.
+pub fn capture(project_path: &str, story_id: &str, url: &str, viewport: Viewport) -> Result<Screenshot, String> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("Only http(s) pages can be captured: {}", url));
+    }
+    let viewport = Viewport {
+        width: viewport.width.clamp(200, 3840),
+        height: viewport.height.clamp(200, 4320),
+    };
+    let browser = find_browser().ok_or("No Chrome, Chromium, or Edge installation found")?;
+
+    let dir = evidence_dir(project_path, story_id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create evidence directory: {}", e))?;
+    let captured_at = chrono::Utc::now();
+    let path = dir.join(format!(
+        "{}-{}x{}.png",
+        captured_at.format("%Y%m%d-%H%M%S"),
+        viewport.width,
+        viewport.height
+    ));
+
+    // A throwaway profile keeps the capture apart from a browser the user has open
+    let profile = std::env::temp_dir().join(format!("ideate-screenshot-{}", uuid::Uuid::new_v4()));
+    let mut child = Command::new(&browser)
+        .args([
+            "--headless=new".to_string(),
+            "--disable-gpu".to_string(),
+            "--hide-scrollbars".to_string(),
+            "--no-first-run".to_string(),
+            "--no-default-browser-check".to_string(),
+            format!("--user-data-dir={}", profile.display()),
+            format!("--window-size={},{}", viewport.width, viewport.height),
+            format!("--virtual-time-budget={}", LOAD_BUDGET_MS),
+            format!("--screenshot={}", path.display()),
+            url.to_string(),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", browser.display(), e))?;
+
+    let started = Instant::now();
+    let finished = loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break true,
+            Ok(None) if started.elapsed() < CAPTURE_TIMEOUT => thread::sleep(Duration::from_millis(200)),
+            _ => {
+                terminate_child(&mut child, Duration::from_secs(2));
+                break false;
+            }
+        }
+    };
+    let _ = fs::remove_dir_all(&profile);
+
+    if !finished {
+        return Err(format!("Screenshot of {} timed out", url));
+    }
+    if !path.exists() {
+        return Err(format!("Browser did not write a screenshot of {}", url));
+    }
+
+    Ok(Screenshot {
+        path: path.to_string_lossy().to_string(),
+        story_id: story_id.to_string(),
+        url: Some(url.to_string()),
+        width: Some(viewport.width),
+        height: Some(viewport.height),
+        captured_at: captured_at.to_rfc3339(),
+    })
+}
+
+/// Screenshots a page into the story's evidence directory.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn capture_preview_screenshot(
+    project_path: String,
+    story_id: String,
+    url: String,
+    viewport: Option<Viewport>,
+) -> Result<Screenshot, String> {
+    tokio::task::spawn_blocking(move || capture(&project_path, &story_id, &url, viewport.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Lists a story's screenshots, newest first.
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_story_screenshots(project_path: String, story_id: String) -> Result<Vec<Screenshot>, String> {
+    let dir = evidence_dir(&project_path, &story_id);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut screenshots: Vec<Screenshot> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .map(|path| {
+            let captured_at = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+                .unwrap_or_default();
+            // Sizes are encoded in the file name as "<timestamp>-<width>x<height>.png"
+            let size = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.rsplit('-').next())
+                .and_then(|s| s.split_once('x'))
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+            Screenshot {
+                path: path.to_string_lossy().to_string(),
+                story_id: story_id.clone(),
+                url: None,
+                width: size.map(|(w, _)| w),
+                height: size.map(|(_, h)| h),
+                captured_at,
+            }
+        })
+        .collect();
+    screenshots.sort_by(|a, b| b.captured_at.cmp(&a.captured_at));
+
+    Ok(screenshots)
+}
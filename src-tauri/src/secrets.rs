@@ -0,0 +1,199 @@
+//! Secret storage for provider credentials (API keys, auth tokens).
+//!
+//! Secrets are kept in the OS keychain (Keychain on macOS, Credential Manager
+//! on Windows, the Secret Service on Linux), separate from preferences so they
+//! are never echoed back to the frontend. Where no keychain is available, such
+//! as a headless Linux server, they fall back to `secrets.json` in the app data
+//! directory, restricted to the current user on Unix. Secrets left in that
+//! file by earlier versions are moved into the keychain once one is usable.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+#[cfg(feature = "gui")]
+use std::path::PathBuf;
+use std::sync::OnceLock;
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+
+/// Keychain service the entries are stored under, the app's bundle identifier.
+const KEYCHAIN_SERVICE: &str = "com.kevinelliott.ideate";
+
+/// Entry looked up to tell whether the keychain works at all.
+const PROBE_KEY: &str = "keychain-probe";
+
+const SECRETS_FILE: &str = "secrets.json";
+
+#[cfg(feature = "gui")]
+fn get_secrets_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_data_dir(app)?;
+
+    if !app_data_dir.exists() {
+        fs::create_dir_all(&app_data_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    Ok(app_data_dir.join(SECRETS_FILE))
+}
+
+fn read_secrets_file(path: &Path) -> Result<HashMap<String, String>, String> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read secrets.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse secrets.json: {}", e))
+}
+
+fn write_secrets_file(path: &Path, secrets: &HashMap<String, String>) -> Result<(), String> {
+    if secrets.is_empty() {
+        if path.exists() {
+            fs::remove_file(path).map_err(|e| format!("Failed to remove secrets.json: {}", e))?;
+        }
+        return Ok(());
+    }
+
+    let json = serde_json::to_string_pretty(secrets)
+        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+
+    fs::write(path, json).map_err(|e| format!("Failed to write secrets.json: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict secrets.json permissions: {}", e))?;
+    }
+
+    Ok(())
+}
+
+fn keychain_entry(key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, key).map_err(|e| format!("Failed to open keychain entry {}: {}", key, e))
+}
+
+/// Whether the OS has a keychain we can use. Checked once per launch, since
+/// on Linux it means a round trip to the Secret Service.
+fn keychain_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let available = keyring::Entry::new(KEYCHAIN_SERVICE, PROBE_KEY)
+            .and_then(|entry| entry.get_password())
+            .map_or_else(|e| matches!(e, keyring::Error::NoEntry), |_| true);
+        if !available {
+            tracing::warn!("No OS keychain available; storing secrets in {}", SECRETS_FILE);
+        }
+        available
+    })
+}
+
+/// Moves secrets out of `path` into the keychain. Any that can't be moved
+/// stay in the file, where reads still find them.
+fn migrate_secrets_file(path: &Path) -> Result<(), String> {
+    let mut secrets = read_secrets_file(path)?;
+    if secrets.is_empty() {
+        return Ok(());
+    }
+
+    let count = secrets.len();
+    secrets.retain(|key, value| match keychain_entry(key).and_then(|entry| {
+        entry
+            .set_password(value)
+            .map_err(|e| format!("Failed to store {} in the keychain: {}", key, e))
+    }) {
+        Ok(()) => false,
+        Err(e) => {
+            tracing::warn!("{}", e);
+            true
+        }
+    });
+    tracing::info!("Moved {} secret(s) from {} to the keychain", count - secrets.len(), SECRETS_FILE);
+    write_secrets_file(path, &secrets)
+}
+
+/// Whether to use the keychain rather than the file at `path`, moving the
+/// file's secrets into the keychain first when it is.
+fn use_keychain(path: &Path) -> Result<bool, String> {
+    if !keychain_available() {
+        return Ok(false);
+    }
+    migrate_secrets_file(path)?;
+    Ok(true)
+}
+
+fn read_secret(path: &Path, key: &str) -> Result<Option<String>, String> {
+    if use_keychain(path)? {
+        match keychain_entry(key)?.get_password() {
+            Ok(value) => return Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(format!("Failed to read {} from the keychain: {}", key, e)),
+        }
+    }
+    Ok(read_secrets_file(path)?.remove(key))
+}
+
+#[cfg(feature = "gui")]
+fn store_secret(path: &Path, key: &str, value: &str) -> Result<(), String> {
+    if use_keychain(path)? {
+        return keychain_entry(key)?
+            .set_password(value)
+            .map_err(|e| format!("Failed to store {} in the keychain: {}", key, e));
+    }
+    let mut secrets = read_secrets_file(path)?;
+    secrets.insert(key.to_string(), value.to_string());
+    write_secrets_file(path, &secrets)
+}
+
+#[cfg(feature = "gui")]
+fn remove_secret(path: &Path, key: &str) -> Result<(), String> {
+    if use_keychain(path)? {
+        match keychain_entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(format!("Failed to delete {} from the keychain: {}", key, e)),
+        }
+    }
+    let mut secrets = read_secrets_file(path)?;
+    if secrets.remove(key).is_some() {
+        write_secrets_file(path, &secrets)?;
+    }
+    Ok(())
+}
+
+/// Reads a secret by key (internal function, not a command).
+#[cfg(feature = "gui")]
+pub fn get_secret(app: &AppHandle, key: &str) -> Result<Option<String>, String> {
+    read_secret(&get_secrets_file_path(app)?, key)
+}
+
+/// Reads a secret from the store for `app_data_dir`, for the headless CLI.
+pub fn get_secret_in(app_data_dir: &Path, key: &str) -> Result<Option<String>, String> {
+    read_secret(&app_data_dir.join(SECRETS_FILE), key)
+}
+
+/// Stores a secret under the given key, replacing any previous value.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn set_secret(app: AppHandle, key: String, value: String) -> Result<(), String> {
+    store_secret(&get_secrets_file_path(&app)?, &key, &value)
+}
+
+/// Removes a stored secret.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn delete_secret(app: AppHandle, key: String) -> Result<(), String> {
+    remove_secret(&get_secrets_file_path(&app)?, &key)
+}
+
+/// Checks whether a secret is stored, without exposing its value.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn has_secret(app: AppHandle, key: String) -> Result<bool, String> {
+    Ok(get_secret(&app, &key)?
+        .map(|v| !v.is_empty())
+        .unwrap_or(false))
+}
@@ -0,0 +1,479 @@
+//! Read-only spectator streams of live builds.
+//!
+//! `start_spectator_server` serves one project's build to teammates who
+//! don't have the app: a page that shows the stories, agent output, and what
+//! the build has changed so far, fed by a WebSocket. It listens on every
+//! interface, so anyone on the local network can open the link, and can also
+//! be tunneled to a public URL with the project's tunnel provider.
+//!
+//! - `GET /` — the spectator page
+//! - `GET /api/status` — project, build, stories, and a diff summary
+//! - `GET /api/events` (WebSocket) — agent output, story status, and a new
+//!   diff summary after each story
+//!
+//! Every request needs the server's token as `?token=`. Each server mints
+//! its own, and stopping the server revokes it. Nothing can be started,
+//! stopped, or changed through it, and output is always redacted.
+
+use std::collections::HashMap;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+use tokio::sync::{broadcast, watch};
+
+use crate::control_api::{request_token, tokens_match};
+use crate::event_bridge::{self, BridgeEvent};
+use crate::git_binary;
+use crate::integrations::tunnels;
+use crate::orchestrator::{self, BuildStatusInfo};
+use crate::projects::{load_prd, load_project_settings, load_projects};
+use crate::redact;
+
+/// Events spectators receive.
+const SPECTATOR_EVENTS: &[&str] = &[
+    "agent-output",
+    "agent-exit",
+    "build-status",
+    "build-log",
+    "build-story-status",
+    "build-story-finished",
+    "build-story-failed",
+    "build-finished",
+];
+
+/// Files listed in a diff summary; the totals count all of them.
+const MAX_DIFF_FILES: usize = 50;
+
+const TUNNEL_READY_TIMEOUT: Duration = Duration::from_secs(45);
+
+const PAGE: &str = include_str!("spectator.html");
+
+lazy_static::lazy_static! {
+    static ref SERVERS: Mutex<HashMap<String, SpectatorHandle>> = Mutex::new(HashMap::new());
+}
+
+struct SpectatorHandle {
+    port: u16,
+    token: String,
+    started_at: String,
+    tunnel_id: Option<String>,
+    public_url: Option<String>,
+    viewers: Arc<AtomicUsize>,
+    /// Set to true to stop the server and close its WebSockets; it never
+    /// changes otherwise.
+    shutdown_tx: watch::Sender<bool>,
+}
+
+struct SpectatorState {
+    app: AppHandle,
+    project_id: String,
+    token: String,
+    viewers: Arc<AtomicUsize>,
+    shutdown_rx: watch::Receiver<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectatorInfo {
+    pub project_id: String,
+    pub port: u16,
+    pub token: String,
+    /// Link for this computer.
+    pub local_url: String,
+    /// Link for others on the local network.
+    pub network_url: Option<String>,
+    /// Link through a tunnel, when started with `public`.
+    pub public_url: Option<String>,
+    pub viewers: usize,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileChange {
+    path: String,
+    insertions: u64,
+    deletions: u64,
+}
+
+/// What the build has changed in the project so far.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiffSummary {
+    files_changed: usize,
+    insertions: u64,
+    deletions: u64,
+    files: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpectatorStory {
+    id: String,
+    title: String,
+    passes: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpectatorStatus {
+    project_name: String,
+    build: Option<BuildStatusInfo>,
+    stories: Vec<SpectatorStory>,
+    changes: DiffSummary,
+}
+
+fn git_output(dir: &str, args: &[&str]) -> Option<String> {
+    let output = git_binary::command().args(args).current_dir(dir).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Changes since the build started, committed or not; with no build, the
+/// uncommitted ones.
+fn diff_summary(project_path: &str, since: Option<&str>) -> DiffSummary {
+    let base = since
+        .and_then(|since| git_output(project_path, &["rev-list", "-n", "1", &format!("--before={}", since), "HEAD"]))
+        .filter(|rev| !rev.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let Some(numstat) = git_output(project_path, &["diff", "--numstat", &base]) else {
+        return DiffSummary::default();
+    };
+    let mut summary = DiffSummary::default();
+    for line in numstat.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        // Binary files show "-" for both counts
+        let insertions = added.parse().unwrap_or(0);
+        let deletions = removed.parse().unwrap_or(0);
+        summary.files_changed += 1;
+        summary.insertions += insertions;
+        summary.deletions += deletions;
+        if summary.files.len() < MAX_DIFF_FILES {
+            summary.files.push(FileChange {
+                path: path.to_string(),
+                insertions,
+                deletions,
+            });
+        }
+    }
+    summary
+}
+
+/// Masks secrets in every string in `value`.
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            if let std::borrow::Cow::Owned(masked) = redact::redact(text) {
+                *text = masked;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::Object(fields) => fields.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// An address others on the local network can reach this computer at. No
+/// packets are sent; connecting a UDP socket only picks the route.
+fn network_ip() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then(|| ip.to_string())
+}
+
+fn project_path(app: &AppHandle, project_id: &str) -> Result<(String, String), String> {
+    load_projects(app.clone())?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .map(|p| (p.name, p.path))
+        .ok_or_else(|| format!("Project {} not found", project_id))
+}
+
+fn status_for(app: &AppHandle, project_id: &str) -> Result<SpectatorStatus, String> {
+    let (project_name, path) = project_path(app, project_id)?;
+    let build = orchestrator::get_build_status(project_id.to_string())?;
+    let stories = load_prd(path.clone())?
+        .map(|prd| prd.user_stories)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| SpectatorStory {
+            title: redact::redact(&s.title).into_owned(),
+            id: s.id,
+            passes: s.passes,
+        })
+        .collect();
+    Ok(SpectatorStatus {
+        changes: diff_summary(&path, build.as_ref().map(|b| b.started_at.as_str())),
+        project_name,
+        build,
+        stories,
+    })
+}
+
+async fn require_token(State(state): State<Arc<SpectatorState>>, request: Request, next: Next) -> Response {
+    if !request_token(&request).is_some_and(|token| tokens_match(&token, &state.token)) {
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response();
+    }
+    next.run(request).await
+}
+
+async fn page() -> Html<&'static str> {
+    Html(PAGE)
+}
+
+async fn status(State(state): State<Arc<SpectatorState>>) -> Result<Json<SpectatorStatus>, (StatusCode, String)> {
+    let app = state.app.clone();
+    let project_id = state.project_id.clone();
+    tokio::task::spawn_blocking(move || status_for(&app, &project_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+async fn send_json(socket: &mut WebSocket, value: &impl Serialize) -> bool {
+    let Ok(text) = serde_json::to_string(value) else {
+        return true;
+    };
+    socket.send(Message::Text(text.into())).await.is_ok()
+}
+
+async fn diff_event(state: &SpectatorState) -> Option<Value> {
+    let app = state.app.clone();
+    let project_id = state.project_id.clone();
+    let changes = tokio::task::spawn_blocking(move || {
+        let (_, path) = project_path(&app, &project_id).ok()?;
+        let build = orchestrator::get_build_status(project_id).ok().flatten();
+        Some(diff_summary(&path, build.as_ref().map(|b| b.started_at.as_str())))
+    })
+    .await
+    .ok()??;
+    Some(json!({
+        "event": "diff-summary",
+        "projectId": state.project_id,
+        "payload": changes,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+async fn watch(mut socket: WebSocket, state: Arc<SpectatorState>) {
+    state.viewers.fetch_add(1, Ordering::SeqCst);
+    let mut events = event_bridge::subscribe();
+    let mut shutdown = state.shutdown_rx.clone();
+    loop {
+        tokio::select! {
+            received = events.recv() => match received {
+                Ok(BridgeEvent { event, project_id, mut payload, timestamp }) => {
+                    if project_id.as_deref() != Some(state.project_id.as_str())
+                        || !SPECTATOR_EVENTS.contains(&event.as_str())
+                    {
+                        continue;
+                    }
+                    redact_value(&mut payload);
+                    let finished_story = event == "build-story-finished";
+                    let message = json!({ "event": event, "projectId": project_id, "payload": payload, "timestamp": timestamp });
+                    if !send_json(&mut socket, &message).await {
+                        break;
+                    }
+                    if finished_story {
+                        if let Some(diff) = diff_event(&state).await {
+                            if !send_json(&mut socket, &diff).await {
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                // Spectators can't send anything
+                Some(Ok(_)) => {}
+            },
+            // Upgraded sockets outlive the server's graceful shutdown
+            _ = shutdown.changed() => break,
+        }
+    }
+    state.viewers.fetch_sub(1, Ordering::SeqCst);
+}
+
+async fn events(ws: WebSocketUpgrade, State(state): State<Arc<SpectatorState>>) -> Response {
+    ws.on_upgrade(move |socket| watch(socket, state))
+}
+
+fn info(project_id: &str, handle: &SpectatorHandle) -> SpectatorInfo {
+    let link = |host: &str| format!("http://{}:{}/?token={}", host, handle.port, handle.token);
+    SpectatorInfo {
+        project_id: project_id.to_string(),
+        port: handle.port,
+        token: handle.token.clone(),
+        local_url: link("127.0.0.1"),
+        network_url: network_ip().map(|ip| link(&ip)),
+        public_url: handle
+            .public_url
+            .as_ref()
+            .map(|url| format!("{}/?token={}", url.trim_end_matches('/'), handle.token)),
+        viewers: handle.viewers.load(Ordering::SeqCst),
+        started_at: handle.started_at.clone(),
+    }
+}
+
+/// Tunnels a running spectator server to a public URL.
+async fn publish(app: &AppHandle, project_id: &str, port: u16) -> Result<(String, String), String> {
+    let (_, path) = project_path(app, project_id)?;
+    let provider = load_project_settings(path)
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.tunnel_provider);
+    let tunnel = tunnels::ensure_tunnel(
+        app,
+        project_id,
+        port,
+        provider.as_deref(),
+        "Spectator view",
+        TUNNEL_READY_TIMEOUT,
+    )
+    .await?;
+    let url = tunnel.url.ok_or("Tunnel has no public URL")?;
+    Ok((tunnel.tunnel_id, url))
+}
+
+/// Starts a spectator server for the project.
+fn serve(app: &AppHandle, project_id: &str) -> Result<SpectatorHandle, String> {
+    // Bound synchronously so the caller can hold the `SERVERS` lock throughout
+    let listener = std::net::TcpListener::bind("0.0.0.0:0")
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+        .and_then(tokio::net::TcpListener::from_std)
+        .map_err(|e| format!("Failed to bind to port: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to get local address: {}", e))?
+        .port();
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let viewers = Arc::new(AtomicUsize::new(0));
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let state = Arc::new(SpectatorState {
+        app: app.clone(),
+        project_id: project_id.to_string(),
+        token: token.clone(),
+        viewers: viewers.clone(),
+        shutdown_rx: shutdown_rx.clone(),
+    });
+    let router = Router::new()
+        .route("/", get(page))
+        .route("/api/status", get(status))
+        .route("/api/events", get(events))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    let mut shutdown = shutdown_rx;
+    tokio::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async move {
+            let _ = shutdown.changed().await;
+        });
+        if let Err(e) = server.await {
+            tracing::error!("Spectator server error: {}", e);
+        }
+    });
+
+    tracing::info!("Spectator view for project {} on port {}", project_id, port);
+    Ok(SpectatorHandle {
+        port,
+        token,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        tunnel_id: None,
+        public_url: None,
+        viewers,
+        shutdown_tx,
+    })
+}
+
+/// Starts serving a read-only view of the project's build, or returns the
+/// running one. With `public`, also tunnels it to a public URL.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_spectator_server(
+    app: AppHandle,
+    project_id: String,
+    public: Option<bool>,
+) -> Result<SpectatorInfo, String> {
+    project_path(&app, &project_id)?;
+    // Checked and registered under one lock, so concurrent starts share a server
+    let (port, published, started) = {
+        let mut servers = SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        match servers.get(&project_id) {
+            Some(handle) => (handle.port, handle.public_url.is_some(), false),
+            None => {
+                let handle = serve(&app, &project_id)?;
+                let port = handle.port;
+                servers.insert(project_id.clone(), handle);
+                (port, false, true)
+            }
+        }
+    };
+    if started {
+        crate::telemetry::record(&app, "feature.spectator");
+    }
+
+    if public.unwrap_or(false) && !published {
+        let (tunnel_id, url) = publish(&app, &project_id, port).await?;
+        if let Some(handle) = SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?.get_mut(&project_id) {
+            handle.tunnel_id = Some(tunnel_id);
+            handle.public_url = Some(url);
+        }
+    }
+    get_spectator_server(project_id)?.ok_or_else(|| "Spectator server stopped".to_string())
+}
+
+/// Returns the project's spectator server, if one is running.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_spectator_server(project_id: String) -> Result<Option<SpectatorInfo>, String> {
+    let servers = SERVERS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(servers.get(&project_id).map(|handle| info(&project_id, handle)))
+}
+
+/// Stops the project's spectator server and its tunnel. Its links stop
+/// working immediately.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_spectator_server(app: AppHandle, project_id: String) -> Result<(), String> {
+    let handle = SERVERS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .remove(&project_id);
+    let Some(handle) = handle else {
+        return Ok(());
+    };
+    handle.shutdown_tx.send_replace(true);
+    if let Some(tunnel_id) = handle.tunnel_id {
+        tunnels::stop_tunnel(app, tunnel_id).await?;
+    }
+    Ok(())
+}
+
+/// Stops every spectator server. Their tunnels are stopped with the others.
+pub fn stop_all() {
+    if let Ok(mut servers) = SERVERS.lock() {
+        for (_, handle) in servers.drain() {
+            handle.shutdown_tx.send_replace(true);
+        }
+    }
+}
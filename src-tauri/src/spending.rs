@@ -0,0 +1,375 @@
+//! Monthly spending reports.
+//!
+//! Aggregates the recorded runs of every project for one calendar month into
+//! a per-project, per-model breakdown and renders it as Markdown, CSV, and a
+//! self-contained HTML page suitable for printing to PDF. The rendered files
+//! are kept in `reports/` in the app data directory.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::currency::{self, DisplayCurrency};
+use crate::models::CostEntry;
+use crate::projects::{load_cost_history, load_projects};
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSpending {
+    pub agent_id: String,
+    pub model: String,
+    pub runs: usize,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSpending {
+    pub project_id: String,
+    pub project_name: String,
+    pub runs: usize,
+    pub total_tokens: i64,
+    pub cost: f64,
+    /// Sorted by cost, highest first.
+    pub models: Vec<ModelSpending>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingReport {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub generated_at: String,
+    pub display_currency: DisplayCurrency,
+    pub runs: usize,
+    pub total_tokens: i64,
+    pub total_cost: f64,
+    /// Projects with runs in the month, highest cost first.
+    pub projects: Vec<ProjectSpending>,
+    /// Totals per agent and model across all projects.
+    pub models: Vec<ModelSpending>,
+    /// Projects whose cost history couldn't be read.
+    pub skipped_projects: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedSpendingReport {
+    pub report: SpendingReport,
+    pub markdown: String,
+    pub csv: String,
+    pub html: String,
+    /// Where the rendered files were saved.
+    pub directory: String,
+}
+
+/// Parses `YYYY-MM` into the first day of that month.
+fn parse_month(month: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("Failed to generate spending report: invalid month {:?}, expected YYYY-MM", month))
+}
+
+fn in_month(entry: &CostEntry, start: NaiveDate) -> bool {
+    chrono::DateTime::parse_from_rfc3339(&entry.timestamp).is_ok_and(|t| {
+        let date = t.with_timezone(&chrono::Utc).date_naive();
+        date.year() == start.year() && date.month() == start.month()
+    })
+}
+
+fn add_entry(totals: &mut BTreeMap<(String, String), ModelSpending>, entry: &CostEntry) {
+    let model = entry.model.clone().unwrap_or_else(|| "unknown".to_string());
+    let spending = totals
+        .entry((entry.agent_id.clone(), model.clone()))
+        .or_insert_with(|| ModelSpending {
+            agent_id: entry.agent_id.clone(),
+            model,
+            ..Default::default()
+        });
+    spending.runs += 1;
+    spending.input_tokens += entry.input_tokens.unwrap_or(0);
+    spending.output_tokens += entry.output_tokens.unwrap_or(0);
+    spending.total_tokens += entry
+        .total_tokens
+        .unwrap_or(entry.input_tokens.unwrap_or(0) + entry.output_tokens.unwrap_or(0));
+    spending.cost += entry.cost.unwrap_or(0.0);
+}
+
+fn by_cost(totals: BTreeMap<(String, String), ModelSpending>) -> Vec<ModelSpending> {
+    let mut models: Vec<_> = totals.into_values().collect();
+    models.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    models
+}
+
+fn build_report(app: &AppHandle, month: NaiveDate) -> Result<SpendingReport, String> {
+    let mut projects = Vec::new();
+    let mut skipped_projects = Vec::new();
+    let mut all_models = BTreeMap::new();
+
+    for project in load_projects(app.clone())? {
+        let history = match load_cost_history(project.path.clone()) {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!("Skipping {} in spending report: {}", project.name, e);
+                skipped_projects.push(project.name);
+                continue;
+            }
+        };
+        let mut models = BTreeMap::new();
+        for entry in history.entries.iter().filter(|e| in_month(e, month)) {
+            add_entry(&mut models, entry);
+            add_entry(&mut all_models, entry);
+        }
+        if models.is_empty() {
+            continue;
+        }
+        let models = by_cost(models);
+        projects.push(ProjectSpending {
+            project_id: project.id,
+            project_name: project.name,
+            runs: models.iter().map(|m| m.runs).sum(),
+            total_tokens: models.iter().map(|m| m.total_tokens).sum(),
+            cost: models.iter().map(|m| m.cost).sum(),
+            models,
+        });
+    }
+    projects.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+    Ok(SpendingReport {
+        month: month.format("%Y-%m").to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        display_currency: currency::display_currency(app),
+        runs: projects.iter().map(|p| p.runs).sum(),
+        total_tokens: projects.iter().map(|p| p.total_tokens).sum(),
+        total_cost: projects.iter().map(|p| p.cost).sum(),
+        projects,
+        models: by_cost(all_models),
+        skipped_projects,
+    })
+}
+
+impl SpendingReport {
+    fn month_label(&self) -> String {
+        parse_month(&self.month)
+            .map(|d| d.format("%B %Y").to_string())
+            .unwrap_or_else(|_| self.month.clone())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let currency = &self.display_currency;
+        let mut out = format!("# Spending Report: {}\n\n", self.month_label());
+        out.push_str(&format!(
+            "**Total:** {} · {} tokens · {} runs across {} project(s)\n\n",
+            currency.format(self.total_cost),
+            self.total_tokens,
+            self.runs,
+            self.projects.len()
+        ));
+        if self.projects.is_empty() {
+            out.push_str("No runs were recorded this month.\n");
+        }
+
+        for project in &self.projects {
+            out.push_str(&format!(
+                "## {}\n\n{} · {} tokens · {} runs\n\n",
+                project.project_name,
+                currency.format(project.cost),
+                project.total_tokens,
+                project.runs
+            ));
+            out.push_str("| Agent | Model | Runs | Input | Output | Cost |\n|---|---|---:|---:|---:|---:|\n");
+            for m in &project.models {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    m.agent_id,
+                    m.model,
+                    m.runs,
+                    m.input_tokens,
+                    m.output_tokens,
+                    currency.format(m.cost)
+                ));
+            }
+            out.push('\n');
+        }
+
+        if !self.models.is_empty() {
+            out.push_str("## By Model\n\n| Agent | Model | Runs | Tokens | Cost |\n|---|---|---:|---:|---:|\n");
+            for m in &self.models {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    m.agent_id,
+                    m.model,
+                    m.runs,
+                    m.total_tokens,
+                    currency.format(m.cost)
+                ));
+            }
+            out.push('\n');
+        }
+        if !self.skipped_projects.is_empty() {
+            out.push_str(&format!("_Not included (unreadable cost history): {}_\n", self.skipped_projects.join(", ")));
+        }
+        out
+    }
+
+    /// One row per project, agent, and model.
+    pub fn to_csv(&self) -> String {
+        let currency = &self.display_currency;
+        let mut header = "month,project,agent,model,runs,input_tokens,output_tokens,total_tokens,cost_usd".to_string();
+        if !currency.is_usd() {
+            header.push_str(&format!(",cost_{}", currency.code.to_lowercase()));
+        }
+        let mut out = header + "\n";
+        for project in &self.projects {
+            for m in &project.models {
+                out.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{:.4}",
+                    self.month,
+                    csv_field(&project.project_name),
+                    csv_field(&m.agent_id),
+                    csv_field(&m.model),
+                    m.runs,
+                    m.input_tokens,
+                    m.output_tokens,
+                    m.total_tokens,
+                    m.cost
+                ));
+                if !currency.is_usd() {
+                    out.push_str(&format!(",{:.4}", currency.convert(m.cost)));
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// A standalone page with print styles, for saving as PDF.
+    pub fn to_html(&self) -> String {
+        let currency = &self.display_currency;
+        let title = format!("Spending Report: {}", self.month_label());
+        let mut body = format!(
+            "<h1>{}</h1>\n<p class=\"summary\">{} · {} tokens · {} runs across {} project(s)</p>\n",
+            escape_html(&title),
+            escape_html(&currency.format(self.total_cost)),
+            self.total_tokens,
+            self.runs,
+            self.projects.len()
+        );
+        if self.projects.is_empty() {
+            body.push_str("<p>No runs were recorded this month.</p>\n");
+        }
+
+        for project in &self.projects {
+            body.push_str(&format!(
+                "<h2>{}</h2>\n<p>{} · {} tokens · {} runs</p>\n<table>\n<tr><th>Agent</th><th>Model</th><th>Runs</th><th>Input</th><th>Output</th><th>Cost</th></tr>\n",
+                escape_html(&project.project_name),
+                escape_html(&currency.format(project.cost)),
+                project.total_tokens,
+                project.runs
+            ));
+            for m in &project.models {
+                body.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&m.agent_id),
+                    escape_html(&m.model),
+                    m.runs,
+                    m.input_tokens,
+                    m.output_tokens,
+                    escape_html(&currency.format(m.cost))
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        if !self.models.is_empty() {
+            body.push_str("<h2>By Model</h2>\n<table>\n<tr><th>Agent</th><th>Model</th><th>Runs</th><th>Tokens</th><th>Cost</th></tr>\n");
+            for m in &self.models {
+                body.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&m.agent_id),
+                    escape_html(&m.model),
+                    m.runs,
+                    m.total_tokens,
+                    escape_html(&currency.format(m.cost))
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+             body {{ font-family: -apple-system, system-ui, sans-serif; color: #222; margin: 2rem; }}\n\
+             h1 {{ font-size: 1.5rem; }} h2 {{ font-size: 1.15rem; margin-top: 2rem; }}\n\
+             .summary {{ font-size: 1.05rem; }}\n\
+             table {{ border-collapse: collapse; width: 100%; font-size: 0.85rem; }}\n\
+             th, td {{ border-bottom: 1px solid #ddd; padding: 0.35rem 0.5rem; text-align: left; }}\n\
+             td:nth-child(n+3), th:nth-child(n+3) {{ text-align: right; }}\n\
+             @media print {{ body {{ margin: 0; }} h2 {{ break-after: avoid; }} tr {{ break-inside: avoid; }} }}\n\
+             </style>\n</head>\n<body>\n{}<footer><p>Generated {}</p></footer>\n</body>\n</html>\n",
+            escape_html(&title),
+            body,
+            escape_html(&self.generated_at)
+        )
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn reports_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_data_dir(app)?.join("reports");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create reports directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Builds the spending report for `month` (`YYYY-MM`) across all projects,
+/// saves it as Markdown, CSV, and HTML, and returns it.
+#[tauri::command]
+pub fn generate_spending_report(app: AppHandle, month: String) -> Result<GeneratedSpendingReport, String> {
+    let start = parse_month(&month)?;
+    let report = build_report(&app, start)?;
+    let markdown = report.to_markdown();
+    let csv = report.to_csv();
+    let html = report.to_html();
+
+    let dir = reports_dir(&app)?;
+    let stem = format!("spending-{}", report.month);
+    for (ext, content) in [("md", &markdown), ("csv", &csv), ("html", &html)] {
+        let path = dir.join(format!("{}.{}", stem, ext));
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    tracing::info!(
+        "Generated spending report for {}: {} runs, ${:.2}",
+        report.month,
+        report.runs,
+        report.total_cost
+    );
+
+    Ok(GeneratedSpendingReport {
+        report,
+        markdown,
+        csv,
+        html,
+        directory: dir.to_string_lossy().to_string(),
+    })
+}
@@ -4,16 +4,24 @@
 //! to guide the AI agents during development.
 
 use std::fs;
+use std::path::Path;
+#[cfg(feature = "gui")]
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+#[cfg(feature = "gui")]
+use tauri::{AppHandle, Emitter};
 
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
 use crate::models::{Stack, StackTool};
+#[cfg(feature = "gui")]
+use crate::models::{StackToolUpdate, StoredProject};
+use crate::projects::load_project_settings;
+#[cfg(feature = "gui")]
+use crate::projects::load_projects;
 
+#[cfg(feature = "gui")]
 fn get_stacks_file_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir = app_data_dir(app)?;
     
     if !app_data_dir.exists() {
         fs::create_dir_all(&app_data_dir)
@@ -41,6 +49,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "React Router".to_string(), category: "Routing".to_string(), version: Some("7".to_string()), description: Some("Declarative routing for React".to_string()), website: Some("https://reactrouter.com".to_string()) },
             ],
             tags: vec!["react".to_string(), "vite".to_string(), "typescript".to_string(), "spa".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -64,6 +73,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "Tailwind CSS".to_string(), category: "Styling".to_string(), version: Some("4".to_string()), description: Some("Utility-first CSS framework".to_string()), website: Some("https://tailwindcss.com".to_string()) },
             ],
             tags: vec!["react".to_string(), "tauri".to_string(), "desktop".to_string(), "mobile".to_string(), "rust".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -87,6 +97,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "Tailwind CSS".to_string(), category: "Styling".to_string(), version: Some("4".to_string()), description: Some("Utility-first CSS framework".to_string()), website: Some("https://tailwindcss.com".to_string()) },
             ],
             tags: vec!["react".to_string(), "supabase".to_string(), "postgresql".to_string(), "fullstack".to_string(), "auth".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -109,6 +120,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "Vercel".to_string(), category: "Deployment".to_string(), version: None, description: Some("Platform for frontend frameworks and static sites".to_string()), website: Some("https://vercel.com".to_string()) },
             ],
             tags: vec!["nextjs".to_string(), "react".to_string(), "ssr".to_string(), "fullstack".to_string(), "vercel".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -131,6 +143,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "Vite".to_string(), category: "Build Tool".to_string(), version: Some("6".to_string()), description: Some("Next generation frontend tooling".to_string()), website: Some("https://vite.dev".to_string()) },
             ],
             tags: vec!["svelte".to_string(), "sveltekit".to_string(), "typescript".to_string(), "fullstack".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -154,6 +167,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "uv".to_string(), category: "Package Manager".to_string(), version: None, description: Some("Extremely fast Python package installer".to_string()), website: Some("https://docs.astral.sh/uv".to_string()) },
             ],
             tags: vec!["python".to_string(), "fastapi".to_string(), "api".to_string(), "backend".to_string(), "async".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -176,6 +190,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "PostgreSQL".to_string(), category: "Database".to_string(), version: None, description: Some("Powerful open source relational database".to_string()), website: Some("https://postgresql.org".to_string()) },
             ],
             tags: vec!["node".to_string(), "express".to_string(), "prisma".to_string(), "typescript".to_string(), "backend".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -198,6 +213,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "React Navigation".to_string(), category: "Navigation".to_string(), version: Some("7".to_string()), description: Some("Routing and navigation for React Native".to_string()), website: Some("https://reactnavigation.org".to_string()) },
             ],
             tags: vec!["react-native".to_string(), "expo".to_string(), "mobile".to_string(), "ios".to_string(), "android".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -219,6 +235,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "serde".to_string(), category: "Serialization".to_string(), version: Some("1".to_string()), description: Some("Serialization framework for Rust".to_string()), website: Some("https://serde.rs".to_string()) },
             ],
             tags: vec!["rust".to_string(), "cli".to_string(), "terminal".to_string(), "command-line".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -240,6 +257,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "MDX".to_string(), category: "Content".to_string(), version: None, description: Some("Markdown for the component era".to_string()), website: Some("https://mdxjs.com".to_string()) },
             ],
             tags: vec!["astro".to_string(), "static".to_string(), "content".to_string(), "blog".to_string(), "docs".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -261,6 +279,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "PostgreSQL".to_string(), category: "Database".to_string(), version: None, description: Some("Powerful open source relational database".to_string()), website: Some("https://postgresql.org".to_string()) },
             ],
             tags: vec!["go".to_string(), "golang".to_string(), "api".to_string(), "backend".to_string(), "performance".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -284,6 +303,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
                 StackTool { name: "Tailwind CSS".to_string(), category: "Styling".to_string(), version: Some("4".to_string()), description: Some("Utility-first CSS framework".to_string()), website: Some("https://tailwindcss.com".to_string()) },
             ],
             tags: vec!["t3".to_string(), "nextjs".to_string(), "trpc".to_string(), "prisma".to_string(), "fullstack".to_string(), "typesafe".to_string()],
+            prompt_snippets: Vec::new(),
             is_builtin: true,
             is_published: false,
             author: Some("Ideate".to_string()),
@@ -295,6 +315,7 @@ fn create_builtin_stacks() -> Vec<Stack> {
 }
 
 /// Loads all stacks from the app data directory, including built-in stacks.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn load_stacks(app: AppHandle) -> Result<Vec<Stack>, String> {
     let stacks_path = get_stacks_file_path(&app)?;
@@ -305,20 +326,22 @@ pub fn load_stacks(app: AppHandle) -> Result<Vec<Stack>, String> {
         return Ok(builtin_stacks);
     }
     
-    let content = fs::read_to_string(&stacks_path)
-        .map_err(|e| format!("Failed to read stacks.json: {}", e))?;
-    
-    let custom_stacks: Vec<Stack> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse stacks.json: {}", e))?;
-    
     // Combine builtin and custom stacks
     let mut all_stacks = builtin_stacks;
-    all_stacks.extend(custom_stacks);
+    all_stacks.extend(read_custom_stacks(&stacks_path)?);
     
     Ok(all_stacks)
 }
 
+fn read_custom_stacks(stacks_path: &Path) -> Result<Vec<Stack>, String> {
+    let content = fs::read_to_string(stacks_path)
+        .map_err(|e| format!("Failed to read stacks.json: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse stacks.json: {}", e))
+}
+
 /// Saves custom stacks to the app data directory (builtin stacks are not saved).
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn save_stacks(app: AppHandle, stacks: Vec<Stack>) -> Result<(), String> {
     let stacks_path = get_stacks_file_path(&app)?;
@@ -338,6 +361,7 @@ pub fn save_stacks(app: AppHandle, stacks: Vec<Stack>) -> Result<(), String> {
 }
 
 /// Deletes a custom stack by ID.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn delete_stack(app: AppHandle, stack_id: String) -> Result<(), String> {
     let stacks_path = get_stacks_file_path(&app)?;
@@ -362,3 +386,387 @@ pub fn delete_stack(app: AppHandle, stack_id: String) -> Result<(), String> {
     
     Ok(())
 }
+
+// ============================================================================
+// Stack Composition
+// ============================================================================
+
+/// Merges stacks into one, for projects that combine several, such as a
+/// React frontend with a FastAPI backend. Tools are de-duplicated by name,
+/// the first keeping its place and taking details it lacks from later ones;
+/// tags and prompt snippets are merged in order. A single stack is returned
+/// as it is.
+pub fn compose_stacks(stacks: Vec<Stack>) -> Option<Stack> {
+    let mut stacks = stacks.into_iter();
+    let mut composed = stacks.next()?;
+    let mut names = vec![composed.name.clone()];
+    let mut descriptions = vec![composed.description.clone()];
+    let mut ids = vec![composed.id.clone()];
+
+    for stack in stacks {
+        ids.push(stack.id);
+        names.push(stack.name);
+        descriptions.push(stack.description);
+        for tool in stack.tools {
+            match composed.tools.iter_mut().find(|t| t.name.eq_ignore_ascii_case(&tool.name)) {
+                Some(existing) => {
+                    existing.version = existing.version.take().or(tool.version);
+                    existing.description = existing.description.take().or(tool.description);
+                    existing.website = existing.website.take().or(tool.website);
+                }
+                None => composed.tools.push(tool),
+            }
+        }
+        for tag in stack.tags {
+            if !composed.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                composed.tags.push(tag);
+            }
+        }
+        for snippet in stack.prompt_snippets {
+            if !composed.prompt_snippets.iter().any(|s| s.trim() == snippet.trim()) {
+                composed.prompt_snippets.push(snippet);
+            }
+        }
+        if stack.updated_at > composed.updated_at {
+            composed.updated_at = stack.updated_at;
+        }
+    }
+
+    if ids.len() > 1 {
+        composed.id = ids.join("+");
+        composed.name = names.join(" + ");
+        composed.description = descriptions
+            .into_iter()
+            .filter(|d| !d.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        composed.is_builtin = false;
+        composed.is_published = false;
+    }
+    Some(composed)
+}
+
+/// The stack composed of `stack_ids`, in order. Custom stacks are read from
+/// `app_data_dir`; unknown ids are skipped.
+pub(crate) fn effective_stack_in(app_data_dir: Option<&Path>, stack_ids: &[String]) -> Option<Stack> {
+    if stack_ids.is_empty() {
+        return None;
+    }
+    let custom_stacks = app_data_dir
+        .map(|dir| dir.join("stacks.json"))
+        .filter(|path| path.exists())
+        .and_then(|path| read_custom_stacks(&path).ok())
+        .unwrap_or_default();
+    let mut available = create_builtin_stacks();
+    available.extend(custom_stacks);
+    let stacks = stack_ids
+        .iter()
+        .filter_map(|id| available.iter().find(|s| &s.id == id).cloned())
+        .collect();
+    compose_stacks(stacks)
+}
+
+/// A project's effective stack: its stack composed with its extra stacks.
+#[cfg(feature = "gui")]
+pub(crate) fn effective_stack(app: &AppHandle, project: &StoredProject) -> Option<Stack> {
+    let app_data_dir = app_data_dir(app).ok();
+    effective_stack_in(app_data_dir.as_deref(), &project.stack_ids())
+}
+
+/// Returns the effective stack of a project, composed from its stacks.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_project_stack(app: AppHandle, project_id: String) -> Result<Option<Stack>, String> {
+    let project = load_projects(app.clone())?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    Ok(effective_stack(&app, &project))
+}
+
+// ============================================================================
+// Prompt Snippets
+// ============================================================================
+
+/// Prompt snippets of the stack composed of `stack_ids` for builds of the
+/// project at `project_path`, or none if the project opted out. Custom stacks
+/// are read from `app_data_dir`.
+pub(crate) fn prompt_snippets_in(app_data_dir: Option<&Path>, project_path: &str, stack_ids: &[String]) -> Vec<String> {
+    let excluded = load_project_settings(project_path.to_string())
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.exclude_stack_snippets)
+        .unwrap_or(false);
+    if excluded {
+        return Vec::new();
+    }
+    effective_stack_in(app_data_dir, stack_ids)
+        .map(|s| s.prompt_snippets)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|snippet| !snippet.trim().is_empty())
+        .collect()
+}
+
+// ============================================================================
+// Stack Detection
+// ============================================================================
+
+/// Picks the built-in stack that best matches a project's manifests, if any.
+#[cfg(feature = "gui")]
+pub fn detect_stack(project_path: &Path) -> Option<&'static str> {
+    let read = |name: &str| fs::read_to_string(project_path.join(name)).ok();
+
+    if let Some(package) = read("package.json").and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok()) {
+        let has = |dep: &str| {
+            ["dependencies", "devDependencies"]
+                .iter()
+                .any(|section| package[section].get(dep).is_some())
+        };
+        let stack = if has("next") && has("@trpc/server") && (has("prisma") || has("@prisma/client")) {
+            Some("builtin-t3")
+        } else if has("next") {
+            Some("builtin-nextjs")
+        } else if has("@sveltejs/kit") {
+            Some("builtin-sveltekit")
+        } else if has("astro") {
+            Some("builtin-astro")
+        } else if has("expo") || has("react-native") {
+            Some("builtin-react-native")
+        } else if has("@tauri-apps/api") {
+            Some("builtin-react-tauri")
+        } else if has("react") && has("@supabase/supabase-js") {
+            Some("builtin-react-supabase")
+        } else if has("react") && has("vite") {
+            Some("builtin-react-vite")
+        } else if has("express") {
+            Some("builtin-node-express")
+        } else {
+            None
+        };
+        if stack.is_some() {
+            return stack;
+        }
+    }
+
+    if project_path.join("go.mod").exists() {
+        return Some("builtin-go-api");
+    }
+    if ["pyproject.toml", "requirements.txt"]
+        .iter()
+        .filter_map(|name| read(name))
+        .any(|content| content.to_lowercase().contains("fastapi"))
+    {
+        return Some("builtin-python-fastapi");
+    }
+    if project_path.join("Cargo.toml").exists() {
+        return Some("builtin-rust-cli");
+    }
+    None
+}
+
+/// Returns the ID of the built-in stack matching a project directory, if any.
+#[cfg(feature = "gui")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn detect_project_stack(project_path: String) -> Option<String> {
+    detect_stack(Path::new(&project_path)).map(str::to_string)
+}
+
+// ============================================================================
+// Stack Update Checks
+// ============================================================================
+
+/// Maps a stack tool name to the registry and package that publishes it.
+/// Tools without a well-known package (languages, hosted services) return None.
+#[cfg(feature = "gui")]
+fn registry_package_for_tool(tool_name: &str) -> Option<(&'static str, &'static str)> {
+    let mapping = match tool_name {
+        "React" => ("npm", "react"),
+        "Vite" => ("npm", "vite"),
+        "TypeScript" => ("npm", "typescript"),
+        "Tailwind CSS" => ("npm", "tailwindcss"),
+        "React Router" => ("npm", "react-router"),
+        "Next.js" => ("npm", "next"),
+        "NextAuth.js" => ("npm", "next-auth"),
+        "Prisma" => ("npm", "prisma"),
+        "tRPC" => ("npm", "@trpc/server"),
+        "Express" => ("npm", "express"),
+        "Astro" => ("npm", "astro"),
+        "Svelte" => ("npm", "svelte"),
+        "SvelteKit" => ("npm", "@sveltejs/kit"),
+        "Expo" => ("npm", "expo"),
+        "React Native" => ("npm", "react-native"),
+        "React Navigation" => ("npm", "@react-navigation/native"),
+        "NativeWind" => ("npm", "nativewind"),
+        "Tauri" => ("crates", "tauri"),
+        "tokio" => ("crates", "tokio"),
+        "serde" => ("crates", "serde"),
+        "clap" => ("crates", "clap"),
+        "FastAPI" => ("pypi", "fastapi"),
+        "Pydantic" => ("pypi", "pydantic"),
+        "SQLAlchemy" => ("pypi", "sqlalchemy"),
+        _ => return None,
+    };
+    Some(mapping)
+}
+
+/// Fetches the latest published version of a package from its registry.
+#[cfg(feature = "gui")]
+pub(crate) async fn fetch_latest_version(
+    client: &reqwest::Client,
+    registry: &str,
+    package: &str,
+) -> Result<String, String> {
+    let (url, pointer) = match registry {
+        "npm" => (
+            format!("https://registry.npmjs.org/{}/latest", package),
+            "/version",
+        ),
+        "crates" => (
+            format!("https://crates.io/api/v1/crates/{}", package),
+            "/crate/max_stable_version",
+        ),
+        "pypi" => (format!("https://pypi.org/pypi/{}/json", package), "/info/version"),
+        other => return Err(format!("Unknown registry: {}", other)),
+    };
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {}: {}", registry, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to query {}: HTTP {}", registry, response.status()));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {} response: {}", registry, e))?;
+
+    body.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string())
+        .ok_or_else(|| format!("No version found for {} on {}", package, registry))
+}
+
+/// Parses the leading numeric components of a version string ("v19.1.0-rc" -> [19, 1, 0]).
+#[cfg(feature = "gui")]
+fn parse_version_components(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .map_while(|part| part.parse::<u64>().ok())
+        .collect()
+}
+
+/// Returns true if `latest` is newer than `current` at the precision `current` pins.
+/// A stack pinned to "6" is only outdated once a 7.x ships, not on 6.1.
+#[cfg(feature = "gui")]
+pub(crate) fn is_version_outdated(current: &str, latest: &str) -> bool {
+    let current_parts = parse_version_components(current);
+    let latest_parts = parse_version_components(latest);
+
+    if current_parts.is_empty() || latest_parts.is_empty() {
+        return false;
+    }
+
+    for (i, current_part) in current_parts.iter().enumerate() {
+        let latest_part = latest_parts.get(i).copied().unwrap_or(0);
+        if latest_part != *current_part {
+            return latest_part > *current_part;
+        }
+    }
+
+    false
+}
+
+/// Compares every versioned tool in the given stacks against its registry.
+/// Registry lookups are cached per package so shared tools are only fetched once.
+#[cfg(feature = "gui")]
+async fn find_stack_updates(stacks: &[Stack]) -> Result<Vec<StackToolUpdate>, String> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("Ideate/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut latest_cache: std::collections::HashMap<String, Option<String>> =
+        std::collections::HashMap::new();
+    let mut updates = Vec::new();
+
+    for stack in stacks {
+        for tool in &stack.tools {
+            let Some(current_version) = &tool.version else {
+                continue;
+            };
+            let Some((registry, package)) = registry_package_for_tool(&tool.name) else {
+                continue;
+            };
+
+            let cache_key = format!("{}:{}", registry, package);
+            if !latest_cache.contains_key(&cache_key) {
+                let latest = match fetch_latest_version(&client, registry, package).await {
+                    Ok(version) => Some(version),
+                    Err(e) => {
+                        tracing::warn!("Stack update check: {}", e);
+                        None
+                    }
+                };
+                latest_cache.insert(cache_key.clone(), latest);
+            }
+
+            if let Some(Some(latest_version)) = latest_cache.get(&cache_key) {
+                if is_version_outdated(current_version, latest_version) {
+                    updates.push(StackToolUpdate {
+                        stack_id: stack.id.clone(),
+                        stack_name: stack.name.clone(),
+                        tool_name: tool.name.clone(),
+                        registry: registry.to_string(),
+                        package: package.to_string(),
+                        current_version: current_version.clone(),
+                        latest_version: latest_version.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Checks all stacks for tools whose pinned versions are behind the latest release.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn check_stack_updates(app: AppHandle) -> Result<Vec<StackToolUpdate>, String> {
+    let stacks = load_stacks(app)?;
+    find_stack_updates(&stacks).await
+}
+
+/// Runs a stack update check in the background after startup.
+/// Emits `stack-updates-available` if any tool is outdated.
+#[cfg(feature = "gui")]
+pub fn spawn_stack_update_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let stacks = match load_stacks(app.clone()) {
+            Ok(stacks) => stacks,
+            Err(e) => {
+                tracing::info!("Stack update check skipped: {}", e);
+                return;
+            }
+        };
+
+        match find_stack_updates(&stacks).await {
+            Ok(updates) if !updates.is_empty() => {
+                let _ = app.emit("stack-updates-available", updates);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Stack update check failed: {}", e),
+        }
+    });
+}
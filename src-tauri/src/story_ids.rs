@@ -0,0 +1,181 @@
+//! Keeping story IDs unique and stable.
+//!
+//! Agents sometimes write stories without an ID, reuse one, or renumber
+//! stories when they regenerate the PRD, which detaches state.json entries
+//! and story branches from their stories. `fix_story_ids` gives every story
+//! without an ID, and every repeat of an ID after its first use, a new ULID.
+//! With `renumber`, every story gets a ULID, and its state and story branch
+//! move with it, so later PRD edits can't shift them again.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use ulid::Ulid;
+
+use crate::git_binary;
+use crate::models::{ProjectState, Story};
+use crate::orchestrator::is_building;
+use crate::projects::{load_prd, update_prd, update_project_state};
+use crate::worktree::story_worktree_names;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryIdChange {
+    /// Position of the story in the PRD.
+    pub index: usize,
+    /// Empty for a story that had no ID.
+    pub from: String,
+    pub to: String,
+    /// The story's state and branch follow it to the new ID. False for
+    /// stories without an ID and repeats of an ID, whose state is the first
+    /// story's.
+    pub moves_state: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryIdReport {
+    /// Positions of stories without an ID.
+    pub missing: Vec<usize>,
+    /// IDs used by more than one story.
+    pub duplicates: Vec<String>,
+    /// New IDs, planned or applied.
+    pub changes: Vec<StoryIdChange>,
+    /// Story branches renamed along with their stories.
+    pub renamed_branches: Vec<String>,
+    /// False for `check_story_ids`.
+    pub applied: bool,
+}
+
+/// Finds missing and duplicate IDs and picks new ones. The first story with
+/// an ID keeps it; with `renumber`, every story gets a new one.
+fn plan(stories: &[Story], renumber: bool) -> StoryIdReport {
+    let mut report = StoryIdReport::default();
+    let mut seen = HashSet::new();
+    for (index, story) in stories.iter().enumerate() {
+        let id = story.id.trim();
+        let repeated = !id.is_empty() && !seen.insert(id.to_string());
+        if id.is_empty() {
+            report.missing.push(index);
+        } else if repeated && !report.duplicates.iter().any(|d| d == id) {
+            report.duplicates.push(id.to_string());
+        }
+        if renumber || id.is_empty() || repeated {
+            report.changes.push(StoryIdChange {
+                index,
+                from: story.id.clone(),
+                to: Ulid::new().to_string(),
+                moves_state: !id.is_empty() && !repeated,
+            });
+        }
+    }
+    report
+}
+
+/// Old ID to new ID for the renamed stories whose state follows them.
+fn movable(report: &StoryIdReport) -> HashMap<String, String> {
+    report
+        .changes
+        .iter()
+        .filter(|c| c.moves_state)
+        .map(|c| (c.from.clone(), c.to.clone()))
+        .collect()
+}
+
+fn rekey<V>(map: &mut HashMap<String, V>, moves: &HashMap<String, String>) {
+    for (from, to) in moves {
+        if let Some(value) = map.remove(from) {
+            map.insert(to.clone(), value);
+        }
+    }
+}
+
+fn move_state(state: &mut ProjectState, moves: &HashMap<String, String>) {
+    if let Some(id) = state.current_story_id.as_ref().and_then(|id| moves.get(id)) {
+        state.current_story_id = Some(id.clone());
+    }
+    rekey(&mut state.story_statuses, moves);
+    rekey(&mut state.story_retries, moves);
+    if let Some(failures) = &mut state.story_failures {
+        rekey(failures, moves);
+    }
+    if let Some(verifications) = &mut state.story_verifications {
+        rekey(verifications, moves);
+    }
+}
+
+fn git(project_path: &str, args: &[&str]) -> Result<(), String> {
+    let output = git_binary::command()
+        .args(args)
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Renames the story branch, and moves the worktree, of a renamed story.
+/// Returns the new branch name if there was a branch to rename.
+fn move_branch(project_path: &str, from: &str, to: &str) -> Result<Option<String>, String> {
+    let (old_worktree, old_branch) = story_worktree_names(project_path, from);
+    let (new_worktree, new_branch) = story_worktree_names(project_path, to);
+    if git(project_path, &["rev-parse", "--verify", "-q", &format!("refs/heads/{}", old_branch)]).is_err() {
+        return Ok(None);
+    }
+    if old_worktree.exists() {
+        git(
+            project_path,
+            &["worktree", "move", &old_worktree.to_string_lossy(), &new_worktree.to_string_lossy()],
+        )
+        .map_err(|e| format!("Failed to move worktree of {}: {}", from, e))?;
+    }
+    git(project_path, &["branch", "-m", &old_branch, &new_branch])
+        .map_err(|e| format!("Failed to rename branch {}: {}", old_branch, e))?;
+    Ok(Some(new_branch))
+}
+
+/// Reports missing and duplicate story IDs, and the IDs `fix_story_ids`
+/// would assign, without changing anything.
+#[tauri::command(rename_all = "camelCase")]
+pub fn check_story_ids(project_path: String, renumber: Option<bool>) -> Result<StoryIdReport, String> {
+    let prd = load_prd(project_path)?.ok_or("prd.json not found")?;
+    Ok(plan(&prd.user_stories, renumber.unwrap_or(false)))
+}
+
+/// Assigns ULIDs to stories without a unique ID, or to every story with
+/// `renumber`, and moves state.json entries and story branches to the new IDs.
+#[tauri::command(rename_all = "camelCase")]
+pub fn fix_story_ids(project_path: String, renumber: Option<bool>) -> Result<StoryIdReport, String> {
+    if is_building(&project_path) {
+        return Err("Story IDs can't be changed while a build is running".to_string());
+    }
+    let renumber = renumber.unwrap_or(false);
+
+    let mut report = update_prd(&project_path, |prd| {
+        let report = plan(&prd.user_stories, renumber);
+        for change in &report.changes {
+            prd.user_stories[change.index].id = change.to.clone();
+        }
+        Ok(report)
+    })?;
+    if report.changes.is_empty() {
+        return Ok(report);
+    }
+
+    let moves = movable(&report);
+    if !moves.is_empty() {
+        update_project_state(&project_path, |state| move_state(state, &moves))?;
+    }
+    for (from, to) in &moves {
+        // The PRD and state already moved; a branch that can't follow is logged, not fatal
+        match move_branch(&project_path, from, to) {
+            Ok(Some(branch)) => report.renamed_branches.push(branch),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+    report.applied = true;
+    Ok(report)
+}
@@ -0,0 +1,164 @@
+//! Detection and resolution of cloud-sync conflict copies.
+//!
+//! When the app data directory lives in Dropbox, iCloud Drive, Google Drive,
+//! or Syncthing, edits from two machines produce sibling files such as
+//! `projects (conflicted copy).json`, `projects 2.json`, or
+//! `projects.sync-conflict-20250101-120000-ABCDEFG.json`, and the app keeps
+//! reading the original while the other machine's changes sit unnoticed.
+//!
+//! Copies are hashed when checked: ones identical to the document are removed
+//! right away, and the rest are reported with their hash so a resolution
+//! applies only to the content the user saw.
+
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::app_data::{self, ConflictResolution, DOCUMENTS};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictCopy {
+    pub name: String,
+    pub hash: String,
+    pub modified_at: Option<String>,
+    /// Whether the copy parses as JSON; only valid copies can be merged.
+    pub valid: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataConflict {
+    /// The document the copies belong to, e.g. `projects.json`.
+    pub file: String,
+    pub modified_at: Option<String>,
+    pub copies: Vec<ConflictCopy>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataConflictResolution {
+    /// Keep this machine's document and delete the copy.
+    KeepMine,
+    /// Replace the document with the copy.
+    KeepTheirs,
+    /// Add the copy's entries the document lacks; this machine wins on conflicts.
+    Merge,
+}
+
+fn hash(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+fn modified_at(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339())
+}
+
+/// Matches the names sync clients give conflict copies of `file`.
+fn copy_pattern(file: &str) -> Regex {
+    let stem = regex::escape(file.trim_end_matches(".json"));
+    Regex::new(&format!(
+        r"^{}(?: \([^)]*conflicted copy[^)]*\)| \d+| \(\d+\)|\.sync-conflict-\d{{8}}-\d{{6}}-[A-Z0-9]+)\.json$",
+        stem
+    ))
+    .expect("conflict copy pattern is valid")
+}
+
+/// Lists unresolved conflict copies, removing ones identical to their document.
+#[tauri::command]
+pub fn detect_data_conflicts(app: AppHandle) -> Result<Vec<DataConflict>, String> {
+    let data_dir = app_data::app_data_dir(&app)?;
+    let names: Vec<String> = fs::read_dir(&data_dir)
+        .map_err(|e| format!("Failed to read app data directory: {}", e))?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for (file, _) in DOCUMENTS {
+        let pattern = copy_pattern(file);
+        let doc_path = data_dir.join(file);
+        let doc_hash = fs::read(&doc_path).ok().map(|content| hash(&content));
+
+        let mut copies = Vec::new();
+        for name in names.iter().filter(|name| pattern.is_match(name)) {
+            let path = data_dir.join(name);
+            let Ok(content) = fs::read(&path) else { continue };
+            let copy_hash = hash(&content);
+            if doc_hash.as_deref() == Some(copy_hash.as_str()) {
+                tracing::info!("Removing sync copy {} identical to {}", name, file);
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            copies.push(ConflictCopy {
+                name: name.clone(),
+                hash: copy_hash,
+                modified_at: modified_at(&path),
+                valid: serde_json::from_slice::<Value>(&content).is_ok(),
+            });
+        }
+
+        if !copies.is_empty() {
+            tracing::warn!("{} has {} sync conflict copies", file, copies.len());
+            copies.sort_by(|a, b| a.name.cmp(&b.name));
+            conflicts.push(DataConflict {
+                file: file.to_string(),
+                modified_at: modified_at(&doc_path),
+                copies,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Resolves one conflict copy of `file`. `hash` is the copy's hash as
+/// reported by `detect_data_conflicts`.
+#[tauri::command]
+pub fn resolve_data_conflict(
+    app: AppHandle,
+    file: String,
+    copy: String,
+    hash: String,
+    resolution: DataConflictResolution,
+) -> Result<(), String> {
+    let (_, kind) = DOCUMENTS
+        .iter()
+        .find(|(name, _)| *name == file)
+        .ok_or_else(|| format!("Failed to resolve conflict: unknown file {}", file))?;
+    if !copy_pattern(&file).is_match(&copy) {
+        return Err(format!("Failed to resolve conflict: {} is not a copy of {}", copy, file));
+    }
+
+    let data_dir = app_data::app_data_dir(&app)?;
+    let copy_path = data_dir.join(&copy);
+    let content = fs::read(&copy_path).map_err(|e| format!("Failed to read {}: {}", copy, e))?;
+    if self::hash(&content) != hash {
+        return Err(format!(
+            "Conflict: {} changed since it was checked. Check for conflicts again.",
+            copy
+        ));
+    }
+
+    match resolution {
+        DataConflictResolution::KeepMine => {}
+        DataConflictResolution::KeepTheirs | DataConflictResolution::Merge => {
+            let theirs: Value = serde_json::from_slice(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", copy, e))?;
+            if matches!(resolution, DataConflictResolution::KeepTheirs) {
+                app_data::replace_document(&app, &data_dir, &file, theirs)?;
+            } else {
+                app_data::merge_into(&app, &data_dir, &file, *kind, theirs, ConflictResolution::KeepExisting)?;
+            }
+        }
+    }
+
+    fs::remove_file(&copy_path).map_err(|e| format!("Failed to remove {}: {}", copy, e))?;
+    tracing::info!("Resolved sync conflict {} for {} ({:?})", copy, file, resolution);
+    Ok(())
+}
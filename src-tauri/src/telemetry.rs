@@ -0,0 +1,241 @@
+//! Opt-in anonymous telemetry.
+//!
+//! When enabled in Preferences, coarse usage counters (builds run, agents
+//! used, features touched) are kept in `<app data>/telemetry.json` and
+//! uploaded at most once a day. Counters never include project names, paths,
+//! prompts, or any other content. `get_pending_telemetry` returns exactly what
+//! the next upload would send.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::preferences::load_preferences_internal;
+
+/// Collection endpoint, provided at build time. Without one nothing is sent.
+const TELEMETRY_ENDPOINT: Option<&str> = option_env!("IDEATE_TELEMETRY_ENDPOINT");
+const UPLOAD_INTERVAL_HOURS: i64 = 24;
+/// How often the background task checks whether an upload is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_COUNTER_NAME_LEN: usize = 64;
+
+lazy_static::lazy_static! {
+    /// Serializes reads and writes of the telemetry file.
+    static ref STATE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TelemetryState {
+    /// Random ID generated when telemetry is first enabled. Not tied to the
+    /// user or machine.
+    #[serde(default)]
+    install_id: String,
+    #[serde(default)]
+    period_start: Option<String>,
+    #[serde(default)]
+    counters: BTreeMap<String, u64>,
+    #[serde(default)]
+    last_upload_at: Option<String>,
+}
+
+/// The batch the next upload will send.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryBatch {
+    pub install_id: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub period_start: Option<String>,
+    pub counters: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTelemetry {
+    pub enabled: bool,
+    pub endpoint_configured: bool,
+    pub last_upload_at: Option<String>,
+    pub batch: TelemetryBatch,
+}
+
+fn get_state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_data_dir(app)?;
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+    Ok(dir.join("telemetry.json"))
+}
+
+fn read_state(app: &AppHandle) -> Result<TelemetryState, String> {
+    let path = get_state_path(app)?;
+    if !path.exists() {
+        return Ok(TelemetryState::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read telemetry: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse telemetry: {}", e))
+}
+
+fn write_state(app: &AppHandle, state: &TelemetryState) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(state).map_err(|e| format!("Failed to serialize telemetry: {}", e))?;
+    fs::write(get_state_path(app)?, json).map_err(|e| format!("Failed to write telemetry: {}", e))
+}
+
+fn is_enabled(app: &AppHandle) -> bool {
+    load_preferences_internal(app).unwrap_or_default().telemetry_enabled
+}
+
+/// Counter names are short kebab/dot identifiers, so free text can't leak in.
+fn sanitize_counter(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_COUNTER_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then_some(name)
+}
+
+/// Increments a usage counter. Does nothing unless telemetry is enabled.
+pub fn record(app: &AppHandle, counter: &str) {
+    if !is_enabled(app) {
+        return;
+    }
+    let Some(counter) = sanitize_counter(counter) else {
+        return;
+    };
+    let Ok(_guard) = STATE_LOCK.lock() else {
+        return;
+    };
+    let result = read_state(app).and_then(|mut state| {
+        if state.install_id.is_empty() {
+            state.install_id = uuid::Uuid::new_v4().to_string();
+        }
+        state
+            .period_start
+            .get_or_insert_with(|| chrono::Utc::now().to_rfc3339());
+        *state.counters.entry(counter).or_insert(0) += 1;
+        write_state(app, &state)
+    });
+    if let Err(e) = result {
+        tracing::warn!("Telemetry: {}", e);
+    }
+}
+
+fn batch_from(app: &AppHandle, state: &TelemetryState) -> TelemetryBatch {
+    TelemetryBatch {
+        install_id: state.install_id.clone(),
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        period_start: state.period_start.clone(),
+        counters: state.counters.clone(),
+    }
+}
+
+fn upload_due(state: &TelemetryState) -> bool {
+    if state.counters.is_empty() {
+        return false;
+    }
+    state
+        .last_upload_at
+        .as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .is_none_or(|last| {
+            chrono::Utc::now().signed_duration_since(last) >= chrono::Duration::hours(UPLOAD_INTERVAL_HOURS)
+        })
+}
+
+/// Uploads the pending batch if one is due, then clears it. Pending counters
+/// are discarded if telemetry has been turned off.
+async fn upload_if_due(app: &AppHandle) -> Result<(), String> {
+    let batch = {
+        let _guard = STATE_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let mut state = read_state(app)?;
+        if !is_enabled(app) {
+            if !state.counters.is_empty() {
+                state.counters.clear();
+                state.period_start = None;
+                write_state(app, &state)?;
+            }
+            return Ok(());
+        }
+        if !upload_due(&state) {
+            return Ok(());
+        }
+        batch_from(app, &state)
+    };
+    let Some(endpoint) = TELEMETRY_ENDPOINT else {
+        return Ok(());
+    };
+
+    reqwest::Client::new()
+        .post(endpoint)
+        .json(&batch)
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to upload telemetry: {}", e))?;
+
+    // Keep anything recorded while the upload was in flight
+    let _guard = STATE_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let mut state = read_state(app)?;
+    for (name, sent) in &batch.counters {
+        if let Some(count) = state.counters.get_mut(name) {
+            *count = count.saturating_sub(*sent);
+        }
+    }
+    state.counters.retain(|_, count| *count > 0);
+    state.period_start = (!state.counters.is_empty()).then(|| chrono::Utc::now().to_rfc3339());
+    state.last_upload_at = Some(chrono::Utc::now().to_rfc3339());
+    write_state(app, &state)
+}
+
+/// Periodically uploads pending telemetry in the background.
+pub fn spawn_uploader(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = upload_if_due(&app).await {
+                tracing::warn!("Telemetry: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Returns the counters waiting to be uploaded, exactly as they would be sent.
+#[tauri::command]
+pub fn get_pending_telemetry(app: AppHandle) -> Result<PendingTelemetry, String> {
+    let state = {
+        let _guard = STATE_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+        read_state(&app)?
+    };
+    Ok(PendingTelemetry {
+        enabled: is_enabled(&app),
+        endpoint_configured: TELEMETRY_ENDPOINT.is_some(),
+        last_upload_at: state.last_upload_at.clone(),
+        batch: batch_from(&app, &state),
+    })
+}
+
+/// Discards pending counters without uploading them.
+#[tauri::command]
+pub fn clear_pending_telemetry(app: AppHandle) -> Result<(), String> {
+    let _guard = STATE_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let mut state = read_state(&app)?;
+    state.counters.clear();
+    state.period_start = None;
+    write_state(&app, &state)
+}
+
+/// Records that a feature was used from the frontend, e.g. `feature.prd-editor`.
+#[tauri::command]
+pub fn record_feature_usage(app: AppHandle, feature: String) {
+    record(&app, &format!("feature.{}", feature));
+}
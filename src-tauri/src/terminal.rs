@@ -2,17 +2,21 @@
 //!
 //! This module provides functionality for spawning and managing PTY-based
 //! terminal sessions. Each terminal runs in its own thread and communicates
-//! with the frontend via Tauri events.
+//! with the frontend via Tauri events. Output is also kept as plain text in a
+//! bounded scrollback buffer so it can be searched without sending it all to
+//! the frontend.
 
 #[cfg(unix)]
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+#[cfg(unix)]
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 #[cfg(unix)]
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 #[cfg(unix)]
 use std::io::{Read, Write};
 #[cfg(unix)]
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 #[cfg(unix)]
 use std::thread;
 #[cfg(unix)]
@@ -23,11 +27,209 @@ use tauri::Emitter;
 #[cfg(unix)]
 use uuid::Uuid;
 
+#[cfg(unix)]
+use crate::agents;
+#[cfg(unix)]
+use crate::allowlist;
+#[cfg(unix)]
+use crate::cost_attribution::{self, TrackedRun};
+#[cfg(unix)]
+use crate::project_env;
+#[cfg(unix)]
+use crate::projects;
+#[cfg(unix)]
+use crate::terminal_audit::{self, InputLine, TerminalAuditEntry};
+
+/// Lines of output kept per terminal; matches the frontend's xterm scrollback.
+#[cfg(unix)]
+const SCROLLBACK_LINES: usize = 5000;
+
+/// Output without a newline is split into lines of at most this many bytes.
+#[cfg(unix)]
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+/// Most matches returned by one search.
+#[cfg(unix)]
+const MAX_SEARCH_MATCHES: usize = 1000;
+
 #[cfg(unix)]
 struct PtyTerminal {
     master: Box<dyn portable_pty::MasterPty + Send>,
     writer: Box<dyn Write + Send>,
     child: Box<dyn portable_pty::Child + Send>,
+    scrollback: Arc<Mutex<Scrollback>>,
+    on_exit: Option<ExitHook>,
+    audit: AuditContext,
+}
+
+/// What audit entries record about a terminal, and the line being typed.
+#[cfg(unix)]
+struct AuditContext {
+    project_id: Option<String>,
+    agent_id: Option<String>,
+    working_directory: String,
+    input: InputLine,
+}
+
+#[cfg(unix)]
+impl AuditContext {
+    fn new(project_id: Option<String>, agent_id: Option<String>, working_directory: &str) -> Self {
+        Self {
+            project_id,
+            agent_id,
+            working_directory: working_directory.to_string(),
+            input: InputLine::default(),
+        }
+    }
+}
+
+/// Runs once a terminal's process has exited, with its exit code if known.
+#[cfg(unix)]
+type ExitHook = Box<dyn FnOnce(Option<u32>) + Send>;
+
+/// Where the scrollback parser is within an escape sequence.
+#[cfg(unix)]
+#[derive(Default)]
+enum EscapeState {
+    #[default]
+    Text,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// A terminal's recent output as plain text, with escape sequences removed.
+/// State carries across chunks, so sequences split between reads are handled.
+#[cfg(unix)]
+#[derive(Default)]
+struct Scrollback {
+    lines: VecDeque<String>,
+    /// Lines dropped from the front, so line numbers stay stable as output scrolls.
+    dropped: u64,
+    current: String,
+    /// A carriage return was seen; the next text overwrites the line.
+    pending_cr: bool,
+    escape: EscapeState,
+    /// The OSC sequence being read.
+    osc: String,
+    /// The directory the shell last reported with OSC 7.
+    reported_cwd: Option<String>,
+}
+
+#[cfg(unix)]
+impl Scrollback {
+    fn push(&mut self, data: &str) {
+        for c in data.chars() {
+            match self.escape {
+                EscapeState::Text => {}
+                EscapeState::Escape => {
+                    self.escape = match c {
+                        '[' => EscapeState::Csi,
+                        ']' => {
+                            self.osc.clear();
+                            EscapeState::Osc
+                        }
+                        _ => EscapeState::Text,
+                    };
+                    continue;
+                }
+                EscapeState::Csi => {
+                    if ('@'..='~').contains(&c) {
+                        self.escape = EscapeState::Text;
+                    }
+                    continue;
+                }
+                EscapeState::Osc => {
+                    match c {
+                        '\x07' => {
+                            self.finish_osc();
+                            self.escape = EscapeState::Text;
+                        }
+                        '\x1b' => {
+                            self.finish_osc();
+                            self.escape = EscapeState::OscEscape;
+                        }
+                        _ if self.osc.len() < MAX_LINE_BYTES => self.osc.push(c),
+                        _ => {}
+                    }
+                    continue;
+                }
+                EscapeState::OscEscape => {
+                    self.escape = EscapeState::Text;
+                    continue;
+                }
+            }
+
+            match c {
+                '\x1b' => self.escape = EscapeState::Escape,
+                '\n' => self.finish_line(),
+                '\r' => self.pending_cr = true,
+                '\x08' => {
+                    self.current.pop();
+                }
+                c if c.is_control() && c != '\t' => {}
+                c => {
+                    if self.pending_cr {
+                        self.current.clear();
+                        self.pending_cr = false;
+                    }
+                    self.current.push(c);
+                    if self.current.len() >= MAX_LINE_BYTES {
+                        self.finish_line();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Records the directory from an OSC 7 sequence, `7;file://host/path`.
+    fn finish_osc(&mut self) {
+        let osc = std::mem::take(&mut self.osc);
+        let Some(url) = osc.strip_prefix("7;").and_then(|rest| rest.strip_prefix("file://")) else {
+            return;
+        };
+        if let Some(path) = url.find('/').map(|i| percent_decode(&url[i..])) {
+            self.reported_cwd = Some(path);
+        }
+    }
+
+    fn finish_line(&mut self) {
+        self.pending_cr = false;
+        self.lines.push_back(std::mem::take(&mut self.current));
+        if self.lines.len() > SCROLLBACK_LINES {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    fn search(&self, pattern: &Regex) -> TerminalSearchResult {
+        let partial = (!self.current.is_empty()).then_some(&self.current);
+        let mut matches = Vec::new();
+        let mut truncated = false;
+
+        'lines: for (index, text) in self.lines.iter().chain(partial).enumerate() {
+            for found in pattern.find_iter(text).filter(|m| !m.is_empty()) {
+                if matches.len() == MAX_SEARCH_MATCHES {
+                    truncated = true;
+                    break 'lines;
+                }
+                matches.push(TerminalSearchMatch {
+                    line: self.dropped + index as u64,
+                    start: text[..found.start()].chars().count(),
+                    length: found.as_str().chars().count(),
+                    text: text.clone(),
+                });
+            }
+        }
+
+        TerminalSearchResult {
+            matches,
+            first_line: self.dropped,
+            total_lines: self.dropped + self.lines.len() as u64 + partial.is_some() as u64,
+            truncated,
+        }
+    }
 }
 
 #[cfg(unix)]
@@ -41,6 +243,14 @@ pub struct SpawnTerminalResult {
     pub terminal_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpawnAgentSessionResult {
+    pub terminal_id: String,
+    /// ID of the session in the process list; the same as `terminal_id`.
+    pub process_id: String,
+}
+
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TerminalOutputEvent {
@@ -55,9 +265,92 @@ pub struct TerminalExitEvent {
     pub exit_code: Option<u32>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSearchMatch {
+    /// Line number counted from the start of the session.
+    pub line: u64,
+    /// Offset of the match within the line, in characters.
+    pub start: usize,
+    /// Length of the match, in characters.
+    pub length: usize,
+    /// The whole line as plain text.
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSearchResult {
+    pub matches: Vec<TerminalSearchMatch>,
+    /// Number of the oldest line still in the buffer.
+    pub first_line: u64,
+    pub total_lines: u64,
+    /// More matches exist than were returned.
+    pub truncated: bool,
+}
+
+/// Where a terminal's shell is.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalCwd {
+    pub cwd: String,
+    /// "osc7" if the shell reported it, "process" if read from the
+    /// foreground process, or "initial" if it's where the terminal started.
+    pub source: String,
+}
+
+/// Decodes `%XX` escapes in an OSC 7 path.
+#[cfg(unix)]
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The working directory of process `pid`, read from the OS.
+#[cfg(unix)]
+fn process_cwd(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_link(format!("/proc/{}/cwd", pid))
+            .ok()
+            .map(|path| path.to_string_lossy().to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        // lsof prints fields one per line; `n` is the name
+        let output = std::process::Command::new("lsof")
+            .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix('n'))
+            .map(str::to_string)
+    }
+}
+
 /// Builds the shell command with proper environment setup.
 #[cfg(unix)]
-fn build_shell_command(working_directory: &str) -> CommandBuilder {
+fn build_shell_command(app: &AppHandle, working_directory: &str) -> CommandBuilder {
     // Use zsh as fallback on macOS, bash on other Unix systems
     #[cfg(target_os = "macos")]
     let default_shell = "/bin/zsh";
@@ -81,20 +374,80 @@ fn build_shell_command(working_directory: &str) -> CommandBuilder {
         }
     }
 
+    // The project's variables; see `project_env`
+    for (key, value) in project_env::resolve(app, working_directory) {
+        cmd.env(key, value);
+    }
+
     cmd
 }
 
-/// Spawns a new PTY terminal session.
-///
-/// Returns a terminal ID that can be used for subsequent operations.
+/// A project's variables and Ideate's variables describing it, for a
+/// terminal opened on it. Empty if the project isn't registered.
 #[cfg(unix)]
-#[tauri::command(rename_all = "camelCase")]
-pub fn spawn_terminal(
+fn project_variables(app: &AppHandle, project_id: &str) -> HashMap<String, String> {
+    let Some(project) = projects::load_projects(app.clone())
+        .ok()
+        .and_then(|projects| projects.into_iter().find(|p| p.id == project_id))
+    else {
+        return HashMap::new();
+    };
+
+    let mut variables = project_env::resolve(app, &project.path);
+    let story_id = projects::load_project_state(project.path.clone())
+        .ok()
+        .flatten()
+        .and_then(|state| state.current_story_id);
+    if let Some(story_id) = story_id {
+        variables.insert("IDEATE_STORY_ID".to_string(), story_id);
+    }
+    variables.insert("IDEATE_PROJECT_ID".to_string(), project.id);
+    variables.insert("IDEATE_PROJECT_NAME".to_string(), project.name);
+    variables.insert("IDEATE_PROJECT_PATH".to_string(), project.path);
+    variables
+}
+
+/// Quotes an argument for a POSIX shell command line.
+#[cfg(unix)]
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:@".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Removes a terminal's bookkeeping, notifies the frontend, and runs its exit hook.
+#[cfg(unix)]
+fn finish_terminal(app: &AppHandle, terminal_id: &str, mut terminal: PtyTerminal) {
+    // Child may already be dead; just try to get an exit code
+    let exit_code = match terminal.child.try_wait() {
+        Ok(Some(status)) => Some(status.exit_code()),
+        _ => None,
+    };
+
+    let event = TerminalExitEvent {
+        terminal_id: terminal_id.to_string(),
+        exit_code,
+    };
+    let _ = app.emit("terminal-exit", event);
+
+    if let Some(on_exit) = terminal.on_exit.take() {
+        on_exit(exit_code);
+    }
+    // `terminal` drops here, closing master/writer/child handles
+}
+
+/// Runs `cmd` in a new PTY and starts streaming its output.
+#[cfg(unix)]
+fn spawn_pty(
     app: AppHandle,
-    working_directory: String,
+    cmd: CommandBuilder,
     cols: u16,
     rows: u16,
-) -> Result<SpawnTerminalResult, String> {
+    on_exit: Option<ExitHook>,
+    audit: AuditContext,
+) -> Result<String, String> {
     let terminal_id = Uuid::new_v4().to_string();
 
     let pty_system = native_pty_system();
@@ -108,8 +461,6 @@ pub fn spawn_terminal(
         })
         .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
-    let cmd = build_shell_command(&working_directory);
-
     let child = pair
         .slave
         .spawn_command(cmd)
@@ -133,6 +484,8 @@ pub fn spawn_terminal(
     let terminal_id_for_cleanup = terminal_id.clone();
     let app_for_output = app.clone();
     let app_for_cleanup = app.clone();
+    let scrollback = Arc::new(Mutex::new(Scrollback::default()));
+    let scrollback_for_output = scrollback.clone();
 
     // Spawn a thread to read PTY output and emit events
     thread::spawn(move || {
@@ -143,6 +496,9 @@ pub fn spawn_terminal(
                 Ok(n) => {
                     // Convert to string, replacing invalid UTF-8 with replacement character
                     let data = String::from_utf8_lossy(&buffer[..n]).to_string();
+                    if let Ok(mut scrollback) = scrollback_for_output.lock() {
+                        scrollback.push(&data);
+                    }
                     let event = TerminalOutputEvent {
                         terminal_id: terminal_id_for_output.clone(),
                         data,
@@ -157,7 +513,7 @@ pub fn spawn_terminal(
                         // No data available, avoid busy loop
                         thread::sleep(Duration::from_millis(5));
                     } else {
-                        eprintln!("PTY read error: {}", e);
+                        tracing::warn!("PTY read error: {}", e);
                         break;
                     }
                 }
@@ -165,26 +521,18 @@ pub fn spawn_terminal(
         }
 
         // After loop: clean up this terminal and notify frontend
-        if let Ok(mut terminals) = PTY_TERMINALS.lock() {
-            if let Some(mut terminal) = terminals.remove(&terminal_id_for_cleanup) {
-                // Child may already be dead; just try to get an exit code
-                let exit_code = match terminal.child.try_wait() {
-                    Ok(Some(status)) => Some(status.exit_code()),
-                    _ => None,
-                };
-
-                let event = TerminalExitEvent {
-                    terminal_id: terminal_id_for_cleanup.clone(),
-                    exit_code,
-                };
-                let _ = app_for_cleanup.emit("terminal-exit", event);
-                // `terminal` drops here, closing master/writer/child handles
+        let terminal = match PTY_TERMINALS.lock() {
+            Ok(mut terminals) => terminals.remove(&terminal_id_for_cleanup),
+            Err(_) => {
+                tracing::error!(
+                    "Failed to lock PTY_TERMINALS for cleanup of {}",
+                    terminal_id_for_cleanup
+                );
+                None
             }
-        } else {
-            eprintln!(
-                "Failed to lock PTY_TERMINALS for cleanup of {}",
-                terminal_id_for_cleanup
-            );
+        };
+        if let Some(terminal) = terminal {
+            finish_terminal(&app_for_cleanup, &terminal_id_for_cleanup, terminal);
         }
     });
 
@@ -192,6 +540,9 @@ pub fn spawn_terminal(
         master,
         writer,
         child,
+        scrollback,
+        on_exit,
+        audit,
     };
 
     let mut terminals = PTY_TERMINALS
@@ -199,13 +550,101 @@ pub fn spawn_terminal(
         .map_err(|_| "Lock error: PTY_TERMINALS mutex poisoned")?;
     terminals.insert(terminal_id.clone(), pty_terminal);
 
+    Ok(terminal_id)
+}
+
+/// Spawns a new PTY terminal session.
+///
+/// Returns a terminal ID that can be used for subsequent operations.
+/// `project_id` is recorded with the terminal's audit entries, and the shell
+/// gets that project's variables wherever it starts, plus
+/// `IDEATE_PROJECT_ID`, `IDEATE_PROJECT_NAME`, `IDEATE_PROJECT_PATH`, and
+/// `IDEATE_STORY_ID` while a build is working on a story.
+#[cfg(unix)]
+#[tauri::command(rename_all = "camelCase")]
+pub fn spawn_terminal(
+    app: AppHandle,
+    working_directory: String,
+    project_id: Option<String>,
+    cols: u16,
+    rows: u16,
+) -> Result<SpawnTerminalResult, String> {
+    let mut cmd = build_shell_command(&app, &working_directory);
+    if let Some(project_id) = &project_id {
+        for (key, value) in project_variables(&app, project_id) {
+            cmd.env(key, value);
+        }
+    }
+    let audit = AuditContext::new(project_id, None, &working_directory);
+    let terminal_id = spawn_pty(app, cmd, cols, rows, None, audit)?;
     Ok(SpawnTerminalResult { terminal_id })
 }
 
-/// Writes data to a terminal's PTY.
+/// Starts an agent's interactive mode in a PTY terminal.
+///
+/// The agent runs through the user's login shell so it sees the same PATH as
+/// the embedded terminal. When it exits, the agent threads it started are
+/// attributed to the project's costs.
+#[cfg(unix)]
+#[tauri::command(rename_all = "camelCase")]
+pub fn spawn_agent_interactive(
+    app: AppHandle,
+    agent_id: String,
+    project_id: String,
+    project_path: String,
+    cols: u16,
+    rows: u16,
+) -> Result<SpawnAgentSessionResult, String> {
+    let agent = agents::get_built_in_agents()
+        .into_iter()
+        .find(|agent| agent.id == agent_id)
+        .ok_or_else(|| format!("Unknown agent: {}", agent_id))?;
+    allowlist::check(&app, &agent.command, &agent.interactive_args, Some(&project_path))?;
+
+    let command_line = std::iter::once(&agent.command)
+        .chain(&agent.interactive_args)
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut cmd = build_shell_command(&app, &project_path);
+    cmd.arg("-c");
+    cmd.arg(format!("exec {}", command_line));
+
+    let audit = AuditContext::new(Some(project_id.clone()), Some(agent.id.clone()), &project_path);
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let app_for_exit = app.clone();
+    let on_exit: ExitHook = Box::new(move |exit_code| {
+        tracing::info!("{} session in {} exited with {:?}", agent.name, project_path, exit_code);
+        let run = TrackedRun {
+            project_id,
+            agent_id: agent.id,
+            label: format!("{} session", agent.name),
+            started_at,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+        };
+        cost_attribution::spawn_for_run(app_for_exit, project_path, run);
+    });
+
+    let terminal_id = spawn_pty(app, cmd, cols, rows, Some(on_exit), audit)?;
+    Ok(SpawnAgentSessionResult {
+        process_id: terminal_id.clone(),
+        terminal_id,
+    })
+}
+
+/// Whether `terminal_id` names a running terminal.
+#[cfg(unix)]
+pub fn is_terminal(terminal_id: &str) -> bool {
+    PTY_TERMINALS
+        .lock()
+        .is_ok_and(|terminals| terminals.contains_key(terminal_id))
+}
+
+/// Writes data to a terminal's PTY, recording completed lines when the
+/// terminal audit log is enabled.
 #[cfg(unix)]
 #[tauri::command(rename_all = "camelCase")]
-pub fn write_terminal(terminal_id: String, data: String) -> Result<(), String> {
+pub fn write_terminal(app: AppHandle, terminal_id: String, data: String) -> Result<(), String> {
     let mut terminals = PTY_TERMINALS
         .lock()
         .map_err(|_| "Lock error: PTY_TERMINALS mutex poisoned")?;
@@ -224,6 +663,31 @@ pub fn write_terminal(terminal_id: String, data: String) -> Result<(), String> {
         .flush()
         .map_err(|e| format!("Failed to flush PTY: {}", e))?;
 
+    if !terminal_audit::is_enabled() {
+        return Ok(());
+    }
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let audit = &mut terminal.audit;
+    let entries: Vec<_> = audit
+        .input
+        .push(&data)
+        .into_iter()
+        .map(|command| TerminalAuditEntry {
+            timestamp: timestamp.clone(),
+            terminal_id: terminal_id.clone(),
+            project_id: audit.project_id.clone(),
+            agent_id: audit.agent_id.clone(),
+            working_directory: audit.working_directory.clone(),
+            command,
+        })
+        .collect();
+    drop(terminals);
+
+    // The keystrokes already reached the shell; a failed audit write must not
+    // surface as a failed write
+    if let Err(e) = terminal_audit::record(&app, &entries) {
+        tracing::warn!("Terminal audit: {}", e);
+    }
     Ok(())
 }
 
@@ -260,32 +724,78 @@ pub fn kill_terminal(app: AppHandle, terminal_id: String) -> Result<(), String>
         .lock()
         .map_err(|_| "Lock error: PTY_TERMINALS mutex poisoned")?;
 
-    if let Some(mut terminal) = terminals.remove(&terminal_id) {
+    let terminal = terminals.remove(&terminal_id);
+    drop(terminals);
+
+    if let Some(mut terminal) = terminal {
         // Try to kill the child process
         let _ = terminal.child.kill();
-
-        // Wait briefly and get exit status
-        let exit_code = match terminal.child.try_wait() {
-            Ok(Some(status)) => Some(status.exit_code()),
-            _ => None,
-        };
-
-        let event = TerminalExitEvent {
-            terminal_id: terminal_id.clone(),
-            exit_code,
-        };
-        let _ = app.emit("terminal-exit", event);
+        finish_terminal(&app, &terminal_id, terminal);
     }
 
     Ok(())
 }
 
+/// Searches a terminal's scrollback for `query`, as a regular expression
+/// when `regex` is set and otherwise as case-insensitive text.
+#[cfg(unix)]
+#[tauri::command(rename_all = "camelCase")]
+pub fn search_terminal_buffer(terminal_id: String, query: String, regex: bool) -> Result<TerminalSearchResult, String> {
+    let pattern = if regex {
+        Regex::new(&query).map_err(|e| format!("Invalid search pattern: {}", e))?
+    } else {
+        Regex::new(&format!("(?i){}", regex::escape(&query))).map_err(|e| format!("Invalid search pattern: {}", e))?
+    };
+
+    let scrollback = PTY_TERMINALS
+        .lock()
+        .map_err(|_| "Lock error: PTY_TERMINALS mutex poisoned")?
+        .get(&terminal_id)
+        .map(|terminal| terminal.scrollback.clone())
+        .ok_or_else(|| format!("Terminal {} not found", terminal_id))?;
+    let scrollback = scrollback.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(scrollback.search(&pattern))
+}
+
+/// Returns a terminal's current directory. Shells that report it with OSC 7
+/// (zsh and fish on macOS, or any shell with a prompt hook) are trusted;
+/// otherwise it's read from the terminal's foreground process, falling back
+/// to the shell and then to where the terminal started.
+#[cfg(unix)]
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_terminal_cwd(terminal_id: String) -> Result<TerminalCwd, String> {
+    let (scrollback, pids, initial) = {
+        let terminals = PTY_TERMINALS
+            .lock()
+            .map_err(|_| "Lock error: PTY_TERMINALS mutex poisoned")?;
+        let terminal = terminals
+            .get(&terminal_id)
+            .ok_or_else(|| format!("Terminal {} not found", terminal_id))?;
+        let foreground = terminal.master.process_group_leader().and_then(|pid| u32::try_from(pid).ok());
+        let pids: Vec<u32> = foreground.into_iter().chain(terminal.child.process_id()).collect();
+        (terminal.scrollback.clone(), pids, terminal.audit.working_directory.clone())
+    };
+
+    let reported = scrollback.lock().map_err(|e| format!("Lock error: {}", e))?.reported_cwd.clone();
+    if let Some(cwd) = reported {
+        return Ok(TerminalCwd { cwd, source: "osc7".to_string() });
+    }
+    if let Some(cwd) = pids.into_iter().find_map(process_cwd) {
+        return Ok(TerminalCwd { cwd, source: "process".to_string() });
+    }
+    Ok(TerminalCwd {
+        cwd: initial,
+        source: "initial".to_string(),
+    })
+}
+
 // Non-Unix stubs - terminal not supported on Windows
 #[cfg(not(unix))]
 #[tauri::command]
 pub fn spawn_terminal(
     _app: AppHandle,
     _working_directory: String,
+    _project_id: Option<String>,
     _cols: u16,
     _rows: u16,
 ) -> Result<SpawnTerminalResult, String> {
@@ -294,7 +804,25 @@ pub fn spawn_terminal(
 
 #[cfg(not(unix))]
 #[tauri::command]
-pub fn write_terminal(_terminal_id: String, _data: String) -> Result<(), String> {
+pub fn spawn_agent_interactive(
+    _app: AppHandle,
+    _agent_id: String,
+    _project_id: String,
+    _project_path: String,
+    _cols: u16,
+    _rows: u16,
+) -> Result<SpawnAgentSessionResult, String> {
+    Err("Embedded terminal is only supported on Unix-like systems".into())
+}
+
+#[cfg(not(unix))]
+pub fn is_terminal(_terminal_id: &str) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+#[tauri::command]
+pub fn write_terminal(_app: AppHandle, _terminal_id: String, _data: String) -> Result<(), String> {
     Err("Embedded terminal is only supported on Unix-like systems".into())
 }
 
@@ -309,3 +837,15 @@ pub fn resize_terminal(_terminal_id: String, _cols: u16, _rows: u16) -> Result<(
 pub fn kill_terminal(_app: AppHandle, _terminal_id: String) -> Result<(), String> {
     Err("Embedded terminal is only supported on Unix-like systems".into())
 }
+
+#[cfg(not(unix))]
+#[tauri::command]
+pub fn search_terminal_buffer(_terminal_id: String, _query: String, _regex: bool) -> Result<TerminalSearchResult, String> {
+    Err("Embedded terminal is only supported on Unix-like systems".into())
+}
+
+#[cfg(not(unix))]
+#[tauri::command]
+pub fn get_terminal_cwd(_terminal_id: String) -> Result<TerminalCwd, String> {
+    Err("Embedded terminal is only supported on Unix-like systems".into())
+}
@@ -0,0 +1,213 @@
+//! Opt-in audit log of commands sent to embedded terminals.
+//!
+//! Keystrokes reach `write_terminal` one chunk at a time, so each terminal
+//! assembles them into lines; a line is recorded when Enter is sent. Editing
+//! keys are applied, but the log reflects what was typed rather than what the
+//! shell ran after completion or history expansion. Entries are appended to
+//! `terminal-audit.jsonl` in the app data directory, one JSON object per
+//! line, with secrets masked as in process logs.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::models::Preferences;
+use crate::redact;
+
+const AUDIT_FILE: &str = "terminal-audit.jsonl";
+
+/// Longest line kept; the rest of a longer line is dropped.
+const MAX_LINE_CHARS: usize = 4096;
+
+/// Entries returned by a query without a limit.
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalAuditEntry {
+    pub timestamp: String,
+    pub terminal_id: String,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// Set when the terminal runs an interactive agent session.
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    pub working_directory: String,
+    pub command: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalAuditQuery {
+    pub project_id: Option<String>,
+    pub terminal_id: Option<String>,
+    /// RFC 3339 timestamp; only entries at or after it are returned.
+    pub since: Option<String>,
+    /// Case-insensitive text the command must contain.
+    pub contains: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Where the input parser is within an escape sequence.
+#[derive(Debug, Default)]
+enum Escape {
+    #[default]
+    None,
+    Start,
+    Csi,
+    Ss3,
+}
+
+/// Assembles a terminal's input into lines.
+#[derive(Debug, Default)]
+pub struct InputLine {
+    line: String,
+    escape: Escape,
+}
+
+impl InputLine {
+    /// Feeds input and returns the non-empty lines it completed.
+    pub fn push(&mut self, data: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        for c in data.chars() {
+            match self.escape {
+                Escape::Start => {
+                    self.escape = match c {
+                        '[' => Escape::Csi,
+                        'O' => Escape::Ss3,
+                        _ => Escape::None,
+                    };
+                    continue;
+                }
+                Escape::Csi => {
+                    // Arrow keys, bracketed paste markers, and the like
+                    if ('\x40'..='\x7e').contains(&c) {
+                        self.escape = Escape::None;
+                    }
+                    continue;
+                }
+                Escape::Ss3 => {
+                    self.escape = Escape::None;
+                    continue;
+                }
+                Escape::None => {}
+            }
+            match c {
+                '\r' | '\n' => {
+                    let line = std::mem::take(&mut self.line);
+                    if !line.trim().is_empty() {
+                        lines.push(line);
+                    }
+                }
+                '\x1b' => self.escape = Escape::Start,
+                // Backspace and delete
+                '\x7f' | '\x08' => {
+                    self.line.pop();
+                }
+                // Ctrl-C and Ctrl-U discard the line
+                '\x03' | '\x15' => self.line.clear(),
+                '\t' => self.line.push(c),
+                c if c.is_control() => {}
+                c => {
+                    if self.line.chars().count() < MAX_LINE_CHARS {
+                        self.line.push(c);
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Applies the audit setting from preferences.
+pub fn configure(prefs: &Preferences) {
+    ENABLED.store(prefs.terminal_audit_enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn audit_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_data_dir(app)?;
+    Ok(dir.join(AUDIT_FILE))
+}
+
+/// Appends entries to the audit file.
+pub fn record(app: &AppHandle, entries: &[TerminalAuditEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let path = audit_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let mut content = String::new();
+    for entry in entries {
+        let entry = TerminalAuditEntry {
+            command: redact::for_log(&entry.command).into_owned(),
+            ..entry.clone()
+        };
+        let json = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+        content.push_str(&json);
+        content.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open terminal audit log: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write terminal audit log: {}", e))
+}
+
+fn matches(entry: &TerminalAuditEntry, query: &TerminalAuditQuery, since: Option<i64>, contains: Option<&str>) -> bool {
+    query.project_id.as_ref().is_none_or(|id| entry.project_id.as_ref() == Some(id))
+        && query.terminal_id.as_ref().is_none_or(|id| &entry.terminal_id == id)
+        && since.is_none_or(|since| {
+            chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+                .is_ok_and(|t| t.timestamp_millis() >= since)
+        })
+        && contains.is_none_or(|text| entry.command.to_lowercase().contains(text))
+}
+
+/// Returns audit entries matching `query`, newest first.
+#[tauri::command]
+pub fn query_terminal_audit(app: AppHandle, query: TerminalAuditQuery) -> Result<Vec<TerminalAuditEntry>, String> {
+    let path = audit_path(&app)?;
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read terminal audit log: {}", e)),
+    };
+
+    let since = match &query.since {
+        Some(since) => Some(
+            chrono::DateTime::parse_from_rfc3339(since)
+                .map_err(|e| format!("Invalid since timestamp: {}", e))?
+                .timestamp_millis(),
+        ),
+        None => None,
+    };
+    let contains = query.contains.as_ref().map(|text| text.to_lowercase());
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+
+    Ok(content
+        .lines()
+        .rev()
+        // Skip a line torn by a crash mid-write
+        .filter_map(|line| serde_json::from_str::<TerminalAuditEntry>(line).ok())
+        .filter(|entry| matches(entry, &query, since, contains.as_deref()))
+        .take(limit)
+        .collect())
+}
@@ -0,0 +1,208 @@
+//! Provider rate limits and overload.
+//!
+//! Agents report a throttled provider in their output (Claude's 429 and
+//! `overloaded_error` messages) and in their session files (Amp's
+//! `stopReason`, Claude's API error entries). Whichever sees it first records
+//! the agent as throttled until the provider's `retry-after`, or for
+//! `DEFAULT_COOLDOWN` without one, and emits `provider-throttled`. Builds wait
+//! that out before the next attempt instead of spending a retry on it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+#[cfg(feature = "gui")]
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+#[cfg(feature = "gui")]
+use tauri::{AppHandle, Emitter};
+
+/// How long a provider counts as throttled when it didn't say.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Longest cooldown honored, whatever the provider asks for.
+const MAX_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
+#[cfg(feature = "gui")]
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref THROTTLED: Mutex<HashMap<String, ProviderThrottle>> = Mutex::new(HashMap::new());
+    static ref RATE_LIMIT_RES: Vec<Regex> = [
+        r"(?i)\brate[ _-]?limit(?:ed|_error| exceeded| reached)\b",
+        r"(?i)\btoo many requests\b",
+        r"(?i)\b(?:status|error|code)[^0-9\n]{0,12}429\b",
+        r"(?i)\bAPI Error: 429\b",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).unwrap())
+    .collect();
+    static ref OVERLOADED_RES: Vec<Regex> = [
+        r"(?i)\boverloaded(?:_error)?\b",
+        r"(?i)\bAPI Error: 529\b",
+        r"(?i)\b(?:status|error|code)[^0-9\n]{0,12}529\b",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).unwrap())
+    .collect();
+    /// JSON output lines that report an error rather than the agent's work.
+    static ref ERROR_JSON_RE: Regex =
+        Regex::new(r#""is_error"\s*:\s*true|"type"\s*:\s*"error"|"isApiErrorMessage"\s*:\s*true"#).unwrap();
+    static ref ERROR_LINE_RE: Regex = Regex::new(r"(?i)^\s*(?:API )?error\b").unwrap();
+    static ref RETRY_AFTER_RE: Regex =
+        Regex::new(r"(?i)(?:retry[ _-]?after\W{0,3}|try again in\s+)(\d+)\s*(ms|milliseconds?|s|sec|seconds?|m|min|minutes?)?")
+            .unwrap();
+}
+
+/// What a line of output or a session file said about the provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrottleSignal {
+    /// "rate-limit" or "overloaded"
+    pub kind: &'static str,
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+/// Payload of `provider-throttled`, and an entry of `get_provider_throttles`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderThrottle {
+    pub agent_id: String,
+    /// "rate-limit" or "overloaded"
+    pub kind: String,
+    pub message: String,
+    /// "output" or "session"
+    pub source: String,
+    pub detected_at: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+#[cfg(feature = "gui")]
+pub fn install(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn retry_after(text: &str) -> Option<Duration> {
+    let caps = RETRY_AFTER_RE.captures(text)?;
+    let amount: u64 = caps[1].parse().ok()?;
+    Some(match caps.get(2).map(|m| m.as_str().to_lowercase()) {
+        Some(unit) if unit.starts_with("ms") || unit.starts_with("milli") => Duration::from_millis(amount),
+        Some(unit) if unit.starts_with('m') => Duration::from_secs(amount * 60),
+        _ => Duration::from_secs(amount),
+    })
+}
+
+/// Recognizes a rate-limit or overload message in `text`.
+pub fn detect(text: &str) -> Option<ThrottleSignal> {
+    let kind = if OVERLOADED_RES.iter().any(|re| re.is_match(text)) {
+        "overloaded"
+    } else if RATE_LIMIT_RES.iter().any(|re| re.is_match(text)) {
+        "rate-limit"
+    } else {
+        return None;
+    };
+    let message = text
+        .lines()
+        .find(|line| OVERLOADED_RES.iter().chain(RATE_LIMIT_RES.iter()).any(|re| re.is_match(line)))
+        .unwrap_or(text)
+        .trim()
+        .chars()
+        .take(300)
+        .collect();
+    Some(ThrottleSignal {
+        kind,
+        retry_after: retry_after(text),
+        message,
+    })
+}
+
+/// Like `detect`, for one line of agent output. Only error output counts, so
+/// an agent discussing rate limits (say, while implementing them) doesn't.
+#[cfg(feature = "gui")]
+pub fn detect_in_output(stream: &str, line: &str) -> Option<ThrottleSignal> {
+    let trimmed = line.trim();
+    let is_error = if trimmed.starts_with('{') && trimmed.ends_with('}') {
+        ERROR_JSON_RE.is_match(trimmed)
+    } else {
+        stream == "stderr" || ERROR_LINE_RE.is_match(trimmed)
+    };
+    if is_error {
+        detect(trimmed)
+    } else {
+        None
+    }
+}
+
+/// Recognizes a throttling stop reason from an Amp thread.
+pub fn detect_stop_reason(stop_reason: &str) -> Option<ThrottleSignal> {
+    let reason = stop_reason.to_lowercase();
+    let kind = if reason.contains("overload") {
+        "overloaded"
+    } else if reason.contains("rate_limit") || reason.contains("rate-limit") || reason.contains("429") {
+        "rate-limit"
+    } else {
+        return None;
+    };
+    Some(ThrottleSignal {
+        kind,
+        retry_after: None,
+        message: format!("stopReason: {}", stop_reason),
+    })
+}
+
+/// Marks `agent_id` throttled from `observed_at`. Signals whose cooldown has
+/// already passed, e.g. old ones found in session files, are ignored.
+pub fn record(agent_id: &str, signal: &ThrottleSignal, source: &str, observed_at: DateTime<Utc>) {
+    let cooldown = signal.retry_after.unwrap_or(DEFAULT_COOLDOWN).min(MAX_COOLDOWN);
+    let until = observed_at + chrono::Duration::from_std(cooldown).unwrap_or_else(|_| chrono::Duration::zero());
+    if until <= Utc::now() {
+        return;
+    }
+    let throttle = ProviderThrottle {
+        agent_id: agent_id.to_string(),
+        kind: signal.kind.to_string(),
+        message: signal.message.clone(),
+        source: source.to_string(),
+        detected_at: observed_at,
+        until,
+    };
+    let Ok(mut throttled) = THROTTLED.lock() else {
+        return;
+    };
+    // Already known for at least as long
+    if throttled.get(agent_id).is_some_and(|t| t.until >= throttle.until) {
+        return;
+    }
+    throttled.insert(agent_id.to_string(), throttle.clone());
+    drop(throttled);
+
+    tracing::warn!(
+        "{} is throttled ({}) until {}: {}",
+        agent_id,
+        throttle.kind,
+        throttle.until.to_rfc3339(),
+        throttle.message
+    );
+    #[cfg(feature = "gui")]
+    if let Some(app) = APP.get() {
+        let _ = app.emit("provider-throttled", throttle);
+    }
+}
+
+/// How long `agent_id` stays throttled, if it is.
+pub fn remaining(agent_id: &str) -> Option<Duration> {
+    let until = THROTTLED.lock().ok()?.get(agent_id)?.until;
+    (until - Utc::now()).to_std().ok().filter(|d| !d.is_zero())
+}
+
+/// Agents currently throttled.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn get_provider_throttles() -> Result<Vec<ProviderThrottle>, String> {
+    let now = Utc::now();
+    let mut throttled = THROTTLED.lock().map_err(|e| format!("Lock error: {}", e))?;
+    throttled.retain(|_, t| t.until > now);
+    Ok(throttled.values().cloned().collect())
+}
@@ -0,0 +1,170 @@
+//! System tray / menu bar status item.
+//!
+//! Shows running builds with their story progress and lets the user pause,
+//! resume, or stop them and reopen the main window. While a build runs,
+//! closing the main window hides it instead of quitting so the build keeps
+//! going in the background.
+
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder};
+use tauri::tray::{TrayIcon, TrayIconBuilder};
+use tauri::{AppHandle, Manager, Window, WindowEvent, Wry};
+
+use crate::orchestrator::{self, BuildStatusInfo};
+use crate::projects::{load_prd, load_projects};
+
+const TRAY_ID: &str = "ideate-tray";
+const MAIN_WINDOW: &str = "main";
+
+/// Creates the tray icon. Call once from `setup`.
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Ideate")
+        .menu(&build_menu(app, &[])?)
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    refresh(app);
+    Ok(())
+}
+
+/// Stories passed and total for a project's PRD.
+fn story_progress(project_path: &str) -> Option<(usize, usize)> {
+    let prd = load_prd(project_path.to_string()).ok().flatten()?;
+    let passed = prd.user_stories.iter().filter(|s| s.passes).count();
+    Some((passed, prd.user_stories.len()))
+}
+
+fn build_menu(app: &AppHandle, builds: &[BuildStatusInfo]) -> tauri::Result<Menu<Wry>> {
+    let projects = load_projects(app.clone()).unwrap_or_default();
+    let mut menu = MenuBuilder::new(app);
+
+    if builds.is_empty() {
+        menu = menu.item(&MenuItemBuilder::new("No builds running").enabled(false).build(app)?);
+    }
+
+    for build in builds {
+        let name = projects
+            .iter()
+            .find(|p| p.id == build.project_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| build.project_id.clone());
+        let mut summary = build.status.clone();
+        if let Some((passed, total)) = story_progress(&build.project_path) {
+            summary = format!("{} · {}/{} stories", summary, passed, total);
+        }
+        if !build.running_story_ids.is_empty() {
+            summary = format!("{} · {}", summary, build.running_story_ids.join(", "));
+        }
+
+        let (toggle_id, toggle_label) = if build.status == "paused" || build.status == "pausing" {
+            (format!("tray:resume:{}", build.project_id), "Resume Build")
+        } else {
+            (format!("tray:pause:{}", build.project_id), "Pause Build")
+        };
+
+        menu = menu
+            .item(&MenuItemBuilder::new(name).enabled(false).build(app)?)
+            .item(&MenuItemBuilder::new(format!("  {}", summary)).enabled(false).build(app)?)
+            .item(
+                &MenuItemBuilder::new(toggle_label)
+                    .id(toggle_id)
+                    .enabled(build.status != "stopping")
+                    .build(app)?,
+            )
+            .item(
+                &MenuItemBuilder::new("Stop Build")
+                    .id(format!("tray:stop:{}", build.project_id))
+                    .enabled(build.status != "stopping")
+                    .build(app)?,
+            )
+            .separator();
+    }
+
+    if builds.is_empty() {
+        menu = menu.separator();
+    }
+    menu.item(&MenuItemBuilder::new("Open Ideate").id("tray:open").build(app)?)
+        .item(&MenuItemBuilder::new("Quit Ideate").id("tray:quit").build(app)?)
+        .build()
+}
+
+fn tray(app: &AppHandle) -> Option<TrayIcon> {
+    app.tray_by_id(TRAY_ID)
+}
+
+/// Rebuilds the tray menu and title from the current builds.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = tray(app) else {
+        return;
+    };
+    let builds = orchestrator::list_active_builds().unwrap_or_default();
+
+    if let Ok(menu) = build_menu(app, &builds) {
+        let _ = tray.set_menu(Some(menu));
+    }
+
+    let tooltip = match builds.len() {
+        0 => "Ideate".to_string(),
+        1 => "Ideate — 1 build running".to_string(),
+        n => format!("Ideate — {} builds running", n),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+
+    // Menu bar text on macOS: progress of the single running build
+    let title = match builds.as_slice() {
+        [build] => story_progress(&build.project_path).map(|(passed, total)| format!("{}/{}", passed, total)),
+        [] => None,
+        builds => Some(format!("{} builds", builds.len())),
+    };
+    let _ = tray.set_title(title);
+}
+
+/// Shows and focuses the main window.
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    match id {
+        "tray:open" => show_main_window(app),
+        "tray:quit" => app.exit(0),
+        _ => {
+            if let Some(project_id) = id.strip_prefix("tray:pause:") {
+                let _ = orchestrator::pause_build(app.clone(), project_id.to_string());
+            } else if let Some(project_id) = id.strip_prefix("tray:resume:") {
+                let _ = orchestrator::resume_build(project_id.to_string());
+            } else if let Some(project_id) = id.strip_prefix("tray:stop:") {
+                let project_id = project_id.to_string();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let _ = orchestrator::stop_build(app, project_id).await;
+                });
+            }
+            refresh(app);
+        }
+    }
+}
+
+/// Keeps the app alive in the tray while builds run: closing the main window
+/// hides it instead.
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        let building = orchestrator::list_active_builds()
+            .map(|builds| !builds.is_empty())
+            .unwrap_or(false);
+        if building {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+    }
+}
@@ -4,9 +4,14 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri::{
+    AppHandle, LogicalSize, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder, WindowEvent,
+};
 
+use crate::app_data::app_data_dir;
 use crate::macos;
+use crate::migrations;
 
 /// Panel state for a single project.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +27,10 @@ pub struct ProjectPanelState {
     pub agent_panel_collapsed: bool,
     #[serde(default = "default_agent_panel_height")]
     pub agent_panel_height: f64,
+    #[serde(default)]
+    pub split_view_enabled: bool,
+    #[serde(default = "default_split_view_width")]
+    pub split_view_width: f64,
 }
 
 fn default_agent_panel_collapsed() -> bool {
@@ -32,6 +41,90 @@ fn default_agent_panel_height() -> f64 {
     200.0
 }
 
+fn default_split_view_width() -> f64 {
+    450.0
+}
+
+/// A named snapshot of panel dimensions and collapse states.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutPreset {
+    pub name: String,
+    pub panels: ProjectPanelState,
+    #[serde(default)]
+    pub is_builtin: bool,
+}
+
+/// Built-in presets, shown unless the user saves a preset with the same name.
+fn builtin_layout_presets() -> Vec<LayoutPreset> {
+    vec![
+        LayoutPreset {
+            name: "Focus".to_string(),
+            panels: ProjectPanelState {
+                log_panel_collapsed: true,
+                log_panel_height: 200.0,
+                preview_panel_collapsed: true,
+                preview_panel_width: 400.0,
+                terminal_panel_collapsed: true,
+                terminal_panel_height: 300.0,
+                agent_panel_collapsed: true,
+                agent_panel_height: 200.0,
+                split_view_enabled: false,
+                split_view_width: default_split_view_width(),
+            },
+            is_builtin: true,
+        },
+        LayoutPreset {
+            name: "Debugging".to_string(),
+            panels: ProjectPanelState {
+                log_panel_collapsed: false,
+                log_panel_height: 320.0,
+                preview_panel_collapsed: false,
+                preview_panel_width: 480.0,
+                terminal_panel_collapsed: false,
+                terminal_panel_height: 300.0,
+                agent_panel_collapsed: false,
+                agent_panel_height: 240.0,
+                split_view_enabled: false,
+                split_view_width: default_split_view_width(),
+            },
+            is_builtin: true,
+        },
+    ]
+}
+
+/// The display a window was on, in physical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl MonitorInfo {
+    fn from_monitor(monitor: &Monitor) -> Self {
+        MonitorInfo {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+
+    fn same_bounds(&self, monitor: &Monitor) -> bool {
+        self.x == monitor.position().x
+            && self.y == monitor.position().y
+            && self.width == monitor.size().width
+            && self.height == monitor.size().height
+    }
+}
+
 /// Window state (position and size).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +134,13 @@ pub struct WindowState {
     pub x: Option<f64>,
     pub y: Option<f64>,
     pub maximized: bool,
+    /// The display the window was on.
+    #[serde(default)]
+    pub monitor: Option<MonitorInfo>,
+    /// Scale factor `x` and `y` were converted to logical units with; the
+    /// window's current one is assumed when missing.
+    #[serde(default)]
+    pub scale_factor: Option<f64>,
 }
 
 impl Default for WindowState {
@@ -51,6 +151,8 @@ impl Default for WindowState {
             x: None,
             y: None,
             maximized: false,
+            monitor: None,
+            scale_factor: None,
         }
     }
 }
@@ -61,15 +163,16 @@ impl Default for WindowState {
 pub struct UiState {
     #[serde(default)]
     pub panel_states: HashMap<String, ProjectPanelState>,
+    /// Window states keyed by window label
     #[serde(default)]
-    pub window_state: Option<WindowState>,
+    pub window_states: HashMap<String, WindowState>,
+    /// User-defined layout presets keyed by name
+    #[serde(default)]
+    pub layout_presets: HashMap<String, LayoutPreset>,
 }
 
 fn get_ui_state_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir = app_data_dir(app)?;
 
     if !app_data_dir.exists() {
         fs::create_dir_all(&app_data_dir)
@@ -79,35 +182,33 @@ fn get_ui_state_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(app_data_dir.join("ui-state.json"))
 }
 
-/// Loads UI state from disk.
-#[tauri::command]
-pub fn load_ui_state(app: AppHandle) -> Result<UiState, String> {
-    let path = get_ui_state_path(&app)?;
+/// Reads UI state from disk, upgrading documents from older versions.
+fn read_ui_state(app: &AppHandle) -> Result<UiState, String> {
+    let path = get_ui_state_path(app)?;
+    Ok(migrations::UI_STATE.load(&path)?.unwrap_or_default())
+}
 
-    if !path.exists() {
-        return Ok(UiState::default());
-    }
+/// Writes UI state to disk.
+fn write_ui_state(app: &AppHandle, state: &UiState) -> Result<(), String> {
+    let path = get_ui_state_path(app)?;
 
-    let content =
-        fs::read_to_string(&path).map_err(|e| format!("Failed to read ui-state.json: {}", e))?;
+    let json = migrations::UI_STATE.to_json(state)?;
+
+    fs::write(&path, json).map_err(|e| format!("Failed to write ui-state.json: {}", e))?;
 
-    let state: UiState = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse ui-state.json: {}", e))?;
+    Ok(())
+}
 
-    Ok(state)
+/// Loads UI state from disk.
+#[tauri::command]
+pub fn load_ui_state(app: AppHandle) -> Result<UiState, String> {
+    read_ui_state(&app)
 }
 
 /// Saves UI state to disk.
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_ui_state(app: AppHandle, state: UiState) -> Result<(), String> {
-    let path = get_ui_state_path(&app)?;
-
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize UI state: {}", e))?;
-
-    fs::write(&path, json).map_err(|e| format!("Failed to write ui-state.json: {}", e))?;
-
-    Ok(())
+    write_ui_state(&app, &state)
 }
 
 /// Saves just the panel states (convenience method).
@@ -116,76 +217,314 @@ pub fn save_panel_states(
     app: AppHandle,
     panel_states: HashMap<String, ProjectPanelState>,
 ) -> Result<(), String> {
-    let path = get_ui_state_path(&app)?;
-
-    // Load existing state to preserve window state
-    let mut state = if path.exists() {
-        let content = fs::read_to_string(&path)
-            .map_err(|e| format!("Failed to read ui-state.json: {}", e))?;
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        UiState::default()
-    };
+    // Load existing state to preserve window states
+    let mut state = read_ui_state(&app).unwrap_or_default();
 
     state.panel_states = panel_states;
 
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize UI state: {}", e))?;
+    write_ui_state(&app, &state)
+}
 
-    fs::write(&path, json).map_err(|e| format!("Failed to write ui-state.json: {}", e))?;
+/// Saves the state of a single window, keyed by its label (convenience method).
+#[tauri::command(rename_all = "camelCase")]
+pub fn save_window_state(
+    app: AppHandle,
+    label: String,
+    window_state: WindowState,
+) -> Result<(), String> {
+    // Load existing state to preserve panel and other window states
+    let mut state = read_ui_state(&app).unwrap_or_default();
 
-    Ok(())
+    state.window_states.insert(label, window_state);
+
+    write_ui_state(&app, &state)
 }
 
-/// Saves just the window state (convenience method).
+/// Saves the given panel layout as a named preset, replacing any preset with the same name.
 #[tauri::command(rename_all = "camelCase")]
-pub fn save_window_state(app: AppHandle, window_state: WindowState) -> Result<(), String> {
-    let path = get_ui_state_path(&app)?;
-
-    // Load existing state to preserve panel states
-    let mut state = if path.exists() {
-        let content = fs::read_to_string(&path)
-            .map_err(|e| format!("Failed to read ui-state.json: {}", e))?;
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        UiState::default()
+pub fn save_layout_preset(
+    app: AppHandle,
+    name: String,
+    panels: ProjectPanelState,
+) -> Result<LayoutPreset, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+
+    let mut state = read_ui_state(&app).unwrap_or_default();
+
+    let preset = LayoutPreset {
+        name: name.clone(),
+        panels,
+        is_builtin: false,
     };
+    state.layout_presets.insert(name, preset.clone());
 
-    state.window_state = Some(window_state);
+    write_ui_state(&app, &state)?;
 
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize UI state: {}", e))?;
+    Ok(preset)
+}
 
-    fs::write(&path, json).map_err(|e| format!("Failed to write ui-state.json: {}", e))?;
+/// Lists built-in and user-defined layout presets, sorted by name.
+#[tauri::command]
+pub fn list_layout_presets(app: AppHandle) -> Result<Vec<LayoutPreset>, String> {
+    let state = read_ui_state(&app)?;
+
+    let mut presets: Vec<LayoutPreset> = builtin_layout_presets()
+        .into_iter()
+        .filter(|p| !state.layout_presets.contains_key(&p.name))
+        .collect();
+    presets.extend(state.layout_presets.into_values());
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(presets)
+}
+
+/// Applies a layout preset to a project's panels and returns the resulting panel state.
+#[tauri::command(rename_all = "camelCase")]
+pub fn apply_layout_preset(
+    app: AppHandle,
+    project_id: String,
+    name: String,
+) -> Result<ProjectPanelState, String> {
+    let mut state = read_ui_state(&app).unwrap_or_default();
+
+    let preset = state
+        .layout_presets
+        .get(&name)
+        .cloned()
+        .or_else(|| builtin_layout_presets().into_iter().find(|p| p.name == name))
+        .ok_or_else(|| format!("Layout preset '{}' not found", name))?;
+
+    state.panel_states.insert(project_id, preset.panels.clone());
+
+    write_ui_state(&app, &state)?;
+
+    Ok(preset.panels)
+}
+
+/// The connected display matching a saved one: by name and bounds, then by
+/// name alone (the layout changed), then by bounds (names can be missing).
+fn find_monitor<'a>(monitors: &'a [Monitor], saved: &MonitorInfo) -> Option<&'a Monitor> {
+    let named = |m: &&Monitor| saved.name.is_some() && m.name() == saved.name.as_ref();
+    monitors
+        .iter()
+        .find(|m| named(m) && saved.same_bounds(m))
+        .or_else(|| monitors.iter().find(named))
+        .or_else(|| monitors.iter().find(|m| saved.same_bounds(m)))
+}
 
+/// Keeps a window of `size` at `position` inside `monitor`'s work area,
+/// shrinking it if it doesn't fit. Everything is in physical pixels.
+fn clamp_to_monitor(
+    monitor: &Monitor,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let area = monitor.work_area();
+    let width = size.width.min(area.size.width);
+    let height = size.height.min(area.size.height);
+    let max_x = area.position.x + (area.size.width - width) as i32;
+    let max_y = area.position.y + (area.size.height - height) as i32;
+    (
+        PhysicalPosition::new(position.x.clamp(area.position.x, max_x), position.y.clamp(area.position.y, max_y)),
+        PhysicalSize::new(width, height),
+    )
+}
+
+/// Restores a window's saved size and position, if any. A window is put
+/// back on the display it was on, even if that display has moved in the
+/// layout; if it's gone, the window goes to the same place on the primary
+/// display. Either way it's kept within the display's visible area.
+fn restore_window_state(app: &AppHandle, window: &WebviewWindow) {
+    let Ok(state) = read_ui_state(app) else {
+        return;
+    };
+
+    let Some(saved) = state.window_states.get(window.label()) else {
+        return;
+    };
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let primary = window.primary_monitor().ok().flatten().or_else(|| monitors.first().cloned());
+    let has_size = saved.width > 0.0 && saved.height > 0.0;
+
+    if let (Some(x), Some(y)) = (saved.x, saved.y) {
+        let scale = saved
+            .scale_factor
+            .or_else(|| window.scale_factor().ok())
+            .unwrap_or(1.0);
+        let mut position = PhysicalPosition::new((x * scale).round() as i32, (y * scale).round() as i32);
+
+        // The display to restore onto, and where its origin was when saved
+        let target = match &saved.monitor {
+            Some(info) => find_monitor(&monitors, info)
+                .cloned()
+                .or_else(|| primary.clone())
+                .map(|monitor| (monitor, Some((info.x, info.y)))),
+            None => monitors
+                .iter()
+                .find(|m| {
+                    let (origin, size) = (m.position(), m.size());
+                    (origin.x..origin.x + size.width as i32).contains(&position.x)
+                        && (origin.y..origin.y + size.height as i32).contains(&position.y)
+                })
+                .or(primary.as_ref())
+                .cloned()
+                .map(|monitor| (monitor, None)),
+        };
+
+        match target {
+            Some((monitor, saved_origin)) => {
+                if let Some((origin_x, origin_y)) = saved_origin {
+                    position.x += monitor.position().x - origin_x;
+                    position.y += monitor.position().y - origin_y;
+                }
+                let size = if has_size {
+                    LogicalSize::new(saved.width, saved.height).to_physical::<u32>(monitor.scale_factor())
+                } else {
+                    window.outer_size().unwrap_or_default()
+                };
+                let (position, size) = clamp_to_monitor(&monitor, position, size);
+                let _ = window.set_position(position);
+                // After moving, so the size is set at the display's scale
+                if has_size {
+                    let _ = window.set_size(size);
+                }
+            }
+            None => {
+                let _ = window.set_position(position);
+                if has_size {
+                    let _ = window.set_size(LogicalSize::new(saved.width, saved.height));
+                }
+            }
+        }
+    } else if has_size {
+        let _ = window.set_size(LogicalSize::new(saved.width, saved.height));
+    }
+
+    if saved.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Captures a window's current size and position in logical units, and the
+/// display it's on.
+fn capture_window_state(window: &WebviewWindow) -> Option<WindowState> {
+    let scale = window.scale_factor().ok()?;
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| MonitorInfo::from_monitor(&monitor));
+
+    Some(WindowState {
+        width: size.width,
+        height: size.height,
+        x: Some(position.x),
+        y: Some(position.y),
+        maximized,
+        monitor,
+        scale_factor: Some(scale),
+    })
+}
+
+/// Restores the calling window's saved size and position.
+#[tauri::command]
+pub fn restore_current_window_state(app: AppHandle, window: WebviewWindow) -> Result<(), String> {
+    restore_window_state(&app, &window);
     Ok(())
 }
 
-/// Opens or focuses the Process Viewer window.
-pub fn open_process_viewer(app: AppHandle) -> Result<(), String> {
-    const WINDOW_LABEL: &str = "process-viewer";
-    
+/// Saves the calling window's size, position, and display.
+#[tauri::command]
+pub fn save_current_window_state(app: AppHandle, window: WebviewWindow) -> Result<(), String> {
+    let window_state =
+        capture_window_state(&window).ok_or_else(|| "Failed to read window state".to_string())?;
+    save_window_state(app, window.label().to_string(), window_state)
+}
+
+/// Restores a secondary window's saved state and persists it again when the window closes.
+fn track_window_state(app: &AppHandle, window: &WebviewWindow) {
+    restore_window_state(app, window);
+
+    let app = app.clone();
+    let tracked = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            if let Some(window_state) = capture_window_state(&tracked) {
+                let label = tracked.label().to_string();
+                if let Err(e) = save_window_state(app.clone(), label, window_state) {
+                    tracing::warn!("Failed to save window state: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// How `open_app_window` sets up a window.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppWindowOptions {
+    pub title: Option<String>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub min_width: Option<f64>,
+    pub min_height: Option<f64>,
+    /// Keep the app menu; secondary windows have none by default.
+    #[serde(default)]
+    pub show_menu: bool,
+}
+
+/// Prefix of `open_app_window` labels.
+const APP_WINDOW_PREFIX: &str = "app-";
+
+/// Labels Tauri accepts for windows.
+fn valid_window_label(label: &str) -> bool {
+    !label.is_empty()
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | ':'))
+}
+
+/// Opens a window on `route`, or focuses the window already open with
+/// `label`. Its size and position are saved when it closes and restored the
+/// next time a window with that label opens.
+fn open_window(app: &AppHandle, label: &str, route: &str, options: AppWindowOptions) -> Result<(), String> {
+    if !valid_window_label(label) {
+        return Err(format!("Invalid window label: {}", label));
+    }
+    if !route.starts_with('/') {
+        return Err(format!("Window route must start with '/': {}", route));
+    }
+
     // Check if window already exists
-    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+    if let Some(window) = app.get_webview_window(label) {
         // Focus existing window
         window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
         return Ok(());
     }
-    
-    // Create new window without a menu bar
-    let url = WebviewUrl::App("/process-viewer".into());
-    
-    use tauri::menu::MenuBuilder;
-    let empty_menu = MenuBuilder::new(&app).build().map_err(|e| format!("Failed to build menu: {}", e))?;
-    
-    WebviewWindowBuilder::new(&app, WINDOW_LABEL, url)
-        .title("Process Viewer")
-        .inner_size(900.0, 600.0)
-        .min_inner_size(600.0, 400.0)
-        .resizable(true)
-        .menu(empty_menu)
+
+    let title = options.title.unwrap_or_else(|| "Ideate".to_string());
+    let mut builder = WebviewWindowBuilder::new(app, label, WebviewUrl::App(route.into()))
+        .title(&title)
+        .inner_size(options.width.unwrap_or(900.0), options.height.unwrap_or(600.0))
+        .min_inner_size(options.min_width.unwrap_or(500.0), options.min_height.unwrap_or(300.0))
+        .resizable(true);
+    if !options.show_menu {
+        use tauri::menu::MenuBuilder;
+        let empty_menu = MenuBuilder::new(app).build().map_err(|e| format!("Failed to build menu: {}", e))?;
+        builder = builder.menu(empty_menu);
+    }
+    let window = builder
         .build()
-        .map_err(|e| format!("Failed to create process viewer window: {}", e))?;
+        .map_err(|e| format!("Failed to create {} window: {}", title, e))?;
+
+    track_window_state(app, &window);
     
     // Disable native fullscreen for new window to prevent macOS crash
     macos::disable_native_fullscreen_for_new_window();
@@ -193,6 +532,39 @@ pub fn open_process_viewer(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Opens a secondary window — a log viewer, a usage dashboard — on a
+/// frontend route, or focuses the one already open with `label`. Windows
+/// are labeled `app-<label>`, which the default capability covers; the full
+/// label is returned.
+#[tauri::command(rename_all = "camelCase")]
+pub fn open_app_window(
+    app: AppHandle,
+    label: String,
+    route: String,
+    options: Option<AppWindowOptions>,
+) -> Result<String, String> {
+    let label = format!("{}{}", APP_WINDOW_PREFIX, label);
+    open_window(&app, &label, &route, options.unwrap_or_default())?;
+    Ok(label)
+}
+
+/// Opens or focuses the Process Viewer window.
+pub fn open_process_viewer(app: AppHandle) -> Result<(), String> {
+    open_window(
+        &app,
+        "process-viewer",
+        "/process-viewer",
+        AppWindowOptions {
+            title: Some("Process Viewer".to_string()),
+            width: Some(900.0),
+            height: Some(600.0),
+            min_width: Some(600.0),
+            min_height: Some(400.0),
+            show_menu: false,
+        },
+    )
+}
+
 /// Tauri command to open the process viewer from frontend.
 #[tauri::command]
 pub fn open_process_viewer_command(app: AppHandle) -> Result<(), String> {
@@ -204,33 +576,20 @@ pub fn open_process_viewer_command(app: AppHandle) -> Result<(), String> {
 pub fn open_story_manager_command(app: AppHandle, project_id: String, project_name: String) -> Result<(), String> {
     // Use project-specific window label
     let window_label = format!("story-manager-{}", project_id.replace("-", "").chars().take(12).collect::<String>());
-    
-    // Check if window already exists
-    if let Some(window) = app.get_webview_window(&window_label) {
-        // Focus existing window
-        window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
-        return Ok(());
-    }
-    
-    // Create new window with projectId as query parameter
-    let url = WebviewUrl::App(format!("/story-manager?projectId={}", project_id).into());
-    
-    use tauri::menu::MenuBuilder;
-    let empty_menu = MenuBuilder::new(&app).build().map_err(|e| format!("Failed to build menu: {}", e))?;
-    
-    WebviewWindowBuilder::new(&app, &window_label, url)
-        .title(format!("Story Manager - {}", project_name))
-        .inner_size(800.0, 500.0)
-        .min_inner_size(500.0, 300.0)
-        .resizable(true)
-        .menu(empty_menu)
-        .build()
-        .map_err(|e| format!("Failed to create story manager window: {}", e))?;
-    
-    // Disable native fullscreen for new window to prevent macOS crash
-    macos::disable_native_fullscreen_for_new_window();
-    
-    Ok(())
+
+    open_window(
+        &app,
+        &window_label,
+        &format!("/story-manager?projectId={}", project_id),
+        AppWindowOptions {
+            title: Some(format!("Story Manager - {}", project_name)),
+            width: Some(800.0),
+            height: Some(500.0),
+            min_width: Some(500.0),
+            min_height: Some(300.0),
+            show_menu: false,
+        },
+    )
 }
 
 /// Opens a new project window for a specific project.
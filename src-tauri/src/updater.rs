@@ -0,0 +1,150 @@
+//! In-app updates via tauri-plugin-updater.
+//!
+//! Users pick a release channel in Preferences. Checking, downloading, and
+//! installing are separate commands so the UI can show what is available
+//! before committing to it; download progress is streamed as
+//! `update-progress` events.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::preferences::load_preferences_internal;
+
+/// Public key release manifests are signed with, provided at build time.
+const UPDATER_PUBKEY: Option<&str> = option_env!("IDEATE_UPDATER_PUBKEY");
+
+const STABLE_ENDPOINT: &str = "https://github.com/kevinelliott/ideate/releases/latest/download/latest.json";
+/// Beta builds publish their manifest to the rolling `beta` release.
+const BETA_ENDPOINT: &str = "https://github.com/kevinelliott/ideate/releases/download/beta/latest.json";
+
+lazy_static::lazy_static! {
+    /// The update found by the last check.
+    static ref AVAILABLE: Mutex<Option<Update>> = Mutex::new(None);
+    /// Downloaded package bytes waiting to be installed.
+    static ref DOWNLOADED: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: String,
+    pub date: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProgressEvent {
+    /// "downloading", "downloaded", "installing"
+    pub phase: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+fn channel_endpoint(channel: &str) -> &'static str {
+    match channel {
+        "beta" => BETA_ENDPOINT,
+        _ => STABLE_ENDPOINT,
+    }
+}
+
+fn emit_progress(app: &AppHandle, phase: &str, downloaded: u64, total: Option<u64>) {
+    let _ = app.emit(
+        "update-progress",
+        UpdateProgressEvent {
+            phase: phase.to_string(),
+            downloaded,
+            total,
+        },
+    );
+}
+
+/// Checks for a newer version on `channel`, defaulting to the one selected in
+/// Preferences.
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle, channel: Option<String>) -> Result<Option<UpdateInfo>, String> {
+    let pubkey = UPDATER_PUBKEY.ok_or_else(|| "Updates are not available for this build".to_string())?;
+    let channel = channel.unwrap_or_else(|| load_preferences_internal(&app).unwrap_or_default().update_channel);
+    let endpoint = Url::parse(channel_endpoint(&channel)).map_err(|e| format!("Invalid update endpoint: {}", e))?;
+
+    let update = app
+        .updater_builder()
+        .pubkey(pubkey)
+        .endpoints(vec![endpoint])
+        .map_err(|e| format!("Failed to configure updater: {}", e))?
+        .build()
+        .map_err(|e| format!("Failed to configure updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel: channel.clone(),
+        date: update.date.map(|d| d.to_string()),
+        notes: update.body.clone(),
+    });
+
+    *AVAILABLE.lock().map_err(|e| format!("Lock error: {}", e))? = update;
+    *DOWNLOADED.lock().map_err(|e| format!("Lock error: {}", e))? = None;
+    Ok(info)
+}
+
+/// Downloads the update found by `check_for_update`.
+#[tauri::command]
+pub async fn download_update(app: AppHandle) -> Result<(), String> {
+    let update = AVAILABLE
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .clone()
+        .ok_or_else(|| "No update available - check for updates first".to_string())?;
+
+    let mut downloaded: u64 = 0;
+    let progress_app = app.clone();
+    let bytes = update
+        .download(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                emit_progress(&progress_app, "downloading", downloaded, total);
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+
+    emit_progress(&app, "downloaded", bytes.len() as u64, Some(bytes.len() as u64));
+    *DOWNLOADED.lock().map_err(|e| format!("Lock error: {}", e))? = Some(bytes);
+    Ok(())
+}
+
+/// Installs the downloaded update and restarts the app.
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    // Restarting would interrupt running agents
+    if !crate::orchestrator::list_active_builds()?.is_empty() {
+        return Err("Stop running builds before installing the update".to_string());
+    }
+
+    let update = AVAILABLE
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .clone()
+        .ok_or_else(|| "No update available - check for updates first".to_string())?;
+    let bytes = DOWNLOADED
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .take()
+        .ok_or_else(|| "Update has not been downloaded".to_string())?;
+
+    emit_progress(&app, "installing", bytes.len() as u64, Some(bytes.len() as u64));
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+    app.restart()
+}
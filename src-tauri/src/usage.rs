@@ -1,9 +1,22 @@
 //! Usage tracking for Amp and Claude Code agents.
 
-use serde::Deserialize;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::fs;
-
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "gui")]
+use tauri::{AppHandle, Emitter};
+
+#[cfg(feature = "gui")]
+use crate::app_data::app_data_dir;
+#[cfg(feature = "gui")]
 use crate::models::RecentThreadDuration;
+use crate::perf;
+use crate::throttle;
+#[cfg(feature = "gui")]
+use crate::usage_index;
 
 // ============================================================================
 // Amp Usage Data Structures
@@ -129,6 +142,8 @@ struct ClaudeSessionLine {
     entry_type: Option<String>,
     #[serde(default)]
     message: Option<ClaudeMessage>,
+    #[serde(rename = "isApiErrorMessage", default)]
+    is_api_error_message: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -172,6 +187,7 @@ pub struct ClaudeUsageSummary {
     pub detected_tier: Option<String>,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Deserialize)]
 struct ClaudeSessionEntry {
     #[serde(rename = "sessionId", default)]
@@ -180,144 +196,230 @@ struct ClaudeSessionEntry {
     timestamp: Option<String>,
 }
 
+/// One file's usage, with the times used to filter and compare sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ParsedUsage<T> {
+    pub entry: T,
+    /// When the thread or session started, for `since_timestamp` filtering.
+    pub started_ms: Option<i64>,
+    /// Last activity in the file.
+    pub last_ms: Option<i64>,
+}
+
+/// Where Amp keeps its threads, if it is installed.
+pub(crate) fn amp_threads_dir() -> Option<PathBuf> {
+    let dir = dirs::home_dir()?.join(".local").join("share").join("amp").join("threads");
+    dir.exists().then_some(dir)
+}
+
+/// Where Claude Code keeps its session logs, if it is installed.
+pub(crate) fn claude_projects_dir() -> Option<PathBuf> {
+    let dir = dirs::home_dir()?.join(".claude").join("projects");
+    dir.exists().then_some(dir)
+}
+
+/// Amp thread files: `<threads>/T-*.json`.
+pub(crate) fn amp_thread_paths(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    glob_paths(&dir.join("T-*.json"))
+}
+
+/// Claude Code session files: `<projects>/*/*.jsonl`.
+pub(crate) fn claude_session_paths(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    glob_paths(&dir.join("*").join("*.jsonl"))
+}
+
 // ============================================================================
-// Amp Usage Loading
+// Parallel Scanning
 // ============================================================================
 
-fn load_amp_usage_sync(since_timestamp: Option<i64>) -> Result<AmpUsageSummary, String> {
-    let home_dir =
-        dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
+/// Payload of `usage-scan-progress`, emitted while usage files are parsed.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageScanProgress {
+    pub source: String,
+    pub scanned: usize,
+    pub total: usize,
+}
 
-    let amp_threads_dir = home_dir
-        .join(".local")
-        .join("share")
-        .join("amp")
-        .join("threads");
+/// Files parsed between progress reports.
+const PROGRESS_INTERVAL: usize = 50;
 
-    if !amp_threads_dir.exists() {
-        return Ok(AmpUsageSummary {
-            entries: Vec::new(),
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            total_tokens: 0,
-            total_credits: 0.0,
-            total_duration_ms: 0,
-            thread_count: 0,
-        });
-    }
+/// Reports `(scanned, total)` as files are parsed.
+pub(crate) type ProgressFn<'a> = &'a (dyn Fn(usize, usize) + Sync);
 
-    let pattern = amp_threads_dir.join("T-*.json");
-    let pattern_str = pattern.to_string_lossy();
+fn glob_paths(pattern: &Path) -> Result<Vec<PathBuf>, String> {
+    Ok(glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| format!("Glob pattern error: {}", e))?
+        .filter_map(Result::ok)
+        .collect())
+}
 
-    let mut entries: Vec<AmpUsageEntry> = Vec::new();
-    let mut thread_count = 0;
+/// Parses `paths` on the rayon pool. Results keep the order of `paths`.
+pub(crate) fn scan_files<T: Send>(
+    paths: &[PathBuf],
+    on_progress: ProgressFn,
+    parse: impl Fn(&Path) -> Option<T> + Sync,
+) -> Vec<T> {
+    let total = paths.len();
+    let scanned = AtomicUsize::new(0);
+    on_progress(0, total);
+
+    paths
+        .par_iter()
+        .filter_map(|path| {
+            let result = parse(path);
+            let done = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if done.is_multiple_of(PROGRESS_INTERVAL) || done == total {
+                on_progress(done, total);
+            }
+            result
+        })
+        .collect()
+}
 
-    for path in glob::glob(&pattern_str).map_err(|e| format!("Glob pattern error: {}", e))? {
-        if let Ok(thread_path) = path {
-            // Get file modification time for duration calculation
-            let file_mtime_ms = fs::metadata(&thread_path)
-                .ok()
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_millis() as i64)
-                .unwrap_or(0);
-
-            if let Ok(content) = fs::read_to_string(&thread_path) {
-                if let Ok(thread) = serde_json::from_str::<AmpThread>(&content) {
-                    let created_at_ms = thread.created;
-
-                    // Filter by since_timestamp if provided
-                    if let Some(since) = since_timestamp {
-                        if let Some(created_ms) = created_at_ms {
-                            if created_ms < since {
-                                continue;
-                            }
-                        }
-                    }
+/// A file's modification time in Unix milliseconds.
+pub(crate) fn modified_ms(path: &Path) -> Option<i64> {
+    fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+}
 
-                    // Aggregate usage from all assistant messages
-                    let mut input_tokens: i64 = 0;
-                    let mut output_tokens: i64 = 0;
-                    let mut cache_creation_tokens: i64 = 0;
-                    let mut cache_read_tokens: i64 = 0;
-                    let mut credits: f64 = 0.0;
-                    let mut last_model: Option<String> = None;
-                    let mut last_stop_reason: Option<String> = None;
-
-                    for msg in &thread.messages {
-                        if msg.role.as_deref() == Some("assistant") {
-                            if let Some(usage) = &msg.usage {
-                                input_tokens += usage.input_tokens.unwrap_or(0);
-                                output_tokens += usage.output_tokens.unwrap_or(0);
-                                cache_creation_tokens +=
-                                    usage.cache_creation_input_tokens.unwrap_or(0);
-                                cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0);
-                                credits += usage.credits.unwrap_or(0.0);
-                                if usage.model.is_some() {
-                                    last_model = usage.model.clone();
-                                }
-                            }
-                            if let Some(state) = &msg.state {
-                                if state.stop_reason.is_some() {
-                                    last_stop_reason = state.stop_reason.clone();
-                                }
-                            }
-                        }
-                    }
+/// Emits `usage-scan-progress` events for `source`.
+#[cfg(feature = "gui")]
+pub(crate) fn emit_progress<'a>(app: &'a AppHandle, source: &'a str) -> impl Fn(usize, usize) + Sync + 'a {
+    move |scanned, total| {
+        let _ = app.emit(
+            "usage-scan-progress",
+            UsageScanProgress {
+                source: source.to_string(),
+                scanned,
+                total,
+            },
+        );
+    }
+}
 
-                    // Calculate duration from creation to last modification
-                    let duration_ms = match created_at_ms {
-                        Some(created) if file_mtime_ms > created => file_mtime_ms - created,
-                        _ => 0,
-                    };
-
-                    // Only add if there's actual usage
-                    if input_tokens > 0 || output_tokens > 0 || credits > 0.0 {
-                        thread_count += 1;
-
-                        let thread_id = thread_path
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        // Format timestamp from unix ms to ISO string
-                        let timestamp = created_at_ms
-                            .map(|ms| {
-                                chrono::DateTime::from_timestamp_millis(ms)
-                                    .map(|dt| dt.to_rfc3339())
-                                    .unwrap_or_else(|| "unknown".to_string())
-                            })
-                            .unwrap_or_else(|| "unknown".to_string());
-
-                        let entry = AmpUsageEntry {
-                            thread_id,
-                            thread_title: thread.title.clone(),
-                            timestamp,
-                            model: last_model,
-                            input_tokens,
-                            output_tokens,
-                            total_tokens: input_tokens + output_tokens,
-                            cache_creation_tokens,
-                            cache_read_tokens,
-                            credits,
-                            duration_ms,
-                            stop_reason: last_stop_reason,
-                        };
-                        entries.push(entry);
-                    }
+// ============================================================================
+// Amp Usage Loading
+// ============================================================================
+
+/// Sums the usage in one thread file, or `None` if it has none or predates `since_timestamp`.
+pub(crate) fn parse_amp_thread(
+    thread_path: &Path,
+    since_timestamp: Option<i64>,
+) -> Option<ParsedUsage<AmpUsageEntry>> {
+    // Get file modification time for duration calculation
+    let file_mtime_ms = modified_ms(thread_path).unwrap_or(0);
+
+    let content = fs::read_to_string(thread_path).ok()?;
+    let thread = serde_json::from_str::<AmpThread>(&content).ok()?;
+    let created_at_ms = thread.created;
+
+    // Filter by since_timestamp if provided
+    if let Some(since) = since_timestamp {
+        if let Some(created_ms) = created_at_ms {
+            if created_ms < since {
+                return None;
+            }
+        }
+    }
+
+    // Aggregate usage from all assistant messages
+    let mut input_tokens: i64 = 0;
+    let mut output_tokens: i64 = 0;
+    let mut cache_creation_tokens: i64 = 0;
+    let mut cache_read_tokens: i64 = 0;
+    let mut credits: f64 = 0.0;
+    let mut last_model: Option<String> = None;
+    let mut last_stop_reason: Option<String> = None;
+
+    for msg in &thread.messages {
+        if msg.role.as_deref() == Some("assistant") {
+            if let Some(usage) = &msg.usage {
+                input_tokens += usage.input_tokens.unwrap_or(0);
+                output_tokens += usage.output_tokens.unwrap_or(0);
+                cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
+                cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+                credits += usage.credits.unwrap_or(0.0);
+                if usage.model.is_some() {
+                    last_model = usage.model.clone();
                 }
             }
+            if let Some(state) = &msg.state {
+                if state.stop_reason.is_some() {
+                    last_stop_reason = state.stop_reason.clone();
+                }
+            }
+        }
+    }
+
+    if let Some(signal) = last_stop_reason.as_deref().and_then(throttle::detect_stop_reason) {
+        if let Some(at) = chrono::DateTime::from_timestamp_millis(file_mtime_ms) {
+            throttle::record("amp", &signal, "session", at);
         }
     }
 
+    // Only add if there's actual usage
+    if input_tokens == 0 && output_tokens == 0 && credits <= 0.0 {
+        return None;
+    }
+
+    // Calculate duration from creation to last modification
+    let duration_ms = match created_at_ms {
+        Some(created) if file_mtime_ms > created => file_mtime_ms - created,
+        _ => 0,
+    };
+
+    let thread_id = thread_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // Format timestamp from unix ms to ISO string
+    let timestamp = created_at_ms
+        .map(|ms| {
+            chrono::DateTime::from_timestamp_millis(ms)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_else(|| "unknown".to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let entry = AmpUsageEntry {
+        thread_id,
+        thread_title: thread.title,
+        timestamp,
+        model: last_model,
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        cache_creation_tokens,
+        cache_read_tokens,
+        credits,
+        duration_ms,
+        stop_reason: last_stop_reason,
+    };
+    Some(ParsedUsage {
+        entry,
+        started_ms: created_at_ms,
+        last_ms: Some(file_mtime_ms),
+    })
+}
+
+/// Totals Amp thread usage.
+pub(crate) fn summarize_amp(entries: Vec<AmpUsageEntry>) -> AmpUsageSummary {
+    let thread_count = entries.len() as i32;
     let total_input_tokens: i64 = entries.iter().map(|e| e.input_tokens).sum();
     let total_output_tokens: i64 = entries.iter().map(|e| e.output_tokens).sum();
     let total_tokens: i64 = entries.iter().map(|e| e.total_tokens).sum();
     let total_credits: f64 = entries.iter().map(|e| e.credits).sum();
     let total_duration_ms: i64 = entries.iter().map(|e| e.duration_ms).sum();
 
-    Ok(AmpUsageSummary {
+    AmpUsageSummary {
         entries,
         total_input_tokens,
         total_output_tokens,
@@ -325,167 +427,180 @@ fn load_amp_usage_sync(since_timestamp: Option<i64>) -> Result<AmpUsageSummary,
         total_credits,
         total_duration_ms,
         thread_count,
-    })
+    }
+}
+
+pub(crate) fn load_amp_usage_sync(
+    since_timestamp: Option<i64>,
+    on_progress: ProgressFn,
+) -> Result<AmpUsageSummary, String> {
+    let Some(dir) = amp_threads_dir() else {
+        return Ok(summarize_amp(Vec::new()));
+    };
+    let paths = amp_thread_paths(&dir)?;
+    let mut timer = perf::start("usage.scan").subject("amp");
+    timer.set_files(paths.len());
+    let threads = scan_files(&paths, on_progress, |path| parse_amp_thread(path, since_timestamp));
+    Ok(summarize_amp(threads.into_iter().map(|t| t.entry).collect()))
 }
 
-/// Loads Amp usage statistics from thread files.
+/// Loads Amp usage statistics from the usage index, emitting `usage-scan-progress`
+/// while changed thread files are parsed.
+#[cfg(feature = "gui")]
 #[tauri::command]
-pub async fn load_amp_usage(since_timestamp: Option<i64>) -> Result<AmpUsageSummary, String> {
-    tokio::task::spawn_blocking(move || load_amp_usage_sync(since_timestamp))
-        .await
-        .map_err(|e| format!("Task join error: {}", e))?
+pub async fn load_amp_usage(app: AppHandle, since_timestamp: Option<i64>) -> Result<AmpUsageSummary, String> {
+    let data_dir = app_data_dir(&app)?;
+    tokio::task::spawn_blocking(move || {
+        usage_index::amp_usage(&data_dir, since_timestamp, &emit_progress(&app, "amp"))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 // ============================================================================
 // Claude Usage Loading
 // ============================================================================
 
-fn load_claude_usage_sync(since_timestamp: Option<i64>) -> Result<ClaudeUsageSummary, String> {
-    let home_dir =
-        dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
-
-    let claude_projects_dir = home_dir.join(".claude").join("projects");
-
-    if !claude_projects_dir.exists() {
-        return Ok(ClaudeUsageSummary {
-            entries: Vec::new(),
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            total_tokens: 0,
-            total_duration_ms: 0,
-            session_count: 0,
-            detected_tier: None,
-        });
+/// Sums the usage in one session file, or `None` if it has none or started
+/// before `since_timestamp`.
+pub(crate) fn parse_claude_session(
+    session_path: &Path,
+    since_timestamp: Option<i64>,
+) -> Option<ParsedUsage<ClaudeUsageEntry>> {
+    // Extract project path from the parent directory name
+    let project_name = session_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // Session ID is the filename without .jsonl
+    let session_id = session_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // A file last written before the cutoff can't hold a session started after it
+    if let Some(since) = since_timestamp {
+        if modified_ms(session_path).is_some_and(|mtime| mtime < since) {
+            return None;
+        }
     }
 
-    // Find all JSONL session files: ~/.claude/projects/*/*.jsonl
-    let pattern = claude_projects_dir.join("*").join("*.jsonl");
-    let pattern_str = pattern.to_string_lossy();
-
-    let mut entries: Vec<ClaudeUsageEntry> = Vec::new();
-    let mut session_count = 0;
-    let mut latest_service_tier: Option<String> = None;
-    let mut latest_timestamp: Option<i64> = None;
-
-    for path in glob::glob(&pattern_str).map_err(|e| format!("Glob pattern error: {}", e))? {
-        if let Ok(session_path) = path {
-            // Extract project path from the parent directory name
-            let project_name = session_path
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            // Session ID is the filename without .jsonl
-            let session_id = session_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            if let Ok(file_content) = fs::read_to_string(&session_path) {
-                let mut total_input: i64 = 0;
-                let mut total_output: i64 = 0;
-                let mut total_cache_creation: i64 = 0;
-                let mut total_cache_read: i64 = 0;
-                let mut first_model: Option<String> = None;
-                let mut first_timestamp: Option<i64> = None;
-                let mut last_timestamp: Option<i64> = None;
-                let mut session_service_tier: Option<String> = None;
-                let mut has_usage = false;
-
-                for line in file_content.lines() {
-                    if let Ok(entry) = serde_json::from_str::<ClaudeSessionLine>(line) {
-                        // Parse timestamp
-                        if let Some(ts_str) = &entry.timestamp {
-                            if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts_str) {
-                                let ts_ms = dt.timestamp_millis();
-                                if first_timestamp.is_none() {
-                                    first_timestamp = Some(ts_ms);
-                                }
-                                last_timestamp = Some(ts_ms);
-                            }
+    let reader = BufReader::new(fs::File::open(session_path).ok()?);
+    let mut total_input: i64 = 0;
+    let mut total_output: i64 = 0;
+    let mut total_cache_creation: i64 = 0;
+    let mut total_cache_read: i64 = 0;
+    let mut first_model: Option<String> = None;
+    let mut first_timestamp: Option<i64> = None;
+    let mut last_timestamp: Option<i64> = None;
+    let mut session_service_tier: Option<String> = None;
+    let mut has_usage = false;
+
+    for line in reader.lines().map_while(Result::ok) {
+        if let Ok(entry) = serde_json::from_str::<ClaudeSessionLine>(&line) {
+            // Parse timestamp
+            if let Some(ts_str) = &entry.timestamp {
+                if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts_str) {
+                    let ts_ms = dt.timestamp_millis();
+                    if first_timestamp.is_none() {
+                        // Sessions are filtered by start time, so stop at once if it's too old
+                        if since_timestamp.is_some_and(|since| ts_ms < since) {
+                            return None;
                         }
+                        first_timestamp = Some(ts_ms);
+                    }
+                    last_timestamp = Some(ts_ms);
+                }
+            }
 
-                        // Extract usage from assistant messages
-                        if entry.entry_type.as_deref() == Some("assistant") {
-                            if let Some(message) = &entry.message {
-                                if first_model.is_none() {
-                                    first_model = message.model.clone();
-                                }
+            if entry.is_api_error_message {
+                let at = last_timestamp.and_then(chrono::DateTime::from_timestamp_millis);
+                if let (Some(signal), Some(at)) = (throttle::detect(&line), at) {
+                    throttle::record("claude-code", &signal, "session", at);
+                }
+            }
 
-                                if let Some(usage) = &message.usage {
-                                    has_usage = true;
-                                    total_input += usage.input_tokens.unwrap_or(0);
-                                    total_output += usage.output_tokens.unwrap_or(0);
-                                    total_cache_creation +=
-                                        usage.cache_creation_input_tokens.unwrap_or(0);
-                                    total_cache_read +=
-                                        usage.cache_read_input_tokens.unwrap_or(0);
-
-                                    // Track the most recent service tier
-                                    if usage.service_tier.is_some() {
-                                        session_service_tier = usage.service_tier.clone();
-                                    }
-                                }
-                            }
-                        }
+            // Extract usage from assistant messages
+            if entry.entry_type.as_deref() == Some("assistant") {
+                if let Some(message) = &entry.message {
+                    if first_model.is_none() {
+                        first_model = message.model.clone();
                     }
-                }
 
-                // Filter by since_timestamp using first_timestamp
-                if let Some(since) = since_timestamp {
-                    if let Some(first_ts) = first_timestamp {
-                        if first_ts < since {
-                            continue;
+                    if let Some(usage) = &message.usage {
+                        has_usage = true;
+                        total_input += usage.input_tokens.unwrap_or(0);
+                        total_output += usage.output_tokens.unwrap_or(0);
+                        total_cache_creation += usage.cache_creation_input_tokens.unwrap_or(0);
+                        total_cache_read += usage.cache_read_input_tokens.unwrap_or(0);
+
+                        // Track the most recent service tier
+                        if usage.service_tier.is_some() {
+                            session_service_tier = usage.service_tier.clone();
                         }
                     }
                 }
+            }
+        }
+    }
 
-                // Only add if there was actual usage
-                if has_usage && (total_input > 0 || total_output > 0) {
-                    session_count += 1;
-
-                    let duration_ms = match (first_timestamp, last_timestamp) {
-                        (Some(first), Some(last)) if last > first => last - first,
-                        _ => 0,
-                    };
-
-                    let timestamp = first_timestamp
-                        .and_then(|ts| chrono::DateTime::from_timestamp(ts / 1000, 0))
-                        .map(|dt| dt.to_rfc3339())
-                        .unwrap_or_else(|| "unknown".to_string());
-
-                    // Track the latest service tier across all sessions
-                    if let Some(ts) = last_timestamp {
-                        if latest_timestamp.is_none() || ts > latest_timestamp.unwrap_or(0) {
-                            if session_service_tier.is_some() {
-                                latest_timestamp = Some(ts);
-                                latest_service_tier = session_service_tier.clone();
-                            }
-                        }
-                    }
+    // Only add if there was actual usage
+    if !has_usage || (total_input == 0 && total_output == 0) {
+        return None;
+    }
 
-                    let entry = ClaudeUsageEntry {
-                        session_id,
-                        project_path: project_name,
-                        timestamp,
-                        model: first_model,
-                        input_tokens: total_input,
-                        output_tokens: total_output,
-                        total_tokens: total_input + total_output,
-                        cache_creation_tokens: total_cache_creation,
-                        cache_read_tokens: total_cache_read,
-                        duration_ms,
-                        service_tier: session_service_tier,
-                    };
-                    entries.push(entry);
-                }
+    let duration_ms = match (first_timestamp, last_timestamp) {
+        (Some(first), Some(last)) if last > first => last - first,
+        _ => 0,
+    };
+
+    let timestamp = first_timestamp
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts / 1000, 0))
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let entry = ClaudeUsageEntry {
+        session_id,
+        project_path: project_name,
+        timestamp,
+        model: first_model,
+        input_tokens: total_input,
+        output_tokens: total_output,
+        total_tokens: total_input + total_output,
+        cache_creation_tokens: total_cache_creation,
+        cache_read_tokens: total_cache_read,
+        duration_ms,
+        service_tier: session_service_tier,
+    };
+    Some(ParsedUsage {
+        entry,
+        started_ms: first_timestamp,
+        last_ms: last_timestamp,
+    })
+}
+
+/// Totals Claude Code session usage and detects the plan from the most
+/// recently active session that reports a service tier.
+pub(crate) fn summarize_claude(sessions: Vec<ParsedUsage<ClaudeUsageEntry>>) -> ClaudeUsageSummary {
+    // Track the latest service tier across all sessions
+    let mut latest_service_tier: Option<String> = None;
+    let mut latest_timestamp: Option<i64> = None;
+    for session in &sessions {
+        if let Some(ts) = session.last_ms {
+            if session.entry.service_tier.is_some() && latest_timestamp.is_none_or(|latest| ts > latest) {
+                latest_timestamp = Some(ts);
+                latest_service_tier = session.entry.service_tier.clone();
             }
         }
     }
 
+    let entries: Vec<ClaudeUsageEntry> = sessions.into_iter().map(|s| s.entry).collect();
+    let session_count = entries.len() as i32;
+
     let total_input_tokens: i64 = entries.iter().map(|e| e.input_tokens).sum();
     let total_output_tokens: i64 = entries.iter().map(|e| e.output_tokens).sum();
     let total_tokens: i64 = entries.iter().map(|e| e.total_tokens).sum();
@@ -499,7 +614,7 @@ fn load_claude_usage_sync(since_timestamp: Option<i64>) -> Result<ClaudeUsageSum
         other => other.to_string(),
     });
 
-    Ok(ClaudeUsageSummary {
+    ClaudeUsageSummary {
         entries,
         total_input_tokens,
         total_output_tokens,
@@ -507,21 +622,169 @@ fn load_claude_usage_sync(since_timestamp: Option<i64>) -> Result<ClaudeUsageSum
         total_duration_ms,
         session_count,
         detected_tier,
+    }
+}
+
+pub(crate) fn load_claude_usage_sync(
+    since_timestamp: Option<i64>,
+    on_progress: ProgressFn,
+) -> Result<ClaudeUsageSummary, String> {
+    let Some(dir) = claude_projects_dir() else {
+        return Ok(summarize_claude(Vec::new()));
+    };
+    let paths = claude_session_paths(&dir)?;
+    let mut timer = perf::start("usage.scan").subject("claude");
+    timer.set_files(paths.len());
+    let sessions = scan_files(&paths, on_progress, |path| {
+        parse_claude_session(path, since_timestamp)
+    });
+    Ok(summarize_claude(sessions))
+}
+
+/// Loads Claude Code usage statistics from the usage index, emitting
+/// `usage-scan-progress` while changed session files are parsed.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn load_claude_usage(
+    app: AppHandle,
+    since_timestamp: Option<i64>,
+) -> Result<ClaudeUsageSummary, String> {
+    let data_dir = app_data_dir(&app)?;
+    tokio::task::spawn_blocking(move || {
+        usage_index::claude_usage(&data_dir, since_timestamp, &emit_progress(&app, "claude"))
     })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ============================================================================
+// Combined Usage
+// ============================================================================
+
+/// One agent's usage totals in `AllUsageSummary`.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentUsageTotals {
+    pub agent_id: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    /// Amp credits; `None` for agents that don't bill in credits.
+    pub credits: Option<f64>,
+    pub duration_ms: i64,
+    pub session_count: i32,
+}
+
+/// One thread or session in the merged timeline of `AllUsageSummary`.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTimelineEntry {
+    pub agent_id: String,
+    pub session_id: String,
+    pub title: Option<String>,
+    pub timestamp: String,
+    pub model: Option<String>,
+    pub total_tokens: i64,
+    pub credits: Option<f64>,
+    pub duration_ms: i64,
 }
 
-/// Loads Claude Code usage statistics from session files.
+/// Usage of every supported agent, from `load_all_usage`.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllUsageSummary {
+    pub amp: AmpUsageSummary,
+    pub claude: ClaudeUsageSummary,
+    pub agents: Vec<AgentUsageTotals>,
+    /// Every agent's threads and sessions, newest first.
+    pub timeline: Vec<UsageTimelineEntry>,
+}
+
+#[cfg(feature = "gui")]
+fn combine_usage(amp: AmpUsageSummary, claude: ClaudeUsageSummary) -> AllUsageSummary {
+    let agents = vec![
+        AgentUsageTotals {
+            agent_id: "amp".to_string(),
+            input_tokens: amp.total_input_tokens,
+            output_tokens: amp.total_output_tokens,
+            total_tokens: amp.total_tokens,
+            credits: Some(amp.total_credits),
+            duration_ms: amp.total_duration_ms,
+            session_count: amp.thread_count,
+        },
+        AgentUsageTotals {
+            agent_id: "claude-code".to_string(),
+            input_tokens: claude.total_input_tokens,
+            output_tokens: claude.total_output_tokens,
+            total_tokens: claude.total_tokens,
+            credits: None,
+            duration_ms: claude.total_duration_ms,
+            session_count: claude.session_count,
+        },
+    ];
+
+    let mut timeline: Vec<UsageTimelineEntry> = amp
+        .entries
+        .iter()
+        .map(|e| UsageTimelineEntry {
+            agent_id: "amp".to_string(),
+            session_id: e.thread_id.clone(),
+            title: e.thread_title.clone(),
+            timestamp: e.timestamp.clone(),
+            model: e.model.clone(),
+            total_tokens: e.total_tokens,
+            credits: Some(e.credits),
+            duration_ms: e.duration_ms,
+        })
+        .chain(claude.entries.iter().map(|e| UsageTimelineEntry {
+            agent_id: "claude-code".to_string(),
+            session_id: e.session_id.clone(),
+            title: None,
+            timestamp: e.timestamp.clone(),
+            model: e.model.clone(),
+            total_tokens: e.total_tokens,
+            credits: None,
+            duration_ms: e.duration_ms,
+        }))
+        .collect();
+    // Entries with an "unknown" timestamp go last
+    timeline.sort_by_key(|e| std::cmp::Reverse(chrono::DateTime::parse_from_rfc3339(&e.timestamp).ok()));
+
+    AllUsageSummary {
+        amp,
+        claude,
+        agents,
+        timeline,
+    }
+}
+
+/// Loads every supported agent's usage at once, emitting `usage-scan-progress`
+/// for each while its changed files are parsed.
+#[cfg(feature = "gui")]
 #[tauri::command]
-pub async fn load_claude_usage(since_timestamp: Option<i64>) -> Result<ClaudeUsageSummary, String> {
-    tokio::task::spawn_blocking(move || load_claude_usage_sync(since_timestamp))
-        .await
-        .map_err(|e| format!("Task join error: {}", e))?
+pub async fn load_all_usage(app: AppHandle, since_timestamp: Option<i64>) -> Result<AllUsageSummary, String> {
+    let data_dir = app_data_dir(&app)?;
+    let (amp_app, amp_dir) = (app.clone(), data_dir.clone());
+    let amp = tokio::task::spawn_blocking(move || {
+        usage_index::amp_usage(&amp_dir, since_timestamp, &emit_progress(&amp_app, "amp"))
+    });
+    let claude = tokio::task::spawn_blocking(move || {
+        usage_index::claude_usage(&data_dir, since_timestamp, &emit_progress(&app, "claude"))
+    });
+    let (amp, claude) = tokio::join!(amp, claude);
+    let amp = amp.map_err(|e| format!("Task join error: {}", e))??;
+    let claude = claude.map_err(|e| format!("Task join error: {}", e))??;
+    Ok(combine_usage(amp, claude))
 }
 
 // ============================================================================
 // Recent Thread Duration
 // ============================================================================
 
+#[cfg(feature = "gui")]
 fn get_recent_amp_thread_duration_sync(since_ms: i64) -> Result<RecentThreadDuration, String> {
     let home_dir =
         dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
@@ -595,6 +858,7 @@ fn get_recent_amp_thread_duration_sync(since_ms: i64) -> Result<RecentThreadDura
 }
 
 /// Gets the duration of the most recently active Amp thread.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn get_recent_amp_thread_duration(since_ms: i64) -> Result<RecentThreadDuration, String> {
     tokio::task::spawn_blocking(move || get_recent_amp_thread_duration_sync(since_ms))
@@ -602,6 +866,7 @@ pub async fn get_recent_amp_thread_duration(since_ms: i64) -> Result<RecentThrea
         .map_err(|e| format!("Task join error: {}", e))?
 }
 
+#[cfg(feature = "gui")]
 fn get_recent_claude_session_duration_sync(since_ms: i64) -> Result<RecentThreadDuration, String> {
     let home_dir =
         dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
@@ -677,6 +942,7 @@ fn get_recent_claude_session_duration_sync(since_ms: i64) -> Result<RecentThread
 }
 
 /// Gets the duration of the most recently active Claude session.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn get_recent_claude_session_duration(
     since_ms: i64,
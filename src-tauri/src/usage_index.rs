@@ -0,0 +1,311 @@
+//! Persistent index of Amp and Claude Code usage files.
+//!
+//! Parsing every thread and session on each usage query grows with history.
+//! The index keeps each file's unfiltered usage together with the size and
+//! modification time it was parsed at, saved under `usage-index/` in the app
+//! data directory. A filesystem watcher on `~/.claude/projects` and the Amp
+//! threads directory records which files change; queries re-parse only those
+//! and answer the rest from the index.
+//!
+//! Sources that aren't watched (the directory appeared after startup, or the
+//! watcher failed or overflowed) fall back to listing the directory and
+//! comparing size and mtime, which still only parses changed files. The
+//! headless CLI scans directly instead.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::perf;
+use crate::usage::{
+    self, AmpUsageEntry, AmpUsageSummary, ClaudeUsageEntry, ClaudeUsageSummary, ParsedUsage,
+    ProgressFn,
+};
+
+/// Bumped when parsing changes, so older indexes are rebuilt.
+const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Source {
+    Amp,
+    Claude,
+}
+
+impl Source {
+    fn name(self) -> &'static str {
+        match self {
+            Source::Amp => "amp",
+            Source::Claude => "claude",
+        }
+    }
+
+    fn dir(self) -> Option<PathBuf> {
+        match self {
+            Source::Amp => usage::amp_threads_dir(),
+            Source::Claude => usage::claude_projects_dir(),
+        }
+    }
+
+    fn list(self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        match self {
+            Source::Amp => usage::amp_thread_paths(dir),
+            Source::Claude => usage::claude_session_paths(dir),
+        }
+    }
+
+    /// Whether a changed path is one of this source's usage files.
+    fn is_usage_file(self, dir: &Path, path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        match self {
+            Source::Amp => path.parent() == Some(dir) && name.starts_with("T-") && name.ends_with(".json"),
+            Source::Claude => {
+                path.parent().and_then(Path::parent) == Some(dir) && name.ends_with(".jsonl")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedFile<T> {
+    size: u64,
+    modified_ms: i64,
+    /// `None` for files without usage, so they aren't re-parsed either.
+    usage: Option<ParsedUsage<T>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceIndex<T> {
+    version: u32,
+    files: BTreeMap<PathBuf, IndexedFile<T>>,
+}
+
+impl<T> Default for SourceIndex<T> {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            files: BTreeMap::new(),
+        }
+    }
+}
+
+/// What the watcher has seen for one source since the last query.
+#[derive(Default)]
+struct Changes {
+    watched: bool,
+    /// A full scan has run since watching began, so `paths` is complete.
+    synced: bool,
+    paths: HashSet<PathBuf>,
+}
+
+lazy_static::lazy_static! {
+    static ref AMP_INDEX: Mutex<Option<SourceIndex<AmpUsageEntry>>> = Mutex::new(None);
+    static ref CLAUDE_INDEX: Mutex<Option<SourceIndex<ClaudeUsageEntry>>> = Mutex::new(None);
+    static ref CHANGES: Mutex<HashMap<Source, Changes>> = Mutex::new(HashMap::new());
+    static ref WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+}
+
+fn index_path(data_dir: &Path, source: Source) -> PathBuf {
+    data_dir.join("usage-index").join(format!("{}.json", source.name()))
+}
+
+fn load_index<T: DeserializeOwned>(data_dir: &Path, source: Source) -> SourceIndex<T> {
+    fs::read_to_string(index_path(data_dir, source))
+        .ok()
+        .and_then(|content| serde_json::from_str::<SourceIndex<T>>(&content).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or_default()
+}
+
+fn save_index<T: Serialize>(data_dir: &Path, source: Source, index: &SourceIndex<T>) {
+    let path = index_path(data_dir, source);
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string(index).map_err(|e| e.to_string()))
+        .and_then(|json| fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        tracing::warn!("Failed to save {} usage index: {}", source.name(), e);
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, i64)> {
+    let size = fs::metadata(path).ok()?.len();
+    Some((size, usage::modified_ms(path)?))
+}
+
+/// Brings the index up to date with the source directory. Returns whether
+/// anything changed.
+fn refresh<T: Send>(
+    index: &mut SourceIndex<T>,
+    source: Source,
+    on_progress: ProgressFn,
+    parse: impl Fn(&Path) -> Option<ParsedUsage<T>> + Sync,
+) -> Result<bool, String> {
+    let Some(dir) = source.dir() else {
+        let changed = !index.files.is_empty();
+        index.files.clear();
+        return Ok(changed);
+    };
+
+    let (full, changed_paths) = {
+        let mut changes = CHANGES.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let changes = changes.entry(source).or_default();
+        let full = !(changes.watched && changes.synced);
+        if changes.watched {
+            changes.synced = true;
+        }
+        (full, std::mem::take(&mut changes.paths))
+    };
+
+    let mut changed = false;
+    let candidates: Vec<PathBuf> = if full {
+        let paths = source.list(&dir)?;
+        let present: HashSet<&PathBuf> = paths.iter().collect();
+        let before = index.files.len();
+        index.files.retain(|path, _| present.contains(path));
+        changed |= index.files.len() != before;
+        paths
+    } else {
+        changed_paths
+            .into_iter()
+            .filter(|path| source.is_usage_file(&dir, path))
+            .collect()
+    };
+
+    // Parse only files that are new or whose size or mtime moved
+    let mut stale = Vec::new();
+    for path in candidates {
+        match file_stamp(&path) {
+            None => changed |= index.files.remove(&path).is_some(),
+            Some(stamp) => {
+                let current = index
+                    .files
+                    .get(&path)
+                    .is_some_and(|f| (f.size, f.modified_ms) == stamp);
+                if !current {
+                    stale.push(path);
+                }
+            }
+        }
+    }
+    if stale.is_empty() {
+        return Ok(changed);
+    }
+
+    let mut timer = perf::start("usage.parse").subject(source.name());
+    timer.set_files(stale.len());
+    let parsed = usage::scan_files(&stale, on_progress, |path| {
+        let (size, modified_ms) = file_stamp(path)?;
+        Some((path.to_path_buf(), IndexedFile { size, modified_ms, usage: parse(path) }))
+    });
+    for (path, file) in parsed {
+        index.files.insert(path, file);
+    }
+    Ok(true)
+}
+
+/// Usage from files that started at or after `since_timestamp`, in path order.
+fn collect<T: Clone>(index: &SourceIndex<T>, since_timestamp: Option<i64>) -> Vec<ParsedUsage<T>> {
+    index
+        .files
+        .values()
+        .filter_map(|file| file.usage.as_ref())
+        .filter(|usage| match (since_timestamp, usage.started_ms) {
+            (Some(since), Some(started)) => started >= since,
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Answers an Amp usage query from the index.
+pub fn amp_usage(
+    data_dir: &Path,
+    since_timestamp: Option<i64>,
+    on_progress: ProgressFn,
+) -> Result<AmpUsageSummary, String> {
+    let mut timer = perf::start("usage.query").subject(Source::Amp.name());
+    let mut guard = AMP_INDEX.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let index = guard.get_or_insert_with(|| load_index(data_dir, Source::Amp));
+    if refresh(index, Source::Amp, on_progress, |path| usage::parse_amp_thread(path, None))? {
+        save_index(data_dir, Source::Amp, index);
+    }
+    timer.set_files(index.files.len());
+    let entries = collect(index, since_timestamp).into_iter().map(|u| u.entry).collect();
+    Ok(usage::summarize_amp(entries))
+}
+
+/// Answers a Claude Code usage query from the index.
+pub fn claude_usage(
+    data_dir: &Path,
+    since_timestamp: Option<i64>,
+    on_progress: ProgressFn,
+) -> Result<ClaudeUsageSummary, String> {
+    let mut timer = perf::start("usage.query").subject(Source::Claude.name());
+    let mut guard = CLAUDE_INDEX.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let index = guard.get_or_insert_with(|| load_index(data_dir, Source::Claude));
+    if refresh(index, Source::Claude, on_progress, |path| usage::parse_claude_session(path, None))? {
+        save_index(data_dir, Source::Claude, index);
+    }
+    timer.set_files(index.files.len());
+    Ok(usage::summarize_claude(collect(index, since_timestamp)))
+}
+
+fn record_event(result: notify::Result<notify::Event>) {
+    let Ok(mut changes) = CHANGES.lock() else { return };
+    match result {
+        Ok(event) if !event.need_rescan() => {
+            for source in [Source::Amp, Source::Claude] {
+                let Some(dir) = source.dir() else { continue };
+                let source_changes = changes.entry(source).or_default();
+                source_changes
+                    .paths
+                    .extend(event.paths.iter().filter(|p| p.starts_with(&dir)).cloned());
+            }
+        }
+        _ => {
+            // Events were dropped; rescan everything on the next query
+            for source_changes in changes.values_mut() {
+                source_changes.synced = false;
+            }
+        }
+    }
+}
+
+/// Starts watching the agent data directories that exist. Called once at startup.
+pub fn start_watcher() {
+    let mut watcher = match notify::recommended_watcher(record_event) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Usage index watcher unavailable: {}", e);
+            return;
+        }
+    };
+
+    for (source, mode) in [
+        (Source::Amp, RecursiveMode::NonRecursive),
+        (Source::Claude, RecursiveMode::Recursive),
+    ] {
+        let Some(dir) = source.dir() else { continue };
+        match watcher.watch(&dir, mode) {
+            Ok(()) => {
+                if let Ok(mut changes) = CHANGES.lock() {
+                    changes.entry(source).or_default().watched = true;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to watch {}: {}", dir.display(), e),
+        }
+    }
+
+    if let Ok(mut slot) = WATCHER.lock() {
+        *slot = Some(watcher);
+    }
+}
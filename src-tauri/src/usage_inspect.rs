@@ -0,0 +1,311 @@
+//! Message-level usage of one Amp thread or Claude Code session.
+//!
+//! The usage summaries total each thread; these break one down by assistant
+//! message, with the tools it called and how long it took, to find the step
+//! of a build that used a surprising number of tokens.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::cost_attribution::AMP_CREDIT_USD;
+use crate::pricing::{self, Pricing};
+use crate::usage::{amp_threads_dir, claude_projects_dir};
+
+/// Characters of message text kept as its preview.
+const PREVIEW_CHARS: usize = 200;
+
+/// Usage of one assistant message.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageUsage {
+    /// Position of the message in the thread or session.
+    pub index: usize,
+    pub timestamp: Option<String>,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub total_tokens: i64,
+    /// Amp credits.
+    pub credits: Option<f64>,
+    /// USD, from Amp credits or the configured model prices.
+    pub cost: Option<f64>,
+    /// Time since the previous message, i.e. how long this one took.
+    pub elapsed_ms: Option<i64>,
+    /// Tools called, with the file or command they were called on.
+    pub tools: Vec<String>,
+    /// Start of the message's text.
+    pub preview: Option<String>,
+    pub stop_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageInspection {
+    pub agent_id: String,
+    /// Thread or session ID.
+    pub id: String,
+    pub title: Option<String>,
+    pub started_at: Option<String>,
+    pub duration_ms: i64,
+    pub messages: Vec<MessageUsage>,
+    pub total_input_tokens: i64,
+    pub total_output_tokens: i64,
+    pub total_cache_creation_tokens: i64,
+    pub total_cache_read_tokens: i64,
+    pub total_tokens: i64,
+    pub total_credits: Option<f64>,
+    pub total_cost: Option<f64>,
+}
+
+/// Rejects IDs that could name a file outside the agent's directory.
+fn check_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("Invalid ID: {}", id));
+    }
+    Ok(())
+}
+
+fn int(value: &Value, pointer: &str) -> i64 {
+    value.pointer(pointer).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn text(value: &Value, pointer: &str) -> Option<String> {
+    value.pointer(pointer).and_then(Value::as_str).map(String::from)
+}
+
+fn parse_time(value: &Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    match value {
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&chrono::Utc)),
+        Value::Number(n) => chrono::DateTime::from_timestamp_millis(n.as_i64()?),
+        _ => None,
+    }
+}
+
+/// Tool calls and text preview of a message's content blocks.
+fn describe_content(content: &Value, message: &mut MessageUsage) {
+    let Some(blocks) = content.as_array() else {
+        if let Some(s) = content.as_str() {
+            message.preview.get_or_insert_with(|| s.chars().take(PREVIEW_CHARS).collect());
+        }
+        return;
+    };
+    for block in blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("tool_use") => {
+                let name = text(block, "/name").unwrap_or_else(|| "tool".to_string());
+                let target = ["/input/file_path", "/input/path", "/input/command", "/input/cmd"]
+                    .iter()
+                    .find_map(|p| text(block, p));
+                message.tools.push(match target {
+                    Some(target) => format!("{} {}", name, target.chars().take(80).collect::<String>()),
+                    None => name,
+                });
+            }
+            Some("text") => {
+                if let Some(s) = text(block, "/text").filter(|s| !s.trim().is_empty()) {
+                    message
+                        .preview
+                        .get_or_insert_with(|| s.trim().chars().take(PREVIEW_CHARS).collect());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn claude_cost(message: &MessageUsage, pricing: &Pricing) -> Option<f64> {
+    let price = pricing::price_for(pricing, message.model.as_deref()?)?;
+    let cache_read = price.cache_read_per_million.unwrap_or(price.input_per_million);
+    let cache_write = price.cache_write_per_million.unwrap_or(price.input_per_million);
+    Some(
+        (message.input_tokens as f64 * price.input_per_million
+            + message.output_tokens as f64 * price.output_per_million
+            + message.cache_read_tokens as f64 * cache_read
+            + message.cache_creation_tokens as f64 * cache_write)
+            / 1_000_000.0,
+    )
+}
+
+/// Fills in the totals, timing, and elapsed times from the messages.
+/// `times` holds when each message with a time was sent, in order, including
+/// user and tool messages.
+fn finish(mut inspection: UsageInspection, times: &[(usize, chrono::DateTime<chrono::Utc>)]) -> UsageInspection {
+    for message in &mut inspection.messages {
+        let at = times.iter().find(|(i, _)| *i == message.index).map(|(_, t)| *t);
+        let before = times.iter().rev().find(|(i, _)| *i < message.index).map(|(_, t)| *t);
+        if let (Some(at), Some(before)) = (at, before) {
+            message.elapsed_ms = Some((at - before).num_milliseconds().max(0));
+        }
+        inspection.total_input_tokens += message.input_tokens;
+        inspection.total_output_tokens += message.output_tokens;
+        inspection.total_cache_creation_tokens += message.cache_creation_tokens;
+        inspection.total_cache_read_tokens += message.cache_read_tokens;
+        if let Some(credits) = message.credits {
+            *inspection.total_credits.get_or_insert(0.0) += credits;
+        }
+        if let Some(cost) = message.cost {
+            *inspection.total_cost.get_or_insert(0.0) += cost;
+        }
+    }
+    inspection.total_tokens = inspection.total_input_tokens + inspection.total_output_tokens;
+    if let (Some((_, first)), Some((_, last))) = (times.first(), times.last()) {
+        inspection.started_at.get_or_insert_with(|| first.to_rfc3339());
+        inspection.duration_ms = (*last - *first).num_milliseconds().max(0);
+    }
+    inspection
+}
+
+fn inspect_amp_thread_sync(thread_id: &str) -> Result<UsageInspection, String> {
+    check_id(thread_id)?;
+    let dir = amp_threads_dir().ok_or("Amp threads directory not found")?;
+    let content = fs::read_to_string(dir.join(format!("{}.json", thread_id)))
+        .map_err(|e| format!("Failed to read thread {}: {}", thread_id, e))?;
+    let thread: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse thread {}: {}", thread_id, e))?;
+
+    let mut inspection = UsageInspection {
+        agent_id: "amp".to_string(),
+        id: thread_id.to_string(),
+        title: text(&thread, "/title"),
+        started_at: thread.get("created").and_then(parse_time).map(|t| t.to_rfc3339()),
+        ..Default::default()
+    };
+    let mut times = Vec::new();
+    let messages = thread.get("messages").and_then(Value::as_array).into_iter().flatten();
+    for (index, msg) in messages.enumerate() {
+        let at = msg
+            .pointer("/usage/timestamp")
+            .or_else(|| msg.pointer("/meta/sentAt"))
+            .and_then(parse_time);
+        if let Some(at) = at {
+            times.push((index, at));
+        }
+        if msg.get("role").and_then(Value::as_str) != Some("assistant") {
+            continue;
+        }
+
+        let credits = msg.pointer("/usage/credits").and_then(Value::as_f64);
+        let mut message = MessageUsage {
+            index,
+            timestamp: at.map(|t| t.to_rfc3339()),
+            model: text(msg, "/usage/model"),
+            input_tokens: int(msg, "/usage/inputTokens"),
+            output_tokens: int(msg, "/usage/outputTokens"),
+            cache_creation_tokens: int(msg, "/usage/cacheCreationInputTokens"),
+            cache_read_tokens: int(msg, "/usage/cacheReadInputTokens"),
+            credits,
+            cost: credits.map(|c| c * AMP_CREDIT_USD),
+            stop_reason: text(msg, "/state/stopReason"),
+            ..Default::default()
+        };
+        message.total_tokens = message.input_tokens + message.output_tokens;
+        if let Some(content) = msg.get("content") {
+            describe_content(content, &mut message);
+        }
+        inspection.messages.push(message);
+    }
+    Ok(finish(inspection, &times))
+}
+
+fn find_claude_session(session_id: &str) -> Result<PathBuf, String> {
+    let dir = claude_projects_dir().ok_or("Claude Code projects directory not found")?;
+    let pattern = dir.join("*").join(format!("{}.jsonl", session_id));
+    glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| format!("Glob pattern error: {}", e))?
+        .flatten()
+        .next()
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
+fn inspect_claude_session_sync(session_id: &str, pricing: &Pricing) -> Result<UsageInspection, String> {
+    check_id(session_id)?;
+    let path = find_claude_session(session_id)?;
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to read session {}: {}", session_id, e))?;
+
+    let mut inspection = UsageInspection {
+        agent_id: "claude-code".to_string(),
+        id: session_id.to_string(),
+        ..Default::default()
+    };
+    let mut times = Vec::new();
+    // Claude Code writes one line per content block of a response, each with
+    // the response's message ID and its usage so far
+    let mut message_ids: Vec<String> = Vec::new();
+    for (index, line) in BufReader::new(file).lines().map_while(Result::ok).enumerate() {
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else { continue };
+        if inspection.title.is_none() && entry.get("type").and_then(Value::as_str) == Some("summary") {
+            inspection.title = text(&entry, "/summary");
+        }
+        let at = entry.get("timestamp").and_then(parse_time);
+        if entry.get("type").and_then(Value::as_str) != Some("assistant") {
+            if let Some(at) = at {
+                times.push((index, at));
+            }
+            continue;
+        }
+
+        let id = text(&entry, "/message/id").unwrap_or_else(|| format!("line-{}", index));
+        let position = match message_ids.iter().position(|m| *m == id) {
+            Some(position) => position,
+            None => {
+                if let Some(at) = at {
+                    times.push((index, at));
+                }
+                message_ids.push(id);
+                inspection.messages.push(MessageUsage {
+                    index,
+                    timestamp: at.map(|t| t.to_rfc3339()),
+                    ..Default::default()
+                });
+                inspection.messages.len() - 1
+            }
+        };
+
+        let message = &mut inspection.messages[position];
+        if let Some(model) = text(&entry, "/message/model") {
+            message.model = Some(model);
+        }
+        if entry.pointer("/message/usage").is_some() {
+            message.input_tokens = int(&entry, "/message/usage/input_tokens");
+            message.output_tokens = int(&entry, "/message/usage/output_tokens");
+            message.cache_creation_tokens = int(&entry, "/message/usage/cache_creation_input_tokens");
+            message.cache_read_tokens = int(&entry, "/message/usage/cache_read_input_tokens");
+            message.total_tokens = message.input_tokens + message.output_tokens;
+        }
+        if let Some(reason) = text(&entry, "/message/stop_reason") {
+            message.stop_reason = Some(reason);
+        }
+        if let Some(content) = entry.pointer("/message/content") {
+            describe_content(content, message);
+        }
+    }
+    for message in &mut inspection.messages {
+        message.cost = claude_cost(message, pricing);
+    }
+    Ok(finish(inspection, &times))
+}
+
+/// Breaks an Amp thread's usage down by assistant message.
+#[tauri::command]
+pub async fn inspect_amp_thread(thread_id: String) -> Result<UsageInspection, String> {
+    tokio::task::spawn_blocking(move || inspect_amp_thread_sync(&thread_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Breaks a Claude Code session's usage down by API response, priced at the
+/// configured model prices.
+#[tauri::command]
+pub async fn inspect_claude_session(app: AppHandle, session_id: String) -> Result<UsageInspection, String> {
+    let pricing = pricing::load_pricing(app)?;
+    tokio::task::spawn_blocking(move || inspect_claude_session_sync(&session_id, &pricing))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -1,53 +1,306 @@
 //! Utility functions used across the application.
 
-use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+/// Bundle identifier from tauri.conf.json; names the app data directory.
+pub const APP_IDENTIFIER: &str = "com.kevinelliott.ideate";
+
+/// File in the default app data directory that points to a relocated one.
+/// It stays behind when the data moves, so the data can be found again.
+pub const DATA_LOCATION_FILE: &str = "data-location.json";
+
+/// Contents of `data-location.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub path: String,
+}
+
+/// Where app data lives given the platform's default directory: the
+/// directory `data-location.json` names, else the default itself.
+pub fn resolve_app_data_dir(default_dir: PathBuf) -> PathBuf {
+    let location = fs::read_to_string(default_dir.join(DATA_LOCATION_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<DataLocation>(&content).ok());
+    match location {
+        Some(location) if !location.path.trim().is_empty() => PathBuf::from(location.path),
+        _ => default_dir,
+    }
+}
+
+/// The platform's default app data directory as Tauri resolves it, for code
+/// running without an `AppHandle`.
+fn headless_default_app_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Failed to get app data directory".to_string())
+}
+
+/// The app data directory, following a relocation, for code running without
+/// an `AppHandle` (the headless CLI).
+pub fn headless_app_data_dir() -> Result<PathBuf, String> {
+    headless_default_app_data_dir().map(resolve_app_data_dir)
+}
+
 /// Returns the path to the .ideate directory within a project.
 pub fn get_ideate_dir(project_path: &str) -> PathBuf {
     PathBuf::from(project_path).join(".ideate")
 }
 
-/// Sanitizes JSON content that may have common formatting issues from AI-generated output.
-/// Handles trailing commas, single-line comments, control characters, and other common issues.
-pub fn sanitize_json(content: &str) -> String {
-    let mut result = content.to_string();
-    
-    // Remove control characters (except for valid whitespace: \t, \n, \r)
-    // These can appear in AI-generated content and break JSON parsing
-    result = result.chars().filter(|c| {
-        !c.is_control() || *c == '\t' || *c == '\n' || *c == '\r'
-    }).collect();
-    
-    // Remove single-line comments (// ...)
-    let single_comment_re = Regex::new(r#"//[^
-]*"#).unwrap();
-    result = single_comment_re.replace_all(&result, "").to_string();
-    
-    // Remove multi-line comments (/* ... */)
-    let multi_comment_re = Regex::new(r#"/\*[\s\S]*?\*/"#).unwrap();
-    result = multi_comment_re.replace_all(&result, "").to_string();
-    
-    // Remove trailing commas before ] or }
-    // Handle: [1, 2, 3,] or {"a": 1,}
-    let trailing_comma_re = Regex::new(r#",(\s*[}\]])"#).unwrap();
-    result = trailing_comma_re.replace_all(&result, "$1").to_string();
-    
-    // Handle multiple trailing commas
-    for _ in 0..5 {
-        let prev = result.clone();
-        result = trailing_comma_re.replace_all(&result, "$1").to_string();
-        if result == prev {
-            break;
+/// What `repair_json` changed in a document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonRepairs {
+    pub byte_order_mark: bool,
+    pub comments: usize,
+    pub extra_commas: usize,
+    pub control_characters: usize,
+    pub single_quoted_strings: usize,
+    pub unquoted_keys: usize,
+}
+
+impl JsonRepairs {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The repairs for messages, e.g. "removed 2 comments, removed 1 extra comma".
+    pub fn summary(&self) -> String {
+        fn counted(verb: &str, n: usize, noun: &str) -> Option<String> {
+            (n > 0).then(|| format!("{} {} {}{}", verb, n, noun, if n == 1 { "" } else { "s" }))
         }
+        let parts: Vec<String> = [
+            self.byte_order_mark.then(|| "removed a byte order mark".to_string()),
+            counted("removed", self.comments, "comment"),
+            counted("removed", self.extra_commas, "extra comma"),
+            counted("escaped", self.control_characters, "control character"),
+            counted("requoted", self.single_quoted_strings, "single-quoted string"),
+            counted("quoted", self.unquoted_keys, "unquoted key"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        parts.join(", ")
+    }
+}
+
+/// Index of the next character in `chars` from `i` that is not whitespace or
+/// part of a comment.
+fn skip_insignificant(chars: &[char], mut i: usize) -> usize {
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            (c, _) if c.is_whitespace() => i += 1,
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => i = comment_end(chars, i),
+            _ => break,
+        }
+    }
+    i
+}
+
+/// Index just past the `/* */` comment starting at `i`, or the end if it is
+/// never closed.
+fn comment_end(chars: &[char], i: usize) -> usize {
+    (i + 2..chars.len().saturating_sub(1))
+        .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+        .map_or(chars.len(), |j| j + 2)
+}
+
+/// Copies the string literal starting at `start` to `out` as a valid JSON
+/// string. Returns the index just past it.
+fn copy_string(chars: &[char], start: usize, out: &mut String, repairs: &mut JsonRepairs) -> usize {
+    let quote = chars[start];
+    if quote == '\'' {
+        repairs.single_quoted_strings += 1;
+    }
+    out.push('"');
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                match chars.get(i + 1) {
+                    // Not a JSON escape, but common in agent output
+                    Some('\'') => out.push('\''),
+                    Some(&next) => {
+                        out.push('\\');
+                        out.push(next);
+                    }
+                    None => {}
+                }
+                i += 2;
+                continue;
+            }
+            c if c == quote => {
+                out.push('"');
+                return i + 1;
+            }
+            '"' => out.push_str("\\\""),
+            c if c.is_control() => {
+                repairs.control_characters += 1;
+                match c {
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    other => out.push_str(&format!("\\u{:04x}", other as u32)),
+                }
+            }
+            c => out.push(c),
+        }
+        i += 1;
+    }
+    // Unterminated; close it so the parser reports what follows
+    out.push('"');
+    chars.len()
+}
+
+/// Repairs the loose JSON agents tend to write: comments, trailing and
+/// doubled commas, raw control characters, single-quoted strings, and
+/// unquoted keys. String contents are left alone, so URLs and other text
+/// containing `//` or `,]` survive.
+pub fn repair_json(content: &str) -> (String, JsonRepairs) {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut repairs = JsonRepairs::default();
+    let mut i = 0;
+    if chars.first() == Some(&'\u{feff}') {
+        repairs.byte_order_mark = true;
+        i = 1;
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        match (c, chars.get(i + 1)) {
+            ('"' | '\'', _) => {
+                i = copy_string(&chars, i, &mut out, &mut repairs);
+                continue;
+            }
+            ('/', Some('/')) => {
+                repairs.comments += 1;
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                repairs.comments += 1;
+                i = comment_end(&chars, i);
+                continue;
+            }
+            (',', _) => {
+                if matches!(chars.get(skip_insignificant(&chars, i + 1)), Some(',' | '}' | ']')) {
+                    repairs.extra_commas += 1;
+                } else {
+                    out.push(c);
+                }
+            }
+            (c, _) if c.is_alphabetic() || c == '_' || c == '$' => {
+                let end = (i..chars.len())
+                    .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_' || chars[j] == '$'))
+                    .unwrap_or(chars.len());
+                let word: String = chars[i..end].iter().collect();
+                let is_key = !matches!(word.as_str(), "true" | "false" | "null")
+                    && chars.get(skip_insignificant(&chars, end)) == Some(&':');
+                if is_key {
+                    repairs.unquoted_keys += 1;
+                    out.push('"');
+                    out.push_str(&word);
+                    out.push('"');
+                } else {
+                    out.push_str(&word);
+                }
+                i = end;
+                continue;
+            }
+            (c, _) if c.is_control() && !c.is_whitespace() => repairs.control_characters += 1,
+            (c, _) => out.push(c),
+        }
+        i += 1;
+    }
+    (out, repairs)
+}
+
+/// Parses JSON that an agent may have written loosely. Content that is valid
+/// as written is parsed directly; otherwise it goes through `repair_json`,
+/// and the repairs are logged or, if it still fails, included in the error.
+/// `name` is the file name used in messages.
+pub fn parse_lenient_json<T: DeserializeOwned>(content: &str, name: &str) -> Result<T, String> {
+    let first_error = match serde_json::from_str(content) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+    let (repaired, repairs) = repair_json(content);
+    if repairs.is_empty() {
+        return Err(format!("Failed to parse {}: {}", name, first_error));
+    }
+    match serde_json::from_str(&repaired) {
+        Ok(value) => {
+            tracing::warn!("Repaired {} while loading it: {}", name, repairs.summary());
+            Ok(value)
+        }
+        Err(e) => Err(format!(
+            "Failed to parse {}: {} (after the automatic repairs, {}, it is still invalid: {})",
+            name,
+            first_error,
+            repairs.summary(),
+            e
+        )),
+    }
+}
+
+/// What an agent replied, from its output: the stream-json result if there
+/// is one, else its assistant text, else the plain output.
+#[cfg(feature = "gui")]
+pub fn agent_reply_text<S: AsRef<str>>(lines: &[S]) -> String {
+    let mut assistant = String::new();
+    let mut plain = String::new();
+    for line in lines.iter().map(AsRef::as_ref) {
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(event) if event.get("type").and_then(|t| t.as_str()) == Some("result") => {
+                if let Some(result) = event.get("result").and_then(|r| r.as_str()) {
+                    return result.to_string();
+                }
+            }
+            Ok(event) => {
+                let blocks = event.pointer("/message/content").and_then(|c| c.as_array());
+                for block in blocks.into_iter().flatten() {
+                    if let Some(text) = block.get("text").and_then(|t| t.as_str()) {
+                        assistant.push_str(text);
+                        assistant.push('\n');
+                    }
+                }
+            }
+            Err(_) => {
+                plain.push_str(line);
+                plain.push('\n');
+            }
+        }
+    }
+    if assistant.trim().is_empty() {
+        plain
+    } else {
+        assistant
+    }
+}
+
+/// Parses the JSON object in an agent's reply, from its first `{` to its
+/// last `}`, leniently. `name` is what the object is called in messages.
+#[cfg(feature = "gui")]
+pub fn parse_reply_json<T: DeserializeOwned>(reply: &str, name: &str) -> Result<T, String> {
+    match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => parse_lenient_json(&reply[start..=end], name),
+        _ => Err(format!("No JSON object for {} in the agent's reply", name)),
     }
-    
-    result
 }
 
 /// Write binary data to a file at the specified path.
 /// This bypasses the fs plugin scope restrictions for user-selected save paths.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn write_binary_file(path: String, data: Vec<u8>) -> Result<(), String> {
     fs::write(&path, &data).map_err(|e| format!("Failed to write file: {}", e))
@@ -56,6 +309,7 @@ pub fn write_binary_file(path: String, data: Vec<u8>) -> Result<(), String> {
 /// Reveal a file or folder in the system file manager.
 /// On macOS, this uses `open -R` to reveal and select the item in Finder.
 /// On other platforms, this opens the containing folder.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn reveal_in_file_manager(path: String) -> Result<(), String> {
     let path = PathBuf::from(&path);
@@ -96,6 +350,7 @@ pub fn reveal_in_file_manager(path: String) -> Result<(), String> {
 }
 
 /// Represents a file or directory entry in the file tree.
+#[cfg(feature = "gui")]
 #[derive(serde::Serialize)]
 pub struct FileEntry {
     pub name: String,
@@ -105,6 +360,7 @@ pub struct FileEntry {
 }
 
 /// List files in a project directory, filtering out common ignored directories.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn list_project_files(project_path: String, max_depth: Option<u32>) -> Result<Vec<FileEntry>, String> {
     let path = PathBuf::from(&project_path);
@@ -116,6 +372,7 @@ pub fn list_project_files(project_path: String, max_depth: Option<u32>) -> Resul
     list_files_recursive(&path, &path, 0, depth)
 }
 
+#[cfg(feature = "gui")]
 fn list_files_recursive(
     base_path: &PathBuf,
     current_path: &PathBuf,
@@ -182,6 +439,7 @@ fn list_files_recursive(
 }
 
 /// Read the contents of a file.
+#[cfg(feature = "gui")]
 #[tauri::command(rename_all = "camelCase")]
 pub fn read_project_file(project_path: String, relative_path: String) -> Result<String, String> {
     let full_path = PathBuf::from(&project_path).join(&relative_path);
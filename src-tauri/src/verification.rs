@@ -0,0 +1,79 @@
+//! Verification gate for stories.
+//!
+//! A project can require checks to pass before a story's changes are kept,
+//! configured under `verification` in `.ideate/config.json`:
+//!
+//! ```json
+//! "verification": { "command": "npm run lint && npm test" }
+//! ```
+//!
+//! Once a story's agent succeeds, the command runs in the story's working
+//! directory: its worktree in parallel builds, so nothing is merged until it
+//! passes, or the project directory before the story is committed. A failing
+//! command fails the story attempt and its output is passed to the retry like
+//! an agent error. Runs are saved to the process logs and show up in process
+//! history as "verification" entries.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::hooks::{run_shell, ShellRun};
+use crate::models::{ProjectConfig, Story, VerificationConfig, VerificationRun};
+use crate::utils::get_ideate_dir;
+
+const DEFAULT_TIMEOUT_MINUTES: u32 = 15;
+
+/// Output lines kept with the result.
+const OUTPUT_TAIL_LINES: usize = 40;
+
+/// Reads the project's verification settings, fresh each time so edits apply
+/// mid-build. Returns `None` when no command is configured.
+pub fn load_config(project_path: &str) -> Option<VerificationConfig> {
+    fs::read_to_string(get_ideate_dir(project_path).join("config.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectConfig>(&content).ok())
+        .and_then(|config| config.verification)
+        .filter(|config| !config.command.trim().is_empty())
+}
+
+/// Runs the verification command for a story, blocking until it exits.
+///
+/// With `app_data_dir`, output is saved as a process log and the run is added
+/// to process history.
+pub fn run(
+    app_data_dir: Option<&Path>,
+    project_id: &str,
+    story: &Story,
+    config: &VerificationConfig,
+    working_directory: &str,
+) -> VerificationRun {
+    let timeout_minutes = config.timeout_minutes.unwrap_or(DEFAULT_TIMEOUT_MINUTES).max(1);
+    let command = config.command.trim();
+    let outcome = run_shell(
+        app_data_dir,
+        ShellRun {
+            command,
+            working_directory,
+            env: vec![
+                ("IDEATE_PROJECT_ID", project_id.to_string()),
+                ("IDEATE_STORY_ID", story.id.clone()),
+                ("IDEATE_STORY_TITLE", story.title.clone()),
+            ],
+            timeout: Duration::from_secs(u64::from(timeout_minutes) * 60),
+            project_id,
+            process_type: "verification",
+            label: format!("verify {}", story.id),
+        },
+    );
+
+    VerificationRun {
+        command: command.to_string(),
+        success: outcome.success,
+        exit_code: outcome.exit_code,
+        output_tail: outcome.output_tail(OUTPUT_TAIL_LINES),
+        reason: outcome.reason,
+        duration_ms: outcome.duration_ms,
+        process_id: outcome.process_id,
+    }
+}
@@ -0,0 +1,314 @@
+//! Outgoing webhooks for lifecycle events.
+//!
+//! Users register URLs with event filters; matching events are POSTed as JSON
+//! signed with HMAC-SHA256 (`X-Ideate-Signature: sha256=<hex>`) and retried
+//! with exponential backoff. Subscriptions live in `webhooks.json` in the app
+//! data directory; signing secrets are kept in the secrets store.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tauri::AppHandle;
+
+use crate::app_data::app_data_dir;
+use crate::secrets;
+
+/// Delivery attempts per event, including the first.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Events that can be subscribed to.
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    "project-created",
+    "build-started",
+    "build-finished",
+    "story-passed",
+    "story-failed",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    /// Event names to deliver. Empty means all events.
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Returned from registration; the secret is only shown once.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredWebhook {
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResult {
+    pub success: bool,
+    pub status: Option<u16>,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+fn secret_key(id: &str) -> String {
+    format!("webhook.{}", id)
+}
+
+fn get_webhooks_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_data_dir(app)?;
+
+    if !app_data_dir.exists() {
+        fs::create_dir_all(&app_data_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    Ok(app_data_dir.join("webhooks.json"))
+}
+
+fn read_webhooks(app: &AppHandle) -> Result<Vec<WebhookSubscription>, String> {
+    let path = get_webhooks_file_path(app)?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read webhooks.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse webhooks.json: {}", e))
+}
+
+fn write_webhooks(app: &AppHandle, webhooks: &[WebhookSubscription]) -> Result<(), String> {
+    let path = get_webhooks_file_path(app)?;
+
+    let json = serde_json::to_string_pretty(webhooks)
+        .map_err(|e| format!("Failed to serialize webhooks: {}", e))?;
+
+    fs::write(&path, json).map_err(|e| format!("Failed to write webhooks.json: {}", e))
+}
+
+fn validate_events(events: &[String]) -> Result<(), String> {
+    match events.iter().find(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+        Some(unknown) => Err(format!("Unknown webhook event: {}", unknown)),
+        None => Ok(()),
+    }
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// POSTs a signed payload, retrying network errors, 429s, and 5xx responses.
+async fn deliver(
+    client: &reqwest::Client,
+    url: &str,
+    secret: Option<&str>,
+    event: &str,
+    payload: &Value,
+) -> WebhookDeliveryResult {
+    let body = serde_json::to_vec(payload).unwrap_or_default();
+    let delivery_id = payload
+        .get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut last_status = None;
+    let mut last_error = None;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("X-Ideate-Event", event)
+            .header("X-Ideate-Delivery", &delivery_id)
+            .body(body.clone());
+        if let Some(secret) = secret {
+            request = request.header("X-Ideate-Signature", sign(secret, &body));
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                last_status = Some(status.as_u16());
+                if status.is_success() {
+                    return WebhookDeliveryResult {
+                        success: true,
+                        status: last_status,
+                        attempts: attempt,
+                        error: None,
+                    };
+                }
+                last_error = Some(format!("HTTP {}", status));
+                // Client errors other than rate limiting won't succeed on retry
+                if status.is_client_error() && status.as_u16() != 429 {
+                    return WebhookDeliveryResult {
+                        success: false,
+                        status: last_status,
+                        attempts: attempt,
+                        error: last_error,
+                    };
+                }
+            }
+            Err(e) => last_error = Some(e.to_string()),
+        }
+
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+    }
+
+    WebhookDeliveryResult {
+        success: false,
+        status: last_status,
+        attempts: MAX_ATTEMPTS,
+        error: last_error,
+    }
+}
+
+fn build_payload(event: &str, data: Value) -> Value {
+    serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "event": event,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+/// Delivers an event to all matching subscriptions in the background.
+pub fn dispatch(app: &AppHandle, event: &str, data: Value) {
+    let app = app.clone();
+    let event = event.to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let webhooks = match read_webhooks(&app) {
+            Ok(w) => w,
+            Err(e) => {
+                tracing::error!("Webhooks: {}", e);
+                return;
+            }
+        };
+
+        let client = reqwest::Client::new();
+        let payload = build_payload(&event, data);
+
+        for webhook in webhooks
+            .iter()
+            .filter(|w| w.enabled && (w.events.is_empty() || w.events.contains(&event)))
+        {
+            let secret = secrets::get_secret(&app, &secret_key(&webhook.id)).ok().flatten();
+            let result = deliver(&client, &webhook.url, secret.as_deref(), &event, &payload).await;
+            if !result.success {
+                tracing::warn!(
+                    "Webhooks: delivery of {} to {} failed after {} attempts: {}",
+                    event,
+                    webhook.url,
+                    result.attempts,
+                    result.error.unwrap_or_default()
+                );
+            }
+        }
+    });
+}
+
+/// Lists registered webhooks.
+#[tauri::command]
+pub fn list_webhooks(app: AppHandle) -> Result<Vec<WebhookSubscription>, String> {
+    read_webhooks(&app)
+}
+
+/// Registers a webhook and generates its signing secret.
+#[tauri::command]
+pub fn register_webhook(
+    app: AppHandle,
+    url: String,
+    events: Vec<String>,
+) -> Result<RegisteredWebhook, String> {
+    validate_events(&events)?;
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err("Webhook URL must start with http:// or https://".to_string());
+    }
+
+    let subscription = WebhookSubscription {
+        id: uuid::Uuid::new_v4().to_string(),
+        url,
+        events,
+        enabled: true,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let secret = uuid::Uuid::new_v4().simple().to_string();
+
+    secrets::set_secret(app.clone(), secret_key(&subscription.id), secret.clone())?;
+
+    let mut webhooks = read_webhooks(&app)?;
+    webhooks.push(subscription.clone());
+    write_webhooks(&app, &webhooks)?;
+
+    Ok(RegisteredWebhook {
+        subscription,
+        secret,
+    })
+}
+
+/// Updates a webhook's URL, event filter, or enabled flag.
+#[tauri::command]
+pub fn update_webhook(app: AppHandle, webhook: WebhookSubscription) -> Result<(), String> {
+    validate_events(&webhook.events)?;
+
+    let mut webhooks = read_webhooks(&app)?;
+    let existing = webhooks
+        .iter_mut()
+        .find(|w| w.id == webhook.id)
+        .ok_or_else(|| format!("Webhook not found: {}", webhook.id))?;
+    *existing = webhook;
+
+    write_webhooks(&app, &webhooks)
+}
+
+/// Deletes a webhook and its signing secret.
+#[tauri::command]
+pub fn delete_webhook(app: AppHandle, id: String) -> Result<(), String> {
+    let mut webhooks = read_webhooks(&app)?;
+    webhooks.retain(|w| w.id != id);
+    write_webhooks(&app, &webhooks)?;
+
+    secrets::delete_secret(app, secret_key(&id))
+}
+
+/// Sends a signed `ping` event to a single webhook and reports the outcome.
+#[tauri::command]
+pub async fn ping_webhook(app: AppHandle, id: String) -> Result<WebhookDeliveryResult, String> {
+    let webhook = read_webhooks(&app)?
+        .into_iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| format!("Webhook not found: {}", id))?;
+    let secret = secrets::get_secret(&app, &secret_key(&id))?;
+
+    let payload = build_payload("ping", serde_json::json!({ "webhookId": id }));
+    Ok(deliver(&reqwest::Client::new(), &webhook.url, secret.as_deref(), "ping", &payload).await)
+}
+
+/// Dispatches a lifecycle event raised by the frontend (e.g. a build finishing).
+#[tauri::command]
+pub fn dispatch_webhook_event(app: AppHandle, event: String, data: Value) -> Result<(), String> {
+    validate_events(std::slice::from_ref(&event))?;
+    dispatch(&app, &event, data);
+    Ok(())
+}
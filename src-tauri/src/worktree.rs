@@ -4,11 +4,18 @@
 //! Also provides snapshot/rollback functionality for undo on build failures.
 
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "gui")]
 use std::path::PathBuf;
-use std::process::Command;
+#[cfg(feature = "gui")]
 use tauri::AppHandle;
 
+use crate::git_binary;
+use crate::perf;
+
 /// Result of creating a story snapshot.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotResult {
@@ -17,6 +24,7 @@ pub struct SnapshotResult {
 }
 
 /// Result of preparing a worktree for a story.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorktreeResult {
@@ -25,11 +33,13 @@ pub struct WorktreeResult {
 }
 
 /// Get the worktrees directory for a project.
+#[cfg(feature = "gui")]
 fn get_worktrees_dir(project_path: &str) -> PathBuf {
     PathBuf::from(project_path).join(".ideate-worktrees")
 }
 
 /// Sanitize story ID for use as a branch name.
+#[cfg(feature = "gui")]
 fn sanitize_branch_name(story_id: &str) -> String {
     story_id
         .chars()
@@ -39,9 +49,10 @@ fn sanitize_branch_name(story_id: &str) -> String {
 }
 
 /// Get the current branch or HEAD ref.
-fn get_base_ref(project_path: &str) -> Result<String, String> {
+#[cfg(feature = "gui")]
+pub fn get_base_ref(project_path: &str) -> Result<String, String> {
     // First check if there are any commits
-    let rev_output = Command::new("git")
+    let rev_output = git_binary::command()
         .args(["rev-parse", "HEAD"])
         .current_dir(project_path)
         .output()
@@ -55,7 +66,7 @@ fn get_base_ref(project_path: &str) -> Result<String, String> {
         return Err(format!("Failed to get HEAD: {}", stderr.trim()));
     }
 
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .current_dir(project_path)
         .output()
@@ -74,16 +85,154 @@ fn get_base_ref(project_path: &str) -> Result<String, String> {
     }
 }
 
+/// Worktree path and branch name a story builds in.
+#[cfg(feature = "gui")]
+pub fn story_worktree_names(project_path: &str, story_id: &str) -> (PathBuf, String) {
+    let name = sanitize_branch_name(story_id);
+    (get_worktrees_dir(project_path).join(&name), format!("story/{}", name))
+}
+
+/// Where a story can be previewed from.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct PreviewWorktree {
+    pub path: PathBuf,
+    pub branch_name: String,
+    /// Added just for the preview, so it should be removed afterwards.
+    pub created: bool,
+}
+
+/// Finds a checkout of a story to run a preview in: its build worktree if
+/// it still has one, otherwise a detached worktree of its branch.
+#[cfg(feature = "gui")]
+pub fn preview_worktree(project_path: &str, story_id: &str) -> Result<PreviewWorktree, String> {
+    let (worktree_path, branch_name) = story_worktree_names(project_path, story_id);
+    if worktree_path.exists() {
+        return Ok(PreviewWorktree {
+            path: worktree_path,
+            branch_name,
+            created: false,
+        });
+    }
+
+    let branch_exists = git_binary::command()
+        .args(["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch_name)])
+        .current_dir(project_path)
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !branch_exists {
+        return Err(format!("Story {} has no worktree or branch to preview", story_id));
+    }
+
+    let path = get_worktrees_dir(project_path).join(format!("preview-{}", sanitize_branch_name(story_id)));
+    if path.exists() {
+        remove_preview_worktree(project_path, &path);
+    }
+    // Detached, so the branch stays free to be checked out or merged
+    let output = git_binary::command()
+        .args(["worktree", "add", "--detach"])
+        .arg(&path)
+        .arg(&branch_name)
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to create preview worktree: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to create preview worktree: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // Share the project's installed dependencies instead of reinstalling
+    #[cfg(unix)]
+    {
+        let node_modules = PathBuf::from(project_path).join("node_modules");
+        if node_modules.is_dir() && !path.join("node_modules").exists() {
+            if let Err(e) = std::os::unix::fs::symlink(&node_modules, path.join("node_modules")) {
+                tracing::warn!("Failed to link node_modules into preview: {}", e);
+            }
+        }
+    }
+
+    Ok(PreviewWorktree {
+        path,
+        branch_name,
+        created: true,
+    })
+}
+
+/// Removes a worktree `preview_worktree` created.
+#[cfg(feature = "gui")]
+pub fn remove_preview_worktree(project_path: &str, path: &std::path::Path) {
+    git_binary::command()
+        .args(["worktree", "remove", "--force"])
+        .arg(path)
+        .current_dir(project_path)
+        .output()
+        .ok();
+    let _ = std::fs::remove_dir_all(path);
+}
+
+/// Size of a commit's changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStat {
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+/// Diff stat of a story's commit, or `None` if `commit` is not that story's
+/// (e.g. `git_commit_story` found nothing to commit and returned HEAD).
+pub fn story_commit_stat(project_path: &str, commit: &str, story_id: &str) -> Option<DiffStat> {
+    let _timer = perf::start("worktree.commit-stat").subject(project_path);
+    let output = git_binary::command()
+        .args(["show", "--shortstat", "--format=%s", commit])
+        .current_dir(project_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    if !lines.next()?.starts_with(&format!("[Story {}]", story_id)) {
+        return None;
+    }
+
+    // e.g. " 3 files changed, 42 insertions(+), 7 deletions(-)"
+    let mut stat = DiffStat::default();
+    for part in lines.find(|l| l.contains("changed"))?.split(',') {
+        let part = part.trim();
+        let count = part
+            .split_whitespace()
+            .next()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(0);
+        if part.contains("changed") {
+            stat.files_changed = count;
+        } else if part.contains("insertion") {
+            stat.insertions = count;
+        } else if part.contains("deletion") {
+            stat.deletions = count;
+        }
+    }
+    Some(stat)
+}
+
 /// Prepare a git worktree for a story.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn prepare_story_worktree(
     _app: AppHandle,
     project_path: String,
     story_id: String,
 ) -> Result<WorktreeResult, String> {
+    git_binary::require()?;
+    let _timer = perf::start("worktree.prepare").subject(project_path.as_str());
     let worktrees_dir = get_worktrees_dir(&project_path);
-    let branch_name = format!("story/{}", sanitize_branch_name(&story_id));
-    let worktree_path = worktrees_dir.join(&sanitize_branch_name(&story_id));
+    let (worktree_path, branch_name) = story_worktree_names(&project_path, &story_id);
 
     // Create worktrees directory if needed
     if !worktrees_dir.exists() {
@@ -93,7 +242,7 @@ pub async fn prepare_story_worktree(
 
     // Remove existing worktree if it exists
     if worktree_path.exists() {
-        let _ = Command::new("git")
+        let _ = git_binary::command()
             .args(["worktree", "remove", "--force", worktree_path.to_str().unwrap()])
             .current_dir(&project_path)
             .output();
@@ -103,7 +252,7 @@ pub async fn prepare_story_worktree(
     }
 
     // Delete existing branch if it exists
-    let _ = Command::new("git")
+    let _ = git_binary::command()
         .args(["branch", "-D", &branch_name])
         .current_dir(&project_path)
         .output();
@@ -112,7 +261,7 @@ pub async fn prepare_story_worktree(
     let base_ref = get_base_ref(&project_path)?;
 
     // Create worktree with a new branch
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args([
             "worktree",
             "add",
@@ -138,6 +287,7 @@ pub async fn prepare_story_worktree(
 
 /// Finalize a story worktree after build completes.
 /// If successful, commits changes and optionally merges back.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn finalize_story_worktree(
     _app: AppHandle,
@@ -147,11 +297,12 @@ pub async fn finalize_story_worktree(
     branch_name: String,
     success: bool,
 ) -> Result<(), String> {
+    let _timer = perf::start("worktree.finalize").subject(project_path.as_str());
     let worktree = PathBuf::from(&worktree_path);
 
     if success && worktree.exists() {
         // Check if there are changes to commit
-        let status_output = Command::new("git")
+        let status_output = git_binary::command()
             .args(["status", "--porcelain"])
             .current_dir(&worktree_path)
             .output()
@@ -161,7 +312,7 @@ pub async fn finalize_story_worktree(
 
         if has_changes {
             // Stage all changes
-            Command::new("git")
+            git_binary::command()
                 .args(["add", "-A"])
                 .current_dir(&worktree_path)
                 .output()
@@ -169,7 +320,7 @@ pub async fn finalize_story_worktree(
 
             // Commit changes
             let commit_message = format!("Story {}: Implementation complete", story_id);
-            Command::new("git")
+            git_binary::command()
                 .args(["commit", "-m", &commit_message])
                 .current_dir(&worktree_path)
                 .output()
@@ -179,14 +330,14 @@ pub async fn finalize_story_worktree(
             let base_ref = get_base_ref(&project_path)?;
             
             // First, ensure we're on the right branch in main repo
-            Command::new("git")
+            git_binary::command()
                 .args(["checkout", &base_ref])
                 .current_dir(&project_path)
                 .output()
                 .ok();
 
             // Merge the story branch
-            let merge_output = Command::new("git")
+            let merge_output = git_binary::command()
                 .args(["merge", &branch_name, "--no-edit"])
                 .current_dir(&project_path)
                 .output()
@@ -195,7 +346,7 @@ pub async fn finalize_story_worktree(
             if !merge_output.status.success() {
                 let stderr = String::from_utf8_lossy(&merge_output.stderr);
                 // If merge fails, abort it
-                Command::new("git")
+                git_binary::command()
                     .args(["merge", "--abort"])
                     .current_dir(&project_path)
                     .output()
@@ -207,7 +358,7 @@ pub async fn finalize_story_worktree(
 
     // Remove the worktree
     if worktree.exists() {
-        Command::new("git")
+        git_binary::command()
             .args(["worktree", "remove", "--force", &worktree_path])
             .current_dir(&project_path)
             .output()
@@ -219,14 +370,14 @@ pub async fn finalize_story_worktree(
 
     // Delete the branch if it was merged or failed
     if success {
-        Command::new("git")
+        git_binary::command()
             .args(["branch", "-d", &branch_name])
             .current_dir(&project_path)
             .output()
             .ok();
     } else {
         // Force delete on failure
-        Command::new("git")
+        git_binary::command()
             .args(["branch", "-D", &branch_name])
             .current_dir(&project_path)
             .output()
@@ -236,7 +387,82 @@ pub async fn finalize_story_worktree(
     Ok(())
 }
 
+/// Commits everything in a worktree. Returns whether there was anything to
+/// commit.
+#[cfg(feature = "gui")]
+pub(crate) fn commit_all(worktree_path: &str, message: &str) -> Result<bool, String> {
+    let status = git_binary::command()
+        .args(["status", "--porcelain"])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to check git status: {}", e))?;
+    if String::from_utf8_lossy(&status.stdout).trim().is_empty() {
+        return Ok(false);
+    }
+
+    git_binary::command()
+        .args(["add", "-A"])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to stage changes: {}", e))?;
+    let output = git_binary::command()
+        .args(["commit", "-m", message])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to commit: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(true)
+}
+
+/// Merges `branch` into whatever is checked out at `path`, aborting the
+/// merge if it conflicts.
+#[cfg(feature = "gui")]
+pub(crate) fn merge_branch(path: &str, branch: &str) -> Result<(), String> {
+    let output = git_binary::command()
+        .args(["merge", branch, "--no-edit"])
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("Failed to merge: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        git_binary::command()
+            .args(["merge", "--abort"])
+            .current_dir(path)
+            .output()
+            .ok();
+        return Err(format!("Merge conflict, changes kept in branch {}: {}", branch, stderr));
+    }
+    Ok(())
+}
+
+/// Diff of what the branch checked out at `worktree_path` changed since it
+/// forked from `base_ref`, uncommitted changes included.
+#[cfg(feature = "gui")]
+pub(crate) fn branch_diff(worktree_path: &str, base_ref: &str) -> Result<String, String> {
+    let merge_base = git_binary::command()
+        .args(["merge-base", base_ref, "HEAD"])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to find merge base: {}", e))?;
+    if !merge_base.status.success() {
+        return Err(format!(
+            "Failed to find merge base: {}",
+            String::from_utf8_lossy(&merge_base.stderr).trim()
+        ));
+    }
+    let merge_base = String::from_utf8_lossy(&merge_base.stdout).trim().to_string();
+    let output = git_binary::command()
+        .args(["diff", &merge_base])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to diff: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 /// Information about a story branch.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StoryBranchInfo {
@@ -247,12 +473,13 @@ pub struct StoryBranchInfo {
 }
 
 /// List all story branches for a project.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn list_story_branches(
     _app: AppHandle,
     project_path: String,
 ) -> Result<Vec<StoryBranchInfo>, String> {
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["branch", "--list", "story/*"])
         .current_dir(&project_path)
         .output()
@@ -266,7 +493,7 @@ pub async fn list_story_branches(
     let mut branches = Vec::new();
 
     // Get current branch
-    let current_output = Command::new("git")
+    let current_output = git_binary::command()
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .current_dir(&project_path)
         .output()
@@ -292,7 +519,7 @@ pub async fn list_story_branches(
         let is_current = branch == current_branch;
 
         // Check if branch is merged into main
-        let merged_output = Command::new("git")
+        let merged_output = git_binary::command()
             .args(["branch", "--merged", &main_branch])
             .current_dir(&project_path)
             .output()
@@ -311,7 +538,7 @@ pub async fn list_story_branches(
             "merged".to_string()
         } else {
             // Check if there would be conflicts
-            let merge_base = Command::new("git")
+            let merge_base = git_binary::command()
                 .args(["merge-base", &main_branch, &branch])
                 .current_dir(&project_path)
                 .output()
@@ -320,7 +547,7 @@ pub async fn list_story_branches(
             if let Some(base_output) = merge_base {
                 if base_output.status.success() {
                     let base = String::from_utf8_lossy(&base_output.stdout).trim().to_string();
-                    let merge_tree = Command::new("git")
+                    let merge_tree = git_binary::command()
                         .args(["merge-tree", &base, &main_branch, &branch])
                         .current_dir(&project_path)
                         .output()
@@ -356,8 +583,9 @@ pub async fn list_story_branches(
 }
 
 /// Get the main branch name (main or master).
+#[cfg(feature = "gui")]
 fn get_main_branch(project_path: &str) -> String {
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["rev-parse", "--verify", "main"])
         .current_dir(project_path)
         .output()
@@ -373,6 +601,7 @@ fn get_main_branch(project_path: &str) -> String {
 }
 
 /// Delete a story branch.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn delete_story_branch(
     _app: AppHandle,
@@ -381,7 +610,7 @@ pub async fn delete_story_branch(
     force: bool,
 ) -> Result<(), String> {
     // First, check if there's a worktree using this branch and remove it
-    let worktree_list = Command::new("git")
+    let worktree_list = git_binary::command()
         .args(["worktree", "list", "--porcelain"])
         .current_dir(&project_path)
         .output()
@@ -400,7 +629,7 @@ pub async fn delete_story_branch(
                 if branch.ends_with(&branch_name) || branch == format!("refs/heads/{}", branch_name) {
                     if let Some(ref wt_path) = current_worktree {
                         // Remove the worktree first
-                        if let Ok(output) = Command::new("git")
+                        if let Ok(output) = git_binary::command()
                             .args(["worktree", "remove", "--force", wt_path])
                             .current_dir(&project_path)
                             .output()
@@ -421,7 +650,7 @@ pub async fn delete_story_branch(
 
     // Now delete the branch
     let flag = if force { "-D" } else { "-d" };
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["branch", flag, &branch_name])
         .current_dir(&project_path)
         .output()
@@ -436,6 +665,7 @@ pub async fn delete_story_branch(
 }
 
 /// Checkout a story branch.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn checkout_story_branch(
     _app: AppHandle,
@@ -443,13 +673,13 @@ pub async fn checkout_story_branch(
     branch_name: String,
 ) -> Result<(), String> {
     // Abort any pending merge first
-    let _ = Command::new("git")
+    let _ = git_binary::command()
         .args(["merge", "--abort"])
         .current_dir(&project_path)
         .output();
 
     // Check for uncommitted changes and stash them to preserve user work
-    let status_output = Command::new("git")
+    let status_output = git_binary::command()
         .args(["status", "--porcelain"])
         .current_dir(&project_path)
         .output()
@@ -457,7 +687,7 @@ pub async fn checkout_story_branch(
 
     if !status_output.stdout.is_empty() {
         // Stash changes instead of discarding them
-        let _ = Command::new("git")
+        let _ = git_binary::command()
             .args([
                 "stash",
                 "push",
@@ -469,7 +699,7 @@ pub async fn checkout_story_branch(
             .output();
     }
 
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["checkout", &branch_name])
         .current_dir(&project_path)
         .output()
@@ -484,6 +714,7 @@ pub async fn checkout_story_branch(
 }
 
 /// Force merge a story branch into the current branch.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn force_merge_story_branch(
     _app: AppHandle,
@@ -491,7 +722,7 @@ pub async fn force_merge_story_branch(
     branch_name: String,
 ) -> Result<(), String> {
     // First try normal merge
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["merge", &branch_name, "--no-edit"])
         .current_dir(&project_path)
         .output()
@@ -502,12 +733,12 @@ pub async fn force_merge_story_branch(
     }
 
     // If conflicts, force accept theirs
-    let _ = Command::new("git")
+    let _ = git_binary::command()
         .args(["merge", "--abort"])
         .current_dir(&project_path)
         .output();
 
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["merge", &branch_name, "-X", "theirs", "--no-edit"])
         .current_dir(&project_path)
         .output()
@@ -522,6 +753,7 @@ pub async fn force_merge_story_branch(
 }
 
 /// Information about a file change in a diff.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileDiff {
@@ -533,6 +765,7 @@ pub struct FileDiff {
 }
 
 /// Result of getting diff for a story branch.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StoryDiffResult {
@@ -544,6 +777,7 @@ pub struct StoryDiffResult {
 }
 
 /// Get the diff for a story branch compared to main.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn get_story_diff(
     _app: AppHandle,
@@ -551,6 +785,7 @@ pub async fn get_story_diff(
     story_id: String,
     branch_name: Option<String>,
 ) -> Result<StoryDiffResult, String> {
+    let mut timer = perf::start("worktree.diff").subject(project_path.as_str());
     // Use provided branch name, or construct from story ID
     let branch_name = branch_name.unwrap_or_else(|| {
         format!("story/{}", sanitize_branch_name(&story_id))
@@ -558,7 +793,7 @@ pub async fn get_story_diff(
     let main_branch = get_main_branch(&project_path);
 
     // Verify the branch exists first
-    let branch_check = Command::new("git")
+    let branch_check = git_binary::command()
         .args(["rev-parse", "--verify", &branch_name])
         .current_dir(&project_path)
         .output()
@@ -572,7 +807,7 @@ pub async fn get_story_diff(
     }
 
     // Get the merge base between main and the story branch
-    let merge_base_output = Command::new("git")
+    let merge_base_output = git_binary::command()
         .args(["merge-base", &main_branch, &branch_name])
         .current_dir(&project_path)
         .output()
@@ -590,7 +825,7 @@ pub async fn get_story_diff(
         .to_string();
 
     // Get list of changed files with stats
-    let diff_stat_output = Command::new("git")
+    let diff_stat_output = git_binary::command()
         .args(["diff", "--numstat", &merge_base, &branch_name])
         .current_dir(&project_path)
         .output()
@@ -601,7 +836,7 @@ pub async fn get_story_diff(
     }
 
     // Get the diff name-status for file status (added, modified, deleted, renamed)
-    let name_status_output = Command::new("git")
+    let name_status_output = git_binary::command()
         .args(["diff", "--name-status", &merge_base, &branch_name])
         .current_dir(&project_path)
         .output()
@@ -645,7 +880,7 @@ pub async fn get_story_diff(
             let file_path = parts[2].to_string();
 
             // Get the diff content for this specific file
-            let file_diff_output = Command::new("git")
+            let file_diff_output = git_binary::command()
                 .args(["diff", &merge_base, &branch_name, "--", &file_path])
                 .current_dir(&project_path)
                 .output()
@@ -673,6 +908,7 @@ pub async fn get_story_diff(
         }
     }
 
+    timer.set_files(files.len());
     Ok(StoryDiffResult {
         story_id,
         branch_name,
@@ -684,14 +920,17 @@ pub async fn get_story_diff(
 
 /// Create a snapshot of the current state before running a story.
 /// Uses git stash if there are uncommitted changes, otherwise creates a lightweight marker.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn create_story_snapshot(
     _app: AppHandle,
     project_path: String,
     story_id: String,
 ) -> Result<SnapshotResult, String> {
+    git_binary::require()?;
+    let _timer = perf::start("worktree.snapshot").subject(project_path.as_str());
     // Check if there are uncommitted changes
-    let status_output = Command::new("git")
+    let status_output = git_binary::command()
         .args(["status", "--porcelain"])
         .current_dir(&project_path)
         .output()
@@ -702,7 +941,7 @@ pub async fn create_story_snapshot(
     if has_changes {
         // Create a stash with a unique message
         let stash_message = format!("ideate-snapshot-{}", story_id);
-        let output = Command::new("git")
+        let output = git_binary::command()
             .args(["stash", "push", "-m", &stash_message, "--include-untracked"])
             .current_dir(&project_path)
             .output()
@@ -714,7 +953,7 @@ pub async fn create_story_snapshot(
         }
 
         // Apply the stash immediately to restore working state (but keep the stash)
-        Command::new("git")
+        git_binary::command()
             .args(["stash", "apply"])
             .current_dir(&project_path)
             .output()
@@ -726,7 +965,7 @@ pub async fn create_story_snapshot(
         })
     } else {
         // No uncommitted changes - record current HEAD as the snapshot
-        let output = Command::new("git")
+        let output = git_binary::command()
             .args(["rev-parse", "HEAD"])
             .current_dir(&project_path)
             .output()
@@ -745,6 +984,7 @@ pub async fn create_story_snapshot(
 }
 
 /// Rollback to a story snapshot, discarding all changes made since.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn rollback_story_changes(
     _app: AppHandle,
@@ -752,23 +992,24 @@ pub async fn rollback_story_changes(
     snapshot_ref: String,
     snapshot_type: String,
 ) -> Result<(), String> {
+    let _timer = perf::start("worktree.rollback").subject(project_path.as_str());
     if snapshot_type == "stash" {
         // First, discard all current changes
-        Command::new("git")
+        git_binary::command()
             .args(["reset", "--hard", "HEAD"])
             .current_dir(&project_path)
             .output()
             .map_err(|e| format!("Failed to reset: {}", e))?;
 
         // Clean untracked files
-        Command::new("git")
+        git_binary::command()
             .args(["clean", "-fd"])
             .current_dir(&project_path)
             .output()
             .ok();
 
         // Find and apply the stash
-        let list_output = Command::new("git")
+        let list_output = git_binary::command()
             .args(["stash", "list"])
             .current_dir(&project_path)
             .output()
@@ -788,7 +1029,7 @@ pub async fn rollback_story_changes(
             let stash_ref = format!("stash@{{{}}}", idx);
             
             // Pop the stash to restore original state
-            let output = Command::new("git")
+            let output = git_binary::command()
                 .args(["stash", "pop", &stash_ref])
                 .current_dir(&project_path)
                 .output()
@@ -801,7 +1042,7 @@ pub async fn rollback_story_changes(
         }
     } else {
         // Commit-based snapshot - reset to that commit
-        let output = Command::new("git")
+        let output = git_binary::command()
             .args(["reset", "--hard", &snapshot_ref])
             .current_dir(&project_path)
             .output()
@@ -813,7 +1054,7 @@ pub async fn rollback_story_changes(
         }
 
         // Clean untracked files
-        Command::new("git")
+        git_binary::command()
             .args(["clean", "-fd"])
             .current_dir(&project_path)
             .output()
@@ -824,6 +1065,7 @@ pub async fn rollback_story_changes(
 }
 
 /// Discard a story snapshot after successful completion.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn discard_story_snapshot(
     _app: AppHandle,
@@ -833,7 +1075,7 @@ pub async fn discard_story_snapshot(
 ) -> Result<(), String> {
     if snapshot_type == "stash" {
         // Find and drop the stash
-        let list_output = Command::new("git")
+        let list_output = git_binary::command()
             .args(["stash", "list"])
             .current_dir(&project_path)
             .output()
@@ -844,7 +1086,7 @@ pub async fn discard_story_snapshot(
         for (idx, line) in stash_list.lines().enumerate() {
             if line.contains(&snapshot_ref) {
                 let stash_ref = format!("stash@{{{}}}", idx);
-                Command::new("git")
+                git_binary::command()
                     .args(["stash", "drop", &stash_ref])
                     .current_dir(&project_path)
                     .output()
@@ -862,14 +1104,20 @@ pub async fn discard_story_snapshot(
 // ============================================================================
 
 /// Check if git is initialized in the project directory.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn check_git_initialized(
     _app: AppHandle,
     project_path: String,
 ) -> Result<bool, String> {
-    let output = Command::new("git")
+    is_git_repo(&project_path)
+}
+
+pub(crate) fn is_git_repo(project_path: &str) -> Result<bool, String> {
+    git_binary::require()?;
+    let output = git_binary::command()
         .args(["rev-parse", "--git-dir"])
-        .current_dir(&project_path)
+        .current_dir(project_path)
         .output()
         .map_err(|e| format!("Failed to check git: {}", e))?;
 
@@ -877,12 +1125,14 @@ pub async fn check_git_initialized(
 }
 
 /// Initialize git repository if not already initialized.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn init_git_repo(
     _app: AppHandle,
     project_path: String,
 ) -> Result<(), String> {
-    let output = Command::new("git")
+    git_binary::require()?;
+    let output = git_binary::command()
         .args(["init"])
         .current_dir(&project_path)
         .output()
@@ -897,6 +1147,7 @@ pub async fn init_git_repo(
 }
 
 /// Commit all changes after a successful story completion.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn git_commit_story(
     _app: AppHandle,
@@ -904,10 +1155,16 @@ pub async fn git_commit_story(
     story_id: String,
     story_title: String,
 ) -> Result<String, String> {
+    commit_story(&project_path, &story_id, &story_title)
+}
+
+/// Commits all changes for a story and returns the new HEAD.
+pub(crate) fn commit_story(project_path: &str, story_id: &str, story_title: &str) -> Result<String, String> {
+    git_binary::require()?;
     // Stage all changes
-    let add_output = Command::new("git")
+    let add_output = git_binary::command()
         .args(["add", "-A"])
-        .current_dir(&project_path)
+        .current_dir(project_path)
         .output()
         .map_err(|e| format!("Failed to stage changes: {}", e))?;
 
@@ -917,9 +1174,9 @@ pub async fn git_commit_story(
     }
 
     // Check if there are changes to commit
-    let status_output = Command::new("git")
+    let status_output = git_binary::command()
         .args(["status", "--porcelain"])
-        .current_dir(&project_path)
+        .current_dir(project_path)
         .output()
         .map_err(|e| format!("Failed to check status: {}", e))?;
 
@@ -927,9 +1184,9 @@ pub async fn git_commit_story(
     
     if !has_changes {
         // No changes to commit, return current HEAD
-        let head_output = Command::new("git")
+        let head_output = git_binary::command()
             .args(["rev-parse", "HEAD"])
-            .current_dir(&project_path)
+            .current_dir(project_path)
             .output()
             .map_err(|e| format!("Failed to get HEAD: {}", e))?;
         
@@ -938,9 +1195,9 @@ pub async fn git_commit_story(
 
     // Commit with story info in message
     let commit_message = format!("[Story {}] {}", story_id, story_title);
-    let commit_output = Command::new("git")
+    let commit_output = git_binary::command()
         .args(["commit", "-m", &commit_message])
-        .current_dir(&project_path)
+        .current_dir(project_path)
         .output()
         .map_err(|e| format!("Failed to commit: {}", e))?;
 
@@ -948,9 +1205,9 @@ pub async fn git_commit_story(
         let stderr = String::from_utf8_lossy(&commit_output.stderr);
         // Check if it's just "nothing to commit"
         if stderr.contains("nothing to commit") {
-            let head_output = Command::new("git")
+            let head_output = git_binary::command()
                 .args(["rev-parse", "HEAD"])
-                .current_dir(&project_path)
+                .current_dir(project_path)
                 .output()
                 .map_err(|e| format!("Failed to get HEAD: {}", e))?;
             return Ok(String::from_utf8_lossy(&head_output.stdout).trim().to_string());
@@ -959,9 +1216,9 @@ pub async fn git_commit_story(
     }
 
     // Return the new commit hash
-    let head_output = Command::new("git")
+    let head_output = git_binary::command()
         .args(["rev-parse", "HEAD"])
-        .current_dir(&project_path)
+        .current_dir(project_path)
         .output()
         .map_err(|e| format!("Failed to get HEAD: {}", e))?;
 
@@ -969,13 +1226,14 @@ pub async fn git_commit_story(
 }
 
 /// Rollback the last commit (used when a story fails after a previous story committed).
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn git_rollback_last_commit(
     _app: AppHandle,
     project_path: String,
 ) -> Result<(), String> {
     // Reset to the previous commit, discarding all changes
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["reset", "--hard", "HEAD~1"])
         .current_dir(&project_path)
         .output()
@@ -987,7 +1245,7 @@ pub async fn git_rollback_last_commit(
     }
 
     // Clean untracked files
-    Command::new("git")
+    git_binary::command()
         .args(["clean", "-fd"])
         .current_dir(&project_path)
         .output()
@@ -997,13 +1255,14 @@ pub async fn git_rollback_last_commit(
 }
 
 /// Rollback all uncommitted changes (discard working directory changes).
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn git_discard_changes(
     _app: AppHandle,
     project_path: String,
 ) -> Result<(), String> {
     // Reset working directory to HEAD
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["reset", "--hard", "HEAD"])
         .current_dir(&project_path)
         .output()
@@ -1015,7 +1274,7 @@ pub async fn git_discard_changes(
     }
 
     // Clean untracked files
-    Command::new("git")
+    git_binary::command()
         .args(["clean", "-fd"])
         .current_dir(&project_path)
         .output()
@@ -1025,11 +1284,13 @@ pub async fn git_discard_changes(
 }
 
 /// Clean up all story worktrees for a project.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn cleanup_all_story_worktrees(
     _app: AppHandle,
     project_path: String,
 ) -> Result<(), String> {
+    let _timer = perf::start("worktree.cleanup").subject(project_path.as_str());
     let worktrees_dir = get_worktrees_dir(&project_path);
 
     if !worktrees_dir.exists() {
@@ -1037,7 +1298,7 @@ pub async fn cleanup_all_story_worktrees(
     }
 
     // List all worktrees
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["worktree", "list", "--porcelain"])
         .current_dir(&project_path)
         .output()
@@ -1050,7 +1311,7 @@ pub async fn cleanup_all_story_worktrees(
         if line.starts_with("worktree ") {
             let path = &line[9..];
             if path.contains(".ideate-worktrees") {
-                Command::new("git")
+                git_binary::command()
                     .args(["worktree", "remove", "--force", path])
                     .current_dir(&project_path)
                     .output()
@@ -1063,7 +1324,7 @@ pub async fn cleanup_all_story_worktrees(
     let _ = std::fs::remove_dir_all(&worktrees_dir);
 
     // Clean up story branches
-    let branch_output = Command::new("git")
+    let branch_output = git_binary::command()
         .args(["branch", "--list", "story/*"])
         .current_dir(&project_path)
         .output()
@@ -1074,7 +1335,7 @@ pub async fn cleanup_all_story_worktrees(
         for branch in branches.lines() {
             let branch = branch.trim().trim_start_matches("* ");
             if !branch.is_empty() {
-                Command::new("git")
+                git_binary::command()
                     .args(["branch", "-D", branch])
                     .current_dir(&project_path)
                     .output()
@@ -1086,17 +1347,278 @@ pub async fn cleanup_all_story_worktrees(
     Ok(())
 }
 
+/// Disk usage of one directory under `.ideate-worktrees`.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeUsage {
+    pub path: String,
+    /// `None` when the directory is no longer a registered worktree.
+    pub branch_name: Option<String>,
+    pub size_bytes: u64,
+    /// Last modification of the worktree directory.
+    pub modified_at: String,
+    pub age_days: u32,
+    pub has_uncommitted_changes: bool,
+    pub is_merged: bool,
+}
+
+/// Per-worktree disk usage of a project, largest first.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeUsageReport {
+    pub total_bytes: u64,
+    pub worktrees: Vec<WorktreeUsage>,
+}
+
+/// A worktree `prune_worktrees` left in place.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedWorktree {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Result of pruning worktrees.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneWorktreesResult {
+    pub removed: Vec<String>,
+    pub skipped: Vec<SkippedWorktree>,
+    pub freed_bytes: u64,
+}
+
+/// Total size of the files under `path`, without following symlinks.
+#[cfg(feature = "gui")]
+fn dir_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(ft) if ft.is_dir() => dir_size(&entry.path()),
+            Ok(ft) if ft.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Registered worktrees under `.ideate-worktrees`, mapped to their branch.
+#[cfg(feature = "gui")]
+fn registered_worktrees(project_path: &str) -> std::collections::HashMap<PathBuf, Option<String>> {
+    let mut worktrees = std::collections::HashMap::new();
+    let Ok(output) = git_binary::command()
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(project_path)
+        .output()
+    else {
+        return worktrees;
+    };
+
+    let mut current: Option<PathBuf> = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            current = Some(PathBuf::from(path));
+            if path.contains(".ideate-worktrees") {
+                worktrees.insert(PathBuf::from(path), None);
+            }
+        } else if let Some(branch) = line.strip_prefix("branch ") {
+            if let Some(entry) = current.as_ref().and_then(|p| worktrees.get_mut(p)) {
+                *entry = Some(branch.trim_start_matches("refs/heads/").to_string());
+            }
+        }
+    }
+    worktrees
+}
+
+/// Whether a worktree has modified, staged, or untracked files.
+#[cfg(feature = "gui")]
+fn has_uncommitted_changes(worktree_path: &std::path::Path) -> bool {
+    git_binary::command()
+        .args(["status", "--porcelain"])
+        .current_dir(worktree_path)
+        .output()
+        .map(|o| !o.status.success() || !o.stdout.is_empty())
+        // If git can't tell us, assume there is work to lose
+        .unwrap_or(true)
+}
+
+#[cfg(feature = "gui")]
+fn is_branch_merged(project_path: &str, branch: &str, main_branch: &str) -> bool {
+    git_binary::command()
+        .args(["merge-base", "--is-ancestor", branch, main_branch])
+        .current_dir(project_path)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "gui")]
+fn collect_worktree_usage(project_path: &str) -> Vec<WorktreeUsage> {
+    let mut timer = perf::start("worktree.usage").subject(project_path);
+    let worktrees_dir = get_worktrees_dir(project_path);
+    let Ok(entries) = std::fs::read_dir(&worktrees_dir) else {
+        return Vec::new();
+    };
+    let registered = registered_worktrees(project_path);
+    let main_branch = get_main_branch(project_path);
+    let now = std::time::SystemTime::now();
+
+    let mut usage: Vec<WorktreeUsage> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+        .map(|entry| {
+            let path = entry.path();
+            // git may report the canonical path, e.g. through /private on macOS
+            let branch_name = registered
+                .get(&path)
+                .or_else(|| path.canonicalize().ok().and_then(|p| registered.get(&p)))
+                .cloned()
+                .flatten();
+            let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(now);
+            let age = now.duration_since(modified).unwrap_or_default();
+            WorktreeUsage {
+                path: path.to_string_lossy().to_string(),
+                size_bytes: dir_size(&path),
+                modified_at: chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339(),
+                age_days: (age.as_secs() / 86_400) as u32,
+                has_uncommitted_changes: branch_name.is_some() && has_uncommitted_changes(&path),
+                is_merged: branch_name
+                    .as_deref()
+                    .map(|b| is_branch_merged(project_path, b, &main_branch))
+                    .unwrap_or(false),
+                branch_name,
+            }
+        })
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.size_bytes));
+    timer.set_files(usage.len());
+    usage
+}
+
+/// Report the disk usage of a project's story worktrees.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn get_worktree_usage(project_path: String) -> Result<WorktreeUsageReport, String> {
+    let worktrees = tokio::task::spawn_blocking(move || collect_worktree_usage(&project_path))
+        .await
+        .map_err(|e| format!("Failed to measure worktrees: {}", e))?;
+    Ok(WorktreeUsageReport {
+        total_bytes: worktrees.iter().map(|w| w.size_bytes).sum(),
+        worktrees,
+    })
+}
+
+/// Remove story worktrees untouched for at least `older_than` days.
+///
+/// Worktrees with uncommitted changes are always kept, as are unmerged ones
+/// when `keep_unmerged` is set. Only the worktree is removed; its branch and
+/// commits stay, so an unmerged story can still be checked out or merged.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn prune_worktrees(
+    project_path: String,
+    older_than: u32,
+    keep_unmerged: bool,
+) -> Result<PruneWorktreesResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut result = PruneWorktreesResult {
+            removed: Vec::new(),
+            skipped: Vec::new(),
+            freed_bytes: 0,
+        };
+
+        for worktree in collect_worktree_usage(&project_path) {
+            let reason = if worktree.age_days < older_than {
+                Some(format!("modified {} day(s) ago", worktree.age_days))
+            } else if worktree.has_uncommitted_changes {
+                Some("has uncommitted changes".to_string())
+            } else if keep_unmerged && worktree.branch_name.is_some() && !worktree.is_merged {
+                Some("branch is not merged".to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                result.skipped.push(SkippedWorktree { path: worktree.path, reason });
+                continue;
+            }
+
+            let removed = if worktree.branch_name.is_some() {
+                // No --force: git refuses if anything changed since the check above
+                git_binary::command()
+                    .args(["worktree", "remove", &worktree.path])
+                    .current_dir(&project_path)
+                    .output()
+                    .map_err(|e| format!("Failed to remove worktree: {}", e))
+                    .and_then(|o| {
+                        if o.status.success() {
+                            Ok(())
+                        } else {
+                            Err(String::from_utf8_lossy(&o.stderr).trim().to_string())
+                        }
+                    })
+            } else {
+                // Leftover directory git no longer tracks
+                std::fs::remove_dir_all(&worktree.path).map_err(|e| e.to_string())
+            };
+
+            match removed {
+                Ok(()) => {
+                    result.freed_bytes += worktree.size_bytes;
+                    result.removed.push(worktree.path);
+                }
+                Err(reason) => result.skipped.push(SkippedWorktree { path: worktree.path, reason }),
+            }
+        }
+
+        // Drop bookkeeping for worktrees deleted outside git
+        let _ = git_binary::command()
+            .args(["worktree", "prune"])
+            .current_dir(&project_path)
+            .output();
+
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Failed to prune worktrees: {}", e))?
+}
+
+/// Size and hash of one version of a conflicting file.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersionInfo {
+    pub size: u64,
+    /// SHA-256 of the content, hex-encoded.
+    pub sha256: String,
+}
+
 /// Information about a conflicting file in a merge.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConflictFileInfo {
     pub file_path: String,
+    /// Contents are empty for binary files; compare the version info instead.
     pub ours_content: String,
     pub theirs_content: String,
     pub base_content: String,
+    pub binary: bool,
+    /// Strategies `merge_with_resolutions` accepts for this file: "ours" and
+    /// "theirs", plus "both" for text files.
+    pub resolutions: Vec<String>,
+    /// `None` where the file does not exist at that version.
+    pub ours: Option<FileVersionInfo>,
+    pub theirs: Option<FileVersionInfo>,
+    pub base: Option<FileVersionInfo>,
 }
 
 /// Result of analyzing merge conflicts.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MergeConflictAnalysis {
@@ -1106,16 +1628,18 @@ pub struct MergeConflictAnalysis {
 }
 
 /// Analyze what conflicts would occur when merging a story branch.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn analyze_merge_conflicts(
     _app: AppHandle,
     project_path: String,
     branch_name: String,
 ) -> Result<MergeConflictAnalysis, String> {
+    let mut timer = perf::start("worktree.merge-analysis").subject(project_path.as_str());
     let main_branch = get_main_branch(&project_path);
 
     // Get merge base
-    let merge_base_output = Command::new("git")
+    let merge_base_output = git_binary::command()
         .args(["merge-base", &main_branch, &branch_name])
         .current_dir(&project_path)
         .output()
@@ -1128,7 +1652,7 @@ pub async fn analyze_merge_conflicts(
     let base_commit = String::from_utf8_lossy(&merge_base_output.stdout).trim().to_string();
 
     // Get list of files changed in the story branch
-    let branch_files_output = Command::new("git")
+    let branch_files_output = git_binary::command()
         .args(["diff", "--name-only", &base_commit, &branch_name])
         .current_dir(&project_path)
         .output()
@@ -1140,7 +1664,7 @@ pub async fn analyze_merge_conflicts(
         .collect();
 
     // Get list of files changed in main since the base
-    let main_files_output = Command::new("git")
+    let main_files_output = git_binary::command()
         .args(["diff", "--name-only", &base_commit, &main_branch])
         .current_dir(&project_path)
         .output()
@@ -1159,21 +1683,37 @@ pub async fn analyze_merge_conflicts(
         if main_files.contains(file_path) {
             // This file was changed in both branches - potential conflict
             // Get content from each version
-            let base_content = get_file_at_ref(&project_path, &base_commit, file_path);
-            let ours_content = get_file_at_ref(&project_path, &main_branch, file_path);
-            let theirs_content = get_file_at_ref(&project_path, &branch_name, file_path);
+            let base = get_file_at_ref(&project_path, &base_commit, file_path);
+            let ours = get_file_at_ref(&project_path, &main_branch, file_path);
+            let theirs = get_file_at_ref(&project_path, &branch_name, file_path);
+            let binary = [&base, &ours, &theirs].into_iter().flatten().any(|bytes| is_binary(bytes));
+
+            let text = |bytes: &Option<Vec<u8>>| match bytes {
+                Some(bytes) if !binary => String::from_utf8_lossy(bytes).to_string(),
+                _ => String::new(),
+            };
+            let mut resolutions = vec!["ours".to_string(), "theirs".to_string()];
+            if !binary {
+                resolutions.push("both".to_string());
+            }
 
             conflicting_files.push(ConflictFileInfo {
                 file_path: file_path.clone(),
-                ours_content,
-                theirs_content,
-                base_content,
+                ours_content: text(&ours),
+                theirs_content: text(&theirs),
+                base_content: text(&base),
+                binary,
+                resolutions,
+                ours: ours.as_deref().map(version_info),
+                theirs: theirs.as_deref().map(version_info),
+                base: base.as_deref().map(version_info),
             });
         } else {
             non_conflicting_count += 1;
         }
     }
 
+    timer.set_files(conflicting_files.len() + non_conflicting_count as usize);
     Ok(MergeConflictAnalysis {
         branch_name,
         conflicting_files,
@@ -1181,29 +1721,157 @@ pub async fn analyze_merge_conflicts(
     })
 }
 
-/// Get file content at a specific git ref.
-fn get_file_at_ref(project_path: &str, git_ref: &str, file_path: &str) -> String {
-    let output = Command::new("git")
+/// Get file content at a specific git ref. `None` if the file does not exist there.
+#[cfg(feature = "gui")]
+fn get_file_at_ref(project_path: &str, git_ref: &str, file_path: &str) -> Option<Vec<u8>> {
+    let output = git_binary::command()
         .args(["show", &format!("{}:{}", git_ref, file_path)])
         .current_dir(project_path)
         .output()
         .ok();
 
-    output
-        .filter(|o| o.status.success())
-        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-        .unwrap_or_default()
+    output.filter(|o| o.status.success()).map(|o| o.stdout)
+}
+
+/// Git's own heuristic: a NUL byte in the first 8000 bytes means binary.
+#[cfg(feature = "gui")]
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|b| *b == 0)
+}
+
+#[cfg(feature = "gui")]
+fn version_info(bytes: &[u8]) -> FileVersionInfo {
+    FileVersionInfo {
+        size: bytes.len() as u64,
+        sha256: hex::encode(Sha256::digest(bytes)),
+    }
 }
 
 /// Resolution strategy for a conflicting file.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileResolution {
     pub file_path: String,
-    pub strategy: String, // "ours", "theirs", "both"
+    pub strategy: String, // "ours", "theirs", "both" (text files only), "manual"
+    /// The resolved file for "manual", e.g. an edited `get_merge_preview` document.
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Three versions of a conflicting file and their automatic merge.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePreview {
+    pub file_path: String,
+    pub base_content: String,
+    pub ours_content: String,
+    pub theirs_content: String,
+    /// The three-way merge, with `<<<<<<< ours` / `>>>>>>> theirs` markers
+    /// around hunks that conflict.
+    pub merged_content: String,
+    pub conflict_count: u32,
+}
+
+/// Three-way merge of one file between main and a story branch, for editing
+/// by hand before `apply_manual_resolution` or a "manual" resolution.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn get_merge_preview(
+    _app: AppHandle,
+    project_path: String,
+    branch_name: String,
+    file_path: String,
+) -> Result<MergePreview, String> {
+    let main_branch = get_main_branch(&project_path);
+    let merge_base_output = git_binary::command()
+        .args(["merge-base", &main_branch, &branch_name])
+        .current_dir(&project_path)
+        .output()
+        .map_err(|e| format!("Failed to get merge base: {}", e))?;
+    if !merge_base_output.status.success() {
+        return Err(format!("Cannot find common ancestor between {} and {}", main_branch, branch_name));
+    }
+    let base_commit = String::from_utf8_lossy(&merge_base_output.stdout).trim().to_string();
+
+    let versions = [base_commit.as_str(), main_branch.as_str(), branch_name.as_str()]
+        .map(|git_ref| get_file_at_ref(&project_path, git_ref, &file_path).unwrap_or_default());
+    if versions.iter().any(|bytes| is_binary(bytes)) {
+        return Err(format!("{} is a binary file and can't be merged by hand", file_path));
+    }
+    let [base_content, ours_content, theirs_content] =
+        versions.map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+
+    let merged_content = match diffy::merge(&base_content, &ours_content, &theirs_content) {
+        Ok(merged) | Err(merged) => merged,
+    };
+    let conflict_count = merged_content.lines().filter(|line| line.starts_with("<<<<<<<")).count() as u32;
+
+    Ok(MergePreview {
+        file_path,
+        base_content,
+        ours_content,
+        theirs_content,
+        merged_content,
+        conflict_count,
+    })
+}
+
+/// Writes and stages a hand-resolved file during a merge.
+#[cfg(feature = "gui")]
+fn write_resolution(project_path: &str, file_path: &str, content: &str) -> Result<(), String> {
+    let relative = std::path::Path::new(file_path);
+    if relative.is_absolute() || relative.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err(format!("Invalid file path: {}", file_path));
+    }
+    if content.lines().any(|line| line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>")) {
+        return Err(format!("{} still has conflict markers", file_path));
+    }
+    let path = PathBuf::from(project_path).join(relative);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", file_path, e))?;
+    let output = git_binary::command()
+        .args(["add", "--", file_path])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to stage {}: {}", file_path, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to stage {}: {}",
+            file_path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Resolves one file of an in-progress merge with `content`, e.g. from an
+/// edited `get_merge_preview`. `merge_with_resolutions` or a manual commit
+/// concludes the merge.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn apply_manual_resolution(
+    _app: AppHandle,
+    project_path: String,
+    file_path: String,
+    content: String,
+) -> Result<(), String> {
+    let merging = git_binary::command()
+        .args(["rev-parse", "-q", "--verify", "MERGE_HEAD"])
+        .current_dir(&project_path)
+        .output()
+        .map_err(|e| format!("Failed to check merge state: {}", e))?;
+    if !merging.status.success() {
+        return Err("No merge is in progress".to_string());
+    }
+    write_resolution(&project_path, &file_path, &content)
 }
 
 /// Merge a story branch with specific resolutions for conflicting files.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn merge_with_resolutions(
     _app: AppHandle,
@@ -1211,8 +1879,21 @@ pub async fn merge_with_resolutions(
     branch_name: String,
     resolutions: Vec<FileResolution>,
 ) -> Result<(), String> {
+    // Binary files can only take one side; check before touching the working tree
+    for resolution in resolutions.iter().filter(|r| r.strategy == "both") {
+        let binary = ["HEAD", branch_name.as_str()].into_iter().any(|git_ref| {
+            get_file_at_ref(&project_path, git_ref, &resolution.file_path).is_some_and(|bytes| is_binary(&bytes))
+        });
+        if binary {
+            return Err(format!(
+                "{} is a binary file and can't keep both versions; keep ours or theirs",
+                resolution.file_path
+            ));
+        }
+    }
+
     // Start the merge (will likely have conflicts)
-    let merge_output = Command::new("git")
+    let merge_output = git_binary::command()
         .args(["merge", &branch_name, "--no-commit", "--no-ff"])
         .current_dir(&project_path)
         .output()
@@ -1220,7 +1901,7 @@ pub async fn merge_with_resolutions(
 
     // If merge succeeded without conflicts, commit and return
     if merge_output.status.success() {
-        Command::new("git")
+        git_binary::command()
             .args(["commit", "-m", &format!("Merge branch '{}'", branch_name)])
             .current_dir(&project_path)
             .output()
@@ -1231,9 +1912,17 @@ pub async fn merge_with_resolutions(
     // Apply resolutions for each file
     for resolution in &resolutions {
         match resolution.strategy.as_str() {
+            "manual" => {
+                let content = resolution
+                    .content
+                    .as_deref()
+                    .ok_or_else(|| format!("No resolved content for {}", resolution.file_path))?;
+                write_resolution(&project_path, &resolution.file_path, content)?;
+                continue;
+            }
             "ours" => {
                 // Keep our version
-                Command::new("git")
+                git_binary::command()
                     .args(["checkout", "--ours", &resolution.file_path])
                     .current_dir(&project_path)
                     .output()
@@ -1241,7 +1930,7 @@ pub async fn merge_with_resolutions(
             }
             "theirs" => {
                 // Keep their version
-                Command::new("git")
+                git_binary::command()
                     .args(["checkout", "--theirs", &resolution.file_path])
                     .current_dir(&project_path)
                     .output()
@@ -1251,13 +1940,13 @@ pub async fn merge_with_resolutions(
                 // Concatenate both versions with clear markers
                 // Note: This is primarily useful for additive files (logs, changelogs, etc.)
                 // For code files, manual editing will likely be needed after merge
-                let ours_output = Command::new("git")
+                let ours_output = git_binary::command()
                     .args(["show", &format!("HEAD:{}", resolution.file_path)])
                     .current_dir(&project_path)
                     .output()
                     .ok();
                 
-                let theirs_output = Command::new("git")
+                let theirs_output = git_binary::command()
                     .args(["show", &format!("{}:{}", branch_name, resolution.file_path)])
                     .current_dir(&project_path)
                     .output()
@@ -1288,7 +1977,7 @@ pub async fn merge_with_resolutions(
         }
 
         // Stage the resolved file
-        Command::new("git")
+        git_binary::command()
             .args(["add", &resolution.file_path])
             .current_dir(&project_path)
             .output()
@@ -1296,7 +1985,7 @@ pub async fn merge_with_resolutions(
     }
 
     // Commit the merge
-    let commit_output = Command::new("git")
+    let commit_output = git_binary::command()
         .args(["commit", "-m", &format!("Merge branch '{}' with custom resolutions", branch_name)])
         .current_dir(&project_path)
         .output()
@@ -1315,12 +2004,13 @@ pub async fn merge_with_resolutions(
 }
 
 /// Abort an in-progress merge.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn abort_merge(
     _app: AppHandle,
     project_path: String,
 ) -> Result<(), String> {
-    let output = Command::new("git")
+    let output = git_binary::command()
         .args(["merge", "--abort"])
         .current_dir(&project_path)
         .output()
@@ -10,15 +10,22 @@ import { useAgentStore } from "./stores/agentStore";
 import { usePromptStore } from "./stores/promptStore";
 import { useIdeasStore } from "./stores/ideasStore";
 import { usePanelStore } from "./stores/panelStore";
-import { useProcessStore } from "./stores/processStore";
+import { useProcessStore, describeTermination, type TerminationReason } from "./stores/processStore";
 import { useIntegrationsStore } from "./stores/integrationsStore";
 import { useStacksStore } from "./stores/stacksStore";
 import { useKeyboardNavigation } from "./hooks/useKeyboardNavigation";
 import { useWindowState } from "./hooks/useWindowState";
 import { usePrdGeneration } from "./hooks/usePrdGeneration";
+import { useBuildEvents } from "./hooks/useBuildEvents";
+import { useLanSync } from "./hooks/useLanSync";
+import { usePreferencesSync } from "./hooks/usePreferencesSync";
+import { useOpenProjectRequests } from "./hooks/useOpenProjectRequests";
+import { useDeepLinks } from "./hooks/useDeepLinks";
 
 import { notify } from "./utils/notify";
 import { ErrorBoundary } from "./components/ErrorBoundary";
+import { listenAgentOutput } from "./utils/agentOutput";
+import type { DataConflict } from "./components/DataConflictsModal";
 
 // Lazy load modals - they are only shown on user interaction
 const NewProjectModal = lazy(() => import("./components/NewProjectModal").then(m => ({ default: m.NewProjectModal })));
@@ -27,23 +34,21 @@ const ImportProjectModal = lazy(() => import("./components/ImportProjectModal").
 const PermissionsModal = lazy(() => import("./components/PermissionsModal").then(m => ({ default: m.PermissionsModal })));
 const WelcomeGuideModal = lazy(() => import("./components/WelcomeGuideModal").then(m => ({ default: m.WelcomeGuideModal })));
 const DisclaimerModal = lazy(() => import("./components/DisclaimerModal").then(m => ({ default: m.DisclaimerModal })));
+const DataConflictsModal = lazy(() => import("./components/DataConflictsModal").then(m => ({ default: m.DataConflictsModal })));
 const CommandPalette = lazy(() => import("./components/CommandPalette").then(m => ({ default: m.CommandPalette })));
 
 interface CreateProjectResult {
   path: string;
   configPath: string;
-}
-
-interface AgentOutputPayload {
-  processId: string;
-  streamType: "stdout" | "stderr";
-  content: string;
+  stackId?: string;
 }
 
 interface AgentExitPayload {
   processId: string;
   exitCode: number | null;
   success: boolean;
+  signal?: number | null;
+  terminationReason?: TerminationReason;
 }
 
 interface Preferences {
@@ -75,6 +80,7 @@ function App() {
   const [showWelcomeGuide, setShowWelcomeGuide] = useState(false);
   const [showDisclaimer, setShowDisclaimer] = useState(false);
   const [showCommandPalette, setShowCommandPalette] = useState(false);
+  const [dataConflicts, setDataConflicts] = useState<DataConflict[]>([]);
   const [preferencesLoaded, setPreferencesLoaded] = useState(false);
   const [pendingPrdGeneration, setPendingPrdGeneration] = useState<{projectId: string, projectPath: string, projectName: string} | null>(null);
   
@@ -102,6 +108,28 @@ function App() {
   // Initialize PRD generation hook at App level so event listeners are always mounted
   usePrdGeneration();
 
+  // Mirror backend build orchestration events into the stores
+  useBuildEvents();
+  useLanSync();
+
+  // Pick up preferences saved in another window or edited on disk
+  usePreferencesSync(!windowProjectId);
+
+  // Open projects launched from the OS once the project list is loaded
+  useOpenProjectRequests(isLoaded && !windowProjectId);
+
+  // Route ideate:// links once projects and ideas are loaded
+  const isIdeasLoaded = useIdeasStore((state) => state.isLoaded);
+  useDeepLinks(isLoaded && isIdeasLoaded && !windowProjectId);
+
+  // Offer to resolve conflict copies left by a cloud sync service
+  useEffect(() => {
+    if (windowProjectId) return;
+    invoke<DataConflict[]>("detect_data_conflicts")
+      .then(setDataConflicts)
+      .catch((error) => console.error("Failed to check for sync conflicts:", error));
+  }, [windowProjectId]);
+
   const isAnyModalOpen = showNewProjectModal || showProjectWizard || showImportProjectModal || showPermissionsModal || showWelcomeGuide || showDisclaimer || showCommandPalette;
 
   useKeyboardNavigation({
@@ -214,25 +242,30 @@ function App() {
       return null;
     };
 
-    const unlistenOutputPromise = listen<AgentOutputPayload>("agent-output", (event) => {
-      const { processId, streamType, content } = event.payload;
+    const unlistenOutputPromise = listenAgentOutput((output) => {
+      const { processId, streamType, content, partial } = output;
       
       // Always append to processStore if the process is registered there
       // This ensures process history has complete logs
       const processStoreProcess = useProcessStore.getState().getProcess(processId);
       if (processStoreProcess) {
-        useProcessStore.getState().appendProcessLog(processId, streamType, content);
+        useProcessStore.getState().appendProcessLog(processId, streamType, content, partial);
       }
       
       // Also append to buildStore if this is a build process (for the build log panel)
       const buildProjectId = findProjectByProcessId(processId);
-      if (buildProjectId) {
+      if (buildProjectId && !partial) {
         useBuildStore.getState().appendLog(buildProjectId, streamType, content, processId);
       }
-    });
+    }, { partial: true });
 
     const unlistenExitPromise = listen<AgentExitPayload>("agent-exit", (event) => {
-      const { processId, exitCode, success } = event.payload;
+      const { processId, exitCode, success, signal, terminationReason } = event.payload;
+
+      const termination = describeTermination(terminationReason, signal);
+      if (termination && useProcessStore.getState().getProcess(processId)) {
+        useProcessStore.getState().appendProcessLog(processId, "system", termination);
+      }
       
       // Check if this is a buildStore process and update build state
       const buildProjectId = findProjectByProcessId(processId);
@@ -242,7 +275,7 @@ function App() {
           exitCode: exitCode,
           success,
         });
-        // Note: The hooks (useBuildLoop, usePrdGeneration) call unregisterProcess
+        // Note: useBuildEvents and usePrdGeneration call unregisterProcess
         // with proper exit info, so process history is saved there.
         return;
       }
@@ -358,18 +391,36 @@ function App() {
     }
   };
 
-  const handleImportExistingProject = async (name: string, directory: string, generatePrd: boolean) => {
+  const handleImportProjectBundle = async (bundlePath: string, destination: string) => {
+    try {
+      const project = await invoke<{ id: string; name: string }>("import_project_bundle", {
+        path: bundlePath,
+        destination,
+      });
+      await loadProjects();
+      setActiveProject(project.id);
+      initSession(project.id);
+      setShowImportProjectModal(false);
+      notify.success("Project imported", `${project.name} was restored from its bundle`);
+    } catch (error) {
+      notify.error("Failed to import bundle", String(error));
+    }
+  };
+
+  const handleImportExistingProject = async (name: string, directory: string, generatePrd: boolean, cloneUrl?: string) => {
     try {
       const result = await invoke<CreateProjectResult>("import_project", {
         name,
         projectPath: directory,
+        cloneUrl,
       });
 
       const newProject = addProject({
         name,
-        description: "Imported existing project",
+        description: cloneUrl ? `Cloned from ${cloneUrl}` : "Imported existing project",
         path: result.path,
         status: "idle",
+        stackId: result.stackId,
       });
 
       // Set the imported project as active BEFORE triggering PRD generation
@@ -441,6 +492,7 @@ function App() {
             isOpen={showImportProjectModal}
             onClose={handleCloseImportProjectModal}
             onImport={handleImportExistingProject}
+            onImportBundle={handleImportProjectBundle}
           />
         )}
         {showPermissionsModal && (
@@ -461,6 +513,13 @@ function App() {
             onAccept={handleAcceptDisclaimer}
           />
         )}
+        {dataConflicts.length > 0 && (
+          <DataConflictsModal
+            isOpen={dataConflicts.length > 0}
+            conflicts={dataConflicts}
+            onClose={() => setDataConflicts([])}
+          />
+        )}
         {showCommandPalette && (
           <CommandPalette
             isOpen={showCommandPalette}
@@ -0,0 +1,154 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import { notify } from "../utils/notify";
+
+type ConflictResolution = "keepExisting" | "useImported";
+
+interface ImportedDocument {
+  name: string;
+  added: number;
+  replaced: number;
+  kept: number;
+}
+
+interface AppDataLocation {
+  path: string;
+  defaultPath: string;
+  relocated: boolean;
+}
+
+interface ImportSummary {
+  exportedAt: string;
+  appVersion: string;
+  documents: ImportedDocument[];
+}
+
+export function AppDataSection() {
+  const [conflict, setConflict] = useState<ConflictResolution>("keepExisting");
+  const [isBusy, setIsBusy] = useState(false);
+  const [location, setLocation] = useState<AppDataLocation | null>(null);
+
+  useEffect(() => {
+    invoke<AppDataLocation>("get_app_data_location")
+      .then(setLocation)
+      .catch(() => setLocation(null));
+  }, []);
+
+  const moveTo = async (path: string) => {
+    if (!confirm(`Move all app data to ${path}? Ideate will restart when it's done.`)) return;
+    setIsBusy(true);
+    try {
+      await invoke("move_app_data", { path });
+    } catch (error) {
+      notify.error("Move failed", String(error));
+      setIsBusy(false);
+    }
+  };
+
+  const handleMove = async () => {
+    const path = await open({ directory: true, multiple: false, title: "Choose App Data Location" });
+    if (typeof path !== "string") return;
+    await moveTo(path);
+  };
+
+  const handleExport = async () => {
+    const date = new Date().toISOString().slice(0, 10);
+    const path = await save({
+      defaultPath: `ideate-data-${date}.json`,
+      filters: [{ name: "Ideate Data", extensions: ["json"] }],
+      title: "Export App Data",
+    });
+    if (!path) return;
+
+    setIsBusy(true);
+    try {
+      await invoke("export_app_data", { path });
+      notify.success("App data exported", `Saved to ${path.split("/").pop()}`);
+    } catch (error) {
+      notify.error("Export failed", String(error));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleImport = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: "Ideate Data", extensions: ["json"] }],
+      title: "Import App Data",
+    });
+    if (typeof path !== "string") return;
+
+    setIsBusy(true);
+    try {
+      const summary = await invoke<ImportSummary>("import_app_data", { path, conflict });
+      const added = summary.documents.reduce((sum, d) => sum + d.added, 0);
+      const replaced = summary.documents.reduce((sum, d) => sum + d.replaced, 0);
+      notify.success(
+        "App data imported",
+        `${added} added, ${replaced} replaced from v${summary.appVersion}. Reloading…`
+      );
+      // Stores and settings were loaded before the import
+      setTimeout(() => window.location.reload(), 1500);
+    } catch (error) {
+      notify.error("Import failed", String(error));
+      setIsBusy(false);
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        App Data
+      </h3>
+      <p className="text-xs text-muted mb-3">
+        Export preferences, projects, stacks, ideas, and window layout to one file to move them to another computer or share them with support. API keys are not included.
+      </p>
+      <div className="space-y-4">
+        <div>
+          <label className="block text-sm text-foreground mb-2">When Importing Entries That Already Exist</label>
+          <select
+            value={conflict}
+            onChange={(e) => setConflict(e.target.value as ConflictResolution)}
+            className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+          >
+            <option value="keepExisting">Keep this computer's version</option>
+            <option value="useImported">Use the imported version</option>
+          </select>
+        </div>
+        <div className="flex gap-2">
+          <button onClick={handleExport} disabled={isBusy} className="btn btn-sm btn-secondary">
+            Export…
+          </button>
+          <button onClick={handleImport} disabled={isBusy} className="btn btn-sm btn-secondary">
+            Import…
+          </button>
+        </div>
+        {location && (
+          <div>
+            <label className="block text-sm text-foreground mb-2">Data Location</label>
+            <p className="text-xs text-secondary font-mono break-all mb-2">{location.path}</p>
+            <div className="flex gap-2">
+              <button onClick={handleMove} disabled={isBusy} className="btn btn-sm btn-secondary">
+                Move…
+              </button>
+              {location.relocated && (
+                <button
+                  onClick={() => moveTo(location.defaultPath)}
+                  disabled={isBusy}
+                  className="btn btn-sm btn-secondary"
+                >
+                  Move Back to Default
+                </button>
+              )}
+            </div>
+            <p className="text-xs text-muted mt-1">
+              Projects index, logs, stacks, and everything else Ideate stores. Choose an empty folder, such as one on another drive or in a synced folder.
+            </p>
+          </div>
+        )}
+      </div>
+    </section>
+  );
+}
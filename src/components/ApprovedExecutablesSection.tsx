@@ -0,0 +1,40 @@
+interface ApprovedExecutablesSectionProps {
+  executables: string[];
+  onExecutablesChange: (executables: string[]) => void;
+}
+
+export function ApprovedExecutablesSection({
+  executables,
+  onExecutablesChange,
+}: ApprovedExecutablesSectionProps) {
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Approved Executables
+      </h3>
+      <p className="text-xs text-muted mb-3">
+        Known agents and tools always run. Other programs need your approval the first time Ideate starts them.
+      </p>
+      {executables.length === 0 ? (
+        <p className="text-xs text-muted">No executables approved.</p>
+      ) : (
+        <div className="space-y-2">
+          {executables.map((executable) => (
+            <div
+              key={executable}
+              className="flex items-center justify-between gap-3 p-2 rounded-lg border border-border"
+            >
+              <code className="text-xs text-foreground truncate">{executable}</code>
+              <button
+                onClick={() => onExecutablesChange(executables.filter((e) => e !== executable))}
+                className="btn btn-sm btn-secondary shrink-0"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+    </section>
+  );
+}
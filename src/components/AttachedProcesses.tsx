@@ -0,0 +1,183 @@
+import { useEffect, useRef, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+interface ExternalProcess {
+  processId: string
+  pid: number
+  label: string
+  projectId: string
+  command: string
+  startedAt: string
+}
+
+interface ExternalProcessStats {
+  processId: string
+  cpuPercent: number
+  memoryBytes: number
+}
+
+interface ExternalProcessExit {
+  processId: string
+  durationMs: number
+}
+
+interface AttachedProcessesProps {
+  projectId: string
+  /** Called when an attached process exits and its history entry is written. */
+  onExit: () => void
+}
+
+function formatMemory(bytes: number): string {
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(0)} MB`
+  return `${(bytes / (1024 * 1024 * 1024)).toFixed(2)} GB`
+}
+
+export function AttachedProcesses({ projectId, onExit }: AttachedProcessesProps) {
+  const [processes, setProcesses] = useState<ExternalProcess[]>([])
+  const [stats, setStats] = useState<Record<string, ExternalProcessStats>>({})
+  const [showForm, setShowForm] = useState(false)
+  const [pid, setPid] = useState('')
+  const [label, setLabel] = useState('')
+  const [error, setError] = useState<string | null>(null)
+  const [attaching, setAttaching] = useState(false)
+  const onExitRef = useRef(onExit)
+  onExitRef.current = onExit
+
+  useEffect(() => {
+    invoke<ExternalProcess[]>('list_external_processes', { projectId })
+      .then(setProcesses)
+      .catch((e) => console.error('Failed to list attached processes:', e))
+  }, [projectId])
+
+  useEffect(() => {
+    const unlistenStats = listen<ExternalProcessStats>('external-process-stats', (event) => {
+      setStats((prev) => ({ ...prev, [event.payload.processId]: event.payload }))
+    })
+    const unlistenExit = listen<ExternalProcessExit>('external-process-exit', (event) => {
+      setProcesses((prev) => prev.filter((p) => p.processId !== event.payload.processId))
+      onExitRef.current()
+    })
+    return () => {
+      unlistenStats.then((fn) => fn())
+      unlistenExit.then((fn) => fn())
+    }
+  }, [])
+
+  const handleAttach = async () => {
+    const parsed = parseInt(pid, 10)
+    if (!parsed || parsed <= 0) {
+      setError('Enter a process ID')
+      return
+    }
+    setAttaching(true)
+    setError(null)
+    try {
+      const process = await invoke<ExternalProcess>('attach_external_process', {
+        pid: parsed,
+        label,
+        projectId,
+      })
+      setProcesses((prev) => [...prev, process])
+      setPid('')
+      setLabel('')
+      setShowForm(false)
+    } catch (e) {
+      setError(String(e))
+    }
+    setAttaching(false)
+  }
+
+  const handleDetach = async (processId: string) => {
+    try {
+      await invoke('detach_external_process', { processId })
+    } catch (e) {
+      console.error('Failed to detach process:', e)
+    }
+    setProcesses((prev) => prev.filter((p) => p.processId !== processId))
+  }
+
+  return (
+    <div className="max-w-4xl mx-auto mb-6">
+      <div className="flex items-center justify-between mb-2">
+        <h3 className="text-sm font-medium text-secondary">Attached Processes</h3>
+        <button
+          onClick={() => {
+            setShowForm(!showForm)
+            setError(null)
+          }}
+          className="text-xs text-accent hover:underline"
+        >
+          {showForm ? 'Cancel' : 'Attach Process…'}
+        </button>
+      </div>
+
+      {showForm && (
+        <div className="border border-border rounded-lg p-3 mb-2 space-y-2">
+          <p className="text-xs text-muted">
+            Monitor an agent you started in a terminal. Its output isn't captured, but its
+            CPU and memory are shown here and its run is added to history when it exits.
+          </p>
+          <div className="flex items-center gap-2">
+            <input
+              type="number"
+              value={pid}
+              onChange={(e) => setPid(e.target.value)}
+              placeholder="PID"
+              className="bg-background border border-border rounded-lg px-3 py-1.5 text-sm w-28"
+            />
+            <input
+              type="text"
+              value={label}
+              onChange={(e) => setLabel(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') handleAttach()
+              }}
+              placeholder="Label (optional)"
+              className="bg-background border border-border rounded-lg px-3 py-1.5 text-sm flex-1"
+            />
+            <button onClick={handleAttach} disabled={attaching} className="btn btn-primary">
+              {attaching ? 'Attaching...' : 'Attach'}
+            </button>
+          </div>
+          {error && <p className="text-xs text-destructive">{error}</p>}
+        </div>
+      )}
+
+      {processes.length === 0 ? (
+        !showForm && <p className="text-xs text-muted">No processes attached</p>
+      ) : (
+        <div className="space-y-2">
+          {processes.map((process) => {
+            const sample = stats[process.processId]
+            return (
+              <div
+                key={process.processId}
+                className="flex items-center gap-3 px-4 py-2 border border-border rounded-lg"
+              >
+                <span className="w-2 h-2 rounded-full bg-success flex-shrink-0" />
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm font-medium truncate">{process.label}</p>
+                  <p className="text-xs text-muted font-mono truncate">
+                    PID {process.pid} • {process.command}
+                  </p>
+                </div>
+                <span className="text-xs text-secondary tabular-nums">
+                  {sample
+                    ? `${sample.cpuPercent.toFixed(1)}% CPU • ${formatMemory(sample.memoryBytes)}`
+                    : 'Sampling…'}
+                </span>
+                <button
+                  onClick={() => handleDetach(process.processId)}
+                  className="text-xs text-muted hover:text-foreground"
+                >
+                  Detach
+                </button>
+              </div>
+            )
+          })}
+        </div>
+      )}
+    </div>
+  )
+}
@@ -1,9 +1,12 @@
 import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useBuildStore } from "../stores/buildStore";
 import { usePrdStore } from "../stores/prdStore";
 import { useBuildLoop } from "../hooks/useBuildLoop";
 import { usePrdGeneration } from "../hooks/usePrdGeneration";
 import { useProjectStore } from "../stores/projectStore";
+import { BuildPlanModal } from "./BuildPlanModal";
+import { PreflightModal, type PreflightReport } from "./PreflightModal";
 
 interface BuildControlsProps {
   projectId: string;
@@ -13,10 +16,11 @@ interface BuildControlsProps {
 export function BuildControls({ projectId, projectPath }: BuildControlsProps) {
   // Subscribe directly to the project state for reactivity
   const projectState = useBuildStore((state) => state.projectStates[projectId]);
-  const pauseBuild = useBuildStore((state) => state.pauseBuild);
+  const requestPause = useBuildStore((state) => state.requestPause);
   
   const status = projectState?.status ?? 'idle';
   const currentStoryId = projectState?.currentStoryId ?? null;
+  const progress = currentStoryId ? projectState?.storyProgress?.[currentStoryId] ?? null : null;
 
   const projectPrd = usePrdStore((state) => state.projectPrds[projectId]);
   const stories = projectPrd?.stories ?? [];
@@ -28,6 +32,9 @@ export function BuildControls({ projectId, projectPath }: BuildControlsProps) {
   const { breakdownStories } = usePrdGeneration();
   
   const [isBreakingDown, setIsBreakingDown] = useState(false);
+  const [isPlanOpen, setIsPlanOpen] = useState(false);
+  const [preflight, setPreflight] = useState<PreflightReport | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
 
   const hasIncompleteStories = stories.some((s) => !s.passes);
   const hasStories = stories.length > 0;
@@ -43,6 +50,26 @@ export function BuildControls({ projectId, projectPath }: BuildControlsProps) {
     }
   };
 
+  // Starts straight away when every check passes; otherwise shows the checklist
+  const handleStartWithPreflight = async (showAlways = false) => {
+    setIsChecking(true);
+    try {
+      const report = await invoke<PreflightReport>("run_preflight", { projectPath });
+      if (!showAlways && report.checks.every((check) => check.status === "pass")) {
+        setPreflight(null);
+        handleStart();
+      } else {
+        setPreflight(report);
+      }
+    } catch (error) {
+      console.error("Preflight failed:", error);
+      setPreflight(null);
+      handleStart();
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
   const PlayIcon = () => (
     <svg
       className="w-4 h-4"
@@ -81,13 +108,41 @@ export function BuildControls({ projectId, projectPath }: BuildControlsProps) {
     return (
       <div className="flex items-center gap-2">
         <button
-          onClick={handleStart}
-          disabled={!canStart}
+          onClick={() => handleStartWithPreflight()}
+          disabled={!canStart || isChecking}
           className="btn btn-primary"
         >
           <PlayIcon />
-          Start Build
+          {isChecking && !preflight ? "Checking..." : "Start Build"}
         </button>
+        <button
+          onClick={() => setIsPlanOpen(true)}
+          disabled={!canStart}
+          className="btn btn-secondary"
+          title="Preview the stories, order, and estimated cost without running agents"
+        >
+          Plan
+        </button>
+        <BuildPlanModal
+          isOpen={isPlanOpen}
+          projectId={projectId}
+          projectPath={projectPath}
+          onClose={() => setIsPlanOpen(false)}
+          onStart={() => {
+            setIsPlanOpen(false);
+            handleStartWithPreflight();
+          }}
+        />
+        <PreflightModal
+          report={preflight}
+          isChecking={isChecking}
+          onClose={() => setPreflight(null)}
+          onRecheck={() => handleStartWithPreflight(true)}
+          onStart={() => {
+            setPreflight(null);
+            handleStart();
+          }}
+        />
         {hasStories && (
           <button
             onClick={handleBreakdownStories}
@@ -132,7 +187,7 @@ export function BuildControls({ projectId, projectPath }: BuildControlsProps) {
     return (
       <div className="flex items-center gap-2">
         <button
-          onClick={() => pauseBuild(projectId)}
+          onClick={() => requestPause(projectId)}
           className="btn btn-secondary"
         >
           <PauseIcon />
@@ -150,6 +205,18 @@ export function BuildControls({ projectId, projectPath }: BuildControlsProps) {
             ? <><span className="pill mr-1.5">{currentStory.id}</span>{currentStory.title}</>
             : 'Build in progress...'}
         </span>
+        {progress && (
+          <div className="flex items-center gap-2 min-w-0" title={progress.file ?? undefined}>
+            {progress.percent !== null && (
+              <div className="w-24 h-1.5 rounded-full bg-border overflow-hidden flex-shrink-0">
+                <div className="h-full bg-accent transition-all" style={{ width: `${progress.percent}%` }} />
+              </div>
+            )}
+            <span className="text-xs text-muted truncate">
+              {[progress.step, progress.file].filter(Boolean).join(" · ")}
+            </span>
+          </div>
+        )}
       </div>
     );
   }
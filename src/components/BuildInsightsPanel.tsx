@@ -0,0 +1,172 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface StoryStats {
+  stories: number;
+  passed: number;
+  failed: number;
+  firstAttemptPasses: number;
+  retries: number;
+  firstAttemptPassRate: number | null;
+  averageRetries: number | null;
+}
+
+interface AgentInsights extends StoryStats {
+  agentId: string;
+  builds: number;
+  processes: number;
+  processSuccessRate: number | null;
+  averageProcessMs: number | null;
+  failures: Record<string, number>;
+}
+
+interface FailureReason {
+  kind: string;
+  attempts: number;
+  stories: number;
+  examples: string[];
+}
+
+interface ProjectInsights extends StoryStats {
+  projectId: string;
+  projectName: string;
+  builds: number;
+}
+
+interface BuildInsights extends StoryStats {
+  generatedAt: string;
+  builds: number;
+  agents: AgentInsights[];
+  failureReasons: FailureReason[];
+  projects: ProjectInsights[];
+}
+
+function percent(rate: number | null): string {
+  return rate === null ? "—" : `${Math.round(rate * 100)}%`;
+}
+
+function decimal(value: number | null): string {
+  return value === null ? "—" : value.toFixed(2);
+}
+
+function duration(ms: number | null): string {
+  if (ms === null) return "—";
+  const minutes = Math.floor(ms / 60000);
+  const seconds = Math.round((ms % 60000) / 1000);
+  return minutes > 0 ? `${minutes}m ${seconds}s` : `${seconds}s`;
+}
+
+export function BuildInsightsPanel() {
+  const [insights, setInsights] = useState<BuildInsights | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<BuildInsights>("get_build_insights")
+      .then(setInsights)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  if (error) {
+    return <p className="p-6 text-sm text-destructive">{error}</p>;
+  }
+  if (!insights) {
+    return <p className="p-6 text-sm text-muted">Loading…</p>;
+  }
+  if (insights.builds === 0) {
+    return <p className="p-6 text-sm text-muted">No builds have finished yet.</p>;
+  }
+
+  return (
+    <div className="flex-1 overflow-y-auto p-6 space-y-6">
+      <div className="grid grid-cols-4 gap-4">
+        <div>
+          <label className="text-xs font-medium text-muted uppercase tracking-wider">Builds</label>
+          <p className="mt-1 text-xl font-bold text-foreground">{insights.builds.toLocaleString()}</p>
+        </div>
+        <div>
+          <label className="text-xs font-medium text-muted uppercase tracking-wider">Stories</label>
+          <p className="mt-1 text-xl font-bold text-foreground">{insights.stories.toLocaleString()}</p>
+        </div>
+        <div>
+          <label className="text-xs font-medium text-muted uppercase tracking-wider">First-Attempt Pass</label>
+          <p className="mt-1 text-xl font-bold text-accent">{percent(insights.firstAttemptPassRate)}</p>
+        </div>
+        <div>
+          <label className="text-xs font-medium text-muted uppercase tracking-wider">Retries / Story</label>
+          <p className="mt-1 text-xl font-bold text-foreground">{decimal(insights.averageRetries)}</p>
+        </div>
+      </div>
+
+      <section>
+        <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-2">Agents</h3>
+        <div className="border border-border rounded-lg overflow-hidden">
+          <table className="w-full text-xs">
+            <thead className="bg-background-secondary text-muted">
+              <tr>
+                <th className="px-3 py-2 text-left font-medium">Agent</th>
+                <th className="px-3 py-2 text-right font-medium">Stories</th>
+                <th className="px-3 py-2 text-right font-medium">First-Attempt Pass</th>
+                <th className="px-3 py-2 text-right font-medium">Retries / Story</th>
+                <th className="px-3 py-2 text-right font-medium">Clean Exits</th>
+                <th className="px-3 py-2 text-right font-medium">Avg Run</th>
+              </tr>
+            </thead>
+            <tbody>
+              {insights.agents.map((agent) => (
+                <tr key={agent.agentId} className="border-t border-border">
+                  <td className="px-3 py-1.5 text-foreground">{agent.agentId}</td>
+                  <td className="px-3 py-1.5 text-right text-muted">{agent.stories}</td>
+                  <td className="px-3 py-1.5 text-right text-foreground">{percent(agent.firstAttemptPassRate)}</td>
+                  <td className="px-3 py-1.5 text-right text-muted">{decimal(agent.averageRetries)}</td>
+                  <td className="px-3 py-1.5 text-right text-muted">{percent(agent.processSuccessRate)}</td>
+                  <td className="px-3 py-1.5 text-right text-muted">{duration(agent.averageProcessMs)}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      </section>
+
+      {insights.failureReasons.length > 0 && (
+        <section>
+          <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-2">Failure Reasons</h3>
+          <div className="space-y-2">
+            {insights.failureReasons.map((reason) => (
+              <div key={reason.kind} className="text-xs">
+                <div className="flex items-center justify-between">
+                  <span className="font-mono text-foreground">{reason.kind}</span>
+                  <span className="text-muted">
+                    {reason.attempts} failed attempt{reason.attempts === 1 ? "" : "s"}, {reason.stories} still failing
+                  </span>
+                </div>
+                {reason.examples.map((example) => (
+                  <p key={example} className="text-muted truncate" title={example}>
+                    {example}
+                  </p>
+                ))}
+              </div>
+            ))}
+          </div>
+        </section>
+      )}
+
+      <section>
+        <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-2">Projects</h3>
+        <table className="w-full text-xs">
+          <tbody>
+            {insights.projects.map((project) => (
+              <tr key={project.projectId} className="border-t border-border">
+                <td className="py-1.5 text-foreground">{project.projectName}</td>
+                <td className="py-1.5 text-right text-muted">{project.builds} builds</td>
+                <td className="py-1.5 text-right text-muted">
+                  {project.passed}/{project.stories} passed
+                </td>
+                <td className="py-1.5 text-right text-foreground">{percent(project.firstAttemptPassRate)} first try</td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      </section>
+    </div>
+  );
+}
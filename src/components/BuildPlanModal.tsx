@@ -0,0 +1,160 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useModalKeyboard } from "../hooks/useModalKeyboard";
+import { formatCost as formatUsd, useDisplayCurrency, type DisplayCurrency } from "../hooks/useDisplayCurrency";
+import { formatTokenEstimate } from "../utils/storyComplexity";
+
+interface PlannedStory {
+  storyId: string;
+  title: string;
+  order: number;
+  wave: number | null;
+  prerequisites: string[];
+  serializedWith: string[];
+  agentId: string;
+  model: string | null;
+  estimatedTokens: number;
+  estimatedCost: number | null;
+  worktreePath: string | null;
+  branchName: string | null;
+  verificationSteps: string[];
+}
+
+interface BuildPlan {
+  projectId: string;
+  mode: string;
+  modeLabel: string;
+  agentId: string;
+  parallelLimit: number | null;
+  baseBranch: string | null;
+  stories: PlannedStory[];
+  totalEstimatedTokens: number;
+  totalEstimatedCost: number | null;
+  warnings: string[];
+}
+
+interface BuildPlanModalProps {
+  isOpen: boolean;
+  projectId: string;
+  projectPath: string;
+  onClose: () => void;
+  onStart: () => void;
+}
+
+function formatCost(cost: number | null, currency: DisplayCurrency): string {
+  return cost === null ? "—" : `~${formatUsd(cost, currency)}`;
+}
+
+export function BuildPlanModal({ isOpen, projectId, projectPath, onClose, onStart }: BuildPlanModalProps) {
+  const [plan, setPlan] = useState<BuildPlan | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const currency = useDisplayCurrency();
+
+  useModalKeyboard(isOpen, onClose);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    setPlan(null);
+    setError(null);
+    invoke<BuildPlan>("plan_build", { projectId, projectPath })
+      .then(setPlan)
+      .catch((err) => setError(String(err)));
+  }, [isOpen, projectId, projectPath]);
+
+  if (!isOpen) return null;
+
+  const handleOverlayClick = (e: React.MouseEvent) => {
+    if (e.target === e.currentTarget) {
+      onClose();
+    }
+  };
+
+  return (
+    <div
+      className="fixed inset-0 bg-black/50 flex items-center justify-center z-50 no-drag"
+      onClick={handleOverlayClick}
+    >
+      <div className="bg-card border border-border rounded-xl p-6 w-full max-w-2xl max-h-[80vh] flex flex-col shadow-xl">
+        <h2 className="text-lg font-semibold text-foreground mb-1">Build Plan</h2>
+        {plan && (
+          <p className="text-sm text-secondary mb-4">
+            {plan.stories.length} {plan.stories.length === 1 ? "story" : "stories"} · {plan.modeLabel}
+            {plan.parallelLimit ? ` (up to ${plan.parallelLimit} at once)` : ""} · {plan.agentId} ·{" "}
+            {formatTokenEstimate(plan.totalEstimatedTokens)} tokens · {formatCost(plan.totalEstimatedCost, currency)}
+          </p>
+        )}
+
+        {error && <p className="text-sm text-destructive mb-4">{error}</p>}
+        {!plan && !error && <p className="text-sm text-muted mb-4">Planning...</p>}
+
+        {plan && plan.warnings.length > 0 && (
+          <div className="mb-3 space-y-1">
+            {plan.warnings.map((warning) => (
+              <p key={warning} className="text-xs text-warning">{warning}</p>
+            ))}
+          </div>
+        )}
+
+        {plan && (
+          <div className="flex-1 overflow-y-auto space-y-2 mb-4">
+            {plan.stories.map((story) => (
+              <div key={story.storyId} className="border border-border rounded-lg">
+                <button
+                  onClick={() => setExpanded(expanded === story.storyId ? null : story.storyId)}
+                  className="w-full flex items-center gap-2 px-3 py-2 text-left text-sm"
+                >
+                  <span className="text-muted w-6">{story.order}.</span>
+                  <span className="pill">{story.storyId}</span>
+                  <span className="flex-1 truncate text-foreground">{story.title}</span>
+                  {story.wave !== null && <span className="text-xs text-muted">wave {story.wave + 1}</span>}
+                  {(story.agentId !== plan.agentId || story.model) && (
+                    <span className="text-xs text-accent">
+                      {story.model ? `${story.agentId} · ${story.model}` : story.agentId}
+                    </span>
+                  )}
+                  <span className="text-xs text-muted">{formatTokenEstimate(story.estimatedTokens)}</span>
+                  <span className="text-xs text-muted min-w-14 text-right">{formatCost(story.estimatedCost, currency)}</span>
+                </button>
+                {expanded === story.storyId && (
+                  <div className="px-3 pb-3 pl-11 space-y-1 text-xs text-secondary">
+                    {story.prerequisites.length > 0 && <p>After: {story.prerequisites.join(", ")}</p>}
+                    {story.serializedWith.length > 0 && (
+                      <p>Not alongside: {story.serializedWith.join(", ")} (shared files)</p>
+                    )}
+                    {story.branchName && (
+                      <p>
+                        Branch <code>{story.branchName}</code> in <code>{story.worktreePath}</code>
+                      </p>
+                    )}
+                    <ul className="list-disc pl-4">
+                      {story.verificationSteps.map((step) => (
+                        <li key={step}>{step}</li>
+                      ))}
+                    </ul>
+                  </div>
+                )}
+              </div>
+            ))}
+          </div>
+        )}
+
+        <div className="flex gap-3 justify-end">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 text-sm font-medium text-secondary hover:text-foreground rounded-lg hover:bg-secondary/10 transition-colors"
+          >
+            Close
+          </button>
+          <button
+            onClick={onStart}
+            disabled={!plan || plan.stories.length === 0}
+            className="btn btn-primary"
+          >
+            Start Build
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,7 +1,8 @@
 import { useMemo, useState, useEffect, useCallback, useRef } from "react";
 import { open } from "@tauri-apps/plugin-shell";
 import { invoke } from "@tauri-apps/api/core";
-import { useBuildStore, type ConflictInfo } from "../stores/buildStore";
+import { listen } from "@tauri-apps/api/event";
+import { useBuildStore, type BuildSwarm, type ConflictInfo } from "../stores/buildStore";
 import { usePrdStore, type Story } from "../stores/prdStore";
 import { useProjectStore } from "../stores/projectStore";
 import { usePanelStore } from "../stores/panelStore";
@@ -10,7 +11,10 @@ import { DiffViewer } from "./DiffViewer";
 import { FileViewer } from "./FileViewer";
 import { ConflictResolver } from "./ConflictResolver";
 import { StreamLogEntry } from "./StreamLogEntry";
+import { SpectatorButton } from "./SpectatorButton";
 import { defaultPlugins } from "../types";
+import { detectDevServerFromPackage, previewCommand } from "../hooks/useDevServer";
+import { notify } from "../utils/notify";
 import { 
   estimateBuildComplexity, 
   formatTokenEstimate, 
@@ -27,6 +31,37 @@ interface StoryBranchInfo {
   isCurrent: boolean;
 }
 
+interface StoryPreviewStatus {
+  projectPath: string;
+  status: string;
+  url: string | null;
+  preview?: { projectPath: string; storyId: string; branchName: string; assignedPort: number };
+}
+
+interface WorktreeUsageReport {
+  totalBytes: number;
+  worktrees: { path: string; branchName: string | null; sizeBytes: number; ageDays: number }[];
+}
+
+interface PruneWorktreesResult {
+  removed: string[];
+  skipped: { path: string; reason: string }[];
+  freedBytes: number;
+}
+
+/** Age in days a worktree must reach before "Prune" removes it. */
+const PRUNE_WORKTREES_OLDER_THAN_DAYS = 7;
+
+const SWARM_PHASE_LABELS: Record<BuildSwarm['phase'], string> = {
+  planning: 'Planning',
+  working: 'Working',
+  merging: 'Merging',
+  reviewing: 'Reviewing',
+  verifying: 'Verifying',
+  done: 'Done',
+  failed: 'Failed',
+};
+
 interface BuildStatusContentProps {
   projectId: string;
 }
@@ -37,6 +72,12 @@ interface StoryWithBuildStatus extends Story {
   buildStatus: StoryBuildStatus;
 }
 
+function formatBytes(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  return `${(bytes / (1024 * 1024 * 1024)).toFixed(2)} GB`;
+}
+
 function getStatusIcon(status: StoryBuildStatus) {
   switch (status) {
     case "pending":
@@ -83,7 +124,12 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
   const [showDependencies, setShowDependencies] = useState(true);
   const [showBranches, setShowBranches] = useState(true);
   const [storyBranches, setStoryBranches] = useState<StoryBranchInfo[]>([]);
+  const [storyPreviews, setStoryPreviews] = useState<Record<string, StoryPreviewStatus>>({});
+  const [startingPreview, setStartingPreview] = useState<string | null>(null);
   const [branchesLoading, setBranchesLoading] = useState(false);
+  const [worktreeUsage, setWorktreeUsage] = useState<WorktreeUsageReport | null>(null);
+  const [pruning, setPruning] = useState(false);
+  const [pruneSummary, setPruneSummary] = useState<string | null>(null);
   const [confirmDelete, setConfirmDelete] = useState<string | null>(null);
   const [confirmForceMerge, setConfirmForceMerge] = useState<string | null>(null);
   const [diffViewerStory, setDiffViewerStory] = useState<{ id: string; title: string; branchName?: string } | null>(null);
@@ -116,6 +162,11 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
   const currentStoryId = projectState?.currentStoryId ?? null;
   const logs = projectState?.logs ?? [];
   const conflictedBranches = projectState?.conflictedBranches ?? [];
+  const budgetWarning = projectState?.budgetWarning ?? null;
+  const setBudgetWarning = useBuildStore((state) => state.setBudgetWarning);
+  const forecast = projectState?.forecast ?? null;
+  const swarm = projectState?.swarm ?? null;
+  const remoteBuild = projectState?.remoteBuild ?? null;
   const clearStorySnapshot = useBuildStore((state) => state.clearStorySnapshot);
   const appendLog = useBuildStore((state) => state.appendLog);
   const reorderStories = usePrdStore((state) => state.reorderStories);
@@ -134,6 +185,13 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
         projectPath: project.path,
       });
       setStoryBranches(branches);
+      const previews = await invoke<StoryPreviewStatus[]>("list_story_previews", {
+        projectPath: project.path,
+      });
+      setStoryPreviews(Object.fromEntries(previews.map((p) => [p.preview!.storyId, p])));
+      setWorktreeUsage(await invoke<WorktreeUsageReport>("get_worktree_usage", {
+        projectPath: project.path,
+      }));
     } catch (e) {
       console.error("Failed to load story branches:", e);
     } finally {
@@ -141,6 +199,78 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
     }
   }, [project?.path]);
 
+  useEffect(() => {
+    if (!project?.path) return;
+    const unlisten = listen<StoryPreviewStatus>("dev-server-status", (event) => {
+      const preview = event.payload.preview;
+      if (!preview || preview.projectPath !== project.path) return;
+      setStoryPreviews((prev) => {
+        const next = { ...prev };
+        if (event.payload.status === "stopped") {
+          delete next[preview.storyId];
+        } else {
+          next[preview.storyId] = event.payload;
+        }
+        return next;
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [project?.path]);
+
+  const handleTogglePreview = async (storyId: string) => {
+    if (!project?.path) return;
+    if (storyPreviews[storyId]) {
+      try {
+        await invoke("stop_story_preview", { projectPath: project.path, storyId });
+      } catch (e) {
+        notify.error("Failed to stop preview", String(e));
+      }
+      return;
+    }
+    setStartingPreview(storyId);
+    try {
+      const config = await detectDevServerFromPackage(project.path);
+      if (!config) {
+        notify.error("No dev server found", "Add a dev or start script to package.json to preview stories");
+        return;
+      }
+      const status = await invoke<StoryPreviewStatus>("start_story_preview", {
+        projectPath: project.path,
+        storyId,
+        command: previewCommand(config),
+        env: config.env ?? null,
+      });
+      setStoryPreviews((prev) => ({ ...prev, [storyId]: status }));
+    } catch (e) {
+      notify.error("Failed to start preview", String(e));
+    } finally {
+      setStartingPreview(null);
+    }
+  };
+
+  const handlePruneWorktrees = async () => {
+    if (!project?.path) return;
+    setPruning(true);
+    try {
+      const result = await invoke<PruneWorktreesResult>("prune_worktrees", {
+        projectPath: project.path,
+        olderThan: PRUNE_WORKTREES_OLDER_THAN_DAYS,
+        keepUnmerged: true,
+      });
+      setPruneSummary(
+        `Removed ${result.removed.length} worktree(s), freed ${formatBytes(result.freedBytes)}` +
+          (result.skipped.length > 0 ? `; kept ${result.skipped.length}` : "")
+      );
+      await loadBranches();
+    } catch (e) {
+      setPruneSummary(`Failed to prune worktrees: ${e}`);
+    } finally {
+      setPruning(false);
+    }
+  };
+
   useEffect(() => {
     loadBranches();
   }, [loadBranches]);
@@ -397,6 +527,7 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
               >
                 ~{formatTokenEstimate(buildComplexity.totalEstimatedTokens)} total
               </span>
+              <SpectatorButton projectId={projectId} />
               {hasDependencies && (
                 <button
                   onClick={() => setShowDependencies(!showDependencies)}
@@ -630,6 +761,82 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
 
       {/* Right: Logs and Conflicts */}
       <div className="flex-1 flex flex-col">
+        {/* Budget Warning */}
+        {budgetWarning && (
+          <div className="px-4 py-2 border-b border-border bg-warning/10 flex items-center justify-between gap-3">
+            <p className="text-sm text-warning">
+              {budgetWarning.scope === "build"
+                ? `Build has spent $${budgetWarning.used.toFixed(2)} of its $${budgetWarning.limit.toFixed(2)} budget`
+                : `Story ${budgetWarning.storyId} has used ${Math.round(budgetWarning.used).toLocaleString()} of ${Math.round(budgetWarning.limit).toLocaleString()} tokens`}
+              {budgetWarning.thresholdPercent < 100 ? ` (${budgetWarning.thresholdPercent}% threshold)` : " (limit reached)"}
+            </p>
+            <button
+              onClick={() => setBudgetWarning(projectId, null)}
+              className="text-xs text-muted hover:text-foreground"
+              title="Dismiss"
+            >
+              ×
+            </button>
+          </div>
+        )}
+
+        {/* Cost Forecast */}
+        {forecast && buildStatus !== 'idle' && forecast.remainingStories > 0 && (
+          <div className="px-4 py-2 border-b border-border text-xs text-muted">
+            {forecast.remainingStories} {forecast.remainingStories === 1 ? "story" : "stories"} left
+            {forecast.totalCost !== null && ` · ~$${forecast.totalCost.toFixed(2)} total ($${forecast.spentCost.toFixed(2)} spent)`}
+            {forecast.estimatedCompletionAt && ` · done around ${new Date(forecast.estimatedCompletionAt).toLocaleTimeString()}`}
+            {forecast.basis === "estimates" && " · rough, no earlier builds to go by"}
+          </div>
+        )}
+
+        {/* Build on another computer, followed over LAN sync */}
+        {remoteBuild && buildStatus === 'idle' && (
+          <div className="px-4 py-2 border-b border-border text-xs space-y-1">
+            <div className="text-secondary">
+              {remoteBuild.status === 'paused' ? 'Paused' : 'Building'} on {remoteBuild.peerName}
+              {remoteBuild.currentStoryId && (
+                <span className="text-foreground">
+                  {' '}· {remoteBuild.currentStoryId}: {remoteBuild.currentStoryTitle}
+                </span>
+              )}
+            </div>
+            {remoteBuild.lastLog && <div className="text-muted font-mono truncate">{remoteBuild.lastLog}</div>}
+          </div>
+        )}
+
+        {/* Swarm */}
+        {swarm && buildStatus !== 'idle' && (
+          <div className="px-4 py-2 border-b border-border text-xs space-y-1">
+            <div className="text-secondary">
+              Swarm · {swarm.storyId}: {swarm.storyTitle} · <span className="text-foreground">{SWARM_PHASE_LABELS[swarm.phase]}</span>
+              {swarm.message && <span className="text-destructive"> · {swarm.message}</span>}
+            </div>
+            {swarm.tasks.map((task) => (
+              <div key={task.id} className="flex items-center gap-2 text-muted" title={task.error ?? task.description}>
+                <span
+                  className={`w-1.5 h-1.5 rounded-full flex-shrink-0 ${
+                    task.status === 'done'
+                      ? 'bg-success'
+                      : task.status === 'failed'
+                        ? 'bg-destructive'
+                        : task.status === 'running'
+                          ? 'bg-accent animate-pulse'
+                          : 'bg-muted'
+                  }`}
+                />
+                <span className="truncate">{task.title}</span>
+              </div>
+            ))}
+            {swarm.review && (
+              <div className={swarm.review.approved ? 'text-success' : 'text-destructive'}>
+                Review: {swarm.review.approved ? 'approved' : 'rejected'}
+                {swarm.review.summary && <span className="text-muted"> · {swarm.review.summary}</span>}
+              </div>
+            )}
+          </div>
+        )}
+
         {/* Conflicts Section */}
         {conflictedBranches.length > 0 && (
           <div className="border-b border-border bg-destructive/5">
@@ -832,6 +1039,30 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
                         </span>
                       </div>
                       <div className="flex items-center gap-1 flex-shrink-0 ml-2">
+                        {storyPreviews[branch.storyId]?.url && (
+                          <button
+                            onClick={() => open(storyPreviews[branch.storyId].url!)}
+                            className="text-[10px] font-mono text-accent hover:underline"
+                            title="Open preview"
+                          >
+                            :{storyPreviews[branch.storyId].preview?.assignedPort}
+                          </button>
+                        )}
+                        {branch.status !== "merged" && (
+                          <button
+                            onClick={() => handleTogglePreview(branch.storyId)}
+                            disabled={startingPreview === branch.storyId}
+                            className={`p-1 rounded hover:bg-accent/20 transition-colors ${
+                              storyPreviews[branch.storyId] ? "text-accent" : "text-muted hover:text-accent"
+                            }`}
+                            title={storyPreviews[branch.storyId] ? "Stop preview" : "Preview story"}
+                          >
+                            <svg className="w-3.5 h-3.5" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M15 12a3 3 0 11-6 0 3 3 0 016 0z" />
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M2.458 12C3.732 7.943 7.523 5 12 5c4.478 0 8.268 2.943 9.542 7-1.274 4.057-5.064 7-9.542 7-4.477 0-8.268-2.943-9.542-7z" />
+                            </svg>
+                          </button>
+                        )}
                         {branch.status !== "merged" && (
                           <button
                             onClick={() => setDiffViewerStory({ id: branch.storyId, title: branch.branchName, branchName: branch.branchName })}
@@ -939,6 +1170,27 @@ export function BuildStatusContent({ projectId }: BuildStatusContentProps) {
               )}
             </div>
           )}
+          {showBranches && worktreeUsage && worktreeUsage.worktrees.length > 0 && (
+            <div className="px-4 py-2 border-t border-border flex items-center justify-between gap-2">
+              <span
+                className="text-xs text-muted truncate"
+                title={worktreeUsage.worktrees
+                  .map((w) => `${w.branchName ?? w.path}: ${formatBytes(w.sizeBytes)}, ${w.ageDays}d old`)
+                  .join("\n")}
+              >
+                {pruneSummary ??
+                  `${worktreeUsage.worktrees.length} worktree(s) using ${formatBytes(worktreeUsage.totalBytes)}`}
+              </span>
+              <button
+                onClick={handlePruneWorktrees}
+                disabled={pruning}
+                className="text-xs px-2 py-1 rounded bg-accent/10 text-accent hover:bg-accent/20 transition-colors flex-shrink-0"
+                title={`Remove clean worktrees older than ${PRUNE_WORKTREES_OLDER_THAN_DAYS} days, keeping unmerged ones`}
+              >
+                {pruning ? "Pruning..." : "Prune"}
+              </button>
+            </div>
+          )}
         </div>
 
         <div className="px-4 py-3 border-b border-border bg-background-secondary flex items-center justify-between">
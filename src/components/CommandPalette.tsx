@@ -43,7 +43,7 @@ export function CommandPalette({
     activeProjectId ? state.projectStates[activeProjectId] : null
   );
   const buildStatus = projectState?.status ?? "idle";
-  const pauseBuild = useBuildStore((state) => state.pauseBuild);
+  const requestPause = useBuildStore((state) => state.requestPause);
 
   const toggleLogPanel = usePanelStore((state) => state.toggleLogPanel);
   const toggleTerminalPanel = usePanelStore((state) => state.toggleTerminalPanel);
@@ -162,7 +162,7 @@ export function CommandPalette({
       disabled: !activeProjectId || buildStatus !== "running",
       action: () => {
         if (activeProjectId && buildStatus === "running") {
-          pauseBuild(activeProjectId);
+          requestPause(activeProjectId);
           onClose();
         }
       },
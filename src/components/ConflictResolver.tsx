@@ -94,11 +94,28 @@ function computeLCS(base: string[], updated: string[]): LCSMatch[] {
   return matches;
 }
 
+interface FileVersionInfo {
+  size: number;
+  sha256: string;
+}
+
 interface ConflictFileInfo {
   filePath: string;
+  /** Empty for binary files */
   oursContent: string;
   theirsContent: string;
   baseContent: string;
+  binary: boolean;
+  resolutions: FileResolution["strategy"][];
+  ours: FileVersionInfo | null;
+  theirs: FileVersionInfo | null;
+  base: FileVersionInfo | null;
+}
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
 }
 
 interface MergeConflictAnalysis {
@@ -109,7 +126,18 @@ interface MergeConflictAnalysis {
 
 interface FileResolution {
   filePath: string;
-  strategy: "ours" | "theirs" | "both";
+  strategy: "ours" | "theirs" | "both" | "manual";
+  /** The edited file, for "manual" */
+  content?: string;
+}
+
+interface MergePreview {
+  filePath: string;
+  baseContent: string;
+  oursContent: string;
+  theirsContent: string;
+  mergedContent: string;
+  conflictCount: number;
 }
 
 interface ConflictResolverProps {
@@ -138,6 +166,8 @@ export function ConflictResolver({
   const [resolutions, setResolutions] = useState<Record<string, FileResolution["strategy"]>>({});
   const [merging, setMerging] = useState(false);
   const [viewMode, setViewMode] = useState<ViewMode>("side-by-side");
+  // Hand-edited content of files resolved "manual"
+  const [manualContent, setManualContent] = useState<Record<string, string>>({});
 
   useModalKeyboard(isOpen, onClose);
 
@@ -145,12 +175,12 @@ export function ConflictResolver({
 
   // Compute diffs for the selected file
   const oursDiff = useMemo(() => {
-    if (!selectedFileInfo) return [];
+    if (!selectedFileInfo || selectedFileInfo.binary) return [];
     return computeLineDiff(selectedFileInfo.baseContent, selectedFileInfo.oursContent);
   }, [selectedFileInfo]);
 
   const theirsDiff = useMemo(() => {
-    if (!selectedFileInfo) return [];
+    if (!selectedFileInfo || selectedFileInfo.binary) return [];
     return computeLineDiff(selectedFileInfo.baseContent, selectedFileInfo.theirsContent);
   }, [selectedFileInfo]);
 
@@ -190,9 +220,27 @@ export function ConflictResolver({
       setSelectedFile(null);
       setError(null);
       setResolutions({});
+      setManualContent({});
     }
   }, [isOpen, loadAnalysis]);
 
+  const handleEditManually = async (filePath: string) => {
+    if (manualContent[filePath] === undefined) {
+      try {
+        const preview = await invoke<MergePreview>("get_merge_preview", {
+          projectPath,
+          branchName,
+          filePath,
+        });
+        setManualContent((c) => ({ ...c, [filePath]: preview.mergedContent }));
+      } catch (e) {
+        setError(e as string);
+        return;
+      }
+    }
+    setResolutions((r) => ({ ...r, [filePath]: "manual" }));
+  };
+
   const handleMerge = async () => {
     if (!analysis) return;
 
@@ -201,6 +249,7 @@ export function ConflictResolver({
       const fileResolutions: FileResolution[] = analysis.conflictingFiles.map((file) => ({
         filePath: file.filePath,
         strategy: resolutions[file.filePath] || "theirs",
+        content: resolutions[file.filePath] === "manual" ? manualContent[file.filePath] : undefined,
       }));
 
       await invoke("merge_with_resolutions", {
@@ -323,6 +372,9 @@ export function ConflictResolver({
                       <span className="flex-1 font-mono text-xs text-foreground truncate" title={file.filePath}>
                         {file.filePath.split("/").pop()}
                       </span>
+                      {file.binary && (
+                        <span className="text-[10px] px-1.5 py-0.5 rounded bg-muted/20 text-muted">binary</span>
+                      )}
                       <span
                         className={`text-[10px] px-1.5 py-0.5 rounded font-medium ${
                           resolutions[file.filePath] === "ours"
@@ -331,6 +383,8 @@ export function ConflictResolver({
                             ? "bg-success/20 text-success"
                             : resolutions[file.filePath] === "both"
                             ? "bg-purple-500/20 text-purple-400"
+                            : resolutions[file.filePath] === "manual"
+                            ? "bg-warning/20 text-warning"
                             : "bg-muted/20 text-muted"
                         }`}
                       >
@@ -349,7 +403,7 @@ export function ConflictResolver({
                     <div className="px-4 py-3 border-b border-border bg-background-secondary">
                       <div className="flex items-center justify-between mb-2">
                         <span className="font-mono text-sm text-foreground">{selectedFileInfo.filePath}</span>
-                        <div className="flex items-center gap-1">
+                        <div className={`flex items-center gap-1 ${selectedFileInfo.binary ? "hidden" : ""}`}>
                           <button
                             onClick={() => setViewMode("side-by-side")}
                             className={`text-xs px-2 py-1 rounded ${
@@ -404,25 +458,83 @@ export function ConflictResolver({
                           </svg>
                           Keep Theirs (story)
                         </button>
-                        <button
-                          onClick={() => setResolutions((r) => ({ ...r, [selectedFileInfo.filePath]: "both" }))}
-                          className={`text-xs px-2 py-1 rounded flex items-center gap-1 ${
-                            resolutions[selectedFileInfo.filePath] === "both"
-                              ? "bg-purple-500 text-white"
-                              : "bg-purple-500/20 text-purple-400 hover:bg-purple-500/30"
-                          }`}
-                        >
-                          <svg className="w-3 h-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M8 7h12m0 0l-4-4m4 4l-4 4m0 6H4m0 0l4 4m-4-4l4-4" />
-                          </svg>
-                          Keep Both
-                        </button>
+                        {selectedFileInfo.resolutions.includes("both") && (
+                          <button
+                            onClick={() => setResolutions((r) => ({ ...r, [selectedFileInfo.filePath]: "both" }))}
+                            className={`text-xs px-2 py-1 rounded flex items-center gap-1 ${
+                              resolutions[selectedFileInfo.filePath] === "both"
+                                ? "bg-purple-500 text-white"
+                                : "bg-purple-500/20 text-purple-400 hover:bg-purple-500/30"
+                            }`}
+                          >
+                            <svg className="w-3 h-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M8 7h12m0 0l-4-4m4 4l-4 4m0 6H4m0 0l4 4m-4-4l4-4" />
+                            </svg>
+                            Keep Both
+                          </button>
+                        )}
+                        {!selectedFileInfo.binary && (
+                          <button
+                            onClick={() => handleEditManually(selectedFileInfo.filePath)}
+                            className={`text-xs px-2 py-1 rounded flex items-center gap-1 ${
+                              resolutions[selectedFileInfo.filePath] === "manual"
+                                ? "bg-warning text-white"
+                                : "bg-warning/20 text-warning hover:bg-warning/30"
+                            }`}
+                          >
+                            <svg className="w-3 h-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z" />
+                            </svg>
+                            Edit Manually
+                          </button>
+                        )}
                       </div>
                     </div>
 
                     {/* Content view */}
                     <div className="flex-1 overflow-hidden flex">
-                      {viewMode === "side-by-side" ? (
+                      {resolutions[selectedFileInfo.filePath] === "manual" ? (
+                        <div className="flex-1 flex flex-col">
+                          <div className="px-3 py-1 bg-warning/10 border-b border-border">
+                            <span className="text-xs font-medium text-warning">
+                              Merged file. Resolve each conflict and remove its markers.
+                            </span>
+                          </div>
+                          <textarea
+                            value={manualContent[selectedFileInfo.filePath] ?? ""}
+                            onChange={(e) =>
+                              setManualContent((c) => ({ ...c, [selectedFileInfo.filePath]: e.target.value }))
+                            }
+                            spellCheck={false}
+                            className="flex-1 p-2 bg-background font-mono text-xs text-foreground resize-none outline-none"
+                          />
+                        </div>
+                      ) : selectedFileInfo.binary ? (
+                        <div className="flex-1 overflow-auto scrollbar-auto-hide p-4">
+                          <p className="text-sm text-muted mb-3">
+                            Binary file. Keep one version; the contents can't be combined.
+                          </p>
+                          <table className="text-xs font-mono">
+                            <tbody>
+                              {([
+                                ["Base", selectedFileInfo.base],
+                                ["Ours (main)", selectedFileInfo.ours],
+                                ["Theirs (story)", selectedFileInfo.theirs],
+                              ] as const).map(([label, version]) => (
+                                <tr key={label}>
+                                  <td className="pr-4 py-1 text-muted">{label}</td>
+                                  <td className="pr-4 py-1 text-foreground">
+                                    {version ? formatSize(version.size) : "not present"}
+                                  </td>
+                                  <td className="py-1 text-muted" title={version?.sha256}>
+                                    {version ? version.sha256.slice(0, 12) : ""}
+                                  </td>
+                                </tr>
+                              ))}
+                            </tbody>
+                          </table>
+                        </div>
+                      ) : viewMode === "side-by-side" ? (
                         <>
                           {/* Ours side */}
                           <div className="flex-1 flex flex-col border-r border-border">
@@ -0,0 +1,120 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface ControlApiInfo {
+  running: boolean;
+  port: number;
+  url: string;
+  token: string | null;
+}
+
+interface ControlApiSectionProps {
+  enabled: boolean;
+  port: number;
+  onEnabledChange: (enabled: boolean) => void;
+  onPortChange: (port: number) => void;
+}
+
+export function ControlApiSection({ enabled, port, onEnabledChange, onPortChange }: ControlApiSectionProps) {
+  const [info, setInfo] = useState<ControlApiInfo | null>(null);
+  const [showToken, setShowToken] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ControlApiInfo>("get_control_api_info")
+      .then(setInfo)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  const handleRegenerate = async () => {
+    setError(null);
+    try {
+      setInfo(await invoke<ControlApiInfo>("regenerate_control_api_token"));
+      setShowToken(true);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Control API
+      </h3>
+      <div className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium text-foreground">
+              Enable Local HTTP API
+            </label>
+            <p className="text-xs text-muted mt-0.5">
+              Lets scripts on this computer list projects and start or stop builds. Applies when saved.
+            </p>
+          </div>
+          <button
+            onClick={() => onEnabledChange(!enabled)}
+            className={`relative w-11 h-6 rounded-full transition-colors ${
+              enabled ? "bg-accent" : "bg-muted/30"
+            }`}
+          >
+            <span
+              className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                enabled ? "translate-x-5" : ""
+              }`}
+            />
+          </button>
+        </div>
+
+        {enabled && (
+          <>
+            <div>
+              <label className="block text-sm text-foreground mb-2">Port</label>
+              <input
+                type="number"
+                value={port}
+                onChange={(e) => onPortChange(parseInt(e.target.value) || 7457)}
+                min={1024}
+                max={65535}
+                className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+              />
+              {info?.running && (
+                <p className="text-xs text-muted mt-1">Listening on {info.url}</p>
+              )}
+            </div>
+
+            <div>
+              <label className="block text-sm text-foreground mb-2">Access Token</label>
+              <div className="flex items-center gap-2">
+                <code className="flex-1 px-3 py-2 rounded-lg border border-border bg-background text-xs text-foreground truncate">
+                  {info?.token ? (showToken ? info.token : "•".repeat(24)) : "Generated when the API starts"}
+                </code>
+                {info?.token && (
+                  <>
+                    <button onClick={() => setShowToken(!showToken)} className="btn btn-sm btn-secondary">
+                      {showToken ? "Hide" : "Show"}
+                    </button>
+                    <button
+                      onClick={() => navigator.clipboard.writeText(info.token ?? "")}
+                      className="btn btn-sm btn-secondary"
+                    >
+                      Copy
+                    </button>
+                  </>
+                )}
+                <button onClick={handleRegenerate} className="btn btn-sm btn-secondary">
+                  Regenerate
+                </button>
+              </div>
+              <p className="text-xs text-muted mt-1">
+                Send as <code>Authorization: Bearer &lt;token&gt;</code>. Live events stream over a
+                WebSocket at <code>/api/events?token=&lt;token&gt;</code>, optionally filtered with{" "}
+                <code>&amp;projects=&lt;id&gt;</code>.
+              </p>
+            </div>
+          </>
+        )}
+        {error && <p className="text-xs text-destructive">{error}</p>}
+      </div>
+    </section>
+  );
+}
@@ -1,8 +1,26 @@
 import { useEffect, useMemo, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useCostStore, type CostEntry, type AmpUsageEntry, type ClaudeUsageEntry } from "../stores/costStore";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
+import { formatCost, useDisplayCurrency } from "../hooks/useDisplayCurrency";
+import { SpendingReportPanel } from "./SpendingReportPanel";
+import { BuildInsightsPanel } from "./BuildInsightsPanel";
+import { UsageInspector } from "./UsageInspector";
+import { notify } from "../utils/notify";
 import { creditsToUsd, estimateApiCost, getAgentConfig } from "../utils/agentPricing";
 
+interface UsageScanProgress {
+  source: "amp" | "claude";
+  scanned: number;
+  total: number;
+}
+
+function formatScanProgress(progress: UsageScanProgress | undefined): string {
+  if (!progress || progress.total === 0) return "";
+  return ` (${formatNumber(progress.scanned)} / ${formatNumber(progress.total)})`;
+}
+
 interface CostModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -19,10 +37,6 @@ function formatCredits(credits: number): string {
   return credits.toFixed(2);
 }
 
-function formatCurrency(amount: number): string {
-  return '$' + amount.toFixed(2);
-}
-
 function formatDate(date: Date | string): string {
   const d = typeof date === 'string' ? new Date(date) : date;
   return new Intl.DateTimeFormat('en-US', {
@@ -65,13 +79,15 @@ function formatProjectPath(path: string): string {
   return path.replace(/^-/, '/').replace(/-/g, '/').split('/').slice(-2).join('/');
 }
 
-type TabType = 'global' | 'project';
+type TabType = 'global' | 'project' | 'report' | 'insights';
 type AgentType = 'amp' | 'claude';
 
 export function CostModal({ isOpen, onClose, projectId, projectPath, projectName }: CostModalProps) {
   const [activeTab, setActiveTab] = useState<TabType>('project');
   const [activeAgent, setActiveAgent] = useState<AgentType>('amp');
+  const [inspectedId, setInspectedId] = useState<string | null>(null);
   const [isLoadingProject, setIsLoadingProject] = useState(false);
+  const currency = useDisplayCurrency();
   
   const allEntries = useCostStore((state) => state.entries);
   const clearEntries = useCostStore((state) => state.clearEntries);
@@ -99,6 +115,17 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
   );
 
   const isLoadingGlobal = isLoadingAmpUsage || isLoadingClaudeUsage;
+  const [scanProgress, setScanProgress] = useState<Partial<Record<UsageScanProgress["source"], UsageScanProgress>>>({});
+
+  useEffect(() => {
+    if (!isOpen) return;
+    const unlisten = listen<UsageScanProgress>("usage-scan-progress", (event) => {
+      setScanProgress((prev) => ({ ...prev, [event.payload.source]: event.payload }));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isOpen]);
 
   // Calculate costs
   const ampCosts = useMemo(() => {
@@ -153,6 +180,15 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
     clearEntries();
   };
 
+  const handleAttributeBuilds = async () => {
+    try {
+      const summary = await invoke<{ added: number }>('reconcile_build_costs', { projectPath });
+      notify.success('Build usage attributed', `${summary.added} new ${summary.added === 1 ? 'entry' : 'entries'} from agent logs`);
+    } catch (error) {
+      notify.error('Failed to attribute build usage', String(error));
+    }
+  };
+
   const handleRefreshUsage = async () => {
     await refreshAllAgentUsage();
   };
@@ -203,9 +239,33 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
           >
             Global Usage
           </button>
+          <button
+            onClick={() => setActiveTab('report')}
+            className={`px-4 py-2.5 text-sm font-medium border-b-2 -mb-px transition-colors ${
+              activeTab === 'report'
+                ? 'border-accent text-accent'
+                : 'border-transparent text-muted hover:text-foreground'
+            }`}
+          >
+            Monthly Report
+          </button>
+          <button
+            onClick={() => setActiveTab('insights')}
+            className={`px-4 py-2.5 text-sm font-medium border-b-2 -mb-px transition-colors ${
+              activeTab === 'insights'
+                ? 'border-accent text-accent'
+                : 'border-transparent text-muted hover:text-foreground'
+            }`}
+          >
+            Build Insights
+          </button>
         </div>
 
-        {activeTab === 'global' ? (
+        {activeTab === 'report' ? (
+          <SpendingReportPanel />
+        ) : activeTab === 'insights' ? (
+          <BuildInsightsPanel />
+        ) : activeTab === 'global' ? (
           <>
             {/* Agent Selector */}
             <div className="px-6 py-3 border-b border-border flex-shrink-0">
@@ -307,13 +367,13 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                               <div className="flex justify-between">
                                 <span className="text-sm text-secondary">Cost (Credits)</span>
                                 <span className="text-sm font-medium text-accent">
-                                  {formatCurrency(ampCosts.realCost)}
+                                  {formatCost(ampCosts.realCost, currency)}
                                 </span>
                               </div>
                               <div className="flex justify-between">
                                 <span className="text-sm text-secondary">Est. API Cost</span>
                                 <span className="text-sm font-medium text-muted" title="Estimated cost at direct API rates">
-                                  {formatCurrency(ampCosts.estimatedApiCost)}
+                                  {formatCost(ampCosts.estimatedApiCost, currency)}
                                 </span>
                               </div>
                             </div>
@@ -347,7 +407,7 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                       <svg className="w-8 h-8 text-accent animate-spin mb-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15" />
                       </svg>
-                      <p className="text-sm text-muted">Scanning Amp threads...</p>
+                      <p className="text-sm text-muted">Scanning Amp threads...{formatScanProgress(scanProgress.amp)}</p>
                     </div>
                   ) : !ampUsage || ampUsage.entries.length === 0 ? (
                     <div className="text-center py-8">
@@ -362,7 +422,8 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                       {ampUsage.entries.slice(-50).reverse().map((entry: AmpUsageEntry, index: number) => (
                         <div
                           key={`${entry.threadId}-${index}`}
-                          className="p-3 rounded-lg bg-background border border-border"
+                          className="p-3 rounded-lg bg-background border border-border cursor-pointer"
+                          onClick={() => setInspectedId(inspectedId === entry.threadId ? null : entry.threadId)}
                         >
                           <div className="flex items-start justify-between gap-4">
                             <div className="flex-1 min-w-0">
@@ -407,6 +468,7 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                               {formatCredits(entry.credits)} credits
                             </span>
                           </div>
+                          {inspectedId === entry.threadId && <UsageInspector agentId="amp" id={entry.threadId} />}
                         </div>
                       ))}
                     </div>
@@ -473,7 +535,7 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                               <div className="flex justify-between">
                                 <span className="text-sm text-secondary">Est. API Cost</span>
                                 <span className="text-sm font-medium text-accent" title="Estimated cost at direct API rates">
-                                  {formatCurrency(claudeCosts.estimatedApiCost)}
+                                  {formatCost(claudeCosts.estimatedApiCost, currency)}
                                 </span>
                               </div>
                               <div className="flex items-start justify-between">
@@ -518,7 +580,7 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                       <svg className="w-8 h-8 text-accent animate-spin mb-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15" />
                       </svg>
-                      <p className="text-sm text-muted">Scanning Claude Code sessions...</p>
+                      <p className="text-sm text-muted">Scanning Claude Code sessions...{formatScanProgress(scanProgress.claude)}</p>
                     </div>
                   ) : !claudeUsage || claudeUsage.entries.length === 0 ? (
                     <div className="text-center py-8">
@@ -533,7 +595,8 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                       {claudeUsage.entries.slice(-50).reverse().map((entry: ClaudeUsageEntry, index: number) => (
                         <div
                           key={`${entry.sessionId}-${index}`}
-                          className="p-3 rounded-lg bg-background border border-border"
+                          className="p-3 rounded-lg bg-background border border-border cursor-pointer"
+                          onClick={() => setInspectedId(inspectedId === entry.sessionId ? null : entry.sessionId)}
                         >
                           <div className="flex items-start justify-between gap-4">
                             <div className="flex-1 min-w-0">
@@ -559,6 +622,7 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                               {formatNumber(entry.totalTokens)} tokens
                             </span>
                           </div>
+                          {inspectedId === entry.sessionId && <UsageInspector agentId="claude-code" id={entry.sessionId} />}
                         </div>
                       ))}
                     </div>
@@ -724,6 +788,13 @@ export function CostModal({ isOpen, onClose, projectId, projectPath, projectName
                 >
                   Clear All
                 </button>
+                <button
+                  onClick={handleAttributeBuilds}
+                  className="px-3 py-1.5 rounded-lg text-xs text-muted hover:text-foreground hover:bg-card transition-colors"
+                  title="Add Amp threads and Claude Code sessions that ran during this project's builds"
+                >
+                  Attribute Build Usage
+                </button>
               </>
             )}
           </div>
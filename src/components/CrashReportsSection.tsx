@@ -0,0 +1,84 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface CrashReport {
+  id: string;
+  createdAt: string;
+  appVersion: string;
+  os: string;
+  message: string;
+  location: string | null;
+  submittedAt: string | null;
+}
+
+export function CrashReportsSection() {
+  const [reports, setReports] = useState<CrashReport[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadReports = async () => {
+    try {
+      setReports(await invoke<CrashReport[]>("get_crash_reports"));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  useEffect(() => {
+    loadReports();
+  }, []);
+
+  const handleSubmit = async (id: string) => {
+    setError(null);
+    try {
+      await invoke("submit_crash_report", { id });
+      await loadReports();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    setError(null);
+    try {
+      await invoke("delete_crash_report", { id });
+      setReports((prev) => prev.filter((r) => r.id !== id));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  if (reports.length === 0 && !error) return null;
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Crash Reports
+      </h3>
+      <p className="text-xs text-muted mb-3">
+        Reports stay on this computer. Reporting one opens a prefilled GitHub issue you can review before submitting.
+      </p>
+      <div className="space-y-2">
+        {reports.map((report) => (
+          <div key={report.id} className="flex items-center justify-between gap-3 p-3 rounded-lg border border-border">
+            <div className="min-w-0">
+              <p className="text-sm text-foreground truncate">{report.message}</p>
+              <p className="text-xs text-muted">
+                {new Date(report.createdAt).toLocaleString()} · v{report.appVersion} · {report.os}
+                {report.submittedAt && " · Reported"}
+              </p>
+            </div>
+            <div className="flex gap-2 shrink-0">
+              <button onClick={() => handleSubmit(report.id)} className="btn btn-sm btn-secondary">
+                Report
+              </button>
+              <button onClick={() => handleDelete(report.id)} className="btn btn-sm btn-secondary">
+                Delete
+              </button>
+            </div>
+          </div>
+        ))}
+      </div>
+      {error && <p className="text-xs text-destructive mt-2">{error}</p>}
+    </section>
+  );
+}
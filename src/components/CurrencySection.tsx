@@ -0,0 +1,110 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { reloadDisplayCurrency, useDisplayCurrency, type DisplayCurrency } from "../hooks/useDisplayCurrency";
+import { notify } from "../utils/notify";
+
+export interface CurrencyConfig {
+  code: string;
+  rateSource: "manual" | "fetch";
+  manualRate: number | null;
+}
+
+const COMMON_CURRENCIES = ["USD", "EUR", "GBP", "JPY", "CAD", "AUD", "CHF", "CNY", "INR", "BRL", "SEK", "NZD"];
+
+const inputClass =
+  "w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent placeholder:text-secondary/60";
+
+interface CurrencySectionProps {
+  config: CurrencyConfig;
+  onChange: (config: CurrencyConfig) => void;
+}
+
+export function CurrencySection({ config, onChange }: CurrencySectionProps) {
+  const current = useDisplayCurrency();
+  const [isRefreshing, setIsRefreshing] = useState(false);
+  const isUsd = config.code.trim().toUpperCase() === "USD";
+
+  const handleRefresh = async () => {
+    setIsRefreshing(true);
+    try {
+      await invoke<DisplayCurrency>("refresh_exchange_rates");
+      await reloadDisplayCurrency();
+      notify.success("Exchange rates updated");
+    } catch (error) {
+      notify.error("Failed to refresh exchange rates", String(error));
+    } finally {
+      setIsRefreshing(false);
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Currency
+      </h3>
+      <div className="space-y-4">
+        <div>
+          <label className="block text-sm text-foreground mb-2">Display Currency</label>
+          <input
+            type="text"
+            list="currency-codes"
+            value={config.code}
+            onChange={(e) => onChange({ ...config, code: e.target.value.toUpperCase().slice(0, 3) })}
+            placeholder="USD"
+            className={inputClass}
+          />
+          <datalist id="currency-codes">
+            {COMMON_CURRENCIES.map((code) => (
+              <option key={code} value={code} />
+            ))}
+          </datalist>
+          <p className="text-xs text-muted mt-1">
+            Costs are recorded in USD and also shown in this currency. Budget limits stay in USD.
+          </p>
+        </div>
+
+        {!isUsd && (
+          <>
+            <div>
+              <label className="block text-sm text-foreground mb-2">Exchange Rate</label>
+              <select
+                value={config.rateSource}
+                onChange={(e) => onChange({ ...config, rateSource: e.target.value as CurrencyConfig["rateSource"] })}
+                className={inputClass}
+              >
+                <option value="fetch">Fetch current rates</option>
+                <option value="manual">Enter a fixed rate</option>
+              </select>
+            </div>
+
+            {config.rateSource === "manual" ? (
+              <div>
+                <label className="block text-sm text-foreground mb-2">{config.code || "Units"} per USD</label>
+                <input
+                  type="number"
+                  value={config.manualRate ?? ""}
+                  onChange={(e) => onChange({ ...config, manualRate: e.target.value ? parseFloat(e.target.value) : null })}
+                  min={0}
+                  step="0.0001"
+                  placeholder="e.g. 0.92"
+                  className={inputClass}
+                />
+              </div>
+            ) : (
+              <div className="flex items-center justify-between gap-3">
+                <p className="text-xs text-muted">
+                  {current.source === "fetched"
+                    ? `1 USD = ${current.rate.toFixed(4)} ${current.code}, updated ${new Date(current.updatedAt ?? "").toLocaleString()}`
+                    : "No rate fetched yet. Costs show in USD until one is."}
+                </p>
+                <button onClick={handleRefresh} disabled={isRefreshing} className="btn btn-sm btn-secondary shrink-0">
+                  {isRefreshing ? "Refreshing…" : "Refresh Rates"}
+                </button>
+              </div>
+            )}
+          </>
+        )}
+      </div>
+    </section>
+  );
+}
@@ -0,0 +1,127 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useModalKeyboard } from "../hooks/useModalKeyboard";
+import { notify } from "../utils/notify";
+
+export interface ConflictCopy {
+  name: string;
+  hash: string;
+  modifiedAt: string | null;
+  valid: boolean;
+}
+
+export interface DataConflict {
+  file: string;
+  modifiedAt: string | null;
+  copies: ConflictCopy[];
+}
+
+type Resolution = "keepMine" | "keepTheirs" | "merge";
+
+interface DataConflictsModalProps {
+  isOpen: boolean;
+  conflicts: DataConflict[];
+  onClose: () => void;
+}
+
+function formatTime(time: string | null) {
+  return time ? new Date(time).toLocaleString() : "unknown";
+}
+
+export function DataConflictsModal({ isOpen, conflicts, onClose }: DataConflictsModalProps) {
+  const [resolved, setResolved] = useState<Set<string>>(new Set());
+  const [busy, setBusy] = useState<string | null>(null);
+
+  useModalKeyboard(isOpen, onClose);
+
+  if (!isOpen) return null;
+
+  const remaining = conflicts
+    .map((conflict) => ({
+      ...conflict,
+      copies: conflict.copies.filter((copy) => !resolved.has(copy.name)),
+    }))
+    .filter((conflict) => conflict.copies.length > 0);
+
+  const handleResolve = async (file: string, copy: ConflictCopy, resolution: Resolution) => {
+    setBusy(copy.name);
+    try {
+      await invoke("resolve_data_conflict", { file, copy: copy.name, hash: copy.hash, resolution });
+      const next = new Set(resolved).add(copy.name);
+      setResolved(next);
+      if (conflicts.every((c) => c.copies.every((cp) => next.has(cp.name)))) {
+        notify.success("Sync conflicts resolved", "Reloading…");
+        // Stores were loaded from the documents before they changed
+        setTimeout(() => window.location.reload(), 1000);
+      }
+    } catch (error) {
+      notify.error("Failed to resolve conflict", String(error));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black/60 flex items-center justify-center z-50 p-4">
+      <div className="bg-card border border-border rounded-xl shadow-2xl max-w-2xl w-full max-h-[80vh] flex flex-col overflow-hidden">
+        <div className="p-6 border-b border-border">
+          <h2 className="text-xl font-semibold text-foreground">Sync Conflicts Found</h2>
+          <p className="text-sm text-muted mt-1">
+            Your sync service saved conflicting copies of Ideate's data. Choose which version to keep for each one.
+          </p>
+        </div>
+
+        <div className="p-6 space-y-4 overflow-y-auto">
+          {remaining.map((conflict) => (
+            <section key={conflict.file}>
+              <h3 className="text-sm font-medium text-foreground mb-1">
+                <code>{conflict.file}</code>
+              </h3>
+              <p className="text-xs text-muted mb-2">This computer's version: modified {formatTime(conflict.modifiedAt)}</p>
+              <div className="space-y-2">
+                {conflict.copies.map((copy) => (
+                  <div key={copy.name} className="p-3 rounded-lg border border-border">
+                    <p className="text-sm text-foreground truncate">{copy.name}</p>
+                    <p className="text-xs text-muted mb-2">
+                      Modified {formatTime(copy.modifiedAt)}
+                      {!copy.valid && " · Damaged, can only be discarded"}
+                    </p>
+                    <div className="flex gap-2">
+                      <button
+                        onClick={() => handleResolve(conflict.file, copy, "keepMine")}
+                        disabled={busy !== null}
+                        className="btn btn-sm btn-secondary"
+                      >
+                        Keep Mine
+                      </button>
+                      <button
+                        onClick={() => handleResolve(conflict.file, copy, "keepTheirs")}
+                        disabled={busy !== null || !copy.valid}
+                        className="btn btn-sm btn-secondary"
+                      >
+                        Keep Theirs
+                      </button>
+                      <button
+                        onClick={() => handleResolve(conflict.file, copy, "merge")}
+                        disabled={busy !== null || !copy.valid}
+                        className="btn btn-sm btn-secondary"
+                      >
+                        Merge
+                      </button>
+                    </div>
+                  </div>
+                ))}
+              </div>
+            </section>
+          ))}
+        </div>
+
+        <div className="p-4 border-t border-border flex justify-end">
+          <button onClick={onClose} className="btn btn-sm btn-secondary">
+            Decide Later
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
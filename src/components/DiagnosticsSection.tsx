@@ -0,0 +1,146 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { notify } from "../utils/notify";
+
+interface OperationReport {
+  operation: string;
+  count: number;
+  totalMs: number;
+  meanMs: number;
+  p95Ms: number;
+  maxMs: number;
+  lastMs: number;
+  filesTotal: number;
+  filesMax: number;
+}
+
+interface PerformanceSample {
+  operation: string;
+  subject: string | null;
+  durationMs: number;
+  files: number | null;
+  finishedAt: string;
+}
+
+interface PerformanceReport {
+  since: string;
+  operations: OperationReport[];
+  slowest: PerformanceSample[];
+}
+
+/** Slowest runs shown; the copied report has them all */
+const SLOWEST_SHOWN = 5;
+
+function ms(value: number): string {
+  return value >= 1000 ? `${(value / 1000).toFixed(1)}s` : `${value}ms`;
+}
+
+export function DiagnosticsSection() {
+  const [isBusy, setIsBusy] = useState(false);
+  const [report, setReport] = useState<PerformanceReport | null>(null);
+
+  const handleGenerate = async () => {
+    setIsBusy(true);
+    try {
+      const path = await invoke<string>("generate_diagnostics_bundle");
+      notify.success("Diagnostics bundle saved", path.split(/[\\/]/).pop());
+    } catch (error) {
+      notify.error("Failed to create diagnostics bundle", String(error));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleLoadReport = async () => {
+    try {
+      setReport(await invoke<PerformanceReport>("get_performance_report"));
+    } catch (error) {
+      notify.error("Failed to load performance report", String(error));
+    }
+  };
+
+  const handleCopyReport = async () => {
+    if (!report) return;
+    await navigator.clipboard.writeText(JSON.stringify(report, null, 2));
+    notify.success("Performance report copied");
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Diagnostics
+      </h3>
+      <p className="text-xs text-muted mb-3">
+        Bundle app logs, recent process history, the last build&apos;s events, timings of slow operations, and app, OS, and agent versions into a zip to attach to a GitHub issue. Secrets and your home directory are removed.
+      </p>
+      <div className="flex gap-2">
+        <button onClick={handleGenerate} disabled={isBusy} className="btn btn-sm btn-secondary">
+          {isBusy ? "Collecting…" : "Create Diagnostics Bundle"}
+        </button>
+        <button onClick={handleLoadReport} className="btn btn-sm btn-secondary">
+          {report ? "Refresh Performance" : "Show Performance"}
+        </button>
+        {report && (
+          <button onClick={handleCopyReport} className="btn btn-sm btn-secondary">
+            Copy Report
+          </button>
+        )}
+      </div>
+
+      {report && (
+        <div className="mt-4 space-y-3">
+          <p className="text-xs text-muted">
+            Usage scans, diffs, and worktree operations since {new Date(report.since).toLocaleString()}.
+          </p>
+          {report.operations.length === 0 ? (
+            <p className="text-xs text-muted">Nothing measured yet.</p>
+          ) : (
+            <div className="border border-border rounded-lg overflow-hidden">
+              <table className="w-full text-xs">
+                <thead className="bg-background-secondary text-muted">
+                  <tr>
+                    <th className="px-3 py-2 text-left font-medium">Operation</th>
+                    <th className="px-3 py-2 text-right font-medium">Runs</th>
+                    <th className="px-3 py-2 text-right font-medium">Total</th>
+                    <th className="px-3 py-2 text-right font-medium">p95</th>
+                    <th className="px-3 py-2 text-right font-medium">Max</th>
+                    <th className="px-3 py-2 text-right font-medium">Most Files</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {report.operations.map((op) => (
+                    <tr key={op.operation} className="border-t border-border">
+                      <td className="px-3 py-1.5 font-mono text-foreground">{op.operation}</td>
+                      <td className="px-3 py-1.5 text-right text-muted">{op.count}</td>
+                      <td className="px-3 py-1.5 text-right text-foreground">{ms(op.totalMs)}</td>
+                      <td className="px-3 py-1.5 text-right text-muted">{ms(op.p95Ms)}</td>
+                      <td className="px-3 py-1.5 text-right text-muted">{ms(op.maxMs)}</td>
+                      <td className="px-3 py-1.5 text-right text-muted">{op.filesMax.toLocaleString()}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+          {report.slowest.length > 0 && (
+            <div className="space-y-1">
+              <h4 className="text-xs font-medium text-secondary">Slowest Runs</h4>
+              {report.slowest.slice(0, SLOWEST_SHOWN).map((sample) => (
+                <div key={`${sample.operation}-${sample.finishedAt}`} className="flex items-center justify-between gap-3 text-xs">
+                  <span className="min-w-0 truncate" title={sample.subject ?? undefined}>
+                    <span className="font-mono text-foreground">{sample.operation}</span>
+                    {sample.subject && <span className="text-muted"> · {sample.subject}</span>}
+                  </span>
+                  <span className="flex-shrink-0 text-muted">
+                    {ms(sample.durationMs)}
+                    {sample.files !== null && ` · ${sample.files.toLocaleString()} files`}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      )}
+    </section>
+  );
+}
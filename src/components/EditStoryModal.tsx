@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import type { Story } from "../stores/prdStore";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
+import { BUILT_IN_AGENTS } from "../types/agents";
 
 interface EditStoryModalProps {
   isOpen: boolean;
@@ -18,6 +19,8 @@ export function EditStoryModal({
   const [title, setTitle] = useState("");
   const [description, setDescription] = useState("");
   const [criteria, setCriteria] = useState<string[]>([]);
+  const [agent, setAgent] = useState("");
+  const [model, setModel] = useState("");
 
   useModalKeyboard(isOpen, onClose);
 
@@ -26,6 +29,8 @@ export function EditStoryModal({
       setTitle(story.title);
       setDescription(story.description);
       setCriteria([...story.acceptanceCriteria]);
+      setAgent(story.agent ?? "");
+      setModel(story.model ?? "");
     }
   }, [story]);
 
@@ -42,6 +47,8 @@ export function EditStoryModal({
       title: title.trim(),
       description: description.trim(),
       acceptanceCriteria: criteria.filter((c) => c.trim() !== ""),
+      agent: agent || undefined,
+      model: model.trim() || undefined,
     });
     onClose();
   };
@@ -155,6 +162,46 @@ export function EditStoryModal({
           </div>
         </div>
 
+        <div className="grid grid-cols-2 gap-3 mt-4">
+          <div>
+            <label className="block text-sm font-medium text-secondary mb-1">
+              Agent
+            </label>
+            <select
+              value={agent}
+              onChange={(e) => setAgent(e.target.value)}
+              className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent text-sm"
+            >
+              <option value="">Project default</option>
+              {BUILT_IN_AGENTS.map((a) => (
+                <option key={a.id} value={a.id}>
+                  {a.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label className="block text-sm font-medium text-secondary mb-1">
+              Model
+            </label>
+            <input
+              type="text"
+              list="story-model-options"
+              value={model}
+              onChange={(e) => setModel(e.target.value)}
+              placeholder="Agent default"
+              className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground placeholder:text-secondary/60 focus:outline-none focus:ring-2 focus:ring-accent text-sm"
+            />
+            <datalist id="story-model-options">
+              {(BUILT_IN_AGENTS.find((a) => a.id === (agent || "claude-code"))?.supportedModels ?? []).map((m) => (
+                <option key={m.id} value={m.id}>
+                  {m.name}
+                </option>
+              ))}
+            </datalist>
+          </div>
+        </div>
+
         <div className="flex justify-end gap-3 mt-6">
           <button
             onClick={onClose}
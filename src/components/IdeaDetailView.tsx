@@ -13,12 +13,13 @@ import { usePrdGeneration } from "../hooks/usePrdGeneration";
 import { useAgentStore } from "../stores/agentStore";
 import { usePrdStore } from "../stores/prdStore";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
+import { useBuildModes } from "../hooks/useBuildModes";
 import { exportIdeaToPdf, loadLogoForPdf } from "../utils/exportPdf";
 import { notify } from "../utils/notify";
 import { defaultPlugins, type AgentPlugin } from "../types";
 
 type AutonomyLevel = "autonomous" | "pause-between" | "manual";
-type BuildMode = "ralph" | "parallel" | "none";
+type BuildMode = string;
 
 interface Preferences {
   defaultAgent: string | null;
@@ -36,12 +37,6 @@ const autonomyOptions: { value: AutonomyLevel; label: string; description: strin
   { value: "manual", label: "Manual", description: "Run one story at a time" },
 ];
 
-const buildModeOptions: { value: BuildMode; label: string; description: string }[] = [
-  { value: "ralph", label: "Ralph", description: "Sequential story execution" },
-  { value: "parallel", label: "Parallel", description: "Run stories concurrently" },
-  { value: "none", label: "None", description: "No automatic building" },
-];
-
 interface CreateProjectResult {
   path: string;
 }
@@ -54,6 +49,12 @@ export function IdeaDetailView({ idea }: IdeaDetailViewProps) {
   const updateIdea = useIdeasStore((state) => state.updateIdea);
   const removeIdea = useIdeasStore((state) => state.removeIdea);
   const selectIdea = useIdeasStore((state) => state.selectIdea);
+  const queueExpansions = useIdeasStore((state) => state.queueExpansions);
+  const cancelJob = useIdeasStore((state) => state.cancelJob);
+  const expansionJob = useIdeasStore((state) =>
+    state.jobs.filter((job) => job.ideaId === idea.id).at(-1)
+  );
+  const isExpanding = expansionJob?.status === "queued" || expansionJob?.status === "running";
 
   const addProject = useProjectStore((state) => state.addProject);
   const setActiveProject = useProjectStore((state) => state.setActiveProject);
@@ -83,6 +84,7 @@ export function IdeaDetailView({ idea }: IdeaDetailViewProps) {
   
   // Build options state (loaded from app preferences)
   const [buildMode, setBuildMode] = useState<BuildMode>("ralph");
+  const buildModeOptions = useBuildModes();
   const [selectedAgent, setSelectedAgent] = useState<string>("");
   const [autonomyLevel, setAutonomyLevel] = useState<AutonomyLevel>("autonomous");
 
@@ -620,6 +622,59 @@ export function IdeaDetailView({ idea }: IdeaDetailViewProps) {
                 </div>
               )}
 
+              <div className="space-y-2">
+                <div className="flex items-center justify-between gap-3">
+                  <h2 className="text-sm font-medium text-secondary">Draft Stories</h2>
+                  {isExpanding ? (
+                    <div className="flex items-center gap-2 text-xs text-muted">
+                      <span>{expansionJob?.status === "queued" ? "Queued for expansion" : "Expanding..."}</span>
+                      <button
+                        onClick={() => expansionJob && cancelJob(expansionJob.id)}
+                        className="px-2 py-1 rounded-md hover:bg-card transition-colors"
+                      >
+                        Cancel
+                      </button>
+                    </div>
+                  ) : (
+                    <button
+                      onClick={() =>
+                        queueExpansions([idea.id]).catch((error) =>
+                          notify.error("Couldn't queue expansion", String(error))
+                        )
+                      }
+                      className="px-2 py-1 rounded-md text-xs text-secondary hover:text-foreground hover:bg-card transition-colors"
+                      title="Draft a summary, description, and stories with the ideas agent in the background"
+                    >
+                      {idea.draftStories?.length ? "Redraft in background" : "Expand in background"}
+                    </button>
+                  )}
+                </div>
+                {expansionJob?.status === "failed" && (
+                  <p className="text-xs text-destructive">Expansion failed: {expansionJob.error}</p>
+                )}
+                {idea.draftStories?.length ? (
+                  <ol className="space-y-2">
+                    {idea.draftStories.map((story, index) => (
+                      <li key={index} className="bg-card rounded-lg border border-border px-4 py-3">
+                        <div className="text-sm font-medium">
+                          {index + 1}. {story.title}
+                        </div>
+                        {story.description && <p className="text-sm text-secondary mt-1">{story.description}</p>}
+                        {story.acceptanceCriteria.length > 0 && (
+                          <ul className="mt-2 list-disc list-inside text-xs text-muted space-y-0.5">
+                            {story.acceptanceCriteria.map((criterion, i) => (
+                              <li key={i}>{criterion}</li>
+                            ))}
+                          </ul>
+                        )}
+                      </li>
+                    ))}
+                  </ol>
+                ) : (
+                  !isExpanding && <p className="text-xs text-muted">No draft stories yet</p>
+                )}
+              </div>
+
               <div className="pt-6 border-t border-border">
                 <p className="text-xs text-muted">
                   Created {formatDate(idea.createdAt)}
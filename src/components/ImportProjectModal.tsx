@@ -1,18 +1,47 @@
 import { useState, useEffect } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
 import { documentDir } from "@tauri-apps/api/path";
+import { listen } from "@tauri-apps/api/event";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
 
+type ImportSource = "directory" | "clone" | "bundle";
+
+interface CloneProgressEvent {
+  destination: string;
+  phase: string;
+  percent: number | null;
+  message: string;
+}
+
 interface ImportProjectModalProps {
   isOpen: boolean;
   onClose: () => void;
-  onImport: (name: string, directory: string, generatePrd: boolean) => void;
+  onImport: (name: string, directory: string, generatePrd: boolean, cloneUrl?: string) => Promise<void> | void;
+  onImportBundle: (bundlePath: string, destination: string) => Promise<void> | void;
 }
 
-export function ImportProjectModal({ isOpen, onClose, onImport }: ImportProjectModalProps) {
+const SOURCE_LABELS: Record<ImportSource, string> = {
+  directory: "Local Directory",
+  clone: "Clone from URL",
+  bundle: "Project Bundle",
+};
+
+/** Folder name git would clone a remote into, e.g. "repo" for ".../owner/repo.git". */
+function repoFolderName(url: string): string {
+  const trimmed = url.trim().replace(/\/+$/, "").replace(/\.git$/, "");
+  const parts = trimmed.split(/[/:]/);
+  return parts[parts.length - 1] || "";
+}
+
+export function ImportProjectModal({ isOpen, onClose, onImport, onImportBundle }: ImportProjectModalProps) {
   const [name, setName] = useState("");
   const [directory, setDirectory] = useState<string | null>(null);
   const [generatePrd, setGeneratePrd] = useState(true);
+  const [source, setSource] = useState<ImportSource>("directory");
+  const [cloneUrl, setCloneUrl] = useState("");
+  const [bundlePath, setBundlePath] = useState<string | null>(null);
+  const [isImporting, setIsImporting] = useState(false);
+  const [cloneProgress, setCloneProgress] = useState<CloneProgressEvent | null>(null);
 
   useModalKeyboard(isOpen, onClose);
 
@@ -21,12 +50,31 @@ export function ImportProjectModal({ isOpen, onClose, onImport }: ImportProjectM
       setName("");
       setDirectory(null);
       setGeneratePrd(true);
+      setSource("directory");
+      setCloneUrl("");
+      setBundlePath(null);
+      setIsImporting(false);
+      setCloneProgress(null);
     }
   }, [isOpen]);
 
   useEffect(() => {
-    if (directory && !name) {
-      const parts = directory.split("/");
+    if (!isOpen) return;
+    const unlisten = listen<CloneProgressEvent>("clone-progress", (event) => {
+      setCloneProgress(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isOpen]);
+
+  const repoName = repoFolderName(cloneUrl);
+  const destination = source === "clone" && directory && repoName ? `${directory}/${repoName}` : directory;
+
+  useEffect(() => {
+    const folderSource = source === "clone" ? (repoName ? `/${repoName}` : null) : directory;
+    if (folderSource && !name) {
+      const parts = folderSource.split("/");
       const folderName = parts[parts.length - 1];
       if (folderName) {
         const formattedName = folderName
@@ -35,7 +83,7 @@ export function ImportProjectModal({ isOpen, onClose, onImport }: ImportProjectM
         setName(formattedName);
       }
     }
-  }, [directory, name]);
+  }, [directory, name, source, repoName]);
 
   if (!isOpen) return null;
 
@@ -45,12 +93,34 @@ export function ImportProjectModal({ isOpen, onClose, onImport }: ImportProjectM
     }
   };
 
-  const handleImport = () => {
-    if (name.trim() && directory) {
-      onImport(name.trim(), directory, generatePrd);
-      setName("");
-      setDirectory(null);
-      setGeneratePrd(true);
+  const canImport =
+    source === "bundle"
+      ? !!bundlePath && !!directory && !isImporting
+      : !!name.trim() && !!destination && (source === "directory" || !!repoName) && !isImporting;
+
+  const handleImport = async () => {
+    if (!canImport || !destination) return;
+    setIsImporting(true);
+    setCloneProgress(null);
+    try {
+      if (source === "bundle") {
+        if (bundlePath) await onImportBundle(bundlePath, destination);
+        return;
+      }
+      await onImport(name.trim(), destination, generatePrd, source === "clone" ? cloneUrl.trim() : undefined);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  const handleBundlePick = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Ideate Project Bundle", extensions: ["ideatepkg"] }],
+      title: "Choose Project Bundle",
+    });
+    if (typeof selected === "string") {
+      setBundlePath(selected);
     }
   };
 
@@ -61,7 +131,12 @@ export function ImportProjectModal({ isOpen, onClose, onImport }: ImportProjectM
         directory: true,
         multiple: false,
         defaultPath,
-        title: "Choose Existing Project Directory",
+        title:
+          source === "clone"
+            ? "Choose Where to Clone"
+            : source === "bundle"
+              ? "Choose Where to Create the Project"
+              : "Choose Existing Project Directory",
       });
       if (selected) {
         setDirectory(selected);
@@ -85,82 +160,158 @@ export function ImportProjectModal({ isOpen, onClose, onImport }: ImportProjectM
         </div>
 
         <div className="p-5 space-y-4">
+          <div className="flex gap-2">
+            {(["directory", "clone", "bundle"] as const).map((option) => (
+              <button
+                key={option}
+                onClick={() => setSource(option)}
+                disabled={isImporting}
+                className={`flex-1 px-3 py-1.5 rounded-md border text-sm transition-colors ${
+                  source === option
+                    ? "border-accent bg-accent/10 text-accent"
+                    : "border-border text-secondary hover:text-foreground"
+                }`}
+              >
+                {SOURCE_LABELS[option]}
+              </button>
+            ))}
+          </div>
+
+          {source === "clone" && (
+            <div>
+              <label className="label">Repository URL</label>
+              <input
+                type="text"
+                value={cloneUrl}
+                onChange={(e) => setCloneUrl(e.target.value)}
+                placeholder="https://github.com/owner/repo.git or git@github.com:owner/repo.git"
+                className="input"
+                disabled={isImporting}
+              />
+              <p className="text-xs text-muted mt-1">
+                Private HTTPS repos use your GitHub token or a stored git.&lt;host&gt;.token secret; SSH uses your ssh-agent.
+              </p>
+            </div>
+          )}
+
+          {source === "bundle" && (
+            <div>
+              <label className="label">Bundle File</label>
+              <button
+                onClick={handleBundlePick}
+                disabled={isImporting}
+                className="w-full px-3 py-2 rounded-md border border-border bg-card text-left hover:bg-border/30 transition-colors text-sm"
+              >
+                <span className={bundlePath ? "text-foreground" : "text-muted"}>
+                  {bundlePath || "Choose .ideatepkg File..."}
+                </span>
+              </button>
+              <p className="text-xs text-muted mt-1">
+                Restores the repository with its story branches, the PRD, design, build state, and cost history.
+              </p>
+            </div>
+          )}
+
           <div>
-            <label className="label">Project Directory</label>
+            <label className="label">
+              {source === "clone" ? "Clone Into" : source === "bundle" ? "Create Project In" : "Project Directory"}
+            </label>
             <button
               onClick={handleDirectoryPick}
+              disabled={isImporting}
               className="w-full px-3 py-2 rounded-md border border-border bg-card text-left hover:bg-border/30 transition-colors text-sm"
             >
               <span className={directory ? "text-foreground" : "text-muted"}>
-                {directory || "Choose Existing Directory..."}
+                {destination || (source === "directory" ? "Choose Existing Directory..." : "Choose Parent Directory...")}
               </span>
             </button>
           </div>
 
-          <div>
-            <label className="label">Project Name</label>
-            <input
-              type="text"
-              value={name}
-              onChange={(e) => setName(e.target.value)}
-              placeholder="My Existing App"
-              className="input"
-            />
-            <p className="text-xs text-muted mt-1">
-              Auto-filled from directory name
-            </p>
-          </div>
+          {source !== "bundle" && (
+            <>
+              <div>
+                <label className="label">Project Name</label>
+                <input
+                  type="text"
+                  value={name}
+                  onChange={(e) => setName(e.target.value)}
+                  placeholder="My Existing App"
+                  className="input"
+                />
+                <p className="text-xs text-muted mt-1">
+                  Auto-filled from directory name
+                </p>
+              </div>
 
-          <div className="pt-2">
-            <label className="block text-sm text-foreground mb-3">
-              PRD Generation
-            </label>
-            <div className="flex gap-2">
-              <button
-                onClick={() => setGeneratePrd(true)}
-                className={`flex-1 flex items-center justify-center gap-2 px-3 py-2.5 rounded-lg border transition-colors ${
-                  generatePrd
-                    ? "border-accent bg-accent/10 text-accent"
-                    : "border-border hover:border-secondary text-secondary hover:text-foreground"
-                }`}
-              >
-                <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z" />
-                </svg>
-                <span className="text-sm font-medium">Generate PRD</span>
-              </button>
-              <button
-                onClick={() => setGeneratePrd(false)}
-                className={`flex-1 flex items-center justify-center gap-2 px-3 py-2.5 rounded-lg border transition-colors ${
-                  !generatePrd
-                    ? "border-accent bg-accent/10 text-accent"
-                    : "border-border hover:border-secondary text-secondary hover:text-foreground"
-                }`}
-              >
-                <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M18.364 18.364A9 9 0 005.636 5.636m12.728 12.728A9 9 0 015.636 5.636m12.728 12.728L5.636 5.636" />
-                </svg>
-                <span className="text-sm font-medium">Skip PRD</span>
-              </button>
-            </div>
-            <p className="text-xs text-muted mt-2">
-              {generatePrd
-                ? "Analyze the codebase and generate user stories that describe the existing functionality."
-                : "Import the project without generating a PRD. You can add stories manually later."}
-            </p>
-          </div>
+              <div className="pt-2">
+                <label className="block text-sm text-foreground mb-3">
+                  PRD Generation
+                </label>
+                <div className="flex gap-2">
+                  <button
+                    onClick={() => setGeneratePrd(true)}
+                    className={`flex-1 flex items-center justify-center gap-2 px-3 py-2.5 rounded-lg border transition-colors ${
+                      generatePrd
+                        ? "border-accent bg-accent/10 text-accent"
+                        : "border-border hover:border-secondary text-secondary hover:text-foreground"
+                    }`}
+                  >
+                    <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z" />
+                    </svg>
+                    <span className="text-sm font-medium">Generate PRD</span>
+                  </button>
+                  <button
+                    onClick={() => setGeneratePrd(false)}
+                    className={`flex-1 flex items-center justify-center gap-2 px-3 py-2.5 rounded-lg border transition-colors ${
+                      !generatePrd
+                        ? "border-accent bg-accent/10 text-accent"
+                        : "border-border hover:border-secondary text-secondary hover:text-foreground"
+                    }`}
+                  >
+                    <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M18.364 18.364A9 9 0 005.636 5.636m12.728 12.728A9 9 0 015.636 5.636m12.728 12.728L5.636 5.636" />
+                    </svg>
+                    <span className="text-sm font-medium">Skip PRD</span>
+                  </button>
+                </div>
+                <p className="text-xs text-muted mt-2">
+                  {generatePrd
+                    ? "Analyze the codebase and generate user stories that describe the existing functionality."
+                    : "Import the project without generating a PRD. You can add stories manually later."}
+                </p>
+              </div>
+            </>
+          )}
         </div>
 
-        <div className="px-5 py-4 border-t border-border flex justify-end gap-2">
-          <button onClick={onClose} className="btn btn-ghost">
+        <div className="px-5 py-4 border-t border-border flex items-center justify-end gap-2">
+          {isImporting && source === "clone" && (
+            <span className="mr-auto text-xs text-muted truncate">
+              {cloneProgress
+                ? cloneProgress.percent !== null
+                  ? `${cloneProgress.phase}: ${cloneProgress.percent}%`
+                  : cloneProgress.message
+                : "Cloning..."}
+            </span>
+          )}
+          <button onClick={onClose} className="btn btn-ghost" disabled={isImporting}>
             Cancel
           </button>
           <button
             onClick={handleImport}
-            disabled={!name.trim() || !directory}
+            disabled={!canImport}
             className="btn btn-primary"
           >
-            Import Project
+            {isImporting && source === "clone"
+              ? "Cloning..."
+              : source === "clone"
+                ? "Clone & Import"
+                : source === "bundle"
+                  ? isImporting
+                    ? "Importing..."
+                    : "Import Bundle"
+                  : "Import Project"}
           </button>
         </div>
       </div>
@@ -0,0 +1,224 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+export interface LanSyncPeer {
+  deviceId: string;
+  name: string;
+  address: string | null;
+  connected: boolean;
+  lastSeen: string;
+  lastSync: string | null;
+  projects: { syncId: string; name: string }[];
+  error: string | null;
+}
+
+export interface LanSyncConflict {
+  id: string;
+  projectId: string;
+  projectName: string;
+  path: string;
+  peerName: string;
+  savedCopy: string;
+  detectedAt: string;
+}
+
+export interface LanSyncStatus {
+  running: boolean;
+  port: number;
+  deviceId: string | null;
+  deviceName: string;
+  key: string | null;
+  peers: LanSyncPeer[];
+  conflicts: LanSyncConflict[];
+}
+
+interface LanSyncSectionProps {
+  enabled: boolean;
+  port: number;
+  onEnabledChange: (enabled: boolean) => void;
+  onPortChange: (port: number) => void;
+}
+
+export function LanSyncSection({ enabled, port, onEnabledChange, onPortChange }: LanSyncSectionProps) {
+  const [status, setStatus] = useState<LanSyncStatus | null>(null);
+  const [showKey, setShowKey] = useState(false);
+  const [keyInput, setKeyInput] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const refresh = () =>
+      invoke<LanSyncStatus>("get_lan_sync_status")
+        .then(setStatus)
+        .catch((err) => setError(String(err)));
+    refresh();
+    // Peers come and go while the section is open
+    const interval = setInterval(refresh, 3000);
+    return () => clearInterval(interval);
+  }, []);
+
+  const run = async (command: string, args?: Record<string, unknown>) => {
+    setError(null);
+    try {
+      setStatus(await invoke<LanSyncStatus>(command, args));
+      return true;
+    } catch (err) {
+      setError(String(err));
+      return false;
+    }
+  };
+
+  const handleUseKey = async () => {
+    if (await run("set_lan_sync_key", { key: keyInput })) {
+      setKeyInput("");
+    }
+  };
+
+  const handleDismiss = async (id: string) => {
+    await invoke("dismiss_lan_sync_conflict", { id });
+    setStatus((current) =>
+      current ? { ...current, conflicts: current.conflicts.filter((c) => c.id !== id) } : current
+    );
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        LAN Sync
+      </h3>
+      <div className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium text-foreground">
+              Sync With Other Computers
+            </label>
+            <p className="text-xs text-muted mt-0.5">
+              Finds Ideate on your local network and keeps the .ideate state and build progress of projects marked
+              for sync in step, so a build started on one computer can be followed on another. Applies when saved.
+            </p>
+          </div>
+          <button
+            onClick={() => onEnabledChange(!enabled)}
+            className={`relative w-11 h-6 rounded-full transition-colors ${
+              enabled ? "bg-accent" : "bg-muted/30"
+            }`}
+          >
+            <span
+              className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                enabled ? "translate-x-5" : ""
+              }`}
+            />
+          </button>
+        </div>
+
+        {enabled && (
+          <>
+            <div>
+              <label className="block text-sm text-foreground mb-2">Port</label>
+              <input
+                type="number"
+                value={port}
+                onChange={(e) => onPortChange(parseInt(e.target.value) || 7458)}
+                min={1024}
+                max={65535}
+                className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+              />
+              {status?.running && (
+                <p className="text-xs text-muted mt-1">
+                  Listening on port {status.port} as {status.deviceName}
+                </p>
+              )}
+            </div>
+
+            <div>
+              <label className="block text-sm text-foreground mb-2">Sync Key</label>
+              <div className="flex items-center gap-2">
+                <code className="flex-1 px-3 py-2 rounded-lg border border-border bg-background text-xs text-foreground truncate">
+                  {status?.key ? (showKey ? status.key : "•".repeat(24)) : "Generated when sync starts"}
+                </code>
+                {status?.key && (
+                  <>
+                    <button onClick={() => setShowKey(!showKey)} className="btn btn-sm btn-secondary">
+                      {showKey ? "Hide" : "Show"}
+                    </button>
+                    <button
+                      onClick={() => navigator.clipboard.writeText(status.key ?? "")}
+                      className="btn btn-sm btn-secondary"
+                    >
+                      Copy
+                    </button>
+                  </>
+                )}
+                <button onClick={() => run("regenerate_lan_sync_key")} className="btn btn-sm btn-secondary">
+                  Regenerate
+                </button>
+              </div>
+              <div className="flex items-center gap-2 mt-2">
+                <input
+                  type="password"
+                  value={keyInput}
+                  onChange={(e) => setKeyInput(e.target.value)}
+                  placeholder="Paste the key from another computer"
+                  className="flex-1 px-3 py-2 rounded-lg border border-border bg-background text-foreground text-xs focus:outline-none focus:ring-2 focus:ring-accent"
+                />
+                <button onClick={handleUseKey} disabled={!keyInput.trim()} className="btn btn-sm btn-secondary">
+                  Use Key
+                </button>
+              </div>
+              <p className="text-xs text-muted mt-1">
+                Computers connect only when they have the same key. Messages are signed with it but not encrypted.
+              </p>
+            </div>
+
+            <div>
+              <label className="block text-sm text-foreground mb-2">Computers</label>
+              {status?.peers.length ? (
+                <div className="space-y-1">
+                  {status.peers.map((peer) => (
+                    <div key={peer.deviceId} className="flex items-center gap-2 text-xs">
+                      <span className={`w-2 h-2 rounded-full ${peer.connected ? "bg-success" : "bg-muted/40"}`} />
+                      <span className="text-foreground">{peer.name}</span>
+                      <span className="text-muted truncate">
+                        {peer.connected
+                          ? `${peer.projects.length} project${peer.projects.length === 1 ? "" : "s"} shared`
+                          : peer.error ?? peer.address ?? "Not connected"}
+                      </span>
+                    </div>
+                  ))}
+                </div>
+              ) : (
+                <p className="text-xs text-muted">
+                  {status?.running ? "Looking for other computers…" : "Not running"}
+                </p>
+              )}
+            </div>
+
+            {status && status.conflicts.length > 0 && (
+              <div>
+                <label className="block text-sm text-foreground mb-2">Conflicts</label>
+                <div className="space-y-2">
+                  {status.conflicts.map((conflict) => (
+                    <div key={conflict.id} className="flex items-start gap-2 text-xs">
+                      <div className="flex-1 min-w-0">
+                        <div className="text-foreground">
+                          {conflict.projectName}: .ideate/{conflict.path}
+                        </div>
+                        <div className="text-muted truncate">
+                          Changed here and on {conflict.peerName}; kept theirs, this computer&apos;s copy is in{" "}
+                          {conflict.savedCopy}
+                        </div>
+                      </div>
+                      <button onClick={() => handleDismiss(conflict.id)} className="btn btn-sm btn-secondary">
+                        Dismiss
+                      </button>
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+          </>
+        )}
+        {error && <p className="text-xs text-destructive">{error}</p>}
+      </div>
+    </section>
+  );
+}
@@ -4,6 +4,7 @@ import { useProjectStore } from "../stores/projectStore";
 import { usePrdStore, type Story, type PrdMetadata } from "../stores/prdStore";
 import { useIdeasStore } from "../stores/ideasStore";
 import { useProcessStore } from "../stores/processStore";
+import { notify } from "../utils/notify";
 
 // Lazy load view components
 const IdeaDetailView = lazy(() => import("./IdeaDetailView").then(m => ({ default: m.IdeaDetailView })));
@@ -31,9 +32,38 @@ interface Prd {
     passes: boolean
     status?: string
     notes: string
+    agent?: string
+    model?: string
   }>
 }
 
+interface PrdRecoveryReport {
+  error: string
+  quarantinedPath: string
+  restoredFrom: string | null
+  restoredStories: number
+  extractedStoryIds: string[]
+  prd: Prd
+}
+
+/** Quarantines an unreadable prd.json and loads what could be recovered. */
+async function recoverPrd(projectPath: string): Promise<Prd | null> {
+  try {
+    const report = await invoke<PrdRecoveryReport>("recover_prd", { projectPath });
+    const restored = report.restoredFrom
+      ? `Restored ${report.restoredStories} stories from ${report.restoredFrom}. `
+      : "No earlier version was found. ";
+    notify.warning(
+      "prd.json was unreadable and has been recovered",
+      `${restored}Recovered ${report.extractedStoryIds.length} stories from the damaged file, which was moved to ${report.quarantinedPath}.`
+    );
+    return report.prd;
+  } catch (error) {
+    console.error("Failed to recover PRD:", error);
+    return null;
+  }
+}
+
 // Loading fallback for views
 function ViewFallback() {
   return (
@@ -96,45 +126,54 @@ export function MainContent() {
       // Track which project we're loading
       loadingProjectIdRef.current = activeProjectId;
 
+      let prd: Prd | null;
       try {
-        const prd = await invoke<Prd | null>("load_prd", {
+        prd = await invoke<Prd | null>("load_prd", {
           projectPath: activeProject.path,
         });
-
-        // Check if the active project changed while we were loading
-        // If so, don't set the PRD - it will be loaded again for the new project
-        if (loadingProjectIdRef.current !== activeProjectId) {
-          return;
-        }
-
-        if (prd && prd.userStories) {
-          const stories: Story[] = prd.userStories.map(story => ({
-            id: story.id,
-            title: story.title,
-            description: story.description,
-            acceptanceCriteria: story.acceptanceCriteria,
-            priority: story.priority,
-            passes: story.passes,
-            notes: story.notes
-          }));
-          const metadata: PrdMetadata = {
-            project: prd.project,
-            description: prd.description,
-            branchName: prd.branchName,
-          };
-          // Always set PRD even if empty - this marks it as "ready"
-          setPrd(activeProjectId, stories, metadata);
-        } else {
-          // No PRD file exists - mark as ready with empty stories
-          setStatus(activeProjectId, "ready");
-        }
       } catch (error) {
         console.error("Failed to load PRD:", error);
-        // Only set error if we're still on the same project
-        if (loadingProjectIdRef.current === activeProjectId) {
-          setStatus(activeProjectId, "error");
+        prd = String(error).includes("Failed to parse prd.json")
+          ? await recoverPrd(activeProject.path)
+          : null;
+        if (!prd) {
+          // Only set error if we're still on the same project
+          if (loadingProjectIdRef.current === activeProjectId) {
+            setStatus(activeProjectId, "error");
+          }
+          return;
         }
       }
+
+      // Check if the active project changed while we were loading
+      // If so, don't set the PRD - it will be loaded again for the new project
+      if (loadingProjectIdRef.current !== activeProjectId) {
+        return;
+      }
+
+      if (prd && prd.userStories) {
+        const stories: Story[] = prd.userStories.map(story => ({
+          id: story.id,
+          title: story.title,
+          description: story.description,
+          acceptanceCriteria: story.acceptanceCriteria,
+          priority: story.priority,
+          passes: story.passes,
+          notes: story.notes,
+          agent: story.agent,
+          model: story.model,
+        }));
+        const metadata: PrdMetadata = {
+          project: prd.project,
+          description: prd.description,
+          branchName: prd.branchName,
+        };
+        // Always set PRD even if empty - this marks it as "ready"
+        setPrd(activeProjectId, stories, metadata);
+      } else {
+        // No PRD file exists - mark as ready with empty stories
+        setStatus(activeProjectId, "ready");
+      }
     }
 
     loadPrd();
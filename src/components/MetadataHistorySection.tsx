@@ -0,0 +1,122 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+type MetadataMode = "off" | "branch" | "notes";
+
+interface MetadataHistoryConfig {
+  mode: "branch" | "notes";
+  branch: string | null;
+}
+
+interface MetadataCommit {
+  hash: string;
+  message: string;
+  committedAt: string;
+}
+
+interface MetadataHistorySectionProps {
+  projectPath: string;
+}
+
+const modeOptions: { value: MetadataMode; label: string; description: string }[] = [
+  { value: "off", label: "In project commits", description: ".ideate changes are committed with each story" },
+  { value: "branch", label: "Separate branch", description: "Snapshots go to the ideate-meta branch" },
+  { value: "notes", label: "Git notes", description: "PRD, state, and costs are attached to story commits" },
+];
+
+export function MetadataHistorySection({ projectPath }: MetadataHistorySectionProps) {
+  const [mode, setMode] = useState<MetadataMode>("off");
+  const [history, setHistory] = useState<MetadataCommit[] | null>(null);
+  const [isUpdating, setIsUpdating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<MetadataHistoryConfig | null>("get_metadata_history_config", { projectPath })
+      .then((config) => setMode(config?.mode ?? "off"))
+      .catch((err) => setError(String(err)));
+    setHistory(null);
+  }, [projectPath]);
+
+  const handleModeChange = async (next: MetadataMode) => {
+    if (next === mode) return;
+    setIsUpdating(true);
+    setError(null);
+    try {
+      if (next === "off") {
+        await invoke("disable_metadata_history", { projectPath });
+      } else {
+        await invoke("enable_metadata_history", { projectPath, mode: next });
+      }
+      setMode(next);
+      setHistory(null);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsUpdating(false);
+    }
+  };
+
+  const handleViewHistory = async () => {
+    setError(null);
+    try {
+      setHistory(await invoke<MetadataCommit[]>("get_metadata_history", { projectPath, limit: 20 }));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <label className="block text-sm font-medium text-foreground">Metadata History</label>
+      <p className="text-xs text-muted mb-2">Where PRD, state, and cost changes are recorded in git</p>
+      <div className="space-y-2">
+        {modeOptions.map((option) => (
+          <label
+            key={option.value}
+            className={`flex items-start gap-3 p-3 rounded-lg border cursor-pointer transition-colors ${
+              mode === option.value ? "border-accent bg-accent/5" : "border-border hover:border-accent/50"
+            }`}
+          >
+            <input
+              type="radio"
+              name="metadataHistory"
+              value={option.value}
+              checked={mode === option.value}
+              disabled={isUpdating}
+              onChange={() => handleModeChange(option.value)}
+              className="mt-0.5 accent-accent"
+            />
+            <div>
+              <div className="text-sm font-medium">{option.label}</div>
+              <div className="text-xs text-muted">{option.description}</div>
+            </div>
+          </label>
+        ))}
+      </div>
+
+      {mode !== "off" && (
+        <button onClick={handleViewHistory} className="btn btn-sm btn-secondary">
+          View Metadata History
+        </button>
+      )}
+
+      {history && (
+        <div className="max-h-48 overflow-y-auto rounded-lg border border-border divide-y divide-border">
+          {history.length === 0 ? (
+            <p className="p-3 text-xs text-muted">Nothing recorded yet</p>
+          ) : (
+            history.map((entry) => (
+              <div key={entry.hash} className="px-3 py-2 text-xs flex items-center gap-2">
+                <code className="text-muted">{entry.hash.slice(0, 7)}</code>
+                <span className="flex-1 truncate text-foreground">{entry.message}</span>
+                <span className="text-muted">{new Date(entry.committedAt).toLocaleString()}</span>
+              </div>
+            ))
+          )}
+        </div>
+      )}
+
+      {error && <p className="text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -5,9 +5,11 @@ import { useBuildStore } from "../stores/buildStore";
 import { useCostStore } from "../stores/costStore";
 import type { Project } from "../stores/projectStore";
 import { defaultPlugins } from "../types";
+import { useBuildModes } from "../hooks/useBuildModes";
+import { formatCost, useDisplayCurrency } from "../hooks/useDisplayCurrency";
 
 type AutonomyLevel = "autonomous" | "pause-between" | "manual";
-type BuildMode = "ralph" | "parallel" | "none";
+type BuildMode = string;
 
 interface ProjectSettings {
   agent: string | null;
@@ -25,13 +27,8 @@ const autonomyLabels: Record<AutonomyLevel, string> = {
   manual: "Manual",
 };
 
-const buildModeLabels: Record<BuildMode, string> = {
-  ralph: "Ralph (Sequential)",
-  parallel: "Parallel",
-  none: "None",
-};
-
 export function OverviewContent({ project }: OverviewContentProps) {
+  const currency = useDisplayCurrency();
   const projectPrd = usePrdStore((state) => state.projectPrds[project.id]);
   const stories = projectPrd?.stories ?? [];
   // Subscribe directly to the project state for reactivity
@@ -40,6 +37,7 @@ export function OverviewContent({ project }: OverviewContentProps) {
 
   const [settings, setSettings] = useState<ProjectSettings | null>(null);
   const [isLoading, setIsLoading] = useState(true);
+  const buildModeOptions = useBuildModes();
 
   const buildStatus = projectState?.status ?? 'idle';
 
@@ -147,7 +145,7 @@ export function OverviewContent({ project }: OverviewContentProps) {
                 <div>
                   <label className="text-xs font-medium text-muted uppercase tracking-wider">Mode</label>
                   <p className="mt-1 text-sm text-foreground">
-                    {settings?.buildMode ? buildModeLabels[settings.buildMode] : "Ralph (Sequential)"}
+                    {buildModeOptions.find((o) => o.value === (settings?.buildMode ?? "ralph"))?.label ?? settings?.buildMode}
                   </p>
                 </div>
                 <div>
@@ -239,7 +237,7 @@ export function OverviewContent({ project }: OverviewContentProps) {
               <div className="grid grid-cols-4 gap-6">
                 <div>
                   <label className="text-xs font-medium text-muted uppercase tracking-wider">Total Cost</label>
-                  <p className="mt-1 text-2xl font-bold text-accent">${projectCosts.totalCost.toFixed(2)}</p>
+                  <p className="mt-1 text-2xl font-bold text-accent">{formatCost(projectCosts.totalCost, currency)}</p>
                 </div>
                 <div>
                   <label className="text-xs font-medium text-muted uppercase tracking-wider">Input Tokens</label>
@@ -1,16 +1,68 @@
+import { useCallback, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
 
+type PermissionStatus = "granted" | "denied" | "prompt" | "not-found" | "not-applicable" | "unknown";
+
+interface PathPermission {
+  path: string;
+  status: PermissionStatus;
+  error?: string;
+}
+
+interface PermissionReport {
+  fullDiskAccess: PermissionStatus;
+  claudeData: PathPermission;
+  ampData: PathPermission;
+  notifications: PermissionStatus;
+}
+
+const STATUS_LABELS: Record<PermissionStatus, { label: string; className: string }> = {
+  granted: { label: "Granted", className: "text-success" },
+  denied: { label: "Denied", className: "text-destructive" },
+  prompt: { label: "Not asked yet", className: "text-warning" },
+  "not-found": { label: "Not installed", className: "text-muted" },
+  "not-applicable": { label: "Not needed", className: "text-muted" },
+  unknown: { label: "Unknown", className: "text-muted" },
+};
+
 interface PermissionsModalProps {
   isOpen: boolean;
   onClose: () => void;
 }
 
 export function PermissionsModal({ isOpen, onClose }: PermissionsModalProps) {
+  const [report, setReport] = useState<PermissionReport | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+
   useModalKeyboard(isOpen, onClose);
 
+  const checkPermissions = useCallback(async () => {
+    setIsChecking(true);
+    try {
+      setReport(await invoke<PermissionReport>("check_permissions"));
+    } catch (error) {
+      console.error("Failed to check permissions:", error);
+    } finally {
+      setIsChecking(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isOpen) checkPermissions();
+  }, [isOpen, checkPermissions]);
+
   if (!isOpen) return null;
 
+  const rows: { label: string; status: PermissionStatus; detail?: string }[] = report
+    ? [
+        { label: "Full Disk Access", status: report.fullDiskAccess },
+        { label: "Claude Code data", status: report.claudeData.status, detail: report.claudeData.error ?? report.claudeData.path },
+        { label: "Amp data", status: report.ampData.status, detail: report.ampData.error ?? report.ampData.path },
+        { label: "Notifications", status: report.notifications },
+      ]
+    : [];
+
   const handleOverlayClick = (e: React.MouseEvent) => {
     if (e.target === e.currentTarget) {
       onClose();
@@ -64,9 +116,25 @@ export function PermissionsModal({ isOpen, onClose }: PermissionsModalProps) {
               <li>Restart Ideate</li>
             </ol>
           </div>
+
+          {report && (
+            <div className="mt-4 rounded-md border border-border divide-y divide-border">
+              {rows.map((row) => (
+                <div key={row.label} className="px-3 py-2 flex items-center justify-between gap-3" title={row.detail}>
+                  <span className="text-sm text-foreground">{row.label}</span>
+                  <span className={`text-xs font-medium ${STATUS_LABELS[row.status].className}`}>
+                    {STATUS_LABELS[row.status].label}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
         </div>
 
         <div className="px-5 py-4 border-t border-border flex justify-end gap-2">
+          <button onClick={checkPermissions} disabled={isChecking} className="btn btn-ghost mr-auto">
+            {isChecking ? "Checking..." : "Check Again"}
+          </button>
           <button onClick={onClose} className="btn btn-ghost">
             Cancel
           </button>
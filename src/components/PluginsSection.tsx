@@ -0,0 +1,132 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface PluginCommand {
+  name: string;
+  description: string | null;
+}
+
+interface PluginInfo {
+  id: string;
+  name: string;
+  version: string | null;
+  path: string;
+  commands: PluginCommand[];
+  error: string | null;
+}
+
+interface PluginsInfo {
+  directory: string;
+  plugins: PluginInfo[];
+}
+
+export function PluginsSection() {
+  const [info, setInfo] = useState<PluginsInfo | null>(null);
+  const [isReloading, setIsReloading] = useState(false);
+  const [running, setRunning] = useState<string | null>(null);
+  const [output, setOutput] = useState<{ key: string; text: string; failed: boolean } | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<PluginsInfo>("list_plugins")
+      .then(setInfo)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  const handleReload = async () => {
+    setError(null);
+    setIsReloading(true);
+    try {
+      setInfo(await invoke<PluginsInfo>("reload_plugins"));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsReloading(false);
+    }
+  };
+
+  const handleRun = async (pluginId: string, command: string) => {
+    const key = `${pluginId}:${command}`;
+    setRunning(key);
+    setOutput(null);
+    try {
+      const result = await invoke<unknown>("invoke_plugin_command", { pluginId, command });
+      setOutput({ key, text: JSON.stringify(result, null, 2), failed: false });
+    } catch (err) {
+      setOutput({ key, text: String(err), failed: true });
+    } finally {
+      setRunning(null);
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Plugins
+      </h3>
+      <p className="text-xs text-muted mb-3">
+        Executables in the plugins folder that speak Ideate's JSON-over-stdio protocol add their own commands.
+      </p>
+      <div className="flex gap-2 mb-3">
+        <button onClick={handleReload} disabled={isReloading} className="btn btn-sm btn-secondary">
+          {isReloading ? "Reloading..." : "Reload"}
+        </button>
+        {info && (
+          <button
+            onClick={() => invoke("reveal_in_file_manager", { path: info.directory }).catch((err) => setError(String(err)))}
+            className="btn btn-sm btn-secondary"
+          >
+            Open Folder
+          </button>
+        )}
+      </div>
+
+      {info && info.plugins.length === 0 && (
+        <p className="text-xs text-muted">No plugins installed.</p>
+      )}
+      <div className="space-y-2">
+        {info?.plugins.map((plugin) => (
+          <div key={plugin.id} className="p-3 rounded-lg border border-border">
+            <p className="text-sm text-foreground">
+              {plugin.name}
+              {plugin.version && <span className="text-xs text-muted"> v{plugin.version}</span>}
+            </p>
+            {plugin.error && <p className="text-xs text-destructive mt-1">{plugin.error}</p>}
+            {plugin.commands.map((command) => {
+              const key = `${plugin.id}:${command.name}`;
+              return (
+                <div key={key} className="mt-2">
+                  <div className="flex items-center justify-between gap-3">
+                    <div className="min-w-0">
+                      <code className="text-xs text-foreground">{command.name}</code>
+                      {command.description && (
+                        <p className="text-xs text-muted truncate">{command.description}</p>
+                      )}
+                    </div>
+                    <button
+                      onClick={() => handleRun(plugin.id, command.name)}
+                      disabled={running !== null}
+                      className="btn btn-sm btn-secondary shrink-0"
+                    >
+                      {running === key ? "Running..." : "Run"}
+                    </button>
+                  </div>
+                  {output?.key === key && (
+                    <pre
+                      className={`mt-2 p-2 rounded bg-background text-xs whitespace-pre-wrap max-h-40 overflow-auto ${
+                        output.failed ? "text-destructive" : "text-foreground"
+                      }`}
+                    >
+                      {output.text}
+                    </pre>
+                  )}
+                </div>
+              );
+            })}
+          </div>
+        ))}
+      </div>
+      {error && <p className="text-xs text-destructive mt-2">{error}</p>}
+    </section>
+  );
+}
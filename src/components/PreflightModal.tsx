@@ -0,0 +1,91 @@
+import { useModalKeyboard } from "../hooks/useModalKeyboard";
+
+export type CheckStatus = "pass" | "warn" | "fail";
+
+export interface PreflightCheck {
+  id: string;
+  label: string;
+  status: CheckStatus;
+  message: string;
+  fix?: string;
+}
+
+export interface PreflightReport {
+  checks: PreflightCheck[];
+  ready: boolean;
+}
+
+interface PreflightModalProps {
+  report: PreflightReport | null;
+  isChecking: boolean;
+  onClose: () => void;
+  onRecheck: () => void;
+  onStart: () => void;
+}
+
+const STATUS_ICONS: Record<CheckStatus, { icon: string; className: string }> = {
+  pass: { icon: "✓", className: "text-success" },
+  warn: { icon: "!", className: "text-warning" },
+  fail: { icon: "✕", className: "text-destructive" },
+};
+
+export function PreflightModal({ report, isChecking, onClose, onRecheck, onStart }: PreflightModalProps) {
+  useModalKeyboard(report !== null, onClose);
+
+  if (!report) return null;
+
+  const handleOverlayClick = (e: React.MouseEvent) => {
+    if (e.target === e.currentTarget) {
+      onClose();
+    }
+  };
+
+  return (
+    <div
+      className="fixed inset-0 bg-black/50 flex items-center justify-center z-50 no-drag"
+      onClick={handleOverlayClick}
+    >
+      <div className="bg-card border border-border rounded-xl p-6 w-full max-w-lg max-h-[80vh] flex flex-col shadow-xl">
+        <h2 className="text-lg font-semibold text-foreground mb-1">Before You Build</h2>
+        <p className="text-sm text-secondary mb-4">
+          {report.ready
+            ? "The build can start, but some checks need attention."
+            : "Fix the failed checks before starting the build."}
+        </p>
+
+        <div className="flex-1 overflow-y-auto space-y-2 mb-4">
+          {report.checks.map((check) => {
+            const { icon, className } = STATUS_ICONS[check.status];
+            return (
+              <div key={check.id} className="flex items-start gap-3 px-3 py-2 border border-border rounded-lg">
+                <span className={`w-4 text-center font-semibold ${className}`}>{icon}</span>
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm text-foreground">{check.label}</p>
+                  <p className="text-xs text-secondary break-words">{check.message}</p>
+                  {check.fix && check.status !== "pass" && (
+                    <p className="text-xs text-muted mt-0.5">{check.fix}</p>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+        </div>
+
+        <div className="flex gap-3 justify-end">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 text-sm font-medium text-secondary hover:text-foreground rounded-lg hover:bg-secondary/10 transition-colors"
+          >
+            Close
+          </button>
+          <button onClick={onRecheck} disabled={isChecking} className="btn btn-secondary">
+            {isChecking ? "Checking..." : "Check Again"}
+          </button>
+          <button onClick={onStart} disabled={!report.ready || isChecking} className="btn btn-primary">
+            Start Anyway
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,9 +1,19 @@
 import { useEffect, useRef, useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useDevServer } from "../hooks/useDevServer";
 import { useTunnel } from "../hooks/useTunnel";
 import { useBuildStore } from "../stores/buildStore";
 import { usePanelStore } from "../stores/panelStore";
 import { useIntegrationsStore } from "../stores/integrationsStore";
+import { notify } from "../utils/notify";
+
+interface MobilePreview {
+  url: string;
+  tunnelId: string;
+  provider: string;
+  port: number;
+  qrCode: string;
+}
 
 interface PreviewPanelProps {
   projectId: string;
@@ -39,6 +49,9 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
   const [isIframeLoaded, setIsIframeLoaded] = useState(false);
   const [showTunnelSetup, setShowTunnelSetup] = useState(false);
   const [showTunnelDisconnect, setShowTunnelDisconnect] = useState(false);
+  const [proxyUrl, setProxyUrl] = useState<string | null>(null);
+  const [mobilePreview, setMobilePreview] = useState<MobilePreview | null>(null);
+  const [isOpeningMobile, setIsOpeningMobile] = useState(false);
 
   const startXRef = useRef(0);
   const startWidthRef = useRef(0);
@@ -49,6 +62,7 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
     status,
     config,
     url,
+    processId,
     error,
     wasManualyStopped,
     detectDevServer,
@@ -71,6 +85,31 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
 
   const setOutRayConfig = useIntegrationsStore((state) => state.setOutRayConfig);
 
+  // Serve the preview through the backend proxy so its URL stays stable across
+  // dev server restarts and the page reloads itself when a story is merged
+  useEffect(() => {
+    if (status !== 'running' || !url) {
+      setProxyUrl(null);
+      return;
+    }
+    let cancelled = false;
+    invoke<{ url: string }>('start_preview_proxy', {
+      projectId,
+      projectPath,
+      processId: processId ?? undefined,
+      targetPort: localPort ?? undefined,
+    })
+      .then((info) => {
+        if (!cancelled) setProxyUrl(info.url);
+      })
+      .catch((e) => console.error('Failed to start preview proxy:', e));
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, projectPath, processId, status, url, localPort]);
+
+  const previewUrl = proxyUrl ?? url;
+
   // Track build state to refresh preview after story completion - subscribe directly for reactivity
   const buildState = useBuildStore((state) => state.projectStates[projectId]);
   
@@ -79,12 +118,14 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
     (s) => s === 'complete'
   ).length;
 
-  // Refresh preview when a story completes (if server is running)
+  // Refresh preview when a story completes (if server is running). The proxy
+  // reloads the page itself, so this only applies to direct dev server URLs.
   useEffect(() => {
     if (
       completedCount > lastCompletedCountRef.current &&
       status === 'running' &&
       url &&
+      !proxyUrl &&
       iframeRef.current
     ) {
       // Small delay to let any file writes complete
@@ -96,7 +137,7 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
       }, 1000);
     }
     lastCompletedCountRef.current = completedCount;
-  }, [completedCount, status, url]);
+  }, [completedCount, status, url, proxyUrl]);
 
   // Auto-detect dev server when panel is expanded (and user hasn't manually stopped it)
   useEffect(() => {
@@ -115,7 +156,7 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
   // Reset iframe loaded state when URL changes
   useEffect(() => {
     setIsIframeLoaded(false);
-  }, [url]);
+  }, [previewUrl]);
 
   // Close zoom presets dropdown when clicking outside
   useEffect(() => {
@@ -166,9 +207,20 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
   };
 
   const handleRefresh = () => {
-    if (iframeRef.current && url) {
+    if (iframeRef.current && previewUrl) {
       setIsIframeLoaded(false);
-      iframeRef.current.src = url;
+      iframeRef.current.src = previewUrl;
+    }
+  };
+
+  const handleOpenOnPhone = async () => {
+    setIsOpeningMobile(true);
+    try {
+      setMobilePreview(await invoke<MobilePreview>('get_mobile_preview', { projectId }));
+    } catch (e) {
+      notify.error('Could not open on phone', String(e));
+    } finally {
+      setIsOpeningMobile(false);
     }
   };
 
@@ -433,6 +485,22 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
                   </svg>
                 )}
               </button>
+
+              {/* Mobile preview button */}
+              <button
+                onClick={handleOpenOnPhone}
+                disabled={!url || status !== 'running' || isOpeningMobile}
+                className={`p-1 rounded transition-colors flex items-center justify-center ${
+                  !url || status !== 'running' || isOpeningMobile
+                    ? 'text-muted/50 cursor-not-allowed'
+                    : 'text-muted hover:text-foreground hover:bg-card'
+                }`}
+                title={isOpeningMobile ? 'Opening tunnel...' : 'Open on phone'}
+              >
+                <svg className={`w-3.5 h-3.5 ${isOpeningMobile ? 'animate-pulse' : ''}`} fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M12 18h.01M8 21h8a2 2 0 002-2V5a2 2 0 00-2-2H8a2 2 0 00-2 2v14a2 2 0 002 2z" />
+                </svg>
+              </button>
             </div>
           </div>
 
@@ -510,7 +578,7 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
               >
                 <iframe
                   ref={iframeRef}
-                  src={url}
+                  src={previewUrl ?? undefined}
                   onLoad={handleIframeLoad}
                   className="border-none origin-top-left"
                   style={{
@@ -677,6 +745,33 @@ export function PreviewPanel({ projectId, projectPath }: PreviewPanelProps) {
           </div>
         </div>
       )}
+
+      {/* Mobile preview QR code */}
+      {mobilePreview && (
+        <div
+          className="fixed inset-0 bg-black/50 flex items-center justify-center z-50"
+          onClick={(e) => {
+            if (e.target === e.currentTarget) setMobilePreview(null);
+          }}
+        >
+          <div className="bg-card rounded-xl shadow-2xl w-full max-w-sm p-6 mx-4">
+            <h2 className="text-lg font-semibold mb-1">Open on Phone</h2>
+            <p className="text-sm text-muted mb-4">Scan with your phone's camera to open the running app.</p>
+            <div className="flex justify-center mb-4">
+              <img src={mobilePreview.qrCode} alt="QR code for the preview URL" className="w-56 h-56 rounded-lg bg-white" />
+            </div>
+            <div className="bg-background rounded-lg p-3 border border-border mb-6">
+              <code className="text-sm text-success break-all">{mobilePreview.url}</code>
+            </div>
+            <button
+              onClick={() => setMobilePreview(null)}
+              className="w-full px-4 py-2 rounded-lg border border-border text-foreground font-medium hover:bg-card transition-colors"
+            >
+              Done
+            </button>
+          </div>
+        </div>
+      )}
     </div>
   );
 }
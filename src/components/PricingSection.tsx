@@ -0,0 +1,162 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useProjectStore } from "../stores/projectStore";
+import { useCostStore } from "../stores/costStore";
+import { notify } from "../utils/notify";
+
+interface ModelPrice {
+  inputPerMillion: number;
+  outputPerMillion: number;
+  cacheReadPerMillion: number | null;
+  cacheWritePerMillion: number | null;
+}
+
+type Pricing = Record<string, ModelPrice>;
+
+const inputClass =
+  "w-full px-2 py-1 rounded border border-border bg-background text-foreground text-xs focus:outline-none focus:ring-2 focus:ring-accent";
+
+function parseRate(value: string): number | null {
+  const rate = parseFloat(value);
+  return Number.isFinite(rate) ? rate : null;
+}
+
+export function PricingSection() {
+  const [pricing, setPricing] = useState<Pricing>({});
+  const [newModel, setNewModel] = useState("");
+  const [isRecomputing, setIsRecomputing] = useState(false);
+  const projects = useProjectStore((state) => state.projects);
+  const recomputeProjectCosts = useCostStore((state) => state.recomputeProjectCosts);
+
+  useEffect(() => {
+    invoke<Pricing>("load_pricing")
+      .then(setPricing)
+      .catch((error) => notify.error("Failed to load pricing", String(error)));
+  }, []);
+
+  const savePrice = async (model: string, price: ModelPrice) => {
+    try {
+      setPricing(await invoke<Pricing>("set_model_price", { model, price }));
+    } catch (error) {
+      notify.error("Failed to save price", String(error));
+    }
+  };
+
+  const handleRateChange = (model: string, field: keyof ModelPrice, value: string) => {
+    const rate = parseRate(value);
+    if (rate === null && (field === "inputPerMillion" || field === "outputPerMillion")) return;
+    savePrice(model, { ...pricing[model], [field]: rate });
+  };
+
+  const handleAdd = () => {
+    const model = newModel.trim();
+    if (!model) return;
+    savePrice(model, { inputPerMillion: 0, outputPerMillion: 0, cacheReadPerMillion: null, cacheWritePerMillion: null });
+    setNewModel("");
+  };
+
+  const handleDelete = async (model: string) => {
+    try {
+      setPricing(await invoke<Pricing>("delete_model_price", { model }));
+    } catch (error) {
+      notify.error("Failed to remove price", String(error));
+    }
+  };
+
+  const handleReset = async () => {
+    try {
+      setPricing(await invoke<Pricing>("reset_pricing"));
+    } catch (error) {
+      notify.error("Failed to reset pricing", String(error));
+    }
+  };
+
+  const handleRecompute = async () => {
+    setIsRecomputing(true);
+    let updated = 0;
+    try {
+      for (const project of projects) {
+        updated += (await recomputeProjectCosts(project.id, project.path)).updated;
+      }
+      notify.success("Costs recomputed", `${updated} ${updated === 1 ? "entry" : "entries"} updated`);
+    } catch (error) {
+      notify.error("Failed to recompute costs", String(error));
+    } finally {
+      setIsRecomputing(false);
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Model Pricing
+      </h3>
+      <p className="text-xs text-muted mb-3">
+        USD per million tokens, used to price recorded runs. Model names match by prefix, so "claude-sonnet-4" also covers dated versions.
+      </p>
+      <div className="border border-border rounded-lg overflow-hidden">
+        <table className="w-full text-xs">
+          <thead className="bg-background-secondary text-muted">
+            <tr>
+              <th className="text-left font-medium px-2 py-1.5">Model</th>
+              <th className="text-left font-medium px-2 py-1.5">Input</th>
+              <th className="text-left font-medium px-2 py-1.5">Output</th>
+              <th className="text-left font-medium px-2 py-1.5">Cache Read</th>
+              <th className="text-left font-medium px-2 py-1.5">Cache Write</th>
+              <th />
+            </tr>
+          </thead>
+          <tbody>
+            {Object.entries(pricing).map(([model, price]) => (
+              <tr key={model} className="border-t border-border">
+                <td className="px-2 py-1 text-foreground font-mono">{model}</td>
+                {(["inputPerMillion", "outputPerMillion", "cacheReadPerMillion", "cacheWritePerMillion"] as const).map((field) => (
+                  <td key={field} className="px-2 py-1">
+                    <input
+                      type="number"
+                      min={0}
+                      step="0.01"
+                      defaultValue={price[field] ?? ""}
+                      onBlur={(e) => {
+                        if (parseRate(e.target.value) !== price[field]) {
+                          handleRateChange(model, field, e.target.value);
+                        }
+                      }}
+                      className={inputClass}
+                    />
+                  </td>
+                ))}
+                <td className="px-2 py-1 text-right">
+                  <button onClick={() => handleDelete(model)} className="text-muted hover:text-destructive" title="Remove">
+                    ×
+                  </button>
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      </div>
+      <div className="flex items-center gap-2 mt-3">
+        <input
+          type="text"
+          value={newModel}
+          onChange={(e) => setNewModel(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder="Model name"
+          className="flex-1 px-3 py-1.5 rounded-lg border border-border bg-background text-foreground text-sm focus:outline-none focus:ring-2 focus:ring-accent"
+        />
+        <button onClick={handleAdd} disabled={!newModel.trim()} className="btn btn-sm btn-secondary">
+          Add Model
+        </button>
+      </div>
+      <div className="flex gap-2 mt-3">
+        <button onClick={handleRecompute} disabled={isRecomputing || projects.length === 0} className="btn btn-sm btn-secondary">
+          {isRecomputing ? "Recomputing…" : "Recompute Project Costs"}
+        </button>
+        <button onClick={handleReset} className="btn btn-sm btn-secondary">
+          Reset to Defaults
+        </button>
+      </div>
+    </section>
+  );
+}
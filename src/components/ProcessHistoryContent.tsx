@@ -1,5 +1,7 @@
 import { useEffect, useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { AttachedProcesses } from './AttachedProcesses'
+import { openAppWindow, windowLabelFor } from '../utils/appWindow'
 
 interface ProcessCommand {
   executable: string
@@ -18,6 +20,7 @@ interface ProcessHistoryEntry {
   exitCode: number | null
   success: boolean
   agentId?: string
+  fallbackFrom?: string
   command?: ProcessCommand
   logFilePath?: string
 }
@@ -26,6 +29,42 @@ interface ProcessHistory {
   entries: ProcessHistoryEntry[]
 }
 
+interface LogChunk {
+  content: string
+  offset: number
+  endOffset: number
+  totalSize: number
+}
+
+interface LogFileInfo {
+  size: number
+  lineCount: number
+}
+
+interface LogSearchMatch {
+  processId: string
+  processType: string
+  label: string
+  startedAt: string
+  logFilePath: string
+  lineNumber: number
+  line: string
+  contextBefore: string[]
+  contextAfter: string[]
+}
+
+interface LogSearchResult {
+  matches: LogSearchMatch[]
+  filesSearched: number
+  truncated: boolean
+}
+
+/** Lines shown when a log is first opened. */
+const LOG_TAIL_LINES = 1000
+
+/** Bytes fetched per "Load earlier" click. */
+const LOG_CHUNK_BYTES = 256 * 1024
+
 interface ProcessHistoryContentProps {
   projectId: string
 }
@@ -51,6 +90,12 @@ function formatDate(isoDate: string): string {
   })
 }
 
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+}
+
 function getProcessTypeLabel(type: string): string {
   switch (type) {
     case 'build': return 'Story Build'
@@ -58,6 +103,8 @@ function getProcessTypeLabel(type: string): string {
     case 'chat': return 'Chat'
     case 'dev-server': return 'Dev Server'
     case 'detection': return 'Detection'
+    case 'hook': return 'Hook'
+    case 'external': return 'External'
     default: return type
   }
 }
@@ -69,6 +116,8 @@ function getProcessTypeColor(type: string): string {
     case 'chat': return 'bg-blue-500/10 text-blue-500'
     case 'dev-server': return 'bg-orange-500/10 text-orange-500'
     case 'detection': return 'bg-muted/20 text-muted'
+    case 'hook': return 'bg-teal-500/10 text-teal-500'
+    case 'external': return 'bg-yellow-500/10 text-yellow-600'
     default: return 'bg-muted/10 text-muted'
   }
 }
@@ -76,16 +125,25 @@ function getProcessTypeColor(type: string): string {
 function ProcessHistoryItem({ entry }: { entry: ProcessHistoryEntry }) {
   const [expanded, setExpanded] = useState(false)
   const [logContent, setLogContent] = useState<string | null>(null)
+  const [logOffset, setLogOffset] = useState(0)
+  const [logInfo, setLogInfo] = useState<LogFileInfo | null>(null)
   const [loadingLog, setLoadingLog] = useState(false)
+  const [loadingEarlier, setLoadingEarlier] = useState(false)
 
   const handleToggle = async () => {
     if (!expanded && entry.logFilePath && !logContent) {
       setLoadingLog(true)
       try {
-        const content = await invoke<string>('read_process_log_file', {
-          logFilePath: entry.logFilePath,
-        })
-        setLogContent(content)
+        const [chunk, info] = await Promise.all([
+          invoke<LogChunk>('read_process_log_file', {
+            logFilePath: entry.logFilePath,
+            tailLines: LOG_TAIL_LINES,
+          }),
+          invoke<LogFileInfo>('get_log_file_info', { logFilePath: entry.logFilePath }),
+        ])
+        setLogContent(chunk.content)
+        setLogOffset(chunk.offset)
+        setLogInfo(info)
       } catch (e) {
         console.error('Failed to read log file:', e)
         setLogContent('Failed to load log file')
@@ -95,6 +153,33 @@ function ProcessHistoryItem({ entry }: { entry: ProcessHistoryEntry }) {
     setExpanded(!expanded)
   }
 
+  const handleLoadEarlier = async () => {
+    if (!entry.logFilePath || logOffset === 0) return
+    setLoadingEarlier(true)
+    try {
+      const offset = Math.max(0, logOffset - LOG_CHUNK_BYTES)
+      const chunk = await invoke<LogChunk>('read_process_log_file', {
+        logFilePath: entry.logFilePath,
+        offset,
+        limit: logOffset - offset,
+      })
+      setLogContent((prev) => chunk.content + (prev ?? ''))
+      setLogOffset(chunk.offset)
+    } catch (e) {
+      console.error('Failed to read log file:', e)
+    }
+    setLoadingEarlier(false)
+  }
+
+  const handleOpenLogWindow = (logFilePath: string) => {
+    const route = `/log-viewer?path=${encodeURIComponent(logFilePath)}`
+    openAppWindow(windowLabelFor('log', logFilePath), route, {
+      title: `Log - ${entry.label}`,
+      width: 900,
+      height: 600,
+    }).catch((e) => console.error('Failed to open log window:', e))
+  }
+
   return (
     <div className="border border-border rounded-lg overflow-hidden">
       <button
@@ -133,7 +218,13 @@ function ProcessHistoryItem({ entry }: { entry: ProcessHistoryEntry }) {
         </div>
 
         {entry.agentId && (
-          <span className="text-xs text-muted capitalize">{entry.agentId}</span>
+          <span
+            className="text-xs text-muted capitalize"
+            title={entry.fallbackFrom ? `Used after ${entry.fallbackFrom} failed` : undefined}
+          >
+            {entry.agentId}
+            {entry.fallbackFrom && <span className="normal-case text-warning"> (fallback)</span>}
+          </span>
         )}
       </button>
 
@@ -178,7 +269,34 @@ function ProcessHistoryItem({ entry }: { entry: ProcessHistoryEntry }) {
             {/* Log content */}
             {entry.logFilePath && (
               <div>
-                <div className="text-sm text-muted mb-1">Log output:</div>
+                <div className="flex items-center justify-between text-sm text-muted mb-1">
+                  <span>Log output:</span>
+                  <div className="flex items-center gap-2">
+                    {logInfo && (
+                      <span className="text-xs">
+                        {logInfo.lineCount.toLocaleString()} lines • {formatBytes(logInfo.size)}
+                      </span>
+                    )}
+                    {/* Compressed logs can't be followed */}
+                    {!entry.logFilePath.endsWith('.gz') && (
+                      <button
+                        onClick={() => handleOpenLogWindow(entry.logFilePath!)}
+                        className="text-xs text-accent hover:underline"
+                      >
+                        Open in window
+                      </button>
+                    )}
+                  </div>
+                </div>
+                {!loadingLog && logContent && logOffset > 0 && (
+                  <button
+                    onClick={handleLoadEarlier}
+                    disabled={loadingEarlier}
+                    className="btn btn-sm btn-secondary mb-2"
+                  >
+                    {loadingEarlier ? 'Loading...' : `Load earlier (${formatBytes(logOffset)} not shown)`}
+                  </button>
+                )}
                 {loadingLog ? (
                   <div className="text-sm text-muted">Loading...</div>
                 ) : logContent ? (
@@ -197,10 +315,78 @@ function ProcessHistoryItem({ entry }: { entry: ProcessHistoryEntry }) {
   )
 }
 
+function LogSearchResults({ result }: { result: LogSearchResult }) {
+  if (result.matches.length === 0) {
+    return (
+      <div className="text-center text-muted py-12">
+        No matches in {result.filesSearched} {result.filesSearched === 1 ? 'log' : 'logs'}
+      </div>
+    )
+  }
+
+  return (
+    <div className="max-w-4xl mx-auto space-y-3">
+      <p className="text-xs text-muted">
+        {result.matches.length} {result.matches.length === 1 ? 'match' : 'matches'} in {result.filesSearched}{' '}
+        {result.filesSearched === 1 ? 'log' : 'logs'}
+        {result.truncated && ' (showing the first ones only)'}
+      </p>
+      {result.matches.map((match) => (
+        <div
+          key={`${match.logFilePath}:${match.lineNumber}`}
+          className="border border-border rounded-lg bg-card overflow-hidden"
+        >
+          <div className="px-3 py-2 border-b border-border flex items-center justify-between text-xs">
+            <span className="font-medium truncate">
+              {getProcessTypeLabel(match.processType)}: {match.label}
+            </span>
+            <span className="text-muted flex-shrink-0 ml-2">
+              {formatDate(match.startedAt)} · line {match.lineNumber}
+            </span>
+          </div>
+          <pre className="text-xs font-mono p-3 overflow-x-auto whitespace-pre-wrap">
+            {match.contextBefore.map((line, i) => (
+              <div key={`b${i}`} className="text-muted">{line}</div>
+            ))}
+            <div className="text-foreground bg-accent/10">{match.line}</div>
+            {match.contextAfter.map((line, i) => (
+              <div key={`a${i}`} className="text-muted">{line}</div>
+            ))}
+          </pre>
+        </div>
+      ))}
+    </div>
+  )
+}
+
 export function ProcessHistoryContent({ projectId }: ProcessHistoryContentProps) {
   const [history, setHistory] = useState<ProcessHistoryEntry[]>([])
   const [loading, setLoading] = useState(true)
   const [filter, setFilter] = useState<string>('all')
+  const [query, setQuery] = useState('')
+  const [searching, setSearching] = useState(false)
+  const [searchResult, setSearchResult] = useState<LogSearchResult | null>(null)
+
+  useEffect(() => {
+    setQuery('')
+    setSearchResult(null)
+  }, [projectId])
+
+  const runSearch = async () => {
+    if (!query.trim()) {
+      setSearchResult(null)
+      return
+    }
+    setSearching(true)
+    try {
+      setSearchResult(await invoke<LogSearchResult>('search_process_logs', { projectId, query }))
+    } catch (e) {
+      console.error('Failed to search process logs:', e)
+    }
+    setSearching(false)
+  }
+
+  const [historyVersion, setHistoryVersion] = useState(0)
 
   useEffect(() => {
     async function loadHistory() {
@@ -214,7 +400,7 @@ export function ProcessHistoryContent({ projectId }: ProcessHistoryContentProps)
       setLoading(false)
     }
     loadHistory()
-  }, [projectId])
+  }, [projectId, historyVersion])
 
   const filteredHistory = filter === 'all' 
     ? history 
@@ -234,26 +420,45 @@ export function ProcessHistoryContent({ projectId }: ProcessHistoryContentProps)
             </p>
           </div>
 
-          {processTypes.length > 1 && (
-            <select
-              value={filter}
-              onChange={(e) => setFilter(e.target.value)}
-              className="bg-background border border-border rounded-lg px-3 py-1.5 text-sm"
-            >
-              <option value="all">All types</option>
-              {processTypes.map((type) => (
-                <option key={type} value={type}>
-                  {getProcessTypeLabel(type)}
-                </option>
-              ))}
-            </select>
-          )}
+          <div className="flex items-center gap-2">
+            <input
+              type="search"
+              value={query}
+              onChange={(e) => {
+                setQuery(e.target.value)
+                if (!e.target.value) setSearchResult(null)
+              }}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') runSearch()
+              }}
+              placeholder={searching ? 'Searching…' : 'Search logs'}
+              className="bg-background border border-border rounded-lg px-3 py-1.5 text-sm w-56"
+            />
+
+            {processTypes.length > 1 && (
+              <select
+                value={filter}
+                onChange={(e) => setFilter(e.target.value)}
+                className="bg-background border border-border rounded-lg px-3 py-1.5 text-sm"
+              >
+                <option value="all">All types</option>
+                {processTypes.map((type) => (
+                  <option key={type} value={type}>
+                    {getProcessTypeLabel(type)}
+                  </option>
+                ))}
+              </select>
+            )}
+          </div>
         </div>
       </div>
 
       {/* Content */}
       <div className="flex-1 overflow-auto p-6">
-        {loading ? (
+        <AttachedProcesses projectId={projectId} onExit={() => setHistoryVersion((v) => v + 1)} />
+        {searchResult ? (
+          <LogSearchResults result={searchResult} />
+        ) : loading ? (
           <div className="flex items-center justify-center h-full">
             <div className="text-muted">Loading history...</div>
           </div>
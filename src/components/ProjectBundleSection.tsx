@@ -0,0 +1,61 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
+import { notify } from "../utils/notify";
+
+interface BundleExportSummary {
+  path: string;
+  files: number;
+  branches: string[];
+  skipped: string[];
+  bytes: number;
+}
+
+interface ProjectBundleSectionProps {
+  projectId: string;
+  projectName: string;
+}
+
+export function ProjectBundleSection({ projectId, projectName }: ProjectBundleSectionProps) {
+  const [isExporting, setIsExporting] = useState(false);
+
+  const handleExport = async () => {
+    const slug = projectName.toLowerCase().replace(/[^a-z0-9]+/g, "-").replace(/^-|-$/g, "") || "project";
+    const path = await save({
+      defaultPath: `${slug}.ideatepkg`,
+      filters: [{ name: "Ideate Project Bundle", extensions: ["ideatepkg"] }],
+      title: "Export Project Bundle",
+    });
+    if (!path) return;
+
+    setIsExporting(true);
+    try {
+      const summary = await invoke<BundleExportSummary>("export_project_bundle", { projectId, path });
+      const size = (summary.bytes / (1024 * 1024)).toFixed(1);
+      notify.success(
+        "Project bundle exported",
+        `${summary.files} files and ${summary.branches.length} branches, ${size} MB`
+      );
+      if (summary.skipped.length > 0) {
+        notify.warning("Some files were too large to bundle", summary.skipped.join(", "));
+      }
+    } catch (error) {
+      notify.error("Export failed", String(error));
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <label className="block text-sm font-medium text-foreground">Project Bundle</label>
+      <p className="text-xs text-muted mb-2">
+        Save the PRD, design, build state, cost history, and every branch to one .ideatepkg file that a teammate can
+        import to pick up where this build left off. Secret values are not included.
+      </p>
+      <button onClick={handleExport} disabled={isExporting} className="btn btn-sm btn-secondary">
+        {isExporting ? "Exporting..." : "Export Bundle…"}
+      </button>
+    </div>
+  );
+}
@@ -0,0 +1,163 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface EnvVariable {
+  name: string;
+  value: string | null;
+  secret: boolean;
+  hasValue: boolean;
+}
+
+interface ProjectEnv {
+  syncDotenv: boolean;
+  variables: EnvVariable[];
+}
+
+interface ProjectEnvSectionProps {
+  projectPath: string;
+}
+
+interface EditableVariable {
+  name: string;
+  /** For a secret, empty keeps the stored value */
+  value: string;
+  secret: boolean;
+  hasValue: boolean;
+}
+
+const toEditable = (env: ProjectEnv): EditableVariable[] =>
+  env.variables.map((v) => ({ name: v.name, value: v.value ?? "", secret: v.secret, hasValue: v.hasValue }));
+
+export function ProjectEnvSection({ projectPath }: ProjectEnvSectionProps) {
+  const [variables, setVariables] = useState<EditableVariable[]>([]);
+  const [syncDotenv, setSyncDotenv] = useState(false);
+  const [isDirty, setIsDirty] = useState(false);
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setError(null);
+    setIsDirty(false);
+    invoke<ProjectEnv>("load_project_env", { projectPath })
+      .then((env) => {
+        setVariables(toEditable(env));
+        setSyncDotenv(env.syncDotenv);
+      })
+      .catch((err) => setError(String(err)));
+  }, [projectPath]);
+
+  const update = (index: number, change: Partial<EditableVariable>) => {
+    setVariables((current) => current.map((v, i) => (i === index ? { ...v, ...change } : v)));
+    setIsDirty(true);
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    setError(null);
+    try {
+      const env = await invoke<ProjectEnv>("save_project_env", {
+        projectPath,
+        syncDotenv,
+        variables: variables
+          .filter((v) => v.name.trim())
+          .map((v) => ({
+            name: v.name.trim(),
+            // An empty secret keeps what is stored
+            value: v.secret && !v.value ? null : v.value,
+            secret: v.secret,
+          })),
+      });
+      setVariables(toEditable(env));
+      setSyncDotenv(env.syncDotenv);
+      setIsDirty(false);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <label className="block text-sm font-medium text-foreground">Environment Variables</label>
+      <p className="text-xs text-muted mb-2">
+        Passed to agents and terminals started in this project. Secret values are kept in Ideate&apos;s secret store, not
+        in .ideate/env.json, and are masked in logs.
+      </p>
+
+      {variables.length > 0 && (
+        <div className="space-y-2">
+          {variables.map((variable, index) => (
+            <div key={index} className="flex items-center gap-2">
+              <input
+                type="text"
+                value={variable.name}
+                onChange={(e) => update(index, { name: e.target.value })}
+                placeholder="NAME"
+                className="w-40 px-2 py-1.5 rounded-lg border border-border bg-background text-foreground text-xs font-mono focus:outline-none focus:ring-2 focus:ring-accent"
+              />
+              <input
+                type={variable.secret ? "password" : "text"}
+                value={variable.value}
+                onChange={(e) => update(index, { value: e.target.value })}
+                placeholder={variable.secret && variable.hasValue ? "•••••••• (unchanged)" : "value"}
+                className="flex-1 min-w-0 px-2 py-1.5 rounded-lg border border-border bg-background text-foreground text-xs font-mono focus:outline-none focus:ring-2 focus:ring-accent"
+              />
+              <label className="flex items-center gap-1 text-xs text-muted cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={variable.secret}
+                  onChange={(e) => update(index, { secret: e.target.checked, hasValue: false })}
+                  className="accent-accent"
+                />
+                Secret
+              </label>
+              <button
+                onClick={() => {
+                  setVariables((current) => current.filter((_, i) => i !== index));
+                  setIsDirty(true);
+                }}
+                className="text-xs text-muted hover:text-destructive"
+                title="Remove"
+              >
+                ×
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <label className="flex items-center gap-2 text-xs text-muted cursor-pointer">
+        <input
+          type="checkbox"
+          checked={syncDotenv}
+          onChange={(e) => {
+            setSyncDotenv(e.target.checked);
+            setIsDirty(true);
+          }}
+          className="accent-accent"
+        />
+        Also write them to .env (added to .gitignore)
+      </label>
+
+      <div className="flex items-center gap-2">
+        <button
+          onClick={() => {
+            setVariables((current) => [...current, { name: "", value: "", secret: false, hasValue: false }]);
+            setIsDirty(true);
+          }}
+          className="btn btn-sm btn-secondary"
+        >
+          Add Variable
+        </button>
+        {isDirty && (
+          <button onClick={handleSave} disabled={isSaving} className="btn btn-sm btn-primary">
+            {isSaving ? "Saving..." : "Save Variables"}
+          </button>
+        )}
+      </div>
+
+      {error && <p className="text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
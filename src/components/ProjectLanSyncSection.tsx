@@ -0,0 +1,100 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { LanSyncStatus } from "./LanSyncSection";
+
+interface ProjectLanSyncSectionProps {
+  projectPath: string;
+}
+
+interface OfferedProject {
+  syncId: string;
+  name: string;
+  peerName: string;
+}
+
+export function ProjectLanSyncSection({ projectPath }: ProjectLanSyncSectionProps) {
+  const [syncId, setSyncId] = useState<string | null>(null);
+  const [status, setStatus] = useState<LanSyncStatus | null>(null);
+  const [linkTo, setLinkTo] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setError(null);
+    invoke<unknown>("get_project_metadata", { projectPath, key: "syncId" })
+      .then((value) => setSyncId(typeof value === "string" && value ? value : null))
+      .catch((err) => setError(String(err)));
+    invoke<LanSyncStatus>("get_lan_sync_status")
+      .then(setStatus)
+      .catch(() => setStatus(null));
+  }, [projectPath]);
+
+  const handleToggle = async () => {
+    setError(null);
+    try {
+      setSyncId(await invoke<string | null>("set_project_lan_sync", { projectPath, enabled: !syncId }));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleLink = async () => {
+    setError(null);
+    try {
+      await invoke("link_lan_sync_project", { projectPath, syncId: linkTo });
+      setSyncId(linkTo);
+      setLinkTo("");
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  // Projects other computers share that no project here is paired with yet
+  const offered: OfferedProject[] = (status?.peers ?? []).flatMap((peer) =>
+    peer.projects
+      .filter((p) => p.syncId !== syncId)
+      .map((p) => ({ syncId: p.syncId, name: p.name, peerName: peer.name }))
+  );
+  const pairedWith = (status?.peers ?? []).filter((peer) => peer.projects.some((p) => p.syncId === syncId));
+
+  return (
+    <div className="space-y-2">
+      <label className="flex items-start gap-3 cursor-pointer">
+        <input type="checkbox" checked={!!syncId} onChange={handleToggle} className="mt-0.5 accent-accent" />
+        <div>
+          <div className="text-sm font-medium text-foreground">Sync over LAN</div>
+          <div className="text-xs text-muted">
+            {!syncId
+              ? "Share this project's .ideate state and build progress with your other computers"
+              : pairedWith.length > 0
+                ? `Syncing with ${pairedWith.map((p) => p.name).join(", ")}`
+                : status?.running
+                  ? "Waiting for another computer with this project"
+                  : "Turn on LAN Sync in Settings to start"}
+          </div>
+        </div>
+      </label>
+
+      {offered.length > 0 && (
+        <div className="flex items-center gap-2 pl-7">
+          <select
+            value={linkTo}
+            onChange={(e) => setLinkTo(e.target.value)}
+            className="flex-1 min-w-0 px-2 py-1.5 rounded-lg border border-border bg-background text-foreground text-xs focus:outline-none focus:ring-2 focus:ring-accent"
+          >
+            <option value="">Pair with a project on another computer…</option>
+            {offered.map((p) => (
+              <option key={`${p.peerName}-${p.syncId}`} value={p.syncId}>
+                {p.name} on {p.peerName}
+              </option>
+            ))}
+          </select>
+          <button onClick={handleLink} disabled={!linkTo} className="btn btn-sm btn-secondary">
+            Pair
+          </button>
+        </div>
+      )}
+
+      {error && <p className="text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
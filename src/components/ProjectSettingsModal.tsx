@@ -2,14 +2,22 @@ import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
 import { defaultPlugins, type AgentPlugin } from "../types";
+import { useBuildModes } from "../hooks/useBuildModes";
+import { MetadataHistorySection } from "./MetadataHistorySection";
+import { ProjectEnvSection } from "./ProjectEnvSection";
+import { ProjectBundleSection } from "./ProjectBundleSection";
+import { ProjectLanSyncSection } from "./ProjectLanSyncSection";
+import { ProjectStacksSection } from "./ProjectStacksSection";
 
 export type AutonomyLevel = "autonomous" | "pause-between" | "manual";
-export type BuildMode = "ralph" | "parallel" | "none";
+export type BuildMode = string;
 
 interface ProjectSettings {
   agent: string | null;
   autonomy: AutonomyLevel;
   buildMode: BuildMode | null;
+  muteNotifications?: boolean;
+  excludeStackSnippets?: boolean;
 }
 
 interface Preferences {
@@ -32,12 +40,6 @@ const autonomyOptions: { value: AutonomyLevel; label: string; description: strin
   { value: "manual", label: "Manual", description: "Requires manual start for each story" },
 ];
 
-const buildModeOptions: { value: BuildMode; label: string; description: string }[] = [
-  { value: "ralph", label: "Ralph (Sequential)", description: "Execute stories one at a time in order" },
-  { value: "parallel", label: "Parallel", description: "Run multiple stories concurrently" },
-  { value: "none", label: "None", description: "No automatic building" },
-];
-
 function SettingsIcon({ className = "w-4 h-4" }: { className?: string }) {
   return (
     <svg className={className} fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -57,6 +59,9 @@ export function ProjectSettingsModal({
   const [selectedAgent, setSelectedAgent] = useState<string>("");
   const [autonomyLevel, setAutonomyLevel] = useState<AutonomyLevel>("autonomous");
   const [buildMode, setBuildMode] = useState<BuildMode>("ralph");
+  const [muteNotifications, setMuteNotifications] = useState(false);
+  const [excludeStackSnippets, setExcludeStackSnippets] = useState(false);
+  const buildModeOptions = useBuildModes();
   const [isLoading, setIsLoading] = useState(true);
   const [isSaving, setIsSaving] = useState(false);
   const [hasChanges, setHasChanges] = useState(false);
@@ -64,6 +69,8 @@ export function ProjectSettingsModal({
     agent: string;
     autonomy: AutonomyLevel;
     buildMode: BuildMode;
+    muteNotifications: boolean;
+    excludeStackSnippets: boolean;
   } | null>(null);
 
   useModalKeyboard(isOpen, onClose);
@@ -89,11 +96,21 @@ export function ProjectSettingsModal({
           const agent = settings?.agent || defaultAgent;
           const autonomy = settings?.autonomy || defaultAutonomy;
           const mode = settings?.buildMode || defaultBuildMode;
+          const muted = settings?.muteNotifications ?? false;
+          const excludeSnippets = settings?.excludeStackSnippets ?? false;
 
           setSelectedAgent(agent);
           setAutonomyLevel(autonomy);
           setBuildMode(mode);
-          setOriginalSettings({ agent, autonomy, buildMode: mode });
+          setMuteNotifications(muted);
+          setExcludeStackSnippets(excludeSnippets);
+          setOriginalSettings({
+            agent,
+            autonomy,
+            buildMode: mode,
+            muteNotifications: muted,
+            excludeStackSnippets: excludeSnippets,
+          });
           setHasChanges(false);
         }
       } catch (err) {
@@ -122,10 +139,12 @@ export function ProjectSettingsModal({
       const changed =
         selectedAgent !== originalSettings.agent ||
         autonomyLevel !== originalSettings.autonomy ||
-        buildMode !== originalSettings.buildMode;
+        buildMode !== originalSettings.buildMode ||
+        muteNotifications !== originalSettings.muteNotifications ||
+        excludeStackSnippets !== originalSettings.excludeStackSnippets;
       setHasChanges(changed);
     }
-  }, [selectedAgent, autonomyLevel, buildMode, originalSettings]);
+  }, [selectedAgent, autonomyLevel, buildMode, muteNotifications, excludeStackSnippets, originalSettings]);
 
   const handleSave = async () => {
     setIsSaving(true);
@@ -136,6 +155,8 @@ export function ProjectSettingsModal({
           agent: selectedAgent || null,
           autonomy: autonomyLevel,
           buildMode: buildMode,
+          muteNotifications,
+          excludeStackSnippets,
         },
       });
       
@@ -144,7 +165,13 @@ export function ProjectSettingsModal({
         detail: { projectId, projectPath }
       }));
       
-      setOriginalSettings({ agent: selectedAgent, autonomy: autonomyLevel, buildMode });
+      setOriginalSettings({
+        agent: selectedAgent,
+        autonomy: autonomyLevel,
+        buildMode,
+        muteNotifications,
+        excludeStackSnippets,
+      });
       setHasChanges(false);
       onClose();
     } catch (err) {
@@ -279,6 +306,44 @@ export function ProjectSettingsModal({
                   ))}
                 </div>
               </div>
+
+              {/* Notifications */}
+              <label className="flex items-start gap-3 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={muteNotifications}
+                  onChange={(e) => setMuteNotifications(e.target.checked)}
+                  className="mt-0.5 accent-accent"
+                />
+                <div>
+                  <div className="text-sm font-medium text-foreground">Mute notifications</div>
+                  <div className="text-xs text-muted">Don't show desktop notifications for this project's builds</div>
+                </div>
+              </label>
+
+              <ProjectStacksSection projectId={projectId} />
+
+              {/* Stack prompt snippets */}
+              <label className="flex items-start gap-3 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={excludeStackSnippets}
+                  onChange={(e) => setExcludeStackSnippets(e.target.checked)}
+                  className="mt-0.5 accent-accent"
+                />
+                <div>
+                  <div className="text-sm font-medium text-foreground">Skip stack prompt snippets</div>
+                  <div className="text-xs text-muted">Don't add the stack's conventions to this project's build prompts</div>
+                </div>
+              </label>
+
+              <ProjectLanSyncSection projectPath={projectPath} />
+
+              <ProjectEnvSection projectPath={projectPath} />
+
+              <MetadataHistorySection projectPath={projectPath} />
+
+              <ProjectBundleSection projectId={projectId} projectName={projectName} />
             </>
           )}
         </div>
@@ -0,0 +1,90 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useProjectStore } from "../stores/projectStore";
+import { useStacksStore, type Stack } from "../stores/stacksStore";
+
+interface ProjectStacksSectionProps {
+  projectId: string;
+}
+
+export function ProjectStacksSection({ projectId }: ProjectStacksSectionProps) {
+  const project = useProjectStore((state) => state.projects.find((p) => p.id === projectId));
+  const updateProject = useProjectStore((state) => state.updateProject);
+  const stacks = useStacksStore((state) => state.stacks);
+  const [effective, setEffective] = useState<Stack | null>(null);
+
+  const stackId = project?.stackId ?? "";
+  const extraStackIds = project?.extraStackIds ?? [];
+
+  useEffect(() => {
+    invoke<Stack | null>("get_project_stack", { projectId })
+      .then(setEffective)
+      .catch(() => setEffective(null));
+  }, [projectId, stackId, extraStackIds.join(",")]);
+
+  if (!project) return null;
+
+  const handleStackChange = (id: string) => {
+    updateProject(projectId, {
+      stackId: id || undefined,
+      extraStackIds: id ? extraStackIds.filter((extra) => extra !== id) : [],
+    });
+  };
+
+  const toggleExtraStack = (id: string) => {
+    const next = extraStackIds.includes(id)
+      ? extraStackIds.filter((extra) => extra !== id)
+      : [...extraStackIds, id];
+    updateProject(projectId, { extraStackIds: next });
+  };
+
+  return (
+    <div className="space-y-2">
+      <label className="block text-sm font-medium text-foreground">Stack</label>
+      <p className="text-xs text-muted mb-2">
+        Combine stacks for projects with several parts, such as a frontend and an API
+      </p>
+      <select
+        value={stackId}
+        onChange={(e) => handleStackChange(e.target.value)}
+        className="w-full px-3 py-2 bg-card border border-border rounded-lg text-sm text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+      >
+        <option value="">No stack</option>
+        {stacks.map((stack) => (
+          <option key={stack.id} value={stack.id}>
+            {stack.icon ? `${stack.icon} ` : ""}{stack.name}
+          </option>
+        ))}
+      </select>
+
+      {stackId && (
+        <div className="flex flex-wrap gap-1">
+          {stacks
+            .filter((stack) => stack.id !== stackId)
+            .map((stack) => {
+              const selected = extraStackIds.includes(stack.id);
+              return (
+                <button
+                  key={stack.id}
+                  onClick={() => toggleExtraStack(stack.id)}
+                  className={`text-xs px-2 py-0.5 rounded-full border transition-colors ${
+                    selected
+                      ? "border-accent bg-accent/10 text-accent"
+                      : "border-border text-muted hover:text-foreground"
+                  }`}
+                >
+                  {selected ? "✓ " : "+ "}{stack.name}
+                </button>
+              );
+            })}
+        </div>
+      )}
+
+      {effective && extraStackIds.length > 0 && (
+        <p className="text-xs text-muted">
+          {effective.tools.length} tools: {effective.tools.map((tool) => tool.name).join(", ")}
+        </p>
+      )}
+    </div>
+  );
+}
@@ -6,9 +6,12 @@ import { useCostStore } from "../stores/costStore";
 import { usePrdStore } from "../stores/prdStore";
 import { CostModal } from "./CostModal";
 import { ProjectSettingsModal, SettingsIcon } from "./ProjectSettingsModal";
+import { useBuildModes } from "../hooks/useBuildModes";
+import { formatCost, useDisplayCurrency } from "../hooks/useDisplayCurrency";
 
 export type AutonomyLevel = "autonomous" | "pause-between" | "manual";
-export type BuildMode = "ralph" | "parallel" | "none";
+/** Build mode id from `list_build_modes`, or "none". */
+export type BuildMode = string;
 
 interface ProjectSettings {
   agent: string | null;
@@ -39,12 +42,6 @@ const autonomyOptions: { value: AutonomyLevel; label: string }[] = [
   { value: "manual", label: "Manual" },
 ];
 
-const buildModeOptions: { value: BuildMode; label: string; description: string }[] = [
-  { value: "ralph", label: "Ralph", description: "Sequential story execution" },
-  { value: "parallel", label: "Parallel", description: "Run stories concurrently" },
-  { value: "none", label: "None", description: "No automatic building" },
-];
-
 function AgentIcon() {
   return (
     <svg className="w-3.5 h-3.5" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -112,34 +109,31 @@ function getAutonomyIcon(level: AutonomyLevel) {
   }
 }
 
-function getBuildModeIcon(mode: BuildMode) {
-  switch (mode) {
-    case "ralph":
-      return <RalphIcon />;
-    case "parallel":
-      return <ParallelIcon />;
-    case "none":
-      return <NoneIcon />;
-  }
+function getBuildModeIcon(mode: BuildMode, parallel: boolean) {
+  if (mode === "none") return <NoneIcon />;
+  return parallel ? <ParallelIcon /> : <RalphIcon />;
 }
 
 export function ProjectTopBar({ projectId, projectPath, projectName, projectDescription }: ProjectTopBarProps) {
   const [selectedAgent, setSelectedAgent] = useState<string>("");
   const [autonomyLevel, setAutonomyLevel] = useState<AutonomyLevel>("autonomous");
   const [buildMode, setBuildMode] = useState<BuildMode>("ralph");
+  const buildModeOptions = useBuildModes();
+  const currency = useDisplayCurrency();
   const [isLoading, setIsLoading] = useState(true);
   const [isCostModalOpen, setIsCostModalOpen] = useState(false);
   const [isSettingsModalOpen, setIsSettingsModalOpen] = useState(false);
 
   const buildStatus = useBuildStore((state) => state.projectStates[projectId]?.status ?? 'idle');
-  const pauseBuild = useBuildStore((state) => state.pauseBuild);
+  const requestPause = useBuildStore((state) => state.requestPause);
+  const requestResume = useBuildStore((state) => state.requestResume);
   
   const handleStartBuild = () => {
     window.dispatchEvent(new CustomEvent('sidebar-start-build', { detail: { projectId } }));
   };
   
   const handleResumeBuild = () => {
-    window.dispatchEvent(new CustomEvent('resume-build', { detail: { projectId } }));
+    requestResume(projectId);
   };
   
   const handleCancelBuild = () => {
@@ -294,9 +288,10 @@ export function ProjectTopBar({ projectId, projectPath, projectName, projectDesc
                 className="flex items-center gap-1 px-2 py-1 rounded text-xs text-muted hover:text-foreground hover:bg-card transition-colors"
                 title="View usage & costs"
               >
-                <span className="font-medium">$</span>
-                {hasCostData && projectSummary.totalCost > 0 && (
-                  <span className="text-accent">{projectSummary.totalCost.toFixed(2)}</span>
+                {hasCostData && projectSummary.totalCost > 0 ? (
+                  <span className="text-accent">{formatCost(projectSummary.totalCost, currency)}</span>
+                ) : (
+                  <span className="font-medium">$</span>
                 )}
               </button>
 
@@ -305,7 +300,7 @@ export function ProjectTopBar({ projectId, projectPath, projectName, projectDesc
               {/* Build Mode selector */}
               <div className={`flex items-center gap-1.5 ${isBuilding ? 'opacity-50' : ''}`}>
                 <span className="text-muted">
-                  {getBuildModeIcon(buildMode)}
+                  {getBuildModeIcon(buildMode, buildModeOptions.find(o => o.value === buildMode)?.parallel ?? false)}
                 </span>
                 <select
                   value={buildMode}
@@ -411,7 +406,7 @@ export function ProjectTopBar({ projectId, projectPath, projectName, projectDesc
                 )}
                 {buildStatus === "running" && (
                   <button
-                    onClick={() => pauseBuild(projectId)}
+                    onClick={() => requestPause(projectId)}
                     className="p-1.5 rounded text-warning hover:bg-warning/10 transition-colors"
                     title="Pause build"
                   >
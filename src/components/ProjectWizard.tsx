@@ -2,7 +2,6 @@ import { useState, useEffect, useRef } from "react";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { documentDir, homeDir } from "@tauri-apps/api/path";
 import { invoke } from "@tauri-apps/api/core";
-import { listen } from "@tauri-apps/api/event";
 import Markdown from "react-markdown";
 import remarkGfm from "remark-gfm";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
@@ -14,7 +13,9 @@ import { useIdeaGeneration } from "../hooks/useIdeaGeneration";
 import { useIdeasStore } from "../stores/ideasStore";
 import { usePromptStore } from "../stores/promptStore";
 import { defaultPlugins } from "../types";
+import { spawnAgent } from "../utils/spawnAgent";
 import { jsPDF } from "jspdf";
+import { listenAgentOutput } from "../utils/agentOutput";
 
 type WizardPhase = 
   | "idea" 
@@ -719,32 +720,22 @@ export function ProjectWizard({ isOpen, onClose }: ProjectWizardProps) {
         args = [...agent.args, designPrompt, "--stream-json"];
       }
 
-      interface SpawnAgentResult {
-        processId: string;
-      }
-
       interface WaitAgentResult {
         processId: string;
         exitCode: number | null;
         success: boolean;
       }
 
-      interface AgentOutputPayload {
-        processId: string;
-        streamType: 'stdout' | 'stderr';
-        content: string;
-      }
-
-      const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+      const spawnResult = await spawnAgent({
         executable: agent.command,
         args,
         workingDirectory: createdProjectPath,
       });
 
       // Listen for output to capture for error reporting
-      unlistenOutput = await listen<AgentOutputPayload>('agent-output', (event) => {
-        if (event.payload.processId === spawnResult.processId) {
-          outputLines.push(event.payload.content);
+      unlistenOutput = await listenAgentOutput((output) => {
+        if (output.processId === spawnResult.processId) {
+          outputLines.push(output.content);
           // Keep only last 50 lines to avoid memory issues
           if (outputLines.length > 50) {
             outputLines.shift();
@@ -852,32 +843,22 @@ IMPORTANT: Only create files in the design/ folder. Do not implement any actual
         args = [...agent.args, designPrompt, "--stream-json"];
       }
 
-      interface SpawnAgentResult {
-        processId: string;
-      }
-
       interface WaitAgentResult {
         processId: string;
         exitCode: number | null;
         success: boolean;
       }
 
-      interface AgentOutputPayload {
-        processId: string;
-        streamType: 'stdout' | 'stderr';
-        content: string;
-      }
-
-      const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+      const spawnResult = await spawnAgent({
         executable: agent.command,
         args,
         workingDirectory: createdProjectPath,
       });
 
       // Listen for output to capture for error reporting
-      unlistenOutput = await listen<AgentOutputPayload>('agent-output', (event) => {
-        if (event.payload.processId === spawnResult.processId) {
-          outputLines.push(event.payload.content);
+      unlistenOutput = await listenAgentOutput((output) => {
+        if (output.processId === spawnResult.processId) {
+          outputLines.push(output.content);
           if (outputLines.length > 50) {
             outputLines.shift();
           }
@@ -0,0 +1,64 @@
+import { useEffect, useState } from "react";
+import { usePromptStore, type LibraryPrompt } from "../stores/promptStore";
+
+interface PromptHistoryProps {
+  promptId: string;
+  /** Puts a version's text in the editor; an empty string means the default */
+  onLoad: (content: string) => void;
+}
+
+const SOURCE_LABELS: Record<string, string> = {
+  library: "Library",
+  settings: "Settings",
+  rollback: "Rollback",
+};
+
+/** Saved versions of a prompt, newest first, any of which can be loaded back into the editor. */
+export function PromptHistory({ promptId, onLoad }: PromptHistoryProps) {
+  const getHistory = usePromptStore((state) => state.getHistory);
+  const [history, setHistory] = useState<LibraryPrompt | null>(null);
+
+  useEffect(() => {
+    setHistory(null);
+    getHistory(promptId)
+      .then(setHistory)
+      .catch((error) => console.error("Failed to load prompt history:", error));
+  }, [promptId, getHistory]);
+
+  if (!history || history.versions.length === 0) {
+    return null;
+  }
+
+  const versions = [...history.versions].reverse();
+
+  return (
+    <div>
+      <label className="block text-xs text-secondary mb-2">History</label>
+      <div className="max-h-40 overflow-y-auto rounded-lg border border-border divide-y divide-border">
+        {versions.map((version, index) => (
+          <div key={version.version} className="flex items-center justify-between gap-3 px-3 py-1.5 text-xs">
+            <div className="min-w-0">
+              <span className="text-foreground">v{version.version}</span>
+              <span className="text-muted ml-2">
+                {new Date(version.createdAt).toLocaleString()} · {SOURCE_LABELS[version.source] ?? version.source}
+              </span>
+              {(version.note || !version.content) && (
+                <span className="text-muted ml-2 truncate">
+                  {version.note ?? "Default"}
+                </span>
+              )}
+            </div>
+            {index > 0 && (
+              <button
+                onClick={() => onLoad(version.content)}
+                className="flex-shrink-0 px-2 py-0.5 rounded text-secondary hover:text-foreground hover:bg-card transition-colors"
+              >
+                Load
+              </button>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
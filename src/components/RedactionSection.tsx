@@ -0,0 +1,107 @@
+interface RedactionSectionProps {
+  redactSecrets: boolean;
+  redactAgentOutput: boolean;
+  patterns: string[];
+  onRedactSecretsChange: (enabled: boolean) => void;
+  onRedactAgentOutputChange: (enabled: boolean) => void;
+  onPatternsChange: (patterns: string[]) => void;
+}
+
+function invalidPatterns(patterns: string[]): string[] {
+  return patterns.filter((pattern) => {
+    try {
+      new RegExp(pattern);
+      return false;
+    } catch {
+      return true;
+    }
+  });
+}
+
+export function RedactionSection({
+  redactSecrets,
+  redactAgentOutput,
+  patterns,
+  onRedactSecretsChange,
+  onRedactAgentOutputChange,
+  onPatternsChange,
+}: RedactionSectionProps) {
+  const invalid = invalidPatterns(patterns);
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Secret Redaction
+      </h3>
+      <div className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium text-foreground">
+              Redact Secrets in Saved Logs
+            </label>
+            <p className="text-xs text-muted mt-0.5">
+              Masks API keys, tokens, and passwords before process logs are written to disk.
+            </p>
+          </div>
+          <button
+            onClick={() => onRedactSecretsChange(!redactSecrets)}
+            className={`relative w-11 h-6 rounded-full transition-colors ${
+              redactSecrets ? "bg-accent" : "bg-muted/30"
+            }`}
+          >
+            <span
+              className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                redactSecrets ? "translate-x-5" : ""
+              }`}
+            />
+          </button>
+        </div>
+
+        {redactSecrets && (
+          <>
+            <div className="flex items-center justify-between">
+              <div>
+                <label className="text-sm font-medium text-foreground">
+                  Redact Live Agent Output
+                </label>
+                <p className="text-xs text-muted mt-0.5">
+                  Also masks secrets in output shown while agents run.
+                </p>
+              </div>
+              <button
+                onClick={() => onRedactAgentOutputChange(!redactAgentOutput)}
+                className={`relative w-11 h-6 rounded-full transition-colors ${
+                  redactAgentOutput ? "bg-accent" : "bg-muted/30"
+                }`}
+              >
+                <span
+                  className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                    redactAgentOutput ? "translate-x-5" : ""
+                  }`}
+                />
+              </button>
+            </div>
+
+            <div>
+              <label className="block text-sm text-foreground mb-2">Custom Patterns</label>
+              <textarea
+                value={patterns.join("\n")}
+                onChange={(e) => onPatternsChange(e.target.value.split("\n"))}
+                rows={3}
+                className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground font-mono text-xs focus:outline-none focus:ring-2 focus:ring-accent resize-none"
+                placeholder={"One regex per line, e.g. internal-[a-f0-9]{32}"}
+              />
+              {invalid.length > 0 ? (
+                <p className="text-xs text-destructive mt-1">Invalid pattern: {invalid[0]}</p>
+              ) : (
+                <p className="text-xs text-muted mt-1">
+                  Matches are replaced with [REDACTED], in addition to known key formats.
+                </p>
+              )}
+            </div>
+          </>
+        )}
+      </div>
+    </section>
+  );
+}
@@ -149,6 +149,7 @@ export function RequirementsView({ project }: RequirementsViewProps) {
           <Suspense fallback={null}>
             <StoryDetailPanel
               story={selectedStory}
+              projectPath={project.path}
               onClose={handleCloseInspector}
               onEdit={handleEditFromInspector}
             />
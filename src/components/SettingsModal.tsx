@@ -1,11 +1,36 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useTheme, type ColorMode, type ThemeId } from "../hooks/useTheme";
+import { useBuildModes } from "../hooks/useBuildModes";
 import { getTheme } from "../themes";
 import { useModalKeyboard } from "../hooks/useModalKeyboard";
 import { DEFAULT_PROMPTS, PROMPT_CATEGORIES, getPromptsByCategory, type PromptCategory } from "../utils/prompts";
 import { useIntegrationsStore, type OutRayConfig } from "../stores/integrationsStore";
 import { StacksSettingsTab } from "./StacksSettingsTab";
+import { UpdatesSection } from "./UpdatesSection";
+import { CrashReportsSection } from "./CrashReportsSection";
+import { DiagnosticsSection } from "./DiagnosticsSection";
+import { AppDataSection } from "./AppDataSection";
+import { PricingSection } from "./PricingSection";
+import { CurrencySection, type CurrencyConfig } from "./CurrencySection";
+import { TelemetrySection } from "./TelemetrySection";
+import { ControlApiSection } from "./ControlApiSection";
+import { LanSyncSection } from "./LanSyncSection";
+import { PluginsSection } from "./PluginsSection";
+import { RedactionSection } from "./RedactionSection";
+import { ApprovedExecutablesSection } from "./ApprovedExecutablesSection";
+import { TerminalAuditSection } from "./TerminalAuditSection";
+import { PromptHistory } from "./PromptHistory";
+import { notify } from "../utils/notify";
+import { reloadDisplayCurrency } from "../hooks/useDisplayCurrency";
+
+type AgentPriority = "normal" | "low" | "lowest";
+
+interface ProgressRule {
+  pattern?: string;
+  json?: Record<string, string>;
+}
 
 interface Preferences {
   defaultAgent: string | null;
@@ -21,15 +46,42 @@ interface Preferences {
   promptOverrides: Record<string, string>;
   outray?: OutRayConfig;
   buildNotifications: boolean;
+  doNotDisturb: boolean;
   maxTokensPerStory: number | null;
   maxCostPerBuild: number | null;
+  budgetWarningThresholds: number[];
+  currency: CurrencyConfig;
   warnOnLargeStory: boolean;
   ideasAgent: string | null;
   prdAgent: string | null;
   specsAgent: string | null;
   designAgent: string | null;
+  storyTimeoutMinutes: number;
+  maxStoryRetries: number;
+  retryBackoffSeconds: number;
+  fallbackAgents: string[];
+  progressRules: Record<string, ProgressRule[]>;
+  agentPriority: AgentPriority;
+  updateChannel: string;
+  telemetryEnabled: boolean;
+  controlApiEnabled: boolean;
+  controlApiPort: number;
+  lanSyncEnabled: boolean;
+  lanSyncPort: number;
+  redactSecrets: boolean;
+  redactAgentOutput: boolean;
+  redactionPatterns: string[];
+  approvedExecutables: string[];
+  terminalAuditEnabled: boolean;
+  storyContextTokens: number;
+  killGracePeriodMs: number;
+  compressLogsAfterDays: number;
+  agentUpdateCheckHours: number;
+  gitPath?: string | null;
 }
 
+const DEFAULT_CURRENCY: CurrencyConfig = { code: "USD", rateSource: "fetch", manualRate: null };
+
 interface AgentModel {
   id: string;
   name: string;
@@ -54,6 +106,22 @@ interface AgentPluginStatus {
   cliPath?: string;
 }
 
+interface AgentUpdate {
+  agentId: string;
+  agentName: string;
+  installedVersion: string;
+  latestVersion: string;
+  releaseNotesUrl: string;
+}
+
+interface GitInfo {
+  available: boolean;
+  path: string | null;
+  version: string | null;
+  source: "preference" | "path" | "system" | "bundled" | null;
+  guidance: string | null;
+}
+
 interface SettingsModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -64,15 +132,40 @@ type AppIconVariant = "transparent" | "light" | "dark";
 
 export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const { themeId, colorMode, resolvedMode, setThemeId, setColorMode, availableThemes } = useTheme();
+  const buildModeOptions = useBuildModes();
   
   const [activeTab, setActiveTab] = useState<SettingsTab>("general");
   const [defaultAgent, setDefaultAgent] = useState<string>("claude-code");
   const [defaultAutonomy, setDefaultAutonomy] = useState<string>("autonomous");
   const [defaultBuildMode, setDefaultBuildMode] = useState<string>("ralph");
   const [logBufferSize, setLogBufferSize] = useState<number>(1000);
+  const [compressLogsAfterDays, setCompressLogsAfterDays] = useState<number>(7);
   const [maxParallelAgents, setMaxParallelAgents] = useState<number>(4);
+  const [gitPath, setGitPath] = useState("");
+  const [gitInfo, setGitInfo] = useState<GitInfo | null>(null);
+  const [isDetectingGit, setIsDetectingGit] = useState(false);
+  const [agentPriority, setAgentPriority] = useState<AgentPriority>("normal");
+  const [storyTimeoutMinutes, setStoryTimeoutMinutes] = useState<number>(30);
+  const [storyContextTokens, setStoryContextTokens] = useState<number>(12000);
+  const [maxStoryRetries, setMaxStoryRetries] = useState<number>(1);
+  const [retryBackoffSeconds, setRetryBackoffSeconds] = useState<number>(30);
+  const [killGracePeriodMs, setKillGracePeriodMs] = useState<number>(5000);
+  const [fallbackAgents, setFallbackAgents] = useState<string>("");
+  const [progressRules, setProgressRules] = useState<string>("");
+  const [updateChannel, setUpdateChannel] = useState<string>("stable");
+  const [telemetryEnabled, setTelemetryEnabled] = useState<boolean>(false);
+  const [controlApiEnabled, setControlApiEnabled] = useState<boolean>(false);
+  const [controlApiPort, setControlApiPort] = useState<number>(7457);
+  const [lanSyncEnabled, setLanSyncEnabled] = useState<boolean>(false);
+  const [lanSyncPort, setLanSyncPort] = useState<number>(7458);
+  const [redactSecrets, setRedactSecrets] = useState<boolean>(true);
+  const [redactAgentOutput, setRedactAgentOutput] = useState<boolean>(false);
+  const [redactionPatterns, setRedactionPatterns] = useState<string[]>([]);
+  const [approvedExecutables, setApprovedExecutables] = useState<string[]>([]);
+  const [terminalAuditEnabled, setTerminalAuditEnabled] = useState<boolean>(false);
   const [appIcon, setAppIcon] = useState<AppIconVariant>("transparent");
   const [buildNotifications, setBuildNotifications] = useState<boolean>(true);
+  const [doNotDisturb, setDoNotDisturb] = useState<boolean>(false);
   const [promptOverrides, setPromptOverrides] = useState<Record<string, string>>({});
   const [editingPromptId, setEditingPromptId] = useState<string | null>(null);
   const [editingPromptValue, setEditingPromptValue] = useState<string>("");
@@ -81,6 +174,8 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const [iconChanged, setIconChanged] = useState(false);
   const [maxTokensPerStory, setMaxTokensPerStory] = useState<number | null>(null);
   const [maxCostPerBuild, setMaxCostPerBuild] = useState<number | null>(null);
+  const [budgetWarningThresholds, setBudgetWarningThresholds] = useState<string>("50, 80");
+  const [currency, setCurrency] = useState<CurrencyConfig>(DEFAULT_CURRENCY);
   const [warnOnLargeStory, setWarnOnLargeStory] = useState<boolean>(true);
   
   // Wizard agent defaults
@@ -92,6 +187,9 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   // Agent detection state
   const [agentStatuses, setAgentStatuses] = useState<AgentPluginStatus[]>([]);
   const [isDetectingAgents, setIsDetectingAgents] = useState(false);
+  const [agentUpdateCheckHours, setAgentUpdateCheckHours] = useState<number>(24);
+  const [agentUpdates, setAgentUpdates] = useState<AgentUpdate[]>([]);
+  const [isCheckingAgentUpdates, setIsCheckingAgentUpdates] = useState(false);
 
   // Integrations store
   const outrayConfig = useIntegrationsStore((state) => state.outray);
@@ -116,9 +214,24 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
       if (agentStatuses.length === 0) {
         detectAgents();
       }
+      invoke<AgentUpdate[]>("get_agent_updates")
+        .then(setAgentUpdates)
+        .catch(() => setAgentUpdates([]));
+      detectGit();
     }
   }, [isOpen, loadIntegrationsConfig]);
 
+  // Updates found by the background check while the modal is open
+  useEffect(() => {
+    if (!isOpen) return;
+    const unlisten = listen<AgentUpdate>("agent-update-available", ({ payload }) => {
+      setAgentUpdates((updates) => [...updates.filter((u) => u.agentId !== payload.agentId), payload]);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isOpen]);
+
   // Check OutRay auth status when integrations tab is active and OutRay is enabled
   useEffect(() => {
     if (isOpen && activeTab === "integrations" && outrayConfig.enabled) {
@@ -178,12 +291,38 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
         setDefaultAutonomy(prefs.defaultAutonomy || "autonomous");
         setDefaultBuildMode(prefs.defaultBuildMode || "ralph");
         setLogBufferSize(prefs.logBufferSize || 1000);
+        setCompressLogsAfterDays(prefs.compressLogsAfterDays ?? 7);
+        setAgentUpdateCheckHours(prefs.agentUpdateCheckHours ?? 24);
         setMaxParallelAgents(prefs.maxParallelAgents || 4);
+        setGitPath(prefs.gitPath ?? "");
+        setAgentPriority(prefs.agentPriority ?? "normal");
+        setStoryTimeoutMinutes(prefs.storyTimeoutMinutes ?? 30);
+        setStoryContextTokens(prefs.storyContextTokens ?? 12000);
+        setMaxStoryRetries(prefs.maxStoryRetries ?? 1);
+        setRetryBackoffSeconds(prefs.retryBackoffSeconds ?? 30);
+        setKillGracePeriodMs(prefs.killGracePeriodMs ?? 5000);
+        setFallbackAgents((prefs.fallbackAgents ?? []).join(", "));
+        const rules = prefs.progressRules ?? {};
+        setProgressRules(Object.keys(rules).length > 0 ? JSON.stringify(rules, null, 2) : "");
+        setUpdateChannel(prefs.updateChannel ?? "stable");
+        setTelemetryEnabled(prefs.telemetryEnabled ?? false);
+        setControlApiEnabled(prefs.controlApiEnabled ?? false);
+        setControlApiPort(prefs.controlApiPort ?? 7457);
+        setLanSyncEnabled(prefs.lanSyncEnabled ?? false);
+        setLanSyncPort(prefs.lanSyncPort ?? 7458);
+        setRedactSecrets(prefs.redactSecrets ?? true);
+        setRedactAgentOutput(prefs.redactAgentOutput ?? false);
+        setRedactionPatterns(prefs.redactionPatterns ?? []);
+        setApprovedExecutables(prefs.approvedExecutables ?? []);
+        setTerminalAuditEnabled(prefs.terminalAuditEnabled ?? false);
         setAppIcon((prefs.appIcon as AppIconVariant) || "transparent");
         setBuildNotifications(prefs.buildNotifications ?? true);
+        setDoNotDisturb(prefs.doNotDisturb ?? false);
         setPromptOverrides(prefs.promptOverrides || {});
         setMaxTokensPerStory(prefs.maxTokensPerStory ?? null);
         setMaxCostPerBuild(prefs.maxCostPerBuild ?? null);
+        setBudgetWarningThresholds((prefs.budgetWarningThresholds ?? [50, 80]).join(", "));
+        setCurrency(prefs.currency ?? DEFAULT_CURRENCY);
         setWarnOnLargeStory(prefs.warnOnLargeStory ?? true);
         setIdeasAgent(prefs.ideasAgent ?? null);
         setPrdAgent(prefs.prdAgent ?? null);
@@ -208,7 +347,38 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     }
   };
 
+  const detectGit = async () => {
+    setIsDetectingGit(true);
+    try {
+      setGitInfo(await invoke<GitInfo>("detect_git"));
+    } catch (error) {
+      console.error("Failed to detect git:", error);
+    } finally {
+      setIsDetectingGit(false);
+    }
+  };
+
+  const checkAgentUpdates = async () => {
+    setIsCheckingAgentUpdates(true);
+    try {
+      setAgentUpdates(await invoke<AgentUpdate[]>("check_agent_updates"));
+    } catch (error) {
+      console.error("Failed to check agent updates:", error);
+    } finally {
+      setIsCheckingAgentUpdates(false);
+    }
+  };
+
   const handleSave = async () => {
+    let parsedProgressRules: Record<string, ProgressRule[]> = {};
+    if (progressRules.trim()) {
+      try {
+        parsedProgressRules = JSON.parse(progressRules);
+      } catch (error) {
+        notify.error("Invalid progress rules", String(error));
+        return;
+      }
+    }
     setIsSaving(true);
     try {
       const prefs: Preferences = {
@@ -216,7 +386,32 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
         defaultAutonomy,
         defaultBuildMode,
         logBufferSize,
+        compressLogsAfterDays,
+        agentUpdateCheckHours,
         maxParallelAgents,
+        gitPath: gitPath.trim() || null,
+        agentPriority,
+        storyTimeoutMinutes,
+        storyContextTokens,
+        maxStoryRetries,
+        retryBackoffSeconds,
+        killGracePeriodMs,
+        fallbackAgents: fallbackAgents
+          .split(",")
+          .map((a) => a.trim())
+          .filter(Boolean),
+        progressRules: parsedProgressRules,
+        updateChannel,
+        telemetryEnabled,
+        controlApiEnabled,
+        controlApiPort,
+        lanSyncEnabled,
+        lanSyncPort,
+        redactSecrets,
+        redactAgentOutput,
+        redactionPatterns: redactionPatterns.map((p) => p.trim()).filter(Boolean),
+        approvedExecutables,
+        terminalAuditEnabled,
         agentPaths: [],
         themeId: themeId,
         colorMode: colorMode,
@@ -225,8 +420,14 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
         promptOverrides,
         outray: outrayConfig,
         buildNotifications,
+        doNotDisturb,
         maxTokensPerStory,
         maxCostPerBuild,
+        budgetWarningThresholds: budgetWarningThresholds
+          .split(",")
+          .map((t) => parseInt(t.trim(), 10))
+          .filter((t) => t > 0 && t < 100),
+        currency: { ...currency, code: currency.code.trim().toUpperCase() || "USD" },
         warnOnLargeStory,
         ideasAgent,
         prdAgent,
@@ -234,10 +435,12 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
         designAgent,
       };
       await invoke("save_preferences", { preferences: prefs });
+      reloadDisplayCurrency();
       setIsDirty(false);
       onClose();
     } catch (error) {
       console.error("Failed to save preferences:", error);
+      notify.error("Failed to save settings", String(error));
     } finally {
       setIsSaving(false);
     }
@@ -665,14 +868,14 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                       }}
                       className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
                     >
-                      <option value="ralph">Ralph</option>
-                      <option value="parallel">Parallel</option>
-                      <option value="none">None</option>
+                      {buildModeOptions.map((option) => (
+                        <option key={option.value} value={option.value}>
+                          {option.label}
+                        </option>
+                      ))}
                     </select>
                     <p className="text-xs text-muted mt-1">
-                      {defaultBuildMode === "ralph" && "Sequential story execution with Ralph agent system."}
-                      {defaultBuildMode === "parallel" && "Run multiple stories concurrently."}
-                      {defaultBuildMode === "none" && "No automatic building - manual story execution only."}
+                      {buildModeOptions.find((o) => o.value === defaultBuildMode)?.description}
                     </p>
                   </div>
 
@@ -691,7 +894,25 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                       className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
                     />
                     <p className="text-xs text-muted mt-1">
-                      Maximum number of log entries to keep in memory.
+                      Maximum number of log entries to keep in memory. Agent output is sent to the UI in batches of up to a tenth of this.
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Compress Logs After (days)</label>
+                    <input
+                      type="number"
+                      value={compressLogsAfterDays}
+                      onChange={(e) => {
+                        const v = parseInt(e.target.value, 10);
+                        setCompressLogsAfterDays(Number.isFinite(v) && v >= 0 ? v : 7);
+                        setIsDirty(true);
+                      }}
+                      min={0}
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      Saved process logs older than this are gzipped when Ideate starts. They stay viewable and searchable. 0 never compresses them.
                     </p>
                   </div>
 
@@ -714,13 +935,191 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     </p>
                   </div>
 
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Git Executable</label>
+                    <div className="flex gap-2">
+                      <input
+                        type="text"
+                        value={gitPath}
+                        onChange={(e) => {
+                          setGitPath(e.target.value);
+                          setIsDirty(true);
+                        }}
+                        placeholder="Found automatically"
+                        className="flex-1 px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent font-mono text-sm"
+                      />
+                      <button
+                        onClick={detectGit}
+                        disabled={isDetectingGit}
+                        className="px-3 py-2 text-xs rounded-lg bg-card hover:bg-accent/10 text-secondary hover:text-accent transition-colors disabled:opacity-50"
+                      >
+                        {isDetectingGit ? "Checking..." : "Check Again"}
+                      </button>
+                    </div>
+                    {gitInfo && (
+                      <p className={`text-xs mt-1 ${gitInfo.available ? "text-muted" : "text-destructive"}`}>
+                        {gitInfo.available
+                          ? `Using ${gitInfo.version ?? "git"} at ${gitInfo.path}`
+                          : gitInfo.guidance}
+                      </p>
+                    )}
+                    <p className="text-xs text-muted mt-1">
+                      Leave empty to use git from your PATH or a standard install location. Without git, builds run one story at a time without snapshots or commits. Save before checking a new path.
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Agent Priority</label>
+                    <select
+                      value={agentPriority}
+                      onChange={(e) => {
+                        setAgentPriority(e.target.value as AgentPriority);
+                        setIsDirty(true);
+                      }}
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                    >
+                      <option value="normal">Normal</option>
+                      <option value="low">Low</option>
+                      <option value="lowest">Lowest</option>
+                    </select>
+                    <p className="text-xs text-muted mt-1">
+                      CPU priority for agent processes (and disk priority on Linux). Lower it to keep the machine responsive during parallel builds. Applies to agents started after saving.
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Story Timeout (minutes)</label>
+                    <input
+                      type="number"
+                      value={storyTimeoutMinutes}
+                      onChange={(e) => {
+                        const v = parseInt(e.target.value, 10);
+                        setStoryTimeoutMinutes(Number.isFinite(v) && v >= 0 ? v : 30);
+                        setIsDirty(true);
+                      }}
+                      min={0}
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      Stop an agent that runs longer than this and roll back its changes. 0 disables the limit.
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Story Context (tokens)</label>
+                    <input
+                      type="number"
+                      value={storyContextTokens}
+                      onChange={(e) => {
+                        const v = parseInt(e.target.value, 10);
+                        setStoryContextTokens(Number.isFinite(v) && v >= 0 ? v : 12000);
+                        setIsDirty(true);
+                      }}
+                      min={0}
+                      step={1000}
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      Budget for project files added to each story prompt: files the story or design mentions first, then recently changed ones. Files that don&apos;t fit are listed in the build log. 0 adds none.
+                    </p>
+                  </div>
+
+                  <div className="grid grid-cols-2 gap-3">
+                    <div>
+                      <label className="block text-sm text-foreground mb-2">Automatic Retries</label>
+                      <input
+                        type="number"
+                        value={maxStoryRetries}
+                        onChange={(e) => {
+                          const v = parseInt(e.target.value, 10);
+                          setMaxStoryRetries(Number.isFinite(v) && v >= 0 ? Math.min(v, 5) : 1);
+                          setIsDirty(true);
+                        }}
+                        min={0}
+                        max={5}
+                        className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                      />
+                    </div>
+                    <div>
+                      <label className="block text-sm text-foreground mb-2">Retry Delay (seconds)</label>
+                      <input
+                        type="number"
+                        value={retryBackoffSeconds}
+                        onChange={(e) => {
+                          const v = parseInt(e.target.value, 10);
+                          setRetryBackoffSeconds(Number.isFinite(v) && v >= 0 ? v : 30);
+                          setIsDirty(true);
+                        }}
+                        min={0}
+                        className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                      />
+                    </div>
+                  </div>
+                  <p className="text-xs text-muted -mt-2">
+                    Failed stories are retried this many times before being marked failed. The delay doubles with each retry.
+                  </p>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Stop Grace Period (ms)</label>
+                    <input
+                      type="number"
+                      value={killGracePeriodMs}
+                      onChange={(e) => {
+                        const v = parseInt(e.target.value, 10);
+                        setKillGracePeriodMs(Number.isFinite(v) && v >= 0 ? v : 5000);
+                        setIsDirty(true);
+                      }}
+                      min={0}
+                      step={500}
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      How long a stopped agent gets to shut down cleanly before it is force-killed. 0 kills immediately.
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Fallback Agents</label>
+                    <input
+                      type="text"
+                      value={fallbackAgents}
+                      onChange={(e) => {
+                        setFallbackAgents(e.target.value);
+                        setIsDirty(true);
+                      }}
+                      placeholder="claude-code, amp, opencode"
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground placeholder:text-muted focus:outline-none focus:ring-2 focus:ring-accent"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      When a story's agent is missing, signed out, or fails all its retries, the build switches to the next agent in this list. Separate agent IDs with commas.
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Progress Rules</label>
+                    <textarea
+                      value={progressRules}
+                      onChange={(e) => {
+                        setProgressRules(e.target.value);
+                        setIsDirty(true);
+                      }}
+                      rows={5}
+                      spellCheck={false}
+                      placeholder={'{\n  "claude-code": [\n    { "pattern": "^Step (?P<current>\\\\d+)/(?P<total>\\\\d+): (?P<step>.+)$" }\n  ]\n}'}
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground font-mono text-xs placeholder:text-muted focus:outline-none focus:ring-2 focus:ring-accent"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      Per-agent rules that turn output lines into a progress bar. A pattern's named groups <code>step</code>, <code>percent</code>, <code>current</code>, <code>total</code>, and <code>file</code> fill the progress; <code>json</code> rules map the same fields to JSON pointers for agents that print JSON.
+                    </p>
+                  </div>
+
                   <div className="flex items-center justify-between">
                     <div>
                       <label className="text-sm font-medium text-foreground">
                         Build Notifications
                       </label>
                       <p className="text-xs text-muted mt-0.5">
-                        Show native notifications for story completion and build status
+                        Show native notifications for build results, failures, budget warnings, and agents waiting for input
                       </p>
                     </div>
                     <button
@@ -739,6 +1138,32 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                       />
                     </button>
                   </div>
+
+                  <div className="flex items-center justify-between">
+                    <div>
+                      <label className="text-sm font-medium text-foreground">
+                        Do Not Disturb
+                      </label>
+                      <p className="text-xs text-muted mt-0.5">
+                        Silence all desktop notifications. Webhooks are still sent.
+                      </p>
+                    </div>
+                    <button
+                      onClick={() => {
+                        setDoNotDisturb(!doNotDisturb);
+                        setIsDirty(true);
+                      }}
+                      className={`relative w-11 h-6 rounded-full transition-colors ${
+                        doNotDisturb ? "bg-accent" : "bg-muted/30"
+                      }`}
+                    >
+                      <span
+                        className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                          doNotDisturb ? "translate-x-5" : ""
+                        }`}
+                      />
+                    </button>
+                  </div>
                 </div>
               </section>
 
@@ -788,6 +1213,23 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     </p>
                   </div>
 
+                  <div>
+                    <label className="block text-sm text-foreground mb-2">Budget Warnings (%)</label>
+                    <input
+                      type="text"
+                      value={budgetWarningThresholds}
+                      onChange={(e) => {
+                        setBudgetWarningThresholds(e.target.value);
+                        setIsDirty(true);
+                      }}
+                      placeholder="50, 80"
+                      className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent placeholder:text-secondary/60"
+                    />
+                    <p className="text-xs text-muted mt-1">
+                      Warn when a build reaches these percentages of the limits above, before the limit itself.
+                    </p>
+                  </div>
+
                   <div className="flex items-center justify-between">
                     <div>
                       <label className="text-sm font-medium text-foreground">
@@ -816,6 +1258,100 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                 </div>
               </section>
 
+              <PricingSection />
+
+              <CurrencySection
+                config={currency}
+                onChange={(config) => {
+                  setCurrency(config);
+                  setIsDirty(true);
+                }}
+              />
+
+              <UpdatesSection
+                channel={updateChannel}
+                onChannelChange={(channel) => {
+                  setUpdateChannel(channel);
+                  setIsDirty(true);
+                }}
+              />
+
+              <TelemetrySection
+                enabled={telemetryEnabled}
+                onEnabledChange={(enabled) => {
+                  setTelemetryEnabled(enabled);
+                  setIsDirty(true);
+                }}
+              />
+
+              <RedactionSection
+                redactSecrets={redactSecrets}
+                redactAgentOutput={redactAgentOutput}
+                patterns={redactionPatterns}
+                onRedactSecretsChange={(enabled) => {
+                  setRedactSecrets(enabled);
+                  setIsDirty(true);
+                }}
+                onRedactAgentOutputChange={(enabled) => {
+                  setRedactAgentOutput(enabled);
+                  setIsDirty(true);
+                }}
+                onPatternsChange={(patterns) => {
+                  setRedactionPatterns(patterns);
+                  setIsDirty(true);
+                }}
+              />
+
+              <ApprovedExecutablesSection
+                executables={approvedExecutables}
+                onExecutablesChange={(executables) => {
+                  setApprovedExecutables(executables);
+                  setIsDirty(true);
+                }}
+              />
+
+              <TerminalAuditSection
+                enabled={terminalAuditEnabled}
+                onEnabledChange={(enabled) => {
+                  setTerminalAuditEnabled(enabled);
+                  setIsDirty(true);
+                }}
+              />
+
+              <ControlApiSection
+                enabled={controlApiEnabled}
+                port={controlApiPort}
+                onEnabledChange={(enabled) => {
+                  setControlApiEnabled(enabled);
+                  setIsDirty(true);
+                }}
+                onPortChange={(port) => {
+                  setControlApiPort(port);
+                  setIsDirty(true);
+                }}
+              />
+
+              <LanSyncSection
+                enabled={lanSyncEnabled}
+                port={lanSyncPort}
+                onEnabledChange={(enabled) => {
+                  setLanSyncEnabled(enabled);
+                  setIsDirty(true);
+                }}
+                onPortChange={(port) => {
+                  setLanSyncPort(port);
+                  setIsDirty(true);
+                }}
+              />
+
+              <PluginsSection />
+
+              <DiagnosticsSection />
+
+              <CrashReportsSection />
+
+              <AppDataSection />
+
               {/* Wizard Defaults Section */}
               <section>
                 <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
@@ -927,13 +1463,22 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     Detected CLI agents on your system. Install agents to use them in builds.
                   </p>
                 </div>
-                <button
-                  onClick={detectAgents}
-                  disabled={isDetectingAgents}
-                  className="px-3 py-1.5 text-xs rounded-md border border-border hover:bg-card transition-colors disabled:opacity-50"
-                >
-                  {isDetectingAgents ? "Detecting..." : "Refresh"}
-                </button>
+                <div className="flex items-center gap-2">
+                  <button
+                    onClick={checkAgentUpdates}
+                    disabled={isCheckingAgentUpdates}
+                    className="px-3 py-1.5 text-xs rounded-md border border-border hover:bg-card transition-colors disabled:opacity-50"
+                  >
+                    {isCheckingAgentUpdates ? "Checking..." : "Check for Updates"}
+                  </button>
+                  <button
+                    onClick={detectAgents}
+                    disabled={isDetectingAgents}
+                    className="px-3 py-1.5 text-xs rounded-md border border-border hover:bg-card transition-colors disabled:opacity-50"
+                  >
+                    {isDetectingAgents ? "Detecting..." : "Refresh"}
+                  </button>
+                </div>
               </div>
 
               {isDetectingAgents && agentStatuses.length === 0 ? (
@@ -989,6 +1534,21 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                               {agent.cliPath && (
                                 <div className="truncate">Path: <span className="text-foreground font-mono">{agent.cliPath}</span></div>
                               )}
+                              {agentUpdates
+                                .filter((update) => update.agentId === agent.id)
+                                .map((update) => (
+                                  <div key={update.latestVersion} className="text-accent">
+                                    Update available: {update.latestVersion}{" "}
+                                    <a
+                                      href={update.releaseNotesUrl}
+                                      target="_blank"
+                                      rel="noopener noreferrer"
+                                      className="underline hover:text-foreground"
+                                    >
+                                      Release notes
+                                    </a>
+                                  </div>
+                                ))}
                             </div>
                           )}
                         </div>
@@ -1028,6 +1588,24 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                 </div>
               )}
 
+              <div className="mt-6">
+                <label className="block text-sm text-foreground mb-2">Check for Agent Updates Every (hours)</label>
+                <input
+                  type="number"
+                  value={agentUpdateCheckHours}
+                  onChange={(e) => {
+                    const v = parseInt(e.target.value, 10);
+                    setAgentUpdateCheckHours(Number.isFinite(v) && v >= 0 ? v : 24);
+                    setIsDirty(true);
+                  }}
+                  min={0}
+                  className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+                />
+                <p className="text-xs text-muted mt-1">
+                  Compares installed agent CLIs with their latest published versions in the background. 0 never checks.
+                </p>
+              </div>
+
               <div className="mt-6 p-4 rounded-lg bg-card/50 border border-border">
                 <h4 className="text-sm font-medium text-foreground mb-2">About Agent CLIs</h4>
                 <p className="text-xs text-muted leading-relaxed">
@@ -1062,6 +1640,10 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                   <div>
                     <label className="block text-xs text-secondary mb-2">
                       Available variables: {DEFAULT_PROMPTS[editingPromptId]?.variables.join(", ") || "None"}
+                      <span className="block text-muted mt-1">
+                        Prompts are Jinja templates: use {"{% if %}"} and {"{% for %}"}, and {'{% include "story" %}'},
+                        {' "criteria"'}, {'"project"'}, {'"stack"'}, or {'"design"'} for shared sections.
+                      </span>
                     </label>
                     <textarea
                       value={editingPromptValue}
@@ -1071,6 +1653,13 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     />
                   </div>
 
+                  <PromptHistory
+                    promptId={editingPromptId}
+                    onLoad={(content) =>
+                      setEditingPromptValue(content || DEFAULT_PROMPTS[editingPromptId]?.defaultPrompt || "")
+                    }
+                  />
+
                   <div className="flex justify-between">
                     <button
                       onClick={() => handleResetPrompt(editingPromptId)}
@@ -83,8 +83,8 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
   const removeProject = useProjectStore((state) => state.removeProject)
   
   const getProjectState = useBuildStore((state) => state.getProjectState)
-  const pauseBuild = useBuildStore((state) => state.pauseBuild)
-  const resumeBuild = useBuildStore((state) => state.resumeBuild)
+  const requestPause = useBuildStore((state) => state.requestPause)
+  const requestResume = useBuildStore((state) => state.requestResume)
   const resetBuildState = useBuildStore((state) => state.resetBuildState)
   const projectStates = useBuildStore((state) => state.projectStates)
 
@@ -105,6 +105,8 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
   const selectIdea = useIdeasStore((state) => state.selectIdea)
   const addIdea = useIdeasStore((state) => state.addIdea)
   const reorderIdeas = useIdeasStore((state) => state.reorderIdeas)
+  const ideaJobs = useIdeasStore((state) => state.jobs)
+  const queueExpansions = useIdeasStore((state) => state.queueExpansions)
 
   const reorderProjects = useProjectStore((state) => state.reorderProjects)
 
@@ -431,9 +433,9 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
         window.dispatchEvent(new CustomEvent('sidebar-start-build', { detail: { projectId } }))
       }
     } else if (buildStatus === 'running') {
-      pauseBuild(projectId)
+      requestPause(projectId)
     } else if (buildStatus === 'paused') {
-      resumeBuild(projectId)
+      requestResume(projectId)
     }
   }
 
@@ -954,6 +956,22 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
             <h2 className="text-xs font-medium text-muted uppercase tracking-wider">
               Ideas
             </h2>
+            <div className="flex items-center gap-0.5">
+            {ideas.some((idea) => !idea.draftStories?.length) && (
+              <button
+                onClick={() => {
+                  const ids = ideas.filter((idea) => !idea.draftStories?.length).map((idea) => idea.id)
+                  queueExpansions(ids).catch((error) => console.error('Failed to queue idea expansions:', error))
+                }}
+                className="no-drag w-6 h-6 flex items-center justify-center rounded-md hover:bg-card transition-colors"
+                aria-label="Expand Ideas"
+                title="Expand ideas without draft stories in the background"
+              >
+                <svg className="w-4 h-4 text-secondary" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M9.813 15.904L9 18.75l-.813-2.846a4.5 4.5 0 00-3.09-3.09L2.25 12l2.846-.813a4.5 4.5 0 003.09-3.09L9 5.25l.813 2.846a4.5 4.5 0 003.09 3.09L15.75 12l-2.846.813a4.5 4.5 0 00-3.09 3.09z" />
+                </svg>
+              </button>
+            )}
             <button
               onClick={() => setIsCreateIdeaOpen(true)}
               className="no-drag w-6 h-6 flex items-center justify-center rounded-md hover:bg-card transition-colors"
@@ -974,6 +992,7 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
                 />
               </svg>
             </button>
+            </div>
           </div>
           
           <div className="flex-1 overflow-y-auto px-2 pb-2 scrollbar-auto-hide">
@@ -987,6 +1006,9 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
                   const isSelected = idea.id === selectedIdeaId
                   const isDragging = idea.id === draggedIdeaId
                   const isDragOver = idea.id === dragOverIdeaId
+                  const isExpanding = ideaJobs.some(
+                    (job) => job.ideaId === idea.id && (job.status === 'queued' || job.status === 'running')
+                  )
                   return (
                     <li
                       key={idea.id}
@@ -1024,6 +1046,9 @@ export function Sidebar({ onNewProject, onQuickAddProject, onImportProject }: Si
                           />
                         </svg>
                         <span className="text-sm truncate flex-1 min-w-0">{idea.title}</span>
+                        {isExpanding && (
+                          <span className="w-1.5 h-1.5 rounded-full bg-accent animate-pulse flex-shrink-0" title="Expanding in the background" />
+                        )}
                       </button>
                     </li>
                   )
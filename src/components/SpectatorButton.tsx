@@ -0,0 +1,133 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { notify } from "../utils/notify";
+
+interface SpectatorInfo {
+  projectId: string;
+  port: number;
+  token: string;
+  localUrl: string;
+  networkUrl: string | null;
+  publicUrl: string | null;
+  viewers: number;
+  startedAt: string;
+}
+
+interface SpectatorButtonProps {
+  projectId: string;
+}
+
+export function SpectatorButton({ projectId }: SpectatorButtonProps) {
+  const [info, setInfo] = useState<SpectatorInfo | null>(null);
+  const [isOpen, setIsOpen] = useState(false);
+  const [isBusy, setIsBusy] = useState(false);
+
+  useEffect(() => {
+    setInfo(null);
+    const refresh = () =>
+      invoke<SpectatorInfo | null>("get_spectator_server", { projectId })
+        .then(setInfo)
+        .catch(() => setInfo(null));
+    refresh();
+    // Keeps the viewer count current while a view is shared
+    const interval = setInterval(refresh, 5000);
+    return () => clearInterval(interval);
+  }, [projectId]);
+
+  const start = async (makePublic: boolean) => {
+    setIsBusy(true);
+    try {
+      setInfo(await invoke<SpectatorInfo>("start_spectator_server", { projectId, public: makePublic }));
+      setIsOpen(true);
+    } catch (error) {
+      notify.error("Could not share live view", String(error));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const stop = async () => {
+    setIsBusy(true);
+    try {
+      await invoke("stop_spectator_server", { projectId });
+      setInfo(null);
+      setIsOpen(false);
+    } catch (error) {
+      notify.error("Could not stop live view", String(error));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const copy = async (url: string) => {
+    await navigator.clipboard.writeText(url);
+    notify.success("Link copied");
+  };
+
+  const links = info
+    ? [
+        { label: "This computer", url: info.localUrl },
+        ...(info.networkUrl ? [{ label: "Local network", url: info.networkUrl }] : []),
+        ...(info.publicUrl ? [{ label: "Public", url: info.publicUrl }] : []),
+      ]
+    : [];
+
+  return (
+    <div className="relative">
+      <button
+        onClick={() => (info ? setIsOpen(!isOpen) : start(false))}
+        disabled={isBusy}
+        className={`text-[10px] px-1.5 py-0.5 rounded transition-colors ${
+          info ? "bg-accent/20 text-accent" : "bg-muted/10 text-muted hover:bg-muted/20"
+        }`}
+        title={info ? "Live view is shared" : "Share a read-only live view of this build"}
+      >
+        <span className="flex items-center gap-1">
+          <svg className="w-3 h-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M2.036 12.322a1.012 1.012 0 010-.639C3.423 7.51 7.36 4.5 12 4.5c4.638 0 8.573 3.007 9.963 7.178.07.207.07.431 0 .639C20.577 16.49 16.64 19.5 12 19.5c-4.638 0-8.573-3.007-9.963-7.178z" />
+            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M15 12a3 3 0 11-6 0 3 3 0 016 0z" />
+          </svg>
+          {info ? `Live · ${info.viewers}` : "Share"}
+        </span>
+      </button>
+
+      {isOpen && info && (
+        <div className="absolute right-0 top-full mt-1 z-20 w-80 p-3 rounded-lg border border-border bg-card shadow-lg space-y-2">
+          <div className="flex items-center justify-between">
+            <span className="text-xs font-medium text-foreground">Live View</span>
+            <span className="text-[10px] text-muted">
+              {info.viewers} viewer{info.viewers === 1 ? "" : "s"}
+            </span>
+          </div>
+          <p className="text-[10px] text-muted">
+            Anyone with a link can watch agent output and story progress but cannot change anything. Stopping
+            invalidates the links.
+          </p>
+          {links.map((link) => (
+            <div key={link.label}>
+              <div className="text-[10px] text-muted mb-0.5">{link.label}</div>
+              <div className="flex items-center gap-1">
+                <code className="flex-1 px-2 py-1 rounded border border-border bg-background text-[10px] text-foreground truncate">
+                  {link.url}
+                </code>
+                <button onClick={() => copy(link.url)} className="btn btn-sm btn-secondary">
+                  Copy
+                </button>
+              </div>
+            </div>
+          ))}
+          <div className="flex items-center justify-end gap-2 pt-1">
+            {!info.publicUrl && (
+              <button onClick={() => start(true)} disabled={isBusy} className="btn btn-sm btn-secondary">
+                Make Public
+              </button>
+            )}
+            <button onClick={stop} disabled={isBusy} className="btn btn-sm btn-secondary">
+              Stop Sharing
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,176 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { documentDir } from "@tauri-apps/api/path";
+import { save } from "@tauri-apps/plugin-dialog";
+import { open } from "@tauri-apps/plugin-shell";
+import { formatCost, type DisplayCurrency } from "../hooks/useDisplayCurrency";
+import { notify } from "../utils/notify";
+
+interface ModelSpending {
+  agentId: string;
+  model: string;
+  runs: number;
+  inputTokens: number;
+  outputTokens: number;
+  totalTokens: number;
+  cost: number;
+}
+
+interface ProjectSpending {
+  projectId: string;
+  projectName: string;
+  runs: number;
+  totalTokens: number;
+  cost: number;
+  models: ModelSpending[];
+}
+
+interface SpendingReport {
+  month: string;
+  generatedAt: string;
+  displayCurrency: DisplayCurrency;
+  runs: number;
+  totalTokens: number;
+  totalCost: number;
+  projects: ProjectSpending[];
+  models: ModelSpending[];
+  skippedProjects: string[];
+}
+
+interface GeneratedSpendingReport {
+  report: SpendingReport;
+  markdown: string;
+  csv: string;
+  html: string;
+  directory: string;
+}
+
+type ReportFormat = "markdown" | "csv" | "html";
+
+const FORMATS: Record<ReportFormat, { label: string; extension: string }> = {
+  markdown: { label: "Markdown", extension: "md" },
+  csv: { label: "CSV", extension: "csv" },
+  html: { label: "HTML", extension: "html" },
+};
+
+function currentMonth(): string {
+  return new Date().toISOString().slice(0, 7);
+}
+
+export function SpendingReportPanel() {
+  const [month, setMonth] = useState(currentMonth);
+  const [generated, setGenerated] = useState<GeneratedSpendingReport | null>(null);
+  const [isGenerating, setIsGenerating] = useState(false);
+
+  const handleGenerate = async () => {
+    setIsGenerating(true);
+    try {
+      setGenerated(await invoke<GeneratedSpendingReport>("generate_spending_report", { month }));
+    } catch (error) {
+      notify.error("Failed to generate spending report", String(error));
+    } finally {
+      setIsGenerating(false);
+    }
+  };
+
+  const handleExport = async (format: ReportFormat) => {
+    if (!generated) return;
+    const { label, extension } = FORMATS[format];
+    try {
+      const path = await save({
+        defaultPath: `${await documentDir()}/spending-${generated.report.month}.${extension}`,
+        filters: [{ name: label, extensions: [extension] }],
+        title: "Export Spending Report",
+      });
+      if (!path) return;
+      const data = Array.from(new TextEncoder().encode(generated[format]));
+      await invoke("write_binary_file", { path, data });
+      notify.success("Spending report exported", path);
+    } catch (error) {
+      notify.error("Failed to export spending report", String(error));
+    }
+  };
+
+  const report = generated?.report;
+
+  return (
+    <div className="flex-1 overflow-y-auto p-6 space-y-4">
+      <div className="flex items-center gap-2">
+        <input
+          type="month"
+          value={month}
+          max={currentMonth()}
+          onChange={(e) => setMonth(e.target.value)}
+          className="px-3 py-1.5 rounded-lg border border-border bg-background text-foreground text-sm focus:outline-none focus:ring-2 focus:ring-accent"
+        />
+        <button onClick={handleGenerate} disabled={isGenerating || !month} className="btn btn-sm btn-secondary">
+          {isGenerating ? "Generating…" : "Generate Report"}
+        </button>
+      </div>
+
+      {generated && report && (
+        <>
+          <div className="grid grid-cols-3 gap-4">
+            <div>
+              <label className="text-xs font-medium text-muted uppercase tracking-wider">Total Cost</label>
+              <p className="mt-1 text-xl font-bold text-accent">{formatCost(report.totalCost, report.displayCurrency)}</p>
+            </div>
+            <div>
+              <label className="text-xs font-medium text-muted uppercase tracking-wider">Tokens</label>
+              <p className="mt-1 text-xl font-bold text-foreground">{report.totalTokens.toLocaleString()}</p>
+            </div>
+            <div>
+              <label className="text-xs font-medium text-muted uppercase tracking-wider">Runs</label>
+              <p className="mt-1 text-xl font-bold text-foreground">{report.runs.toLocaleString()}</p>
+            </div>
+          </div>
+
+          {report.projects.length === 0 ? (
+            <p className="text-sm text-muted">No runs were recorded this month.</p>
+          ) : (
+            report.projects.map((project) => (
+              <div key={project.projectId} className="border border-border rounded-lg overflow-hidden">
+                <div className="flex items-center justify-between px-3 py-2 bg-background-secondary text-sm">
+                  <span className="font-medium text-foreground">{project.projectName}</span>
+                  <span className="text-accent">{formatCost(project.cost, report.displayCurrency)}</span>
+                </div>
+                <table className="w-full text-xs">
+                  <tbody>
+                    {project.models.map((m) => (
+                      <tr key={`${m.agentId}/${m.model}`} className="border-t border-border">
+                        <td className="px-3 py-1 text-muted">{m.agentId}</td>
+                        <td className="px-3 py-1 text-foreground font-mono">{m.model}</td>
+                        <td className="px-3 py-1 text-right text-muted">{m.runs} runs</td>
+                        <td className="px-3 py-1 text-right text-muted">{m.totalTokens.toLocaleString()} tokens</td>
+                        <td className="px-3 py-1 text-right text-foreground">{formatCost(m.cost, report.displayCurrency)}</td>
+                      </tr>
+                    ))}
+                  </tbody>
+                </table>
+              </div>
+            ))
+          )}
+
+          {report.skippedProjects.length > 0 && (
+            <p className="text-xs text-muted">Not included (unreadable cost history): {report.skippedProjects.join(", ")}</p>
+          )}
+
+          <div className="flex flex-wrap gap-2">
+            {(Object.keys(FORMATS) as ReportFormat[]).map((format) => (
+              <button key={format} onClick={() => handleExport(format)} className="btn btn-sm btn-secondary">
+                Export {FORMATS[format].label}
+              </button>
+            ))}
+            <button
+              onClick={() => open(`${generated.directory}/spending-${report.month}.html`)}
+              className="btn btn-sm btn-secondary"
+              title="Open in your browser to print or save as PDF"
+            >
+              Open for Printing
+            </button>
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
@@ -47,6 +47,7 @@ export function StacksSettingsTab({ onStacksChange }: StacksSettingsTabProps) {
       category: "Web Application",
       tools: [],
       tags: [],
+      promptSnippets: [],
       icon: "📦",
     });
     setSelectedStackId(null);
@@ -78,6 +79,7 @@ export function StacksSettingsTab({ onStacksChange }: StacksSettingsTabProps) {
         category: editingStack.category || "Other",
         tools: editingStack.tools || [],
         tags: editingStack.tags || [],
+        promptSnippets: editingStack.promptSnippets || [],
         icon: editingStack.icon,
         author: "You",
       });
@@ -89,6 +91,7 @@ export function StacksSettingsTab({ onStacksChange }: StacksSettingsTabProps) {
         category: editingStack.category,
         tools: editingStack.tools,
         tags: editingStack.tags,
+        promptSnippets: editingStack.promptSnippets,
         icon: editingStack.icon,
       });
     }
@@ -134,6 +137,27 @@ export function StacksSettingsTab({ onStacksChange }: StacksSettingsTabProps) {
     setEditingStack({ ...editingStack, tools: newTools });
   };
 
+  const handleAddSnippet = () => {
+    if (!editingStack) return;
+    setEditingStack({
+      ...editingStack,
+      promptSnippets: [...(editingStack.promptSnippets || []), ""],
+    });
+  };
+
+  const handleUpdateSnippet = (index: number, value: string) => {
+    if (!editingStack?.promptSnippets) return;
+    const newSnippets = [...editingStack.promptSnippets];
+    newSnippets[index] = value;
+    setEditingStack({ ...editingStack, promptSnippets: newSnippets });
+  };
+
+  const handleRemoveSnippet = (index: number) => {
+    if (!editingStack?.promptSnippets) return;
+    const newSnippets = editingStack.promptSnippets.filter((_, i) => i !== index);
+    setEditingStack({ ...editingStack, promptSnippets: newSnippets });
+  };
+
   const isEditing = isCreating || Boolean(editingStack && selectedStackId);
 
   return (
@@ -307,6 +331,42 @@ export function StacksSettingsTab({ onStacksChange }: StacksSettingsTabProps) {
                 </div>
               </div>
 
+              <div>
+                <div className="flex items-center justify-between mb-2">
+                  <label className="text-xs text-secondary">Prompt Snippets</label>
+                  <button
+                    onClick={handleAddSnippet}
+                    className="text-xs text-accent hover:underline"
+                  >
+                    + Add Snippet
+                  </button>
+                </div>
+                <p className="text-xs text-muted mb-2">
+                  Added to build prompts of projects using this stack: coding conventions, preferred libraries, lint rules.
+                </p>
+                <div className="space-y-2">
+                  {(editingStack.promptSnippets || []).map((snippet, index) => (
+                    <div key={index} className="flex items-start gap-2">
+                      <textarea
+                        value={snippet}
+                        onChange={(e) => handleUpdateSnippet(index, e.target.value)}
+                        placeholder="e.g. Use named exports and keep components under 200 lines."
+                        rows={2}
+                        className="flex-1 px-2 py-1 text-sm bg-card border border-border rounded resize-y focus:outline-none focus:ring-1 focus:ring-accent"
+                      />
+                      <button
+                        onClick={() => handleRemoveSnippet(index)}
+                        className="p-1 text-muted hover:text-destructive"
+                      >
+                        <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                          <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" />
+                        </svg>
+                      </button>
+                    </div>
+                  ))}
+                </div>
+              </div>
+
               <div className="flex justify-end gap-2 pt-2 border-t border-border">
                 <button
                   onClick={handleCancelEdit}
@@ -406,6 +466,21 @@ export function StacksSettingsTab({ onStacksChange }: StacksSettingsTabProps) {
                 </div>
               </div>
 
+              {(selectedStack.promptSnippets ?? []).length > 0 && (
+                <div>
+                  <h5 className="text-xs font-medium text-secondary uppercase tracking-wider mb-2">
+                    Prompt Snippets ({selectedStack.promptSnippets.length})
+                  </h5>
+                  <div className="space-y-2">
+                    {selectedStack.promptSnippets.map((snippet, index) => (
+                      <p key={index} className="p-2 bg-card rounded-lg border border-border text-xs text-secondary whitespace-pre-wrap">
+                        {snippet}
+                      </p>
+                    ))}
+                  </div>
+                </div>
+              )}
+
               {selectedStack.author && (
                 <p className="text-xs text-muted pt-2 border-t border-border">
                   By {selectedStack.author}
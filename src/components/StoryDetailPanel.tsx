@@ -1,12 +1,41 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import type { Story } from "../stores/prdStore";
 
 interface StoryDetailPanelProps {
   story: Story;
+  projectPath: string;
   onClose: () => void;
   onEdit: (story: Story) => void;
 }
 
-export function StoryDetailPanel({ story, onClose, onEdit }: StoryDetailPanelProps) {
+interface StoryPrompt {
+  prompt: string;
+  estimatedTokens: number;
+}
+
+export function StoryDetailPanel({ story, projectPath, onClose, onEdit }: StoryDetailPanelProps) {
+  const [prompt, setPrompt] = useState<StoryPrompt | null>(null);
+  const [promptError, setPromptError] = useState<string | null>(null);
+  const [isLoadingPrompt, setIsLoadingPrompt] = useState(false);
+
+  useEffect(() => {
+    setPrompt(null);
+    setPromptError(null);
+  }, [story.id, projectPath]);
+
+  const handlePreviewPrompt = async () => {
+    setIsLoadingPrompt(true);
+    setPromptError(null);
+    try {
+      setPrompt(await invoke<StoryPrompt>("build_story_prompt", { projectPath, storyId: story.id }));
+    } catch (error) {
+      setPromptError(String(error));
+    } finally {
+      setIsLoadingPrompt(false);
+    }
+  };
+
   const getStatusBadge = () => {
     if (story.passes) {
       return (
@@ -135,6 +164,30 @@ export function StoryDetailPanel({ story, onClose, onEdit }: StoryDetailPanelPro
         <div className="text-xs text-secondary">
           Priority: {story.priority}
         </div>
+
+        <div>
+          <div className="flex items-center justify-between mb-2">
+            <h4 className="text-xs font-medium text-secondary uppercase tracking-wide">
+              Agent Prompt
+            </h4>
+            <button
+              onClick={prompt ? () => setPrompt(null) : handlePreviewPrompt}
+              disabled={isLoadingPrompt}
+              className="text-xs text-accent hover:underline disabled:opacity-50"
+            >
+              {isLoadingPrompt ? "Assembling..." : prompt ? "Hide" : "Preview"}
+            </button>
+          </div>
+          {prompt && (
+            <>
+              <p className="text-xs text-muted mb-2">~{prompt.estimatedTokens.toLocaleString()} tokens</p>
+              <pre className="max-h-96 overflow-auto p-2 rounded border border-border bg-background text-xs text-foreground whitespace-pre-wrap">
+                {prompt.prompt}
+              </pre>
+            </>
+          )}
+          {promptError && <p className="text-xs text-destructive">{promptError}</p>}
+        </div>
       </div>
     </aside>
   );
@@ -1,5 +1,5 @@
 import { useState } from "react";
-import { save } from "@tauri-apps/plugin-dialog";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
 import { usePrdStore } from "../stores/prdStore";
 import { useBuildStore } from "../stores/buildStore";
@@ -33,12 +33,13 @@ export function StoryList({ projectId, projectPath }: StoryListProps) {
   const updateStory = usePrdStore((state) => state.updateStory);
   const addStory = usePrdStore((state) => state.addStory);
   const removeStory = usePrdStore((state) => state.removeStory);
+  const clearPrd = usePrdStore((state) => state.clearPrd);
   const savePrd = usePrdStore((state) => state.savePrd);
   const selectStory = usePrdStore((state) => state.selectStory);
   const reorderStories = usePrdStore((state) => state.reorderStories);
   
   const retryStory = useBuildStore((state) => state.retryStory);
-  const pauseBuild = useBuildStore((state) => state.pauseBuild);
+  const requestPause = useBuildStore((state) => state.requestPause);
   const projectState = useBuildStore((state) => state.projectStates[projectId]);
   const storyRetries = projectState?.storyRetries ?? {};
   
@@ -58,6 +59,7 @@ export function StoryList({ projectId, projectPath }: StoryListProps) {
   const [draggedStoryId, setDraggedStoryId] = useState<string | null>(null);
   const [dragOverStoryId, setDragOverStoryId] = useState<string | null>(null);
   const [isExportingPdf, setIsExportingPdf] = useState(false);
+  const [isImporting, setIsImporting] = useState(false);
   const [generationResult, setGenerationResult] = useState<{ success: boolean; storiesAdded: number } | null>(null);
 
   const handleStoryClick = (storyId: string) => {
@@ -119,7 +121,7 @@ export function StoryList({ projectId, projectPath }: StoryListProps) {
   };
 
   const handlePauseBuild = () => {
-    pauseBuild(projectId);
+    requestPause(projectId);
   };
 
   const handleViewPreviousLogs = (storyId: string) => {
@@ -259,6 +261,38 @@ export function StoryList({ projectId, projectPath }: StoryListProps) {
     );
   };
 
+  const handleImportBacklog = async () => {
+    const path = await open({
+      multiple: false,
+      directory: false,
+      filters: [{ name: "Backlog", extensions: ["md", "json", "csv"] }],
+      title: "Import Backlog",
+    });
+    if (typeof path !== "string") return;
+
+    setIsImporting(true);
+    try {
+      const result = await invoke<{ format: string; imported: string[]; skipped: number }>("import_backlog", {
+        projectPath,
+        path,
+      });
+      if (result.imported.length === 0) {
+        notify.warning("Nothing imported", result.skipped > 0 ? "Every task is already a story." : "No tasks were found in the file.");
+        return;
+      }
+      // Dropping the in-memory copy makes it load again from disk
+      clearPrd(projectId);
+      notify.success(
+        `Imported ${result.imported.length} ${result.imported.length === 1 ? "story" : "stories"}`,
+        result.skipped > 0 ? `${result.skipped} already existing or canceled tasks were skipped.` : undefined
+      );
+    } catch (error) {
+      notify.error("Failed to import backlog", String(error));
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
   const handleExportPdf = async () => {
     if (!project || isExportingPdf) return;
     
@@ -377,6 +411,28 @@ export function StoryList({ projectId, projectPath }: StoryListProps) {
             </svg>
           </button>
 
+          {/* Import backlog button */}
+          <button
+            onClick={handleImportBacklog}
+            disabled={isImporting || isGenerating}
+            className="p-1.5 rounded-lg border border-border text-muted hover:text-foreground hover:bg-card transition-all disabled:opacity-50 disabled:cursor-not-allowed"
+            title="Import stories from tasks.md, PLAN.md, Taskmaster, or a GitHub Projects export"
+          >
+            <svg
+              className={`w-4 h-4 ${isImporting ? 'animate-pulse' : ''}`}
+              fill="none"
+              stroke="currentColor"
+              viewBox="0 0 24 24"
+            >
+              <path
+                strokeLinecap="round"
+                strokeLinejoin="round"
+                strokeWidth={1.5}
+                d="M4 16v1a3 3 0 003 3h10a3 3 0 003-3v-1m-4-4l-4 4m0 0l-4-4m4 4V4"
+              />
+            </svg>
+          </button>
+
           {/* Export PDF button */}
           <button
             onClick={handleExportPdf}
@@ -0,0 +1,103 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface PendingTelemetry {
+  enabled: boolean;
+  endpointConfigured: boolean;
+  lastUploadAt: string | null;
+  batch: {
+    installId: string;
+    appVersion: string;
+    os: string;
+    arch: string;
+    periodStart: string | null;
+    counters: Record<string, number>;
+  };
+}
+
+interface TelemetrySectionProps {
+  enabled: boolean;
+  onEnabledChange: (enabled: boolean) => void;
+}
+
+export function TelemetrySection({ enabled, onEnabledChange }: TelemetrySectionProps) {
+  const [pending, setPending] = useState<PendingTelemetry | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleView = async () => {
+    setError(null);
+    try {
+      setPending(await invoke<PendingTelemetry>("get_pending_telemetry"));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleClear = async () => {
+    setError(null);
+    try {
+      await invoke("clear_pending_telemetry");
+      await handleView();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Privacy
+      </h3>
+      <div className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium text-foreground">
+              Share Anonymous Usage Data
+            </label>
+            <p className="text-xs text-muted mt-0.5">
+              Counts of builds, agents, and features used. Never project names, paths, or content.
+            </p>
+          </div>
+          <button
+            onClick={() => onEnabledChange(!enabled)}
+            className={`relative w-11 h-6 rounded-full transition-colors ${
+              enabled ? "bg-accent" : "bg-muted/30"
+            }`}
+          >
+            <span
+              className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                enabled ? "translate-x-5" : ""
+              }`}
+            />
+          </button>
+        </div>
+
+        <div className="flex gap-2">
+          <button onClick={handleView} className="btn btn-sm btn-secondary">
+            View Pending Data
+          </button>
+          {pending && Object.keys(pending.batch.counters).length > 0 && (
+            <button onClick={handleClear} className="btn btn-sm btn-secondary">
+              Clear
+            </button>
+          )}
+        </div>
+
+        {pending && (
+          <div>
+            <pre className="text-xs text-muted whitespace-pre-wrap max-h-48 overflow-y-auto p-3 rounded-lg border border-border">
+              {JSON.stringify(pending.batch, null, 2)}
+            </pre>
+            <p className="text-xs text-muted mt-1">
+              {pending.lastUploadAt
+                ? `Last uploaded ${new Date(pending.lastUploadAt).toLocaleString()}`
+                : "Nothing has been uploaded yet"}
+              {!pending.endpointConfigured && " · Uploads are disabled in this build"}
+            </p>
+          </div>
+        )}
+        {error && <p className="text-xs text-destructive">{error}</p>}
+      </div>
+    </section>
+  );
+}
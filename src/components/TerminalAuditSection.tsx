@@ -0,0 +1,102 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface TerminalAuditEntry {
+  timestamp: string;
+  terminalId: string;
+  projectId: string | null;
+  agentId: string | null;
+  workingDirectory: string;
+  command: string;
+}
+
+interface TerminalAuditSectionProps {
+  enabled: boolean;
+  onEnabledChange: (enabled: boolean) => void;
+}
+
+const RECENT_LIMIT = 100;
+
+export function TerminalAuditSection({ enabled, onEnabledChange }: TerminalAuditSectionProps) {
+  const [entries, setEntries] = useState<TerminalAuditEntry[] | null>(null);
+  const [filter, setFilter] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  const handleView = async () => {
+    setError(null);
+    try {
+      setEntries(
+        await invoke<TerminalAuditEntry[]>("query_terminal_audit", {
+          query: { contains: filter.trim() || null, limit: RECENT_LIMIT },
+        })
+      );
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Terminal Audit Log
+      </h3>
+      <div className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium text-foreground">
+              Record Terminal Commands
+            </label>
+            <p className="text-xs text-muted mt-0.5">
+              Keeps a log of every line entered in embedded terminals, including agent sessions.
+            </p>
+          </div>
+          <button
+            onClick={() => onEnabledChange(!enabled)}
+            className={`relative w-11 h-6 rounded-full transition-colors ${
+              enabled ? "bg-accent" : "bg-muted/30"
+            }`}
+          >
+            <span
+              className={`absolute top-1 left-1 w-4 h-4 rounded-full bg-white transition-transform ${
+                enabled ? "translate-x-5" : ""
+              }`}
+            />
+          </button>
+        </div>
+
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={filter}
+            onChange={(e) => setFilter(e.target.value)}
+            onKeyDown={(e) => e.key === "Enter" && handleView()}
+            placeholder="Filter commands"
+            className="input flex-1 text-xs"
+          />
+          <button onClick={handleView} className="btn btn-sm btn-secondary">
+            View Recent Commands
+          </button>
+        </div>
+
+        {entries && (
+          <div className="max-h-48 overflow-y-auto p-3 rounded-lg border border-border">
+            {entries.length === 0 ? (
+              <p className="text-xs text-muted">No commands recorded</p>
+            ) : (
+              <ul className="space-y-1">
+                {entries.map((entry, index) => (
+                  <li key={index} className="text-xs font-mono" title={entry.workingDirectory}>
+                    <span className="text-muted">{new Date(entry.timestamp).toLocaleString()} </span>
+                    {entry.agentId && <span className="text-muted">[{entry.agentId}] </span>}
+                    <span className="text-foreground">{entry.command}</span>
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        )}
+        {error && <p className="text-xs text-destructive">{error}</p>}
+      </div>
+    </section>
+  );
+}
@@ -7,6 +7,9 @@ import { useTheme } from "../hooks/useTheme";
 import { getTerminalTheme } from "../utils/terminalThemes";
 import { useTerminalStore } from "../stores/terminalStore";
 import { usePanelStore } from "../stores/panelStore";
+import { useProcessStore } from "../stores/processStore";
+import { BUILT_IN_AGENTS } from "../types/agents";
+import { revealMatch, type TerminalSearchResult } from "../utils/terminalSearch";
 import "@xterm/xterm/css/xterm.css";
 
 interface TerminalPanelProps {
@@ -18,6 +21,11 @@ interface SpawnTerminalResult {
   terminalId: string;
 }
 
+interface SpawnAgentSessionResult {
+  terminalId: string;
+  processId: string;
+}
+
 interface TerminalOutputPayload {
   terminalId: string;
   data: string;
@@ -28,9 +36,15 @@ interface TerminalExitPayload {
   exitCode: number | null;
 }
 
+interface TerminalCwd {
+  cwd: string;
+  source: "osc7" | "process" | "initial";
+}
+
 const MIN_HEIGHT = 100;
 const MAX_HEIGHT = 500;
 const COLLAPSED_HEIGHT = 36;
+const CWD_POLL_MS = 2000;
 export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
   const containerRef = useRef<HTMLDivElement>(null);
   const terminalWrapperRef = useRef<HTMLDivElement>(null);
@@ -52,6 +66,14 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
 
   const [isResizing, setIsResizing] = useState(false);
   const [isSpawning, setIsSpawning] = useState(false);
+  const [isSearchOpen, setIsSearchOpen] = useState(false);
+  const [searchQuery, setSearchQuery] = useState("");
+  const [searchRegex, setSearchRegex] = useState(false);
+  const [searchResult, setSearchResult] = useState<TerminalSearchResult | null>(null);
+  const [searchError, setSearchError] = useState<string | null>(null);
+  const [activeMatch, setActiveMatch] = useState(0);
+  const [cwd, setCwd] = useState<string | null>(null);
+  const searchInputRef = useRef<HTMLInputElement>(null);
   const startYRef = useRef(0);
   const startHeightRef = useRef(0);
 
@@ -63,6 +85,7 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
   const getTerminalId = useTerminalStore((state) => state.getTerminalId);
   const hasTerminal = useTerminalStore((state) => state.hasTerminal);
   const markTerminalExited = useTerminalStore((state) => state.markTerminalExited);
+  const registerProcess = useProcessStore((state) => state.registerProcess);
 
   const handleMouseDown = useCallback(
     (e: React.MouseEvent) => {
@@ -148,6 +171,11 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
         "terminal-exit",
         (event) => {
           const { terminalId, exitCode } = event.payload;
+          // Agent sessions are also tracked as processes
+          const processStore = useProcessStore.getState();
+          if (processStore.getProcess(terminalId)) {
+            processStore.unregisterProcess(terminalId, exitCode, exitCode === 0);
+          }
           if (terminalId === currentTerminalIdRef.current && xtermRef.current) {
             xtermRef.current.writeln(
               `\r\n\x1b[90mShell exited with code ${exitCode ?? "unknown"}\x1b[0m`
@@ -209,6 +237,15 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
       }
     });
 
+    // Cmd+F (Ctrl+Shift+F elsewhere) opens search instead of reaching the shell
+    terminal.attachCustomKeyEventHandler((e) => {
+      if (e.type === "keydown" && e.key.toLowerCase() === "f" && (e.metaKey || (e.ctrlKey && e.shiftKey))) {
+        setIsSearchOpen(true);
+        return false;
+      }
+      return true;
+    });
+
     // Handle keyboard input - send to PTY
     terminal.onData((data) => {
       if (currentTerminalIdRef.current) {
@@ -238,6 +275,7 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
 
           const result = await invoke<SpawnTerminalResult>("spawn_terminal", {
             workingDirectory: projectPath,
+            projectId,
             cols,
             rows,
           });
@@ -326,6 +364,7 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
 
           const result = await invoke<SpawnTerminalResult>("spawn_terminal", {
             workingDirectory: projectPath,
+            projectId,
             cols,
             rows,
           });
@@ -366,6 +405,82 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
     }
   }, [height, isCollapsed]);
 
+  // Follow the shell's working directory while the panel is open
+  useEffect(() => {
+    if (isCollapsed) return;
+    const refresh = () => {
+      const terminalId = currentTerminalIdRef.current;
+      if (!terminalId) {
+        setCwd(null);
+        return;
+      }
+      invoke<TerminalCwd>("get_terminal_cwd", { terminalId })
+        .then((result) => setCwd(result.cwd))
+        .catch(() => setCwd(null));
+    };
+    refresh();
+    const interval = setInterval(refresh, CWD_POLL_MS);
+    return () => clearInterval(interval);
+  }, [isCollapsed, projectId]);
+
+  const handleRevealCwd = () => {
+    if (!cwd) return;
+    invoke("reveal_in_file_manager", { path: cwd }).catch((err) =>
+      console.error("Failed to reveal directory:", err)
+    );
+  };
+
+  // Search the backend scrollback as the query changes
+  useEffect(() => {
+    const terminalId = currentTerminalIdRef.current;
+    if (!isSearchOpen || !searchQuery || !terminalId) {
+      setSearchResult(null);
+      setSearchError(null);
+      return;
+    }
+    const timer = setTimeout(() => {
+      invoke<TerminalSearchResult>("search_terminal_buffer", {
+        terminalId,
+        query: searchQuery,
+        regex: searchRegex,
+      })
+        .then((result) => {
+          setSearchResult(result);
+          setSearchError(null);
+          // Start from the most recent match
+          setActiveMatch(Math.max(0, result.matches.length - 1));
+        })
+        .catch((error) => {
+          setSearchResult(null);
+          setSearchError(String(error));
+        });
+    }, 200);
+    return () => clearTimeout(timer);
+  }, [isSearchOpen, searchQuery, searchRegex, projectId]);
+
+  useEffect(() => {
+    const match = searchResult?.matches[activeMatch];
+    if (match && searchResult && xtermRef.current) {
+      revealMatch(xtermRef.current, match, searchResult);
+    }
+  }, [searchResult, activeMatch]);
+
+  useEffect(() => {
+    if (isSearchOpen) searchInputRef.current?.focus();
+  }, [isSearchOpen]);
+
+  const stepMatch = (delta: number) => {
+    const count = searchResult?.matches.length ?? 0;
+    if (count > 0) setActiveMatch((index) => (index + delta + count) % count);
+  };
+
+  const closeSearch = () => {
+    setIsSearchOpen(false);
+    setSearchQuery("");
+    xtermRef.current?.clearSelection();
+    xtermRef.current?.focus();
+  };
+
   const toggleCollapse = () => {
     setIsCollapsed(!isCollapsed);
   };
@@ -376,12 +491,7 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
     }
   };
 
-  const handleRestart = async () => {
-    if (!xtermRef.current) return;
-
-    xtermRef.current.clear();
-
-    // Kill existing terminal for this project
+  const killCurrentTerminal = async () => {
     const existingId = getTerminalId(projectId);
     if (existingId) {
       unregisterTerminal(projectId);
@@ -390,6 +500,15 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
       );
     }
     currentTerminalIdRef.current = null;
+  };
+
+  const handleRestart = async () => {
+    if (!xtermRef.current) return;
+
+    xtermRef.current.clear();
+
+    // Kill existing terminal for this project
+    await killCurrentTerminal();
 
     // Spawn new terminal
     setIsSpawning(true);
@@ -399,6 +518,7 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
 
       const result = await invoke<SpawnTerminalResult>("spawn_terminal", {
         workingDirectory: projectPath,
+        projectId,
         cols,
         rows,
       });
@@ -417,6 +537,55 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
     }
   };
 
+  const handleStartAgent = async (agentId: string) => {
+    const agent = BUILT_IN_AGENTS.find((plugin) => plugin.id === agentId);
+    if (!xtermRef.current || !agent) return;
+
+    xtermRef.current.clear();
+
+    // The session replaces the project's shell
+    await killCurrentTerminal();
+
+    setIsSpawning(true);
+    try {
+      const cols = xtermRef.current?.cols ?? 80;
+      const rows = xtermRef.current?.rows ?? 24;
+
+      const result = await invoke<SpawnAgentSessionResult>("spawn_agent_interactive", {
+        agentId,
+        projectId,
+        projectPath,
+        cols,
+        rows,
+      });
+
+      currentTerminalIdRef.current = result.terminalId;
+      registerTerminal(projectId, result.terminalId, projectPath);
+      registerProcess({
+        processId: result.processId,
+        projectId,
+        type: "chat",
+        label: `${agent.name} session`,
+        agentId,
+        command: {
+          executable: agent.command,
+          args: agent.interactiveArgs,
+          workingDirectory: projectPath,
+        },
+      });
+      xtermRef.current?.focus();
+    } catch (error) {
+      console.error("Failed to start agent session:", error);
+      if (xtermRef.current) {
+        xtermRef.current.writeln(
+          `\x1b[31mFailed to start ${agent.name}: ${error}\x1b[0m`
+        );
+      }
+    } finally {
+      setIsSpawning(false);
+    }
+  };
+
   const panelHeight = isCollapsed ? COLLAPSED_HEIGHT : height + COLLAPSED_HEIGHT;
 
   return (
@@ -467,7 +636,107 @@ export function TerminalPanel({ projectId, projectPath }: TerminalPanelProps) {
           )}
         </button>
 
+        {!isCollapsed && cwd && (
+          <button
+            onClick={handleRevealCwd}
+            className="ml-3 mr-auto min-w-0 truncate text-xs font-mono text-muted hover:text-foreground transition-colors"
+            title={`${cwd}\nReveal in file manager`}
+          >
+            {cwd.startsWith(projectPath) ? `./${cwd.slice(projectPath.length).replace(/^\//, "")}` : cwd}
+          </button>
+        )}
+
         <div className="flex items-center gap-2">
+          {!isCollapsed && isSearchOpen && (
+            <div className="flex items-center gap-1">
+              <input
+                ref={searchInputRef}
+                type="text"
+                value={searchQuery}
+                onChange={(e) => setSearchQuery(e.target.value)}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") stepMatch(e.shiftKey ? 1 : -1);
+                  if (e.key === "Escape") closeSearch();
+                }}
+                placeholder="Search output"
+                className={`w-48 px-2 py-0.5 rounded border bg-background text-foreground text-xs focus:outline-none focus:ring-1 focus:ring-accent ${
+                  searchError ? "border-destructive" : "border-border"
+                }`}
+                title={searchError ?? undefined}
+              />
+              <button
+                onClick={() => setSearchRegex(!searchRegex)}
+                className={`px-1 rounded text-xs font-mono transition-colors ${
+                  searchRegex ? "text-accent bg-accent/15" : "text-muted hover:text-foreground"
+                }`}
+                title="Use regular expression"
+              >
+                .*
+              </button>
+              <span className="text-xs text-muted w-16 text-right">
+                {searchResult &&
+                  (searchResult.matches.length === 0
+                    ? "No results"
+                    : `${activeMatch + 1}/${searchResult.matches.length}${searchResult.truncated ? "+" : ""}`)}
+              </span>
+              <button
+                onClick={() => stepMatch(-1)}
+                className="p-1 rounded text-muted hover:text-foreground hover:bg-card"
+                title="Previous match (Enter)"
+              >
+                ↑
+              </button>
+              <button
+                onClick={() => stepMatch(1)}
+                className="p-1 rounded text-muted hover:text-foreground hover:bg-card"
+                title="Next match (Shift+Enter)"
+              >
+                ↓
+              </button>
+            </div>
+          )}
+
+          {!isCollapsed && (
+            <select
+              value=""
+              onChange={(e) => e.target.value && handleStartAgent(e.target.value)}
+              disabled={isSpawning}
+              className="text-xs bg-transparent text-muted hover:text-foreground rounded px-1 py-0.5 focus:outline-none"
+              title="Run an agent interactively in this terminal"
+            >
+              <option value="">Agent session…</option>
+              {BUILT_IN_AGENTS.map((plugin) => (
+                <option key={plugin.id} value={plugin.id}>
+                  {plugin.name}
+                </option>
+              ))}
+            </select>
+          )}
+
+          {!isCollapsed && (
+            <button
+              onClick={() => (isSearchOpen ? closeSearch() : setIsSearchOpen(true))}
+              className={`p-1 rounded transition-colors hover:bg-card ${
+                isSearchOpen ? "text-accent" : "text-muted hover:text-foreground"
+              }`}
+              title="Search output"
+            >
+              <svg
+                className="w-3.5 h-3.5"
+                fill="none"
+                stroke="currentColor"
+                viewBox="0 0 24 24"
+              >
+                <path
+                  strokeLinecap="round"
+                  strokeLinejoin="round"
+                  strokeWidth={1.5}
+                  d="M21 21l-6-6m2-5a7 7 0 11-14 0 7 7 0 0114 0z"
+                />
+              </svg>
+            </button>
+          )}
+
           <button
             onClick={handleClear}
             className="p-1 rounded transition-colors text-muted hover:text-foreground hover:bg-card"
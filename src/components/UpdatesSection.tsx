@@ -0,0 +1,126 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface UpdateInfo {
+  version: string;
+  currentVersion: string;
+  channel: string;
+  date: string | null;
+  notes: string | null;
+}
+
+interface UpdateProgressPayload {
+  phase: "downloading" | "downloaded" | "installing";
+  downloaded: number;
+  total: number | null;
+}
+
+type UpdateStatus = "idle" | "checking" | "up-to-date" | "available" | "downloading" | "ready" | "installing";
+
+interface UpdatesSectionProps {
+  channel: string;
+  onChannelChange: (channel: string) => void;
+}
+
+export function UpdatesSection({ channel, onChannelChange }: UpdatesSectionProps) {
+  const [status, setStatus] = useState<UpdateStatus>("idle");
+  const [update, setUpdate] = useState<UpdateInfo | null>(null);
+  const [progress, setProgress] = useState<number | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<UpdateProgressPayload>("update-progress", ({ payload }) => {
+      if (payload.phase === "downloading" && payload.total) {
+        setProgress(Math.round((payload.downloaded / payload.total) * 100));
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const run = async (next: UpdateStatus, action: () => Promise<void>) => {
+    const previous = status;
+    setError(null);
+    setStatus(next);
+    try {
+      await action();
+    } catch (err) {
+      setError(String(err));
+      setStatus(previous);
+    }
+  };
+
+  const handleCheck = () =>
+    run("checking", async () => {
+      const info = await invoke<UpdateInfo | null>("check_for_update", { channel });
+      setUpdate(info);
+      setStatus(info ? "available" : "up-to-date");
+    });
+
+  const handleDownload = () =>
+    run("downloading", async () => {
+      setProgress(0);
+      await invoke("download_update");
+      setStatus("ready");
+    });
+
+  const handleInstall = () => run("installing", () => invoke("install_update"));
+
+  return (
+    <section>
+      <h3 className="text-sm font-medium text-secondary uppercase tracking-wider mb-3">
+        Updates
+      </h3>
+      <div className="space-y-4">
+        <div>
+          <label className="block text-sm text-foreground mb-2">Release Channel</label>
+          <select
+            value={channel}
+            onChange={(e) => {
+              onChannelChange(e.target.value);
+              setUpdate(null);
+              setStatus("idle");
+            }}
+            className="w-full px-3 py-2 rounded-lg border border-border bg-background text-foreground focus:outline-none focus:ring-2 focus:ring-accent"
+          >
+            <option value="stable">Stable</option>
+            <option value="beta">Beta</option>
+          </select>
+          <p className="text-xs text-muted mt-1">Beta releases get new features sooner but may be less reliable</p>
+        </div>
+
+        <div className="flex items-center justify-between gap-3">
+          <p className="text-sm text-secondary">
+            {status === "checking" && "Checking for updates..."}
+            {status === "up-to-date" && "You're on the latest version"}
+            {status === "available" && update && `Version ${update.version} is available (you have ${update.currentVersion})`}
+            {status === "downloading" && `Downloading${progress !== null ? ` ${progress}%` : "..."}`}
+            {status === "ready" && update && `Version ${update.version} is ready to install`}
+            {status === "installing" && "Installing..."}
+          </p>
+          {(status === "idle" || status === "up-to-date" || status === "checking") && (
+            <button onClick={handleCheck} disabled={status === "checking"} className="btn btn-sm btn-secondary">
+              Check for Updates
+            </button>
+          )}
+          {status === "available" && (
+            <button onClick={handleDownload} className="btn btn-sm btn-primary">
+              Download
+            </button>
+          )}
+          {status === "ready" && (
+            <button onClick={handleInstall} className="btn btn-sm btn-primary">
+              Install &amp; Restart
+            </button>
+          )}
+        </div>
+        {update?.notes && (status === "available" || status === "ready") && (
+          <pre className="text-xs text-muted whitespace-pre-wrap max-h-32 overflow-y-auto">{update.notes}</pre>
+        )}
+        {error && <p className="text-xs text-destructive">{error}</p>}
+      </div>
+    </section>
+  );
+}
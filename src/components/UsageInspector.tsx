@@ -0,0 +1,130 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface MessageUsage {
+  index: number;
+  timestamp: string | null;
+  model: string | null;
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  totalTokens: number;
+  credits: number | null;
+  cost: number | null;
+  elapsedMs: number | null;
+  tools: string[];
+  preview: string | null;
+  stopReason: string | null;
+}
+
+interface UsageInspection {
+  agentId: string;
+  id: string;
+  title: string | null;
+  startedAt: string | null;
+  durationMs: number;
+  messages: MessageUsage[];
+  totalInputTokens: number;
+  totalOutputTokens: number;
+  totalCacheCreationTokens: number;
+  totalCacheReadTokens: number;
+  totalTokens: number;
+  totalCredits: number | null;
+  totalCost: number | null;
+}
+
+interface UsageInspectorProps {
+  agentId: "amp" | "claude-code";
+  /** Amp thread ID or Claude Code session ID */
+  id: string;
+}
+
+function formatElapsed(ms: number): string {
+  if (ms < 1000) return `${ms}ms`;
+  if (ms < 60_000) return `${(ms / 1000).toFixed(1)}s`;
+  return `${Math.floor(ms / 60_000)}m ${Math.round((ms % 60_000) / 1000)}s`;
+}
+
+/** Tokens a message used, counting cache reads and writes */
+function weight(message: MessageUsage): number {
+  return message.totalTokens + message.cacheCreationTokens + message.cacheReadTokens;
+}
+
+/** Per-message token, cost, and timing breakdown of one thread or session. */
+export function UsageInspector({ agentId, id }: UsageInspectorProps) {
+  const [inspection, setInspection] = useState<UsageInspection | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setInspection(null);
+    setError(null);
+    const command = agentId === "amp" ? "inspect_amp_thread" : "inspect_claude_session";
+    const args = agentId === "amp" ? { threadId: id } : { sessionId: id };
+    invoke<UsageInspection>(command, args)
+      .then(setInspection)
+      .catch((e) => setError(String(e)));
+  }, [agentId, id]);
+
+  if (error) {
+    return <p className="mt-3 text-xs text-red-400">{error}</p>;
+  }
+  if (!inspection) {
+    return <p className="mt-3 text-xs text-muted">Loading messages...</p>;
+  }
+  if (inspection.messages.length === 0) {
+    return <p className="mt-3 text-xs text-muted">No assistant messages with usage</p>;
+  }
+
+  const heaviest = Math.max(...inspection.messages.map(weight));
+
+  return (
+    <div className="mt-3 border-t border-border pt-3 space-y-1 max-h-80 overflow-y-auto" onClick={(e) => e.stopPropagation()}>
+      {inspection.messages.map((message) => {
+        const share = heaviest > 0 ? weight(message) / heaviest : 0;
+        return (
+          <div key={message.index} className="relative px-2 py-1.5 rounded text-xs">
+            <div
+              className="absolute inset-y-0 left-0 rounded bg-accent/10"
+              style={{ width: `${Math.round(share * 100)}%` }}
+            />
+            <div className="relative flex items-start justify-between gap-3">
+              <div className="flex-1 min-w-0">
+                <div className="flex items-center gap-2 text-muted">
+                  <span>#{message.index}</span>
+                  {message.elapsedMs !== null && <span>⏱ {formatElapsed(message.elapsedMs)}</span>}
+                  {message.stopReason && <span>{message.stopReason.replace("_", " ")}</span>}
+                </div>
+                {message.tools.length > 0 && (
+                  <div className="font-mono text-secondary truncate" title={message.tools.join("\n")}>
+                    {message.tools.join(", ")}
+                  </div>
+                )}
+                {message.preview && (
+                  <div className="text-muted truncate" title={message.preview}>
+                    {message.preview}
+                  </div>
+                )}
+              </div>
+              <div className="text-right flex-shrink-0">
+                <div className="text-foreground">
+                  {message.inputTokens.toLocaleString()} in / {message.outputTokens.toLocaleString()} out
+                </div>
+                {message.cacheReadTokens + message.cacheCreationTokens > 0 && (
+                  <div className="text-muted">
+                    {message.cacheReadTokens.toLocaleString()} cache read / {message.cacheCreationTokens.toLocaleString()} written
+                  </div>
+                )}
+                {message.credits !== null ? (
+                  <div className="text-accent">{message.credits.toFixed(2)} credits</div>
+                ) : message.cost !== null ? (
+                  <div className="text-accent">${message.cost.toFixed(4)}</div>
+                ) : null}
+              </div>
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
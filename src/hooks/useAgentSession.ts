@@ -2,29 +2,25 @@ import { useCallback, useEffect, useRef } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import { useAgentStore, type AgentSession } from '../stores/agentStore'
-import { useProcessStore } from '../stores/processStore'
+import { useProcessStore, type TerminationReason } from '../stores/processStore'
 import { defaultPlugins } from '../types'
-
-interface SpawnAgentResult {
-  processId: string
-}
+import { spawnAgent } from '../utils/spawnAgent'
+import { listenAgentOutput } from '../utils/agentOutput'
 
 interface WaitAgentResult {
   processId: string
   exitCode: number | null
   success: boolean
-}
-
-interface AgentOutputEvent {
-  processId: string
-  streamType: 'stdout' | 'stderr'
-  content: string
+  signal: number | null
+  terminationReason: TerminationReason
 }
 
 interface AgentExitEvent {
   processId: string
   exitCode: number | null
   success: boolean
+  signal: number | null
+  terminationReason: TerminationReason
 }
 
 export function useAgentSession(
@@ -74,11 +70,11 @@ export function useAgentSession(
     let mounted = true
 
     const setupListeners = async () => {
-      unlistenOutputRef.current = await listen<AgentOutputEvent>('agent-output', (event) => {
+      unlistenOutputRef.current = await listenAgentOutput((output) => {
         if (!mounted) return
-        if (event.payload.processId === currentProcessIdRef.current) {
-          appendToLastMessage(projectId, event.payload.content + '\n')
-          onOutputRef.current(event.payload.content, event.payload.streamType)
+        if (output.processId === currentProcessIdRef.current) {
+          appendToLastMessage(projectId, output.content + '\n')
+          onOutputRef.current(output.content, output.streamType)
         }
       })
 
@@ -171,10 +167,11 @@ export function useAgentSession(
         arg.replace('{{prompt}}', fullPrompt)
       )
 
-      const result = await invoke<SpawnAgentResult>('spawn_agent', {
+      const result = await spawnAgent({
         executable: plugin.command,
         args,
         workingDirectory: projectPath,
+        agentId: plugin.id,
       })
 
       currentProcessIdRef.current = result.processId
@@ -0,0 +1,290 @@
+import { useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useBuildStore, type AgentProgress, type BudgetWarning, type BuildForecast, type BuildSwarm, type LogEntry, type StoryBuildStatus } from '../stores/buildStore'
+import { usePrdStore } from '../stores/prdStore'
+import { useCostStore, type PersistedCostEntry } from '../stores/costStore'
+import { useProcessStore } from '../stores/processStore'
+import { useProjectStore } from '../stores/projectStore'
+import { listenWithReplay } from '../utils/eventBus'
+import { notify } from '../utils/notify'
+
+interface BuildStatusPayload {
+  projectId: string
+  status: 'running' | 'paused' | 'idle'
+  currentStoryId: string | null
+  currentStoryTitle: string | null
+}
+
+interface BuildLogPayload {
+  projectId: string
+  logType: LogEntry['type']
+  content: string
+}
+
+interface BuildStoryStatusPayload {
+  projectId: string
+  storyId: string
+  status: StoryBuildStatus
+}
+
+interface BuildProcessPayload {
+  projectId: string
+  storyId: string
+  storyTitle: string
+  processId: string
+  agentId: string
+  fallbackFrom: string | null
+  executable: string
+  args: string[]
+  workingDirectory: string
+  parallel: boolean
+}
+
+interface BuildSnapshotPayload {
+  projectId: string
+  storyId: string
+  snapshotRef: string
+  snapshotType: 'stash' | 'commit'
+}
+
+interface BuildStoryFinishedPayload {
+  projectId: string
+  storyId: string
+  storyTitle: string
+  processId: string | null
+  agentId: string
+  success: boolean
+  exitCode: number | null
+  reason: string | null
+  failureKind: string | null
+  durationMs: number
+  recentOutput: string
+}
+
+interface BuildMergeConflictPayload {
+  projectId: string
+  storyId: string
+  storyTitle: string
+  branchName: string
+}
+
+interface BudgetWarningPayload extends BudgetWarning {
+  projectId: string
+}
+
+interface BuildForecastPayload extends BuildForecast {
+  projectId: string
+}
+
+interface BuildSwarmPayload extends BuildSwarm {
+  projectId: string
+}
+
+interface AgentProgressPayload extends AgentProgress {
+  processId: string
+  projectId: string | null
+  storyId: string | null
+}
+
+interface BuildFinishedPayload {
+  buildId: string
+  projectId: string
+  outcome: 'complete' | 'incomplete' | 'canceled'
+  passed: number
+  failed: number
+  durationMs: number
+}
+
+interface BuildStatusInfo {
+  buildId: string
+  projectId: string
+  projectPath: string
+  mode: string
+  status: string
+  startedAt: string
+  runningStoryIds: string[]
+}
+
+interface CostRecordedPayload {
+  projectId: string
+  projectPath: string
+  entry: PersistedCostEntry
+}
+
+interface CostsReconciledPayload {
+  projectId: string
+  projectPath: string
+  added: number
+  alreadyRecorded: number
+}
+
+/**
+ * Mirrors backend build orchestration events into the build, PRD, process,
+ * and cost stores. Mount once at the app root; builds run in the backend and
+ * the UI only observes them.
+ */
+export function useBuildEvents() {
+  useEffect(() => {
+    const projectName = (projectId: string) =>
+      useProjectStore.getState().projects.find((p) => p.id === projectId)?.name
+    const projectPath = (projectId: string) =>
+      useProjectStore.getState().projects.find((p) => p.id === projectId)?.path
+
+    // Re-attach to builds that kept running while the window was closed
+    invoke<BuildStatusInfo[]>('list_active_builds')
+      .then((builds) => {
+        const build = useBuildStore.getState()
+        for (const info of builds) {
+          build.tryStartBuild(info.projectId)
+          if (info.status === 'paused') {
+            build.pauseBuild(info.projectId)
+          } else {
+            build.startBuild(info.projectId)
+          }
+        }
+      })
+      .catch(() => {})
+
+    const unlisteners = [
+      // Replayed so a reopened window catches up with running builds
+      listenWithReplay<BuildStatusPayload>('build-status', (payload) => {
+        const build = useBuildStore.getState()
+        if (payload.status === 'running') {
+          build.startBuild(payload.projectId)
+        } else if (payload.status === 'paused') {
+          build.pauseBuild(payload.projectId)
+        } else {
+          build.cancelBuild(payload.projectId)
+          return
+        }
+        build.setCurrentStory(payload.projectId, payload.currentStoryId, payload.currentStoryTitle)
+      }),
+
+      listenWithReplay<BuildLogPayload>('build-log', (payload) => {
+        useBuildStore.getState().appendLog(payload.projectId, payload.logType, payload.content)
+      }),
+
+      listenWithReplay<BuildStoryStatusPayload>('build-story-status', (payload) => {
+        useBuildStore.getState().setStoryStatus(payload.projectId, payload.storyId, payload.status)
+        if (payload.status === 'complete') {
+          // The backend has already written prd.json
+          usePrdStore.getState().updateStory(payload.projectId, payload.storyId, { passes: true })
+        }
+      }),
+
+      listen<BuildProcessPayload>('build-process-started', ({ payload }) => {
+        const build = useBuildStore.getState()
+        if (!payload.parallel) {
+          build.setCurrentProcessId(payload.projectId, payload.processId)
+        }
+        build.setStoryProgress(payload.projectId, payload.storyId, null)
+        build.appendLog(
+          payload.projectId,
+          'system',
+          payload.parallel
+            ? `[Parallel] Agent started for ${payload.storyId} (PID: ${payload.processId})`
+            : `Agent process started (ID: ${payload.processId})`
+        )
+        useProcessStore.getState().registerProcess({
+          processId: payload.processId,
+          projectId: payload.projectId,
+          projectName: projectName(payload.projectId),
+          type: 'build',
+          label: payload.parallel ? `[P] ${payload.storyTitle}` : payload.storyTitle,
+          agentId: payload.agentId,
+          fallbackFrom: payload.fallbackFrom ?? undefined,
+          command: {
+            executable: payload.executable,
+            args: payload.args,
+            workingDirectory: payload.workingDirectory,
+          },
+        })
+      }),
+
+      listen<BuildSnapshotPayload>('build-story-snapshot', ({ payload }) => {
+        useBuildStore.getState().setStorySnapshot(payload.projectId, payload.storyId, {
+          snapshotRef: payload.snapshotRef,
+          snapshotType: payload.snapshotType,
+        })
+      }),
+
+      listen<BuildStoryFinishedPayload>('build-story-finished', async ({ payload }) => {
+        if (payload.processId) {
+          useProcessStore.getState().unregisterProcess(payload.processId, payload.exitCode, payload.success)
+        }
+
+        const path = projectPath(payload.projectId)
+        if (path && payload.recentOutput) {
+          useCostStore.getState().parseAndAddFromOutput(
+            payload.projectId,
+            path,
+            payload.agentId,
+            `Story: ${payload.storyTitle}`,
+            payload.recentOutput,
+            payload.durationMs
+          )
+        }
+
+        if (!payload.success) return
+        useBuildStore.getState().clearStorySnapshot(payload.projectId, payload.storyId)
+      }),
+
+      listen<BuildMergeConflictPayload>('build-merge-conflict', ({ payload }) => {
+        useBuildStore.getState().addConflictedBranch(payload.projectId, {
+          storyId: payload.storyId,
+          storyTitle: payload.storyTitle,
+          branchName: payload.branchName,
+        })
+        notify.warning('Merge Conflict', `Story ${payload.storyId} has conflicts. Resolve manually in branch: ${payload.branchName}`)
+      }),
+
+      // The backend also sends a desktop notification for the build's cost
+      listen<BudgetWarningPayload>('budget-warning', ({ payload }) => {
+        const { projectId, ...warning } = payload
+        useBuildStore.getState().setBudgetWarning(projectId, warning)
+      }),
+
+      // Re-projected after each story passes or fails
+      listenWithReplay<BuildForecastPayload>('build-forecast', (payload) => {
+        const { projectId, ...forecast } = payload
+        useBuildStore.getState().setForecast(projectId, forecast)
+      }),
+
+      // Swarm-mode builds report each step of a story's plan, work, and review
+      listenWithReplay<BuildSwarmPayload>('build-swarm', (payload) => {
+        const { projectId, ...swarm } = payload
+        useBuildStore.getState().setSwarm(projectId, swarm)
+      }),
+
+      // Only agents with progress rules configured report progress
+      listen<AgentProgressPayload>('agent-progress', ({ payload }) => {
+        const { projectId, storyId, step, percent, file } = payload
+        if (projectId && storyId) {
+          useBuildStore.getState().setStoryProgress(projectId, storyId, { step, percent, file })
+        }
+      }),
+
+      // Agent threads attributed to a finished build were appended to costs.json
+      listen<CostsReconciledPayload>('costs-reconciled', ({ payload }) => {
+        useCostStore.getState().reloadProjectCostHistory(payload.projectId, payload.projectPath)
+      }),
+
+      // Usage an agent reported in its structured output was appended to costs.json
+      listen<CostRecordedPayload>('cost-recorded', ({ payload }) => {
+        useCostStore.getState().addRecordedEntry(payload.entry)
+      }),
+
+      // Desktop notifications for story and build outcomes are sent by the backend
+      listen<BuildFinishedPayload>('build-finished', ({ payload }) => {
+        useBuildStore.getState().releaseBuildLoop(payload.projectId)
+        useBuildStore.getState().setForecast(payload.projectId, null)
+        useBuildStore.getState().setSwarm(payload.projectId, null)
+      }),
+    ]
+
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()))
+    }
+  }, [])
+}
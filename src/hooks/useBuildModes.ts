@@ -0,0 +1,64 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+
+export interface BuildModeOption {
+  value: string
+  label: string
+  description: string
+  parallel: boolean
+}
+
+interface BuildModeInfo {
+  id: string
+  label: string
+  description: string
+  parallel: boolean
+}
+
+const NONE_OPTION: BuildModeOption = {
+  value: 'none',
+  label: 'None',
+  description: 'No automatic building',
+  parallel: false,
+}
+
+// Shown until the backend list arrives
+const FALLBACK_OPTIONS: BuildModeOption[] = [
+  { value: 'ralph', label: 'Ralph', description: 'Sequential story execution', parallel: false },
+  { value: 'parallel', label: 'Parallel', description: 'Run stories concurrently', parallel: true },
+  NONE_OPTION,
+]
+
+let cachedOptions: Promise<BuildModeOption[]> | null = null
+
+function loadBuildModes(): Promise<BuildModeOption[]> {
+  if (!cachedOptions) {
+    cachedOptions = invoke<BuildModeInfo[]>('list_build_modes')
+      .then((modes) => [
+        ...modes.map((m) => ({ value: m.id, label: m.label, description: m.description, parallel: m.parallel })),
+        NONE_OPTION,
+      ])
+      .catch(() => {
+        cachedOptions = null
+        return FALLBACK_OPTIONS
+      })
+  }
+  return cachedOptions
+}
+
+/** Build modes offered by the backend orchestrator, plus "none". */
+export function useBuildModes(): BuildModeOption[] {
+  const [options, setOptions] = useState<BuildModeOption[]>(FALLBACK_OPTIONS)
+
+  useEffect(() => {
+    let cancelled = false
+    loadBuildModes().then((loaded) => {
+      if (!cancelled) setOptions(loaded)
+    })
+    return () => {
+      cancelled = true
+    }
+  }, [])
+
+  return options
+}
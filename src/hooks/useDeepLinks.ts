@@ -0,0 +1,54 @@
+import { useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useProjectStore } from '../stores/projectStore'
+import { useIdeasStore } from '../stores/ideasStore'
+
+type DeepLinkAction =
+  | { type: 'open-project'; projectId: string }
+  | { type: 'new-idea'; title: string; summary: string; description: string }
+
+async function runAction(action: DeepLinkAction) {
+  switch (action.type) {
+    case 'open-project':
+      useIdeasStore.getState().selectIdea(null)
+      useProjectStore.getState().setActiveProject(action.projectId)
+      break
+    case 'new-idea': {
+      const ideas = useIdeasStore.getState()
+      const idea = await ideas.addIdea({
+        title: action.title || 'Untitled Idea',
+        summary: action.summary,
+        description: action.description,
+      })
+      ideas.selectIdea(idea.id)
+      break
+    }
+  }
+}
+
+/**
+ * Carries out `ideate://` links routed by the backend. Actions queue there
+ * until projects and ideas have loaded.
+ */
+export function useDeepLinks(enabled: boolean) {
+  useEffect(() => {
+    if (!enabled) return
+
+    const drain = async () => {
+      const actions = await invoke<DeepLinkAction[]>('take_pending_deep_links').catch(() => [])
+      for (const action of actions) {
+        await runAction(action)
+      }
+    }
+
+    drain()
+    const unlisten = listen<DeepLinkAction>('deep-link', () => {
+      drain()
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [enabled])
+}
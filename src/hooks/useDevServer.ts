@@ -5,8 +5,10 @@ import { readTextFile, exists } from '@tauri-apps/plugin-fs'
 import { usePromptStore } from '../stores/promptStore'
 import { useProcessStore } from '../stores/processStore'
 import { notify } from '../utils/notify'
+import { spawnAgent } from '../utils/spawnAgent'
+import { listenAgentOutput } from '../utils/agentOutput'
 
-interface DevServerConfig {
+export interface DevServerConfig {
   command: string
   args: string[]
   url: string
@@ -14,16 +16,6 @@ interface DevServerConfig {
   env?: Record<string, string>
 }
 
-interface SpawnAgentResult {
-  processId: string
-}
-
-interface AgentOutputEvent {
-  processId: string
-  streamType: string
-  content: string
-}
-
 interface AgentExitEvent {
   processId: string
   exitCode: number | null
@@ -36,7 +28,7 @@ export type DevServerStatus = 'idle' | 'detecting' | 'starting' | 'running' | 's
 // This persists across component remounts to prevent auto-restart
 const manuallyStoppedProjects = new Set<string>()
 
-async function detectDevServerFromPackage(projectPath: string): Promise<DevServerConfig | null> {
+export async function detectDevServerFromPackage(projectPath: string): Promise<DevServerConfig | null> {
   try {
     const packageJsonPath = `${projectPath}/package.json`
     
@@ -129,6 +121,19 @@ async function detectDevServerFromPackage(projectPath: string): Promise<DevServe
   }
 }
 
+/** Frameworks whose dev servers ignore the PORT variable. */
+const PORT_FLAG_FRAMEWORKS = ['Vite', 'Vue', 'Svelte', 'Angular', 'Astro', 'Gatsby']
+
+/**
+ * Shell command that runs a detected dev server on `{port}`, the placeholder
+ * start_story_preview fills in with the port it assigns.
+ */
+export function previewCommand(config: DevServerConfig): string {
+  const base = [config.command, ...config.args].join(' ')
+  if (!PORT_FLAG_FRAMEWORKS.includes(config.framework ?? '')) return base
+  return config.command === 'npm' ? `${base} -- --port {port}` : `${base} --port {port}`
+}
+
 export function useDevServer(projectPath: string, projectId?: string) {
   const [status, setStatus] = useState<DevServerStatus>('idle')
   const [config, setConfig] = useState<DevServerConfig | null>(null)
@@ -202,19 +207,19 @@ export function useDevServer(projectPath: string, projectId?: string) {
     }
     
     const setupListeners = async () => {
-      unlistenOutputRef.current = await listen<AgentOutputEvent>('agent-output', (event) => {
+      unlistenOutputRef.current = await listenAgentOutput((output) => {
         if (!mounted) return
         
-        if (event.payload.processId === serverProcessIdRef.current) {
+        if (output.processId === serverProcessIdRef.current) {
           // Batch log updates to reduce UI pressure (for local logs state)
-          logBufferRef.current.push(event.payload.content)
+          logBufferRef.current.push(output.content)
           if (logFlushTimeoutRef.current === null) {
             logFlushTimeoutRef.current = window.setTimeout(flushLogs, 100)
           }
           
           // Detect URL from output - handles port changes like "Port 5173 is in use, trying another one..."
           // Strip ANSI escape codes first for cleaner matching
-          const cleanContent = event.payload.content.replace(/\x1b\[[0-9;]*m/g, '')
+          const cleanContent = output.content.replace(/\x1b\[[0-9;]*m/g, '')
           
           // Match URLs with port numbers (e.g., http://localhost:5178/)
           // Use a comprehensive regex that captures the full URL with port
@@ -240,11 +245,11 @@ export function useDevServer(projectPath: string, projectId?: string) {
               })
             }
           }
-        } else if (event.payload.processId === detectProcessIdRef.current) {
+        } else if (output.processId === detectProcessIdRef.current) {
           // Collect detection output for JSON parsing
-          detectOutputRef.current += event.payload.content + '\n'
+          detectOutputRef.current += output.content + '\n'
           // Also log to processStore for display in AgentRunView
-          appendProcessLog(event.payload.processId, event.payload.streamType as 'stdout' | 'stderr', event.payload.content)
+          appendProcessLog(output.processId, output.streamType as 'stdout' | 'stderr', output.content)
         }
       })
       
@@ -329,7 +334,7 @@ export function useDevServer(projectPath: string, projectId?: string) {
     const prompt = usePromptStore.getState().getPrompt('devServerDetection')
 
     try {
-      const result = await invoke<SpawnAgentResult>('spawn_agent', {
+      const result = await spawnAgent({
         executable: 'amp',
         args: ['--execute', prompt],
         workingDirectory: projectPath,
@@ -461,10 +466,11 @@ export function useDevServer(projectPath: string, projectId?: string) {
     setUrl(config.url)
     
     try {
-      const result = await invoke<SpawnAgentResult>('spawn_agent', {
+      const result = await spawnAgent({
         executable: config.command,
         args: config.args,
         workingDirectory: projectPath,
+        processType: 'dev-server',
       })
       
       serverProcessIdRef.current = result.processId
@@ -559,6 +565,7 @@ export function useDevServer(projectPath: string, projectId?: string) {
     status,
     config,
     url,
+    processId: serverProcessIdRef.current,
     error,
     logs,
     wasManualyStopped,
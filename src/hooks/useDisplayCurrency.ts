@@ -0,0 +1,61 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+
+export interface DisplayCurrency {
+  code: string
+  /** Units of `code` per USD. */
+  rate: number
+  source: 'usd' | 'manual' | 'fetched'
+  updatedAt: string | null
+}
+
+const USD: DisplayCurrency = { code: 'USD', rate: 1, source: 'usd', updatedAt: null }
+
+let current: DisplayCurrency = USD
+let loading: Promise<void> | null = null
+const listeners = new Set<(currency: DisplayCurrency) => void>()
+
+function setCurrent(currency: DisplayCurrency) {
+  current = currency
+  listeners.forEach((listener) => listener(currency))
+}
+
+/** Re-reads the display currency, e.g. after preferences or rates change. */
+export function reloadDisplayCurrency(): Promise<void> {
+  loading = invoke<DisplayCurrency>('get_display_currency')
+    .then(setCurrent)
+    .catch(() => setCurrent(USD))
+  return loading
+}
+
+/** `$1.23`, or `$1.23 (€1.13)` when a non-USD display currency is set. */
+export function formatCost(usd: number, currency: DisplayCurrency = current, digits = 2): string {
+  const dollars = `$${usd.toFixed(digits)}`
+  if (currency.code === 'USD') return dollars
+  try {
+    const converted = new Intl.NumberFormat(undefined, {
+      style: 'currency',
+      currency: currency.code,
+      minimumFractionDigits: digits,
+      maximumFractionDigits: digits,
+    }).format(usd * currency.rate)
+    return `${dollars} (${converted})`
+  } catch {
+    return `${dollars} (${(usd * currency.rate).toFixed(digits)} ${currency.code})`
+  }
+}
+
+/** The currency costs are displayed in alongside USD. */
+export function useDisplayCurrency(): DisplayCurrency {
+  const [currency, setCurrency] = useState<DisplayCurrency>(current)
+
+  useEffect(() => {
+    listeners.add(setCurrency)
+    if (!loading) reloadDisplayCurrency()
+    return () => {
+      listeners.delete(setCurrency)
+    }
+  }, [])
+
+  return currency
+}
@@ -0,0 +1,100 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+interface FileTail {
+  tailId: string
+  path: string
+  offset: number
+}
+
+interface TailLinesEvent {
+  tailId: string
+  seq: number
+  lines: string[]
+  reset: boolean
+}
+
+interface TailEndEvent {
+  tailId: string
+  reason: 'eof' | 'stopped' | 'error'
+  error?: string
+}
+
+/** Lines kept in memory; older ones are dropped. */
+const MAX_LINES = 5000
+
+/**
+ * Live view of a file's last lines. With `follow`, lines appended to the
+ * file keep arriving until the component unmounts or `path` changes.
+ */
+export function useFileTail(path: string | null, follow = true) {
+  const [lines, setLines] = useState<string[]>([])
+  const [active, setActive] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    if (!path) return
+    let cancelled = false
+    let tailId: string | null = null
+    // Batches can arrive before tail_file returns the ID they belong to
+    const early: TailLinesEvent[] = []
+    const earlyEnds: TailEndEvent[] = []
+
+    setLines([])
+    setError(null)
+
+    const handleLines = (event: TailLinesEvent) => {
+      setLines((prev) => {
+        const next = event.reset ? event.lines : [...prev, ...event.lines]
+        return next.length > MAX_LINES ? next.slice(next.length - MAX_LINES) : next
+      })
+      invoke('ack_tail_file', { tailId: event.tailId, seq: event.seq }).catch(() => {})
+    }
+
+    const unlistenLines = listen<TailLinesEvent>('tail-file-lines', (event) => {
+      if (tailId === null) {
+        early.push(event.payload)
+      } else if (event.payload.tailId === tailId) {
+        handleLines(event.payload)
+      }
+    })
+    const handleEnd = (event: TailEndEvent) => {
+      setActive(false)
+      if (event.error) setError(event.error)
+    }
+    const unlistenEnd = listen<TailEndEvent>('tail-file-end', (event) => {
+      if (tailId === null) {
+        earlyEnds.push(event.payload)
+      } else if (event.payload.tailId === tailId) {
+        handleEnd(event.payload)
+      }
+    })
+
+    Promise.all([unlistenLines, unlistenEnd])
+      .then(() => invoke<FileTail>('tail_file', { path, follow }))
+      .then((tail) => {
+        if (cancelled) {
+          invoke('stop_tail_file', { tailId: tail.tailId }).catch(() => {})
+          return
+        }
+        tailId = tail.tailId
+        setActive(true)
+        early.filter((e) => e.tailId === tail.tailId).forEach(handleLines)
+        early.length = 0
+        earlyEnds.filter((e) => e.tailId === tail.tailId).forEach(handleEnd)
+      })
+      .catch((e) => {
+        if (!cancelled) setError(String(e))
+      })
+
+    return () => {
+      cancelled = true
+      if (tailId) invoke('stop_tail_file', { tailId }).catch(() => {})
+      unlistenLines.then((fn) => fn())
+      unlistenEnd.then((fn) => fn())
+    }
+  }, [path, follow])
+
+  return { lines, active, error }
+}
@@ -1,14 +1,11 @@
 import { useCallback, useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import { listen } from '@tauri-apps/api/event'
 import { homeDir } from '@tauri-apps/api/path'
 import { defaultPlugins, type AgentPlugin } from '../types'
 import { usePromptStore } from '../stores/promptStore'
 import { useProcessStore } from '../stores/processStore'
-
-interface SpawnAgentResult {
-  processId: string
-}
+import { spawnAgent } from '../utils/spawnAgent'
+import { listenAgentOutput } from '../utils/agentOutput'
 
 interface WaitAgentResult {
   processId: string
@@ -16,12 +13,6 @@ interface WaitAgentResult {
   success: boolean
 }
 
-interface AgentOutputPayload {
-  processId: string
-  streamType: 'stdout' | 'stderr'
-  content: string
-}
-
 type GenerationType = 'generate' | 'shorten' | 'lengthen' | 'simplify'
 
 const PROMPT_IDS: Record<GenerationType, string> = {
@@ -168,7 +159,7 @@ function cleanText(text: string): string {
 export function useIdeaGeneration() {
   const [isGenerating, setIsGenerating] = useState(false)
   const [generationType, setGenerationType] = useState<GenerationType | null>(null)
-  const getPrompt = usePromptStore((state) => state.getPrompt)
+  const renderPrompt = usePromptStore((state) => state.renderPrompt)
   const registerProcess = useProcessStore((state) => state.registerProcess)
   const unregisterProcess = useProcessStore((state) => state.unregisterProcess)
 
@@ -201,10 +192,10 @@ export function useIdeaGeneration() {
       }
 
       const promptId = PROMPT_IDS[type] as keyof typeof import('../utils/prompts').DEFAULT_PROMPTS
-      const prompt = getPrompt(promptId, {
-        '{{title}}': title,
-        '{{summary}}': summary,
-        '{{description}}': currentDescription,
+      const prompt = await renderPrompt(promptId, {
+        title,
+        summary,
+        description: currentDescription,
       })
 
       const args = plugin.argsTemplate.map((arg: string) =>
@@ -213,10 +204,10 @@ export function useIdeaGeneration() {
 
       const workingDirectory = await homeDir()
 
-      const spawnResult = await invoke<SpawnAgentResult>('spawn_agent', {
+      const spawnResult = await spawnAgent({
         executable: plugin.command,
         args,
-        workingDirectory
+        workingDirectory,
       })
 
       // Register process so it appears in Process Viewer and can be stopped
@@ -235,11 +226,11 @@ export function useIdeaGeneration() {
 
       const lines: string[] = []
       
-      const unlistenOutput = await listen<AgentOutputPayload>('agent-output', (event) => {
-        if (event.payload.processId === spawnResult.processId) {
+      const unlistenOutput = await listenAgentOutput((output) => {
+        if (output.processId === spawnResult.processId) {
           // Each event contains a line without its newline (stripped by BufReader.lines())
           // Collect lines separately to preserve structure
-          lines.push(event.payload.content)
+          lines.push(output.content)
         }
       })
 
@@ -266,7 +257,7 @@ export function useIdeaGeneration() {
       setIsGenerating(false)
       setGenerationType(null)
     }
-  }, [getPrompt, registerProcess, unregisterProcess])
+  }, [renderPrompt, registerProcess, unregisterProcess])
 
   return {
     generateDescription,
@@ -0,0 +1,86 @@
+import { useEffect } from 'react'
+import { listen } from '@tauri-apps/api/event'
+import { useBuildStore } from '../stores/buildStore'
+import { usePrdStore } from '../stores/prdStore'
+import { useCostStore } from '../stores/costStore'
+import { useProjectStore } from '../stores/projectStore'
+import { notify } from '../utils/notify'
+import type { LanSyncConflict } from '../components/LanSyncSection'
+
+interface LanSyncAppliedPayload {
+  projectId: string
+  /** Relative to .ideate */
+  path: string
+  peerName: string
+}
+
+interface LanSyncEventPayload {
+  projectId: string
+  peerName: string
+  event: string
+  payload: Record<string, unknown>
+}
+
+/**
+ * Follows what LAN sync brings in from other computers: reloads documents
+ * they changed, surfaces conflicts, and tracks builds running there.
+ */
+export function useLanSync() {
+  useEffect(() => {
+    const unlisteners = [
+      listen<LanSyncAppliedPayload>('lan-sync-applied', ({ payload }) => {
+        const { projectId, path } = payload
+        if (path === 'prd.json') {
+          // Dropping the in-memory copy makes it load again from disk
+          usePrdStore.getState().clearPrd(projectId)
+        } else if (path === 'costs.json') {
+          const project = useProjectStore.getState().projects.find((p) => p.id === projectId)
+          if (project) {
+            useCostStore.getState().reloadProjectCostHistory(projectId, project.path)
+          }
+        }
+      }),
+
+      listen<LanSyncConflict>('lan-sync-conflict', ({ payload }) => {
+        notify.warning(
+          `Sync conflict in ${payload.projectName}`,
+          `.ideate/${payload.path} changed here and on ${payload.peerName}. Kept theirs; yours is in ${payload.savedCopy}.`
+        )
+      }),
+
+      listen<LanSyncEventPayload>('lan-sync-event', ({ payload }) => {
+        const { projectId, peerName, event, payload: data } = payload
+        const build = useBuildStore.getState()
+        switch (event) {
+          case 'build-status':
+            if (data.status === 'running' || data.status === 'paused') {
+              build.updateRemoteBuild(projectId, {
+                peerName,
+                status: data.status,
+                currentStoryId: (data.currentStoryId as string | null) ?? null,
+                currentStoryTitle: (data.currentStoryTitle as string | null) ?? null,
+              })
+            } else {
+              build.updateRemoteBuild(projectId, null)
+            }
+            break
+          case 'build-log':
+            build.updateRemoteBuild(projectId, { peerName, lastLog: String(data.content ?? '') })
+            break
+          case 'build-story-status':
+            if (data.status === 'complete' && typeof data.storyId === 'string') {
+              usePrdStore.getState().updateStory(projectId, data.storyId, { passes: true })
+            }
+            break
+          case 'build-finished':
+            build.updateRemoteBuild(projectId, null)
+            break
+        }
+      }),
+    ]
+
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()))
+    }
+  }, [])
+}
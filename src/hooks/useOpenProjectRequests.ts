@@ -0,0 +1,67 @@
+import { useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useProjectStore } from '../stores/projectStore'
+import { useAgentStore } from '../stores/agentStore'
+import { notify } from '../utils/notify'
+
+interface OpenProjectRequest {
+  path: string
+  name: string
+}
+
+const normalizePath = (path: string) => path.replace(/[\\/]+$/, '')
+
+async function openProject(request: OpenProjectRequest) {
+  const store = useProjectStore.getState()
+  const existing = store.projects.find((p) => normalizePath(p.path) === normalizePath(request.path))
+  if (existing) {
+    store.setActiveProject(existing.id)
+    return
+  }
+
+  try {
+    const result = await invoke<{ path: string }>('import_project', {
+      name: request.name,
+      projectPath: request.path,
+    })
+    const project = store.addProject({
+      name: request.name,
+      description: 'Imported existing project',
+      path: result.path,
+      status: 'idle',
+    })
+    store.setActiveProject(project.id)
+    useAgentStore.getState().initSession(project.id)
+    notify.success('Project opened', `${request.name} is ready to go`)
+  } catch (error) {
+    console.error('Failed to open project:', error)
+    notify.error('Failed to open project', String(error))
+  }
+}
+
+/**
+ * Opens projects the OS asked the app to open (file associations, "Open
+ * With"). Requests queue in the backend until projects have loaded.
+ */
+export function useOpenProjectRequests(enabled: boolean) {
+  useEffect(() => {
+    if (!enabled) return
+
+    const drain = async () => {
+      const requests = await invoke<OpenProjectRequest[]>('take_pending_open_projects').catch(() => [])
+      for (const request of requests) {
+        await openProject(request)
+      }
+    }
+
+    drain()
+    const unlisten = listen<OpenProjectRequest>('open-project-requested', () => {
+      drain()
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [enabled])
+}
@@ -9,6 +9,7 @@ import { useAgentStore } from "../stores/agentStore";
 import { defaultPlugins, type AgentPlugin } from "../types";
 import { useProjectStore } from "../stores/projectStore";
 import { notify } from "../utils/notify";
+import { spawnAgent } from "../utils/spawnAgent";
 
 type LogType = "stdout" | "stderr" | "system";
 
@@ -41,10 +42,6 @@ function safeSetPrd(
   return true;
 }
 
-interface SpawnAgentResult {
-  processId: string;
-}
-
 interface WaitAgentResult {
   processId: string;
   exitCode: number | null;
@@ -130,10 +127,12 @@ export function usePrdGeneration() {
 
         const startTime = Date.now();
 
-        const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+        const spawnResult = await spawnAgent({
           executable: plugin.command,
           args,
           workingDirectory: projectPath,
+          agentId: selectedAgentId,
+          costDescription: "PRD Generation",
         });
 
         setCurrentProcessId(activeProjectId, spawnResult.processId);
@@ -312,10 +311,12 @@ export function usePrdGeneration() {
 
         const startTime = Date.now();
 
-        const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+        const spawnResult = await spawnAgent({
           executable: plugin.command,
           args,
           workingDirectory: projectPath,
+          agentId: selectedAgentId,
+          costDescription: "Codebase Analysis",
         });
 
         setCurrentProcessId(projectId, spawnResult.processId);
@@ -504,10 +505,12 @@ export function usePrdGeneration() {
 
         const startTime = Date.now();
 
-        const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+        const spawnResult = await spawnAgent({
           executable: plugin.command,
           args,
           workingDirectory: projectPath,
+          agentId: selectedAgentId,
+          costDescription: "Story Generation",
         });
 
         setCurrentProcessId(projectId, spawnResult.processId);
@@ -680,10 +683,12 @@ export function usePrdGeneration() {
 
         const startTime = Date.now();
 
-        const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+        const spawnResult = await spawnAgent({
           executable: plugin.command,
           args,
           workingDirectory: projectPath,
+          agentId: selectedAgentId,
+          costDescription: "Story Breakdown",
         });
 
         setCurrentProcessId(projectId, spawnResult.processId);
@@ -913,10 +918,12 @@ export function usePrdGeneration() {
 
         const startTime = Date.now();
 
-        const spawnResult = await invoke<SpawnAgentResult>("spawn_agent", {
+        const spawnResult = await spawnAgent({
           executable: plugin.command,
           args,
           workingDirectory: projectPath,
+          agentId: selectedAgentId,
+          costDescription: "PRD from Idea",
         });
 
         setCurrentProcessId(projectId, spawnResult.processId);
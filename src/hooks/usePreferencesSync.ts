@@ -0,0 +1,61 @@
+import { useEffect } from 'react'
+import { listen } from '@tauri-apps/api/event'
+import { useAgentStore } from '../stores/agentStore'
+import { useIntegrationsStore } from '../stores/integrationsStore'
+import { usePromptStore } from '../stores/promptStore'
+import { useThemeStore } from '../stores/themeStore'
+import { notify } from '../utils/notify'
+
+interface PreferenceChange {
+  key: string
+  oldValue: unknown
+  newValue: unknown
+}
+
+interface PreferencesChangedPayload {
+  /** 'file' when preferences.json was edited outside the app */
+  source: 'app' | 'file'
+  changes: PreferenceChange[]
+  preferences: { defaultAgent?: string | null }
+}
+
+interface PreferencesInvalidPayload {
+  error: string
+}
+
+/**
+ * Reloads the stores that cache preferences when they change, whether in
+ * another window or by an edit to preferences.json. Only the window passing
+ * `notifyErrors` reports edits that couldn't be applied.
+ */
+export function usePreferencesSync(notifyErrors: boolean) {
+  useEffect(() => {
+    const unlisteners = [
+      listen<PreferencesChangedPayload>('preferences-changed', ({ payload }) => {
+        const changed = new Set(payload.changes.map((change) => change.key))
+        if (changed.has('themeId') || changed.has('colorMode') || changed.has('theme')) {
+          useThemeStore.getState().loadTheme()
+        }
+        if (changed.has('defaultAgent') && payload.preferences.defaultAgent) {
+          useAgentStore.getState().setDefaultAgentId(payload.preferences.defaultAgent)
+        }
+        if (changed.has('promptOverrides')) {
+          usePromptStore.getState().loadOverrides()
+        }
+        if (changed.has('outray')) {
+          useIntegrationsStore.getState().loadConfig()
+        }
+      }),
+
+      listen<PreferencesInvalidPayload>('preferences-invalid', ({ payload }) => {
+        if (notifyErrors) {
+          notify.error('Edited preferences were not applied', payload.error)
+        }
+      }),
+    ]
+
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()))
+    }
+  }, [notifyErrors])
+}
@@ -4,6 +4,7 @@ import { useBuildStore, type StoryBuildStatus } from '../stores/buildStore'
 import { useProjectStore } from '../stores/projectStore'
 
 export interface ProjectState {
+  schemaVersion?: number
   currentStoryId: string | null
   storyStatuses: Record<string, string>
   storyRetries: Record<string, { retryCount: number }>
@@ -2,194 +2,174 @@ import { useCallback, useEffect, useRef, useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { useIntegrationsStore } from '../stores/integrationsStore'
-import { useProcessStore } from '../stores/processStore'
 import { notify } from '../utils/notify'
 
-interface SpawnAgentResult {
-  processId: string
+/** Matches `allowlist::NOT_ALLOWED_PREFIX` in the backend. */
+const NOT_ALLOWED_PREFIX = 'Executable not allowed:'
+
+interface TunnelInfo {
+  tunnelId: string
+  provider: string
+  projectId: string
+  label: string
+  status: 'starting' | 'running'
+  port: number
+  subdomain: string | null
+  url: string | null
+  startedAt: string
 }
 
-interface AgentOutputEvent {
-  processId: string
-  streamType: string
-  content: string
+interface TunnelReadyEvent {
+  tunnelId: string
+  projectId: string
+  port: number
+  url: string
 }
 
-interface AgentExitEvent {
-  processId: string
+interface TunnelClosedEvent {
+  tunnelId: string
+  projectId: string
   exitCode: number | null
   success: boolean
 }
 
 export type TunnelStatus = 'idle' | 'starting' | 'running' | 'stopping' | 'stopped' | 'error'
 
+/** The project's OutRay tunnel to its dev server, run by the backend tunnel manager. */
 export function useTunnel(projectId: string, localPort: number | null) {
   const [status, setStatus] = useState<TunnelStatus>('idle')
   const [publicUrl, setPublicUrl] = useState<string | null>(null)
   const [error, setError] = useState<string | null>(null)
-  
-  const processIdRef = useRef<string | null>(null)
+
+  const tunnelIdRef = useRef<string | null>(null)
   const statusRef = useRef<TunnelStatus>('idle')
-  const unlistenOutputRef = useRef<UnlistenFn | null>(null)
-  const unlistenExitRef = useRef<UnlistenFn | null>(null)
-  
+  const unlistenReadyRef = useRef<UnlistenFn | null>(null)
+  const unlistenClosedRef = useRef<UnlistenFn | null>(null)
+
   const outrayConfig = useIntegrationsStore((state) => state.outray)
-  const registerProcess = useProcessStore((state) => state.registerProcess)
-  const updateProcessUrl = useProcessStore((state) => state.updateProcessUrl)
-  const unregisterProcess = useProcessStore((state) => state.unregisterProcess)
-  
-  // Check for existing tunnel on mount
+
+  // Pick up a tunnel the backend is already running for this port
   useEffect(() => {
-    const existingTunnels = useProcessStore.getState().getProcessesByType('tunnel')
-    const tunnelForProject = existingTunnels.find((p) => p.projectId === projectId)
-    if (tunnelForProject) {
-      processIdRef.current = tunnelForProject.processId
-      statusRef.current = 'running'
-      setStatus('running')
-      // Restore the tunnel URL from processStore
-      if (tunnelForProject.url) {
-        setPublicUrl(tunnelForProject.url)
-      }
+    if (!localPort) return
+    let cancelled = false
+    invoke<TunnelInfo[]>('list_project_tunnels', { projectId })
+      .then((tunnels) => {
+        const existing = tunnels.find((t) => t.port === localPort)
+        if (cancelled || !existing) return
+        tunnelIdRef.current = existing.tunnelId
+        statusRef.current = existing.status
+        setStatus(existing.status)
+        setPublicUrl(existing.url)
+      })
+      .catch((e) => console.error('Failed to list tunnels:', e))
+    return () => {
+      cancelled = true
     }
-  }, [projectId])
-  
-  // Set up event listeners for tunnel output
+  }, [projectId, localPort])
+
   useEffect(() => {
     let mounted = true
-    
+
     const setupListeners = async () => {
-      unlistenOutputRef.current = await listen<AgentOutputEvent>('agent-output', (event) => {
+      unlistenReadyRef.current = await listen<TunnelReadyEvent>('tunnel-ready', (event) => {
         if (!mounted) return
-        const { processId, content } = event.payload
-        
-        if (processId !== processIdRef.current) return
-        
-        // Parse OutRay output to extract public URL
-        // OutRay typically outputs something like: "Tunnel is live at https://xxx.outray.app"
-        console.log('[useTunnel] OutRay output:', content)
-        const urlMatch = content.match(/https?:\/\/[^\s]+\.outray\.app[^\s]*/i)
-        if (urlMatch && statusRef.current === 'starting') {
-          const detectedUrl = urlMatch[0]
-          setPublicUrl(detectedUrl)
-          statusRef.current = 'running'
-          setStatus('running')
-          // Store URL in processStore for persistence across remounts
-          if (processIdRef.current) {
-            updateProcessUrl(processIdRef.current, detectedUrl)
-          }
-          notify.success('Tunnel active', detectedUrl)
-        }
+        const { tunnelId, url } = event.payload
+        if (tunnelId !== tunnelIdRef.current) return
+
+        setPublicUrl(url)
+        statusRef.current = 'running'
+        setStatus('running')
+        notify.success('Tunnel active', url)
       })
-      
-      unlistenExitRef.current = await listen<AgentExitEvent>('agent-exit', (event) => {
+
+      unlistenClosedRef.current = await listen<TunnelClosedEvent>('tunnel-closed', (event) => {
         if (!mounted) return
-        const { processId, exitCode, success } = event.payload
-        
-        if (processId !== processIdRef.current) return
-        
+        const { tunnelId, exitCode, success } = event.payload
+        if (tunnelId !== tunnelIdRef.current) return
+
         const wasUserStopped = statusRef.current === 'stopping'
-        
-        unregisterProcess(processId, exitCode, success)
-        processIdRef.current = null
+
+        tunnelIdRef.current = null
         statusRef.current = 'stopped'
         setStatus('stopped')
         setPublicUrl(null)
-        
+
         if (!success && !wasUserStopped) {
           notify.error('Tunnel closed', `Exit code: ${exitCode}`)
         }
       })
     }
-    
+
     setupListeners()
-    
+
     return () => {
       mounted = false
-      unlistenOutputRef.current?.()
-      unlistenExitRef.current?.()
+      unlistenReadyRef.current?.()
+      unlistenClosedRef.current?.()
     }
-  }, [unregisterProcess, updateProcessUrl])
-  
+  }, [])
+
   const startTunnel = useCallback(async (subdomain?: string) => {
     if (!localPort) {
       setError('No local port available')
       return
     }
-    
-    if (processIdRef.current) return
+
+    if (tunnelIdRef.current) return
     if (statusRef.current === 'starting' || statusRef.current === 'running') return
-    
-    const tunnelSubdomain = subdomain || outrayConfig.defaultSubdomain
-    
+
     statusRef.current = 'starting'
     setStatus('starting')
     setError(null)
     setPublicUrl(null)
-    
+
+    const customCliPath = outrayConfig.useCustomPath ? outrayConfig.cliPath ?? undefined : undefined
+    const request = {
+      projectId,
+      port: localPort,
+      provider: 'outray',
+      subdomain: subdomain || outrayConfig.defaultSubdomain || undefined,
+      label: `Tunnel (port ${localPort})`,
+      customCliPath,
+    }
+
     try {
-      // Determine executable based on whether using custom path or bundled sidecar
-      let executable: string
-      let needsAuthToken = false
-      
-      if (outrayConfig.useCustomPath && outrayConfig.cliPath) {
-        // Use custom path directly
-        executable = outrayConfig.cliPath
-      } else {
-        // Get the best available outray executable
-        const execInfo = await invoke<{ path: string; needsAuthToken: boolean }>('get_sidecar_path')
-        executable = execInfo.path
-        needsAuthToken = execInfo.needsAuthToken
-      }
-      
-      // Build args - if using npx, we need to add 'outray' as the first arg
-      const args: string[] = []
-      if (executable === 'npx') {
-        args.push('outray')
-      }
-      
-      args.push(String(localPort))
-      
-      // Only pass --key if using bundled binary (which has os.homedir issues)
-      if (needsAuthToken) {
-        const authToken = await invoke<string | null>('get_auth_token')
-        if (authToken) {
-          args.push('--key', authToken)
-        }
+      let tunnel: TunnelInfo
+      try {
+        tunnel = await invoke<TunnelInfo>('start_tunnel', request)
+      } catch (e) {
+        if (!customCliPath || !String(e).startsWith(NOT_ALLOWED_PREFIX)) throw e
+        // The backend asks the user in a native dialog
+        const approved = await invoke<boolean>('approve_executable', { executable: customCliPath })
+        if (!approved) throw e
+        tunnel = await invoke<TunnelInfo>('start_tunnel', request)
       }
-      
-      if (tunnelSubdomain) {
-        args.push('--subdomain', tunnelSubdomain)
+      tunnelIdRef.current = tunnel.tunnelId
+
+      // The URL may have been parsed before the ready listener saw this tunnel's ID
+      if (tunnel.url && statusRef.current === 'starting') {
+        setPublicUrl(tunnel.url)
+        statusRef.current = 'running'
+        setStatus('running')
+        notify.success('Tunnel active', tunnel.url)
       }
-      
-      const result = await invoke<SpawnAgentResult>('spawn_agent', {
-        executable,
-        args,
-        workingDirectory: '.',
-      })
-      
-      processIdRef.current = result.processId
-      
-      registerProcess({
-        processId: result.processId,
-        projectId,
-        type: 'tunnel',
-        label: `Tunnel (port ${localPort})`,
-        command: {
-          executable,
-          args,
-          workingDirectory: '.',
-        },
-      })
-      
+
       // Timeout fallback - if we don't get a URL in 30 seconds, consider it failed
-      setTimeout(() => {
-        if (statusRef.current === 'starting') {
-          setError('Tunnel failed to start. Check OutRay authentication.')
-          statusRef.current = 'error'
-          setStatus('error')
+      const tunnelId = tunnel.tunnelId
+      setTimeout(async () => {
+        if (statusRef.current !== 'starting' || tunnelIdRef.current !== tunnelId) return
+        const current = await invoke<TunnelInfo | null>('get_tunnel_status', { tunnelId }).catch(() => null)
+        if (current?.url) {
+          setPublicUrl(current.url)
+          statusRef.current = 'running'
+          setStatus('running')
+          return
         }
+        setError('Tunnel failed to start. Check OutRay authentication.')
+        statusRef.current = 'error'
+        setStatus('error')
       }, 30000)
-      
+
     } catch (e) {
       const errorMessage = String(e)
       setError(errorMessage)
@@ -197,18 +177,18 @@ export function useTunnel(projectId: string, localPort: number | null) {
       setStatus('error')
       notify.error('Tunnel failed', errorMessage)
     }
-  }, [localPort, projectId, outrayConfig, registerProcess])
-  
+  }, [localPort, projectId, outrayConfig])
+
   const stopTunnel = useCallback(async () => {
-    if (!processIdRef.current) return
-    
+    const tunnelId = tunnelIdRef.current
+    if (!tunnelId) return
+
     statusRef.current = 'stopping'
     setStatus('stopping')
-    
+
     try {
-      await invoke('kill_agent', { processId: processIdRef.current })
-      unregisterProcess(processIdRef.current, null, true)
-      processIdRef.current = null
+      await invoke('stop_tunnel', { tunnelId })
+      tunnelIdRef.current = null
       statusRef.current = 'stopped'
       setStatus('stopped')
       setPublicUrl(null)
@@ -217,8 +197,8 @@ export function useTunnel(projectId: string, localPort: number | null) {
       statusRef.current = 'error'
       setStatus('error')
     }
-  }, [unregisterProcess])
-  
+  }, [])
+
   const toggleTunnel = useCallback(async () => {
     if (statusRef.current === 'running' || statusRef.current === 'starting') {
       await stopTunnel()
@@ -226,7 +206,7 @@ export function useTunnel(projectId: string, localPort: number | null) {
       await startTunnel()
     }
   }, [startTunnel, stopTunnel])
-  
+
   const copyUrl = useCallback(async () => {
     if (publicUrl) {
       try {
@@ -237,7 +217,7 @@ export function useTunnel(projectId: string, localPort: number | null) {
       }
     }
   }, [publicUrl])
-  
+
   return {
     status,
     publicUrl,
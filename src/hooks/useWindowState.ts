@@ -1,20 +1,12 @@
 import { useEffect, useRef } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import { getCurrentWindow, LogicalSize, LogicalPosition } from '@tauri-apps/api/window'
-
-interface WindowState {
-  width: number
-  height: number
-  x?: number
-  y?: number
-  maximized: boolean
-}
-
-interface UiState {
-  panelStates: Record<string, unknown>
-  windowState?: WindowState
-}
+import { getCurrentWindow } from '@tauri-apps/api/window'
 
+/**
+ * Restores this window's size and position on mount and saves them as it
+ * moves. The backend records which display the window is on, so it comes
+ * back on that display, or on the primary one if it's been disconnected.
+ */
 export function useWindowState() {
   const saveTimeoutRef = useRef<number | null>(null)
   const isRestoredRef = useRef(false)
@@ -28,25 +20,7 @@ export function useWindowState() {
       isRestoredRef.current = true
 
       try {
-        const uiState = await invoke<UiState>('load_ui_state')
-        const windowState = uiState.windowState
-
-        if (windowState) {
-          // Set size first
-          if (windowState.width > 0 && windowState.height > 0) {
-            await appWindow.setSize(new LogicalSize(windowState.width, windowState.height))
-          }
-
-          // Set position if available
-          if (windowState.x !== undefined && windowState.y !== undefined) {
-            await appWindow.setPosition(new LogicalPosition(windowState.x, windowState.y))
-          }
-
-          // Maximize if was maximized
-          if (windowState.maximized) {
-            await appWindow.maximize()
-          }
-        }
+        await invoke('restore_current_window_state')
       } catch (e) {
         console.error('Failed to restore window state:', e)
       }
@@ -57,19 +31,7 @@ export function useWindowState() {
     // Save window state on changes (debounced)
     const saveWindowState = async () => {
       try {
-        const size = await appWindow.innerSize()
-        const position = await appWindow.outerPosition()
-        const maximized = await appWindow.isMaximized()
-
-        const windowState: WindowState = {
-          width: size.width,
-          height: size.height,
-          x: position.x,
-          y: position.y,
-          maximized,
-        }
-
-        await invoke('save_window_state', { windowState })
+        await invoke('save_current_window_state')
       } catch (e) {
         console.error('Failed to save window state:', e)
       }
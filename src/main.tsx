@@ -3,6 +3,7 @@ import { createRoot } from "react-dom/client";
 import App from "./App";
 import { ProcessViewerWindow } from "./windows/ProcessViewerWindow";
 import { StoryManagerWindow } from "./windows/StoryManagerWindow";
+import { LogViewerWindow } from "./windows/LogViewerWindow";
 import "./index.css";
 
 // Import prdStore early to ensure event listeners are registered for cross-window communication
@@ -23,6 +24,10 @@ function Root() {
     return <StoryManagerWindow />;
   }
   
+  if (path === "/log-viewer") {
+    return <LogViewerWindow />;
+  }
+  
   return <App />;
 }
 
@@ -1,4 +1,5 @@
 import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
 
 export type BuildStatus = 'idle' | 'running' | 'paused'
 
@@ -34,6 +35,75 @@ export interface SnapshotInfo {
   snapshotType: 'stash' | 'commit'
 }
 
+export interface BudgetWarning {
+  buildId: string
+  scope: 'story' | 'build'
+  storyId: string | null
+  thresholdPercent: number
+  used: number
+  limit: number
+  buildTokens: number
+  buildCost: number
+}
+
+/** Projected cost and completion time of a running build */
+export interface BuildForecast {
+  buildId: string | null
+  completedStories: number
+  remainingStories: number
+  spentCost: number
+  elapsedMs: number
+  remainingCost: number | null
+  totalCost: number | null
+  remainingMs: number | null
+  estimatedCompletionAt: string | null
+  basis: 'history' | 'estimates'
+  sampleBuilds: number
+}
+
+export interface SwarmTask {
+  id: string
+  title: string
+  description: string
+  files: string[]
+  status: 'pending' | 'running' | 'done' | 'failed'
+  branchName: string | null
+  error: string | null
+}
+
+export interface SwarmReview {
+  approved: boolean
+  summary: string
+  issues: string[]
+}
+
+/** The story a swarm-mode build is working on, split between agents */
+export interface BuildSwarm {
+  storyId: string
+  storyTitle: string
+  phase: 'planning' | 'working' | 'merging' | 'reviewing' | 'verifying' | 'done' | 'failed'
+  tasks: SwarmTask[]
+  review: SwarmReview | null
+  message: string | null
+}
+
+/** A build of this project running on another computer, followed over LAN sync */
+export interface RemoteBuild {
+  peerName: string
+  status: 'running' | 'paused'
+  currentStoryId: string | null
+  currentStoryTitle: string | null
+  lastLog: string | null
+  updatedAt: string
+}
+
+/** Progress read from an agent's output by its progress rules */
+export interface AgentProgress {
+  step: string | null
+  percent: number | null
+  file: string | null
+}
+
 export interface ProjectBuildState {
   status: BuildStatus
   currentStoryId: string | null
@@ -45,6 +115,11 @@ export interface ProjectBuildState {
   logs: LogEntry[]
   lastExitInfo: ProcessExitInfo | null
   conflictedBranches: ConflictInfo[]
+  budgetWarning: BudgetWarning | null
+  forecast: BuildForecast | null
+  swarm: BuildSwarm | null
+  remoteBuild: RemoteBuild | null
+  storyProgress: Record<string, AgentProgress>
 }
 
 const createEmptyProjectState = (): ProjectBuildState => ({
@@ -58,6 +133,11 @@ const createEmptyProjectState = (): ProjectBuildState => ({
   logs: [],
   lastExitInfo: null,
   conflictedBranches: [],
+  budgetWarning: null,
+  forecast: null,
+  swarm: null,
+  remoteBuild: null,
+  storyProgress: {},
 })
 
 interface BuildStore {
@@ -78,6 +158,9 @@ interface BuildStore {
   pauseBuild: (projectId: string) => void
   resumeBuild: (projectId: string) => void
   cancelBuild: (projectId: string) => void
+  // Ask the backend orchestrator to pause at the next story boundary / resume
+  requestPause: (projectId: string) => Promise<void>
+  requestResume: (projectId: string) => Promise<void>
   setCurrentStory: (projectId: string, storyId: string | null, storyTitle?: string | null) => void
   setCurrentProcessId: (projectId: string, processId: string | null) => void
   setStoryStatus: (projectId: string, storyId: string, status: StoryBuildStatus) => void
@@ -92,6 +175,11 @@ interface BuildStore {
   addConflictedBranch: (projectId: string, conflict: ConflictInfo) => void
   removeConflictedBranch: (projectId: string, branchName: string) => void
   clearConflictedBranches: (projectId: string) => void
+  setBudgetWarning: (projectId: string, warning: BudgetWarning | null) => void
+  setForecast: (projectId: string, forecast: BuildForecast | null) => void
+  setSwarm: (projectId: string, swarm: BuildSwarm | null) => void
+  updateRemoteBuild: (projectId: string, update: Partial<RemoteBuild> | null) => void
+  setStoryProgress: (projectId: string, storyId: string, progress: AgentProgress | null) => void
   
   // Snapshot management
   setStorySnapshot: (projectId: string, storyId: string, snapshot: SnapshotInfo) => void
@@ -166,6 +254,22 @@ export const useBuildStore = create<BuildStore>((set, get) => ({
     }))
   },
 
+  requestPause: async (projectId) => {
+    try {
+      await invoke('pause_build', { projectId })
+    } catch (error) {
+      get().appendLog(projectId, 'system', `Failed to pause build: ${error}`)
+    }
+  },
+
+  requestResume: async (projectId) => {
+    const resumed = await invoke<boolean>('resume_build', { projectId }).catch(() => false)
+    if (!resumed) {
+      // No paused backend build (e.g. after a restart); start a new one for the remaining stories
+      window.dispatchEvent(new CustomEvent('resume-build', { detail: { projectId } }))
+    }
+  },
+
   cancelBuild: (projectId) => {
     set((state) => ({
       projectStates: {
@@ -437,6 +541,82 @@ export const useBuildStore = create<BuildStore>((set, get) => ({
     }))
   },
 
+  setBudgetWarning: (projectId, warning) => {
+    set((state) => ({
+      projectStates: {
+        ...state.projectStates,
+        [projectId]: {
+          ...(state.projectStates[projectId] || createEmptyProjectState()),
+          budgetWarning: warning,
+        },
+      },
+    }))
+  },
+
+  setForecast: (projectId, forecast) => {
+    set((state) => ({
+      projectStates: {
+        ...state.projectStates,
+        [projectId]: {
+          ...(state.projectStates[projectId] || createEmptyProjectState()),
+          forecast,
+        },
+      },
+    }))
+  },
+
+  setSwarm: (projectId, swarm) => {
+    set((state) => ({
+      projectStates: {
+        ...state.projectStates,
+        [projectId]: {
+          ...(state.projectStates[projectId] || createEmptyProjectState()),
+          swarm,
+        },
+      },
+    }))
+  },
+
+  updateRemoteBuild: (projectId, update) => {
+    set((state) => {
+      const projectState = state.projectStates[projectId] || createEmptyProjectState()
+      const remoteBuild: RemoteBuild | null = update
+        ? {
+            peerName: '',
+            status: 'running',
+            currentStoryId: null,
+            currentStoryTitle: null,
+            lastLog: null,
+            ...projectState.remoteBuild,
+            ...update,
+            updatedAt: new Date().toISOString(),
+          }
+        : null
+      return {
+        projectStates: {
+          ...state.projectStates,
+          [projectId]: { ...projectState, remoteBuild },
+        },
+      }
+    })
+  },
+
+  setStoryProgress: (projectId, storyId, progress) => {
+    set((state) => {
+      const projectState = state.projectStates[projectId] || createEmptyProjectState()
+      const { [storyId]: _, ...rest } = projectState.storyProgress
+      return {
+        projectStates: {
+          ...state.projectStates,
+          [projectId]: {
+            ...projectState,
+            storyProgress: progress ? { ...rest, [storyId]: progress } : rest,
+          },
+        },
+      }
+    })
+  },
+
   setStorySnapshot: (projectId, storyId, snapshot) => {
     set((state) => {
       const projectState = state.projectStates[projectId] || createEmptyProjectState()
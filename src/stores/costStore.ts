@@ -18,7 +18,7 @@ export interface CostEntry {
   rawOutput?: string
 }
 
-interface PersistedCostEntry {
+export interface PersistedCostEntry {
   id: string
   projectId: string
   timestamp: string
@@ -97,16 +97,51 @@ export interface ClaudeUsageSummary {
   detectedTier: string | null
 }
 
+export interface AgentUsageTotals {
+  agentId: string
+  inputTokens: number
+  outputTokens: number
+  totalTokens: number
+  credits: number | null
+  durationMs: number
+  sessionCount: number
+}
+
+export interface UsageTimelineEntry {
+  agentId: string
+  sessionId: string
+  title: string | null
+  timestamp: string
+  model: string | null
+  totalTokens: number
+  credits: number | null
+  durationMs: number
+}
+
+export interface AllUsageSummary {
+  amp: AmpUsageSummary
+  claude: ClaudeUsageSummary
+  agents: AgentUsageTotals[]
+  timeline: UsageTimelineEntry[]
+}
+
 interface RecentThreadDuration {
   threadId: string | null
   durationMs: number
   stopReason: string | null
 }
 
+export interface RecomputeSummary {
+  updated: number
+  unchanged: number
+  skipped: number
+}
+
 interface CostStore {
   entries: CostEntry[]
   ampUsage: AmpUsageSummary | null
   claudeUsage: ClaudeUsageSummary | null
+  allUsage: AllUsageSummary | null
   isLoadingAmpUsage: boolean
   isLoadingClaudeUsage: boolean
   loadedProjects: Set<string>
@@ -118,14 +153,17 @@ interface CostStore {
   clearEntries: (projectId?: string, projectPath?: string) => void
   recordAgentRun: (projectId: string, projectPath: string, agentId: string, description: string, output?: string, durationMs?: number) => Promise<void>
   parseAndAddFromOutput: (projectId: string, projectPath: string, agentId: string, description: string, output: string, durationMs?: number) => void
+  addRecordedEntry: (entry: PersistedCostEntry) => void
   loadProjectCostHistory: (projectId: string, projectPath: string) => Promise<void>
   saveProjectCostHistory: (projectId: string, projectPath: string) => Promise<void>
+  reloadProjectCostHistory: (projectId: string, projectPath: string) => Promise<void>
+  recomputeProjectCosts: (projectId: string, projectPath: string) => Promise<RecomputeSummary>
   loadAmpUsage: (sinceTimestamp?: number) => Promise<AmpUsageSummary | null>
   refreshAmpUsage: () => Promise<AmpUsageSummary | null>
   loadClaudeUsage: (sinceTimestamp?: number) => Promise<ClaudeUsageSummary | null>
   refreshClaudeUsage: () => Promise<ClaudeUsageSummary | null>
-  loadAllAgentUsage: (sinceTimestamp?: number) => Promise<void>
-  refreshAllAgentUsage: () => Promise<void>
+  loadAllAgentUsage: (sinceTimestamp?: number) => Promise<AllUsageSummary | null>
+  refreshAllAgentUsage: () => Promise<AllUsageSummary | null>
 }
 
 // Regex patterns to extract cost/token info from various agent outputs
@@ -138,6 +176,9 @@ const COST_PATTERNS = {
   tokensUsed: /tokens?\s*used[:\s]+([\d,]+)/i,
 }
 
+/** A stream-json `result` message, whose usage the backend records itself */
+const STRUCTURED_RESULT = /^\{.*"type"\s*:\s*"result".*"usage"/m
+
 function parseNumber(str: string): number {
   return parseInt(str.replace(/,/g, ''), 10)
 }
@@ -227,6 +268,7 @@ export const useCostStore = create<CostStore>((set, get) => ({
   entries: [],
   ampUsage: null,
   claudeUsage: null,
+  allUsage: null,
   isLoadingAmpUsage: false,
   isLoadingClaudeUsage: false,
   loadedProjects: new Set<string>(),
@@ -296,6 +338,10 @@ export const useCostStore = create<CostStore>((set, get) => ({
   },
 
   recordAgentRun: async (projectId, projectPath, agentId, description, output, durationMs) => {
+    if (output && STRUCTURED_RESULT.test(output)) {
+      // Already recorded from the stream and announced with cost-recorded
+      return
+    }
     const costInfo = output ? extractCostInfo(output) : {}
     
     // If no manual duration provided, try to get it from agent thread/session files
@@ -335,6 +381,16 @@ export const useCostStore = create<CostStore>((set, get) => ({
     get().recordAgentRun(projectId, projectPath, agentId, description, output, durationMs)
   },
 
+  addRecordedEntry: (entry) => {
+    set((state) => {
+      // Entries of projects not loaded yet arrive with the rest of costs.json
+      if (!state.loadedProjects.has(entry.projectId) || state.entries.some((e) => e.id === entry.id)) {
+        return state
+      }
+      return { entries: [...state.entries, fromPersistedEntry(entry)] }
+    })
+  },
+
   loadProjectCostHistory: async (projectId, projectPath) => {
     const { loadedProjects } = get()
     if (loadedProjects.has(projectId)) {
@@ -377,6 +433,25 @@ export const useCostStore = create<CostStore>((set, get) => ({
     }
   },
 
+  reloadProjectCostHistory: async (projectId, projectPath) => {
+    // Drop the stale entries so the load reads the file again
+    set((state) => {
+      const loadedProjects = new Set(state.loadedProjects)
+      loadedProjects.delete(projectId)
+      return {
+        entries: state.entries.filter((e) => e.projectId !== projectId),
+        loadedProjects,
+      }
+    })
+    await get().loadProjectCostHistory(projectId, projectPath)
+  },
+
+  recomputeProjectCosts: async (projectId, projectPath) => {
+    const summary = await invoke<RecomputeSummary>('recompute_costs', { projectPath })
+    await get().reloadProjectCostHistory(projectId, projectPath)
+    return summary
+  },
+
   loadAmpUsage: async (sinceTimestamp?: number) => {
     set({ isLoadingAmpUsage: true })
     try {
@@ -438,16 +513,28 @@ export const useCostStore = create<CostStore>((set, get) => ({
   },
 
   loadAllAgentUsage: async (sinceTimestamp?: number) => {
-    await Promise.all([
-      get().loadAmpUsage(sinceTimestamp),
-      get().loadClaudeUsage(sinceTimestamp),
-    ])
+    set({ isLoadingAmpUsage: true, isLoadingClaudeUsage: true })
+    try {
+      const usage = await invoke<AllUsageSummary>('load_all_usage', {
+        sinceTimestamp: sinceTimestamp ?? null,
+      })
+      set({
+        allUsage: usage,
+        ampUsage: usage.amp,
+        claudeUsage: usage.claude,
+        isLoadingAmpUsage: false,
+        isLoadingClaudeUsage: false,
+      })
+      return usage
+    } catch (error) {
+      console.error('Failed to load agent usage:', error)
+      set({ isLoadingAmpUsage: false, isLoadingClaudeUsage: false })
+      return null
+    }
   },
 
   refreshAllAgentUsage: async () => {
-    await Promise.all([
-      get().refreshAmpUsage(),
-      get().refreshClaudeUsage(),
-    ])
+    set({ allUsage: null, ampUsage: null, claudeUsage: null })
+    return get().loadAllAgentUsage()
   },
 }))
@@ -1,5 +1,12 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+export interface DraftStory {
+  title: string
+  description: string
+  acceptanceCriteria: string[]
+}
 
 export interface Idea {
   id: string
@@ -8,12 +15,32 @@ export interface Idea {
   description: string
   createdAt: string
   updatedAt: string
+  draftStories?: DraftStory[]
+}
+
+export type IdeaJobStatus = 'queued' | 'running' | 'done' | 'failed' | 'canceled'
+
+export interface IdeaJob {
+  id: string
+  ideaId: string
+  ideaTitle: string
+  agentId: string
+  status: IdeaJobStatus
+  error: string | null
+  processId: string | null
+  queuedAt: string
+  finishedAt: string | null
+}
+
+interface IdeaJobEvent extends IdeaJob {
+  idea: Idea | null
 }
 
 interface IdeasState {
   ideas: Idea[]
   selectedIdeaId: string | null
   isLoaded: boolean
+  jobs: IdeaJob[]
   
   loadIdeas: () => Promise<void>
   addIdea: (idea: Omit<Idea, 'id' | 'createdAt' | 'updatedAt'>) => Promise<Idea>
@@ -22,6 +49,10 @@ interface IdeasState {
   reorderIdeas: (fromIndex: number, toIndex: number) => Promise<void>
   selectIdea: (id: string | null) => void
   getSelectedIdea: () => Idea | null
+  loadJobs: () => Promise<void>
+  queueExpansions: (ideaIds: string[], agentId?: string) => Promise<IdeaJob[]>
+  cancelJob: (jobId: string) => Promise<void>
+  clearFinishedJobs: () => Promise<void>
 }
 
 function generateId(): string {
@@ -32,11 +63,14 @@ export const useIdeasStore = create<IdeasState>((set, get) => ({
   ideas: [],
   selectedIdeaId: null,
   isLoaded: false,
+  jobs: [],
 
   loadIdeas: async () => {
     try {
       const ideas = await invoke<Idea[]>('load_ideas')
       set({ ideas, isLoaded: true })
+      // Jobs outlive a reload of the window
+      await get().loadJobs()
     } catch (error) {
       console.error('Failed to load ideas:', error)
       set({ ideas: [], isLoaded: true })
@@ -119,4 +153,46 @@ export const useIdeasStore = create<IdeasState>((set, get) => ({
     const { ideas, selectedIdeaId } = get()
     return ideas.find((idea) => idea.id === selectedIdeaId) ?? null
   },
+
+  loadJobs: async () => {
+    try {
+      const jobs = await invoke<IdeaJob[]>('list_idea_jobs')
+      set({ jobs })
+    } catch (error) {
+      console.error('Failed to load idea jobs:', error)
+    }
+  },
+
+  queueExpansions: async (ideaIds, agentId) => {
+    return invoke<IdeaJob[]>('queue_idea_expansions', { ideaIds, agentId })
+  },
+
+  cancelJob: async (jobId) => {
+    try {
+      await invoke('cancel_idea_job', { jobId })
+    } catch (error) {
+      console.error('Failed to cancel idea job:', error)
+    }
+  },
+
+  clearFinishedJobs: async () => {
+    try {
+      await invoke('clear_finished_idea_jobs')
+      set({ jobs: get().jobs.filter((job) => job.status === 'queued' || job.status === 'running') })
+    } catch (error) {
+      console.error('Failed to clear idea jobs:', error)
+    }
+  },
 }))
+
+// The backend saves expanded ideas itself, so they are only merged in here
+listen<IdeaJobEvent>('idea-job', ({ payload }) => {
+  const { idea, ...job } = payload
+  useIdeasStore.setState((state) => {
+    const jobs = state.jobs.some((j) => j.id === job.id)
+      ? state.jobs.map((j) => (j.id === job.id ? job : j))
+      : [...state.jobs, job]
+    const ideas = idea ? state.ideas.map((i) => (i.id === idea.id ? idea : i)) : state.ideas
+    return { jobs, ideas }
+  })
+})
@@ -29,7 +29,16 @@ const DEFAULT_PANEL_STATE: ProjectPanelState = {
 
 interface UiState {
   panelStates: Record<string, ProjectPanelState>
-  windowState?: WindowState
+  windowStates?: Record<string, WindowState>
+}
+
+interface MonitorInfo {
+  name: string | null
+  x: number
+  y: number
+  width: number
+  height: number
+  scaleFactor: number
 }
 
 interface WindowState {
@@ -38,6 +47,8 @@ interface WindowState {
   x?: number
   y?: number
   maximized: boolean
+  monitor?: MonitorInfo | null
+  scaleFactor?: number | null
 }
 
 interface PanelStore {
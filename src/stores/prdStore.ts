@@ -1,6 +1,7 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, emit } from '@tauri-apps/api/event'
+import { notify } from '../utils/notify'
 
 // Debug: log when prdStore module is loaded
 console.log('[prdStore] Module loaded in window:', window.location.pathname)
@@ -13,6 +14,10 @@ export interface Story {
   priority: number
   passes: boolean
   notes: string
+  /** Agent to build this story with, over the project's agent */
+  agent?: string
+  /** Model to build this story with */
+  model?: string
 }
 
 export interface PrdMetadata {
@@ -254,6 +259,8 @@ export const usePrdStore = create<PrdState>((set, get) => ({
       await invoke('save_prd', { projectPath, prd })
     } catch (error) {
       console.error('Failed to save PRD:', error)
+      // Conflicts mean an agent or another tool edited prd.json meanwhile
+      notify.error('Could not save PRD', String(error))
     }
   },
 }))
@@ -313,6 +320,8 @@ listen<{ projectId?: string }>('request-story-list', async (event) => {
           priority: number
           passes: boolean
           notes: string
+          agent?: string
+          model?: string
         }>
       }
       const prd = await invoke<Prd | null>('load_prd', { projectPath: project.path })
@@ -325,6 +334,8 @@ listen<{ projectId?: string }>('request-story-list', async (event) => {
           priority: s.priority ?? index + 1,
           passes: s.passes ?? false,
           notes: s.notes || '',
+          agent: s.agent,
+          model: s.model,
         }))
         usePrdStore.getState().setPrd(targetProjectId, stories, {
           project: prd.project,
@@ -9,6 +9,28 @@ export interface ProcessLogEntry {
   timestamp: Date
   type: 'stdout' | 'stderr' | 'system'
   content: string
+  /** Unfinished line of raw output; the next entry on this stream replaces it */
+  partial?: boolean
+}
+
+/** Why a process stopped, as reported in `agent-exit` */
+export type TerminationReason = 'exited' | 'canceled' | 'timeout' | 'budget' | 'signal'
+
+/** Log line for a process that did not simply exit, or null if it did */
+export function describeTermination(reason: TerminationReason | undefined, signal: number | null | undefined): string | null {
+  const suffix = signal != null ? ` (signal ${signal})` : ''
+  switch (reason) {
+    case 'canceled':
+      return `Process stopped by user${suffix}`
+    case 'timeout':
+      return `Process stopped after exceeding the story timeout${suffix}`
+    case 'budget':
+      return `Process stopped after exceeding the token or cost limit${suffix}`
+    case 'signal':
+      return `Process was killed by signal ${signal ?? 'unknown'} (crash or out of memory?)`
+    default:
+      return null
+  }
 }
 
 export interface ProcessCommand {
@@ -25,6 +47,8 @@ export interface RunningProcess {
   label: string
   startedAt: Date
   agentId?: string
+  /** Agent this process's agent replaced after it failed or could not run */
+  fallbackFrom?: string
   command?: ProcessCommand
   url?: string
 }
@@ -56,7 +80,7 @@ interface ProcessStore {
   selectProcess: (processId: string | null) => void
   
   // Log management
-  appendProcessLog: (processId: string, type: ProcessLogEntry['type'], content: string) => void
+  appendProcessLog: (processId: string, type: ProcessLogEntry['type'], content: string, partial?: boolean) => void
   getProcessLogs: (processId: string) => ProcessLogEntry[]
   clearProcessLogs: (processId: string) => void
   saveProcessLogToFile: (processId: string) => Promise<string | null>
@@ -113,7 +137,9 @@ export const useProcessStore = create<ProcessStore>((set, get) => ({
         id: crypto.randomUUID(),
         timestamp: startedAt,
         type: 'system',
-        content: `Agent: ${process.agentId}`,
+        content: process.fallbackFrom
+          ? `Agent: ${process.agentId} (fallback from ${process.fallbackFrom})`
+          : `Agent: ${process.agentId}`,
       })
     }
     
@@ -228,6 +254,7 @@ export const useProcessStore = create<ProcessStore>((set, get) => ({
           exitCode: exitCode ?? null,
           success: success ?? true,
           agentId: process.agentId,
+          fallbackFrom: process.fallbackFrom,
           command: process.command,
           logFilePath,
         }
@@ -307,19 +334,25 @@ export const useProcessStore = create<ProcessStore>((set, get) => ({
     set({ selectedProcessId: processId })
   },
 
-  appendProcessLog: (processId, type, content) => {
+  appendProcessLog: (processId, type, content, partial) => {
     const entry: ProcessLogEntry = {
       id: crypto.randomUUID(),
       timestamp: new Date(),
       type,
       content,
+      partial,
     }
-    set((state) => ({
-      processLogs: {
-        ...state.processLogs,
-        [processId]: [...(state.processLogs[processId] || []), entry],
-      },
-    }))
+    set((state) => {
+      const logs = state.processLogs[processId] || []
+      const last = logs[logs.length - 1]
+      const kept = last?.partial && last.type === type ? logs.slice(0, -1) : logs
+      return {
+        processLogs: {
+          ...state.processLogs,
+          [processId]: [...kept, entry],
+        },
+      }
+    })
   },
 
   getProcessLogs: (processId) => {
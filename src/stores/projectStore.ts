@@ -12,6 +12,8 @@ export interface Project {
   status: ProjectStatus
   createdAt: string
   stackId?: string
+  /** Stacks composed with `stackId` into the project's effective stack */
+  extraStackIds?: string[]
 }
 
 interface ProjectState {
@@ -27,7 +29,6 @@ interface ProjectState {
   setActiveProject: (id: string | null) => void
   updateProject: (id: string, updates: Partial<Project>) => void
   loadProjects: () => Promise<void>
-  saveProjects: () => Promise<void>
   setProjects: (projects: Project[]) => void
   reorderProjects: (fromIndex: number, toIndex: number) => void
   showProcessHistory: (projectId: string) => void
@@ -41,6 +42,23 @@ interface ProjectState {
   showDesign: (projectId: string) => void
 }
 
+/**
+ * Runs a registry command and adopts the list it returns. The backend merges
+ * each change into the stored registry, so edits from other windows survive.
+ */
+async function persistProjects(
+  set: (partial: Partial<ProjectState>) => void,
+  command: string,
+  args: Record<string, unknown>
+) {
+  try {
+    const projects = await invoke<Project[]>(command, args)
+    set({ projects })
+  } catch (error) {
+    console.error('Failed to save projects:', error)
+  }
+}
+
 export const useProjectStore = create<ProjectState>((set, get) => ({
   projects: [],
   activeProjectId: null,
@@ -59,7 +77,7 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
     set((state) => ({
       projects: [...state.projects, newProject],
     }))
-    get().saveProjects()
+    persistProjects(set, 'add_project', { project: newProject })
     return newProject
   },
 
@@ -68,7 +86,7 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
       projects: state.projects.filter((p) => p.id !== id),
       activeProjectId: state.activeProjectId === id ? null : state.activeProjectId,
     }))
-    get().saveProjects()
+    persistProjects(set, 'remove_project', { id })
   },
 
   setActiveProject: (id) => {
@@ -99,7 +117,7 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
         p.id === id ? { ...p, ...updates } : p
       ),
     }))
-    get().saveProjects()
+    persistProjects(set, 'update_project', { id, updates })
   },
 
   setProjects: (projects) => {
@@ -115,7 +133,7 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
     const [removed] = newProjects.splice(fromIndex, 1)
     newProjects.splice(toIndex, 0, removed)
     set({ projects: newProjects })
-    get().saveProjects()
+    persistProjects(set, 'reorder_projects', { ids: newProjects.map((p) => p.id) })
   },
 
   loadProjects: async () => {
@@ -128,15 +146,6 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
     }
   },
 
-  saveProjects: async () => {
-    const { projects } = get()
-    try {
-      await invoke('save_projects', { projects })
-    } catch (error) {
-      console.error('Failed to save projects:', error)
-    }
-  },
-
   showProcessHistory: (projectId) => {
     set((state) => ({
       processHistoryProjectId: projectId,
@@ -11,6 +11,22 @@ interface Preferences {
   promptOverrides: Record<string, string>
 }
 
+export interface PromptVersion {
+  version: number
+  /** Empty when the prompt was reset to its default */
+  content: string
+  createdAt: string
+  note: string | null
+  source: 'library' | 'settings' | 'rollback'
+}
+
+export interface LibraryPrompt {
+  id: string
+  createdAt: string
+  updatedAt: string
+  versions: PromptVersion[]
+}
+
 interface PromptStore {
   overrides: PromptOverrides
   isLoaded: boolean
@@ -19,6 +35,15 @@ interface PromptStore {
   setOverrides: (overrides: PromptOverrides) => void
   
   getPrompt: (promptId: keyof typeof DEFAULT_PROMPTS, variables?: Record<string, string>) => string
+  /**
+   * Renders a prompt in the backend, with partials and, given a `projectPath`
+   * (and `storyId`), the project's variables.
+   */
+  renderPrompt: (promptId: keyof typeof DEFAULT_PROMPTS, context?: Record<string, unknown>) => Promise<string>
+  getHistory: (name: string) => Promise<LibraryPrompt | null>
+  rollbackPrompt: (name: string, version: number) => Promise<void>
+  /** Undoes every prompt edit made after `since`, e.g. a build's start */
+  rollbackPromptsSince: (since: string) => Promise<LibraryPrompt[]>
 }
 
 export const usePromptStore = create<PromptStore>((set, get) => ({
@@ -59,4 +84,31 @@ export const usePromptStore = create<PromptStore>((set, get) => ({
     
     return promptText
   },
+
+  renderPrompt: async (promptId, context = {}) => {
+    const template = DEFAULT_PROMPTS[promptId]
+    if (!template) {
+      throw new Error(`Unknown prompt: ${promptId}`)
+    }
+    return invoke<string>('render_prompt', {
+      templateId: promptId,
+      context,
+      fallback: template.defaultPrompt,
+    })
+  },
+
+  getHistory: async (name) => {
+    return invoke<LibraryPrompt | null>('get_library_prompt', { name })
+  },
+
+  rollbackPrompt: async (name, version) => {
+    await invoke('rollback_library_prompt', { name, version })
+    await get().loadOverrides()
+  },
+
+  rollbackPromptsSince: async (since) => {
+    const changed = await invoke<LibraryPrompt[]>('rollback_prompts_since', { since })
+    await get().loadOverrides()
+    return changed
+  },
 }))
@@ -16,6 +16,8 @@ export interface Stack {
   category: string
   tools: StackTool[]
   tags: string[]
+  /** Added to the build prompts of projects using this stack */
+  promptSnippets: string[]
   isBuiltin: boolean
   isPublished: boolean
   author?: string
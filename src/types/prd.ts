@@ -9,6 +9,8 @@ export interface UserStory {
   passes: boolean
   status: StoryStatus
   notes: string
+  agent?: string
+  model?: string
 }
 
 export interface PRD {
@@ -0,0 +1,99 @@
+/**
+ * Listener for agent process output.
+ * The backend batches output lines into one `agent-output` event per interval;
+ * this unpacks them so callers can handle one line at a time.
+ *
+ * Processes spawned with the `raw` output mode send chunks as they arrive
+ * instead, which may end mid-line or redraw a line with carriage returns.
+ * Those are reassembled into lines here, showing what a terminal would.
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+export interface AgentOutputLine {
+  streamType: 'stdout' | 'stderr'
+  content: string
+}
+
+export interface AgentOutputBatch {
+  processId: string
+  lines: AgentOutputLine[]
+  /** Each entry is a raw chunk rather than a line */
+  raw?: boolean
+}
+
+export interface AgentOutput extends AgentOutputLine {
+  processId: string
+  /** The line is still being written; the next output on this stream replaces it */
+  partial?: boolean
+}
+
+export interface AgentOutputOptions {
+  /** Also deliver raw output's unfinished last line each time it changes */
+  partial?: boolean
+}
+
+/** What a terminal shows for a line redrawn with carriage returns */
+function redrawn(line: string): string {
+  const text = line.endsWith('\r') ? line.slice(0, -1) : line
+  return text.slice(text.lastIndexOf('\r') + 1)
+}
+
+/** Calls `handler` for each output line, in the order the process printed them. */
+export async function listenAgentOutput(
+  handler: (output: AgentOutput) => void,
+  options: AgentOutputOptions = {}
+): Promise<UnlistenFn> {
+  // Unfinished line of each raw stream, keyed by process ID and stream
+  const pending = new Map<string, AgentOutput>()
+
+  const unlistenOutput = await listen<AgentOutputBatch>('agent-output', (event) => {
+    const { processId, lines, raw } = event.payload
+    if (!raw) {
+      for (const line of lines) {
+        handler({ processId, ...line })
+      }
+      return
+    }
+
+    const updated = new Set<string>()
+    for (const { streamType, content } of lines) {
+      const key = `${processId}:${streamType}`
+      const parts = ((pending.get(key)?.content ?? '') + content).split('\n')
+      const rest = parts.pop() ?? ''
+      for (const part of parts) {
+        handler({ processId, streamType, content: redrawn(part) })
+      }
+      if (rest) {
+        pending.set(key, { processId, streamType, content: rest })
+        updated.add(key)
+      } else {
+        pending.delete(key)
+      }
+    }
+
+    if (options.partial) {
+      for (const key of updated) {
+        const line = pending.get(key)
+        if (line) {
+          handler({ ...line, content: redrawn(line.content), partial: true })
+        }
+      }
+    }
+  })
+
+  // `agent-exit` follows a process's last output; finish any line it left open
+  const unlistenExit = await listen<{ processId: string }>('agent-exit', (event) => {
+    for (const [key, line] of pending) {
+      if (line.processId === event.payload.processId) {
+        pending.delete(key)
+        handler({ ...line, content: redrawn(line.content) })
+      }
+    }
+  })
+
+  return () => {
+    unlistenOutput()
+    unlistenExit()
+  }
+}
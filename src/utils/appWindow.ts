@@ -0,0 +1,31 @@
+/**
+ * Opens frontend routes as separate windows. One window is kept per label:
+ * opening a label that's already open focuses it. Size and position are
+ * remembered per label.
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+
+export interface AppWindowOptions {
+  title?: string
+  width?: number
+  height?: number
+  minWidth?: number
+  minHeight?: number
+  /** Keep the app menu; secondary windows have none by default */
+  showMenu?: boolean
+}
+
+/** Opens `route` in the window labeled `label`. Resolves to the window's full label. */
+export function openAppWindow(label: string, route: string, options: AppWindowOptions = {}): Promise<string> {
+  return invoke<string>('open_app_window', { label, route, options })
+}
+
+/** Window label for a value such as a file path, stable across launches */
+export function windowLabelFor(prefix: string, value: string): string {
+  let hash = 0
+  for (let i = 0; i < value.length; i++) {
+    hash = (hash * 31 + value.charCodeAt(i)) | 0
+  }
+  return `${prefix}-${(hash >>> 0).toString(36)}`
+}
@@ -0,0 +1,69 @@
+/**
+ * Listener for events the backend buffers for replay.
+ * A window opened while a build is running would otherwise miss everything
+ * sent before it subscribed. This delivers the buffered events first, then
+ * live ones, dropping live events the replay already covered.
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+interface BusEvent<T> {
+  seq: number
+  timestamp: string
+  payload: T & { eventSeq?: number }
+}
+
+interface EventReplay<T> {
+  topic: string
+  events: BusEvent<T>[]
+  latestSeq: number
+  truncated: boolean
+}
+
+export interface ReplayOptions {
+  /** Only replay events numbered after this */
+  sinceSeq?: number
+}
+
+/** Calls `handler` with each buffered `topic` event, then each new one. */
+export async function listenWithReplay<T>(
+  topic: string,
+  handler: (payload: T) => void,
+  options: ReplayOptions = {}
+): Promise<UnlistenFn> {
+  let lastSeq = options.sinceSeq ?? 0
+  // Live events that arrive while the replay is in flight
+  let queued: (T & { eventSeq?: number })[] | null = []
+
+  const deliver = ({ eventSeq, ...payload }: T & { eventSeq?: number }) => {
+    if (eventSeq !== undefined) {
+      if (eventSeq <= lastSeq) return
+      lastSeq = eventSeq
+    }
+    handler(payload as T)
+  }
+
+  const unlisten = await listen<T & { eventSeq?: number }>(topic, (event) => {
+    if (queued) {
+      queued.push(event.payload)
+    } else {
+      deliver(event.payload)
+    }
+  })
+
+  try {
+    const replay = await invoke<EventReplay<T>>('replay_events', { topic, sinceSeq: lastSeq })
+    if (replay.truncated) {
+      console.warn(`[eventBus] Older ${topic} events were dropped before they could be replayed`)
+    }
+    replay.events.forEach((event) => deliver(event.payload))
+  } catch (e) {
+    console.error(`[eventBus] Failed to replay ${topic}:`, e)
+  }
+  const pending = queued
+  queued = null
+  pending.forEach(deliver)
+
+  return unlisten
+}
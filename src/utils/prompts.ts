@@ -320,7 +320,7 @@ IMPORTANT:
     name: "Story Implementation",
     description: "Implements a user story following its acceptance criteria",
     category: "stories",
-    variables: ["{{storyId}}", "{{storyTitle}}", "{{storyDescription}}", "{{acceptanceCriteria}}", "{{notes}}"],
+    variables: ["{{storyId}}", "{{storyTitle}}", "{{storyDescription}}", "{{acceptanceCriteria}}", "{{notes}}", "{{stack}}", "{{design}}", "{{files}}"],
     defaultPrompt: `Implement the following user story:
 
 ## {{storyId}}: {{storyTitle}}
@@ -331,10 +331,114 @@ IMPORTANT:
 {{acceptanceCriteria}}
 
 {{notes}}
+{% if stack %}
+
+{% include "stack" %}
+{% endif %}
+{% if design %}
+
+{% include "design" %}
+{% endif %}
+{% if files %}
+
+## Relevant Files
+{% include "files" %}
+{% endif %}
 
 Please implement this user story following the acceptance criteria. When done, ensure all quality checks pass (typecheck, lint, build).`,
   },
 
+  swarmPlanner: {
+    id: "swarmPlanner",
+    name: "Swarm Planner",
+    description: "Splits a story into tasks for several agents to work on at once in swarm mode",
+    category: "stories",
+    variables: ["{{storyId}}", "{{storyTitle}}", "{{storyDescription}}", "{{acceptanceCriteria}}", "{{notes}}", "{{maxTasks}}"],
+    defaultPrompt: `You are planning work for a team of coding agents who will implement one user story at the same time.
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+{{notes}}
+
+Explore the codebase, but do not modify any files. Split the story into at most {{maxTasks}} tasks that can be done at the same time without editing the same files. A small story can be a single task.
+
+Respond with only a JSON object, no other text:
+{
+  "tasks": [
+    { "title": "short task title", "description": "what to do, in enough detail to do it without seeing the other tasks", "files": ["files this task creates or changes"] }
+  ]
+}`,
+  },
+
+  swarmWorker: {
+    id: "swarmWorker",
+    name: "Swarm Worker",
+    description: "Implements one task of a story alongside the other agents in swarm mode",
+    category: "stories",
+    variables: ["{{storyId}}", "{{storyTitle}}", "{{storyDescription}}", "{{acceptanceCriteria}}", "{{task.title}}", "{{task.description}}", "{{task.files}}", "{{tasks}}"],
+    defaultPrompt: `You are one of several coding agents implementing a user story together. The others are working on the other tasks at the same time in separate copies of the repository, and your changes will be merged with theirs.
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+## Your task: {{task.title}}
+
+{{task.description}}
+{% if task.files %}
+
+Files: {{ task.files | join(", ") }}
+{% endif %}
+
+### The other tasks
+{% for other in tasks if other.id != task.id %}
+- {{ other.title }}
+{% else %}
+- None
+{% endfor %}
+
+Do only your task, and leave the files of the other tasks alone. Do not commit; your changes are committed for you.`,
+  },
+
+  swarmReviewer: {
+    id: "swarmReviewer",
+    name: "Swarm Reviewer",
+    description: "Reviews the combined changes of a swarm before the story is merged",
+    category: "stories",
+    variables: ["{{storyId}}", "{{storyTitle}}", "{{storyDescription}}", "{{acceptanceCriteria}}", "{{tasks}}", "{{diff}}"],
+    defaultPrompt: `You are reviewing the combined work of several coding agents on one user story before it is merged.
+
+## {{storyId}}: {{storyTitle}}
+
+{{storyDescription}}
+
+### Acceptance Criteria:
+{{acceptanceCriteria}}
+
+### Tasks
+{% for task in tasks %}
+- {{ task.title }}
+{% endfor %}
+
+### Combined diff
+\`\`\`diff
+{{diff}}
+\`\`\`
+
+Check that the changes meet every acceptance criterion, fit together, and have no obvious bugs. You may read the code, but do not modify any files.
+
+Respond with only a JSON object, no other text:
+{ "approved": true, "summary": "one paragraph", "issues": ["problems that must be fixed before merging"] }`,
+  },
+
   ideaDescriptionGenerate: {
     id: "ideaDescriptionGenerate",
     name: "Generate Idea Description",
@@ -416,6 +520,30 @@ Make it:
 Output ONLY the simplified description in Markdown format. Do not include any preamble or explanation - just the simplified description itself.`,
   },
 
+  ideaExpansion: {
+    id: "ideaExpansion",
+    name: "Expand Idea in Background",
+    description: "Drafts a summary, description, and stories for an idea queued for background expansion",
+    category: "ideas",
+    variables: ["{{title}}", "{{summary}}", "{{description}}"],
+    defaultPrompt: `Expand this rough app idea into something ready to plan a build from.
+
+Title: {{title}}
+Summary: {{summary}}
+Notes: {{description}}
+
+Respond with only a JSON object, no other text:
+{
+  "summary": "one or two sentences describing the app",
+  "description": "a fuller description: who it is for, the core features, and anything out of scope",
+  "stories": [
+    { "title": "short story title", "description": "what to build", "acceptanceCriteria": ["..."] }
+  ]
+}
+
+Draft 5 to 12 stories, ordered so each builds on the ones before it.`,
+  },
+
   devServerDetection: {
     id: "devServerDetection",
     name: "Dev Server Detection",
@@ -0,0 +1,89 @@
+/**
+ * Wrapper around the `spawn_agent` command.
+ * The backend only runs allowlisted executables; for anything else it asks
+ * the user once, and the spawn is retried if they approve it.
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import type { ProcessType } from '../stores/processStore'
+
+/** Matches `allowlist::NOT_ALLOWED_PREFIX` in the backend. */
+const NOT_ALLOWED_PREFIX = 'Executable not allowed:'
+
+/** `lines` emits output once each line ends; `raw` emits chunks as they arrive */
+export type OutputMode = 'lines' | 'raw'
+
+/**
+ * Output mode for each kind of process. Dev servers and their installs draw
+ * progress bars and spinners with carriage returns, which only show up live
+ * in raw mode; everything else is parsed line by line.
+ */
+export const OUTPUT_MODES: Record<ProcessType, OutputMode> = {
+  build: 'lines',
+  chat: 'lines',
+  prd: 'lines',
+  'dev-server': 'raw',
+  detection: 'lines',
+  tunnel: 'lines',
+}
+
+export interface SpawnAgentOptions {
+  executable: string
+  args: string[]
+  workingDirectory: string
+  env?: Record<string, string>
+  /** Picks the output mode from `OUTPUT_MODES`; lines when unset */
+  processType?: ProcessType
+  /** Applies this agent's progress rules to the output, and records the usage it reports as a cost */
+  agentId?: string
+  /** Description of the cost entry recorded for the run */
+  costDescription?: string
+  /** CPU priority; the Agent Priority setting when unset */
+  priority?: 'normal' | 'low' | 'lowest'
+  /** Creates the working directory if it is missing */
+  ensureExists?: boolean
+}
+
+export interface SpawnAgentResult {
+  processId: string
+}
+
+export async function spawnAgent({ processType, ...options }: SpawnAgentOptions): Promise<SpawnAgentResult> {
+  const request = { ...options, outputMode: processType ? OUTPUT_MODES[processType] : 'lines' }
+  try {
+    return await invoke<SpawnAgentResult>('spawn_agent', request)
+  } catch (error) {
+    if (!String(error).startsWith(NOT_ALLOWED_PREFIX)) {
+      throw error
+    }
+
+    // The backend asks the user in a native dialog
+    const approved = await invoke<boolean>('approve_executable', { executable: options.executable })
+    if (!approved) {
+      throw error
+    }
+
+    return invoke<SpawnAgentResult>('spawn_agent', request)
+  }
+}
+
+/** A command the project configures in `.ideate/config.json` */
+export type ConfiguredCommand = 'verification' | 'preBuild' | 'postBuild' | 'preStory' | 'postStory'
+
+/**
+ * Runs a command the project configures, like its verification command or a
+ * hook, through the user's shell. The command line itself is read from the
+ * project's config by the backend. Output and exit events match `spawnAgent`'s.
+ */
+export function spawnShellCommand(
+  projectPath: string,
+  command: ConfiguredCommand,
+  options: { env?: Record<string, string>; processType?: ProcessType } = {}
+): Promise<SpawnAgentResult> {
+  return invoke<SpawnAgentResult>('spawn_shell_command', {
+    projectPath,
+    command,
+    env: options.env,
+    outputMode: options.processType ? OUTPUT_MODES[options.processType] : 'lines',
+  })
+}
@@ -0,0 +1,67 @@
+import type { Terminal } from "@xterm/xterm";
+
+export interface TerminalSearchMatch {
+  /** Line number counted from the start of the session. */
+  line: number;
+  start: number;
+  length: number;
+  text: string;
+}
+
+export interface TerminalSearchResult {
+  matches: TerminalSearchMatch[];
+  firstLine: number;
+  totalLines: number;
+  truncated: boolean;
+}
+
+interface LogicalLine {
+  /** Buffer row the line starts on. */
+  row: number;
+  text: string;
+}
+
+/** The xterm buffer up to the cursor as unwrapped lines. */
+function logicalLines(terminal: Terminal): LogicalLine[] {
+  const buffer = terminal.buffer.active;
+  const lastRow = buffer.baseY + buffer.cursorY;
+  const lines: LogicalLine[] = [];
+  for (let row = 0; row <= lastRow; row++) {
+    const line = buffer.getLine(row);
+    if (!line) continue;
+    const text = line.translateToString(false);
+    if (line.isWrapped && lines.length > 0) {
+      lines[lines.length - 1].text += text;
+    } else {
+      lines.push({ row, text });
+    }
+  }
+  return lines.map((line) => ({ ...line, text: line.text.trimEnd() }));
+}
+
+/**
+ * Selects a backend search match in xterm and scrolls it into view. Backend
+ * lines are counted from the end to find the buffer line, falling back to the
+ * nearest line with the same text. Returns false if xterm no longer has it.
+ */
+export function revealMatch(terminal: Terminal, match: TerminalSearchMatch, result: TerminalSearchResult): boolean {
+  const lines = logicalLines(terminal);
+  const needle = match.text.slice(match.start, match.start + match.length);
+  const fits = (index: number) =>
+    lines[index]?.text.slice(match.start, match.start + match.length) === needle;
+
+  const expected = lines.length - (result.totalLines - match.line);
+  let index = -1;
+  for (let distance = 0; distance <= lines.length + Math.abs(expected) && index < 0; distance++) {
+    if (fits(expected - distance)) index = expected - distance;
+    else if (fits(expected + distance)) index = expected + distance;
+  }
+  if (index < 0) return false;
+
+  const { row } = lines[index];
+  const cols = terminal.cols;
+  const matchRow = row + Math.floor(match.start / cols);
+  terminal.select(match.start % cols, matchRow, match.length);
+  terminal.scrollToLine(Math.max(0, matchRow - Math.floor(terminal.rows / 2)));
+  return true;
+}
@@ -0,0 +1,54 @@
+import { useEffect, useRef, useState } from "react";
+import { useThemeStore } from "../stores/themeStore";
+import { useFileTail } from "../hooks/useFileTail";
+
+/** Follows a log file, given as `?path=`, in its own window. */
+export function LogViewerWindow() {
+  const loadTheme = useThemeStore((state) => state.loadTheme);
+  const [path] = useState(() => new URLSearchParams(window.location.search).get("path"));
+  const [autoScroll, setAutoScroll] = useState(true);
+  const { lines, active, error } = useFileTail(path);
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    loadTheme();
+  }, [loadTheme]);
+
+  useEffect(() => {
+    if (autoScroll) {
+      bottomRef.current?.scrollIntoView({ block: "end" });
+    }
+  }, [lines, autoScroll]);
+
+  if (!path) {
+    return (
+      <div className="flex items-center justify-center h-screen bg-background text-muted text-sm">
+        No log file selected
+      </div>
+    );
+  }
+
+  return (
+    <div className="flex flex-col h-screen bg-background text-foreground">
+      <div className="flex items-center justify-between gap-3 px-3 py-2 border-b border-border">
+        <div className="min-w-0">
+          <div className="text-sm font-semibold truncate" title={path}>
+            {path.split(/[\\/]/).pop()}
+          </div>
+          <div className="text-xs text-muted">
+            {active ? "Following" : "Stopped"} • {lines.length.toLocaleString()} lines
+          </div>
+        </div>
+        <label className="flex items-center gap-1.5 text-xs text-muted flex-shrink-0">
+          <input type="checkbox" checked={autoScroll} onChange={(e) => setAutoScroll(e.target.checked)} />
+          Auto-scroll
+        </label>
+      </div>
+      {error && <div className="px-3 py-2 text-xs text-destructive border-b border-border">{error}</div>}
+      <pre className="flex-1 overflow-auto p-3 text-xs font-mono whitespace-pre-wrap">
+        {lines.join("\n")}
+        <div ref={bottomRef} />
+      </pre>
+    </div>
+  );
+}
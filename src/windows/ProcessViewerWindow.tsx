@@ -4,12 +4,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { useThemeStore } from "../stores/themeStore";
 import type { RunningProcess } from "../stores/processStore";
 import { StreamLogEntry } from "../components/StreamLogEntry";
-
-interface AgentOutputPayload {
-  processId: string;
-  streamType: "stdout" | "stderr";
-  content: string;
-}
+import { listenAgentOutput } from "../utils/agentOutput";
 
 interface AgentExitPayload {
   processId: string;
@@ -64,6 +59,8 @@ interface LogEntryData {
   type: "stdout" | "stderr" | "system";
   content: string;
   timestamp: Date;
+  /** Unfinished line the next entry on this stream replaces */
+  partial?: boolean;
 }
 
 function formatDuration(ms: number): string {
@@ -353,18 +350,18 @@ export function ProcessViewerWindow() {
 
   // Listen for agent output and exit events
   useEffect(() => {
-    const unlistenOutputPromise = listen<AgentOutputPayload>("agent-output", (event) => {
-      const { processId, streamType, content } = event.payload;
-      const logEntry: LogEntryData = {
-        type: streamType,
-        content,
-        timestamp: new Date(),
-      };
-      setLocalLogs((prev) => ({
-        ...prev,
-        [processId]: [...(prev[processId] || []), logEntry],
-      }));
-    });
+    const unlistenOutputPromise = listenAgentOutput(
+      ({ processId, streamType, content, partial }) => {
+        const entry: LogEntryData = { type: streamType, content, timestamp: new Date(), partial };
+        setLocalLogs((prev) => {
+          const logs = prev[processId] || [];
+          const last = logs[logs.length - 1];
+          const kept = last?.partial && last.type === streamType ? logs.slice(0, -1) : logs;
+          return { ...prev, [processId]: [...kept, entry] };
+        });
+      },
+      { partial: true }
+    );
 
     const unlistenExitPromise = listen<AgentExitPayload>("agent-exit", (event) => {
       const { processId } = event.payload;